@@ -0,0 +1,58 @@
+//! 结果流水线基准测试
+//!
+//! 与 `--load-test` 使用同一条流水线：验证 → 创建份额 → 模拟提交，分别测量各阶段和整条流水线。
+
+use cgminer_rs::mining::load_test::{self, LoadTestConfig, ResultPipeline};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// 每次迭代处理的结果数
+const BATCH: u64 = 10_000;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(4)
+        .build()
+        .unwrap()
+}
+
+/// 基准测试：各阶段单独的开销
+fn bench_pipeline_stages(c: &mut Criterion) {
+    let rt = runtime();
+    let pipeline = rt.block_on(ResultPipeline::new());
+    let valid = rt.block_on(pipeline.validate(pipeline.synthetic_result(1))).unwrap();
+    let share = pipeline.create_share(&valid).unwrap();
+
+    let mut group = c.benchmark_group("result_pipeline_stage");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("validate", |b| {
+        let mut index = 0u64;
+        b.iter(|| {
+            index += 1;
+            black_box(rt.block_on(pipeline.validate(pipeline.synthetic_result(index))))
+        });
+    });
+    group.bench_function("create_share", |b| {
+        b.iter(|| black_box(pipeline.create_share(&valid)));
+    });
+    group.bench_function("submit", |b| {
+        b.iter(|| black_box(pipeline.submit(&share)));
+    });
+    group.finish();
+}
+
+/// 基准测试：整条流水线在不同并发度下的吞吐量
+fn bench_pipeline_throughput(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("result_pipeline");
+    group.throughput(Throughput::Elements(BATCH));
+    for concurrency in [1usize, 4, 8].iter() {
+        group.bench_with_input(BenchmarkId::new("tasks", concurrency), concurrency, |b, &concurrency| {
+            let config = LoadTestConfig { results: BATCH, concurrency };
+            b.iter(|| black_box(rt.block_on(load_test::run(&config))));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipeline_stages, bench_pipeline_throughput);
+criterion_main!(benches);
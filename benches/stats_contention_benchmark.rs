@@ -0,0 +1,103 @@
+//! 挖矿统计锁竞争基准测试
+//!
+//! 模拟结果收集、推送事件和 API 查询同时更新/读取挖矿统计：对比原来的
+//! `RwLock<MiningStats>` (每个份额取写锁，查询也取写锁刷新运行时间) 和原子计数器 `StatsCounters`。
+
+use cgminer_rs::mining::{MiningStats, StatsCounters};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 每个写入任务记录的份额数
+const SHARES_PER_WRITER: usize = 2_000;
+
+/// 每个读取任务的查询次数
+const READS_PER_READER: usize = 200;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(4)
+        .build()
+        .unwrap()
+}
+
+/// 原来的做法：所有路径共用一个 RwLock
+async fn locked_stats(writers: usize) {
+    let stats = Arc::new(RwLock::new(MiningStats::new()));
+    stats.write().await.start();
+
+    let mut tasks = Vec::new();
+    for w in 0..writers {
+        let stats = stats.clone();
+        tasks.push(tokio::spawn(async move {
+            for i in 0..SHARES_PER_WRITER {
+                stats.write().await.record_accepted_share((w * i) as f64);
+                if i % 16 == 0 {
+                    stats.write().await.update_hashrate(i as f64);
+                }
+            }
+        }));
+    }
+    let stats_reader = stats.clone();
+    tasks.push(tokio::spawn(async move {
+        for _ in 0..READS_PER_READER {
+            let mut stats = stats_reader.write().await;
+            stats.update_uptime();
+            black_box(stats.clone());
+            drop(stats);
+            tokio::task::yield_now().await;
+        }
+    }));
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+/// 现在的做法：原子计数器，读取方取快照
+async fn atomic_stats(writers: usize) {
+    let stats = Arc::new(StatsCounters::new());
+    stats.start();
+
+    let mut tasks = Vec::new();
+    for w in 0..writers {
+        let stats = stats.clone();
+        tasks.push(tokio::spawn(async move {
+            for i in 0..SHARES_PER_WRITER {
+                stats.record_accepted_share((w * i) as f64);
+                if i % 16 == 0 {
+                    stats.update_hashrate(i as f64);
+                }
+            }
+        }));
+    }
+    let stats_reader = stats.clone();
+    tasks.push(tokio::spawn(async move {
+        for _ in 0..READS_PER_READER {
+            black_box(stats_reader.snapshot());
+            tokio::task::yield_now().await;
+        }
+    }));
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+/// 基准测试：不同写入并发度下的统计更新
+fn bench_stats_contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stats_contention");
+    let rt = runtime();
+
+    for writers in [1usize, 4, 16].iter() {
+        group.bench_with_input(BenchmarkId::new("rwlock", writers), writers, |b, &writers| {
+            b.iter(|| rt.block_on(locked_stats(writers)));
+        });
+        group.bench_with_input(BenchmarkId::new("atomic", writers), writers, |b, &writers| {
+            b.iter(|| rt.block_on(atomic_stats(writers)));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_stats_contention);
+criterion_main!(benches);
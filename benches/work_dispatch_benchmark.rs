@@ -0,0 +1,53 @@
+//! 工作分发复制开销基准测试
+//!
+//! 一份工作分发给多台设备：对比每台设备深拷贝 `Work` 和共享 `Arc<Work>` (按需写时复制 extranonce2)。
+
+use cgminer_core::Work;
+use cgminer_rs::mining::WorkItem;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn sample_work() -> Work {
+    let mut work = Work::new("bench".to_string(), [0xff; 32], [7; 80], 1024.0);
+    work.coinbase1 = vec![1; 120];
+    work.coinbase2 = vec![2; 200];
+    work.merkle_branches = vec![vec![3u8; 32]; 12];
+    work
+}
+
+/// 基准测试：一份工作分发给 N 台设备
+fn bench_work_fanout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("work_fanout");
+    let item = WorkItem::new(sample_work());
+
+    for devices in [4usize, 32, 128].iter() {
+        group.bench_with_input(BenchmarkId::new("deep_clone", devices), devices, |b, &devices| {
+            b.iter(|| {
+                let works: Vec<Work> = (0..devices).map(|_| Work::clone(&item.work)).collect();
+                black_box(works);
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("shared", devices), devices, |b, &devices| {
+            b.iter(|| {
+                let works: Vec<_> = (0..devices).map(|_| item.shared_work()).collect();
+                black_box(works);
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("shared_with_extranonce2", devices), devices, |b, &devices| {
+            b.iter(|| {
+                let items: Vec<WorkItem> = (0..devices)
+                    .map(|device| {
+                        let mut per_device = item.clone();
+                        per_device.set_extranonce2((device as u32).to_le_bytes().to_vec());
+                        per_device
+                    })
+                    .collect();
+                black_box(items);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_work_fanout);
+criterion_main!(benches);
@@ -0,0 +1,148 @@
+//! 控制操作审计：记录改变状态的请求，查询接口 `/api/v1/audit` 需要 admin 权限
+//!
+//! 中间件在认证之后运行，从请求扩展取操作者，对已知资源 (设备、风扇、运行时设置、
+//! 日志过滤、API 密钥) 在请求前后各取一次快照作为改动前后的值。
+
+use crate::api::ApiResponse;
+use crate::logging::{filter as log_filter, redact};
+use crate::mining::MiningManager;
+use crate::security::audit::{AuditEntry, AuditQuery};
+use crate::security::{Actor, ApiKeyStore, AuditLog};
+use axum::body::Body;
+use axum::extract::{Query, Request, State};
+use axum::http::{Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use axum::Router;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::warn;
+
+/// 读取请求体的上限，与 axum 默认的请求体上限一致
+const MAX_AUDITED_BODY: usize = 2 * 1024 * 1024;
+
+/// 审计中间件的状态
+#[derive(Clone)]
+pub struct AuditContext {
+    pub log: Arc<AuditLog>,
+    pub mining_manager: Arc<MiningManager>,
+    pub keys: Option<Arc<ApiKeyStore>>,
+}
+
+pub fn routes(log: Arc<AuditLog>) -> Router {
+    Router::new()
+        .route("/api/v1/audit", get(query_audit))
+        .with_state(log)
+}
+
+/// 查询审计记录，按时间倒序
+pub async fn query_audit(
+    State(log): State<Arc<AuditLog>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<ApiResponse<Vec<AuditEntry>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    Ok(Json(ApiResponse::success(log.query(&query).await)))
+}
+
+/// 改变状态的请求：除 GET/HEAD/OPTIONS 外的 API 请求，登录和登出不算
+fn is_audited(method: &Method, path: &str) -> bool {
+    ![Method::GET, Method::HEAD, Method::OPTIONS].contains(method)
+        && path.starts_with("/api/")
+        && !matches!(path, "/api/login" | "/api/logout")
+}
+
+/// 审计中间件
+pub async fn audit_middleware(State(context): State<AuditContext>, request: Request, next: Next) -> Response {
+    if !is_audited(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let actor = request.extensions().get::<Actor>().cloned().unwrap_or_else(Actor::anonymous);
+
+    // 读出请求体用于记录，再原样交给处理器
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_AUDITED_BODY).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return (StatusCode::PAYLOAD_TOO_LARGE, Json(ApiResponse::<()>::error("Request body is too large".to_string())))
+                .into_response();
+        }
+    };
+    let body_json = serde_json::from_slice::<serde_json::Value>(&bytes).ok().map(|mut value| {
+        redact::redact_json(&mut value);
+        value
+    });
+
+    let previous = snapshot(&context, &path).await;
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    let status = response.status();
+    let current = if status.is_success() { snapshot(&context, &path).await } else { None };
+
+    let entry = AuditEntry {
+        id: 0,
+        timestamp: 0,
+        actor: actor.0,
+        method,
+        path,
+        status: status.as_u16(),
+        request: body_json,
+        previous,
+        current,
+    };
+    if let Err(e) = context.log.record(entry).await {
+        warn!("❌ Failed to write audit record: {}", e);
+    }
+    response
+}
+
+/// 请求路径对应资源的当前值，敏感字段已遮蔽
+async fn snapshot(context: &AuditContext, path: &str) -> Option<serde_json::Value> {
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    let manager = &context.mining_manager;
+    match segments.as_slice() {
+        ["api", "v1", "devices", id, "fan"] => {
+            let status = manager.get_device_fan_status(id.parse().ok()?).await.ok()?;
+            Some(redact::to_redacted_json(&status))
+        }
+        ["api", "v1", "devices", id, _] => {
+            let id: u32 = id.parse().ok()?;
+            let device = manager.get_all_device_info().await.into_iter().find(|device| device.id == id)?;
+            Some(json!({
+                "status": device.status,
+                "frequency": device.frequency,
+                "voltage": device.voltage,
+                "fan_speed": device.fan_speed,
+            }))
+        }
+        ["api", "v1", "cores", id, "config"] => {
+            let config = manager.get_core_config(id).await?;
+            Some(redact::to_redacted_json(&json!({ "enabled": config.enabled, "custom_params": config.custom_params })))
+        }
+        // `/api/v1/config` 的更新目前只作用于运行时设置
+        ["api", "v1", "config" | "settings"] => Some(redact::to_redacted_json(&manager.get_runtime_settings().await)),
+        ["api", "v1", "logging"] => log_filter::current().map(|settings| redact::to_redacted_json(&settings)),
+        ["api", "v1", "keys", id, ..] => {
+            let keys = context.keys.as_ref()?.list().await;
+            keys.into_iter().find(|key| key.id == *id).map(|key| redact::to_redacted_json(&key))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audited_requests() {
+        assert!(is_audited(&Method::POST, "/api/v1/devices/0/restart"));
+        assert!(is_audited(&Method::PUT, "/api/v1/settings"));
+        assert!(is_audited(&Method::DELETE, "/api/v1/keys/key_1"));
+        assert!(is_audited(&Method::POST, "/api/push/subscribe"));
+        assert!(!is_audited(&Method::GET, "/api/v1/devices"));
+        assert!(!is_audited(&Method::POST, "/api/login"));
+        assert!(!is_audited(&Method::POST, "/login"));
+    }
+}
@@ -1,21 +1,38 @@
 use axum::{
-    extract::{Request, State},
-    http::{header::AUTHORIZATION, StatusCode},
+    extract::{ConnectInfo, Request, State},
+    http::{header::{AUTHORIZATION, RETRY_AFTER}, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Json, Response},
 };
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, warn};
+use crate::api::ApiResponse;
+use crate::api::server::ServerConfig;
+use crate::logging::{i18n::msg, redact};
+use crate::security::{Actor, ApiKeyStore, ApiScope, LoginGuard, SimpleSecurityManager};
+use crate::web::auth::{session_id, AuthRejection, WebAuth, CSRF_HEADER};
 
 /// 认证配置
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct AuthConfig {
     pub enabled: bool,
     pub token: Option<String>,
     pub api_keys: Vec<String>,
 }
 
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("enabled", &self.enabled)
+            .field("token", &redact::mask_option(&self.token))
+            .field("api_keys", &self.api_keys.iter().map(|key| redact::mask(key)).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
 impl Default for AuthConfig {
     fn default() -> Self {
         Self {
@@ -26,16 +43,122 @@ impl Default for AuthConfig {
     }
 }
 
+/// API 服务器的认证状态：`api.auth_token`、管理的 API 密钥和仪表板登录会话
+pub struct ServerAuth {
+    pub api: AuthConfig,
+    pub web: Arc<WebAuth>,
+    /// 带权限范围的 API 密钥，`security.api_keys_file` 为空时不启用
+    pub keys: Option<Arc<ApiKeyStore>>,
+    /// 认证失败的 IP 锁定
+    pub lockout: Arc<LoginGuard>,
+}
+
+impl ServerAuth {
+    pub fn from_config(config: &ServerConfig) -> Self {
+        Self {
+            api: AuthConfig {
+                enabled: config.api.auth_token.is_some(),
+                token: config.api.auth_token.clone(),
+                api_keys: Vec::new(),
+            },
+            web: Arc::new(WebAuth::new(config.web.auth.clone(), config.tls_enabled())),
+            keys: (!config.security.api_keys_file.is_empty()).then(|| {
+                let security = Arc::new(SimpleSecurityManager::new(&config.security.key_file));
+                Arc::new(ApiKeyStore::new(security, &config.security.api_keys_file))
+            }),
+            lockout: Arc::new(LoginGuard::new(config.security.lockout.clone())),
+        }
+    }
+
+    /// 是否要求认证：配置了 token、启用了登录，或存在 API 密钥
+    async fn required(&self) -> bool {
+        if self.api.enabled || self.web.enabled() {
+            return true;
+        }
+        match &self.keys {
+            Some(keys) => keys.has_keys().await,
+            None => false,
+        }
+    }
+
+    /// `Authorization` 头对应的权限范围和操作者，`api.auth_token` 和静态 key 拥有全部权限
+    async fn scope_for(&self, auth_header: &str) -> Option<(ApiScope, Actor)> {
+        let (credential, is_api_key) = if let Some(token) = auth_header.strip_prefix("Bearer ") {
+            (token, false)
+        } else if let Some(api_key) = auth_header.strip_prefix("ApiKey ") {
+            (api_key, true)
+        } else {
+            return None;
+        };
+        let static_match = if is_api_key {
+            is_valid_api_key(&self.api, credential)
+        } else {
+            is_valid_token(&self.api, credential)
+        };
+        if static_match {
+            let actor = if is_api_key { "api_key" } else { "token" };
+            return Some((ApiScope::Admin, Actor(actor.to_string())));
+        }
+        let key = self.keys.as_ref()?.authenticate(credential).await?;
+        Some((key.scope, Actor(format!("key:{} ({})", key.name, key.id))))
+    }
+}
+
+/// 无需认证的路径：健康检查、登录页和登录接口、页面资源
+fn is_public_path(path: &str) -> bool {
+    matches!(path, "/health" | "/login" | "/api/login") || path.starts_with("/assets/")
+}
+
+/// 被锁定的客户端
+fn locked_out_response(remaining: Duration) -> Response {
+    let secs = remaining.as_secs().max(1);
+    let message = format!("Too many failed authentication attempts; try again in {}s", secs);
+    (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, secs.to_string())], Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+/// 登录接口：被锁定的 IP 直接拒绝，按登录结果记录失败或清除计数
+async fn guarded_login(auth: &ServerAuth, client: Option<IpAddr>, request: Request, next: Next) -> Response {
+    let Some(ip) = client else {
+        return next.run(request).await;
+    };
+    if let Some(remaining) = auth.lockout.check(ip) {
+        return locked_out_response(remaining);
+    }
+    let response = next.run(request).await;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        auth.lockout.record_failure(ip, "login");
+    } else if response.status().is_success() && auth.web.enabled() {
+        auth.lockout.record_success(ip);
+    }
+    response
+}
+
 /// 认证中间件
+///
+/// 带有效 `Authorization` 头 (Bearer token 或 ApiKey) 的请求按其权限范围放行，
+/// 范围不足时返回 403；否则启用了仪表板登录时检查会话 cookie 和 CSRF 令牌。
+/// 通过的请求带上操作者 (`Actor` 扩展) 供审计使用。登录失败和无效凭据按客户端 IP 计数，
+/// 超过 `security.lockout` 的上限后该 IP 的认证请求返回 429。
 pub async fn auth_middleware(
-    State(auth_config): State<Arc<AuthConfig>>,
-    request: Request,
+    State(auth): State<Arc<ServerAuth>>,
+    mut request: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    // 如果认证未启用，直接通过
-    if !auth_config.enabled {
-        debug!("Authentication disabled, allowing request");
-        return Ok(next.run(request).await);
+) -> Response {
+    let client = request.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip());
+    if request.uri().path() == "/api/login" {
+        return guarded_login(&auth, client, request, next).await;
+    }
+
+    // 如果认证未启用，直接通过；剖析接口 (`/debug/`) 始终需要凭据
+    let always_authenticated = request.uri().path().starts_with("/debug/");
+    if is_public_path(request.uri().path()) || (!always_authenticated && !auth.required().await) {
+        debug!("Authentication not required, allowing request");
+        request.extensions_mut().insert(Actor::anonymous());
+        return next.run(request).await;
+    }
+
+    if let Some(remaining) = client.and_then(|ip| auth.lockout.check(ip)) {
+        return locked_out_response(remaining);
     }
 
     // 检查 Authorization 头
@@ -45,27 +168,49 @@ pub async fn auth_middleware(
         .and_then(|header| header.to_str().ok());
 
     if let Some(auth_str) = auth_header {
-        if auth_str.starts_with("Bearer ") {
-            let token = &auth_str[7..];
-            
-            // 检查 token 是否有效
-            if is_valid_token(&auth_config, token) {
-                debug!("Valid token provided, allowing request");
-                return Ok(next.run(request).await);
+        let credential = auth.scope_for(auth_str).await;
+        if let Some(ip) = client {
+            match credential {
+                Some(_) => auth.lockout.record_success(ip),
+                None => {
+                    auth.lockout.record_failure(ip, "api");
+                }
+            }
+        }
+        if let Some((scope, actor)) = credential {
+            let required = ApiScope::required_for(request.method(), request.uri().path());
+            if scope >= required {
+                debug!("Valid {} credential provided, allowing request", scope.as_str());
+                request.extensions_mut().insert(actor);
+                return next.run(request).await;
+            }
+            warn!("{}", msg::auth_failed());
+            let message = format!("API key scope '{}' does not allow this request (requires '{}')", scope.as_str(), required.as_str());
+            return (StatusCode::FORBIDDEN, Json(ApiResponse::<()>::error(message))).into_response();
+        }
+    }
+
+    // 检查仪表板登录会话，页面请求未登录时跳转到登录页
+    if auth.web.enabled() {
+        let page = matches!(request.uri().path(), "/" | "/fleet" | "/history");
+        let csrf = request.headers().get(CSRF_HEADER).and_then(|value| value.to_str().ok());
+        match auth.web.authorize(request.method(), page, session_id(request.headers()), csrf) {
+            Ok(username) => {
+                let actor = username.map_or_else(Actor::anonymous, |username| Actor(format!("user:{}", username)));
+                request.extensions_mut().insert(actor);
+                return next.run(request).await;
             }
-        } else if auth_str.starts_with("ApiKey ") {
-            let api_key = &auth_str[7..];
-            
-            // 检查 API key 是否有效
-            if is_valid_api_key(&auth_config, api_key) {
-                debug!("Valid API key provided, allowing request");
-                return Ok(next.run(request).await);
+            Err(rejection) => {
+                if !matches!(rejection, AuthRejection::LoginRequired) {
+                    warn!("{}", msg::auth_failed());
+                }
+                return rejection.into_response();
             }
         }
     }
 
-    warn!("Authentication failed for request");
-    Err(StatusCode::UNAUTHORIZED)
+    warn!("{}", msg::auth_failed());
+    (StatusCode::UNAUTHORIZED, Json(ApiResponse::<()>::error("Authentication required".to_string()))).into_response()
 }
 
 /// 检查 token 是否有效
@@ -0,0 +1,342 @@
+//! 命令行客户端 (`cgminer-rs status|devices|pools|pause|resume|restart-device <id>`)
+//!
+//! 通过正在运行的实例的 API 查询状态和执行控制操作，便于经 SSH 编写批量脚本。
+//! 地址和令牌依次取自 `--api-url`/`--api-token` (或 `CGMINER_CLIENT_URL`/`CGMINER_CLIENT_TOKEN`)
+//! 和配置文件的 `[api]` 段 (含 include、profile 和环境变量覆盖)。
+//!
+//! 输出为便于阅读的文本，`--json` 时原样输出响应中的 `data`。请求失败时退出码为 1。
+
+use crate::api::{ApiResponse, DeviceStatusResponse, PoolStatusResponse, SystemStatusResponse};
+use crate::config::{apply_env_overrides, include, profiles, ApiConfig, CLIENT_TOKEN_ENV, CLIENT_URL_ENV};
+use crate::utils::hashrate_formatter::format_hashrate;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args as ClapArgs, Subcommand};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::path::Path;
+use std::time::Duration;
+
+/// 配置中没有 `[api]` 段时的默认端口
+const DEFAULT_API_PORT: u16 = 4028;
+
+/// 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 客户端子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum ClientCommand {
+    /// Show the mining status of a running instance
+    Status(ClientOptions),
+    /// List devices
+    Devices(ClientOptions),
+    /// List pools
+    Pools(ClientOptions),
+    /// Stop fetching new work until resumed (pool connections stay up)
+    Pause(ClientOptions),
+    /// Resume paused mining
+    Resume(ClientOptions),
+    /// Restart a device
+    RestartDevice {
+        /// Device ID
+        id: u32,
+        #[command(flatten)]
+        options: ClientOptions,
+    },
+}
+
+/// 连接选项
+#[derive(ClapArgs, Debug, Clone, Default)]
+pub struct ClientOptions {
+    /// API base URL (default: from the [api] section of the config file)
+    #[arg(long, env = CLIENT_URL_ENV)]
+    pub api_url: Option<String>,
+    /// API token or key (default: api.auth_token from the config file)
+    #[arg(long, env = CLIENT_TOKEN_ENV, hide_env_values = true)]
+    pub api_token: Option<String>,
+    /// Print the response data as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl ClientCommand {
+    pub fn options(&self) -> &ClientOptions {
+        match self {
+            ClientCommand::Status(options)
+            | ClientCommand::Devices(options)
+            | ClientCommand::Pools(options)
+            | ClientCommand::Pause(options)
+            | ClientCommand::Resume(options)
+            | ClientCommand::RestartDevice { options, .. } => options,
+        }
+    }
+}
+
+/// 解析出的连接目标
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    pub base_url: String,
+    pub token: Option<String>,
+    /// 实例使用自签名证书
+    pub accept_self_signed: bool,
+}
+
+impl Endpoint {
+    /// 命令行/环境变量优先，其余取自配置
+    pub fn resolve(options: &ClientOptions, api: Option<&ApiConfig>) -> Self {
+        let from_config = api.map(Self::from_api_config);
+        let base_url = options.api_url.clone()
+            .or_else(|| from_config.as_ref().map(|endpoint| endpoint.base_url.clone()))
+            .unwrap_or_else(|| format!("http://127.0.0.1:{}", DEFAULT_API_PORT));
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: options.api_token.clone().or_else(|| from_config.as_ref().and_then(|endpoint| endpoint.token.clone())),
+            accept_self_signed: from_config.map(|endpoint| endpoint.accept_self_signed).unwrap_or(false),
+        }
+    }
+
+    fn from_api_config(api: &ApiConfig) -> Self {
+        // 监听所有地址时从本机连接
+        let host = match api.bind_address.as_str() {
+            "" | "0.0.0.0" => "127.0.0.1".to_string(),
+            "::" | "[::]" => "[::1]".to_string(),
+            host if host.contains(':') && !host.starts_with('[') => format!("[{}]", host),
+            host => host.to_string(),
+        };
+        let port = if api.port == 0 { DEFAULT_API_PORT } else { api.port };
+        let scheme = if api.tls_enabled() { "https" } else { "http" };
+        Self {
+            base_url: format!("{}://{}:{}", scheme, host, port),
+            token: api.auth_token.clone(),
+            accept_self_signed: api.tls.self_signed,
+        }
+    }
+}
+
+/// 读取配置文件的 `[api]` 段；文件不存在时为 None
+pub fn api_config_from_file(path: &str, profile: Option<&str>) -> Result<Option<ApiConfig>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let mut value = include::load_layered(Path::new(path))?;
+    profiles::apply_profile(&mut value, profile, profiles::local_hostname().as_deref())?;
+    apply_env_overrides(&mut value, std::env::vars())?;
+    match value.get("api") {
+        Some(api) => Ok(Some(api.clone().try_into().context("Invalid [api] section")?)),
+        None => Ok(None),
+    }
+}
+
+/// API 客户端
+pub struct ApiClient {
+    endpoint: Endpoint,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    pub fn new(endpoint: Endpoint) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .danger_accept_invalid_certs(endpoint.accept_self_signed)
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self { endpoint, http })
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let request = match &self.endpoint.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request.send().await
+            .with_context(|| format!("Failed to reach the API at {}", self.endpoint.base_url))?;
+        let status = response.status();
+        let body: ApiResponse<serde_json::Value> = response.json().await
+            .with_context(|| format!("Unexpected response from the API (HTTP {})", status))?;
+        if !body.success || !status.is_success() {
+            let message = body.error.unwrap_or_else(|| format!("HTTP {}", status));
+            bail!("{}", message);
+        }
+        let data = body.data.ok_or_else(|| anyhow!("API response has no data"))?;
+        serde_json::from_value(data).context("Unexpected response data from the API")
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.send(self.http.get(format!("{}{}", self.endpoint.base_url, path))).await
+    }
+
+    pub async fn post<T: DeserializeOwned>(&self, path: &str, body: serde_json::Value) -> Result<T> {
+        self.send(self.http.post(format!("{}{}", self.endpoint.base_url, path)).json(&body)).await
+    }
+}
+
+/// 执行子命令，返回要打印的文本
+pub async fn run(command: &ClientCommand, client: &ApiClient) -> Result<String> {
+    let json = command.options().json;
+    let output = match command {
+        ClientCommand::Status(_) => {
+            let status: serde_json::Value = client.get("/api/v1/status").await?;
+            if json {
+                return Ok(serde_json::to_string_pretty(&status)?);
+            }
+            format_status(&serde_json::from_value(status)?)
+        }
+        ClientCommand::Devices(_) => {
+            let devices: serde_json::Value = client.get("/api/v1/devices").await?;
+            if json {
+                return Ok(serde_json::to_string_pretty(&devices)?);
+            }
+            format_devices(&serde_json::from_value(devices)?)
+        }
+        ClientCommand::Pools(_) => {
+            let pools: serde_json::Value = client.get("/api/v1/pools").await?;
+            if json {
+                return Ok(serde_json::to_string_pretty(&pools)?);
+            }
+            format_pools(&serde_json::from_value(pools)?)
+        }
+        ClientCommand::Pause(_) | ClientCommand::Resume(_) => {
+            let name = if matches!(command, ClientCommand::Pause(_)) { "pause" } else { "resume" };
+            let response: serde_json::Value = client.post("/api/v1/control", json!({ "command": name })).await?;
+            if response.get("success").and_then(|success| success.as_bool()) != Some(true) {
+                let message = response.get("message").and_then(|message| message.as_str()).unwrap_or("command failed");
+                bail!("{}", message);
+            }
+            if json {
+                return Ok(serde_json::to_string_pretty(&response)?);
+            }
+            response.get("message").and_then(|message| message.as_str()).unwrap_or_default().to_string()
+        }
+        ClientCommand::RestartDevice { id, .. } => {
+            let message: String = client.post(&format!("/api/v1/devices/{}/restart", id), json!({})).await?;
+            if json {
+                return Ok(serde_json::to_string_pretty(&message)?);
+            }
+            message
+        }
+    };
+    Ok(output)
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, secs % 60)
+    }
+}
+
+pub fn format_status(status: &SystemStatusResponse) -> String {
+    [
+        format!("State:      {}", status.mining_state),
+        format!("Version:    {}", status.version),
+        format!("Uptime:     {}", format_uptime(status.uptime)),
+        format!("Hashrate:   {}", format_hashrate(status.total_hashrate)),
+        format!("Shares:     {} accepted, {} rejected, {} HW errors",
+            status.accepted_shares, status.rejected_shares, status.hardware_errors),
+        format!("Devices:    {} active", status.active_devices),
+        format!("Pools:      {} connected", status.connected_pools),
+        format!("Difficulty: {} (best share {})", status.current_difficulty, status.best_share),
+    ].join("\n")
+}
+
+pub fn format_devices(devices: &[DeviceStatusResponse]) -> String {
+    let mut lines = vec![format!("{:>4}  {:<20} {:<10} {:>8} {:>14} {:>9} {:>9} {:>6}",
+        "ID", "NAME", "STATUS", "TEMP", "HASHRATE", "ACCEPTED", "REJECTED", "HW")];
+    for device in devices {
+        let temperature = device.temperature.map(|t| format!("{:.1}°C", t)).unwrap_or_else(|| "-".to_string());
+        lines.push(format!("{:>4}  {:<20} {:<10} {:>8} {:>14} {:>9} {:>9} {:>6}",
+            device.device_id, device.name, device.status, temperature, format_hashrate(device.hashrate),
+            device.accepted_shares, device.rejected_shares, device.hardware_errors));
+    }
+    lines.join("\n")
+}
+
+pub fn format_pools(pools: &[PoolStatusResponse]) -> String {
+    let mut lines = vec![format!("{:>4}  {:<40} {:<12} {:>4} {:>9} {:>9} {:>6} {:>8}",
+        "ID", "URL", "STATUS", "PRIO", "ACCEPTED", "REJECTED", "STALE", "PING")];
+    for pool in pools {
+        let ping = pool.ping.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "-".to_string());
+        lines.push(format!("{:>4}  {:<40} {:<12} {:>4} {:>9} {:>9} {:>6} {:>8}",
+            pool.pool_id, pool.url, pool.status, pool.priority,
+            pool.accepted_shares, pool.rejected_shares, pool.stale_shares, ping));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_config(bind_address: &str, port: u16) -> ApiConfig {
+        ApiConfig {
+            enabled: true,
+            bind_address: bind_address.to_string(),
+            port,
+            auth_token: Some("config-token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_endpoint_from_config() {
+        let endpoint = Endpoint::resolve(&ClientOptions::default(), Some(&api_config("0.0.0.0", 4029)));
+        assert_eq!(endpoint.base_url, "http://127.0.0.1:4029");
+        assert_eq!(endpoint.token.as_deref(), Some("config-token"));
+
+        let endpoint = Endpoint::resolve(&ClientOptions::default(), Some(&api_config("::", 0)));
+        assert_eq!(endpoint.base_url, format!("http://[::1]:{}", DEFAULT_API_PORT));
+
+        let endpoint = Endpoint::resolve(&ClientOptions::default(), None);
+        assert_eq!(endpoint.base_url, format!("http://127.0.0.1:{}", DEFAULT_API_PORT));
+        assert_eq!(endpoint.token, None);
+    }
+
+    #[test]
+    fn test_options_override_config() {
+        let options = ClientOptions {
+            api_url: Some("https://miner-07:4028/".to_string()),
+            api_token: Some("cli-token".to_string()),
+            json: false,
+        };
+        let endpoint = Endpoint::resolve(&options, Some(&api_config("192.168.1.7", 4028)));
+        assert_eq!(endpoint.base_url, "https://miner-07:4028");
+        assert_eq!(endpoint.token.as_deref(), Some("cli-token"));
+    }
+
+    #[test]
+    fn test_reads_api_section() {
+        let dir = std::env::temp_dir().join(format!("cgminer-client-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cgminer.toml");
+        std::fs::write(&path, "[api]\nenabled = true\nbind_address = \"127.0.0.1\"\nport = 4100\nauth_token = \"secret\"\n").unwrap();
+        let api = api_config_from_file(path.to_str().unwrap(), None).unwrap().unwrap();
+        assert_eq!(api.port, 4100);
+        assert_eq!(api.auth_token.as_deref(), Some("secret"));
+        assert!(api_config_from_file(dir.join("missing.toml").to_str().unwrap(), None).unwrap().is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_format_devices() {
+        let devices = vec![DeviceStatusResponse {
+            device_id: 3,
+            name: "chain-3".to_string(),
+            status: "Mining".to_string(),
+            temperature: Some(71.25),
+            hashrate: 0.0,
+            accepted_shares: 12,
+            rejected_shares: 1,
+            hardware_errors: 0,
+            uptime: 60,
+            last_share_time: None,
+        }];
+        let output = format_devices(&devices);
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.lines().nth(1).unwrap().contains("chain-3"));
+        assert!(output.contains("71.2°C") || output.contains("71.3°C"));
+        assert_eq!(format_uptime(3723), "1h 2m");
+    }
+}
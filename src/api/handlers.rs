@@ -1,7 +1,18 @@
 use crate::api::{
     AppState, ApiResponse, SystemStatusResponse, DeviceStatusResponse,
-    PoolStatusResponse, StatsResponse, ConfigUpdateRequest, ControlRequest, ControlResponse
+    PoolStatusResponse, StatsResponse, ConfigUpdateRequest, ControlRequest, ControlResponse,
+    FanOverrideRequest, DeviceMappingResponse, CoreDevicesResponse, DeviceControlRequest,
+    CoreConfigUpdateRequest, CoreConfigResponse, CoreStatsResponse, PoolDeviceStatsData,
 };
+use crate::config::{FREQUENCY_RANGE, VOLTAGE_RANGE};
+use crate::device::{DeviceInfo, DeviceStatus, FanStatus, RampUpStatus, AutoTuneStatus, PowerStatus, ChipHeatmap};
+use crate::device::eeprom::BoardEeprom;
+use crate::error::{DeviceError, MiningError};
+use crate::logging::filter::{self as log_filter, LogFilterSettings, LogFilterUpdate};
+use crate::logging::redact;
+use crate::mining::{HashBackend, RuntimeSettings, RuntimeSettingsUpdate, QueueStats};
+use crate::monitoring::MemoryStatus;
+use crate::pool::PoolDeviceStats;
 use axum::{
     extract::{Path, State, Query},
     http::StatusCode,
@@ -52,6 +63,7 @@ pub async fn get_stats(
         rejected_shares: mining_stats.rejected_shares,
         hardware_errors: mining_stats.hardware_errors,
         stale_shares: mining_stats.stale_shares,
+        below_target_results: mining_stats.below_target_results,
         best_share: mining_stats.best_share,
         current_difficulty: mining_stats.current_difficulty,
         average_hashrate: mining_stats.average_hashrate,
@@ -76,42 +88,13 @@ pub async fn get_stats(
 
 /// 获取所有设备
 pub async fn get_devices(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<Vec<DeviceStatusResponse>>>, (StatusCode, Json<ApiResponse<()>>)> {
-    // 这里应该从设备管理器获取实际的设备列表
-    // 为了简化，我们返回模拟数据
-    let devices = vec![
-        DeviceStatusResponse {
-            device_id: 0,
-            name: "Maijie L7 Chain 0".to_string(),
-            status: "Mining".to_string(),
-            temperature: Some(65.5),
-            hashrate: 38.0,
-            accepted_shares: 1250,
-            rejected_shares: 15,
-            hardware_errors: 2,
-            uptime: 3600,
-            last_share_time: Some(std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs()),
-        },
-        DeviceStatusResponse {
-            device_id: 1,
-            name: "Maijie L7 Chain 1".to_string(),
-            status: "Mining".to_string(),
-            temperature: Some(67.2),
-            hashrate: 37.5,
-            accepted_shares: 1180,
-            rejected_shares: 12,
-            hardware_errors: 1,
-            uptime: 3600,
-            last_share_time: Some(std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs()),
-        },
-    ];
+    let mut devices: Vec<DeviceStatusResponse> = state.mining_manager.get_all_device_info().await
+        .into_iter()
+        .map(device_status_response)
+        .collect();
+    devices.sort_by_key(|device| device.device_id);
 
     Ok(Json(ApiResponse::success(devices)))
 }
@@ -119,73 +102,331 @@ pub async fn get_devices(
 /// 获取单个设备
 pub async fn get_device(
     Path(device_id): Path<u32>,
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<DeviceStatusResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
-    // 这里应该从设备管理器获取实际的设备信息
-    // 为了简化，我们返回模拟数据
-    if device_id > 1 {
-        return Err((
+    let device = state.mining_manager.get_all_device_info().await
+        .into_iter()
+        .find(|info| info.id == device_id)
+        .ok_or_else(|| (
             StatusCode::NOT_FOUND,
             Json(ApiResponse::error(format!("Device {} not found", device_id))),
-        ));
-    }
+        ))?;
 
-    let device = DeviceStatusResponse {
-        device_id,
-        name: format!("Maijie L7 Chain {}", device_id),
-        status: "Mining".to_string(),
-        temperature: Some(65.5 + device_id as f32),
-        hashrate: 38.0 - device_id as f64 * 0.5,
-        accepted_shares: 1250 - device_id as u64 * 70,
-        rejected_shares: 15 - device_id as u64 * 3,
-        hardware_errors: 2 - device_id as u64,
-        uptime: 3600,
-        last_share_time: Some(std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()),
-    };
+    Ok(Json(ApiResponse::success(device_status_response(device))))
+}
 
-    Ok(Json(ApiResponse::success(device)))
+/// 设备信息转为 API 响应；核心设备的数字由核心汇总统计按设备数平均得到
+fn device_status_response(info: DeviceInfo) -> DeviceStatusResponse {
+    let status = match &info.status {
+        DeviceStatus::Error(_) => "Error".to_string(),
+        status => format!("{:?}", status),
+    };
+    DeviceStatusResponse {
+        device_id: info.id,
+        name: info.name,
+        status,
+        temperature: info.temperature,
+        hashrate: info.hashrate,
+        accepted_shares: info.accepted_shares,
+        rejected_shares: info.rejected_shares,
+        hardware_errors: info.hardware_errors,
+        uptime: info.uptime.as_secs(),
+        last_share_time: info.last_share_time
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_secs()),
+    }
 }
 
 /// 重启设备
 pub async fn restart_device(
     Path(device_id): Path<u32>,
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
     info!("Restarting device {}", device_id);
 
-    // 这里应该调用设备管理器的重启方法
-    // 为了简化，我们只是返回成功消息
-    if device_id > 1 {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::error(format!("Device {} not found", device_id))),
-        ));
-    }
+    state.mining_manager.restart_device(device_id).await
+        .map_err(device_error_response)?;
 
     Ok(Json(ApiResponse::success(format!("Device {} restart initiated", device_id))))
 }
 
-/// 更新设备配置
+/// 启用设备
+pub async fn enable_device(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
+    state.mining_manager.set_device_enabled(device_id, true).await
+        .map_err(device_error_response)?;
+
+    Ok(Json(ApiResponse::success(format!("Device {} enabled", device_id))))
+}
+
+/// 禁用设备
+pub async fn disable_device(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
+    state.mining_manager.set_device_enabled(device_id, false).await
+        .map_err(device_error_response)?;
+
+    Ok(Json(ApiResponse::success(format!("Device {} disabled", device_id))))
+}
+
+/// 更新设备配置：启用/禁用、频率和电压
 pub async fn update_device_config(
     Path(device_id): Path<u32>,
-    State(_state): State<AppState>,
-    Json(config): Json<serde_json::Value>,
+    State(state): State<AppState>,
+    Json(request): Json<DeviceControlRequest>,
 ) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
-    info!("Updating device {} configuration: {:?}", device_id, config);
+    info!("Updating device {} configuration: {:?}", device_id, request);
 
-    // 这里应该验证配置并应用到设备
-    // 为了简化，我们只是返回成功消息
-    if device_id > 1 {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, Json(ApiResponse::error(message)));
+    if request.enabled.is_none() && request.frequency.is_none() && request.voltage.is_none() {
+        return Err(bad_request("No device settings given (enabled, frequency, voltage)".to_string()));
+    }
+    if let Some(frequency) = request.frequency.filter(|f| !FREQUENCY_RANGE.contains(f)) {
+        return Err(bad_request(format!(
+            "Frequency {} MHz is out of range ({}-{})", frequency, FREQUENCY_RANGE.start(), FREQUENCY_RANGE.end()
+        )));
+    }
+    if let Some(voltage) = request.voltage.filter(|v| !VOLTAGE_RANGE.contains(v)) {
+        return Err(bad_request(format!(
+            "Voltage {} mV is out of range ({}-{})", voltage, VOLTAGE_RANGE.start(), VOLTAGE_RANGE.end()
+        )));
+    }
+
+    let manager = &state.mining_manager;
+    // 先禁用再调参，启用放在最后，让设备以新的参数启动
+    if request.enabled == Some(false) {
+        manager.set_device_enabled(device_id, false).await.map_err(device_error_response)?;
+    }
+    if let Some(frequency) = request.frequency {
+        manager.set_device_frequency(device_id, frequency).await.map_err(device_error_response)?;
+    }
+    if let Some(voltage) = request.voltage {
+        manager.set_device_voltage(device_id, voltage).await.map_err(device_error_response)?;
+    }
+    if request.enabled == Some(true) {
+        manager.set_device_enabled(device_id, true).await.map_err(device_error_response)?;
+    }
+
+    Ok(Json(ApiResponse::success(format!("Device {} configuration updated", device_id))))
+}
+
+/// 获取设备分批启动状态
+pub async fn get_ramp_up_status(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<RampUpStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let status = state.mining_manager.get_ramp_up_status().await;
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// 获取各链自动调优进度
+pub async fn get_auto_tune_status(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<AutoTuneStatus>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let statuses = state.mining_manager.get_auto_tune_status().await;
+    Ok(Json(ApiResponse::success(statuses)))
+}
+
+/// 获取板卡功率和功率上限状态
+pub async fn get_power_status(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<PowerStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let status = state.mining_manager.get_power_status().await;
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// 获取设备算力板的 EEPROM 信息 (序列号、版本、校准数据)
+pub async fn get_device_eeprom(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<BoardEeprom>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let eeprom = state.mining_manager.get_board_eeprom(device_id).await.ok_or_else(|| (
+        StatusCode::NOT_FOUND,
+        Json(ApiResponse::error(format!("No EEPROM data for device {}", device_id))),
+    ))?;
+    Ok(Json(ApiResponse::success(eeprom)))
+}
+
+/// 获取所有设备的芯片热力图
+pub async fn get_chip_heatmaps(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<ChipHeatmap>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let heatmaps = state.mining_manager.get_chip_heatmaps().await;
+    Ok(Json(ApiResponse::success(heatmaps)))
+}
+
+/// 获取单个设备的芯片热力图
+pub async fn get_device_chips(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<ChipHeatmap>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let heatmap = state.mining_manager.get_chip_heatmap(device_id).await
+        .map_err(device_error_response)?
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!("Device {} does not report per-chip statistics", device_id))),
+        ))?;
+    Ok(Json(ApiResponse::success(heatmap)))
+}
+
+/// 获取设备的核心映射
+pub async fn get_device_mapping(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<DeviceMappingResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let mapping = state.mining_manager.get_device_mapping(device_id).await.ok_or_else(|| (
+        StatusCode::NOT_FOUND,
+        Json(ApiResponse::error(format!("No core mapping for device {}", device_id))),
+    ))?;
+    let stats = state.mining_manager.get_mapping_stats().await;
+
+    Ok(Json(ApiResponse::success(DeviceMappingResponse { mapping, stats })))
+}
+
+/// 获取核心映射的所有设备
+pub async fn get_core_devices(
+    Path(core_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<CoreDevicesResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let mappings = state.mining_manager.get_core_device_mappings(&core_id).await;
+    if mappings.is_empty() {
         return Err((
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::error(format!("Device {} not found", device_id))),
+            Json(ApiResponse::error(format!("No devices mapped to core {}", core_id))),
         ));
     }
+    let stats = state.mining_manager.get_mapping_stats().await;
+
+    Ok(Json(ApiResponse::success(CoreDevicesResponse {
+        core_id,
+        device_ids: mappings.iter().map(|m| m.device_id).collect(),
+        mappings,
+        stats,
+    })))
+}
 
-    Ok(Json(ApiResponse::success(format!("Device {} configuration updated", device_id))))
+/// 获取核心当前的配置
+pub async fn get_core_config(
+    Path(core_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<CoreConfigResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let config = state.mining_manager.get_core_config(&core_id).await
+        .ok_or_else(|| core_not_found(&core_id))?;
+    Ok(Json(ApiResponse::success(CoreConfigResponse {
+        core_id,
+        name: config.name,
+        enabled: config.enabled,
+        custom_params: config.custom_params,
+    })))
+}
+
+/// 获取核心自身上报的统计
+pub async fn get_core_stats(
+    Path(core_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<CoreStatsResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    if state.mining_manager.get_core_config(&core_id).await.is_none() {
+        return Err(core_not_found(&core_id));
+    }
+    let stats = state.mining_manager.get_core_stats(&core_id).await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, Json(ApiResponse::error(e.to_string()))))?;
+
+    Ok(Json(ApiResponse::success(CoreStatsResponse {
+        core_id,
+        total_hashrate: stats.total_hashrate,
+        average_hashrate: stats.average_hashrate,
+        active_devices: stats.active_devices as u64,
+        accepted_work: stats.accepted_work,
+        rejected_work: stats.rejected_work,
+        hardware_errors: stats.hardware_errors,
+    })))
+}
+
+/// 更新核心的配置 (批大小、目标算力、设备数量等)，核心以新配置重建，响应中是新的核心 ID
+pub async fn update_core_config(
+    Path(core_id): Path<String>,
+    State(state): State<AppState>,
+    Json(mut request): Json<CoreConfigUpdateRequest>,
+) -> Result<Json<ApiResponse<CoreConfigResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
+    info!("Updating core {} configuration: {:?}", core_id, request);
+
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, Json(ApiResponse::error(message)));
+    if request.custom_params.is_empty() && request.enabled.is_none() {
+        return Err(bad_request("No core settings given (custom_params, enabled)".to_string()));
+    }
+    for key in ["batch_size", "device_count"] {
+        if let Some(value) = request.custom_params.get(key).filter(|value| !value.is_null()) {
+            if value.as_u64().map_or(true, |count| count == 0) {
+                return Err(bad_request(format!("{} must be a positive integer", key)));
+            }
+        }
+    }
+    if let Some(value) = request.custom_params.get("target_hashrate").filter(|value| !value.is_null()) {
+        if value.as_f64().map_or(true, |rate| rate <= 0.0) {
+            return Err(bad_request("target_hashrate must be a positive number".to_string()));
+        }
+    }
+    // auto 在这里解析为具体后端，核心只收到本机支持的后端
+    if let Some(value) = request.custom_params.get("hash_backend").filter(|value| !value.is_null()).cloned() {
+        let backend: HashBackend = serde_json::from_value(value.clone())
+            .map_err(|_| bad_request(format!("Unknown hash_backend {}", value)))?;
+        let backend = backend.resolve().map_err(bad_request)?;
+        request.custom_params.insert("hash_backend".to_string(), serde_json::Value::String(backend.to_string()));
+    }
+
+    let manager = &state.mining_manager;
+    if manager.get_core_config(&core_id).await.is_none() {
+        return Err(core_not_found(&core_id));
+    }
+    let (core_id, config) = manager.update_core_config(&core_id, request.custom_params, request.enabled).await
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, Json(ApiResponse::error(e.to_string()))))?;
+
+    Ok(Json(ApiResponse::success(CoreConfigResponse {
+        core_id,
+        name: config.name,
+        enabled: config.enabled,
+        custom_params: config.custom_params,
+    })))
+}
+
+fn core_not_found(core_id: &str) -> (StatusCode, Json<ApiResponse<()>>) {
+    (StatusCode::NOT_FOUND, Json(ApiResponse::error(format!("Core {} not found", core_id))))
+}
+
+/// 获取设备风扇状态
+pub async fn get_device_fan(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<FanStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    state.mining_manager.get_device_fan_status(device_id).await
+        .map(|status| Json(ApiResponse::success(status)))
+        .map_err(device_error_response)
+}
+
+/// 设置或清除设备风扇手动覆盖
+pub async fn set_device_fan(
+    Path(device_id): Path<u32>,
+    State(state): State<AppState>,
+    Json(request): Json<FanOverrideRequest>,
+) -> Result<Json<ApiResponse<FanStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    info!("Setting device {} fan override: {:?}", device_id, request.speed);
+
+    state.mining_manager.set_device_fan_override(device_id, request.speed).await
+        .map_err(device_error_response)?;
+    state.mining_manager.get_device_fan_status(device_id).await
+        .map(|status| Json(ApiResponse::success(status)))
+        .map_err(device_error_response)
+}
+
+/// 将设备相关错误转换为 API 错误响应
+fn device_error_response(error: MiningError) -> (StatusCode, Json<ApiResponse<()>>) {
+    let status = match &error {
+        MiningError::Device(DeviceError::NotFound { .. }) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiResponse::error(error.to_string())))
 }
 
 /// 获取所有矿池
@@ -264,13 +505,35 @@ pub async fn get_pool(
     Ok(Json(ApiResponse::success(pool)))
 }
 
+/// 按 (矿池, 设备) 统计的查询参数
+#[derive(Debug, Deserialize)]
+pub struct PoolDeviceStatsQuery {
+    pub pool: Option<u32>,
+    pub device: Option<u32>,
+}
+
+/// 获取按 (矿池, 设备) 统计的份额，可按矿池或设备过滤
+pub async fn get_pool_device_stats(
+    Query(query): Query<PoolDeviceStatsQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<PoolDeviceStatsData>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let stats = state.mining_manager.get_pool_device_stats().await
+        .into_iter()
+        .filter(|s| query.pool.map_or(true, |pool| s.pool_id == pool))
+        .filter(|s| query.device.map_or(true, |device| s.device_id == device))
+        .map(PoolDeviceStatsData::from)
+        .collect();
+
+    Ok(Json(ApiResponse::success(stats)))
+}
+
 /// 更新矿池配置
 pub async fn update_pool_config(
     Path(pool_id): Path<u32>,
     State(_state): State<AppState>,
     Json(config): Json<serde_json::Value>,
 ) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
-    info!("Updating pool {} configuration: {:?}", pool_id, config);
+    info!("Updating pool {} configuration: {}", pool_id, redact::to_redacted_json(&config));
 
     // 这里应该验证配置并应用到矿池
     // 为了简化，我们只是返回成功消息
@@ -286,7 +549,7 @@ pub async fn update_pool_config(
 
 /// 控制命令
 pub async fn control_command(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(request): Json<ControlRequest>,
 ) -> Result<Json<ApiResponse<ControlResponse>>, (StatusCode, Json<ApiResponse<()>>)> {
     info!("Executing control command: {}", request.command);
@@ -310,18 +573,36 @@ pub async fn control_command(
             message: "Mining restarted successfully".to_string(),
             result: None,
         },
-        "pause" => ControlResponse {
-            command: request.command.clone(),
-            success: true,
-            message: "Mining paused successfully".to_string(),
-            result: None,
-        },
-        "resume" => ControlResponse {
-            command: request.command.clone(),
-            success: true,
-            message: "Mining resumed successfully".to_string(),
-            result: None,
-        },
+        "pause" | "resume" => {
+            let result = if request.command == "pause" {
+                state.mining_manager.pause().await
+            } else {
+                state.mining_manager.resume().await
+            };
+            match result {
+                Ok(()) => ControlResponse {
+                    command: request.command.clone(),
+                    success: true,
+                    message: format!("Mining {}d successfully", request.command),
+                    result: None,
+                },
+                Err(e) => ControlResponse {
+                    command: request.command.clone(),
+                    success: false,
+                    message: e.to_string(),
+                    result: None,
+                },
+            }
+        }
+        "stats" => {
+            let stats = state.mining_manager.get_pool_device_stats().await;
+            ControlResponse {
+                command: request.command.clone(),
+                success: true,
+                message: format!("{} pool/device entries", stats.len()),
+                result: Some(cgminer_stats(stats)),
+            }
+        }
         _ => ControlResponse {
             command: request.command.clone(),
             success: false,
@@ -333,19 +614,123 @@ pub async fn control_command(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// cgminer `stats` 命令格式的 (矿池, 设备) 份额统计，供按 cgminer API 解析的监控工具使用
+fn cgminer_stats(stats: Vec<PoolDeviceStats>) -> serde_json::Value {
+    let entries: Vec<serde_json::Value> = stats.into_iter().enumerate().map(|(index, stats)| {
+        let data = PoolDeviceStatsData::from(stats);
+        serde_json::json!({
+            "STATS": index,
+            "ID": format!("POOL{}-DEV{}", data.pool_id, data.device_id),
+            "Pool": data.pool_id,
+            "Device": data.device_id,
+            "Accepted": data.accepted_shares,
+            "Rejected": data.rejected_shares,
+            "Difficulty Accepted": data.accepted_difficulty,
+            "Difficulty Rejected": data.rejected_difficulty,
+            "Pool Rejected%": data.reject_rate,
+            "Last Share Time": data.last_share_time.unwrap_or(0),
+            "Reject Reasons": data.reject_reasons,
+        })
+    }).collect();
+
+    serde_json::json!({ "STATS": entries })
+}
+
+/// 获取当前配置，敏感字段已遮蔽
+pub async fn get_config(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let config = state.mining_manager.get_config().await;
+    Ok(Json(ApiResponse::success(redact::to_redacted_json(&config))))
+}
+
 /// 更新配置
 pub async fn update_config(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Json(request): Json<ConfigUpdateRequest>,
 ) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
     info!("Updating configuration: {:?}", request);
 
-    // 这里应该验证配置并应用更改
-    // 为了简化，我们只是返回成功消息
+    // 挖矿间隔设置可以在运行时生效
+    if let Some(mining_config) = &request.mining_config {
+        let update = RuntimeSettingsUpdate {
+            scan_time: mining_config.scan_interval,
+            work_restart_timeout: mining_config.work_restart_timeout,
+            ..Default::default()
+        };
+        state.mining_manager.update_runtime_settings(update).await
+            .map_err(settings_error_response)?;
+    }
+
+    // 其他配置项的运行时应用尚未实现
 
     Ok(Json(ApiResponse::success("Configuration updated successfully".to_string())))
 }
 
+/// 获取运行时设置
+pub async fn get_runtime_settings(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<RuntimeSettings>>, (StatusCode, Json<ApiResponse<()>>)> {
+    Ok(Json(ApiResponse::success(state.mining_manager.get_runtime_settings().await)))
+}
+
+/// 获取工作/结果队列的深度和丢弃计数
+pub async fn get_queue_stats(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<QueueStats>>>, (StatusCode, Json<ApiResponse<()>>)> {
+    Ok(Json(ApiResponse::success(state.mining_manager.get_queue_stats().await)))
+}
+
+/// 获取内存预算状态
+pub async fn get_memory_status(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<MemoryStatus>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let status = state.mining_manager.get_monitoring_system().memory_status().await;
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// 更新运行时设置
+pub async fn update_runtime_settings(
+    State(state): State<AppState>,
+    Json(request): Json<RuntimeSettingsUpdate>,
+) -> Result<Json<ApiResponse<RuntimeSettings>>, (StatusCode, Json<ApiResponse<()>>)> {
+    info!("Updating runtime settings: {:?}", request);
+
+    state.mining_manager.update_runtime_settings(request).await
+        .map(|settings| Json(ApiResponse::success(settings)))
+        .map_err(settings_error_response)
+}
+
+/// 获取日志过滤设置
+pub async fn get_log_filter() -> Result<Json<ApiResponse<LogFilterSettings>>, (StatusCode, Json<ApiResponse<()>>)> {
+    log_filter::current()
+        .map(|settings| Json(ApiResponse::success(settings)))
+        .ok_or_else(|| (StatusCode::SERVICE_UNAVAILABLE, Json(ApiResponse::error("Logging is not initialized".to_string()))))
+}
+
+/// 运行时调整日志级别和按目标覆盖，不写回配置文件
+pub async fn update_log_filter(
+    Json(request): Json<LogFilterUpdate>,
+) -> Result<Json<ApiResponse<LogFilterSettings>>, (StatusCode, Json<ApiResponse<()>>)> {
+    let settings = log_filter::update(request).map_err(settings_error_response)?;
+    info!("Log filter updated: level={}, targets={}", settings.level, settings.targets);
+    Ok(Json(ApiResponse::success(settings)))
+}
+
+/// 将当前运行时设置写回配置文件
+pub async fn save_config(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<String>>, (StatusCode, Json<ApiResponse<()>>)> {
+    state.mining_manager.save_runtime_settings().await
+        .map(|path| Json(ApiResponse::success(format!("Settings saved to {}", path))))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e.to_string()))))
+}
+
+/// 设置验证失败返回 400
+fn settings_error_response(error: MiningError) -> (StatusCode, Json<ApiResponse<()>>) {
+    (StatusCode::BAD_REQUEST, Json(ApiResponse::error(error.to_string())))
+}
+
 /// 查询参数
 #[derive(Debug, Deserialize)]
 pub struct QueryParams {
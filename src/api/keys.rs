@@ -0,0 +1,76 @@
+//! API 密钥管理接口 (`/api/v1/keys`)，需要 admin 权限
+
+use crate::api::ApiResponse;
+use crate::error::MiningError;
+use crate::security::auth::{ApiKeyInfo, IssuedKey, MAX_API_KEYS};
+use crate::security::{ApiKeyStore, ApiScope};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::{delete, get, post};
+use axum::Router;
+use serde::Deserialize;
+use std::sync::Arc;
+
+type ApiResult<T> = Result<Json<ApiResponse<T>>, (StatusCode, Json<ApiResponse<()>>)>;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateKeyRequest {
+    pub name: String,
+    pub scope: ApiScope,
+}
+
+pub fn routes(store: Arc<ApiKeyStore>) -> Router {
+    Router::new()
+        .route("/api/v1/keys", get(list_keys).post(create_key))
+        .route("/api/v1/keys/:id/rotate", post(rotate_key))
+        .route("/api/v1/keys/:id", delete(revoke_key))
+        .with_state(store)
+}
+
+fn store_error(e: MiningError) -> (StatusCode, Json<ApiResponse<()>>) {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiResponse::error(e.to_string())))
+}
+
+fn not_found(id: &str) -> (StatusCode, Json<ApiResponse<()>>) {
+    (StatusCode::NOT_FOUND, Json(ApiResponse::error(format!("API key {} not found", id))))
+}
+
+/// 列出密钥
+pub async fn list_keys(State(store): State<Arc<ApiKeyStore>>) -> ApiResult<Vec<ApiKeyInfo>> {
+    Ok(Json(ApiResponse::success(store.list().await)))
+}
+
+/// 创建密钥，明文只在响应中出现这一次
+pub async fn create_key(
+    State(store): State<Arc<ApiKeyStore>>,
+    Json(request): Json<CreateKeyRequest>,
+) -> ApiResult<IssuedKey> {
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, Json(ApiResponse::error(message)));
+    let name_len = request.name.trim().chars().count();
+    if name_len == 0 || name_len > 64 {
+        return Err(bad_request("API key name must be 1-64 characters".to_string()));
+    }
+    if store.list().await.len() >= MAX_API_KEYS {
+        return Err(bad_request(format!("At most {} API keys can be stored", MAX_API_KEYS)));
+    }
+    store.create(&request.name, request.scope).await
+        .map(|issued| Json(ApiResponse::success(issued)))
+        .map_err(store_error)
+}
+
+/// 轮换密钥，旧明文立即失效
+pub async fn rotate_key(State(store): State<Arc<ApiKeyStore>>, Path(id): Path<String>) -> ApiResult<IssuedKey> {
+    match store.rotate(&id).await.map_err(store_error)? {
+        Some(issued) => Ok(Json(ApiResponse::success(issued))),
+        None => Err(not_found(&id)),
+    }
+}
+
+/// 吊销密钥
+pub async fn revoke_key(State(store): State<Arc<ApiKeyStore>>, Path(id): Path<String>) -> ApiResult<ApiKeyInfo> {
+    match store.revoke(&id).await.map_err(store_error)? {
+        Some(key) => Ok(Json(ApiResponse::success(key))),
+        None => Err(not_found(&id)),
+    }
+}
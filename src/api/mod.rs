@@ -2,8 +2,17 @@ pub mod server;
 pub mod handlers;
 pub mod websocket;
 pub mod auth;
+pub mod keys;
+pub mod audit;
+pub mod tls;
+pub mod client;
+#[cfg(feature = "profiling")]
+pub mod profiling;
 
 use crate::mining::MiningManager;
+use crate::logging::redact;
+use crate::device::{DeviceCoreMapping, FanSpeedTarget, MappingStats};
+use crate::pool::PoolDeviceStats;
 use axum::{
     http::StatusCode,
     response::Json,
@@ -11,9 +20,8 @@ use axum::{
     Router,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
 pub use handlers::*;
 
@@ -115,6 +123,7 @@ pub struct MiningStatsData {
     pub rejected_shares: u64,
     pub hardware_errors: u64,
     pub stale_shares: u64,
+    pub below_target_results: u64,
     pub best_share: f64,
     pub current_difficulty: f64,
     pub average_hashrate: f64,
@@ -151,6 +160,84 @@ pub struct PoolStatsData {
     pub disconnection_count: u32,
 }
 
+/// 按 (矿池, 设备) 统计的份额
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolDeviceStatsData {
+    pub pool_id: u32,
+    pub device_id: u32,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub accepted_difficulty: f64,
+    pub rejected_difficulty: f64,
+    /// 拒绝率 (%)
+    pub reject_rate: f64,
+    pub last_share_time: Option<u64>,
+    pub reject_reasons: BTreeMap<String, u64>,
+}
+
+impl From<PoolDeviceStats> for PoolDeviceStatsData {
+    fn from(stats: PoolDeviceStats) -> Self {
+        Self {
+            pool_id: stats.pool_id,
+            device_id: stats.device_id,
+            accepted_shares: stats.accepted_shares,
+            rejected_shares: stats.rejected_shares,
+            accepted_difficulty: stats.accepted_difficulty,
+            rejected_difficulty: stats.rejected_difficulty,
+            reject_rate: stats.get_reject_rate(),
+            last_share_time: stats.last_share_time
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|time| time.as_secs()),
+            reject_reasons: stats.reject_reasons,
+        }
+    }
+}
+
+/// 设备核心映射响应
+#[derive(Debug, Serialize)]
+pub struct DeviceMappingResponse {
+    pub mapping: DeviceCoreMapping,
+    pub stats: MappingStats,
+}
+
+/// 核心设备列表响应
+#[derive(Debug, Serialize)]
+pub struct CoreDevicesResponse {
+    pub core_id: String,
+    pub device_ids: Vec<u32>,
+    pub mappings: Vec<DeviceCoreMapping>,
+    pub stats: MappingStats,
+}
+
+/// 核心统计响应
+#[derive(Debug, Serialize)]
+pub struct CoreStatsResponse {
+    pub core_id: String,
+    pub total_hashrate: f64,
+    pub average_hashrate: f64,
+    pub active_devices: u64,
+    pub accepted_work: u64,
+    pub rejected_work: u64,
+    pub hardware_errors: u64,
+}
+
+/// 核心配置更新请求，`custom_params` 合并到当前参数 (null 删除该键)
+#[derive(Debug, Deserialize)]
+pub struct CoreConfigUpdateRequest {
+    #[serde(default)]
+    pub custom_params: std::collections::HashMap<String, serde_json::Value>,
+    pub enabled: Option<bool>,
+}
+
+/// 核心配置响应
+#[derive(Debug, Serialize)]
+pub struct CoreConfigResponse {
+    pub core_id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub custom_params: std::collections::HashMap<String, serde_json::Value>,
+}
+
 /// 配置更新请求
 #[derive(Debug, Deserialize)]
 pub struct ConfigUpdateRequest {
@@ -168,7 +255,24 @@ pub struct DeviceConfigUpdate {
     pub auto_tune: Option<bool>,
 }
 
+/// 设备控制请求，未给出的字段保持不变
+#[derive(Debug, Deserialize)]
+pub struct DeviceControlRequest {
+    pub enabled: Option<bool>,
+    /// 频率 (MHz)
+    pub frequency: Option<u32>,
+    /// 电压 (mV)
+    pub voltage: Option<u32>,
+}
+
+/// 风扇手动覆盖请求，`speed` 为空时清除覆盖
 #[derive(Debug, Deserialize)]
+pub struct FanOverrideRequest {
+    #[serde(default)]
+    pub speed: Option<FanSpeedTarget>,
+}
+
+#[derive(Deserialize)]
 pub struct PoolConfigUpdate {
     pub pool_id: u32,
     pub enabled: Option<bool>,
@@ -178,6 +282,19 @@ pub struct PoolConfigUpdate {
     pub password: Option<String>,
 }
 
+impl std::fmt::Debug for PoolConfigUpdate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PoolConfigUpdate")
+            .field("pool_id", &self.pool_id)
+            .field("enabled", &self.enabled)
+            .field("priority", &self.priority)
+            .field("url", &self.url)
+            .field("user", &self.user)
+            .field("password", &redact::mask_option(&self.password))
+            .finish()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MiningConfigUpdate {
     pub work_restart_timeout: Option<u64>,
@@ -242,18 +359,39 @@ pub fn create_routes(state: AppState) -> Router {
 
         // 设备管理路由
         .route("/api/v1/devices", get(get_devices))
+        .route("/api/v1/devices/ramp-up", get(get_ramp_up_status))
+        .route("/api/v1/devices/auto-tune", get(get_auto_tune_status))
+        .route("/api/v1/devices/power", get(get_power_status))
+        .route("/api/v1/devices/chips", get(get_chip_heatmaps))
         .route("/api/v1/devices/:id", get(get_device))
+        .route("/api/v1/devices/:id/chips", get(get_device_chips))
+        .route("/api/v1/devices/:id/eeprom", get(get_device_eeprom))
         .route("/api/v1/devices/:id/restart", post(restart_device))
-        .route("/api/v1/devices/:id/config", post(update_device_config))
+        .route("/api/v1/devices/:id/enable", post(enable_device))
+        .route("/api/v1/devices/:id/disable", post(disable_device))
+        .route("/api/v1/devices/:id/config", post(update_device_config).put(update_device_config))
+        .route("/api/v1/devices/:id/fan", get(get_device_fan).post(set_device_fan))
+        .route("/api/v1/devices/:id/mapping", get(get_device_mapping))
+
+        // 核心相关
+        .route("/api/v1/cores/:id/devices", get(get_core_devices))
+        .route("/api/v1/cores/:id/stats", get(get_core_stats))
+        .route("/api/v1/cores/:id/config", get(get_core_config).put(update_core_config))
 
         // 矿池管理路由
         .route("/api/v1/pools", get(get_pools))
+        .route("/api/v1/pools/devices", get(get_pool_device_stats))
         .route("/api/v1/pools/:id", get(get_pool))
         .route("/api/v1/pools/:id/config", post(update_pool_config))
 
         // 控制路由
         .route("/api/v1/control", post(control_command))
-        .route("/api/v1/config", post(update_config))
+        .route("/api/v1/config", get(get_config).post(update_config))
+        .route("/api/v1/config/save", post(save_config))
+        .route("/api/v1/settings", get(get_runtime_settings).put(update_runtime_settings))
+        .route("/api/v1/queues", get(get_queue_stats))
+        .route("/api/v1/memory", get(get_memory_status))
+        .route("/api/v1/logging", get(get_log_filter).put(update_log_filter))
 
         // WebSocket 路由
         .route("/api/v1/ws", get(websocket_handler))
@@ -261,11 +399,6 @@ pub fn create_routes(state: AppState) -> Router {
         // 健康检查
         .route("/health", get(health_check))
 
-        .layer(
-            ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
-                .layer(CorsLayer::permissive())
-        )
         .with_state(state)
 }
 
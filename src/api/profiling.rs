@@ -0,0 +1,181 @@
+//! 运行时剖析接口 (`profiling` 特性)，需要 admin 权限
+//!
+//! - `GET /debug/pprof/profile?seconds=N` — 采样 N 秒 CPU 调用栈，返回 pprof protobuf
+//!   (`go tool pprof` / `pprof` 可直接读取)，`format=flamegraph` 时返回 SVG 火焰图；仅 Unix
+//! - `GET /debug/tasks?stuck_after_secs=N` — 被监视的后台任务及卡住检测，见 [`task_watch`]
+//!
+//! 即使没有配置任何认证，`/debug/` 下的接口也要求凭据，见 `api::auth`。
+
+use crate::api::ApiResponse;
+use crate::performance::task_watch::{self, TaskSnapshot};
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 默认采样时长 (秒)
+const DEFAULT_PROFILE_SECS: u64 = 10;
+
+/// 采样时长上限，须小于 API 的 30 秒请求超时
+const MAX_PROFILE_SECS: u64 = 25;
+
+pub fn routes() -> Router {
+    let router = Router::new().route("/debug/tasks", get(list_tasks));
+    #[cfg(unix)]
+    let router = router.route("/debug/pprof/profile", get(cpu::profile));
+    router
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TasksQuery {
+    pub stuck_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TasksResponse {
+    pub stuck_after_secs: u64,
+    pub stuck: usize,
+    pub tasks: Vec<TaskSnapshot>,
+}
+
+/// 列出后台任务，卡住的排在前面
+pub async fn list_tasks(Query(query): Query<TasksQuery>) -> Json<ApiResponse<TasksResponse>> {
+    let stuck_after = query.stuck_after_secs
+        .map(Duration::from_secs)
+        .unwrap_or(task_watch::DEFAULT_STUCK_AFTER);
+    let tasks = task_watch::list(stuck_after);
+    Json(ApiResponse::success(TasksResponse {
+        stuck_after_secs: stuck_after.as_secs(),
+        stuck: tasks.iter().filter(|task| task.stuck).count(),
+        tasks,
+    }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProfileQuery {
+    pub seconds: Option<u64>,
+    /// `pprof` (默认) 或 `flamegraph`
+    pub format: Option<String>,
+}
+
+impl ProfileQuery {
+    fn duration(&self) -> Result<Duration, String> {
+        match self.seconds.unwrap_or(DEFAULT_PROFILE_SECS) {
+            0 => Err("seconds must be greater than 0".to_string()),
+            seconds if seconds > MAX_PROFILE_SECS => Err(format!("seconds must be at most {}", MAX_PROFILE_SECS)),
+            seconds => Ok(Duration::from_secs(seconds)),
+        }
+    }
+
+    fn flamegraph(&self) -> Result<bool, String> {
+        match self.format.as_deref() {
+            None | Some("pprof") => Ok(false),
+            Some("flamegraph") => Ok(true),
+            Some(other) => Err(format!("Unknown profile format '{}' (expected pprof or flamegraph)", other)),
+        }
+    }
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+#[cfg(unix)]
+mod cpu {
+    use super::*;
+    use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+    use pprof::protos::Message;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// 采样频率 (Hz)，与 Go pprof 默认值相近且避开定时任务的整数周期
+    const SAMPLE_FREQUENCY: i32 = 99;
+
+    /// 同一时间只允许一次采样 (信号处理器是进程级的)
+    static PROFILING: AtomicBool = AtomicBool::new(false);
+
+    struct ProfilingSlot;
+
+    impl ProfilingSlot {
+        fn acquire() -> Option<Self> {
+            PROFILING.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).ok().map(|_| ProfilingSlot)
+        }
+    }
+
+    impl Drop for ProfilingSlot {
+        fn drop(&mut self) {
+            PROFILING.store(false, Ordering::Release);
+        }
+    }
+
+    /// 采样期间占用一个阻塞线程，不影响运行时的工作线程
+    fn capture(duration: Duration, flamegraph: bool) -> Result<Vec<u8>, String> {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(SAMPLE_FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .map_err(|e| format!("Failed to start profiler: {}", e))?;
+        std::thread::sleep(duration);
+        let report = guard.report().build().map_err(|e| format!("Failed to build profile: {}", e))?;
+
+        let mut body = Vec::new();
+        if flamegraph {
+            report.flamegraph(&mut body).map_err(|e| format!("Failed to render flamegraph: {}", e))?;
+        } else {
+            let profile = report.pprof().map_err(|e| format!("Failed to encode profile: {}", e))?;
+            profile.encode(&mut body).map_err(|e| format!("Failed to encode profile: {}", e))?;
+        }
+        Ok(body)
+    }
+
+    pub async fn profile(Query(query): Query<ProfileQuery>) -> Response {
+        let (duration, flamegraph) = match query.duration().and_then(|duration| query.flamegraph().map(|flamegraph| (duration, flamegraph))) {
+            Ok(parsed) => parsed,
+            Err(message) => return error_response(StatusCode::BAD_REQUEST, message),
+        };
+        let Some(slot) = ProfilingSlot::acquire() else {
+            return error_response(StatusCode::CONFLICT, "A CPU profile is already being captured".to_string());
+        };
+
+        tracing::info!("Capturing {}s CPU profile", duration.as_secs());
+        let result = tokio::task::spawn_blocking(move || {
+            let _slot = slot;
+            capture(duration, flamegraph)
+        }).await;
+
+        match result {
+            Ok(Ok(body)) if flamegraph => ([(CONTENT_TYPE, "image/svg+xml")], body).into_response(),
+            Ok(Ok(body)) => (
+                [
+                    (CONTENT_TYPE, "application/octet-stream"),
+                    (CONTENT_DISPOSITION, "attachment; filename=\"cpu.pb\""),
+                ],
+                body,
+            ).into_response(),
+            Ok(Err(message)) => error_response(StatusCode::INTERNAL_SERVER_ERROR, message),
+            Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Profiler task failed: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_query() {
+        let query = ProfileQuery::default();
+        assert_eq!(query.duration(), Ok(Duration::from_secs(DEFAULT_PROFILE_SECS)));
+        assert_eq!(query.flamegraph(), Ok(false));
+
+        let query = ProfileQuery { seconds: Some(MAX_PROFILE_SECS + 1), format: Some("flamegraph".to_string()) };
+        assert!(query.duration().is_err());
+        assert_eq!(query.flamegraph(), Ok(true));
+
+        let query = ProfileQuery { seconds: Some(0), format: Some("svg".to_string()) };
+        assert!(query.duration().is_err());
+        assert!(query.flamegraph().is_err());
+    }
+}
@@ -1,7 +1,15 @@
 use crate::api::{AppState, create_routes};
-use crate::config::ApiConfig;
+use crate::api::auth::{auth_middleware, ServerAuth};
+use crate::api::audit::{self, audit_middleware, AuditContext};
+use crate::api::keys;
+use crate::api::tls::load_rustls_config;
+use crate::config::{ApiConfig, Config};
 use crate::error::ApiError;
 use crate::mining::MiningManager;
+use crate::security::{lockout, AuditLog, SecurityConfig};
+use crate::web::{self, FleetMonitor, WebConfig};
+use axum::http::{header::STRICT_TRANSPORT_SECURITY, HeaderValue};
+use axum::middleware;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -9,18 +17,49 @@ use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::{
     cors::{CorsLayer, Any},
+    set_header::SetResponseHeaderLayer,
     trace::TraceLayer,
     timeout::TimeoutLayer,
 };
 use tracing::{info, warn, error};
 use std::time::Duration;
 
+/// API 服务器配置
+///
+/// REST API 和 Web 仪表板由同一个服务器提供：监听地址、TLS 和 token 来自 `[api]`，
+/// 仪表板开关和登录来自 `[web]`，API 密钥存储和审计来自 `[security]`。
+#[derive(Debug, Clone, Default)]
+pub struct ServerConfig {
+    pub api: ApiConfig,
+    pub web: WebConfig,
+    pub security: SecurityConfig,
+}
+
+impl ServerConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            api: config.api.clone(),
+            web: config.web.clone(),
+            security: config.security.clone(),
+        }
+    }
+
+    /// 是否启用 HTTPS
+    pub fn tls_enabled(&self) -> bool {
+        self.api.tls_enabled()
+    }
+}
+
 /// API 服务器
 pub struct ApiServer {
     /// 服务器配置
-    config: ApiConfig,
+    config: ServerConfig,
     /// 挖矿管理器
     mining_manager: Arc<MiningManager>,
+    /// 认证 (token 和仪表板会话)
+    auth: Arc<ServerAuth>,
+    /// 机群轮询器，启用 `[web.fleet]` 时随服务器启动
+    fleet: Arc<RwLock<Option<Arc<FleetMonitor>>>>,
     /// 服务器句柄
     server_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
     /// 运行状态
@@ -29,10 +68,13 @@ pub struct ApiServer {
 
 impl ApiServer {
     /// 创建新的 API 服务器
-    pub fn new(config: ApiConfig, mining_manager: Arc<MiningManager>) -> Self {
+    pub fn new(config: ServerConfig, mining_manager: Arc<MiningManager>) -> Self {
+        let auth = Arc::new(ServerAuth::from_config(&config));
         Self {
             config,
             mining_manager,
+            auth,
+            fleet: Arc::new(RwLock::new(None)),
             server_handle: Arc::new(RwLock::new(None)),
             running: Arc::new(RwLock::new(false)),
         }
@@ -40,12 +82,12 @@ impl ApiServer {
 
     /// 启动 API 服务器
     pub async fn start(&self) -> Result<(), ApiError> {
-        if !self.config.enabled {
+        if !self.config.api.enabled {
             info!("API server is disabled");
             return Ok(());
         }
 
-        info!("Starting API server on {}:{}", self.config.bind_address, self.config.port);
+        info!("Starting API server on {}", self.get_address());
 
         // 检查是否已经在运行
         if *self.running.read().await {
@@ -58,8 +100,65 @@ impl ApiServer {
             mining_manager: self.mining_manager.clone(),
         };
 
-        // 创建路由
-        let app = create_routes(app_state)
+        // 创建路由，启用仪表板时合并 Web 路由
+        let mut app = create_routes(app_state);
+
+        // 读取 API 密钥存储，读取失败时不启动，避免在没有认证的情况下开放 API
+        if let Some(keys) = &self.auth.keys {
+            let count = keys.load().await
+                .map_err(|e| ApiError::ServerStartFailed { error: format!("Failed to load API keys: {}", e) })?;
+            if count > 0 {
+                info!("🔑 Loaded {} API keys", count);
+            }
+            app = app.merge(keys::routes(keys.clone()));
+        }
+
+        // 审计日志同样在读取失败时不启动
+        let audit_log = if self.config.security.audit.enabled {
+            let log = Arc::new(AuditLog::new(self.config.security.audit.clone()));
+            let count = log.load().await
+                .map_err(|e| ApiError::ServerStartFailed { error: format!("Failed to load audit log: {}", e) })?;
+            info!("📝 Audit log {} ({} records)", self.config.security.audit.path, count);
+            app = app.merge(audit::routes(log.clone()));
+            Some(log)
+        } else {
+            None
+        };
+        #[cfg(feature = "profiling")]
+        {
+            warn!("Profiling endpoints enabled under /debug/ (admin credentials required)");
+            app = app.merge(crate::api::profiling::routes());
+        }
+        if self.config.web.enabled {
+            let fleet = if self.config.web.fleet.enabled {
+                let fleet = Arc::new(FleetMonitor::new(self.config.web.fleet.clone())
+                    .map_err(|e| ApiError::ServerStartFailed { error: e.to_string() })?);
+                *self.fleet.write().await = Some(fleet.clone());
+                Some(fleet)
+            } else {
+                None
+            };
+            let monitoring = self.mining_manager.get_monitoring_system();
+            app = app.merge(web::router(&self.config.web, monitoring, self.auth.web.clone(), fleet));
+        }
+        // 审计在认证之后运行，只记录通过认证的请求
+        if let Some(log) = audit_log {
+            let context = AuditContext {
+                log,
+                mining_manager: self.mining_manager.clone(),
+                keys: self.auth.keys.clone(),
+            };
+            app = app.layer(middleware::from_fn_with_state(context, audit_middleware));
+        }
+        let mut app = app.layer(middleware::from_fn_with_state(self.auth.clone(), auth_middleware));
+        lockout::forward_alerts(self.auth.lockout.subscribe(), self.mining_manager.get_monitoring_system());
+        // HTTPS 时告诉浏览器以后只用 HTTPS 访问
+        if let Some(hsts) = self.config.api.tls.hsts_header().filter(|_| self.config.tls_enabled()) {
+            let value = HeaderValue::from_str(&hsts)
+                .map_err(|e| ApiError::ServerStartFailed { error: format!("Invalid HSTS header: {}", e) })?;
+            app = app.layer(SetResponseHeaderLayer::if_not_present(STRICT_TRANSPORT_SECURITY, value));
+        }
+        let app = app
             .layer(
                 ServiceBuilder::new()
                     .layer(TraceLayer::new_for_http())
@@ -68,7 +167,7 @@ impl ApiServer {
             );
 
         // 解析绑定地址
-        let addr = format!("{}:{}", self.config.bind_address, self.config.port)
+        let addr = self.get_address()
             .parse::<SocketAddr>()
             .map_err(|e| ApiError::ServerStartFailed {
                 error: format!("Invalid bind address: {}", e),
@@ -80,23 +179,58 @@ impl ApiServer {
                 error: format!("Failed to bind to address: {}", e),
             })?;
 
+        // 配置了证书或自签名时使用 HTTPS
+        let tls = if self.config.tls_enabled() {
+            Some(load_rustls_config(&self.config.api).await?)
+        } else {
+            None
+        };
+
         let running = self.running.clone();
         let server_handle = self.server_handle.clone();
 
         // 在后台运行服务器
-        let handle = tokio::spawn(async move {
-            *running.write().await = true;
-
-            if let Err(e) = axum::serve(listener, app).await {
-                error!("API server error: {}", e);
+        let handle = match tls {
+            Some(tls) => {
+                let listener = listener.into_std()
+                    .map_err(|e| ApiError::ServerStartFailed {
+                        error: format!("Failed to prepare TLS listener: {}", e),
+                    })?;
+                tokio::spawn(async move {
+                    *running.write().await = true;
+
+                    if let Err(e) = axum_server::from_tcp_rustls(listener, tls).serve(app.into_make_service_with_connect_info::<SocketAddr>()).await {
+                        error!("API server error: {}", e);
+                    }
+
+                    *running.write().await = false;
+                })
             }
+            None => tokio::spawn(async move {
+                *running.write().await = true;
+
+                if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
+                    error!("API server error: {}", e);
+                }
 
-            *running.write().await = false;
-        });
+                *running.write().await = false;
+            }),
+        };
 
         *server_handle.write().await = Some(handle);
+        *self.running.write().await = true;
 
-        info!("API server started successfully on http://{}", addr);
+        info!("API server started successfully on {}", self.get_url());
+        if self.config.web.enabled {
+            info!("📊 Web dashboard available at {}/", self.get_url());
+            if !self.config.web.auth.enabled {
+                warn!("Web dashboard login is disabled; set [web.auth] before exposing it beyond localhost");
+            }
+            if let Some(fleet) = self.fleet.read().await.as_ref() {
+                fleet.start();
+                info!("🛰️ Fleet view available at {}/fleet", self.get_url());
+            }
+        }
         Ok(())
     }
 
@@ -114,6 +248,9 @@ impl ApiServer {
         if let Some(handle) = self.server_handle.write().await.take() {
             handle.abort();
         }
+        if let Some(fleet) = self.fleet.write().await.take() {
+            fleet.stop();
+        }
 
         *self.running.write().await = false;
 
@@ -128,12 +265,13 @@ impl ApiServer {
 
     /// 获取服务器地址
     pub fn get_address(&self) -> String {
-        format!("{}:{}", self.config.bind_address, self.config.port)
+        format!("{}:{}", self.config.api.bind_address, self.config.api.port)
     }
 
     /// 获取服务器URL
     pub fn get_url(&self) -> String {
-        format!("http://{}:{}", self.config.bind_address, self.config.port)
+        let scheme = if self.config.tls_enabled() { "https" } else { "http" };
+        format!("{}://{}", scheme, self.get_address())
     }
 
     /// 创建 CORS 层
@@ -150,17 +288,17 @@ impl ApiServer {
                 axum::http::header::CONTENT_TYPE,
                 axum::http::header::AUTHORIZATION,
                 axum::http::header::ACCEPT,
+                axum::http::HeaderName::from_static(crate::web::auth::CSRF_HEADER),
             ]);
 
         // 配置允许的来源
-        if self.config.allow_origins.contains(&"*".to_string()) {
+        if self.config.api.allow_origins.contains(&"*".to_string()) {
             cors = cors.allow_origin(Any);
         } else {
-            for origin in &self.config.allow_origins {
-                if let Ok(origin_header) = origin.parse::<axum::http::HeaderValue>() {
-                    cors = cors.allow_origin(origin_header);
-                }
-            }
+            let origins: Vec<axum::http::HeaderValue> = self.config.api.allow_origins.iter()
+                .filter_map(|origin| origin.parse().ok())
+                .collect();
+            cors = cors.allow_origin(origins);
         }
 
         cors
@@ -168,7 +306,7 @@ impl ApiServer {
 
     /// 验证认证令牌
     pub fn validate_auth_token(&self, token: Option<&str>) -> bool {
-        match (&self.config.auth_token, token) {
+        match (&self.config.api.auth_token, token) {
             (Some(expected), Some(provided)) => expected == provided,
             (None, _) => true, // 如果没有配置认证令牌，则允许所有请求
             (Some(_), None) => false, // 配置了认证令牌但请求中没有提供
@@ -184,9 +322,9 @@ impl ApiServer {
             running: self.is_running().await,
             address: self.get_address(),
             url: self.get_url(),
-            auth_required: self.config.auth_token.is_some(),
-            cors_enabled: !self.config.allow_origins.is_empty(),
-            allowed_origins: self.config.allow_origins.clone(),
+            auth_required: self.config.api.auth_token.is_some() || self.config.web.auth.enabled,
+            cors_enabled: !self.config.api.allow_origins.is_empty(),
+            allowed_origins: self.config.api.allow_origins.clone(),
         }
     }
 
@@ -206,7 +344,7 @@ impl ApiServer {
     }
 
     /// 重新加载配置
-    pub async fn reload_config(&mut self, new_config: ApiConfig) -> Result<(), ApiError> {
+    pub async fn reload_config(&mut self, new_config: ServerConfig) -> Result<(), ApiError> {
         info!("Reloading API server configuration");
 
         let was_running = self.is_running().await;
@@ -216,11 +354,12 @@ impl ApiServer {
             self.stop().await?;
         }
 
-        // 更新配置
+        // 更新配置，登录会话随之失效
+        self.auth = Arc::new(ServerAuth::from_config(&new_config));
         self.config = new_config;
 
         // 如果之前在运行，重新启动
-        if was_running && self.config.enabled {
+        if was_running && self.config.api.enabled {
             self.start().await?;
         }
 
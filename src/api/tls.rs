@@ -0,0 +1,227 @@
+//! API 和仪表板的 HTTPS
+//!
+//! 证书和私钥来自 `api.tls_cert` / `api.tls_key`，`api.tls.self_signed` 时在文件不存在时
+//! 生成自签名证书。协议版本和加密套件由 `api.tls.policy` 或显式的套件列表决定，
+//! HTTPS 响应带 HSTS 头。
+
+use crate::config::ApiConfig;
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 自签名证书的默认位置
+pub const DEFAULT_SELF_SIGNED_CERT: &str = "./data/tls/cert.pem";
+pub const DEFAULT_SELF_SIGNED_KEY: &str = "./data/tls/key.pem";
+
+/// 协议版本和加密套件策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsPolicy {
+    /// 只允许 TLS 1.3
+    Modern,
+    /// TLS 1.2 和 1.3，只用 ECDHE + AEAD 套件
+    #[default]
+    Intermediate,
+}
+
+/// HTTPS 选项 (`[api.tls]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsOptions {
+    /// 证书文件不存在时生成自签名证书，未设置 tls_cert/tls_key 时保存到 ./data/tls/
+    pub self_signed: bool,
+    /// 自签名证书额外的主机名或 IP (localhost、127.0.0.1 和本机名总是包含)
+    pub hostnames: Vec<String>,
+    /// HSTS max-age (秒)，0 表示不发送
+    pub hsts_max_age_secs: u64,
+    pub policy: TlsPolicy,
+    /// 显式指定的加密套件 (rustls 名称，如 TLS13_AES_256_GCM_SHA384)，为空时按 policy
+    pub cipher_suites: Vec<String>,
+}
+
+impl Default for TlsOptions {
+    fn default() -> Self {
+        Self {
+            self_signed: false,
+            hostnames: Vec::new(),
+            hsts_max_age_secs: 31_536_000,
+            policy: TlsPolicy::Intermediate,
+            cipher_suites: Vec::new(),
+        }
+    }
+}
+
+impl TlsOptions {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if let Err(e) = cipher_suites(self) {
+            errors.push(("api.tls.cipher_suites", e));
+        }
+        errors
+    }
+
+    /// HSTS 头的值，未启用时为 None
+    pub fn hsts_header(&self) -> Option<String> {
+        (self.hsts_max_age_secs > 0).then(|| format!("max-age={}", self.hsts_max_age_secs))
+    }
+}
+
+/// 按策略或显式列表选出的加密套件
+fn cipher_suites(options: &TlsOptions) -> Result<Vec<rustls::SupportedCipherSuite>, String> {
+    let allowed = |suite: &rustls::SupportedCipherSuite| {
+        options.policy != TlsPolicy::Modern || suite.version().version == rustls::ProtocolVersion::TLSv1_3
+    };
+    if options.cipher_suites.is_empty() {
+        return Ok(rustls::DEFAULT_CIPHER_SUITES.iter().copied().filter(allowed).collect());
+    }
+
+    let mut suites = Vec::new();
+    for name in &options.cipher_suites {
+        let suite = rustls::ALL_CIPHER_SUITES
+            .iter()
+            .find(|suite| format!("{:?}", suite.suite()).eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Unknown TLS cipher suite '{}'", name))?;
+        if !allowed(suite) {
+            return Err(format!("Cipher suite '{}' is not TLS 1.3 but api.tls.policy is \"modern\"", name));
+        }
+        suites.push(*suite);
+    }
+    Ok(suites)
+}
+
+/// 读取 (必要时生成) 证书并按策略构建 rustls 配置
+pub async fn load_rustls_config(api: &ApiConfig) -> Result<axum_server::tls_rustls::RustlsConfig, ApiError> {
+    let failed = |error: String| ApiError::ServerStartFailed { error };
+    let (cert_path, key_path) = api.tls_paths().ok_or_else(|| failed("TLS is not configured".to_string()))?;
+
+    if api.tls.self_signed && !(Path::new(&cert_path).exists() && Path::new(&key_path).exists()) {
+        let hostnames = self_signed_hostnames(api);
+        generate_self_signed(&cert_path, &key_path, &hostnames).map_err(failed)?;
+        info!("🔐 Generated self-signed certificate {} for {}", cert_path, hostnames.join(", "));
+        warn!("Browsers will warn about the self-signed certificate until it is trusted on each client");
+    }
+
+    let certs = read_pem(&cert_path).map_err(failed)?
+        .into_iter()
+        .filter_map(|item| match item {
+            rustls_pemfile::Item::X509Certificate(der) => Some(rustls::Certificate(der)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if certs.is_empty() {
+        return Err(failed(format!("No certificate found in {}", cert_path)));
+    }
+    let key = read_pem(&key_path).map_err(failed)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(der) | rustls_pemfile::Item::RSAKey(der) | rustls_pemfile::Item::ECKey(der) => {
+                Some(rustls::PrivateKey(der))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| failed(format!("No private key found in {}", key_path)))?;
+
+    let suites = cipher_suites(&api.tls).map_err(failed)?;
+    let versions: &[&rustls::SupportedProtocolVersion] = match api.tls.policy {
+        TlsPolicy::Modern => &[&rustls::version::TLS13],
+        TlsPolicy::Intermediate => &[&rustls::version::TLS13, &rustls::version::TLS12],
+    };
+    let mut config = rustls::ServerConfig::builder()
+        .with_cipher_suites(&suites)
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(versions)
+        .map_err(|e| failed(format!("Invalid TLS cipher policy: {}", e)))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| failed(format!("Invalid TLS certificate/key: {}", e)))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(axum_server::tls_rustls::RustlsConfig::from_config(Arc::new(config)))
+}
+
+fn read_pem(path: &str) -> Result<Vec<rustls_pemfile::Item>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    rustls_pemfile::read_all(&mut std::io::BufReader::new(file)).map_err(|e| format!("Failed to parse {}: {}", path, e))
+}
+
+/// 自签名证书包含的主机名：本机回环地址、本机名、具体的绑定地址和配置的额外名称
+fn self_signed_hostnames(api: &ApiConfig) -> Vec<String> {
+    let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()];
+    names.extend(sysinfo::System::host_name());
+    if !matches!(api.bind_address.as_str(), "0.0.0.0" | "::" | "") {
+        names.push(api.bind_address.clone());
+    }
+    names.extend(api.tls.hostnames.iter().cloned());
+    let mut unique = Vec::new();
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+    unique
+}
+
+fn generate_self_signed(cert_path: &str, key_path: &str, hostnames: &[String]) -> Result<(), String> {
+    let mut params = rcgen::CertificateParams::new(hostnames.to_vec());
+    params.distinguished_name.push(rcgen::DnType::CommonName, rcgen::DnValue::Utf8String("cgminer-rs".to_string()));
+    let cert = rcgen::Certificate::from_params(params)
+        .map_err(|e| format!("Failed to generate self-signed certificate: {}", e))?;
+    let cert_pem = cert.serialize_pem().map_err(|e| format!("Failed to encode certificate: {}", e))?;
+
+    for path in [cert_path, key_path] {
+        if let Some(dir) = Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+    }
+    std::fs::write(cert_path, cert_pem).map_err(|e| format!("Failed to write {}: {}", cert_path, e))?;
+    crate::security::write_private(Path::new(key_path), cert.serialize_private_key_pem().as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cipher_policy() {
+        let modern = TlsOptions { policy: TlsPolicy::Modern, ..Default::default() };
+        let suites = cipher_suites(&modern).unwrap();
+        assert!(!suites.is_empty());
+        assert!(suites.iter().all(|suite| suite.version().version == rustls::ProtocolVersion::TLSv1_3));
+
+        let explicit = TlsOptions { cipher_suites: vec!["tls13_aes_256_gcm_sha384".to_string()], ..Default::default() };
+        assert_eq!(cipher_suites(&explicit).unwrap().len(), 1);
+
+        let unknown = TlsOptions { cipher_suites: vec!["RC4_MD5".to_string()], ..Default::default() };
+        assert_eq!(unknown.validate().len(), 1);
+
+        let legacy_in_modern = TlsOptions {
+            policy: TlsPolicy::Modern,
+            cipher_suites: vec!["TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256".to_string()],
+            ..Default::default()
+        };
+        assert!(cipher_suites(&legacy_in_modern).is_err());
+        assert_eq!(TlsOptions { hsts_max_age_secs: 0, ..Default::default() }.hsts_header(), None);
+    }
+
+    #[tokio::test]
+    async fn test_self_signed_generation() {
+        let dir = std::env::temp_dir().join(format!("cgminer-tls-{}", std::process::id()));
+        let api = ApiConfig {
+            tls_cert: Some(dir.join("cert.pem").display().to_string()),
+            tls_key: Some(dir.join("key.pem").display().to_string()),
+            tls: TlsOptions { self_signed: true, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(load_rustls_config(&api).await.is_ok());
+        let first = std::fs::read(dir.join("cert.pem")).unwrap();
+
+        // 已有证书时直接复用
+        assert!(load_rustls_config(&api).await.is_ok());
+        assert_eq!(std::fs::read(dir.join("cert.pem")).unwrap(), first);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
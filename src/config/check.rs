@@ -0,0 +1,482 @@
+//! 配置检查 (`--check-config`)
+//!
+//! 解析并验证配置文件，输出带行号的诊断信息（未知键、矿池URL、端口冲突、
+//! 告警阈值等），让配置错误在启动前暴露，而不是在运行时以模糊的错误失败。
+
+use super::format::ConfigFormat;
+use super::wallet::AddressCheck;
+use super::{apply_env_overrides, include, profiles, Config, PoolProtocol};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// 通过 serde alias 接受的旧键名，不报告为未知键
+const KEY_ALIASES: &[&str] = &["user", "static_files_dir"];
+
+/// 已迁移到其他位置的键，报告新位置而不是未知键
+const MOVED_KEYS: &[(&str, &str)] = &[
+    ("web.bind_address", "api.bind_address"),
+    ("web.port", "api.port"),
+    ("web.tls_cert", "api.tls_cert"),
+    ("web.tls_key", "api.tls_key"),
+];
+
+/// 支持的矿池URL协议
+const POOL_SCHEMES: &[&str] = &["stratum+tcp", "stratum+ssl", "stratum+tls", "stratum", "tcp"];
+
+/// getwork 矿池支持的URL协议
+const GETWORK_SCHEMES: &[&str] = &["http", "https"];
+
+/// 诊断级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 单条诊断信息
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 配置文件中的行号（从1开始）
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// 配置检查报告
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    pub path: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CheckReport {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, line: Option<usize>, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic { severity: Severity::Error, line, message: message.into() });
+    }
+
+    fn warning(&mut self, line: Option<usize>, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic { severity: Severity::Warning, line, message: message.into() });
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning).count()
+    }
+
+    /// 是否存在错误（存在时 `--check-config` 以非零状态退出）
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            let level = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            match diagnostic.line {
+                Some(line) => writeln!(f, "{}: {}:{}: {}", level, self.path, line, diagnostic.message)?,
+                None => writeln!(f, "{}: {}: {}", level, self.path, diagnostic.message)?,
+            }
+        }
+        write!(f, "{}: {} error(s), {} warning(s)", self.path, self.error_count(), self.warning_count())
+    }
+}
+
+/// 检查配置文件
+pub fn check_config_file(path: &str, profile: Option<&str>) -> CheckReport {
+    let mut report = CheckReport::new(path);
+
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            report.error(None, format!("Failed to read config file: {}", e));
+            return report;
+        }
+    };
+
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new("."));
+    match ConfigFormat::from_path(Path::new(path)) {
+        ConfigFormat::Toml => check_config_str(&text, base_dir, profile, &mut report),
+        format => match format.parse(&text) {
+            // 行号定位只支持 TOML
+            Ok(value) => check_value(value, "", base_dir, profile, &mut report),
+            Err(e) => report.error(None, format!("{:#}", e)),
+        },
+    }
+    report
+}
+
+/// 检查配置内容，`base_dir` 为 include 相对路径的基准目录，`profile` 为选用的档案
+pub fn check_config_str(text: &str, base_dir: &Path, profile: Option<&str>, report: &mut CheckReport) {
+    let value: toml::Value = match toml::from_str(text) {
+        Ok(value) => value,
+        Err(e) => {
+            let line = e.span().map(|span| line_of_offset(text, span.start));
+            report.error(line, e.message().trim().to_string());
+            return;
+        }
+    };
+
+    check_value(value, text, base_dir, profile, report);
+}
+
+/// 检查已解析的配置，`text` 为用于定位行号的 TOML 原文
+fn check_value(mut value: toml::Value, text: &str, base_dir: &Path, profile: Option<&str>, report: &mut CheckReport) {
+    // 被包含文件中的键没有行号，只在主文件中定位
+    let included = value.get(include::INCLUDE_KEY).is_some();
+    value = match include::resolve_includes(value, base_dir) {
+        Ok(value) => value,
+        Err(e) => {
+            report.error(None, format!("{:#}", e));
+            return;
+        }
+    };
+    if included {
+        report.warning(None, "Includes are merged; line numbers refer to the main file only");
+    }
+
+    match profiles::apply_profile(&mut value, profile, profiles::local_hostname().as_deref()) {
+        Ok(Some(name)) => report.warning(None, format!("Profile '{}' is applied; line numbers refer to the base sections", name)),
+        Ok(None) => {}
+        Err(e) => {
+            report.error(None, format!("{:#}", e));
+            return;
+        }
+    }
+
+    if let Err(e) = apply_env_overrides(&mut value, std::env::vars()) {
+        report.error(None, format!("{:#}", e));
+        return;
+    }
+
+    let config: Config = match value.clone().try_into() {
+        Ok(config) => config,
+        Err(e) => {
+            let e: toml::de::Error = e;
+            report.error(None, e.message().trim().to_string());
+            return;
+        }
+    };
+
+    // 将解析结果重新序列化，文件中存在但被忽略的键即为未知键
+    if let Ok(known) = toml::Value::try_from(&config) {
+        check_unknown_keys(text, &value, &known, &mut Vec::new(), report);
+    }
+
+    for error in config.validation_errors() {
+        let path: Vec<PathSegment> = error.key.split('.').map(|k| PathSegment::Key(k.to_string())).collect();
+        report.error(find_key_line(text, &path), error.message);
+    }
+
+    check_pools(text, &config, report);
+    check_thresholds(text, &config, report);
+}
+
+/// 配置路径段
+#[derive(Debug, Clone)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn format_path(path: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            PathSegment::Index(index) => out.push_str(&format!("[{}]", index)),
+        }
+    }
+    out
+}
+
+fn check_unknown_keys(
+    text: &str,
+    file: &toml::Value,
+    known: &toml::Value,
+    path: &mut Vec<PathSegment>,
+    report: &mut CheckReport,
+) {
+    match (file, known) {
+        (toml::Value::Table(file_table), toml::Value::Table(known_table)) => {
+            for (key, child) in file_table {
+                if KEY_ALIASES.contains(&key.as_str()) {
+                    continue;
+                }
+                path.push(PathSegment::Key(key.clone()));
+                match known_table.get(key) {
+                    Some(known_child) => check_unknown_keys(text, child, known_child, path, report),
+                    None => {
+                        let line = find_key_line(text, path);
+                        let key = format_path(path);
+                        match MOVED_KEYS.iter().find(|(old, _)| *old == key) {
+                            Some((_, new)) => report.warning(line, format!(
+                                "Key '{}' is ignored; the dashboard is served by the API server, use '{}' instead",
+                                key, new
+                            )),
+                            None => report.warning(line, format!("Unknown key '{}' is ignored", key)),
+                        }
+                    }
+                }
+                path.pop();
+            }
+        }
+        (toml::Value::Array(file_items), toml::Value::Array(known_items)) => {
+            for (index, (child, known_child)) in file_items.iter().zip(known_items).enumerate() {
+                path.push(PathSegment::Index(index));
+                check_unknown_keys(text, child, known_child, path, report);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_pools(text: &str, config: &Config, report: &mut CheckReport) {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    for (index, pool) in config.pools.pools.iter().enumerate() {
+        let path = [PathSegment::Key("pools".into()), PathSegment::Key("pools".into()), PathSegment::Index(index), PathSegment::Key("url".into())];
+        let line = find_key_line(text, &path);
+
+        match url::Url::parse(&pool.url) {
+            Ok(url) => {
+                let schemes = match pool.protocol {
+                    PoolProtocol::Stratum => POOL_SCHEMES,
+                    PoolProtocol::Getwork => GETWORK_SCHEMES,
+                };
+                if !schemes.contains(&url.scheme()) {
+                    report.error(line, format!(
+                        "Pool {} URL '{}' has unsupported scheme '{}' (expected one of: {})",
+                        index, pool.url, url.scheme(), schemes.join(", ")
+                    ));
+                }
+                if url.host_str().map_or(true, str::is_empty) {
+                    report.error(line, format!("Pool {} URL '{}' has no host", index, pool.url));
+                }
+                // http(s) 有默认端口
+                if url.port_or_known_default().is_none() {
+                    report.error(line, format!("Pool {} URL '{}' has no port", index, pool.url));
+                }
+            }
+            Err(e) => {
+                report.error(line, format!("Pool {} URL '{}' is invalid: {}", index, pool.url, e));
+            }
+        }
+
+        if pool.username.trim().is_empty() {
+            report.error(line, format!("Pool {} has an empty username", index));
+        }
+
+        if let Some(first) = seen.insert(pool.url.as_str(), index) {
+            report.warning(line, format!("Pool {} has the same URL as pool {}", index, first));
+        }
+    }
+
+    // fail 模式下已作为验证错误报告
+    if config.pools.address_check == AddressCheck::Warn {
+        for (index, message) in config.wallet_address_issues() {
+            let path = [
+                PathSegment::Key("pools".into()),
+                PathSegment::Key("pools".into()),
+                PathSegment::Index(index),
+                PathSegment::Key("username".into()),
+            ];
+            report.warning(find_key_line(text, &path), message);
+        }
+    }
+}
+
+fn check_thresholds(text: &str, config: &Config, report: &mut CheckReport) {
+    let key_line = |section: &str, key: &str| {
+        find_key_line(text, &[
+            PathSegment::Key("monitoring".into()),
+            PathSegment::Key(section.into()),
+            PathSegment::Key(key.into()),
+        ])
+    };
+
+    if config.monitoring.enabled {
+        let thresholds = &config.monitoring.alert_thresholds;
+        let percents = [
+            ("hashrate_drop_percent", thresholds.hashrate_drop_percent),
+            ("error_rate_percent", thresholds.error_rate_percent),
+            ("max_cpu_usage", thresholds.max_cpu_usage),
+            ("max_memory_usage", thresholds.max_memory_usage),
+        ];
+        for (key, value) in percents {
+            if !(0.0..=100.0).contains(&value) {
+                report.error(key_line("alert_thresholds", key), format!("Alert {} ({}) must be between 0 and 100", key, value));
+            }
+        }
+
+        let thermal = &config.devices.thermal;
+        let lowest_limit = thermal.device_limits.iter()
+            .map(|limit| limit.temperature_limit)
+            .fold(crate::device::DeviceConfig::default().temperature_limit, f32::min);
+        if thermal.enabled && thresholds.temperature_critical > lowest_limit {
+            report.warning(key_line("alert_thresholds", "temperature_critical"), format!(
+                "Alert temperature_critical ({}) is above the thermal limit ({}); devices are throttled before the alert fires",
+                thresholds.temperature_critical, lowest_limit
+            ));
+        }
+    }
+
+    let mut last_band = f32::MIN;
+    for band in &config.devices.fan_control.bands {
+        if band.max_temperature < last_band {
+            report.warning(None, "Fan control bands are not in ascending temperature order".to_string());
+            break;
+        }
+        last_band = band.max_temperature;
+    }
+}
+
+fn line_of_offset(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// 在配置文本中查找键所在行：定位所属表头（`[a.b]` / 第n个 `[[a.b]]`）后的 `key =` 行，
+/// 键本身是表时返回其表头行
+fn find_key_line(text: &str, path: &[PathSegment]) -> Option<usize> {
+    let (key, parents) = match path.split_last() {
+        Some((PathSegment::Key(key), parents)) => (key.as_str(), parents),
+        _ => return None,
+    };
+
+    let mut header = String::new();
+    let mut occurrence = None;
+    for segment in parents {
+        match segment {
+            PathSegment::Key(k) => {
+                if !header.is_empty() {
+                    header.push('.');
+                }
+                header.push_str(k);
+                occurrence = None;
+            }
+            PathSegment::Index(i) => occurrence = Some(*i),
+        }
+    }
+    let full = if header.is_empty() { key.to_string() } else { format!("{}.{}", header, key) };
+
+    let mut current_header = String::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for (number, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.starts_with('[') {
+            let name = line.trim_start_matches('[').split(']').next().unwrap_or("").trim().to_string();
+            if name == full || name.starts_with(&format!("{}.", full)) {
+                return Some(number + 1);
+            }
+            if line.starts_with("[[") {
+                *counts.entry(name.clone()).or_insert(0) += 1;
+            }
+            current_header = name;
+            continue;
+        }
+
+        let in_parent = current_header == header
+            && occurrence.map_or(true, |i| counts.get(&header).copied() == Some(i + 1));
+        if in_parent {
+            if let Some(rest) = line.strip_prefix(key) {
+                if rest.trim_start().starts_with('=') {
+                    return Some(number + 1);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(text: &str) -> CheckReport {
+        let mut report = CheckReport::new("test.toml");
+        check_config_str(text, Path::new("."), None, &mut report);
+        report
+    }
+
+    fn default_toml() -> String {
+        toml::to_string(&Config::default()).unwrap()
+    }
+
+    #[test]
+    fn test_default_config_is_clean() {
+        let report = check(&default_toml());
+        assert!(!report.has_errors(), "{}", report);
+    }
+
+    #[test]
+    fn test_syntax_error_line() {
+        let report = check("[general]\nlog_level = \n");
+        assert!(report.has_errors());
+        assert_eq!(report.diagnostics[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_unknown_key_warning() {
+        let text = default_toml().replacen("[api]\n", "[api]\nprot = 4028\n", 1);
+        let report = check(&text);
+        let unknown = report.diagnostics.iter()
+            .find(|d| d.message.contains("api.prot"))
+            .expect("unknown key reported");
+        assert_eq!(unknown.severity, Severity::Warning);
+        let expected = text.lines().position(|l| l.starts_with("prot")).map(|i| i + 1);
+        assert_eq!(unknown.line, expected);
+    }
+
+    #[test]
+    fn test_port_conflict() {
+        let mut config = Config::default();
+        config.api.enabled = true;
+        config.monitoring.enabled = true;
+        config.monitoring.web_port = Some(config.api.port);
+        let report = check(&toml::to_string(&config).unwrap());
+        assert!(report.diagnostics.iter().any(|d| d.message.contains("Port conflict")));
+    }
+
+    #[test]
+    fn test_getwork_pool_url() {
+        let mut config = Config::default();
+        config.pools.pools[0].url = "http://127.0.0.1:18443".to_string();
+        assert!(check(&toml::to_string(&config).unwrap()).diagnostics.iter().any(|d| d.message.contains("unsupported scheme")));
+
+        config.pools.pools[0].protocol = PoolProtocol::Getwork;
+        let report = check(&toml::to_string(&config).unwrap());
+        assert!(!report.has_errors(), "{}", report);
+    }
+
+    #[test]
+    fn test_moved_web_key() {
+        let text = default_toml().replacen("[web]\n", "[web]\nport = 8080\n", 1);
+        let report = check(&text);
+        let moved = report.diagnostics.iter()
+            .find(|d| d.message.contains("web.port"))
+            .expect("moved key reported");
+        assert_eq!(moved.severity, Severity::Warning);
+        assert!(moved.message.contains("api.port"));
+    }
+}
@@ -0,0 +1,93 @@
+//! 配置文件格式
+//!
+//! 按扩展名识别 TOML / JSON / YAML，三种格式使用同一套 serde 结构。
+//! 非 TOML 格式先转换为 `toml::Value`，再走与 TOML 相同的 include、环境变量覆盖和验证流程。
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// 根据扩展名识别格式，未知扩展名按 TOML 处理
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("json") => ConfigFormat::Json,
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Toml,
+        }
+    }
+
+    /// 解析配置内容
+    pub fn parse(&self, content: &str) -> Result<toml::Value> {
+        let json = match self {
+            ConfigFormat::Toml => return toml::from_str(content).map_err(Into::into),
+            ConfigFormat::Json => serde_json::from_str::<serde_json::Value>(content)?,
+            ConfigFormat::Yaml => serde_yaml::from_str::<serde_json::Value>(content)?,
+        };
+
+        // TOML 没有 null，省略的可选字段与 null 等价
+        let json = strip_nulls(json);
+        if !json.is_object() {
+            anyhow::bail!("Config root must be an object");
+        }
+        toml::Value::try_from(json).context("Config contains values that cannot be represented")
+    }
+
+    /// 序列化配置
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(match self {
+            ConfigFormat::Toml => toml::to_string_pretty(value)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(value)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(value)?,
+        })
+    }
+}
+
+fn strip_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().filter(|v| !v.is_null()).map(strip_nulls).collect())
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(ConfigFormat::from_path(Path::new("rig.json")), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path(Path::new("rig.YML")), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path(Path::new("cgminer.toml")), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_path(Path::new("cgminer")), ConfigFormat::Toml);
+    }
+
+    #[test]
+    fn test_round_trip_all_formats() {
+        let defaults = Config::default();
+        for format in [ConfigFormat::Toml, ConfigFormat::Json, ConfigFormat::Yaml] {
+            let content = format.serialize(&defaults).unwrap();
+            let config: Config = format.parse(&content).unwrap().try_into().unwrap();
+            assert_eq!(config.api.port, defaults.api.port, "{:?}", format);
+            assert_eq!(config.pools.pools.len(), defaults.pools.pools.len(), "{:?}", format);
+            assert_eq!(config.devices.fan_control.bands[0].speed, defaults.devices.fan_control.bands[0].speed);
+        }
+    }
+}
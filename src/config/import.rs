@@ -0,0 +1,287 @@
+//! 导入原版 cgminer / bmminer 配置 (`--import-cgminer-conf`)
+//!
+//! 读取 C 版 cgminer 的 JSON 格式 `.conf`（pools 数组、api-listen、failover-only 等），
+//! 映射到 cgminer-rs 的 `Config` 结构并输出等价的 TOML。无法映射的键会列在输出文件头部。
+
+use super::{Config, PoolInfo, PoolProtocol, PoolStrategy, ProxyConfig};
+use crate::mining::Algorithm;
+use crate::device::fan_control::{FanBandConfig, FanSpeedTarget};
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// 导入结果
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    pub config: Config,
+    /// 无法映射的原版配置键
+    pub unmapped: Vec<String>,
+}
+
+impl ImportResult {
+    /// 生成 TOML，无法映射的键以注释形式写在文件头
+    pub fn to_toml(&self, source: &str) -> Result<String> {
+        let body = toml::to_string_pretty(&self.config).context("Failed to serialize imported config")?;
+
+        let mut out = format!("# 由 cgminer-rs --import-cgminer-conf 从 {} 转换\n", source);
+        if !self.unmapped.is_empty() {
+            out.push_str("# 以下原版配置项没有对应的设置，未导入:\n");
+            for key in &self.unmapped {
+                out.push_str(&format!("#   {}\n", key));
+            }
+        }
+        out.push('\n');
+        out.push_str(&body);
+        Ok(out)
+    }
+}
+
+/// 读取并转换 cgminer 配置文件
+pub fn import_cgminer_conf(path: &str) -> Result<ImportResult> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cgminer config: {}", path))?;
+    let json: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse cgminer config as JSON: {}", path))?;
+    convert(&json)
+}
+
+/// 将 cgminer JSON 配置映射到 `Config`
+pub fn convert(json: &Value) -> Result<ImportResult> {
+    let object = json.as_object()
+        .ok_or_else(|| anyhow::anyhow!("cgminer config must be a JSON object"))?;
+
+    let mut config = Config::default();
+    config.pools.pools.clear();
+    let mut unmapped = Vec::new();
+    let mut proxy = None;
+    let mut algorithm = Algorithm::default();
+
+    for (key, value) in object {
+        match key.as_str() {
+            "pools" => {
+                let pools = value.as_array()
+                    .ok_or_else(|| anyhow::anyhow!("'pools' must be an array"))?;
+                for (index, pool) in pools.iter().enumerate() {
+                    config.pools.pools.push(convert_pool(index, pool)?);
+                }
+            }
+            "api-listen" => config.api.enabled = as_bool(value),
+            "api-port" => config.api.port = as_number(key, value)?,
+            "api-network" => {
+                if as_bool(value) {
+                    config.api.bind_address = "0.0.0.0".to_string();
+                }
+            }
+            "failover-only" => {
+                if as_bool(value) {
+                    config.pools.strategy = PoolStrategy::Failover;
+                }
+            }
+            "load-balance" | "balance" => {
+                if as_bool(value) {
+                    config.pools.strategy = PoolStrategy::LoadBalance;
+                }
+            }
+            "round-robin" => {
+                if as_bool(value) {
+                    config.pools.strategy = PoolStrategy::RoundRobin;
+                }
+            }
+            "failover-switch-delay" => config.pools.failover_timeout = as_number(key, value)?,
+            "scan-time" => config.general.scan_time = as_number(key, value)?,
+            "expiry" => config.general.work_restart_timeout = as_number(key, value)?,
+            "log" => config.hashmeter.log_interval = as_number(key, value)?,
+            "debug" | "verbose" => {
+                if as_bool(value) {
+                    config.general.log_level = "debug".to_string();
+                }
+            }
+            "quiet" => {
+                if as_bool(value) {
+                    config.general.log_level = "warn".to_string();
+                }
+            }
+            "socks-proxy" => proxy = Some(convert_proxy(value)?),
+            "scrypt" => {
+                if as_bool(value) {
+                    algorithm = Algorithm::Scrypt;
+                }
+            }
+            "bitmain-freq" | "anu-freq" => {
+                let frequency: u32 = as_number(key, value)?;
+                for chain in &mut config.devices.chains {
+                    chain.frequency = frequency;
+                }
+            }
+            "bitmain-fan-pwm" => {
+                let percent: u32 = as_number(key, value)?;
+                config.devices.fan_control.enabled = true;
+                config.devices.fan_control.bands = vec![FanBandConfig {
+                    max_temperature: 200.0,
+                    speed: FanSpeedTarget::Percent(percent.min(100)),
+                }];
+            }
+            "bitmain-fan-ctrl" => {
+                if !as_bool(value) {
+                    config.devices.fan_control.enabled = false;
+                }
+            }
+            _ => unmapped.push(key.clone()),
+        }
+    }
+
+    if config.pools.pools.is_empty() {
+        anyhow::bail!("cgminer config contains no pools");
+    }
+
+    for pool in &mut config.pools.pools {
+        pool.algorithm = algorithm;
+        if let Some(proxy) = &proxy {
+            pool.proxy = Some(proxy.clone());
+        }
+    }
+
+    unmapped.sort();
+    Ok(ImportResult { config, unmapped })
+}
+
+fn convert_pool(index: usize, pool: &Value) -> Result<PoolInfo> {
+    let field = |name: &str| pool.get(name).and_then(Value::as_str).map(str::to_string);
+
+    // 原版支持 "quota": "权重;URL" 形式
+    let (url, quota) = match (field("url"), field("quota")) {
+        (Some(url), _) => (url, None),
+        (None, Some(quota)) => {
+            let (weight, url) = quota.split_once(';')
+                .ok_or_else(|| anyhow::anyhow!("Pool {} quota '{}' is not in 'weight;url' form", index, quota))?;
+            let weight = weight.trim().parse::<u32>()
+                .with_context(|| format!("Pool {} quota weight '{}' is not a number", index, weight))?;
+            (url.trim().to_string(), Some(weight))
+        }
+        (None, None) => anyhow::bail!("Pool {} has no url", index),
+    };
+
+    let url = normalize_pool_url(&url);
+    // 原版对 http:// 地址使用 getwork
+    let protocol = PoolProtocol::from_url(&url);
+
+    Ok(PoolInfo {
+        name: Some(format!("pool-{}", index)),
+        url,
+        username: field("user").unwrap_or_default(),
+        password: field("pass").unwrap_or_else(|| "x".to_string()),
+        priority: index.min(u8::MAX as usize) as u8,
+        quota,
+        enabled: true,
+        proxy: None,
+        algorithm: Algorithm::default(),
+        protocol,
+        quirks: None,
+        fee: None,
+        profit_key: None,
+    })
+}
+
+/// 原版允许省略协议，默认为 stratum+tcp
+fn normalize_pool_url(url: &str) -> String {
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("stratum+tcp://{}", url)
+    }
+}
+
+fn convert_proxy(value: &Value) -> Result<ProxyConfig> {
+    let address = value.as_str()
+        .ok_or_else(|| anyhow::anyhow!("'socks-proxy' must be a host:port string"))?;
+    let (host, port) = address.rsplit_once(':')
+        .ok_or_else(|| anyhow::anyhow!("'socks-proxy' must be a host:port string"))?;
+    let port = port.parse()
+        .with_context(|| format!("Invalid socks-proxy port: {}", port))?;
+
+    Ok(ProxyConfig {
+        proxy_type: "socks5".to_string(),
+        host: host.to_string(),
+        port,
+        username: None,
+        password: None,
+        skip_verify: None,
+        server_name: None,
+        ca_cert: None,
+        client_cert: None,
+        client_key: None,
+    })
+}
+
+/// 原版配置中布尔值可能是 true/false 或字符串
+fn as_bool(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::String(s) => !matches!(s.to_ascii_lowercase().as_str(), "" | "false" | "0" | "no"),
+        Value::Number(n) => n.as_f64().map_or(false, |n| n != 0.0),
+        _ => false,
+    }
+}
+
+/// 原版配置中数字通常以字符串保存（如 "api-port": "4028"）
+fn as_number<T: std::str::FromStr>(key: &str, value: &Value) -> Result<T> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => anyhow::bail!("'{}' must be a number", key),
+    };
+    text.parse::<T>().map_err(|_| anyhow::anyhow!("'{}' value '{}' is not a valid number", key, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_cgminer_conf() {
+        let json: Value = serde_json::from_str(r#"{
+            "pools": [
+                {"url": "stratum+tcp://a.pool:3333", "user": "wallet.1", "pass": "x"},
+                {"url": "b.pool:3333", "user": "wallet.2", "pass": "123"}
+            ],
+            "api-listen": true,
+            "api-port": "4029",
+            "failover-only": true,
+            "scan-time": "15",
+            "bitmain-fan-pwm": "80",
+            "hotplug": "5"
+        }"#).unwrap();
+
+        let result = convert(&json).unwrap();
+        let config = &result.config;
+        assert_eq!(config.pools.pools.len(), 2);
+        assert_eq!(config.pools.pools[1].url, "stratum+tcp://b.pool:3333");
+        assert_eq!(config.pools.pools[1].priority, 1);
+        assert_eq!(config.api.port, 4029);
+        assert!(matches!(config.pools.strategy, PoolStrategy::Failover));
+        assert_eq!(config.general.scan_time, 15);
+        assert_eq!(config.devices.fan_control.bands[0].speed, FanSpeedTarget::Percent(80));
+        assert_eq!(result.unmapped, vec!["hotplug".to_string()]);
+
+        let toml_text = result.to_toml("cgminer.conf").unwrap();
+        let parsed: Config = toml::from_str(&toml_text).unwrap();
+        assert!(parsed.validate().is_ok());
+    }
+
+    #[test]
+    fn test_quota_pool_and_missing_pools() {
+        let json: Value = serde_json::from_str(r#"{"pools": [{"quota": "2;stratum+tcp://q.pool:3333", "user": "u"}]}"#).unwrap();
+        let pool = &convert(&json).unwrap().config.pools.pools[0];
+        assert_eq!(pool.quota, Some(2));
+        assert_eq!(pool.url, "stratum+tcp://q.pool:3333");
+        assert_eq!(pool.algorithm, Algorithm::Sha256d);
+        assert_eq!(pool.protocol, PoolProtocol::Stratum);
+
+        let json = serde_json::json!({"pools": [{"url": "http://127.0.0.1:8332", "user": "u"}]});
+        assert_eq!(convert(&json).unwrap().config.pools.pools[0].protocol, PoolProtocol::Getwork);
+
+        let json = serde_json::json!({"pools": [{"url": "ltc.pool:3333", "user": "u"}], "scrypt": true});
+        assert_eq!(convert(&json).unwrap().config.pools.pools[0].algorithm, Algorithm::Scrypt);
+
+        assert!(convert(&serde_json::json!({"api-listen": true})).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! 配置文件分层 (`include = [...]`)
+//!
+//! 主配置中的 `include` 列出额外的配置文件，按顺序合并到主配置之上，后面的文件覆盖前面的。
+//! 表按键递归合并，其他值（包括数组）整体替换。被包含的文件也可以继续 `include`，
+//! 相对路径以所在文件的目录为基准。被包含的文件可以是任意支持的格式。
+
+use super::format::ConfigFormat;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// 包含文件列表的键名
+pub const INCLUDE_KEY: &str = "include";
+
+/// 最大嵌套深度
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// 读取配置文件并展开其中的 `include`
+pub fn load_layered(path: &Path) -> Result<toml::Value> {
+    let mut stack = Vec::new();
+    load_file(path, &mut stack)
+}
+
+fn load_file(path: &Path, stack: &mut Vec<PathBuf>) -> Result<toml::Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let value = ConfigFormat::from_path(path).parse(&content)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical) {
+        anyhow::bail!("Config include cycle detected at {}", path.display());
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        anyhow::bail!("Config includes are nested deeper than {} levels at {}", MAX_INCLUDE_DEPTH, path.display());
+    }
+
+    stack.push(canonical);
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let result = expand_includes(value, base_dir, stack);
+    stack.pop();
+    result
+}
+
+/// 配置文件及其直接或间接包含的所有文件，主配置在最前
+pub fn layered_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    collect_files(path, &mut files)?;
+    Ok(files)
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if files.iter().any(|file| file == path) {
+        return Ok(());
+    }
+    if files.len() >= MAX_INCLUDE_DEPTH * MAX_INCLUDE_DEPTH {
+        anyhow::bail!("Too many included config files at {}", path.display());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let value = ConfigFormat::from_path(path).parse(&content)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+    files.push(path.to_path_buf());
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    if let Some(includes) = value.get(INCLUDE_KEY) {
+        for include in include_paths(includes)? {
+            collect_files(&base_dir.join(include), files)?;
+        }
+    }
+    Ok(())
+}
+
+/// 展开已解析配置中的 `include`，`base_dir` 为相对路径的基准目录
+pub fn resolve_includes(value: toml::Value, base_dir: &Path) -> Result<toml::Value> {
+    let mut stack = Vec::new();
+    expand_includes(value, base_dir, &mut stack)
+}
+
+fn expand_includes(mut value: toml::Value, base_dir: &Path, stack: &mut Vec<PathBuf>) -> Result<toml::Value> {
+    let includes = match value.as_table_mut().and_then(|t| t.remove(INCLUDE_KEY)) {
+        Some(includes) => include_paths(&includes)?,
+        None => return Ok(value),
+    };
+
+    for include in includes {
+        let include_path = base_dir.join(&include);
+        let layer = load_file(&include_path, stack)
+            .with_context(|| format!("Failed to load included config: {}", include))?;
+        merge_values(&mut value, layer);
+    }
+
+    Ok(value)
+}
+
+fn include_paths(value: &toml::Value) -> Result<Vec<String>> {
+    match value {
+        toml::Value::String(path) => Ok(vec![path.clone()]),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow::anyhow!("'include' entries must be file paths"))
+            })
+            .collect(),
+        _ => anyhow::bail!("'include' must be a file path or a list of file paths"),
+    }
+}
+
+/// 将 `overlay` 合并到 `base`：表递归合并，其余值替换
+pub fn merge_values(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
+            for (key, overlay_value) in overlay_table {
+                match base_table.get_mut(&key) {
+                    Some(base_value) => merge_values(base_value, overlay_value),
+                    None => {
+                        base_table.insert(key, overlay_value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_values() {
+        let mut base: toml::Value = toml::from_str(
+            "[api]\nport = 4028\nenabled = true\n[[pools.pools]]\nurl = \"a\"\n",
+        ).unwrap();
+        let overlay: toml::Value = toml::from_str(
+            "[api]\nport = 5000\n[[pools.pools]]\nurl = \"b\"\n",
+        ).unwrap();
+        merge_values(&mut base, overlay);
+
+        assert_eq!(base["api"]["port"].as_integer(), Some(5000));
+        assert_eq!(base["api"]["enabled"].as_bool(), Some(true));
+        let pools = base["pools"]["pools"].as_array().unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0]["url"].as_str(), Some("b"));
+    }
+
+    #[test]
+    fn test_include_order_and_cycle() {
+        let dir = std::env::temp_dir().join(format!("cgminer-include-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.toml"), "include = [\"a.toml\", \"b.toml\"]\n[api]\nport = 1\n").unwrap();
+        std::fs::write(dir.join("a.toml"), "[api]\nport = 2\nbind_address = \"a\"\n").unwrap();
+        std::fs::write(dir.join("b.toml"), "[api]\nport = 3\n").unwrap();
+
+        let value = load_layered(&dir.join("main.toml")).unwrap();
+        assert_eq!(value["api"]["port"].as_integer(), Some(3));
+        assert_eq!(value["api"]["bind_address"].as_str(), Some("a"));
+        assert!(value.get(INCLUDE_KEY).is_none());
+        let files = layered_files(&dir.join("main.toml")).unwrap();
+        assert_eq!(files, vec![dir.join("main.toml"), dir.join("a.toml"), dir.join("b.toml")]);
+
+        std::fs::write(dir.join("b.toml"), "include = \"main.toml\"\n").unwrap();
+        assert!(load_layered(&dir.join("main.toml")).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
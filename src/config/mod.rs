@@ -0,0 +1,2647 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::web::WebConfig;
+use crate::api::client::ClientCommand;
+use crate::api::tls::{TlsOptions, DEFAULT_SELF_SIGNED_CERT, DEFAULT_SELF_SIGNED_KEY};
+use crate::monitoring::{HistoryConfig, MemoryBudgetConfig, PushConfig, RecoveryConfig};
+use crate::mining::{Algorithm, Network, CoreFailoverConfig, CpuLoadTargetConfig, CpuThermalThrottleConfig, DeviceCount, HashBackend, HashmeterConfig, OverflowPolicy, WorkerPriorityConfig};
+use crate::pool::share_audit::ShareAuditConfig;
+use crate::pool::share_verify::ShareVerifyConfig;
+use crate::pool::quirks::PoolQuirksConfig;
+use crate::pool::switcher::ProfitSwitchConfig;
+use crate::device::{AutoTuneConfig, ChipHealthConfig, FanControlConfig, PowerConfig, RampUpConfig, ThermalConfig};
+use crate::device::architecture::DeviceArchitectureConfig;
+use crate::logging::crash::CrashReportConfig;
+use crate::mining::session_report::SessionReportConfig;
+use crate::mining::stats_file::StatsFileConfig;
+use crate::logging::i18n::LogLanguage;
+use crate::logging::sampling::LogSamplingRule;
+use crate::logging::shipper::LogShippingConfig;
+use crate::logging::redact;
+use crate::security::SecurityConfig;
+use runtime::RuntimeConfig;
+use wallet::{AddressCheck, AddressStatus};
+
+pub mod check;
+pub mod format;
+pub mod import;
+pub mod include;
+pub mod persist;
+pub mod profiles;
+pub mod runtime;
+pub mod template;
+pub mod wallet;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Configuration file path
+    #[arg(short, long, default_value = "cgminer.toml")]
+    pub config: String,
+
+    /// Write a fully commented default configuration file and exit
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "cgminer.toml")]
+    pub generate_config: Option<String>,
+
+    /// Convert a classic cgminer/bmminer JSON .conf into a TOML config written to --config, then exit
+    #[arg(long, value_name = "PATH")]
+    pub import_cgminer_conf: Option<String>,
+
+    /// Validate the configuration file and exit (non-zero on errors)
+    #[arg(long)]
+    pub check_config: bool,
+
+    /// Read a password from stdin, print its hash for web.auth.password_hash, and exit
+    #[arg(long)]
+    pub hash_password: bool,
+
+    /// Upload a crash report to crash_report.upload_url and exit (default: the newest report)
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "")]
+    pub upload_crash_report: Option<String>,
+
+    /// Write an encrypted bundle of configs, persisted state and the device map to PATH, then exit
+    #[arg(long, value_name = "PATH")]
+    pub create_backup: Option<String>,
+
+    /// Restore all files from a bundle written by --create-backup, then exit
+    #[arg(long, value_name = "PATH")]
+    pub restore_backup: Option<String>,
+
+    /// Mine known block headers on the selected core, check it finds their golden nonces, and exit
+    #[arg(long)]
+    pub self_test: bool,
+
+    /// Push this many synthetic results through the result pipeline, report throughput and per-stage latency, and exit
+    #[arg(long, value_name = "RESULTS")]
+    pub load_test: Option<u64>,
+
+    /// With --load-test, exit with an error when throughput is below this many results per second
+    #[arg(long, value_name = "RATE", requires = "load_test")]
+    pub load_test_min_rate: Option<f64>,
+
+    /// Run a mock Stratum pool with deterministic jobs on PORT (default 3333) until Ctrl+C, for demos and testing
+    #[arg(long, value_name = "PORT", num_args = 0..=1, default_missing_value = "3333")]
+    pub mock_pool: Option<u16>,
+
+    /// Select a [profiles.<name>] block (default: match by hostname)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Show the full-screen terminal dashboard instead of console logs (needs --features=tui)
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Register cgminer-rs as an auto-start Windows service using this --config, then exit (needs --features=win-service)
+    #[arg(long)]
+    pub install_service: bool,
+
+    /// Stop and remove the Windows service, then exit
+    #[arg(long)]
+    pub uninstall_service: bool,
+
+    /// Run under the Windows service control manager (passed by the installed service)
+    #[arg(long, hide = true)]
+    pub run_as_service: bool,
+
+    /// Serve a core plugin over --core-socket (started by the miner for sandboxed plugin cores)
+    #[arg(long, hide = true, value_name = "PATH", requires = "core_socket")]
+    pub core_host: Option<String>,
+
+    /// Unix socket the core plugin host connects to
+    #[arg(long, hide = true, value_name = "PATH")]
+    pub core_socket: Option<String>,
+
+    /// Enable debug mode
+    #[arg(short, long)]
+    pub debug: bool,
+
+    /// API server port
+    #[arg(long, default_value = "4028")]
+    pub api_port: u16,
+
+    /// Disable API server
+    #[arg(long)]
+    pub no_api: bool,
+
+    /// Log level
+    #[arg(long, default_value = "info")]
+    pub log_level: String,
+
+    /// SOCKS5 proxy URL (e.g., socks5://127.0.0.1:1080 or socks5+tls://proxy.example.com:1080)
+    #[arg(long, help = "SOCKS5 proxy URL for pool connections")]
+    pub proxy: Option<String>,
+
+    /// SOCKS5 proxy username for authentication
+    #[arg(long, help = "Username for SOCKS5 proxy authentication")]
+    pub proxy_user: Option<String>,
+
+    /// SOCKS5 proxy password for authentication
+    #[arg(long, help = "Password for SOCKS5 proxy authentication")]
+    pub proxy_pass: Option<String>,
+
+    /// Pool URL to connect to, repeatable (takes priority over config file pools)
+    #[arg(short = 'o', long = "url", visible_alias = "pool", help = "Mining pool URL (stratum+tcp://pool:port), repeatable")]
+    pub pool: Vec<String>,
+
+    /// Pool username/worker name, matched to `-o` by position
+    #[arg(short = 'u', long, help = "Pool username or worker name, repeatable")]
+    pub user: Vec<String>,
+
+    /// Pool password, matched to `-o` by position
+    #[arg(short = 'p', long, help = "Pool password, repeatable")]
+    pub pass: Vec<String>,
+
+    /// Query or control a running instance through its API instead of mining
+    #[command(subcommand)]
+    pub command: Option<ClientCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub general: GeneralConfig,
+    pub cores: CoresConfig,
+    pub devices: DeviceConfig,
+    pub pools: PoolConfig,
+    pub api: ApiConfig,
+    pub monitoring: MonitoringConfig,
+    #[serde(default)]
+    pub web: WebConfig,
+    #[serde(default)]
+    pub hashmeter: HashmeterConfig,
+    #[serde(default)]
+    pub share_audit: ShareAuditConfig,
+    #[serde(default)]
+    pub share_verify: ShareVerifyConfig,
+    #[serde(default)]
+    pub log_shipping: LogShippingConfig,
+    #[serde(default)]
+    pub crash_report: CrashReportConfig,
+    #[serde(default)]
+    pub session_report: SessionReportConfig,
+    /// 供 Hive OS 等农场代理读取的统计文件
+    #[serde(default)]
+    pub stats_file: StatsFileConfig,
+    #[serde(default)]
+    pub security: SecurityConfig,
+    /// Tokio 运行时
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
+    pub performance: Option<PerformanceConfig>,
+    pub limits: Option<LimitsConfig>,
+    pub logging: Option<LoggingConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct GeneralConfig {
+    pub log_level: String,
+    /// 按目标覆盖日志级别，如 "pool=debug,device=warn"
+    pub log_targets: String,
+    /// 高频日志采样规则
+    pub log_sampling: Vec<LogSamplingRule>,
+    pub log_file: Option<PathBuf>,
+    pub pid_file: Option<PathBuf>,
+    pub work_restart_timeout: u64,
+    pub scan_time: u64,
+    /// 结果收集间隔 (毫秒) - 参考原版cgminer的ASIC轮询延迟
+    pub result_collection_interval_ms: u64,
+    /// 空闲时结果轮询间隔的上限 (毫秒)，0 表示使用默认值 1000
+    pub result_collection_max_interval_ms: u64,
+    /// 运行日志语言
+    pub log_language: LogLanguage,
+    /// 工作队列容量
+    pub max_work_queue_size: usize,
+    /// 工作队列满时的处理方式
+    pub work_queue_overflow: OverflowPolicy,
+    /// 结果队列容量
+    pub max_result_queue_size: usize,
+    /// 结果队列满时的处理方式
+    pub result_queue_overflow: OverflowPolicy,
+    /// 停机的总超时 (秒)，超时后剩余任务直接中止；0 表示使用默认值 10
+    pub shutdown_timeout_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CoresConfig {
+    pub enabled_cores: Vec<String>,
+    pub default_core: String,
+    /// 同时有多个核心时的选择顺序 (核心类型，如 "maijie-l7")，为空时 ASIC > GPU > CPU
+    pub priority: Vec<String>,
+    pub cpu_btc: Option<BtcSoftwareCoreConfig>,
+    pub gpu_btc: Option<GpuBtcCoreConfig>,
+    pub maijie_l7: Option<MaijieL7CoreConfig>,
+    /// 模拟核心配置（需要 `sim` 特性）
+    pub sim: Option<SimCoreConfig>,
+    /// Whatsminer 整机监管（需要 `whatsminer` 特性）
+    pub whatsminer: Option<WhatsminerCoreConfig>,
+    /// Avalon A12/A13 链驱动（需要 `avalon` 特性）
+    pub avalon: Option<AvalonCoreConfig>,
+    /// 动态加载的核心插件（需要 `dynamic-loading` 特性）
+    pub plugins: CorePluginConfig,
+    /// 选中核心的健康检查和故障转移
+    pub failover: CoreFailoverConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BtcSoftwareCoreConfig {
+    pub enabled: bool,
+    /// 设备 (挖矿线程) 数量，`"auto"` 按物理核心数和 cgroup 配额确定
+    pub device_count: DeviceCount,
+    /// 自动模式下为应用自身保留的核心数
+    pub reserved_cores: u32,
+    pub min_hashrate: f64,
+    pub max_hashrate: f64,
+    pub error_rate: f64,
+    pub batch_size: u32,
+    pub work_timeout_ms: u64,
+    /// 双 SHA256 内核：auto、sha_ni、avx512、avx2、neon 或 generic
+    pub hash_backend: HashBackend,
+    /// CPU绑定配置
+    pub cpu_affinity: Option<CpuAffinityConfig>,
+    /// 按整机 CPU 负载目标暂停/恢复挖矿线程
+    pub load_target: CpuLoadTargetConfig,
+    /// 封装温度超过上限时降低核心占空比
+    pub thermal_throttle: CpuThermalThrottleConfig,
+    /// 工作线程的 nice 值和调度策略 (Windows 为优先级类别)
+    pub worker_priority: WorkerPriorityConfig,
+}
+
+impl Default for BtcSoftwareCoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            device_count: DeviceCount::Auto,
+            reserved_cores: 1,
+            min_hashrate: 0.0,
+            max_hashrate: 0.0,
+            error_rate: 0.0,
+            batch_size: 0,
+            work_timeout_ms: 0,
+            hash_backend: HashBackend::Auto,
+            cpu_affinity: None,
+            load_target: CpuLoadTargetConfig::default(),
+            thermal_throttle: CpuThermalThrottleConfig::default(),
+            worker_priority: WorkerPriorityConfig::default(),
+        }
+    }
+}
+
+impl BtcSoftwareCoreConfig {
+    /// 实际的设备数量，`"auto"` 时按本机拓扑计算
+    pub fn resolved_device_count(&self) -> u32 {
+        self.device_count.resolve(self.reserved_cores)
+    }
+}
+
+/// CPU 绑定策略名
+pub const CPU_AFFINITY_STRATEGIES: &[&str] = &["round_robin", "manual", "performance_first", "physical_only", "intelligent", "numa"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuAffinityConfig {
+    /// 是否启用CPU绑定
+    pub enabled: bool,
+    /// 绑定策略: "round_robin", "manual", "performance_first", "physical_only", "intelligent", "numa"
+    pub strategy: String,
+    /// 手动核心映射 (设备ID -> CPU核心索引)
+    pub manual_mapping: Option<std::collections::HashMap<u32, usize>>,
+    /// 是否避免超线程
+    pub avoid_hyperthreading: Option<bool>,
+    /// 是否优先使用性能核心
+    pub prefer_performance_cores: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpuBtcCoreConfig {
+    pub enabled: bool,
+    pub device_count: u32,
+    pub max_hashrate: f64,
+    pub work_size: u32,
+    pub work_timeout_ms: u64,
+    /// 请求核心使用的 GPU 后端：auto、metal、opencl、cuda，内核由 cgminer-gpu-btc-core 提供
+    pub backend: GpuBackend,
+    /// OpenCL 平台序号，未设置时使用第一个有 GPU 的平台
+    pub platform: Option<u32>,
+    /// 按 GPU 序号的设置，为空时使用枚举到的全部 GPU
+    pub gpus: Vec<GpuDeviceConfig>,
+    /// CUDA 后端每个 GPU 的流数量，作为 `streams` 参数传给核心，流水线由核心实现
+    pub cuda_streams: u32,
+    /// CUDA 后端使用页锁定 (pinned) 主机缓冲区，作为 `pinned_memory` 参数传给核心
+    pub cuda_pinned_memory: bool,
+}
+
+impl Default for GpuBtcCoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // 默认禁用，需要用户手动启用
+            device_count: 1,
+            max_hashrate: 1_000_000_000_000.0, // 1 TH/s
+            work_size: 32768, // 32K 工作项
+            work_timeout_ms: 2000,
+            backend: GpuBackend::Auto,
+            platform: None,
+            gpus: Vec::new(),
+            cuda_streams: 2,
+            cuda_pinned_memory: true,
+        }
+    }
+}
+
+impl GpuBtcCoreConfig {
+    /// 创建 GPU 核心时的 custom_params，只传入解析后的后端用到的参数
+    pub fn core_params(&self) -> std::collections::HashMap<String, serde_json::Value> {
+        let mut params = std::collections::HashMap::new();
+        params.insert("device_count".to_string(), serde_json::Value::from(self.device_count));
+        params.insert("max_hashrate".to_string(), serde_json::Value::from(self.max_hashrate));
+        params.insert("work_size".to_string(), serde_json::Value::from(self.work_size));
+        params.insert("work_timeout_ms".to_string(), serde_json::Value::from(self.work_timeout_ms));
+
+        let backend = self.backend.resolve();
+        params.insert("backend".to_string(), serde_json::Value::from(backend.as_str()));
+        match backend {
+            GpuBackend::Metal => {
+                params.insert("threads_per_threadgroup".to_string(), serde_json::Value::from(512));
+            }
+            GpuBackend::Cuda => {
+                params.insert("streams".to_string(), serde_json::Value::from(self.cuda_streams));
+                params.insert("pinned_memory".to_string(), serde_json::Value::from(self.cuda_pinned_memory));
+            }
+            GpuBackend::OpenCl | GpuBackend::Auto => {}
+        }
+        if let Some(platform) = self.platform {
+            params.insert("platform".to_string(), serde_json::Value::from(platform));
+        }
+        // 每个 GPU 一个设备，工作组大小可按 GPU 设置
+        if !self.gpus.is_empty() {
+            let gpus: Vec<serde_json::Value> = self.gpus.iter()
+                .filter(|gpu| gpu.enabled)
+                .map(|gpu| serde_json::json!({
+                    "index": gpu.index,
+                    "work_size": gpu.work_size.unwrap_or(self.work_size),
+                }))
+                .collect();
+            params.insert("gpus".to_string(), serde_json::Value::Array(gpus));
+        }
+        params
+    }
+}
+
+/// GPU 计算后端。本仓库只把后端名称和相关参数传给 GPU 核心，不包含任何 GPU 内核
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuBackend {
+    #[default]
+    Auto,
+    Metal,
+    /// 需要 --features=gpu-opencl，且 cgminer-gpu-btc-core 实现了 opencl 特性
+    OpenCl,
+    /// 需要 --features=gpu-cuda，且 cgminer-gpu-btc-core 实现了 cuda 特性
+    Cuda,
+}
+
+impl GpuBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GpuBackend::Auto => "auto",
+            GpuBackend::Metal => "metal",
+            GpuBackend::OpenCl => "opencl",
+            GpuBackend::Cuda => "cuda",
+        }
+    }
+
+    /// 本平台上 auto 对应的后端：macOS 为 metal，启用 gpu-cuda 时为 cuda，否则为 opencl
+    pub fn resolve(self) -> GpuBackend {
+        match self {
+            GpuBackend::Auto if cfg!(target_os = "macos") => GpuBackend::Metal,
+            GpuBackend::Auto if cfg!(feature = "gpu-cuda") => GpuBackend::Cuda,
+            GpuBackend::Auto => GpuBackend::OpenCl,
+            backend => backend,
+        }
+    }
+}
+
+/// 单个 GPU 的设置 (`[[cores.gpu_btc.gpus]]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuDeviceConfig {
+    /// 平台内的 GPU 序号
+    pub index: u32,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 该 GPU 的工作组大小，未设置时使用 work_size
+    #[serde(default)]
+    pub work_size: Option<u32>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaijieL7CoreConfig {
+    pub enabled: bool,
+    pub chain_count: u32,
+    pub spi_speed: u32,
+    pub uart_baud: u32,
+    pub auto_detect: bool,
+    pub power_limit: f64,
+    pub cooling_mode: String,
+}
+
+/// 核心插件配置 - 从目录加载第三方核心动态库
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorePluginConfig {
+    pub enabled: bool,
+    /// 插件目录
+    pub dir: String,
+    /// 只加载这些插件 (注册名)，为空时加载目录下全部
+    pub allow: Vec<String>,
+    /// 按注册名传给核心的 custom_params
+    pub params: std::collections::HashMap<String, std::collections::HashMap<String, serde_json::Value>>,
+    /// 在子进程中运行插件核心
+    pub sandbox: PluginSandboxConfig,
+}
+
+impl Default for CorePluginConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "./plugins".to_string(),
+            allow: Vec::new(),
+            params: std::collections::HashMap::new(),
+            sandbox: PluginSandboxConfig::default(),
+        }
+    }
+}
+
+/// 插件核心子进程配置 (仅 unix)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginSandboxConfig {
+    pub enabled: bool,
+    /// 窗口内允许的子进程重启次数，超出后不再重启
+    pub max_restarts: u32,
+    /// 重启计数窗口 (秒)
+    pub restart_window_secs: u64,
+    /// 等待子进程连接的时间 (秒)
+    pub startup_timeout_secs: u64,
+}
+
+impl Default for PluginSandboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_restarts: 5,
+            restart_window_secs: 600,
+            startup_timeout_secs: 10,
+        }
+    }
+}
+
+/// 模拟核心配置 - 确定性的虚拟设备，用于集成测试和开发
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimCoreConfig {
+    pub enabled: bool,
+    pub device_count: u32,
+    /// 每个虚拟设备的算力 (H/s)
+    pub hashrate: f64,
+    /// 随机数种子，相同种子产生相同的结果序列
+    pub seed: u64,
+    /// 硬件错误注入概率 (0.0-1.0)
+    pub hardware_error_rate: f64,
+    /// 环境温度 (°C)
+    pub ambient_temperature: f32,
+    /// 满载稳态温度 (°C)
+    pub load_temperature: f32,
+    /// 升温时间常数 (秒)
+    pub thermal_time_constant_secs: f64,
+    /// 提交工作延迟 (毫秒)
+    pub work_latency_ms: u64,
+    /// 获取结果延迟 (毫秒)
+    pub result_latency_ms: u64,
+    /// 运行指定秒数后注入设备故障
+    pub fail_after_secs: Option<u64>,
+    /// 模拟 ASIC 硬件（链协议级），代替 `[[devices.boards]]` 的硬件后端
+    pub asic: SimAsicConfig,
+}
+
+impl Default for SimCoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            device_count: 2,
+            hashrate: 1_000_000_000.0, // 1 GH/s
+            seed: 42,
+            hardware_error_rate: 0.0,
+            ambient_temperature: 25.0,
+            load_temperature: 65.0,
+            thermal_time_constant_secs: 30.0,
+            work_latency_ms: 0,
+            result_latency_ms: 0,
+            fail_after_secs: None,
+            asic: SimAsicConfig::default(),
+        }
+    }
+}
+
+/// 模拟 ASIC 硬件配置 (`[cores.sim.asic]`) - 在 SPI 上应答链控制器的芯片命令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimAsicConfig {
+    pub enabled: bool,
+    pub chain_count: u32,
+    pub chips_per_chain: u32,
+    /// 每个芯片的哈希核心数，芯片算力 = 频率 × 核心数
+    pub cores_per_chip: u32,
+    /// 随机 CRC 错误概率 (0.0-1.0)
+    pub crc_error_rate: f64,
+    /// 链 N 的复位线为 reset_gpio_base + N
+    pub reset_gpio_base: u32,
+    /// 注入的故障
+    pub faults: Vec<SimFault>,
+}
+
+impl Default for SimAsicConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chain_count: 3,
+            chips_per_chain: 4,
+            cores_per_chip: 100,
+            crc_error_rate: 0.0,
+            reset_gpio_base: 20,
+            faults: Vec::new(),
+        }
+    }
+}
+
+/// 模拟故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimFaultKind {
+    /// 链不再应答，硬件复位后恢复
+    ChainDropout,
+    /// 链响应大量损坏，复位后恢复
+    CrcBurst,
+    /// 链温度突升
+    TemperatureSpike,
+}
+
+/// 一次故障注入 (`faults = [{ kind = "chain_dropout", chain_id = 1, after_secs = 600 }]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimFault {
+    pub kind: SimFaultKind,
+    pub chain_id: u8,
+    /// 启动后多久发生 (秒)
+    #[serde(default)]
+    pub after_secs: u64,
+    /// 持续时间 (秒)，未设置时持续到链复位 (温度突升为永久)
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+    /// 温度突升幅度 (°C)
+    #[serde(default = "default_spike_celsius")]
+    pub spike_celsius: f32,
+    /// CRC 突发期间的错误概率 (0.0-1.0)
+    #[serde(default = "default_burst_error_rate")]
+    pub burst_error_rate: f64,
+}
+
+fn default_spike_celsius() -> f32 {
+    30.0
+}
+
+fn default_burst_error_rate() -> f64 {
+    0.5
+}
+
+impl SimAsicConfig {
+    /// 验证模拟 ASIC 配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.chain_count == 0 || self.chain_count > 16 {
+            anyhow::bail!("Sim ASIC chain_count must be between 1 and 16");
+        }
+        if self.chips_per_chain == 0 || self.chips_per_chain > 128 {
+            anyhow::bail!("Sim ASIC chips_per_chain must be between 1 and 128");
+        }
+        if !(0.0..=1.0).contains(&self.crc_error_rate) {
+            anyhow::bail!("Sim ASIC crc_error_rate must be between 0.0 and 1.0");
+        }
+        for fault in &self.faults {
+            if fault.chain_id as u32 >= self.chain_count {
+                anyhow::bail!("Sim ASIC fault targets chain {} but only {} chains exist", fault.chain_id, self.chain_count);
+            }
+            if !(0.0..=1.0).contains(&fault.burst_error_rate) {
+                anyhow::bail!("Sim ASIC fault burst_error_rate must be between 0.0 and 1.0");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whatsminer 配置 - 通过 btminer API 监管运行原厂固件的整机，每块算力板作为一个设备
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhatsminerCoreConfig {
+    pub enabled: bool,
+    /// 矿机地址，`ip` 或 `ip:port`，端口默认 4028
+    pub hosts: Vec<String>,
+    /// 单次 API 请求超时 (毫秒)
+    pub timeout_ms: u64,
+    /// 统计缓存时间 (秒)，同一台矿机在此期间内只请求一次
+    pub poll_interval_secs: u64,
+}
+
+impl Default for WhatsminerCoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hosts: Vec::new(),
+            timeout_ms: 3000,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+/// Avalon 机型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AvalonModel {
+    #[default]
+    A12,
+    A13,
+}
+
+/// Avalon 单条链的频率档位
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvalonChainProfile {
+    /// 链序号 (AUC 上的 MM 模块序号)
+    pub id: u8,
+    /// 4 个 PLL 的频率 (MHz)
+    pub pll: Vec<u32>,
+    /// 电压 (mV)
+    pub voltage: u32,
+}
+
+/// Avalon 配置 - 通过 AUC 转接器驱动 A12/A13 的 MM 模块，每条链作为一个设备
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AvalonCoreConfig {
+    pub enabled: bool,
+    pub model: AvalonModel,
+    /// MM 模块数量
+    pub chain_count: u32,
+    /// 固定风扇转速 (%)，为空时由 devices.fan_control 决定
+    pub fan_speed: Option<u32>,
+    /// 按链覆盖频率和电压，未列出的链使用机型默认值
+    pub chains: Vec<AvalonChainProfile>,
+}
+
+impl Default for AvalonCoreConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: AvalonModel::A12,
+            chain_count: 3,
+            fan_speed: None,
+            chains: Vec::new(),
+        }
+    }
+}
+
+impl AvalonCoreConfig {
+    /// 指定链的频率档位
+    pub fn profile_for(&self, id: u8) -> AvalonChainProfile {
+        self.chains.iter().find(|chain| chain.id == id).cloned().unwrap_or_else(|| {
+            let (pll, voltage) = self.model.default_profile();
+            AvalonChainProfile { id, pll, voltage }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DeviceConfig {
+    pub auto_detect: bool,
+    pub scan_interval: u64,
+    pub chains: Vec<ChainConfig>,
+    #[serde(default)]
+    pub fan_control: FanControlConfig,
+    #[serde(default)]
+    pub ramp_up: RampUpConfig,
+    #[serde(default)]
+    pub thermal: ThermalConfig,
+    #[serde(default)]
+    pub auto_tune: AutoTuneConfig,
+    #[serde(default)]
+    pub power: PowerConfig,
+    #[serde(default)]
+    pub chip_health: ChipHealthConfig,
+    /// 各板的 SPI/UART/GPIO 设备（需要 `hardware` 特性，仅 Linux）
+    #[serde(default)]
+    pub boards: Vec<BoardHardwareConfig>,
+}
+
+/// 硬件后端支持的串口波特率
+pub const UART_BAUD_RATES: &[u32] = &[9600, 19200, 38400, 57600, 115200, 230400, 460800, 921600, 1500000, 3000000];
+
+/// 单块板的硬件配置 (`[[devices.boards]]`)，需要 `hardware` 特性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardHardwareConfig {
+    /// 该板承载的链ID
+    pub chains: Vec<u8>,
+    /// SPI 设备，如 "/dev/spidev0.0"
+    pub spi_device: Option<String>,
+    /// SPI 时钟 (Hz)
+    pub spi_speed_hz: u32,
+    /// 串口设备，如 "/dev/ttyS1" 或 "/dev/ttyUSB0"
+    pub uart_device: Option<String>,
+    /// 串口波特率
+    pub uart_baud: u32,
+    /// GPIO 控制器，如 "/dev/gpiochip0"
+    pub gpio_chip: Option<String>,
+    /// 算力板 EEPROM (sysfs)，`{chain}` 替换为链ID，如 "/sys/bus/i2c/devices/0-005{chain}/eeprom"
+    pub eeprom_path: Option<String>,
+    /// 单次传输超时 (毫秒)
+    pub timeout_ms: u64,
+    /// 总线错误后重新打开设备的重试次数
+    pub max_retries: u32,
+}
+
+impl Default for BoardHardwareConfig {
+    fn default() -> Self {
+        Self {
+            chains: Vec::new(),
+            spi_device: None,
+            spi_speed_hz: 6_000_000,
+            uart_device: None,
+            uart_baud: 115200,
+            gpio_chip: None,
+            eeprom_path: None,
+            timeout_ms: 1000,
+            max_retries: 2,
+        }
+    }
+}
+
+impl BoardHardwareConfig {
+    /// 验证板配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.chains.is_empty() {
+            anyhow::bail!("Board needs at least one chain");
+        }
+        if self.spi_device.is_none() && self.uart_device.is_none() {
+            anyhow::bail!("Board for chains {:?} needs spi_device or uart_device", self.chains);
+        }
+        if self.uart_device.is_some() && !UART_BAUD_RATES.contains(&self.uart_baud) {
+            anyhow::bail!("Unsupported uart_baud {}", self.uart_baud);
+        }
+        if self.timeout_ms == 0 {
+            anyhow::bail!("Board timeout_ms must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+/// 链频率允许范围 (MHz)
+pub const FREQUENCY_RANGE: std::ops::RangeInclusive<u32> = 100..=1000;
+
+/// 链电压允许范围 (mV)
+pub const VOLTAGE_RANGE: std::ops::RangeInclusive<u32> = 600..=1000;
+
+/// extranonce2 设备子区间数上限 (2 字节的 extranonce2 每个子区间仍有 64 个值)
+pub const MAX_EXTRANONCE2_PARTITIONS: u32 = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainConfig {
+    pub id: u8,
+    pub enabled: bool,
+    pub frequency: u32,
+    pub voltage: u32,
+    pub auto_tune: bool,
+    pub chip_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PoolConfig {
+    pub strategy: PoolStrategy,
+    pub failover_timeout: u64,
+    pub retry_interval: u64,
+    pub pools: Vec<PoolInfo>,
+    /// 用户名像钱包地址但校验失败时：off 不检查、warn 告警、fail 拒绝启动
+    pub address_check: AddressCheck,
+    /// 每次流水线发送的份额数，0 表示默认值 16，1 表示逐个提交
+    pub submit_pipeline_depth: usize,
+    /// 比特币网络：决定难度 1 目标和钱包地址的网络前缀
+    pub network: Network,
+    /// extranonce2 空间均分的设备子区间数，设备 N 使用第 N % partitions 个，0 和 1 表示不划分
+    pub extranonce2_partitions: u32,
+    /// 按收益切换矿池
+    pub profit_switch: ProfitSwitchConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum PoolStrategy {
+    Failover,
+    RoundRobin,
+    LoadBalance,
+    Quota,
+}
+
+impl Default for PoolStrategy {
+    fn default() -> Self {
+        PoolStrategy::Failover  // 默认使用故障转移策略
+    }
+}
+
+/// 矿池协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolProtocol {
+    #[default]
+    Stratum,
+    /// HTTP getwork + 长轮询 (旧式私有矿池和测试环境)
+    Getwork,
+}
+
+impl PoolProtocol {
+    /// 按地址推断协议：http(s):// 为 getwork，其余为 Stratum
+    pub fn from_url(url: &str) -> Self {
+        if PoolProtocol::is_http_url(url) {
+            PoolProtocol::Getwork
+        } else {
+            PoolProtocol::Stratum
+        }
+    }
+
+    fn is_http_url(url: &str) -> bool {
+        url.starts_with("http://") || url.starts_with("https://")
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PoolInfo {
+    pub name: Option<String>,
+    pub url: String,
+    #[serde(alias = "user")]
+    pub username: String,
+    pub password: String,
+    pub priority: u8,
+    pub quota: Option<u32>,
+    pub enabled: bool,
+    /// 代理配置
+    pub proxy: Option<ProxyConfig>,
+    /// 挖矿算法
+    #[serde(default)]
+    pub algorithm: Algorithm,
+    /// 矿池协议
+    #[serde(default)]
+    pub protocol: PoolProtocol,
+    /// 协议差异，未设置时按地址自动识别
+    pub quirks: Option<PoolQuirksConfig>,
+    /// 矿池费率 (%)，按收益切换时从收益中扣除
+    pub fee: Option<f64>,
+    /// 在收益数据中的键，默认使用矿池名称
+    pub profit_key: Option<String>,
+}
+
+impl std::fmt::Debug for PoolInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PoolInfo")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &redact::mask(&self.password))
+            .field("priority", &self.priority)
+            .field("quota", &self.quota)
+            .field("enabled", &self.enabled)
+            .field("proxy", &self.proxy)
+            .field("algorithm", &self.algorithm)
+            .field("protocol", &self.protocol)
+            .field("quirks", &self.quirks)
+            .field("fee", &self.fee)
+            .field("profit_key", &self.profit_key)
+            .finish()
+    }
+}
+
+/// 代理配置
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// 代理类型：socks5, socks5+tls
+    pub proxy_type: String,
+    /// 代理服务器地址
+    pub host: String,
+    /// 代理服务器端口
+    pub port: u16,
+    /// 代理认证用户名（可选）
+    pub username: Option<String>,
+    /// 代理认证密码（可选）
+    pub password: Option<String>,
+    /// TLS配置：是否跳过证书验证
+    pub skip_verify: Option<bool>,
+    /// TLS配置：服务器名称
+    pub server_name: Option<String>,
+    /// TLS配置：CA证书路径
+    pub ca_cert: Option<String>,
+    /// TLS配置：客户端证书路径
+    pub client_cert: Option<String>,
+    /// TLS配置：客户端私钥路径
+    pub client_key: Option<String>,
+}
+
+impl std::fmt::Debug for ProxyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyConfig")
+            .field("proxy_type", &self.proxy_type)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("password", &redact::mask_option(&self.password))
+            .field("skip_verify", &self.skip_verify)
+            .field("server_name", &self.server_name)
+            .field("ca_cert", &self.ca_cert)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .finish()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    pub allow_origins: Vec<String>,
+    pub auth_token: Option<String>,
+    /// TLS 证书文件 (PEM)，与 tls_key 同时配置时 API 和仪表板使用 HTTPS
+    pub tls_cert: Option<String>,
+    /// TLS 私钥文件 (PEM)
+    pub tls_key: Option<String>,
+    /// 自签名证书、HSTS 和加密套件策略
+    pub tls: TlsOptions,
+}
+
+impl ApiConfig {
+    /// 是否启用 HTTPS：配置了证书和私钥，或启用了自签名证书
+    pub fn tls_enabled(&self) -> bool {
+        self.tls_paths().is_some()
+    }
+
+    /// 证书和私钥路径，自签名且未指定路径时使用 ./data/tls/ 下的默认文件
+    pub fn tls_paths(&self) -> Option<(String, String)> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Some((cert.clone(), key.clone())),
+            (None, None) if self.tls.self_signed => {
+                Some((DEFAULT_SELF_SIGNED_CERT.to_string(), DEFAULT_SELF_SIGNED_KEY.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiConfig")
+            .field("enabled", &self.enabled)
+            .field("bind_address", &self.bind_address)
+            .field("port", &self.port)
+            .field("allow_origins", &self.allow_origins)
+            .field("auth_token", &redact::mask_option(&self.auth_token))
+            .field("tls_cert", &self.tls_cert)
+            .field("tls_key", &self.tls_key)
+            .field("tls", &self.tls)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MonitoringConfig {
+    pub enabled: bool,
+    pub metrics_interval: u64,
+    pub web_port: Option<u16>,
+    pub alert_thresholds: AlertThresholds,
+    /// 严重告警的 Web Push 通知
+    pub push: PushConfig,
+    /// 降采样的指标历史，供历史图表使用
+    pub history: HistoryConfig,
+    /// 进程内存预算
+    pub memory: MemoryBudgetConfig,
+    /// 总算力持续为 0 时的分级恢复
+    pub recovery: RecoveryConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AlertThresholds {
+    pub temperature_warning: f32,
+    pub temperature_critical: f32,
+    pub hashrate_drop_percent: f32,
+    pub error_rate_percent: f32,
+    pub max_temperature: f32,
+    pub max_cpu_usage: f32,
+    pub max_memory_usage: f32,
+    pub max_device_temperature: f32,
+    pub max_error_rate: f32,
+    pub min_hashrate: f64,
+}
+
+/// 性能优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceConfig {
+    /// 算力优化配置
+    pub hashrate_optimization: Option<HashrateOptimizationConfig>,
+    /// 内存优化配置
+    pub memory_optimization: Option<MemoryOptimizationConfig>,
+    /// 线程优化配置
+    pub thread_optimization: Option<ThreadOptimizationConfig>,
+    /// 批处理优化配置
+    pub batch_optimization: Option<BatchOptimizationConfig>,
+    /// 网络优化配置
+    pub network_optimization: Option<NetworkOptimizationConfig>,
+}
+
+/// 算力优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HashrateOptimizationConfig {
+    /// 基础算力 (H/s)
+    pub base_hashrate: f64,
+    /// 算力变化范围 (0.0-1.0)
+    pub hashrate_variance: f64,
+    /// 频率-算力因子
+    pub frequency_hashrate_factor: f64,
+    /// 电压-算力因子
+    pub voltage_hashrate_factor: f64,
+    /// 温度影响因子
+    pub temperature_impact_factor: f64,
+    /// 自适应调整
+    pub adaptive_adjustment: bool,
+}
+
+/// 内存优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryOptimizationConfig {
+    /// 工作缓存大小
+    pub work_cache_size: u32,
+    /// 结果缓存大小
+    pub result_cache_size: u32,
+    /// 统计保留时间 (秒)
+    pub stats_retention_seconds: u64,
+    /// 启用内存池
+    pub enable_memory_pool: bool,
+    /// 预分配内存 (MB)
+    pub preallocated_memory_mb: u32,
+}
+
+/// 线程优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadOptimizationConfig {
+    /// 每设备工作线程数
+    pub worker_threads_per_device: u32,
+    /// 线程优先级
+    pub thread_priority: String,
+    /// 线程栈大小 (KB)
+    pub thread_stack_size_kb: u32,
+    /// 启用线程池
+    pub enable_thread_pool: bool,
+}
+
+/// 批处理优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOptimizationConfig {
+    /// 默认批次大小
+    pub default_batch_size: u32,
+    /// 最小批次大小
+    pub min_batch_size: u32,
+    /// 最大批次大小
+    pub max_batch_size: u32,
+    /// 自适应批次大小
+    pub adaptive_batch_size: bool,
+    /// 批次超时 (毫秒)
+    pub batch_timeout_ms: u64,
+}
+
+/// 网络优化配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkOptimizationConfig {
+    /// 连接池大小
+    pub connection_pool_size: u32,
+    /// 请求超时 (毫秒)
+    pub request_timeout_ms: u64,
+    /// 最大并发请求数
+    pub max_concurrent_requests: u32,
+    /// 保活间隔 (秒)
+    pub keepalive_interval: u64,
+}
+
+/// 系统资源限制配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitsConfig {
+    /// 最大内存使用 (MB)
+    pub max_memory_mb: u64,
+    /// 最大CPU使用率 (%)
+    pub max_cpu_percent: f64,
+    /// 最大打开文件数
+    pub max_open_files: u32,
+    /// 最大网络连接数
+    pub max_network_connections: u32,
+}
+
+/// 日志配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// 日志级别
+    pub level: String,
+    /// 日志文件路径
+    pub file: String,
+    /// 最大文件大小
+    pub max_size: String,
+    /// 最大文件数量
+    pub max_files: u32,
+    /// 控制台输出
+    pub console: bool,
+    /// JSON格式
+    pub json_format: bool,
+    /// 日志轮转: never / hourly / daily / size
+    pub rotation: String,
+    /// 按大小轮转时压缩旧文件 (gzip)
+    #[serde(default)]
+    pub compress: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            general: GeneralConfig {
+                log_level: "info".to_string(),
+                log_targets: String::new(),
+                log_sampling: Vec::new(),
+                log_file: None,
+                pid_file: Some(PathBuf::from("/tmp/cgminer-rs.pid")),
+                work_restart_timeout: 60,
+                scan_time: 30,
+                result_collection_interval_ms: 20,
+                result_collection_max_interval_ms: 1000,
+                log_language: LogLanguage::default(),
+                max_work_queue_size: 1000,
+                work_queue_overflow: OverflowPolicy::DropOldest,
+                max_result_queue_size: 1000,
+                result_queue_overflow: OverflowPolicy::Block,
+                shutdown_timeout_secs: 10,
+            },
+            cores: CoresConfig {
+                enabled_cores: vec!["cpu-btc".to_string()],
+                default_core: "cpu-btc".to_string(),
+                priority: Vec::new(),
+                cpu_btc: Some(BtcSoftwareCoreConfig {
+                    enabled: true,
+                    device_count: DeviceCount::Fixed(4),
+                    reserved_cores: 1,
+                    min_hashrate: 1_000_000_000.0, // 1 GH/s
+                    max_hashrate: 5_000_000_000.0, // 5 GH/s
+                    error_rate: 0.01, // 1%
+                    batch_size: 1000,
+                    work_timeout_ms: 5000,
+                    hash_backend: HashBackend::Auto,
+                    cpu_affinity: Some(CpuAffinityConfig {
+                        enabled: true,
+                        strategy: "round_robin".to_string(),
+                        manual_mapping: None,
+                        avoid_hyperthreading: Some(false),
+                        prefer_performance_cores: Some(true),
+                    }),
+                    load_target: CpuLoadTargetConfig::default(),
+                    thermal_throttle: CpuThermalThrottleConfig::default(),
+                    worker_priority: WorkerPriorityConfig::default(),
+                }),
+                gpu_btc: Some(GpuBtcCoreConfig::default()),
+                maijie_l7: Some(MaijieL7CoreConfig {
+                    enabled: false,
+                    chain_count: 3,
+                    spi_speed: 6_000_000, // 6MHz
+                    uart_baud: 115200,
+                    auto_detect: true,
+                    power_limit: 3000.0, // 3kW
+                    cooling_mode: "auto".to_string(),
+                }),
+                sim: None,
+                whatsminer: None,
+                avalon: None,
+                plugins: CorePluginConfig::default(),
+                failover: CoreFailoverConfig::default(),
+            },
+            devices: DeviceConfig {
+                auto_detect: true,
+                scan_interval: 5,
+                chains: vec![
+                    ChainConfig {
+                        id: 0,
+                        enabled: true,
+                        frequency: 500,
+                        voltage: 850,
+                        auto_tune: true,
+                        chip_count: 76,
+                    },
+                    ChainConfig {
+                        id: 1,
+                        enabled: true,
+                        frequency: 500,
+                        voltage: 850,
+                        auto_tune: true,
+                        chip_count: 76,
+                    },
+                ],
+                fan_control: FanControlConfig::default(),
+                ramp_up: RampUpConfig::default(),
+                thermal: ThermalConfig::default(),
+                auto_tune: AutoTuneConfig::default(),
+                power: PowerConfig::default(),
+                chip_health: ChipHealthConfig::default(),
+                boards: Vec::new(),
+            },
+            pools: PoolConfig {
+                strategy: PoolStrategy::Failover,
+                failover_timeout: 30,
+                retry_interval: 10,
+                address_check: AddressCheck::Fail,
+                submit_pipeline_depth: 16,
+                network: Network::Mainnet,
+                extranonce2_partitions: 1,
+                profit_switch: ProfitSwitchConfig::default(),
+                pools: vec![
+                    PoolInfo {
+                        name: Some("example-pool".to_string()),
+                        url: "stratum+tcp://pool.example.com:4444".to_string(),
+                        username: "username".to_string(),
+                        password: "password".to_string(),
+                        priority: 1,
+                        quota: None,
+                        enabled: true,
+                        proxy: None,
+                        algorithm: Algorithm::Sha256d,
+                        protocol: PoolProtocol::Stratum,
+                        quirks: None,
+                    fee: None,
+                    profit_key: None,
+                        fee: None,
+                        profit_key: None,
+                    },
+                ],
+            },
+            api: ApiConfig {
+                enabled: true,
+                bind_address: "127.0.0.1".to_string(),
+                port: 4028,
+                allow_origins: vec!["*".to_string()],
+                auth_token: None,
+                tls_cert: None,
+                tls_key: None,
+                tls: TlsOptions::default(),
+            },
+            monitoring: MonitoringConfig {
+                enabled: true,
+                metrics_interval: 30,
+                web_port: Some(8888),
+                alert_thresholds: AlertThresholds {
+                    temperature_warning: 80.0,
+                    temperature_critical: 90.0,
+                    hashrate_drop_percent: 20.0,
+                    error_rate_percent: 5.0,
+                    max_temperature: 85.0,
+                    max_cpu_usage: 80.0,
+                    max_memory_usage: 90.0,
+                    max_device_temperature: 85.0,
+                    max_error_rate: 5.0,
+                    min_hashrate: 50.0,
+                },
+                push: PushConfig::default(),
+                history: HistoryConfig::default(),
+                memory: MemoryBudgetConfig::default(),
+                recovery: RecoveryConfig::default(),
+            },
+            web: WebConfig::default(),
+            hashmeter: HashmeterConfig::default(),
+            share_audit: ShareAuditConfig::default(),
+            share_verify: ShareVerifyConfig::default(),
+            log_shipping: LogShippingConfig::default(),
+            crash_report: CrashReportConfig::default(),
+            session_report: SessionReportConfig::default(),
+            stats_file: StatsFileConfig::default(),
+            security: SecurityConfig::default(),
+            runtime: RuntimeConfig::default(),
+            performance: None,
+            limits: None,
+            logging: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &str) -> Result<Self> {
+        Self::load_with_profile(path, None)
+    }
+
+    /// 加载配置并应用档案，`profile` 为空时按主机名匹配
+    pub fn load_with_profile(path: &str, profile: Option<&str>) -> Result<Self> {
+        // 读取配置并按顺序合并 include 中的文件
+        let mut value = include::load_layered(std::path::Path::new(path))?;
+
+        // 应用 [profiles.<name>] 覆盖
+        if let Some(name) = profiles::apply_profile(&mut value, profile, profiles::local_hostname().as_deref())? {
+            tracing::info!("Applied config profile '{}'", name);
+        }
+
+        // 应用 CGMINER_* 环境变量覆盖
+        apply_env_overrides(&mut value, std::env::vars())?;
+
+        let config: Config = value.try_into()
+            .with_context(|| format!("Failed to parse config file: {}", path))?;
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// 应用CLI参数覆盖配置
+    pub fn apply_cli_args(&mut self, args: &Args) -> Result<()> {
+        // 应用API端口覆盖
+        if args.api_port != 4028 {
+            self.api.port = args.api_port;
+        }
+
+        // 应用API禁用选项
+        if args.no_api {
+            self.api.enabled = false;
+        }
+
+        // 应用日志级别覆盖
+        if args.log_level != "info" {
+            self.general.log_level = args.log_level.clone();
+        }
+        if args.debug {
+            self.general.log_level = "debug".to_string();
+        }
+
+        // 处理代理和矿池相关的CLI参数
+        if args.proxy.is_some() || !args.pool.is_empty() || !args.user.is_empty() || !args.pass.is_empty() {
+            self.apply_pool_cli_args(args)?;
+        }
+
+        Ok(())
+    }
+
+    /// 应用矿池相关的CLI参数
+    fn apply_pool_cli_args(&mut self, args: &Args) -> Result<()> {
+        if !args.pool.is_empty() {
+            // 与原版 cgminer 一致：每个 -o 对应一个矿池，-u/-p 按出现顺序配对，
+            // 缺少时沿用上一个值。CLI 矿池优先，配置文件中的矿池作为备用
+            let mut cli_pools = Vec::with_capacity(args.pool.len());
+            for (i, url) in args.pool.iter().enumerate() {
+                let username = args.user.get(i).or(args.user.last())
+                    .cloned()
+                    .unwrap_or_else(|| "worker".to_string());
+                let password = args.pass.get(i).or(args.pass.last())
+                    .cloned()
+                    .unwrap_or_else(|| "x".to_string());
+
+                cli_pools.push(PoolInfo {
+                    name: Some(format!("cli-pool-{}", i)),
+                    url: url.clone(),
+                    username,
+                    password,
+                    priority: i.min(u8::MAX as usize) as u8,
+                    quota: None,
+                    enabled: true,
+                    proxy: None,
+                    algorithm: Algorithm::default(),
+                    protocol: PoolProtocol::from_url(url),
+                    quirks: None,
+                });
+            }
+
+            let offset = cli_pools.len();
+            let mut backups: Vec<PoolInfo> = self.pools.pools
+                .drain(..)
+                .filter(|pool| !cli_pools.iter().any(|cli| cli.url == pool.url))
+                .collect();
+            backups.sort_by_key(|pool| pool.priority);
+            for (i, pool) in backups.iter_mut().enumerate() {
+                pool.priority = (offset + i).min(u8::MAX as usize) as u8;
+            }
+
+            self.pools.pools = cli_pools;
+            self.pools.pools.extend(backups);
+        } else {
+            // 未指定矿池URL时，-u/-p 按顺序应用到配置文件中的矿池
+            for (pool, user) in self.pools.pools.iter_mut().zip(&args.user) {
+                pool.username = user.clone();
+            }
+            for (pool, pass) in self.pools.pools.iter_mut().zip(&args.pass) {
+                pool.password = pass.clone();
+            }
+        }
+
+        // 处理代理配置
+        if let Some(proxy_url) = &args.proxy {
+            let proxy_config = self.parse_proxy_url(proxy_url, args)?;
+
+            // 应用代理配置到所有启用的矿池
+            for pool in &mut self.pools.pools {
+                if pool.enabled {
+                    pool.proxy = Some(proxy_config.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 解析代理URL并创建代理配置
+    fn parse_proxy_url(&self, proxy_url: &str, args: &Args) -> Result<ProxyConfig> {
+        use url::Url;
+
+        let parsed_url = Url::parse(proxy_url)
+            .with_context(|| format!("Invalid proxy URL: {}", proxy_url))?;
+
+        let proxy_type = match parsed_url.scheme() {
+            "socks5" => "socks5".to_string(),
+            "socks5+tls" => "socks5+tls".to_string(),
+            scheme => {
+                anyhow::bail!("Unsupported proxy scheme: {}. Use 'socks5' or 'socks5+tls'", scheme);
+            }
+        };
+
+        let host = parsed_url.host_str()
+            .ok_or_else(|| anyhow::anyhow!("Proxy URL must include a host"))?
+            .to_string();
+
+        let port = parsed_url.port()
+            .ok_or_else(|| anyhow::anyhow!("Proxy URL must include a port"))?;
+
+        // 优先使用CLI参数中的认证信息，其次使用URL中的认证信息
+        let username = args.proxy_user.clone()
+            .or_else(|| {
+                if parsed_url.username().is_empty() {
+                    None
+                } else {
+                    Some(parsed_url.username().to_string())
+                }
+            });
+
+        let password = args.proxy_pass.clone()
+            .or_else(|| parsed_url.password().map(|p| p.to_string()));
+
+        // 解析URL查询参数中的TLS配置
+        let mut skip_verify = None;
+        let mut server_name = None;
+        let mut ca_cert = None;
+        let mut client_cert = None;
+        let mut client_key = None;
+
+        for (key, value) in parsed_url.query_pairs() {
+            match key.as_ref() {
+                "skip_verify" => {
+                    skip_verify = Some(value.parse::<bool>()
+                        .with_context(|| format!("Invalid skip_verify value: {}", value))?);
+                },
+                "server_name" => {
+                    server_name = Some(value.to_string());
+                },
+                "ca_cert" => {
+                    ca_cert = Some(value.to_string());
+                },
+                "client_cert" => {
+                    client_cert = Some(value.to_string());
+                },
+                "client_key" => {
+                    client_key = Some(value.to_string());
+                },
+                _ => {
+                    // 忽略未知参数，但记录警告
+                    eprintln!("Warning: Unknown proxy URL parameter: {}", key);
+                }
+            }
+        }
+
+        Ok(ProxyConfig {
+            proxy_type,
+            host,
+            port,
+            username,
+            password,
+            skip_verify,
+            server_name,
+            ca_cert,
+            client_cert,
+            client_key,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn save(&self, path: &str) -> Result<()> {
+        let config_content = format::ConfigFormat::from_path(std::path::Path::new(path))
+            .serialize(self)
+            .context("Failed to serialize config")?;
+
+        std::fs::write(path, config_content)
+            .with_context(|| format!("Failed to write config file: {}", path))?;
+
+        Ok(())
+    }
+
+    /// 验证配置，汇总所有错误后一并返回
+    pub fn validate(&self) -> Result<()> {
+        let errors = self.validation_errors();
+        match errors.as_slice() {
+            [] => Ok(()),
+            [error] => anyhow::bail!("{}", error),
+            errors => anyhow::bail!(
+                "Configuration has {} errors:\n{}",
+                errors.len(),
+                errors.iter().map(|e| format!("  - {}", e)).collect::<Vec<_>>().join("\n")
+            ),
+        }
+    }
+
+    /// 用户名像钱包地址但校验和不对的已启用矿池：(矿池下标, 说明)
+    pub fn wallet_address_issues(&self) -> Vec<(usize, String)> {
+        if self.pools.address_check == AddressCheck::Off {
+            return Vec::new();
+        }
+        self.pools.pools.iter().enumerate()
+            .filter(|(_, pool)| pool.enabled)
+            .filter_map(|(index, pool)| match wallet::check_username(&pool.username, self.pools.network) {
+                AddressStatus::Invalid(reason) => Some((index, format!(
+                    "Pool {} username '{}' looks like a wallet address but {}; check it for typos \
+                     (set pools.address_check = \"off\" if this is intended)",
+                    index, wallet::wallet_part(&pool.username), reason
+                ))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 收集所有配置错误（包括跨字段约束），不在第一个错误处停止
+    pub fn validation_errors(&self) -> Vec<ValidationError> {
+        // 注意：核心配置现在完全由编译特性和系统优先级逻辑控制
+        // enabled_cores和default_core都不再需要配置验证
+        let mut errors = Vec::new();
+        let mut error = |key: &str, message: String| errors.push(ValidationError { key: key.to_string(), message });
+
+        let arch_limits = DeviceArchitectureConfig::default();
+        let max_per_core = arch_limits.max_devices_per_core;
+        let mut total_devices = 0u32;
+
+        // 验证Bitcoin软算法核心配置
+        if let Some(cpu_btc_config) = &self.cores.cpu_btc {
+            if cpu_btc_config.enabled {
+                let device_count = cpu_btc_config.resolved_device_count();
+                total_devices += device_count;
+                if device_count == 0 || device_count > max_per_core {
+                    error("cores.cpu_btc.device_count", format!(
+                        "Bitcoin software core device count {} is out of range (1-{})",
+                        device_count, max_per_core
+                    ));
+                }
+                if cpu_btc_config.min_hashrate >= cpu_btc_config.max_hashrate {
+                    error("cores.cpu_btc.min_hashrate", "Bitcoin software core min_hashrate must be less than max_hashrate".to_string());
+                }
+                if cpu_btc_config.error_rate < 0.0 || cpu_btc_config.error_rate > 1.0 {
+                    error("cores.cpu_btc.error_rate", "Bitcoin software core error_rate must be between 0.0 and 1.0".to_string());
+                }
+                if let Err(e) = cpu_btc_config.hash_backend.resolve() {
+                    error("cores.cpu_btc.hash_backend", e);
+                }
+                if let Err(e) = cpu_btc_config.load_target.validate() {
+                    error("cores.cpu_btc.load_target", e.to_string());
+                }
+                if let Err(e) = cpu_btc_config.thermal_throttle.validate() {
+                    error("cores.cpu_btc.thermal_throttle", e.to_string());
+                }
+                if let Err(e) = cpu_btc_config.worker_priority.validate() {
+                    error("cores.cpu_btc.worker_priority", e.to_string());
+                }
+                if let Some(affinity) = cpu_btc_config.cpu_affinity.as_ref().filter(|a| a.enabled) {
+                    if !CPU_AFFINITY_STRATEGIES.contains(&affinity.strategy.as_str()) {
+                        error("cores.cpu_btc.cpu_affinity.strategy", format!(
+                            "Unknown CPU affinity strategy {:?} (expected one of: {})",
+                            affinity.strategy, CPU_AFFINITY_STRATEGIES.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+
+        // 验证GPU核心配置
+        if let Some(gpu_btc_config) = &self.cores.gpu_btc {
+            if gpu_btc_config.enabled {
+                total_devices += gpu_btc_config.device_count;
+                if gpu_btc_config.device_count == 0 || gpu_btc_config.device_count > max_per_core {
+                    error("cores.gpu_btc.device_count", format!(
+                        "GPU core device count {} is out of range (1-{})",
+                        gpu_btc_config.device_count, max_per_core
+                    ));
+                }
+                // 没有编译 GPU 核心时不会创建核心，后端设置不生效
+                match gpu_btc_config.backend {
+                    _ if !cfg!(feature = "gpu-btc") => {}
+                    GpuBackend::Metal if !cfg!(target_os = "macos") => {
+                        error("cores.gpu_btc.backend", "The metal GPU backend is only available on macOS".to_string());
+                    }
+                    GpuBackend::OpenCl if !cfg!(feature = "gpu-opencl") => {
+                        error("cores.gpu_btc.backend", "The opencl GPU backend needs a build with --features=gpu-opencl".to_string());
+                    }
+                    GpuBackend::Cuda if !cfg!(feature = "gpu-cuda") => {
+                        error("cores.gpu_btc.backend", "The cuda GPU backend needs a build with --features=gpu-cuda".to_string());
+                    }
+                    _ => {}
+                }
+                let uses_cuda = gpu_btc_config.backend.resolve() == GpuBackend::Cuda;
+                if uses_cuda && (gpu_btc_config.cuda_streams == 0 || gpu_btc_config.cuda_streams > 8) {
+                    error("cores.gpu_btc.cuda_streams", format!(
+                        "cuda_streams must be between 1 and 8, got {}", gpu_btc_config.cuda_streams
+                    ));
+                }
+                let mut gpu_indexes = std::collections::HashSet::new();
+                for gpu in &gpu_btc_config.gpus {
+                    if !gpu_indexes.insert(gpu.index) {
+                        error("cores.gpu_btc.gpus", format!("GPU {} is configured more than once", gpu.index));
+                    }
+                    if gpu.work_size == Some(0) {
+                        error("cores.gpu_btc.gpus", format!("GPU {} work_size must be greater than 0", gpu.index));
+                    }
+                }
+            }
+        }
+
+        // 验证模拟核心配置
+        if let Some(sim_config) = &self.cores.sim {
+            if sim_config.enabled {
+                total_devices += sim_config.device_count;
+                if sim_config.device_count == 0 || sim_config.device_count > max_per_core {
+                    error("cores.sim.device_count", format!(
+                        "Sim core device count {} is out of range (1-{})",
+                        sim_config.device_count, max_per_core
+                    ));
+                }
+                if sim_config.hashrate <= 0.0 {
+                    error("cores.sim.hashrate", "Sim core hashrate must be greater than 0".to_string());
+                }
+                if sim_config.hardware_error_rate < 0.0 || sim_config.hardware_error_rate > 1.0 {
+                    error("cores.sim.hardware_error_rate", "Sim core hardware_error_rate must be between 0.0 and 1.0".to_string());
+                }
+                if sim_config.thermal_time_constant_secs <= 0.0 {
+                    error("cores.sim.thermal_time_constant_secs", "Sim core thermal_time_constant_secs must be greater than 0".to_string());
+                }
+                if let Err(e) = sim_config.asic.validate() {
+                    error("cores.sim.asic", e.to_string());
+                }
+                if sim_config.asic.enabled && !self.devices.boards.is_empty() {
+                    error("cores.sim.asic", "Simulated ASIC hardware replaces [[devices.boards]], configure only one of them".to_string());
+                }
+            }
+        }
+
+        // 验证 Whatsminer 配置
+        if let Some(whatsminer) = &self.cores.whatsminer {
+            if whatsminer.enabled {
+                if whatsminer.hosts.is_empty() {
+                    error("cores.whatsminer.hosts", "Whatsminer core needs at least one host".to_string());
+                }
+                for host in &whatsminer.hosts {
+                    let port_ok = host.rsplit_once(':').map_or(true, |(_, port)| port.parse::<u16>().is_ok());
+                    if host.trim().is_empty() || !port_ok {
+                        error("cores.whatsminer.hosts", format!("Invalid Whatsminer host '{}', expected ip or ip:port", host));
+                    }
+                }
+                if whatsminer.timeout_ms == 0 {
+                    error("cores.whatsminer.timeout_ms", "Whatsminer timeout_ms must be greater than 0".to_string());
+                }
+                if whatsminer.poll_interval_secs == 0 {
+                    error("cores.whatsminer.poll_interval_secs", "Whatsminer poll_interval_secs must be greater than 0".to_string());
+                }
+            }
+        }
+
+        // 验证 Avalon 配置
+        if let Some(avalon) = &self.cores.avalon {
+            if avalon.enabled {
+                if avalon.chain_count == 0 || avalon.chain_count > 16 {
+                    error("cores.avalon.chain_count", format!("Avalon chain_count {} out of range 1-16", avalon.chain_count));
+                }
+                if avalon.fan_speed.map_or(false, |speed| speed > 100) {
+                    error("cores.avalon.fan_speed", "Avalon fan_speed must be a percentage (0-100)".to_string());
+                }
+                for id in 0..avalon.chain_count.min(16) as u8 {
+                    let routed = self.devices.boards.iter()
+                        .any(|board| board.uart_device.is_some() && board.chains.contains(&id));
+                    if !routed {
+                        error("cores.avalon", format!("Avalon chain {} has no [[devices.boards]] entry with a uart_device", id));
+                    }
+                }
+                for chain in &avalon.chains {
+                    if chain.id as u32 >= avalon.chain_count {
+                        error("cores.avalon.chains", format!("Avalon chain {} does not exist (chain_count = {})", chain.id, avalon.chain_count));
+                    }
+                    if chain.pll.len() != 4 {
+                        error("cores.avalon.chains", format!("Avalon chain {} needs 4 PLL frequencies, got {}", chain.id, chain.pll.len()));
+                    }
+                    if chain.pll.iter().any(|freq| !(25..=1200).contains(freq)) {
+                        error("cores.avalon.chains", format!("Avalon chain {} PLL frequency out of range 25-1200 MHz", chain.id));
+                    }
+                }
+            }
+        }
+
+        // 验证核心插件配置
+        if self.cores.plugins.enabled {
+            if !cfg!(feature = "dynamic-loading") {
+                error("cores.plugins.enabled", "This build has no dynamic core loading; rebuild with --features=dynamic-loading".to_string());
+            }
+            if self.cores.plugins.dir.trim().is_empty() {
+                error("cores.plugins.dir", "cores.plugins.dir cannot be empty when plugins are enabled".to_string());
+            }
+            let sandbox = &self.cores.plugins.sandbox;
+            if sandbox.enabled {
+                if sandbox.restart_window_secs == 0 {
+                    error("cores.plugins.sandbox.restart_window_secs", "cores.plugins.sandbox.restart_window_secs must be greater than 0".to_string());
+                }
+                if sandbox.startup_timeout_secs == 0 {
+                    error("cores.plugins.sandbox.startup_timeout_secs", "cores.plugins.sandbox.startup_timeout_secs must be greater than 0".to_string());
+                }
+            }
+        }
+
+        for (i, core_type) in self.cores.priority.iter().enumerate() {
+            if core_type.trim().is_empty() {
+                error("cores.priority", "cores.priority entries cannot be empty".to_string());
+            } else if self.cores.priority[..i].contains(core_type) {
+                error("cores.priority", format!("Core type '{}' is listed twice in cores.priority", core_type));
+            }
+        }
+        for (key, e) in self.cores.failover.validate() {
+            error(key, e);
+        }
+
+        // 验证Maijie L7 ASIC核心配置
+        if let Some(maijie_l7_config) = &self.cores.maijie_l7 {
+            if maijie_l7_config.enabled {
+                if maijie_l7_config.chain_count == 0 || maijie_l7_config.chain_count > 16 {
+                    error("cores.maijie_l7.chain_count", format!(
+                        "Maijie L7 core chain count {} is out of range (1-16)", maijie_l7_config.chain_count
+                    ));
+                }
+                if maijie_l7_config.spi_speed == 0 || maijie_l7_config.spi_speed > 50_000_000 {
+                    error("cores.maijie_l7.spi_speed", "Maijie L7 core SPI speed must be between 1 and 50,000,000 Hz".to_string());
+                }
+                let valid_bauds = [9600, 19200, 38400, 57600, 115200, 230400, 460800, 921600];
+                if !valid_bauds.contains(&maijie_l7_config.uart_baud) {
+                    error("cores.maijie_l7.uart_baud", "Maijie L7 core UART baud rate must be a standard value".to_string());
+                }
+            }
+        }
+
+        if total_devices > arch_limits.resource_limits.max_total_devices {
+            error("cores", format!(
+                "Enabled cores request {} devices in total, more than the limit of {}",
+                total_devices, arch_limits.resource_limits.max_total_devices
+            ));
+        }
+
+        // 验证矿池配置
+        if self.pools.pools.is_empty() {
+            error("pools.pools", "At least one pool must be configured".to_string());
+        } else if !self.pools.pools.iter().any(|pool| pool.enabled) {
+            error("pools.pools", "All configured pools are disabled; enable at least one pool".to_string());
+        }
+        if self.pools.extranonce2_partitions > MAX_EXTRANONCE2_PARTITIONS {
+            error("pools.extranonce2_partitions", format!(
+                "extranonce2_partitions must be at most {}", MAX_EXTRANONCE2_PARTITIONS
+            ));
+        }
+        for pool in &self.pools.pools {
+            if let Some(quirks) = &pool.quirks {
+                if quirks.extranonce2_size.is_some_and(|size| !(1..=16).contains(&size)) {
+                    error("pools.pools", format!("Pool {} quirks.extranonce2_size must be between 1 and 16", pool.url));
+                }
+                // 1 字节的 extranonce2 只有 256 个值，放不下更多的设备子区间
+                if let Some(size @ 1..=7) = quirks.extranonce2_size {
+                    let values = 1u64 << (8 * size);
+                    if self.pools.extranonce2_partitions as u64 > values {
+                        error("pools.extranonce2_partitions", format!(
+                            "extranonce2_partitions {} exceeds the {} values of pool {} quirks.extranonce2_size",
+                            self.pools.extranonce2_partitions, values, pool.url
+                        ));
+                    }
+                }
+                if quirks.user_agent.as_ref().is_some_and(|agent| agent.trim().is_empty()) {
+                    error("pools.pools", format!("Pool {} quirks.user_agent must not be empty", pool.url));
+                }
+            }
+            if pool.fee.is_some_and(|fee| !(0.0..100.0).contains(&fee)) {
+                error("pools.pools", format!("Pool {} fee must be in [0, 100)", pool.url));
+            }
+            if pool.protocol != PoolProtocol::Getwork {
+                continue;
+            }
+            if !PoolProtocol::is_http_url(&pool.url) {
+                error("pools.pools", format!("Getwork pool {} must use an http:// or https:// URL", pool.url));
+            }
+            if pool.proxy.is_some() {
+                error("pools.pools", format!("Getwork pool {} does not support a SOCKS5 proxy", pool.url));
+            }
+        }
+        let profit_switch = &self.pools.profit_switch;
+        if profit_switch.enabled {
+            if profit_switch.source_url.is_none() && self.pools.pools.iter().all(|pool| pool.fee.is_none()) {
+                error("pools.profit_switch", "Profit switching needs a source_url or a fee on at least one pool".to_string());
+            }
+            if profit_switch.source_url.as_deref().is_some_and(|url| !PoolProtocol::is_http_url(url)) {
+                error("pools.profit_switch.source_url", "Profitability source must be an http:// or https:// URL".to_string());
+            }
+            if profit_switch.interval_secs == 0 {
+                error("pools.profit_switch.interval_secs", "Profit switching interval_secs must be greater than 0".to_string());
+            }
+            if !profit_switch.threshold_percent.is_finite() || profit_switch.threshold_percent < 0.0 {
+                error("pools.profit_switch.threshold_percent", "Profit switching threshold_percent must not be negative".to_string());
+            }
+        }
+        if self.pools.address_check == AddressCheck::Fail {
+            for (_, message) in self.wallet_address_issues() {
+                error("pools.pools", message);
+            }
+        }
+
+        // 验证设备配置
+        if self.devices.chains.is_empty() {
+            error("devices.chains", "At least one chain must be configured".to_string());
+        }
+
+        // 验证频率和电压范围
+        for chain in &self.devices.chains {
+            if !FREQUENCY_RANGE.contains(&chain.frequency) {
+                error("devices.chains", format!("Chain {} frequency {} is out of range ({}-{})",
+                    chain.id, chain.frequency, FREQUENCY_RANGE.start(), FREQUENCY_RANGE.end()));
+            }
+
+            if !VOLTAGE_RANGE.contains(&chain.voltage) {
+                error("devices.chains", format!("Chain {} voltage {} is out of range ({}-{})",
+                    chain.id, chain.voltage, VOLTAGE_RANGE.start(), VOLTAGE_RANGE.end()));
+            }
+        }
+
+        // 验证风扇控制配置
+        if let Err(e) = self.devices.fan_control.validate() {
+            error("devices.fan_control", e.to_string());
+        }
+        if let Err(e) = self.devices.ramp_up.validate() {
+            error("devices.ramp_up", e.to_string());
+        }
+        if let Err(e) = self.devices.thermal.validate() {
+            error("devices.thermal", e.to_string());
+        }
+        if let Err(e) = self.devices.auto_tune.validate() {
+            error("devices.auto_tune", e.to_string());
+        }
+        if let Err(e) = self.devices.chip_health.validate() {
+            error("devices.chip_health", e.to_string());
+        }
+        if let Err(e) = self.devices.power.validate() {
+            error("devices.power", e.to_string());
+        }
+        if self.devices.power.enabled && !self.devices.power.rails.is_empty()
+            && !self.devices.boards.iter().any(|board| board.gpio_chip.is_some())
+        {
+            error("devices.power", "devices.power.rails needs a [[devices.boards]] entry with a gpio_chip".to_string());
+        }
+        if !self.devices.boards.is_empty() && !cfg!(all(feature = "hardware", target_os = "linux")) {
+            error("devices.boards", "devices.boards needs a Linux build with --features=hardware".to_string());
+        }
+        let mut board_chains = std::collections::HashSet::new();
+        for board in &self.devices.boards {
+            if let Err(e) = board.validate() {
+                error("devices.boards", e.to_string());
+            }
+            for chain in &board.chains {
+                if !board_chains.insert(*chain) {
+                    error("devices.boards", format!("Chain {} is assigned to more than one board", chain));
+                }
+            }
+        }
+
+        // 验证API配置
+        if self.api.port < 1024 {
+            error("api.port", format!("API port {} is out of range (1024-65535)", self.api.port));
+        }
+
+        match (&self.api.tls_cert, &self.api.tls_key) {
+            (Some(_), None) => error("api.tls_key", "api.tls_cert is set but api.tls_key is missing".to_string()),
+            (None, Some(_)) => error("api.tls_cert", "api.tls_key is set but api.tls_cert is missing".to_string()),
+            _ => {}
+        }
+        for (key, e) in self.api.tls.validate() {
+            error(key, e);
+        }
+
+        // 验证运行时配置
+        for (key, e) in self.runtime.validate() {
+            error(key, e);
+        }
+
+        // 验证告警推送、指标历史、内存预算和零算力恢复配置
+        if self.monitoring.enabled {
+            for (key, e) in self.monitoring.push.validate() {
+                error(key, e);
+            }
+            for (key, e) in self.monitoring.history.validate() {
+                error(key, e);
+            }
+            for (key, e) in self.monitoring.memory.validate() {
+                error(key, e);
+            }
+            for (key, e) in self.monitoring.recovery.validate() {
+                error(key, e);
+            }
+        }
+
+        // 验证Web界面登录配置
+        if self.web.enabled {
+            if let Err(e) = self.web.auth.validate() {
+                error("web.auth.password_hash", e);
+            }
+            for (key, e) in self.web.fleet.validate() {
+                error(key, e);
+            }
+        }
+
+        // 同时启用的服务不能监听同一端口
+        let mut listeners: Vec<(&str, &str, &str, u16)> = Vec::new();
+        if self.api.enabled {
+            listeners.push(("api", "api.port", self.api.bind_address.as_str(), self.api.port));
+        }
+        if self.monitoring.enabled {
+            if let Some(port) = self.monitoring.web_port {
+                listeners.push(("monitoring", "monitoring.web_port", "0.0.0.0", port));
+            }
+        }
+        let is_wildcard = |addr: &str| matches!(addr, "0.0.0.0" | "::" | "[::]");
+        for (i, (name_a, _, addr_a, port_a)) in listeners.iter().enumerate() {
+            for (name_b, key_b, addr_b, port_b) in &listeners[i + 1..] {
+                if port_a == port_b && (addr_a == addr_b || is_wildcard(addr_a) || is_wildcard(addr_b)) {
+                    error(key_b, format!(
+                        "Port conflict: {} and {} both listen on port {}; give them different ports",
+                        name_a, name_b, port_a
+                    ));
+                }
+            }
+        }
+
+        // 验证监控告警阈值
+        if self.monitoring.enabled {
+            let thresholds = &self.monitoring.alert_thresholds;
+            if thresholds.temperature_critical > 0.0 && thresholds.temperature_warning >= thresholds.temperature_critical {
+                error("monitoring.alert_thresholds.temperature_warning", format!(
+                    "Alert temperature_warning ({}) must be lower than temperature_critical ({})",
+                    thresholds.temperature_warning, thresholds.temperature_critical
+                ));
+            }
+        }
+
+        // 验证日志配置
+        if crate::logging::filter::parse_level(&self.general.log_level).is_none() {
+            error("general.log_level", format!(
+                "Unknown log level '{}' (expected trace, debug, info, warn, error or off)", self.general.log_level
+            ));
+        }
+        if let Err(e) = crate::logging::filter::parse_targets(&self.general.log_targets) {
+            error("general.log_targets", e);
+        }
+        for rule in &self.general.log_sampling {
+            if let Err(e) = rule.validate() {
+                error("general.log_sampling", e);
+            }
+        }
+        if self.general.result_collection_max_interval_ms != 0
+            && self.general.result_collection_max_interval_ms < self.general.result_collection_interval_ms
+        {
+            error("general.result_collection_max_interval_ms", format!(
+                "result_collection_max_interval_ms ({}) must not be less than result_collection_interval_ms ({})",
+                self.general.result_collection_max_interval_ms, self.general.result_collection_interval_ms
+            ));
+        }
+        if self.general.max_work_queue_size == 0 {
+            error("general.max_work_queue_size", "max_work_queue_size must be greater than 0".to_string());
+        }
+        if self.general.max_result_queue_size == 0 {
+            error("general.max_result_queue_size", "max_result_queue_size must be greater than 0".to_string());
+        }
+        if let Some(logging) = &self.logging {
+            match logging.rotation.to_ascii_lowercase().as_str() {
+                "never" | "hourly" | "daily" => {}
+                "size" => {
+                    if crate::logging::rotation::parse_size(&logging.max_size).map_or(true, |size| size == 0) {
+                        error("logging.max_size", format!(
+                            "Log max_size '{}' is not a valid size (e.g. \"10MB\")", logging.max_size
+                        ));
+                    }
+                }
+                other => error("logging.rotation", format!(
+                    "Unknown log rotation '{}' (expected never, hourly, daily or size)", other
+                )),
+            }
+        }
+
+        // 验证算力计量器配置
+        if self.hashmeter.enabled && self.hashmeter.log_interval == 0 {
+            error("hashmeter.log_interval", "Hashmeter log_interval must be greater than 0 seconds".to_string());
+        }
+
+        // 验证远程日志推送配置
+        if self.log_shipping.enabled {
+            let shipping = &self.log_shipping;
+            match url::Url::parse(&shipping.url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                _ => error("log_shipping.url", format!("Log shipping url '{}' must be an http(s) URL", redact::redact_url(&shipping.url))),
+            }
+            if shipping.min_level.parse::<tracing::Level>().is_err() {
+                error("log_shipping.min_level", format!(
+                    "Unknown log shipping min_level '{}' (expected trace, debug, info, warn or error)", shipping.min_level
+                ));
+            }
+            if shipping.batch_size == 0 {
+                error("log_shipping.batch_size", "Log shipping batch_size must be greater than 0".to_string());
+            }
+            if shipping.flush_interval_ms == 0 {
+                error("log_shipping.flush_interval_ms", "Log shipping flush_interval_ms must be greater than 0".to_string());
+            }
+            if crate::logging::rotation::parse_size(&shipping.max_spill_size).is_none() {
+                error("log_shipping.max_spill_size", format!(
+                    "Log shipping max_spill_size '{}' is not a valid size (e.g. \"50MB\")", shipping.max_spill_size
+                ));
+            }
+        }
+
+        // 验证崩溃报告配置
+        if !self.crash_report.upload_url.is_empty() {
+            match url::Url::parse(&self.crash_report.upload_url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                _ => error("crash_report.upload_url", format!(
+                    "Crash report upload_url '{}' must be an http(s) URL", redact::redact_url(&self.crash_report.upload_url)
+                )),
+            }
+        }
+
+        // 验证会话报告配置
+        if self.session_report.enabled {
+            if let Err(e) = self.session_report.rollover() {
+                error("session_report.rollover_time", e);
+            }
+            if !self.session_report.webhook_url.is_empty() {
+                match url::Url::parse(&self.session_report.webhook_url) {
+                    Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                    _ => error("session_report.webhook_url", format!(
+                        "Session report webhook_url '{}' must be an http(s) URL", redact::redact_url(&self.session_report.webhook_url)
+                    )),
+                }
+            }
+        }
+
+        // 验证统计文件配置
+        for (key, e) in self.stats_file.validate() {
+            error(key, e);
+        }
+
+        // 验证安全配置
+        for (key, e) in self.security.validate() {
+            error(key, e);
+        }
+
+        // 验证份额审计日志配置
+        if self.share_audit.enabled {
+            if self.share_audit.file.trim().is_empty() {
+                error("share_audit.file", "Share audit file path cannot be empty".to_string());
+            }
+            if crate::logging::rotation::parse_size(&self.share_audit.max_size).map_or(true, |size| size == 0) {
+                error("share_audit.max_size", format!(
+                    "Share audit max_size '{}' is not a valid size (e.g. \"10MB\")", self.share_audit.max_size
+                ));
+            }
+        }
+
+        // 验证份额验证抽样比例
+        let sample_rate = self.share_verify.sample_rate;
+        if !(sample_rate > 0.0 && sample_rate <= 1.0) {
+            error("share_verify.sample_rate", format!("Share verification sample_rate must be in (0, 1], got {}", sample_rate));
+        }
+
+        errors
+    }
+
+    /// 检查配置是否有效
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+}
+
+/// 配置验证错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// 出错的配置键（如 `api.port`）
+    pub key: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// 环境变量覆盖前缀
+pub const ENV_PREFIX: &str = "CGMINER_";
+
+/// 命令行客户端的 API 地址和令牌
+pub const CLIENT_URL_ENV: &str = "CGMINER_CLIENT_URL";
+pub const CLIENT_TOKEN_ENV: &str = "CGMINER_CLIENT_TOKEN";
+
+/// 备份口令，未设置时从标准输入读取
+pub const BACKUP_PASSPHRASE_ENV: &str = "CGMINER_BACKUP_PASSPHRASE";
+
+/// 带 `CGMINER_` 前缀但不是配置覆盖的环境变量
+const ENV_NON_OVERRIDES: [&str; 3] = [CLIENT_URL_ENV, CLIENT_TOKEN_ENV, BACKUP_PASSPHRASE_ENV];
+
+/// 环境变量路径段
+#[derive(Debug, Clone, PartialEq)]
+enum EnvPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// 将 `CGMINER_*` 环境变量应用到解析后的配置树
+///
+/// 变量名去掉前缀后按 `_` 切分，逐级匹配配置文件和默认配置中已有的键
+/// （如 `CGMINER_API_PORT` -> `api.port`，`CGMINER_GENERAL_LOG_LEVEL` -> `general.log_level`）。
+/// 数字段表示数组下标，`CGMINER_POOLS_0_URL` 是 `pools.pools[0].url` 的简写。
+/// 解析出的键不是配置中的键时 (变量名拼错) 记录警告并忽略该变量。
+pub fn apply_env_overrides<I>(value: &mut toml::Value, vars: I) -> Result<()>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let schema = toml::Value::try_from(Config::default()).ok();
+
+    let mut overrides: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX) && name.len() > ENV_PREFIX.len())
+        .filter(|(name, _)| !ENV_NON_OVERRIDES.contains(&name.as_str()))
+        .collect();
+    overrides.sort();
+
+    for (name, raw) in overrides {
+        let segments: Vec<String> = name[ENV_PREFIX.len()..]
+            .split('_')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_ascii_lowercase())
+            .collect();
+        let path = resolve_env_path(&segments, None, Some(&*value), schema.as_ref());
+        if path.is_empty() {
+            continue;
+        }
+        if !is_known_env_path(value, &path, &raw, schema.as_ref()) {
+            tracing::warn!("Ignoring environment override {}: {} is not a configuration key", name, format_env_path(&path));
+            continue;
+        }
+        let template = lookup_env_path(schema.as_ref(), &path).cloned();
+        set_env_path(value, &path, &raw, template.as_ref())
+            .with_context(|| format!("Invalid environment override {}", name))?;
+        tracing::info!("Applied environment override {}", name);
+    }
+
+    Ok(())
+}
+
+/// 根据已有配置键解析路径，未知键将剩余段用 `_` 连接作为键名
+fn resolve_env_path(
+    segments: &[String],
+    parent_key: Option<&str>,
+    file: Option<&toml::Value>,
+    schema: Option<&toml::Value>,
+) -> Vec<EnvPathSegment> {
+    if segments.is_empty() {
+        return Vec::new();
+    }
+
+    let is_array = matches!(file, Some(toml::Value::Array(_))) || matches!(schema, Some(toml::Value::Array(_)));
+    if is_array {
+        if let Ok(index) = segments[0].parse::<usize>() {
+            let file_child = file.and_then(|v| v.get(index));
+            let schema_child = schema.and_then(|v| v.get(index).or_else(|| v.get(0)));
+            let mut path = vec![EnvPathSegment::Index(index)];
+            path.extend(resolve_env_path(&segments[1..], None, file_child, schema_child));
+            return path;
+        }
+    }
+
+    let is_table = matches!(file, Some(toml::Value::Table(_))) || matches!(schema, Some(toml::Value::Table(_)));
+    if is_table {
+        // `pools_0_url` 中数字直接跟在表名后时，指向同名数组 `pools.pools[0]`
+        if segments[0].parse::<usize>().is_ok() {
+            if let Some(parent) = parent_key {
+                let has_array = |v: Option<&toml::Value>| matches!(v.and_then(|v| v.get(parent)), Some(toml::Value::Array(_)));
+                if has_array(file) || has_array(schema) {
+                    let mut path = vec![EnvPathSegment::Key(parent.to_string())];
+                    path.extend(resolve_env_path(
+                        segments,
+                        None,
+                        file.and_then(|v| v.get(parent)),
+                        schema.and_then(|v| v.get(parent)),
+                    ));
+                    return path;
+                }
+            }
+        }
+
+        for n in (1..=segments.len()).rev() {
+            let key = segments[..n].join("_");
+            let file_child = file.and_then(|v| v.get(&key));
+            let schema_child = schema.and_then(|v| v.get(&key));
+            if file_child.is_some() || schema_child.is_some() {
+                let mut path = vec![EnvPathSegment::Key(key.clone())];
+                path.extend(resolve_env_path(&segments[n..], Some(&key), file_child, schema_child));
+                return path;
+            }
+        }
+    }
+
+    vec![EnvPathSegment::Key(segments.join("_"))]
+}
+
+/// 路径是否是配置中的键：存在于配置文件或默认配置中，或者是默认未设置的可选键
+/// (写入后反序列化为 `Config` 再序列化回来时仍然存在)。值的类型不对时交给加载时的错误处理
+fn is_known_env_path(value: &toml::Value, path: &[EnvPathSegment], raw: &str, schema: Option<&toml::Value>) -> bool {
+    if lookup_env_path(Some(value), path).is_some() || lookup_env_path(schema, path).is_some() {
+        return true;
+    }
+    let mut candidate = value.clone();
+    if set_env_path(&mut candidate, path, raw, None).is_err() {
+        return false;
+    }
+    let Ok(config) = candidate.try_into::<Config>() else {
+        return true;
+    };
+    toml::Value::try_from(config).map_or(true, |round_trip| lookup_env_path(Some(&round_trip), path).is_some())
+}
+
+/// 路径的配置键写法，如 `pools.pools[0].url`
+fn format_env_path(path: &[EnvPathSegment]) -> String {
+    let mut key = String::new();
+    for segment in path {
+        match segment {
+            EnvPathSegment::Key(name) if key.is_empty() => key.push_str(name),
+            EnvPathSegment::Key(name) => {
+                key.push('.');
+                key.push_str(name);
+            }
+            EnvPathSegment::Index(index) => key.push_str(&format!("[{}]", index)),
+        }
+    }
+    key
+}
+
+fn lookup_env_path<'a>(value: Option<&'a toml::Value>, path: &[EnvPathSegment]) -> Option<&'a toml::Value> {
+    let mut current = value?;
+    for segment in path {
+        current = match segment {
+            EnvPathSegment::Key(key) => current.get(key.as_str())?,
+            EnvPathSegment::Index(index) => current.get(*index).or_else(|| current.get(0))?,
+        };
+    }
+    Some(current)
+}
+
+fn set_env_path(value: &mut toml::Value, path: &[EnvPathSegment], raw: &str, template: Option<&toml::Value>) -> Result<()> {
+    let (last, parents) = path.split_last().expect("path is not empty");
+
+    let mut current = value;
+    for segment in parents {
+        current = match segment {
+            EnvPathSegment::Key(key) => {
+                let table = current.as_table_mut()
+                    .ok_or_else(|| anyhow::anyhow!("'{}' is not a table", key))?;
+                table.entry(key.clone()).or_insert_with(|| toml::Value::Table(toml::map::Map::new()))
+            }
+            EnvPathSegment::Index(index) => {
+                let array = current.as_array_mut()
+                    .ok_or_else(|| anyhow::anyhow!("index {} used on a non-array value", index))?;
+                if *index == array.len() {
+                    array.push(toml::Value::Table(toml::map::Map::new()));
+                }
+                array.get_mut(*index)
+                    .ok_or_else(|| anyhow::anyhow!("index {} is out of range", index))?
+            }
+        };
+    }
+
+    let existing = match last {
+        EnvPathSegment::Key(key) => current.get(key.as_str()),
+        EnvPathSegment::Index(index) => current.get(*index),
+    };
+    let parsed = parse_env_value(raw, existing.or(template))?;
+
+    match last {
+        EnvPathSegment::Key(key) => {
+            current.as_table_mut()
+                .ok_or_else(|| anyhow::anyhow!("'{}' is not a table", key))?
+                .insert(key.clone(), parsed);
+        }
+        EnvPathSegment::Index(index) => {
+            let array = current.as_array_mut()
+                .ok_or_else(|| anyhow::anyhow!("index {} used on a non-array value", index))?;
+            if *index == array.len() {
+                array.push(parsed);
+            } else {
+                *array.get_mut(*index).ok_or_else(|| anyhow::anyhow!("index {} is out of range", index))? = parsed;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 按目标键已有的类型解析环境变量值，未知类型时自动推断
+fn parse_env_value(raw: &str, template: Option<&toml::Value>) -> Result<toml::Value> {
+    let value = match template {
+        Some(toml::Value::String(_)) => toml::Value::String(raw.to_string()),
+        Some(toml::Value::Integer(_)) => toml::Value::Integer(raw.trim().parse()
+            .with_context(|| format!("expected an integer, got '{}'", raw))?),
+        Some(toml::Value::Float(_)) => toml::Value::Float(raw.trim().parse()
+            .with_context(|| format!("expected a number, got '{}'", raw))?),
+        Some(toml::Value::Boolean(_)) => toml::Value::Boolean(parse_env_bool(raw)
+            .ok_or_else(|| anyhow::anyhow!("expected a boolean, got '{}'", raw))?),
+        Some(toml::Value::Array(items)) => {
+            let element = items.first();
+            let values = raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|item| parse_env_value(item, element))
+                .collect::<Result<Vec<_>>>()?;
+            toml::Value::Array(values)
+        }
+        _ => {
+            if let Some(b) = parse_env_bool(raw) {
+                toml::Value::Boolean(b)
+            } else if let Ok(i) = raw.parse::<i64>() {
+                toml::Value::Integer(i)
+            } else if let Ok(f) = raw.parse::<f64>() {
+                toml::Value::Float(f)
+            } else {
+                toml::Value::String(raw.to_string())
+            }
+        }
+    };
+    Ok(value)
+}
+
+fn parse_env_bool(raw: &str) -> Option<bool> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        apply_env_overrides(&mut value, vars(&[
+            ("CGMINER_API_PORT", "5000"),
+            ("CGMINER_GENERAL_LOG_LEVEL", "debug"),
+            ("CGMINER_POOLS_0_URL", "stratum+tcp://env.pool:3333"),
+            ("CGMINER_POOLS_0_PASSWORD", "123"),
+            ("CGMINER_DEVICES_CHAINS_1_FREQUENCY", "550"),
+            ("CGMINER_API_ALLOW_ORIGINS", "http://a, http://b"),
+            ("CGMINER_API_AUTH_TOKEN", "env-token"),
+            ("UNRELATED", "ignored"),
+        ])).unwrap();
+
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.api.port, 5000);
+        assert_eq!(config.general.log_level, "debug");
+        assert_eq!(config.pools.pools[0].url, "stratum+tcp://env.pool:3333");
+        assert_eq!(config.pools.pools[0].password, "123");
+        assert_eq!(config.devices.chains[1].frequency, 550);
+        assert_eq!(config.api.allow_origins, vec!["http://a", "http://b"]);
+        assert_eq!(config.api.auth_token.as_deref(), Some("env-token"));
+    }
+
+    #[test]
+    fn test_env_unknown_keys_ignored() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        let before = value.clone();
+        apply_env_overrides(&mut value, vars(&[
+            ("CGMINER_API_PROT", "5000"),
+            ("CGMINER_GENERAL_LOG_LEVLE", "debug"),
+            ("CGMINER_NO_SUCH_SECTION", "1"),
+        ])).unwrap();
+        assert_eq!(value, before);
+        assert_eq!(format_env_path(&[
+            EnvPathSegment::Key("pools".to_string()),
+            EnvPathSegment::Key("pools".to_string()),
+            EnvPathSegment::Index(0),
+            EnvPathSegment::Key("url".to_string()),
+        ]), "pools.pools[0].url");
+    }
+
+    #[test]
+    fn test_cli_pools_take_priority() {
+        let args = Args::parse_from([
+            "cgminer-rs",
+            "-o", "stratum+tcp://a.pool:3333", "-u", "alice.1", "-p", "x",
+            "-o", "stratum+tcp://b.pool:3333",
+        ]);
+        let mut config = Config::default();
+        let file_pools = config.pools.pools.len();
+        config.apply_cli_args(&args).unwrap();
+
+        assert_eq!(config.pools.pools.len(), file_pools + 2);
+        assert_eq!(config.pools.pools[0].url, "stratum+tcp://a.pool:3333");
+        assert_eq!(config.pools.pools[1].username, "alice.1");
+        assert_eq!(config.pools.pools[1].priority, 1);
+        assert!(config.pools.pools[2..].iter().all(|p| p.priority >= 2));
+        assert_eq!(config.pools.pools[0].protocol, PoolProtocol::Stratum);
+    }
+
+    #[test]
+    fn test_getwork_pool_validation() {
+        let mut config = Config::default();
+        let pool = &mut config.pools.pools[0];
+        pool.protocol = PoolProtocol::Getwork;
+        pool.url = "stratum+tcp://a.pool:3333".to_string();
+        pool.proxy = Some(ProxyConfig {
+            proxy_type: "socks5".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1080,
+            username: None,
+            password: None,
+            skip_verify: None,
+            server_name: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+        });
+        let errors = config.validation_errors();
+        assert_eq!(errors.iter().filter(|e| e.key == "pools.pools").count(), 2);
+
+        let pool = &mut config.pools.pools[0];
+        pool.url = "http://127.0.0.1:8332".to_string();
+        pool.proxy = None;
+        assert!(config.validation_errors().iter().all(|e| e.key != "pools.pools"));
+        assert_eq!(PoolProtocol::from_url(&pool.url), PoolProtocol::Getwork);
+    }
+
+    #[test]
+    fn test_pool_quirks_config() {
+        let pool: PoolInfo = toml::from_str(r#"
+            url = "stratum+tcp://a.pool:3333"
+            user = "worker"
+            password = "x"
+            priority = 0
+            enabled = true
+            quirks = { profile = "classic", extranonce2_size = 0 }
+        "#).unwrap();
+        assert_eq!(pool.quirks.as_ref().unwrap().profile, crate::pool::quirks::QuirkProfile::Classic);
+
+        let mut config = Config::default();
+        config.pools.pools[0] = pool;
+        let errors = config.validation_errors();
+        assert!(errors.iter().any(|e| e.key == "pools.pools" && e.message.contains("extranonce2_size")));
+
+        // 1 字节的 extranonce2 最多 256 个子区间
+        config.pools.pools[0].quirks.as_mut().unwrap().extranonce2_size = Some(1);
+        config.pools.extranonce2_partitions = 256;
+        assert!(config.validation_errors().iter().all(|e| e.key != "pools.extranonce2_partitions"));
+        config.pools.extranonce2_partitions = 512;
+        assert!(config.validation_errors().iter().any(|e| e.key == "pools.extranonce2_partitions"));
+    }
+
+    #[test]
+    fn test_gpu_backend_checked_only_with_gpu_core() {
+        let mut config = Config::default();
+        let gpu = config.cores.gpu_btc.as_mut().unwrap();
+        gpu.enabled = true;
+        gpu.backend = GpuBackend::Metal;
+        let rejected = config.validation_errors().iter().any(|e| e.key == "cores.gpu_btc.backend");
+        assert_eq!(rejected, cfg!(feature = "gpu-btc") && !cfg!(target_os = "macos"));
+    }
+
+    #[test]
+    fn test_gpu_cuda_settings() {
+        // 省略的键取 Default 中的值，而不是类型的零值
+        let gpu: GpuBtcCoreConfig = toml::from_str("enabled = true\nbackend = \"opencl\"").unwrap();
+        assert_eq!(gpu.cuda_streams, 2);
+        assert!(gpu.cuda_pinned_memory);
+        assert_eq!(gpu.device_count, 1);
+
+        // CUDA 参数只在后端为 cuda 时传给核心，也只在此时检查
+        let params = gpu.core_params();
+        assert_eq!(params["backend"], "opencl");
+        assert!(!params.contains_key("streams"));
+        let mut config = Config::default();
+        config.cores.gpu_btc = Some(GpuBtcCoreConfig { cuda_streams: 0, ..gpu.clone() });
+        assert!(!config.validation_errors().iter().any(|e| e.key == "cores.gpu_btc.cuda_streams"));
+
+        let cuda = GpuBtcCoreConfig { backend: GpuBackend::Cuda, cuda_streams: 4, ..gpu };
+        let params = cuda.core_params();
+        assert_eq!(params["streams"], 4);
+        assert_eq!(params["pinned_memory"], true);
+        config.cores.gpu_btc = Some(GpuBtcCoreConfig { cuda_streams: 0, ..cuda });
+        assert!(config.validation_errors().iter().any(|e| e.key == "cores.gpu_btc.cuda_streams"));
+    }
+
+    #[test]
+    fn test_profit_switch_config() {
+        let mut config = Config::default();
+        config.pools.profit_switch.enabled = true;
+        let errors = config.validation_errors();
+        assert!(errors.iter().any(|e| e.key == "pools.profit_switch"));
+
+        config.pools.pools[0].fee = Some(1.5);
+        assert!(!config.validation_errors().iter().any(|e| e.key.starts_with("pools.profit_switch")));
+
+        config.pools.profit_switch.source_url = Some("ftp://rates.example.com".to_string());
+        config.pools.pools[0].fee = Some(100.0);
+        let errors = config.validation_errors();
+        assert!(errors.iter().any(|e| e.key == "pools.profit_switch.source_url"));
+        assert!(errors.iter().any(|e| e.key == "pools.pools" && e.message.contains("fee")));
+    }
+
+    #[test]
+    fn test_session_report_config() {
+        let mut config = Config::default();
+        config.session_report.rollover_time = "7pm".to_string();
+        config.session_report.webhook_url = "hooks.example.com/report".to_string();
+        let errors = config.validation_errors();
+        assert!(errors.iter().any(|e| e.key == "session_report.rollover_time"));
+        assert!(errors.iter().any(|e| e.key == "session_report.webhook_url"));
+
+        config.session_report.rollover_time = String::new();
+        config.session_report.webhook_url = "https://hooks.example.com/report".to_string();
+        assert!(!config.validation_errors().iter().any(|e| e.key.starts_with("session_report")));
+    }
+
+    #[test]
+    fn test_env_override_type_error() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        let result = apply_env_overrides(&mut value, vars(&[("CGMINER_API_PORT", "not-a-port")]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_env_non_overrides_ignored() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        let before = value.clone();
+        apply_env_overrides(&mut value, vars(&[
+            ("CGMINER_CLIENT_URL", "http://10.0.3.17:4028"),
+            ("CGMINER_CLIENT_TOKEN", "secret"),
+            ("CGMINER_BACKUP_PASSPHRASE", "secret"),
+        ])).unwrap();
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn test_validation_errors_are_aggregated() {
+        let mut config = Config::default();
+        config.api.enabled = true;
+        config.monitoring.enabled = true;
+        config.monitoring.web_port = Some(config.api.port);
+        config.hashmeter.log_interval = 0;
+        for pool in &mut config.pools.pools {
+            pool.enabled = false;
+        }
+
+        let keys: Vec<String> = config.validation_errors().into_iter().map(|e| e.key).collect();
+        assert_eq!(keys, vec!["pools.pools", "monitoring.web_port", "hashmeter.log_interval"]);
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.starts_with("Configuration has 3 errors"));
+    }
+}
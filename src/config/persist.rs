@@ -0,0 +1,200 @@
+//! 将运行时修改的设置写回配置文件
+//!
+//! 只替换目标键所在行的值部分，保留其余内容、注释和格式；
+//! 文件中没有该键时追加到对应段末尾（段不存在时新建）。
+//! JSON / YAML 配置没有注释，直接解析后更新并重新序列化。
+
+use super::format::ConfigFormat;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// 单个键的更新：(段名, 键名, 新值)
+pub type KeyUpdate<'a> = (&'a str, &'a str, toml::Value);
+
+/// 在配置文本中更新键值
+pub fn update_keys(text: &str, updates: &[KeyUpdate<'_>]) -> String {
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+
+    for (section, key, value) in updates {
+        let rendered = value.to_string();
+        match find_key(&lines, section, key) {
+            Location::Key(index) => {
+                lines[index] = replace_value(&lines[index], &rendered);
+            }
+            Location::SectionEnd(index) => {
+                lines.insert(index, format!("{} = {}", key, rendered));
+            }
+            Location::Missing => {
+                if lines.last().map_or(false, |l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{}]", section));
+                lines.push(format!("{} = {}", key, rendered));
+            }
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// 更新配置文件中的键（先写临时文件再替换，避免写入中断损坏配置）
+pub fn save_keys(path: &str, updates: &[KeyUpdate<'_>]) -> Result<()> {
+    let text = if Path::new(path).exists() {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read config file: {}", path))?
+    } else {
+        String::new()
+    };
+
+    let updated = match ConfigFormat::from_path(Path::new(path)) {
+        ConfigFormat::Toml => {
+            let updated = update_keys(&text, updates);
+            toml::from_str::<toml::Value>(&updated).context("Updated config is not valid TOML")?;
+            updated
+        }
+        format => {
+            let mut value = if text.trim().is_empty() {
+                toml::Value::Table(toml::map::Map::new())
+            } else {
+                format.parse(&text)?
+            };
+            for (section, key, new_value) in updates {
+                let table = value.as_table_mut()
+                    .and_then(|root| {
+                        root.entry(section.to_string())
+                            .or_insert_with(|| toml::Value::Table(toml::map::Map::new()))
+                            .as_table_mut()
+                    })
+                    .ok_or_else(|| anyhow::anyhow!("'{}' is not a table", section))?;
+                table.insert(key.to_string(), new_value.clone());
+            }
+            format.serialize(&value)?
+        }
+    };
+
+    let tmp_path = format!("{}.tmp", path);
+    std::fs::write(&tmp_path, updated).with_context(|| format!("Failed to write config file: {}", tmp_path))?;
+    std::fs::rename(&tmp_path, path).with_context(|| format!("Failed to replace config file: {}", path))?;
+    Ok(())
+}
+
+enum Location {
+    /// 键所在行
+    Key(usize),
+    /// 段存在但没有该键，插入位置
+    SectionEnd(usize),
+    /// 段不存在
+    Missing,
+}
+
+fn find_key(lines: &[String], section: &str, key: &str) -> Location {
+    let mut in_section = false;
+    let mut section_end = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            if in_section {
+                break;
+            }
+            let name = trimmed.trim_start_matches('[').split(']').next().unwrap_or("").trim();
+            in_section = !trimmed.starts_with("[[") && name == section;
+            if in_section {
+                section_end = Some(index + 1);
+            }
+            continue;
+        }
+
+        if in_section {
+            if let Some(rest) = trimmed.strip_prefix(key) {
+                if rest.trim_start().starts_with('=') {
+                    return Location::Key(index);
+                }
+            }
+            // 插入到段内最后一个非空行之后
+            if !trimmed.is_empty() {
+                section_end = Some(index + 1);
+            }
+        }
+    }
+
+    match section_end {
+        Some(index) => Location::SectionEnd(index),
+        None => Location::Missing,
+    }
+}
+
+/// 替换 `key = value  # comment` 中的 value，保留缩进和行尾注释
+fn replace_value(line: &str, rendered: &str) -> String {
+    let eq = match line.find('=') {
+        Some(eq) => eq,
+        None => return line.to_string(),
+    };
+    let (head, rest) = line.split_at(eq + 1);
+    let value_part = rest.trim_start();
+    let leading = &rest[..rest.len() - value_part.len()];
+
+    let comment = find_comment(value_part).map(|pos| {
+        let value_end = value_part[..pos].trim_end().len();
+        &value_part[value_end..]
+    });
+
+    match comment {
+        Some(comment) => format!("{}{}{}{}", head, leading, rendered, comment),
+        None => format!("{}{}{}", head, leading, rendered),
+    }
+}
+
+/// 查找值之后的注释起始位置（忽略字符串中的 `#`）
+fn find_comment(value: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (pos, c) in value.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '#' => return Some(pos),
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_preserves_comments() {
+        let text = "# 通用配置\n[general]\n# 扫描时间间隔 (秒)\nscan_time = 30   # 默认值\nlog_level = \"info\"\n\n[api]\nport = 4028\n";
+        let updated = update_keys(text, &[
+            ("general", "scan_time", toml::Value::Integer(10)),
+            ("general", "result_collection_interval_ms", toml::Value::Integer(50)),
+            ("hashmeter", "log_interval", toml::Value::Integer(15)),
+        ]);
+
+        assert!(updated.contains("# 扫描时间间隔 (秒)\nscan_time = 10   # 默认值\n"));
+        assert!(updated.contains("log_level = \"info\"\nresult_collection_interval_ms = 50\n"));
+        assert!(updated.ends_with("[hashmeter]\nlog_interval = 15\n"));
+
+        let parsed: toml::Value = toml::from_str(&updated).unwrap();
+        assert_eq!(parsed["api"]["port"].as_integer(), Some(4028));
+    }
+
+    #[test]
+    fn test_hash_inside_string() {
+        let line = "password = \"a#b\" # secret";
+        assert_eq!(replace_value(line, "\"c\""), "password = \"c\" # secret");
+    }
+}
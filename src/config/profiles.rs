@@ -0,0 +1,202 @@
+//! 配置档案 (`[profiles.<name>]`)
+//!
+//! 一个配置文件可以为不同机器定义档案，档案中的 `cores` / `devices` / `pools` 段覆盖主配置。
+//! 通过 `--profile <name>` 选择，未指定时用 `hosts` 中的主机名模式匹配本机（支持 `*` / `?`）。
+
+use super::include::merge_values;
+use anyhow::Result;
+
+/// 档案段键名
+pub const PROFILES_KEY: &str = "profiles";
+
+/// 档案中用于主机名匹配的键
+const HOSTS_KEY: &str = "hosts";
+
+/// 档案可以覆盖的段
+const OVERRIDABLE_SECTIONS: &[&str] = &["cores", "devices", "pools"];
+
+/// 选择并应用档案，返回生效的档案名
+pub fn apply_profile(value: &mut toml::Value, requested: Option<&str>, hostname: Option<&str>) -> Result<Option<String>> {
+    let profiles = match value.as_table_mut().and_then(|t| t.remove(PROFILES_KEY)) {
+        Some(toml::Value::Table(profiles)) => profiles,
+        Some(_) => anyhow::bail!("'profiles' must be a table of named profiles"),
+        None => {
+            if let Some(name) = requested {
+                anyhow::bail!("Profile '{}' requested but the config defines no profiles", name);
+            }
+            return Ok(None);
+        }
+    };
+
+    let selected = match requested {
+        Some(name) => {
+            if !profiles.contains_key(name) {
+                let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+                anyhow::bail!("Unknown profile '{}' (available: {})", name, available.join(", "));
+            }
+            Some(name.to_string())
+        }
+        None => match hostname {
+            Some(host) => match_host(&profiles, host)?,
+            None => None,
+        },
+    };
+
+    let name = match selected {
+        Some(name) => name,
+        None => return Ok(None),
+    };
+
+    let profile = profiles.get(&name)
+        .and_then(toml::Value::as_table)
+        .ok_or_else(|| anyhow::anyhow!("Profile '{}' must be a table", name))?;
+
+    for (section, overlay) in profile {
+        if section == HOSTS_KEY {
+            continue;
+        }
+        if !OVERRIDABLE_SECTIONS.contains(&section.as_str()) {
+            anyhow::bail!(
+                "Profile '{}' overrides '{}', only {} can be overridden",
+                name, section, OVERRIDABLE_SECTIONS.join(", ")
+            );
+        }
+        if let Some(root) = value.as_table_mut() {
+            match root.get_mut(section) {
+                Some(base) => merge_values(base, overlay.clone()),
+                None => {
+                    root.insert(section.clone(), overlay.clone());
+                }
+            }
+        }
+    }
+
+    Ok(Some(name))
+}
+
+/// 按主机名选择档案，多个档案匹配时报错
+fn match_host(profiles: &toml::map::Map<String, toml::Value>, hostname: &str) -> Result<Option<String>> {
+    let matches: Vec<&String> = profiles
+        .iter()
+        .filter(|(_, profile)| {
+            profile.get(HOSTS_KEY)
+                .and_then(toml::Value::as_array)
+                .map_or(false, |hosts| {
+                    hosts.iter().filter_map(toml::Value::as_str).any(|pattern| glob_match(pattern, hostname))
+                })
+        })
+        .map(|(name, _)| name)
+        .collect();
+
+    match matches.as_slice() {
+        [] => Ok(None),
+        [name] => Ok(Some((*name).clone())),
+        names => anyhow::bail!(
+            "Host '{}' matches several profiles ({}); select one with --profile",
+            hostname,
+            names.iter().map(|n| n.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// 简单通配符匹配（不区分大小写），`*` 匹配任意字符串，`?` 匹配单个字符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_ascii_lowercase().chars().collect();
+    let text: Vec<char> = text.to_ascii_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// 本机主机名
+pub fn local_hostname() -> Option<String> {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: 缓冲区长度正确，gethostname 最多写入 buf.len() 字节
+        let ret = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+        if ret == 0 {
+            let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            if let Ok(name) = std::str::from_utf8(&buf[..len]) {
+                if !name.is_empty() {
+                    return Some(name.to_string());
+                }
+            }
+        }
+    }
+
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[devices]
+scan_interval = 5
+
+[cores.cpu_btc]
+enabled = true
+device_count = 4
+
+[profiles.dev]
+hosts = ["dev-*", "laptop"]
+[profiles.dev.cores.cpu_btc]
+device_count = 2
+
+[profiles.rack]
+hosts = ["rack-??"]
+[profiles.rack.cores.cpu_btc]
+enabled = false
+[profiles.rack.devices]
+scan_interval = 10
+"#;
+
+    #[test]
+    fn test_profile_selection() {
+        let mut value: toml::Value = toml::from_str(CONFIG).unwrap();
+        let selected = apply_profile(&mut value, None, Some("rack-07")).unwrap();
+        assert_eq!(selected.as_deref(), Some("rack"));
+        assert_eq!(value["cores"]["cpu_btc"]["enabled"].as_bool(), Some(false));
+        assert_eq!(value["cores"]["cpu_btc"]["device_count"].as_integer(), Some(4));
+        assert_eq!(value["devices"]["scan_interval"].as_integer(), Some(10));
+        assert!(value.get(PROFILES_KEY).is_none());
+
+        let mut value: toml::Value = toml::from_str(CONFIG).unwrap();
+        assert_eq!(apply_profile(&mut value, Some("dev"), Some("rack-07")).unwrap().as_deref(), Some("dev"));
+        assert_eq!(value["cores"]["cpu_btc"]["device_count"].as_integer(), Some(2));
+
+        let mut value: toml::Value = toml::from_str(CONFIG).unwrap();
+        assert_eq!(apply_profile(&mut value, None, Some("unknown-host")).unwrap(), None);
+        assert!(apply_profile(&mut toml::from_str(CONFIG).unwrap(), Some("missing"), None).is_err());
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("rack-*", "RACK-12"));
+        assert!(glob_match("rack-??", "rack-07"));
+        assert!(!glob_match("rack-??", "rack-107"));
+        assert!(glob_match("*", "anything"));
+        assert!(!glob_match("dev-*", "prod-1"));
+    }
+}
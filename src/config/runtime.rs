@@ -0,0 +1,179 @@
+//! Tokio 运行时配置 (`[runtime]`)
+//!
+//! 哈希计算在核心自己的线程中进行，应用层的异步任务 (矿池、API、监控) 负载很轻。默认的
+//! `#[tokio::main]` 按 CPU 数创建工作线程，在 2 核 ARM 控制板上与挖矿线程争抢 CPU。
+//! 运行时改为在 `main` 中按这里的配置手动构建；配置必须在运行时创建之前读取，
+//! 因此 [`RuntimeConfig::from_config_file`] 只解析 `[runtime]` 段，其余配置错误留给正常加载时报告。
+
+use super::{apply_env_overrides, include, profiles};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 自动选择时工作线程数的上限
+const AUTO_MAX_WORKER_THREADS: usize = 4;
+
+/// 工作线程数的上限
+pub const MAX_WORKER_THREADS: usize = 256;
+
+/// 运行时类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeFlavor {
+    /// 单核机器使用单线程运行时，否则使用多线程运行时
+    #[default]
+    Auto,
+    /// 多线程运行时
+    MultiThread,
+    /// 单线程运行时，所有异步任务在主线程上运行
+    CurrentThread,
+}
+
+/// Tokio 运行时配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// 运行时类型
+    pub flavor: RuntimeFlavor,
+    /// 多线程运行时的工作线程数，0 表示 CPU 数的一半 (1-4)
+    pub worker_threads: usize,
+    /// 阻塞任务线程池的上限 (串口 I/O、密码哈希、文件写入)
+    pub max_blocking_threads: usize,
+    /// 空闲的阻塞线程保留时间 (秒)
+    pub blocking_keep_alive_secs: u64,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            flavor: RuntimeFlavor::Auto,
+            worker_threads: 0,
+            max_blocking_threads: 64,
+            blocking_keep_alive_secs: 10,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// 在运行时创建之前读取配置文件中的 `[runtime]` 段 (含 include、profile 和环境变量覆盖)。
+    /// 文件不存在或无法解析时使用默认值。
+    pub fn from_config_file(path: &str, profile: Option<&str>) -> Self {
+        let load = || -> anyhow::Result<Self> {
+            let mut value = include::load_layered(Path::new(path))?;
+            profiles::apply_profile(&mut value, profile, profiles::local_hostname().as_deref())?;
+            apply_env_overrides(&mut value, std::env::vars())?;
+            Ok(match value.get("runtime") {
+                Some(runtime) => runtime.clone().try_into()?,
+                None => Self::default(),
+            })
+        };
+        load().unwrap_or_default()
+    }
+
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if self.worker_threads > MAX_WORKER_THREADS {
+            errors.push(("runtime.worker_threads", format!("worker_threads must be at most {}", MAX_WORKER_THREADS)));
+        }
+        if self.flavor == RuntimeFlavor::CurrentThread && self.worker_threads > 1 {
+            errors.push(("runtime.worker_threads", "worker_threads cannot be set with the current_thread runtime".to_string()));
+        }
+        if self.max_blocking_threads == 0 {
+            errors.push(("runtime.max_blocking_threads", "max_blocking_threads must be greater than 0".to_string()));
+        }
+        errors
+    }
+
+    /// 按 CPU 数解析出实际的运行时类型
+    pub fn resolved_flavor(&self, cpus: usize) -> RuntimeFlavor {
+        match self.flavor {
+            RuntimeFlavor::Auto if cpus <= 1 => RuntimeFlavor::CurrentThread,
+            RuntimeFlavor::Auto => RuntimeFlavor::MultiThread,
+            flavor => flavor,
+        }
+    }
+
+    /// 按 CPU 数解析出实际的工作线程数
+    pub fn resolved_worker_threads(&self, cpus: usize) -> usize {
+        if self.worker_threads > 0 {
+            self.worker_threads
+        } else {
+            (cpus / 2).clamp(1, AUTO_MAX_WORKER_THREADS)
+        }
+    }
+
+    /// 构建运行时，返回运行时和说明 (启动时记录到日志)
+    pub fn build(&self) -> std::io::Result<(tokio::runtime::Runtime, String)> {
+        let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        let mut builder = match self.resolved_flavor(cpus) {
+            RuntimeFlavor::CurrentThread => tokio::runtime::Builder::new_current_thread(),
+            _ => {
+                let mut builder = tokio::runtime::Builder::new_multi_thread();
+                builder.worker_threads(self.resolved_worker_threads(cpus));
+                builder
+            }
+        };
+        builder
+            .enable_all()
+            .thread_name("cgminer-rt")
+            .max_blocking_threads(self.max_blocking_threads.max(1))
+            .thread_keep_alive(std::time::Duration::from_secs(self.blocking_keep_alive_secs));
+
+        let description = match self.resolved_flavor(cpus) {
+            RuntimeFlavor::CurrentThread => format!("current_thread runtime ({} CPUs)", cpus),
+            _ => format!("multi_thread runtime, {} worker threads ({} CPUs)", self.resolved_worker_threads(cpus), cpus),
+        };
+        Ok((builder.build()?, description))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_sizing() {
+        let config = RuntimeConfig::default();
+        assert_eq!(config.resolved_flavor(1), RuntimeFlavor::CurrentThread);
+        assert_eq!(config.resolved_flavor(2), RuntimeFlavor::MultiThread);
+        assert_eq!(config.resolved_worker_threads(2), 1);
+        assert_eq!(config.resolved_worker_threads(4), 2);
+        assert_eq!(config.resolved_worker_threads(64), AUTO_MAX_WORKER_THREADS);
+
+        let config = RuntimeConfig { worker_threads: 6, flavor: RuntimeFlavor::MultiThread, ..Default::default() };
+        assert_eq!(config.resolved_flavor(1), RuntimeFlavor::MultiThread);
+        assert_eq!(config.resolved_worker_threads(2), 6);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RuntimeConfig::default().validate().is_empty());
+        let config = RuntimeConfig { flavor: RuntimeFlavor::CurrentThread, worker_threads: 2, max_blocking_threads: 0, ..Default::default() };
+        let keys: Vec<_> = config.validate().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["runtime.worker_threads", "runtime.max_blocking_threads"]);
+    }
+
+    #[test]
+    fn test_reads_runtime_section_only() {
+        let dir = std::env::temp_dir().join(format!("cgminer-runtime-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cgminer.toml");
+        // 其他段无效也不影响读取 [runtime]
+        std::fs::write(&path, "[general]\nscan_time = \"bad\"\n\n[runtime]\nflavor = \"current_thread\"\n").unwrap();
+        let config = RuntimeConfig::from_config_file(path.to_str().unwrap(), None);
+        assert_eq!(config.flavor, RuntimeFlavor::CurrentThread);
+
+        let missing = RuntimeConfig::from_config_file(dir.join("missing.toml").to_str().unwrap(), None);
+        assert_eq!(missing.flavor, RuntimeFlavor::Auto);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_build_runs_tasks() {
+        let config = RuntimeConfig { flavor: RuntimeFlavor::MultiThread, worker_threads: 2, ..Default::default() };
+        let (runtime, description) = config.build().unwrap();
+        assert!(description.contains("2 worker threads"));
+        assert_eq!(runtime.block_on(async { tokio::spawn(async { 7 }).await.unwrap() }), 7);
+    }
+}
@@ -0,0 +1,539 @@
+//! 默认配置文件生成 (`--generate-config`)
+//!
+//! 由 `Config::default()` 序列化得到所有键及其默认值，再逐行加上说明注释，
+//! 可选键和可选段以注释形式给出，保证生成的文件与代码中的默认值一致。
+
+use super::{Config, SimCoreConfig};
+use anyhow::{Context, Result};
+
+/// 文件头
+const HEADER: &str = "\
+# CGMiner-RS 配置文件
+# 由 `cgminer-rs --generate-config` 生成，包含所有支持的键及其默认值
+# 注释掉的键为可选项，取消注释即可启用
+# 任意键都可以通过 CGMINER_* 环境变量覆盖，详见 docs/CONFIGURATION.md
+";
+
+/// 默认不生成的可选段
+const OPTIONAL_SECTIONS: &str = "
+# =============================================================================
+# 可选段（默认不启用）
+# =============================================================================
+
+# 性能优化
+# [performance.hashrate_optimization]
+# base_hashrate = 2000000000.0        # 基础算力 (H/s)
+# hashrate_variance = 0.1             # 算力变化范围 (0.0-1.0)
+# frequency_hashrate_factor = 1.5     # 频率-算力因子
+# voltage_hashrate_factor = 1.2       # 电压-算力因子
+# temperature_impact_factor = 0.95    # 温度影响因子
+# adaptive_adjustment = true          # 自适应调整
+#
+# [performance.memory_optimization]
+# work_cache_size = 1000              # 工作缓存大小
+# result_cache_size = 10000           # 结果缓存大小
+# stats_retention_seconds = 3600      # 统计保留时间 (秒)
+# enable_memory_pool = true           # 启用内存池
+# preallocated_memory_mb = 64         # 预分配内存 (MB)
+#
+# [performance.thread_optimization]
+# worker_threads_per_device = 2       # 每设备工作线程数
+# thread_priority = \"Normal\"          # 线程优先级
+# thread_stack_size_kb = 2048         # 线程栈大小 (KB)
+# enable_thread_pool = true           # 启用线程池
+#
+# [performance.batch_optimization]
+# default_batch_size = 1000           # 默认批次大小
+# min_batch_size = 100                # 最小批次大小
+# max_batch_size = 10000              # 最大批次大小
+# adaptive_batch_size = true          # 自适应批次大小
+# batch_timeout_ms = 1000             # 批次超时 (毫秒)
+#
+# [performance.network_optimization]
+# connection_pool_size = 10           # 连接池大小
+# request_timeout_ms = 5000           # 请求超时 (毫秒)
+# max_concurrent_requests = 100       # 最大并发请求数
+# keepalive_interval = 30             # 保活间隔 (秒)
+
+# 系统资源限制
+# [limits]
+# max_memory_mb = 1024                # 最大内存使用 (MB)
+# max_cpu_percent = 80.0              # 最大CPU使用率 (%)
+# max_open_files = 1024               # 最大打开文件数
+# max_network_connections = 100       # 最大网络连接数
+
+# 日志
+# [logging]
+# level = \"info\"                      # 日志级别
+# file = \"./logs/cgminer.log\"         # 日志文件路径
+# max_size = \"100MB\"                  # 单个文件最大大小
+# max_files = 10                      # 最大文件数量
+# console = true                      # 控制台输出
+# json_format = false                 # JSON格式
+# rotation = \"daily\"                  # 日志轮转: never / hourly / daily / size
+# compress = false                    # 按大小轮转时 gzip 压缩旧文件
+";
+
+/// 段说明
+fn section_comment(section: &str) -> Option<&'static str> {
+    Some(match section {
+        "general" => "通用配置",
+        "cores" => "核心配置 - 启用的核心由编译特性决定 (--features=cpu-btc,gpu-btc,maijie-l7)",
+        "cores.cpu_btc" => "CPU-BTC 软算法核心 (cgminer-cpu-btc-core)",
+        "cores.cpu_btc.cpu_affinity" => "CPU绑定配置",
+        "cores.cpu_btc.load_target" => "CPU 负载目标 - 其他进程需要 CPU 时暂停部分挖矿线程，适合在工作站上挖矿",
+        "cores.cpu_btc.thermal_throttle" => "CPU 温度降频 - 封装温度超过上限时降低核心占空比，带回滞",
+        "cores.cpu_btc.worker_priority" => "工作线程优先级 - 共享主机上让挖矿线程让出 CPU",
+        "cores.gpu_btc" => "GPU-BTC 核心 (cgminer-gpu-btc-core)",
+        "cores.maijie_l7" => "Maijie L7 ASIC 核心 (cgminer-asic-maijie-l7-core)",
+        "cores.sim" => "模拟核心 - 需要 --features=sim，确定性的虚拟设备",
+        "cores.sim.asic" => "模拟 ASIC 硬件 - 在链协议层模拟芯片，每条链一个设备，可注入故障",
+        "cores.plugins" => "核心插件 - 需要 --features=dynamic-loading，从目录加载第三方核心动态库",
+        "cores.plugins.sandbox" => "插件核心子进程 (仅 unix) - 每个核心在单独的进程中运行，崩溃后自动重启",
+        "cores.plugins.params" => "按插件注册名传给核心的参数: [cores.plugins.params.acme-x1] chain_count = 3",
+        "cores.failover" => "核心健康检查 - 选中的核心连续不健康 (取不到统计或算力为 0) 时切换到下一优先级的核心",
+        "devices" => "设备配置",
+        "devices.chains" => "链配置，每条链一个 [[devices.chains]]",
+        "devices.fan_control" => "风扇控制策略",
+        "devices.fan_control.bands" => "温度区间：温度不高于 max_temperature 时使用 speed (0-100 或 \"auto\")",
+        "devices.ramp_up" => "分批启动，避免同时上电触发断路器",
+        "devices.thermal" => "温度保护：超过上限停止分发工作，降到 上限-回滞 以下恢复",
+        "devices.auto_tune" => "自动调优：对 auto_tune = true 的链扫描频率/电压，按板卡保存每瓦算力最高的档位",
+        "devices.chip_health" => "芯片健康：错误率超过阈值的芯片记入坏芯片表，重启后保留",
+        "devices.power" => "电源控制：按顺序打开各链电源，启动时分步升压，超过功率上限时降频",
+        "pools" => "矿池配置",
+        "pools.pools" => "矿池列表，每个矿池一个 [[pools.pools]]",
+        "pools.profit_switch" => "按收益切换 - 其它矿池的收益持续高出当前矿池一定比例时切换，两次切换之间有冷却时间",
+        "api" => "API服务 - 同时提供Web仪表板，端口和TLS共用",
+        "monitoring" => "监控系统",
+        "monitoring.alert_thresholds" => "告警阈值",
+        "monitoring.push" => "Web Push 告警通知 - 严重告警推送到订阅了的浏览器",
+        "monitoring.history" => "指标历史 - 降采样保存，供 /history 图表使用",
+        "monitoring.memory" => "内存预算 - 接近预算时告警并缩减内存中的历史，适用于小内存控制板",
+        "monitoring.recovery" => "零算力恢复 - 运行中总算力持续为 0 时依次重启核心、重启设备、退出进程",
+        "api.tls" => "HTTPS 选项 - 自签名证书、HSTS 和加密套件策略",
+        "web" => "Web管理界面 - 挂在API服务上，地址见 [api]",
+        "web.auth" => "Web登录认证 - 密码哈希用 --hash-password 生成",
+        "web.fleet" => "机群视图 - 轮询其他矿机的 API，在 /fleet 汇总显示",
+        "hashmeter" => "算力计量器",
+        "share_audit" => "份额审计日志 - 每个提交的份额追加一行 JSON，用于与矿池对账",
+        "share_verify" => "提交前的本地份额验证 - 重新计算 nonce 的哈希，不满足份额目标的计为设备硬件错误",
+        "log_shipping" => "远程日志推送 - 批量推送到 Grafana Loki 或 HTTP 批量接口",
+        "log_shipping.labels" => "附加标签，rig / version / job 标签自动添加",
+        "crash_report" => "崩溃报告 - panic 时写入回溯、最近日志、脱敏配置和设备状态",
+        "session_report" => "会话报告 - 停机时和每天的切换时刻写入份额、算力、温度汇总及按矿池/设备的明细",
+        "stats_file" => "农场代理统计文件 - 定期写入 Hive OS / minerstat 自定义矿机可读取的 JSON 统计",
+        "security" => "安全 - 本机加密密钥、API 密钥存储和配置防篡改",
+        "security.audit" => "控制操作审计 - 记录改变状态的 API/仪表板请求，GET /api/v1/audit 查询",
+        "security.lockout" => "暴力破解防护 - 按 IP 统计登录和 API 认证失败，超过次数后锁定",
+        "runtime" => "异步运行时 - 小型控制板上减少工作线程，避免与挖矿线程争抢 CPU",
+        _ => return None,
+    })
+}
+
+/// 键说明
+fn key_comment(path: &str) -> Option<&'static str> {
+    Some(match path {
+        "general.log_level" => "日志级别: trace, debug, info, warn, error",
+        "general.log_targets" => "按目标覆盖日志级别，如 \"pool=debug,device=warn\"，为空时全部使用 log_level",
+        "general.log_sampling" => "高频日志采样，如 [{ target = \"pool::stratum\", every = 100, summary_secs = 10 }]",
+        "general.log_language" => "日志语言: zh (中文), en (English)",
+        "general.pid_file" => "PID 文件路径",
+        "general.work_restart_timeout" => "工作重启超时时间 (秒)",
+        "general.scan_time" => "扫描时间间隔 (秒)",
+        "general.result_collection_interval_ms" => "结果收集间隔 (毫秒)",
+        "general.shutdown_timeout_secs" => "停机总超时 (秒)：依次停止分发、收完结果、等待在途份额、断开矿池、停止核心，超时后剩余任务直接中止",
+        "general.result_collection_max_interval_ms" => "空闲时结果轮询间隔的上限 (毫秒)，没有结果时轮询间隔逐步翻倍到此值",
+        "general.max_work_queue_size" => "工作队列容量 (有界，GET /api/v1/queues 查看深度和丢弃计数)",
+        "general.work_queue_overflow" => "工作队列满时: drop_oldest (丢弃最旧的工作), drop_newest, block (等待分发器)",
+        "general.max_result_queue_size" => "结果队列容量",
+        "general.result_queue_overflow" => "结果队列满时: block (等待，不丢份额), drop_oldest, drop_newest",
+
+        "cores.enabled_cores" => "启用的核心（由编译特性和核心优先级自动选择）",
+        "cores.default_core" => "默认核心",
+        "cores.priority" => "多个核心时的选择顺序 (核心类型，如 \"gpu-btc\")，为空时 ASIC > gpu-btc > cpu-btc；未列出的 ASIC 核心排在列出的之后",
+
+        "cores.cpu_btc.enabled" | "cores.gpu_btc.enabled" | "cores.maijie_l7.enabled" | "cores.sim.enabled" => "是否启用",
+        "cores.cpu_btc.device_count" => "设备 (挖矿线程) 数量，\"auto\" 按物理核心数和容器 CPU 配额确定",
+        "cores.cpu_btc.reserved_cores" => "device_count = \"auto\" 时为矿池连接、API 等保留的核心数",
+        "cores.gpu_btc.device_count" | "cores.sim.device_count" => "设备数量",
+        "cores.cpu_btc.min_hashrate" => "最小算力 (H/s)",
+        "cores.cpu_btc.max_hashrate" | "cores.gpu_btc.max_hashrate" => "最大算力 (H/s)",
+        "cores.cpu_btc.error_rate" => "错误率 (0.0-1.0)",
+        "cores.cpu_btc.batch_size" => "批处理大小",
+        "cores.cpu_btc.load_target.enabled" => "是否按负载目标调整挖矿线程数",
+        "cores.cpu_btc.load_target.target_percent" => "整机 CPU 使用率目标 (%)，挖矿只使用其他进程剩下的部分",
+        "cores.cpu_btc.load_target.interval_secs" => "采样 /proc/stat 的间隔 (秒)",
+        "cores.cpu_btc.load_target.min_threads" => "至少保持运行的挖矿线程数",
+        "cores.cpu_btc.worker_priority.nice" => "nice 值 (-20 到 19)，越大越让出 CPU；负值需要 root",
+        "cores.cpu_btc.worker_priority.sched_policy" => "Linux 调度策略: normal, batch, idle (只在 CPU 空闲时运行)",
+        "cores.cpu_btc.worker_priority.windows_priority_class" => "Windows 优先级类别: idle, below_normal, normal",
+        "cores.cpu_btc.thermal_throttle.enabled" => "是否启用温度降频",
+        "cores.cpu_btc.thermal_throttle.temperature_limit" => "封装温度上限 (°C)，达到后逐步降低占空比",
+        "cores.cpu_btc.thermal_throttle.hysteresis" => "恢复回滞 (°C)，温度低于 上限 - 回滞值 后逐步恢复",
+        "cores.cpu_btc.thermal_throttle.action" => "降频方式: sleep (批次间休眠), batch_size (缩小批次)",
+        "cores.cpu_btc.thermal_throttle.step_percent" => "每次调整的占空比步长 (%)",
+        "cores.cpu_btc.thermal_throttle.min_duty_percent" => "最低占空比 (%)",
+        "cores.cpu_btc.thermal_throttle.interval_secs" => "读取 hwmon 温度的间隔 (秒)",
+        "cores.cpu_btc.hash_backend" => "双 SHA256 内核: auto (按 CPU 特性选择), sha_ni, avx512, avx2, neon, generic",
+        "cores.cpu_btc.work_timeout_ms" | "cores.gpu_btc.work_timeout_ms" => "工作超时 (毫秒)",
+
+        "cores.cpu_btc.cpu_affinity.enabled" => "是否启用CPU绑定",
+        "cores.cpu_btc.cpu_affinity.strategy" => "绑定策略: round_robin, manual, performance_first, physical_only, intelligent, numa (多路服务器按 NUMA 节点放置)",
+        "cores.cpu_btc.cpu_affinity.avoid_hyperthreading" => "是否避免超线程",
+        "cores.cpu_btc.cpu_affinity.prefer_performance_cores" => "是否优先使用性能核心",
+
+        "cores.gpu_btc.work_size" => "工作组大小",
+        "cores.gpu_btc.backend" => "GPU 后端: auto (macOS 为 metal，启用 gpu-cuda 时为 cuda，其他为 opencl), metal, opencl (需要 --features=gpu-opencl), cuda (需要 --features=gpu-cuda)",
+        "cores.gpu_btc.cuda_streams" => "CUDA 每个 GPU 的流数量 (1-8)，传给 GPU 核心，由核心重叠上传、计算和下载",
+        "cores.gpu_btc.cuda_pinned_memory" => "CUDA 使用页锁定主机缓冲区，传给 GPU 核心",
+        "cores.gpu_btc.gpus" => "按 GPU 设置: [{ index = 0, work_size = 65536 }, { index = 1, enabled = false }]，为空时使用全部 GPU",
+
+        "cores.maijie_l7.chain_count" => "链数量 (1-16)",
+        "cores.maijie_l7.spi_speed" => "SPI 速率 (Hz)",
+        "cores.maijie_l7.uart_baud" => "UART 波特率",
+        "cores.maijie_l7.auto_detect" => "自动检测链",
+        "cores.maijie_l7.power_limit" => "功率上限 (W)",
+        "cores.maijie_l7.cooling_mode" => "散热模式",
+
+        "cores.plugins.enabled" => "是否加载核心插件",
+        "cores.plugins.dir" => "插件目录，加载其中的 .so/.dylib/.dll",
+        "cores.plugins.allow" => "只加载这些插件 (注册名)，为空时加载全部",
+        "cores.plugins.sandbox.enabled" => "是否在子进程中运行插件核心，关闭时在主进程内加载",
+        "cores.plugins.sandbox.max_restarts" => "窗口内允许的子进程重启次数",
+        "cores.plugins.sandbox.restart_window_secs" => "重启计数窗口 (秒)",
+        "cores.plugins.sandbox.startup_timeout_secs" => "等待子进程启动的时间 (秒)",
+        "cores.failover.enabled" => "是否启用核心故障转移",
+        "cores.failover.check_interval_secs" => "健康检查间隔 (秒)",
+        "cores.failover.unhealthy_checks" => "连续多少次不健康后切换",
+        "cores.failover.startup_grace_secs" => "核心启动后的宽限期 (秒)，期间不判定为不健康",
+        "cores.sim.hashrate" => "每个虚拟设备的算力 (H/s)",
+        "cores.sim.seed" => "随机种子，相同种子产生相同的结果序列",
+        "cores.sim.hardware_error_rate" => "硬件错误注入概率 (0.0-1.0)",
+        "cores.sim.ambient_temperature" => "环境温度 (°C)",
+        "cores.sim.load_temperature" => "满载稳态温度 (°C)",
+        "cores.sim.thermal_time_constant_secs" => "升温时间常数 (秒)",
+        "cores.sim.work_latency_ms" => "提交工作延迟 (毫秒)",
+        "cores.sim.result_latency_ms" => "获取结果延迟 (毫秒)",
+        "cores.sim.asic.enabled" => "是否启用模拟 ASIC 硬件 (不能与 [[devices.boards]] 同时配置)",
+        "cores.sim.asic.chain_count" => "模拟链数量",
+        "cores.sim.asic.chips_per_chain" => "每条链的芯片数",
+        "cores.sim.asic.cores_per_chip" => "每个芯片的哈希核心数，芯片算力 = 频率 × 核心数",
+        "cores.sim.asic.crc_error_rate" => "随机 CRC 错误概率 (0.0-1.0)",
+        "cores.sim.asic.reset_gpio_base" => "链 N 的复位 GPIO 为 reset_gpio_base + N",
+        "cores.sim.asic.faults" => "故障注入: [{ kind = \"chain_dropout\", chain_id = 1, after_secs = 600 }]，kind 可为 chain_dropout / crc_burst / temperature_spike",
+
+        "devices.auto_detect" => "自动检测设备",
+        "devices.scan_interval" => "设备扫描间隔 (秒)",
+        "devices.chains.id" => "链ID",
+        "devices.chains.enabled" => "是否启用",
+        "devices.chains.frequency" => "频率 (MHz, 100-1000)",
+        "devices.chains.voltage" => "电压 (mV, 600-1000)",
+        "devices.chains.auto_tune" => "自动调优",
+        "devices.chains.chip_count" => "芯片数量",
+        "devices.boards" => "各板的 SPI/UART/GPIO 设备，每块板一个 [[devices.boards]] (需要 --features=hardware，仅 Linux)",
+
+        "devices.fan_control.enabled" => "是否启用风扇策略",
+        "devices.fan_control.device_overrides" => "按设备覆盖的温度区间: [{ device_id = 0, bands = [...] }]",
+        "devices.fan_control.hysteresis" => "降档回滞 (°C)",
+        "devices.fan_control.bands.max_temperature" => "区间温度上限 (°C)",
+        "devices.fan_control.bands.speed" => "风扇转速 (0-100 或 \"auto\")",
+
+        "devices.ramp_up.enabled" => "是否启用分批启动",
+        "devices.ramp_up.batch_size" => "每批启动的设备数量",
+        "devices.ramp_up.batch_delay_secs" => "批次间隔 (秒)",
+
+        "devices.thermal.enabled" => "是否启用温度保护",
+        "devices.thermal.hysteresis" => "恢复回滞 (°C)",
+        "devices.auto_tune.enabled" => "是否启用自动调优",
+        "devices.auto_tune.frequency_min" => "扫描最低频率 (MHz)",
+        "devices.auto_tune.frequency_max" => "扫描最高频率 (MHz)",
+        "devices.auto_tune.frequency_step" => "频率步长 (MHz)",
+        "devices.auto_tune.voltage_min" => "扫描最低电压 (mV)",
+        "devices.auto_tune.voltage_max" => "扫描最高电压 (mV)",
+        "devices.auto_tune.voltage_step" => "电压步长 (mV)",
+        "devices.auto_tune.dwell_secs" => "每个工作点的测量时间 (秒)",
+        "devices.auto_tune.temperature_limit" => "调优温度上限 (°C)",
+        "devices.auto_tune.profile_file" => "已找到档位的保存文件，删除后重新扫描",
+        "devices.chip_health.enabled" => "是否维护坏芯片表",
+        "devices.chip_health.error_rate_threshold" => "判定为坏芯片的错误率 (%)",
+        "devices.chip_health.min_samples" => "判定前至少需要的样本数 (nonce + 错误)",
+        "devices.chip_health.map_file" => "坏芯片表文件，返修后删除对应板卡的条目",
+        "devices.power.enabled" => "是否启用电源控制和功率上限",
+        "devices.power.power_limit_watts" => "板卡功率上限 (W)，未设置时使用 cores.maijie_l7.power_limit",
+        "devices.power.rails" => "电源使能: [{ chain_id = 0, enable_gpio = 17, power_sensor = 2 }]，按顺序打开",
+        "devices.power.rail_delay_ms" => "相邻两路电源打开的间隔 (毫秒)",
+        "devices.power.soft_start_step_mv" => "软启动电压步长 (mV)",
+        "devices.power.soft_start_step_ms" => "软启动每步间隔 (毫秒)",
+        "devices.power.throttle_step_mhz" => "超过功率上限时每次降低的频率 (MHz)",
+        "devices.power.min_frequency" => "降频下限 (MHz)",
+        "devices.thermal.device_limits" => "按设备覆盖的温度上限: [{ device_id = 0, temperature_limit = 80.0 }]，未覆盖的设备使用 85°C",
+
+        "pools.strategy" => "矿池策略: Failover, RoundRobin, LoadBalance, Quota",
+        "pools.failover_timeout" => "故障转移超时 (秒)",
+        "pools.retry_interval" => "重试间隔 (秒)",
+        "pools.network" => "比特币网络: mainnet, testnet 或 regtest，决定难度 1 目标和钱包地址检查",
+        "pools.address_check" => "用户名像钱包地址但校验失败时: off 不检查, warn 告警, fail 拒绝启动",
+        "pools.extranonce2_partitions" => "extranonce2 空间均分的设备子区间数，设备 N 使用第 N % partitions 个 (1 表示所有设备共用一个计数器)",
+        "pools.submit_pipeline_depth" => "份额流水线提交: 一次发送的 mining.submit 数，0 为默认 16，1 为逐个等待响应",
+        "pools.pools.name" => "矿池名称",
+        "pools.pools.url" => "矿池地址 (stratum+tcp://host:port)",
+        "pools.pools.username" => "用户名/矿工名",
+        "pools.pools.password" => "密码",
+        "pools.pools.priority" => "优先级，数字越小越优先",
+        "pools.pools.enabled" => "是否启用",
+        "pools.pools.protocol" => "矿池协议: stratum 或 getwork (HTTP getwork + 长轮询，url 使用 http:// 或 https://)",
+        "pools.pools.algorithm" => "挖矿算法: sha256d (BTC/BCH) 或 scrypt (LTC/DOGE)，决定工作目标和份额难度的换算",
+        "pools.profit_switch.enabled" => "是否按收益切换矿池 (需要 source_url 或矿池的 fee)",
+        "pools.profit_switch.interval_secs" => "检查间隔 (秒)",
+        "pools.profit_switch.threshold_percent" => "其它矿池的收益至少高出当前矿池的比例 (%)",
+        "pools.profit_switch.hold_secs" => "高出需要持续的时间 (秒)",
+        "pools.profit_switch.cooldown_secs" => "两次切换的最小间隔 (秒)",
+
+        "api.enabled" => "是否启用API",
+        "api.bind_address" => "绑定地址",
+        "api.port" => "端口 (1024-65535)",
+        "api.allow_origins" => "允许的跨域来源",
+        "api.tls.self_signed" => "证书文件不存在时生成自签名证书 (未设置 tls_cert/tls_key 时保存到 ./data/tls/)",
+        "api.tls.hostnames" => "自签名证书额外的主机名或 IP，localhost 和本机名总是包含",
+        "api.tls.hsts_max_age_secs" => "HTTPS 响应的 HSTS max-age (秒)，0 表示不发送",
+        "api.tls.policy" => "modern: 只允许 TLS 1.3; intermediate: TLS 1.2/1.3，只用 ECDHE + AEAD 套件",
+        "api.tls.cipher_suites" => "显式指定加密套件 (如 TLS13_AES_256_GCM_SHA384)，为空时按 policy",
+
+        "monitoring.enabled" => "是否启用监控",
+        "monitoring.metrics_interval" => "指标采集间隔 (秒)",
+        "monitoring.web_port" => "监控页面端口",
+        "monitoring.alert_thresholds.temperature_warning" => "温度警告 (°C)",
+        "monitoring.alert_thresholds.temperature_critical" => "温度严重告警 (°C)",
+        "monitoring.alert_thresholds.hashrate_drop_percent" => "算力下降告警 (%)",
+        "monitoring.alert_thresholds.error_rate_percent" => "错误率告警 (%)",
+        "monitoring.alert_thresholds.max_temperature" => "最高温度 (°C)",
+        "monitoring.alert_thresholds.max_cpu_usage" => "最高CPU使用率 (%)",
+        "monitoring.alert_thresholds.max_memory_usage" => "最高内存使用率 (%)",
+        "monitoring.alert_thresholds.max_device_temperature" => "设备最高温度 (°C)",
+        "monitoring.alert_thresholds.max_error_rate" => "最高错误率 (%)",
+        "monitoring.alert_thresholds.min_hashrate" => "最低算力 (GH/s)",
+        "monitoring.push.enabled" => "是否启用告警推送",
+        "monitoring.push.vapid_key_file" => "VAPID 私钥 (PEM, P-256)，用 openssl ecparam -name prime256v1 -genkey -noout 生成",
+        "monitoring.push.subject" => "VAPID 联系方式 (mailto: 或 https: URL)",
+        "monitoring.push.subscriptions_file" => "浏览器订阅的保存文件",
+        "monitoring.push.min_severity" => "推送的最低告警级别 (Info/Warning/Error/Critical)",
+        "monitoring.push.repeat_interval_secs" => "同一告警重复推送的间隔 (秒)",
+        "monitoring.push.ttl_secs" => "推送服务保留未送达通知的时间 (秒)",
+        "monitoring.history.enabled" => "是否保存指标历史",
+        "monitoring.history.path" => "历史数据文件，为空时只保存在内存中",
+        "monitoring.history.save_interval_secs" => "写入历史文件的间隔 (秒)",
+        "monitoring.memory.enabled" => "是否检查进程内存",
+        "monitoring.memory.budget_mb" => "进程常驻内存预算 (MB)",
+        "monitoring.memory.check_interval_secs" => "检查间隔 (秒)",
+        "monitoring.memory.warn_percent" => "超过预算的百分比时发出警告",
+        "monitoring.memory.shrink_percent" => "超过预算的百分比时缩减内存中的指标/告警历史",
+        "monitoring.memory.reduced_history_entries" => "缩减后保留的历史条数",
+        "monitoring.recovery.enabled" => "是否启用零算力恢复",
+        "monitoring.recovery.zero_hashrate_secs" => "总算力持续为 0 多久后执行下一级动作 (秒)",
+        "monitoring.recovery.check_interval_secs" => "检查间隔 (秒)",
+        "monitoring.recovery.actions" => "依次执行的动作: restart_cores, restart_devices, exit",
+        "monitoring.recovery.exit_code" => "exit 动作的进程退出码，由守护进程重新拉起",
+
+        "web.enabled" => "是否启用",
+        "web.auth.enabled" => "是否要求登录",
+        "web.auth.username" => "用户名",
+        "web.auth.password_hash" => "Argon2 密码哈希",
+        "web.auth.session_ttl_secs" => "会话有效期 (秒)，从最后一次访问起算",
+        "web.fleet.enabled" => "是否启用机群视图",
+        "web.fleet.poll_interval_secs" => "轮询间隔 (秒)",
+        "web.fleet.timeout_secs" => "单次请求超时 (秒)",
+        "web.fleet.rigs" => "被轮询的矿机，如 [{ name = \"rig-02\", url = \"http://192.168.1.21:4028\", auth_token = \"...\" }]",
+
+        "hashmeter.enabled" => "是否启用算力计量器",
+        "hashmeter.log_interval" => "输出间隔 (秒)",
+        "hashmeter.per_device_stats" => "输出设备级别统计",
+        "hashmeter.console_output" => "输出到控制台",
+        "hashmeter.format" => "输出格式: standard, classic (原版 cgminer 状态行), json",
+
+        "share_audit.enabled" => "是否启用份额审计日志",
+        "share_audit.file" => "审计文件路径 (JSONL)",
+        "share_audit.max_size" => "单个文件最大大小，超过后轮转",
+        "share_audit.max_files" => "保留的已轮转文件数量",
+        "share_audit.compress" => "压缩已轮转的文件 (gzip)",
+        "share_verify.enabled" => "是否在提交前验证份额 (仅 SHA256d)",
+        "share_verify.sample_rate" => "抽样比例 (0-1]，1 表示验证每个份额，低功耗设备可调低",
+
+        "log_shipping.enabled" => "是否启用远程日志推送",
+        "log_shipping.backend" => "推送目标: loki, http",
+        "log_shipping.url" => "推送地址，Loki 填写服务地址 (如 http://loki:3100)",
+        "log_shipping.rig_name" => "矿机名标签，为空时使用主机名",
+        "log_shipping.min_level" => "推送的最低级别",
+        "log_shipping.batch_size" => "单批最多事件数",
+        "log_shipping.flush_interval_ms" => "最长推送间隔 (毫秒)",
+        "log_shipping.timeout_secs" => "请求超时 (秒)",
+        "log_shipping.spill_dir" => "端点不可达时暂存日志的目录",
+        "log_shipping.max_spill_size" => "暂存文件最大大小，超过后丢弃新日志",
+        "log_shipping.labels" => "附加标签",
+
+        "runtime.flavor" => "运行时类型: auto (单核时单线程) / multi_thread / current_thread",
+        "runtime.worker_threads" => "多线程运行时的工作线程数，0 表示 CPU 数的一半 (1-4)",
+        "runtime.max_blocking_threads" => "阻塞任务线程池上限 (串口 I/O、密码哈希、文件写入)",
+        "runtime.blocking_keep_alive_secs" => "空闲阻塞线程的保留时间 (秒)",
+        "crash_report.enabled" => "是否在 panic 时写入崩溃报告",
+        "crash_report.dir" => "报告目录",
+        "crash_report.log_lines" => "报告中保留的最近日志行数",
+        "crash_report.max_reports" => "目录中保留的报告数量",
+        "crash_report.upload_url" => "--upload-crash-report 的上传地址",
+        "session_report.enabled" => "是否写入会话报告",
+        "session_report.dir" => "报告目录",
+        "session_report.rollover_time" => "每天切换报告的本地时间 (HH:MM)，为空时只在停机时写入",
+        "session_report.webhook_url" => "报告的 POST 地址，为空时不发送",
+        "session_report.max_reports" => "目录中保留的报告数量",
+        "stats_file.enabled" => "是否写入统计文件",
+        "stats_file.path" => "统计文件路径",
+        "stats_file.format" => "文件格式: hiveos 或 minerstat",
+        "stats_file.interval_secs" => "刷新间隔 (秒)",
+        "security.key_file" => "本机加密密钥 (hex)，第一次写入加密文件时生成，权限 0600",
+        "security.api_keys_file" => "API 密钥存储 (加密)，为空时不启用 /api/v1/keys",
+        "security.on_tamper" => "配置文件被外部修改时: ignore 不检查, alert 告警, rollback 告警并还原最后一次备份",
+        "security.tamper_check_interval_secs" => "配置文件检查间隔 (秒)",
+        "security.backup_dir" => "配置文件备份目录，程序保存配置后更新备份",
+        "security.device_map_file" => "设备身份映射文件，启动后更新并与上次比对，随 --create-backup 备份",
+        "security.audit.enabled" => "是否记录控制操作",
+        "security.audit.path" => "审计日志文件 (JSON Lines)",
+        "security.audit.retention_days" => "记录保留天数，0 表示不按时间清理",
+        "security.audit.max_entries" => "最多保留的记录数，超出时丢弃最旧的",
+        "security.lockout.enabled" => "是否启用认证失败锁定",
+        "security.lockout.max_failures" => "窗口内允许的失败次数",
+        "security.lockout.window_secs" => "失败统计窗口 (秒)",
+        "security.lockout.lockout_secs" => "第一次锁定时长 (秒)，之后每次加倍",
+        "security.lockout.max_lockout_secs" => "锁定时长上限 (秒)",
+        _ => return None,
+    })
+}
+
+/// 默认值为空的可选键，以注释形式附在所属段末尾
+fn optional_keys(section: &str) -> &'static [&'static str] {
+    match section {
+        "general" => &["log_file = \"./logs/cgminer.log\"    # 日志文件路径"],
+        "cores.gpu_btc" => &["platform = 0    # OpenCL 平台序号，未设置时使用第一个有 GPU 的平台"],
+        "cores.sim" => &["fail_after_secs = 600    # 运行指定秒数后注入设备故障"],
+        "pools.pools" => &[
+            "quota = 1    # Quota 策略下的配额",
+            "proxy = { proxy_type = \"socks5\", host = \"127.0.0.1\", port = 1080 }    # 代理配置",
+            "quirks = { profile = \"classic\", ping = false }    # 协议差异: auto/standard/classic/nicehash，可覆盖 user_agent、extranonce2_size、ping、extranonce_subscribe；未设置时按地址识别",
+            "fee = 1.5    # 矿池费率 (%)，按收益切换时从收益中扣除",
+            "profit_key = \"btc-pps\"    # 在收益数据中的键，默认使用矿池名称",
+        ],
+        "pools.profit_switch" => &[
+            "source_url = \"https://rates.example.com/pools.json\"    # 收益数据地址，返回 {\"<profit_key>\": 收益} 形式的 JSON；未设置时只比较各矿池的 fee",
+        ],
+        "api" => &[
+            "auth_token = \"change-me\"    # API 认证令牌",
+            "tls_cert = \"/etc/cgminer/server.crt\"    # TLS 证书 (PEM)，与 tls_key 同时配置时使用 HTTPS",
+            "tls_key = \"/etc/cgminer/server.key\"    # TLS 私钥 (PEM)",
+        ],
+        "web" => &["static_path = \"/etc/cgminer/dashboard\"    # 资源覆盖目录，同名文件优先于内嵌的页面、脚本和样式"],
+        "log_shipping" => &["auth_token = \"change-me\"    # Bearer 认证令牌"],
+        _ => &[],
+    }
+}
+
+/// 带注释的默认配置内容
+pub fn generate_default_config() -> Result<String> {
+    let mut config = Config::default();
+    // 模拟核心默认不出现在配置中，这里展示其全部键（保持禁用）
+    config.cores.sim = Some(SimCoreConfig::default());
+
+    let body = toml::to_string(&config).context("Failed to serialize default config")?;
+
+    let mut out = String::from(HEADER);
+    let mut section = String::new();
+    let mut pending_optional: &[&str] = &[];
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            flush_optional(&mut out, pending_optional);
+            let is_array = trimmed.starts_with("[[");
+            let name = trimmed.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+
+            // 同一数组的后续元素不重复段说明
+            out.push('\n');
+            if !(is_array && name == section) {
+                if let Some(comment) = section_comment(&name) {
+                    out.push_str(&format!("# {}\n", comment));
+                }
+            }
+            out.push_str(trimmed);
+            out.push('\n');
+
+            pending_optional = optional_keys(&name);
+            section = name;
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some((key, _)) = trimmed.split_once('=') {
+            let path = if section.is_empty() {
+                key.trim().to_string()
+            } else {
+                format!("{}.{}", section, key.trim())
+            };
+            if let Some(comment) = key_comment(&path) {
+                out.push_str(&format!("# {}\n", comment));
+            }
+        }
+        out.push_str(trimmed);
+        out.push('\n');
+    }
+    flush_optional(&mut out, pending_optional);
+
+    out.push_str(OPTIONAL_SECTIONS);
+    Ok(out)
+}
+
+fn flush_optional(out: &mut String, keys: &[&str]) {
+    for key in keys {
+        out.push_str(&format!("# {}\n", key));
+    }
+}
+
+/// 将默认配置写入文件，已存在时拒绝覆盖
+pub fn write_default_config(path: &str) -> Result<()> {
+    if std::path::Path::new(path).exists() {
+        anyhow::bail!("Refusing to overwrite existing file: {}", path);
+    }
+    let content = generate_default_config()?;
+    std::fs::write(path, content).with_context(|| format!("Failed to write config file: {}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_config_round_trips() {
+        let content = generate_default_config().unwrap();
+        let parsed: Config = toml::from_str(&content).unwrap();
+        let defaults = Config::default();
+
+        assert_eq!(parsed.api.port, defaults.api.port);
+        assert_eq!(parsed.devices.chains.len(), defaults.devices.chains.len());
+        assert_eq!(parsed.pools.pools[0].url, defaults.pools.pools[0].url);
+        assert!(parsed.cores.sim.is_some());
+        assert!(parsed.validate().is_ok());
+    }
+
+    #[test]
+    fn test_every_key_is_commented() {
+        let content = generate_default_config().unwrap();
+        let mut previous = "";
+        for line in content.lines() {
+            if !line.starts_with('#') && !line.starts_with('[') && line.contains('=') {
+                assert!(previous.starts_with('#'), "key without comment: {}", line);
+            }
+            previous = line;
+        }
+    }
+}
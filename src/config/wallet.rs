@@ -0,0 +1,212 @@
+//! 矿池用户名中的钱包地址检查 (`pools.address_check`)
+//!
+//! 很多矿池用 `<钱包地址>.<矿工名>` 作为用户名，地址打错时算力会悄悄记到别人名下。
+//! 看起来像 BTC 地址 (bech32/bech32m 或 base58check) 的用户名会校验校验和，
+//! 不像地址的用户名 (矿池账户名) 不检查：base58 只在全部字符属于 base58 字母表且能解出 25 字节
+//! P2PKH/P2SH 载荷时才当作地址。校验和正确但属于其它网络 (`pools.network`) 的地址同样视为无效。
+
+use crate::mining::Network;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 地址校验失败时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressCheck {
+    /// 不检查
+    Off,
+    /// 启动时醒目告警，继续挖矿
+    Warn,
+    /// 配置验证失败，不启动
+    #[default]
+    Fail,
+}
+
+/// 地址检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressStatus {
+    /// 不像钱包地址
+    NotAnAddress,
+    Valid,
+    /// 像地址但校验失败，附原因
+    Invalid(String),
+}
+
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// 主网和测试网的 P2PKH / P2SH 版本字节
+const BASE58_VERSIONS: [u8; 4] = [0x00, 0x05, 0x6f, 0xc4];
+
+/// 用户名中的地址部分 (`.` 之前)
+pub fn wallet_part(username: &str) -> &str {
+    username.split('.').next().unwrap_or("").trim()
+}
+
+/// 检查用户名中的地址部分
+pub fn check_username(username: &str, network: Network) -> AddressStatus {
+    check_address(wallet_part(username), network)
+}
+
+pub fn check_address(candidate: &str, network: Network) -> AddressStatus {
+    let lower = candidate.to_ascii_lowercase();
+    if ["bc1", "tb1", "bcrt1"].iter().any(|prefix| lower.starts_with(prefix)) && candidate.len() >= 14 {
+        return check_bech32(candidate, network);
+    }
+    let looks_base58 = candidate.starts_with(['1', '3', 'm', 'n', '2'])
+        && (25..=35).contains(&candidate.len())
+        && candidate.bytes().all(|b| BASE58_ALPHABET.contains(&b));
+    if looks_base58 {
+        return check_base58(candidate, network);
+    }
+    AddressStatus::NotAnAddress
+}
+
+fn wrong_network(address_network: &str, network: Network) -> AddressStatus {
+    AddressStatus::Invalid(format!("is a {} address but pools.network is \"{}\"", address_network, network))
+}
+
+fn bech32_polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn check_bech32(address: &str, network: Network) -> AddressStatus {
+    let invalid = |reason: &str| AddressStatus::Invalid(reason.to_string());
+    if address.chars().any(|c| c.is_ascii_lowercase()) && address.chars().any(|c| c.is_ascii_uppercase()) {
+        return invalid("mixes upper and lower case");
+    }
+    let address = address.to_ascii_lowercase();
+    let Some(separator) = address.rfind('1') else {
+        return invalid("has no separator");
+    };
+    let (hrp, data) = (&address[..separator], &address[separator + 1..]);
+    let mut values = Vec::with_capacity(data.len());
+    for c in data.bytes() {
+        match BECH32_CHARSET.iter().position(|&valid| valid == c) {
+            Some(value) => values.push(value as u8),
+            None => return AddressStatus::Invalid(format!("contains '{}', which is not valid in a bech32 address", c as char)),
+        }
+    }
+    if values.len() < 7 {
+        return invalid("is too short");
+    }
+
+    let expanded = hrp.bytes().map(|b| b >> 5).chain([0]).chain(hrp.bytes().map(|b| b & 31));
+    let checksum = bech32_polymod(expanded.chain(values.iter().copied()));
+    // 隔离见证 v0 用 bech32，v1 (taproot) 及以上用 bech32m
+    let version = values[0];
+    let expected = if version == 0 { BECH32_CONST } else { BECH32M_CONST };
+    if checksum != expected {
+        return invalid("has a bad checksum");
+    }
+    let program_len = (values.len() - 7) * 5 / 8;
+    if version > 16 || !(2..=40).contains(&program_len) || (version == 0 && program_len != 20 && program_len != 32) {
+        return invalid("has the wrong length for its witness version");
+    }
+    if hrp != network.bech32_hrp() {
+        let address_network = match hrp {
+            "bc" => Network::Mainnet,
+            "tb" => Network::Testnet,
+            _ => Network::Regtest,
+        };
+        return wrong_network(address_network.as_str(), network);
+    }
+    AddressStatus::Valid
+}
+
+fn base58_decode(text: &str) -> Result<Vec<u8>, char> {
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in text.chars() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&valid| valid as char == c).ok_or(c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = text.chars().take_while(|&c| c == '1').count();
+    Ok([vec![0u8; zeros], bytes].concat())
+}
+
+fn check_base58(address: &str, network: Network) -> AddressStatus {
+    // 解不出 25 字节的 P2PKH/P2SH 载荷时按矿池账户名处理，只有校验和或网络不符才算无效地址
+    let Ok(decoded) = base58_decode(address) else {
+        return AddressStatus::NotAnAddress;
+    };
+    if decoded.len() != 25 || !BASE58_VERSIONS.contains(&decoded[0]) {
+        return AddressStatus::NotAnAddress;
+    }
+    let (payload, checksum) = decoded.split_at(21);
+    if Sha256::digest(Sha256::digest(payload))[..4] != *checksum {
+        return AddressStatus::Invalid("has a bad checksum".to_string());
+    }
+    if !network.base58_versions().contains(&decoded[0]) {
+        let address_network = if Network::Mainnet.base58_versions().contains(&decoded[0]) { "mainnet" } else { "testnet/regtest" };
+        return wrong_network(address_network, network);
+    }
+    AddressStatus::Valid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bech32_addresses() {
+        assert_eq!(check_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Mainnet), AddressStatus::Valid);
+        assert_eq!(check_address("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", Network::Mainnet), AddressStatus::Valid);
+        assert_eq!(check_address("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", Network::Testnet), AddressStatus::Valid);
+        assert_eq!(check_address("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", Network::Mainnet), AddressStatus::Valid);
+        // 最后一位打错
+        assert!(matches!(check_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5", Network::Mainnet), AddressStatus::Invalid(_)));
+        assert!(matches!(check_address("bc1qw508d6qejxtdg4y5r3zarvbry0c5xw7kv8f3t4", Network::Mainnet), AddressStatus::Invalid(_)));
+        assert!(matches!(check_address("bc1qW508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Mainnet), AddressStatus::Invalid(_)));
+    }
+
+    #[test]
+    fn test_base58_addresses_and_usernames() {
+        assert_eq!(check_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", Network::Mainnet), AddressStatus::Valid);
+        assert_eq!(check_address("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", Network::Mainnet), AddressStatus::Valid);
+        assert!(matches!(check_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3", Network::Mainnet), AddressStatus::Invalid(_)));
+        // 含 base58 字母表以外的字符 (0/O/I/l) 或解不出地址载荷的是矿池账户名
+        assert_eq!(check_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNV0", Network::Mainnet), AddressStatus::NotAnAddress);
+        assert_eq!(check_username("mining2024Operator01Account.rig01", Network::Mainnet), AddressStatus::NotAnAddress);
+        assert_eq!(check_username("nicehashAccountName123456789.rig01", Network::Mainnet), AddressStatus::NotAnAddress);
+
+        assert_eq!(check_username("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2.rig01", Network::Mainnet), AddressStatus::Valid);
+        assert_eq!(check_username("alice.rig01", Network::Mainnet), AddressStatus::NotAnAddress);
+        assert_eq!(check_username("username", Network::Mainnet), AddressStatus::NotAnAddress);
+    }
+
+    #[test]
+    fn test_address_network() {
+        assert_eq!(check_address("bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080", Network::Regtest), AddressStatus::Valid);
+        // 测试网和 regtest 的 base58 地址版本字节相同
+        assert_eq!(check_address("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn", Network::Testnet), AddressStatus::Valid);
+        assert_eq!(check_address("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn", Network::Regtest), AddressStatus::Valid);
+
+        let AddressStatus::Invalid(reason) = check_address("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", Network::Mainnet) else {
+            panic!("testnet address accepted on mainnet");
+        };
+        assert_eq!(reason, "is a testnet address but pools.network is \"mainnet\"");
+        assert!(matches!(check_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", Network::Regtest), AddressStatus::Invalid(_)));
+        assert!(matches!(check_address("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn", Network::Mainnet), AddressStatus::Invalid(_)));
+        assert!(matches!(check_address("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", Network::Testnet), AddressStatus::Invalid(_)));
+    }
+}
@@ -0,0 +1,276 @@
+//! 核心注册系统 - 编译时注册所有启用的挖矿核心
+//!
+//! 启用 `dynamic-loading` 特性时还可以从 `cores.plugins.dir` 加载第三方核心动态库 (`plugin`)，
+//! 在 unix 上默认放进子进程运行 (`sandbox`)。
+
+#[cfg(feature = "dynamic-loading")]
+pub mod plugin;
+#[cfg(all(feature = "dynamic-loading", unix))]
+pub mod sandbox;
+
+use cgminer_core::{CoreRegistry, CoreType, CoreInfo, CoreError};
+use crate::config::CorePluginConfig;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+use crate::logging::i18n::msg;
+
+/// 从动态库加载的核心
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginCore {
+    /// 注册名，也是核心实例 ID 的前缀
+    pub key: String,
+    /// 工厂名 (`CoreInfo::name`)
+    pub factory_name: String,
+    pub path: PathBuf,
+}
+
+/// 已加载的插件；动态库加载后不卸载，记录在进程范围内
+static PLUGINS: Mutex<Vec<PluginCore>> = Mutex::new(Vec::new());
+
+/// 工厂名对应的插件核心
+pub fn plugin_for_factory(factory_name: &str) -> Option<PluginCore> {
+    PLUGINS.lock().unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|plugin| plugin.factory_name == factory_name)
+        .cloned()
+}
+
+#[cfg(feature = "cpu-btc")]
+use cgminer_cpu_btc_core;
+
+#[cfg(feature = "maijie-l7")]
+use cgminer_asic_maijie_l7_core;
+
+#[cfg(feature = "gpu-btc")]
+use cgminer_gpu_btc_core;
+
+/// 静态核心注册器 - 在编译时注册所有启用的核心
+pub struct StaticCoreRegistry {
+    /// 核心注册表
+    registry: Arc<CoreRegistry>,
+}
+
+impl StaticCoreRegistry {
+    /// 创建新的静态核心注册器并注册所有启用的核心
+    pub async fn new() -> Result<Self, CoreError> {
+        let registry = Arc::new(CoreRegistry::new());
+        let instance = Self { registry };
+
+        // 静态注册所有启用的核心
+        instance.register_all_cores().await?;
+
+        Ok(instance)
+    }
+
+    /// 获取核心注册表
+    pub fn registry(&self) -> Arc<CoreRegistry> {
+        self.registry.clone()
+    }
+
+    /// 静态注册所有启用的核心
+    async fn register_all_cores(&self) -> Result<(), CoreError> {
+        info!("{}", msg::registering_cores());
+
+        let mut registered_count = 0;
+
+        // 注册Bitcoin软算法核心
+        #[cfg(feature = "cpu-btc")]
+        {
+            if let Err(e) = self.register_cpu_btc_core().await {
+                return Err(CoreError::runtime(msg::core_register_failed("cpu-btc", e)));
+            }
+            registered_count += 1;
+        }
+
+        // 注册Maijie L7 ASIC核心
+        #[cfg(feature = "maijie-l7")]
+        {
+            if let Err(e) = self.register_maijie_l7_core().await {
+                return Err(CoreError::runtime(msg::core_register_failed("maijie-l7", e)));
+            }
+            registered_count += 1;
+        }
+
+        // 注册GPU Bitcoin核心
+        #[cfg(feature = "gpu-btc")]
+        {
+            if let Err(e) = self.register_gpu_btc_core().await {
+                return Err(CoreError::runtime(msg::core_register_failed("gpu-btc", e)));
+            }
+            registered_count += 1;
+        }
+
+        let _stats = self.registry.get_stats().await?;
+        info!("{}", msg::cores_registered(registered_count));
+
+        Ok(())
+    }
+
+    /// 注册Bitcoin软算法核心
+    #[cfg(feature = "cpu-btc")]
+    async fn register_cpu_btc_core(&self) -> Result<(), CoreError> {
+        info!("{}", msg::registering_core("cpu-btc"));
+
+        let factory = cgminer_cpu_btc_core::create_factory();
+        let core_info = factory.core_info();
+
+        self.registry.register_factory("cpu-btc".to_string(), factory).await?;
+
+        info!("{}", msg::core_registered("cpu-btc", &core_info.name, &core_info.core_type));
+        Ok(())
+    }
+
+    /// 注册Maijie L7 ASIC核心
+    #[cfg(feature = "maijie-l7")]
+    async fn register_maijie_l7_core(&self) -> Result<(), CoreError> {
+        info!("{}", msg::registering_core("maijie-l7"));
+
+        let factory = cgminer_asic_maijie_l7_core::create_factory();
+        let core_info = factory.core_info();
+
+        self.registry.register_factory("maijie-l7".to_string(), factory).await?;
+
+        info!("{}", msg::core_registered("maijie-l7", &core_info.name, &core_info.core_type));
+        Ok(())
+    }
+
+    /// 注册GPU Bitcoin核心
+    #[cfg(feature = "gpu-btc")]
+    async fn register_gpu_btc_core(&self) -> Result<(), CoreError> {
+        info!("{}", msg::registering_core("gpu-btc"));
+
+        let factory = cgminer_gpu_btc_core::create_factory();
+        let core_info = factory.core_info();
+
+        self.registry.register_factory("gpu-btc".to_string(), factory).await?;
+
+        info!("{}", msg::core_registered("gpu-btc", &core_info.name, &core_info.core_type));
+        Ok(())
+    }
+
+
+
+    /// 加载并注册插件目录中的核心，返回注册的个数；单个插件失败时跳过
+    pub async fn load_plugins(&self, config: &CorePluginConfig) -> usize {
+        if !config.enabled {
+            return 0;
+        }
+        #[cfg(feature = "dynamic-loading")]
+        {
+            self.load_plugin_dir(config).await
+        }
+        #[cfg(not(feature = "dynamic-loading"))]
+        {
+            warn!("⚠️ cores.plugins is enabled but this build has no dynamic core loading; rebuild with --features=dynamic-loading");
+            0
+        }
+    }
+
+    #[cfg(feature = "dynamic-loading")]
+    async fn load_plugin_dir(&self, config: &CorePluginConfig) -> usize {
+        let dir = std::path::Path::new(&config.dir);
+        let mut registered = 0;
+        for path in plugin::discover(dir) {
+            let Some(key) = plugin::plugin_key(&path) else {
+                continue;
+            };
+            if !config.allow.is_empty() && !config.allow.contains(&key) {
+                info!("Skipping core plugin {} (not in cores.plugins.allow)", path.display());
+                continue;
+            }
+            info!("{}", msg::registering_core(&key));
+
+            let factory = match Self::load_plugin(&path, &key, config).await {
+                Ok(factory) => factory,
+                Err(e) => {
+                    warn!("⚠️ Skipping core plugin {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let core_info = factory.core_info();
+            if let Err(e) = self.registry.register_factory(key.clone(), factory).await {
+                warn!("{}", msg::core_register_failed(&key, e));
+                continue;
+            }
+            PLUGINS.lock().unwrap_or_else(|e| e.into_inner()).push(PluginCore {
+                key: key.clone(),
+                factory_name: core_info.name.clone(),
+                path: path.clone(),
+            });
+            info!("{}", msg::core_registered(&key, &core_info.name, &core_info.core_type));
+            registered += 1;
+        }
+        if registered == 0 {
+            warn!("⚠️ No core plugins loaded from {}", dir.display());
+        }
+        registered
+    }
+
+    /// 创建插件的工厂：启用 `cores.plugins.sandbox` 时在子进程中运行，否则在本进程内加载
+    #[cfg(feature = "dynamic-loading")]
+    async fn load_plugin(path: &std::path::Path, key: &str, config: &CorePluginConfig) -> Result<Box<dyn cgminer_core::CoreFactory>, String> {
+        #[cfg(unix)]
+        if config.sandbox.enabled {
+            let info = sandbox::probe(path, &config.sandbox).await?;
+            return Ok(Box::new(sandbox::SandboxedFactory::new(path.to_path_buf(), info, config.sandbox.clone())));
+        }
+        #[cfg(not(unix))]
+        let _ = config;
+        // 安全性取决于插件本身：握手信息在调用工厂函数前校验
+        unsafe { plugin::load(path, key) }
+    }
+
+    /// 列出所有已注册的核心
+    pub async fn list_registered_cores(&self) -> Result<Vec<CoreInfo>, CoreError> {
+        self.registry.list_factories().await
+    }
+
+    /// 根据类型获取核心
+    pub async fn get_cores_by_type(&self, core_type: &CoreType) -> Result<Vec<CoreInfo>, CoreError> {
+        self.registry.get_factories_by_type(core_type).await
+    }
+
+    /// 获取注册统计信息
+    pub async fn get_registry_stats(&self) -> Result<RegistryStats, CoreError> {
+        let registry_stats = self.registry.get_stats().await?;
+
+        Ok(RegistryStats {
+            registered_factories: registry_stats.registered_factories,
+            active_cores: registry_stats.active_cores,
+        })
+    }
+
+    /// 关闭所有核心
+    pub async fn shutdown(&self) -> Result<(), CoreError> {
+        info!("{}", msg::shutting_down_cores());
+
+        // 关闭所有活跃的核心实例
+        self.registry.shutdown_all().await?;
+
+        info!("{}", msg::cores_shut_down());
+        Ok(())
+    }
+}
+
+/// 注册统计信息
+#[derive(Debug, Clone)]
+pub struct RegistryStats {
+    /// 注册的工厂数量
+    pub registered_factories: usize,
+    /// 活跃的核心数量
+    pub active_cores: usize,
+}
+
+impl std::fmt::Display for RegistryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.active_cores == 0 {
+            // 在静态注册阶段，不显示活跃核心数量（总是0）
+            write!(f, "注册工厂: {}", self.registered_factories)
+        } else {
+            // 在运行时显示完整信息
+            write!(f, "注册工厂: {}, 活跃核心: {}",
+                   self.registered_factories, self.active_cores)
+        }
+    }
+}
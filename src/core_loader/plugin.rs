@@ -0,0 +1,156 @@
+//! 从动态库加载核心 (`dynamic-loading` 特性)
+//!
+//! 插件目录下的每个动态库须导出两个 C 符号：`cgminer_<名称>_core_info() -> *const PluginHandshake`，
+//! 和 `cgminer_<名称>_create_factory() -> *mut Box<dyn CoreFactory>`。名称取自文件名，
+//! 如 `libcgminer_acme_x1_core.so` 的名称是 `acme_x1`，注册为 `acme-x1`。
+//! 握手信息在调用工厂函数前校验，升级后残留的旧插件会被拒绝而不是以不兼容的布局调用。
+//! 插件须与主程序使用同一版本的 rustc 和 cgminer-core 编译，动态库加载后不卸载。
+
+use cgminer_core::CoreFactory;
+use std::ffi::{c_char, c_void, CStr};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// 插件 ABI 版本，cgminer-core 的 trait 或 `PluginHandshake` 有不兼容的修改时递增
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/// `PluginHandshake::magic` 的固定值 ("CGMR")
+pub const PLUGIN_MAGIC: u32 = u32::from_be_bytes(*b"CGMR");
+
+/// 旧版 (ABI 1) 插件导出的版本符号，只用于给出更清楚的错误
+const LEGACY_ABI_VERSION_SYMBOL: &[u8] = b"cgminer_plugin_abi_version\0";
+
+/// 插件握手信息，`cgminer_<名称>_core_info()` 返回指向静态实例的指针
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginHandshake {
+    /// 固定为 `PLUGIN_MAGIC`
+    pub magic: u32,
+    /// 插件编译时的 `PLUGIN_ABI_VERSION`
+    pub abi_version: u32,
+    /// `size_of::<PluginHandshake>()`，以后在末尾追加字段时据此判断插件是否提供
+    pub size: u32,
+    /// 插件版本，NUL 结尾的 UTF-8，可为空指针
+    pub plugin_version: *const c_char,
+}
+
+/// 检查握手信息的魔数、ABI 版本和结构大小
+///
+/// 只依赖所有 ABI 版本共有的前三个字段，版本不匹配时不能假定结构其余部分的布局。
+pub fn check_handshake(magic: u32, abi_version: u32, size: u32) -> Result<(), String> {
+    if magic != PLUGIN_MAGIC {
+        return Err(format!("bad plugin handshake magic {:#010x}; not a cgminer-rs core plugin", magic));
+    }
+    if abi_version != PLUGIN_ABI_VERSION {
+        return Err(format!(
+            "plugin ABI version {} does not match this build ({}); rebuild the plugin against cgminer-rs {}",
+            abi_version,
+            PLUGIN_ABI_VERSION,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+    let expected = std::mem::size_of::<PluginHandshake>() as u32;
+    if size < expected {
+        return Err(format!("plugin handshake is {} bytes, expected at least {}", size, expected));
+    }
+    Ok(())
+}
+
+/// 目录下的动态库，按文件名排序；目录不存在时为空
+pub fn discover(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut libraries: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == std::env::consts::DLL_EXTENSION))
+        .collect();
+    libraries.sort();
+    libraries
+}
+
+/// 文件名中的插件名 (作为注册名，`_` 换成 `-`)
+pub fn plugin_key(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let stem = stem.strip_prefix(std::env::consts::DLL_PREFIX).unwrap_or(stem);
+    let name = stem.strip_prefix("cgminer_").or_else(|| stem.strip_prefix("cgminer-")).unwrap_or(stem);
+    let name = name.strip_suffix("_core").or_else(|| name.strip_suffix("-core")).unwrap_or(name);
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then(|| name.replace('_', "-"))
+}
+
+/// 加载动态库，校验握手信息后调用工厂函数
+///
+/// # Safety
+///
+/// 动态库中的初始化代码和导出函数会在本进程内执行，只加载可信的插件。
+pub unsafe fn load(path: &Path, key: &str) -> Result<Box<dyn CoreFactory>, String> {
+    let library = libloading::Library::new(path).map_err(|e| format!("failed to load library: {}", e))?;
+    let name = key.replace('-', "_");
+
+    let info_symbol = format!("cgminer_{}_core_info\0", name);
+    let core_info = match library.get::<unsafe extern "C" fn() -> *const PluginHandshake>(info_symbol.as_bytes()) {
+        Ok(core_info) => core_info,
+        Err(_) => {
+            if let Ok(legacy) = library.get::<unsafe extern "C" fn() -> u32>(LEGACY_ABI_VERSION_SYMBOL) {
+                return Err(format!(
+                    "plugin uses the old ABI version {} without {}(); rebuild the plugin against cgminer-rs {}",
+                    legacy(),
+                    info_symbol.trim_end_matches('\0'),
+                    env!("CARGO_PKG_VERSION")
+                ));
+            }
+            return Err(format!("missing {}(); not a cgminer-rs core plugin", info_symbol.trim_end_matches('\0')));
+        }
+    };
+    let handshake = core_info();
+    if handshake.is_null() {
+        return Err(format!("{}() returned null", info_symbol.trim_end_matches('\0')));
+    }
+    check_handshake((*handshake).magic, (*handshake).abi_version, (*handshake).size)?;
+    let handshake = &*handshake;
+    if !handshake.plugin_version.is_null() {
+        debug!("Plugin {} version {}", key, CStr::from_ptr(handshake.plugin_version).to_string_lossy());
+    }
+
+    let symbol = format!("cgminer_{}_create_factory\0", name);
+    let create_factory = library
+        .get::<unsafe extern "C" fn() -> *mut c_void>(symbol.as_bytes())
+        .map_err(|_| format!("missing {}()", symbol.trim_end_matches('\0')))?;
+    let factory = create_factory() as *mut Box<dyn CoreFactory>;
+    if factory.is_null() {
+        return Err(format!("{}() returned null", symbol.trim_end_matches('\0')));
+    }
+    let factory = *Box::from_raw(factory);
+
+    // 工厂和它创建的核心都引用库中的代码
+    std::mem::forget(library);
+    Ok(factory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_key_from_file_name() {
+        let file = |name: &str| PathBuf::from(format!("{}{}.{}", std::env::consts::DLL_PREFIX, name, std::env::consts::DLL_EXTENSION));
+        assert_eq!(plugin_key(&file("cgminer_acme_x1_core")).as_deref(), Some("acme-x1"));
+        assert_eq!(plugin_key(&file("cgminer-bitmain-core")).as_deref(), Some("bitmain"));
+        assert_eq!(plugin_key(&file("vendor")).as_deref(), Some("vendor"));
+        assert_eq!(plugin_key(&file("cgminer_bad name_core")), None);
+    }
+
+    #[test]
+    fn test_check_handshake() {
+        let size = std::mem::size_of::<PluginHandshake>() as u32;
+        assert!(check_handshake(PLUGIN_MAGIC, PLUGIN_ABI_VERSION, size).is_ok());
+        // 以后追加字段的插件仍然兼容
+        assert!(check_handshake(PLUGIN_MAGIC, PLUGIN_ABI_VERSION, size + 8).is_ok());
+
+        let stale = check_handshake(PLUGIN_MAGIC, PLUGIN_ABI_VERSION - 1, size).unwrap_err();
+        assert!(stale.contains("does not match this build"), "{}", stale);
+        assert!(check_handshake(0, PLUGIN_ABI_VERSION, size).unwrap_err().contains("magic"));
+        assert!(check_handshake(PLUGIN_MAGIC, PLUGIN_ABI_VERSION, 4).is_err());
+    }
+}
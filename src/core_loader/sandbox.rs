@@ -0,0 +1,428 @@
+//! 在子进程中运行插件核心 (`cores.plugins.sandbox`)
+//!
+//! 每个插件核心实例由一个 `cgminer-rs --core-host <插件>` 子进程承载，主进程通过 unix socket
+//! 上的请求/响应 (4 字节长度 + JSON) 驱动它。子进程崩溃时主进程重启它并重放配置和启动状态，
+//! 厂商核心的段错误只影响当前调用，不会带走整个矿机。
+
+use super::plugin;
+use crate::config::PluginSandboxConfig;
+use async_trait::async_trait;
+use cgminer_core::{
+    CoreCapabilities, CoreConfig, CoreError, CoreFactory, CoreInfo, CoreStats, DeviceInfo, MiningCore, MiningResult, Work,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// 子进程承载插件的命令行参数
+pub const HOST_ARG: &str = "--core-host";
+pub const SOCKET_ARG: &str = "--core-socket";
+
+/// 单个消息的上限，防止损坏的长度字段导致巨量分配
+const MAX_FRAME: usize = 64 * 1024 * 1024;
+
+/// 主进程发给子进程的请求
+#[derive(Debug, Serialize, Deserialize)]
+pub enum HostRequest {
+    Info,
+    Create(CoreConfig),
+    Start,
+    Stop,
+    SubmitWork(Work),
+    CollectResults,
+    Stats,
+    ScanDevices,
+}
+
+/// 子进程的响应
+#[derive(Debug, Serialize, Deserialize)]
+pub enum HostResponse {
+    Info(CoreInfo),
+    Created(CoreCapabilities),
+    Done,
+    Results(Vec<MiningResult>),
+    Stats(CoreStats),
+    Devices(Vec<DeviceInfo>),
+    Error(String),
+}
+
+async fn write_frame<T: Serialize>(stream: &mut UnixStream, message: &T) -> std::io::Result<()> {
+    let data = serde_json::to_vec(message).map_err(std::io::Error::other)?;
+    stream.write_u32(data.len() as u32).await?;
+    stream.write_all(&data).await?;
+    stream.flush().await
+}
+
+/// 读取一个消息，对端关闭时返回 None
+async fn read_frame<T: DeserializeOwned>(stream: &mut UnixStream) -> std::io::Result<Option<T>> {
+    let len = match stream.read_u32().await {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if len > MAX_FRAME {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("frame of {} bytes is too large", len)));
+    }
+    let mut data = vec![0u8; len];
+    stream.read_exact(&mut data).await?;
+    serde_json::from_slice(&data).map(Some).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// 子进程入口：加载插件，连接主进程并处理请求，主进程断开后返回
+pub async fn run_host(plugin_path: &Path, socket: &Path) -> Result<(), String> {
+    let key = plugin::plugin_key(plugin_path).ok_or_else(|| format!("{} is not a core plugin", plugin_path.display()))?;
+    let mut stream = UnixStream::connect(socket).await.map_err(|e| format!("failed to connect to {}: {}", socket.display(), e))?;
+    // 安全性取决于插件本身：这正是要放进子进程的原因
+    let factory = match unsafe { plugin::load(plugin_path, &key) } {
+        Ok(factory) => factory,
+        Err(e) => {
+            // 把握手失败等加载错误作为第一个请求的响应，主进程才能报告原因而不是等待超时
+            if let Ok(Some(_)) = read_frame::<HostRequest>(&mut stream).await {
+                let _ = write_frame(&mut stream, &HostResponse::Error(e.clone())).await;
+            }
+            return Err(e);
+        }
+    };
+
+    let mut core: Option<Box<dyn MiningCore>> = None;
+    while let Some(request) = read_frame::<HostRequest>(&mut stream).await.map_err(|e| e.to_string())? {
+        let response = match (request, core.as_mut()) {
+            (HostRequest::Info, _) => HostResponse::Info(factory.core_info()),
+            (HostRequest::Create(config), _) => match factory.create_core(config).await {
+                Ok(created) => {
+                    let capabilities = created.get_capabilities().clone();
+                    core = Some(created);
+                    HostResponse::Created(capabilities)
+                }
+                Err(e) => HostResponse::Error(e.to_string()),
+            },
+            (_, None) => HostResponse::Error("core has not been created".to_string()),
+            (HostRequest::Start, Some(core)) => done(core.start().await),
+            (HostRequest::Stop, Some(core)) => done(core.stop().await),
+            (HostRequest::SubmitWork(work), Some(core)) => done(core.submit_work(work).await),
+            (HostRequest::CollectResults, Some(core)) => match core.collect_results().await {
+                Ok(results) => HostResponse::Results(results),
+                Err(e) => HostResponse::Error(e.to_string()),
+            },
+            (HostRequest::Stats, Some(core)) => match core.get_stats().await {
+                Ok(stats) => HostResponse::Stats(stats),
+                Err(e) => HostResponse::Error(e.to_string()),
+            },
+            (HostRequest::ScanDevices, Some(core)) => match core.scan_devices().await {
+                Ok(devices) => HostResponse::Devices(devices),
+                Err(e) => HostResponse::Error(e.to_string()),
+            },
+        };
+        write_frame(&mut stream, &response).await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn done(result: Result<(), CoreError>) -> HostResponse {
+    match result {
+        Ok(()) => HostResponse::Done,
+        Err(e) => HostResponse::Error(e.to_string()),
+    }
+}
+
+/// 运行中的子进程
+struct HostProcess {
+    child: Child,
+    stream: UnixStream,
+}
+
+impl HostProcess {
+    /// 启动子进程并等待它连接
+    async fn spawn(plugin_path: &Path, startup_timeout: Duration) -> Result<Self, String> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let socket = std::env::temp_dir().join(format!(
+            "cgminer-core-{}-{}.sock",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).map_err(|e| format!("failed to bind {}: {}", socket.display(), e))?;
+
+        let exe = std::env::current_exe().map_err(|e| format!("failed to locate the miner executable: {}", e))?;
+        let spawned = Command::new(exe)
+            .arg(HOST_ARG)
+            .arg(plugin_path)
+            .arg(SOCKET_ARG)
+            .arg(&socket)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                let _ = std::fs::remove_file(&socket);
+                return Err(format!("failed to start core process: {}", e));
+            }
+        };
+
+        let accepted = tokio::time::timeout(startup_timeout, listener.accept()).await;
+        let _ = std::fs::remove_file(&socket);
+        match accepted {
+            Ok(Ok((stream, _))) => Ok(Self { child, stream }),
+            Ok(Err(e)) => Err(format!("core process did not connect: {}", e)),
+            Err(_) => {
+                let status = child.try_wait().ok().flatten();
+                let _ = child.start_kill();
+                Err(match status {
+                    Some(status) => format!("core process exited during startup ({})", status),
+                    None => format!("core process did not connect within {}s", startup_timeout.as_secs()),
+                })
+            }
+        }
+    }
+
+    async fn request(&mut self, request: &HostRequest) -> std::io::Result<HostResponse> {
+        write_frame(&mut self.stream, request).await?;
+        read_frame(&mut self.stream)
+            .await?
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "core process closed the connection"))
+    }
+}
+
+/// 在子进程中查询插件的核心信息
+pub async fn probe(plugin_path: &Path, policy: &PluginSandboxConfig) -> Result<CoreInfo, String> {
+    let mut host = HostProcess::spawn(plugin_path, Duration::from_secs(policy.startup_timeout_secs)).await?;
+    match host.request(&HostRequest::Info).await.map_err(|e| e.to_string())? {
+        HostResponse::Info(info) => Ok(info),
+        HostResponse::Error(e) => Err(e),
+        other => Err(format!("unexpected response to Info: {:?}", other)),
+    }
+}
+
+/// 在子进程中运行的插件工厂
+pub struct SandboxedFactory {
+    plugin_path: PathBuf,
+    info: CoreInfo,
+    policy: PluginSandboxConfig,
+}
+
+impl SandboxedFactory {
+    pub fn new(plugin_path: PathBuf, info: CoreInfo, policy: PluginSandboxConfig) -> Self {
+        Self { plugin_path, info, policy }
+    }
+}
+
+#[async_trait]
+impl CoreFactory for SandboxedFactory {
+    fn core_info(&self) -> CoreInfo {
+        self.info.clone()
+    }
+
+    async fn create_core(&self, config: CoreConfig) -> Result<Box<dyn MiningCore>, CoreError> {
+        let core = SandboxedCore::spawn(self.plugin_path.clone(), self.info.clone(), self.policy.clone(), config).await?;
+        Ok(Box::new(core))
+    }
+
+    /// 插件自己的校验在子进程创建核心时进行
+    fn validate_config(&self, _config: &CoreConfig) -> Result<(), CoreError> {
+        Ok(())
+    }
+
+    fn default_config(&self) -> CoreConfig {
+        CoreConfig {
+            name: self.info.name.clone(),
+            enabled: true,
+            devices: vec![],
+            custom_params: HashMap::new(),
+        }
+    }
+}
+
+/// 子进程状态，崩溃后据此重建
+struct HostState {
+    process: Option<HostProcess>,
+    started: bool,
+    /// 窗口内的重启时间
+    restarts: VecDeque<Instant>,
+}
+
+/// 代理到子进程的核心
+pub struct SandboxedCore {
+    plugin_path: PathBuf,
+    info: CoreInfo,
+    capabilities: CoreCapabilities,
+    policy: PluginSandboxConfig,
+    config: CoreConfig,
+    state: Mutex<HostState>,
+}
+
+impl SandboxedCore {
+    async fn spawn(plugin_path: PathBuf, info: CoreInfo, policy: PluginSandboxConfig, config: CoreConfig) -> Result<Self, CoreError> {
+        let timeout = Duration::from_secs(policy.startup_timeout_secs);
+        let mut process = HostProcess::spawn(&plugin_path, timeout).await.map_err(CoreError::runtime)?;
+        let capabilities = match process.request(&HostRequest::Create(config.clone())).await {
+            Ok(HostResponse::Created(capabilities)) => capabilities,
+            Ok(HostResponse::Error(e)) => return Err(CoreError::runtime(e)),
+            Ok(other) => return Err(CoreError::runtime(format!("unexpected response to Create: {:?}", other))),
+            Err(e) => return Err(CoreError::runtime(format!("core process failed while creating the core: {}", e))),
+        };
+        info!("🧱 Core {} runs in sandbox process {}", info.name, process.child.id().unwrap_or(0));
+        Ok(Self {
+            plugin_path,
+            info,
+            capabilities,
+            policy,
+            config,
+            state: Mutex::new(HostState { process: Some(process), started: false, restarts: VecDeque::new() }),
+        })
+    }
+
+    /// 发送请求；子进程已崩溃时重启它，本次调用返回错误
+    async fn call(&self, request: HostRequest) -> Result<HostResponse, CoreError> {
+        let mut state = self.state.lock().await;
+        if state.process.is_none() {
+            self.restart(&mut state).await?;
+        }
+        let Some(process) = state.process.as_mut() else {
+            return Err(CoreError::runtime(format!("core process for {} is not running", self.info.name)));
+        };
+        match process.request(&request).await {
+            Ok(HostResponse::Error(e)) => Err(CoreError::runtime(e)),
+            Ok(response) => Ok(response),
+            Err(e) => {
+                let status = process.child.try_wait().ok().flatten();
+                match status {
+                    Some(status) => error!("💥 Core process for {} exited ({}); restarting", self.info.name, status),
+                    None => error!("💥 Lost connection to the core process for {} ({}); restarting", self.info.name, e),
+                }
+                state.process = None;
+                self.restart(&mut state).await?;
+                Err(CoreError::runtime(format!("core process for {} crashed and was restarted", self.info.name)))
+            }
+        }
+    }
+
+    /// 在重启预算内重建子进程，重放配置和启动状态
+    async fn restart(&self, state: &mut HostState) -> Result<(), CoreError> {
+        let now = Instant::now();
+        let window = Duration::from_secs(self.policy.restart_window_secs);
+        while state.restarts.front().is_some_and(|at| now.duration_since(*at) >= window) {
+            state.restarts.pop_front();
+        }
+        if state.restarts.len() >= self.policy.max_restarts as usize {
+            return Err(CoreError::runtime(format!(
+                "core process for {} crashed {} times within {}s; not restarting",
+                self.info.name,
+                state.restarts.len(),
+                self.policy.restart_window_secs
+            )));
+        }
+        state.restarts.push_back(now);
+
+        let timeout = Duration::from_secs(self.policy.startup_timeout_secs);
+        let mut process = HostProcess::spawn(&self.plugin_path, timeout).await.map_err(CoreError::runtime)?;
+        let created = process.request(&HostRequest::Create(self.config.clone())).await;
+        if !matches!(created, Ok(HostResponse::Created(_))) {
+            return Err(CoreError::runtime(format!("restarted core process for {} failed to create the core", self.info.name)));
+        }
+        if state.started && !matches!(process.request(&HostRequest::Start).await, Ok(HostResponse::Done)) {
+            return Err(CoreError::runtime(format!("restarted core process for {} failed to start the core", self.info.name)));
+        }
+        warn!("🔁 Restarted core process for {} ({} restarts in the last {}s)", self.info.name, state.restarts.len(), self.policy.restart_window_secs);
+        state.process = Some(process);
+        Ok(())
+    }
+
+    async fn call_done(&self, request: HostRequest) -> Result<(), CoreError> {
+        match self.call(request).await? {
+            HostResponse::Done => Ok(()),
+            other => Err(CoreError::runtime(format!("unexpected response from core process: {:?}", other))),
+        }
+    }
+}
+
+#[async_trait]
+impl MiningCore for SandboxedCore {
+    /// 子进程在创建时已用配置初始化核心
+    async fn initialize(&mut self, config: CoreConfig) -> Result<(), CoreError> {
+        self.config = config.clone();
+        match self.call(HostRequest::Create(config)).await? {
+            HostResponse::Created(capabilities) => {
+                self.capabilities = capabilities;
+                Ok(())
+            }
+            other => Err(CoreError::runtime(format!("unexpected response from core process: {:?}", other))),
+        }
+    }
+
+    async fn start(&mut self) -> Result<(), CoreError> {
+        self.call_done(HostRequest::Start).await?;
+        self.state.lock().await.started = true;
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), CoreError> {
+        self.state.lock().await.started = false;
+        self.call_done(HostRequest::Stop).await
+    }
+
+    async fn submit_work(&mut self, work: Work) -> Result<(), CoreError> {
+        self.call_done(HostRequest::SubmitWork(work)).await
+    }
+
+    async fn collect_results(&mut self) -> Result<Vec<MiningResult>, CoreError> {
+        match self.call(HostRequest::CollectResults).await? {
+            HostResponse::Results(results) => Ok(results),
+            other => Err(CoreError::runtime(format!("unexpected response from core process: {:?}", other))),
+        }
+    }
+
+    async fn get_stats(&self) -> Result<CoreStats, CoreError> {
+        match self.call(HostRequest::Stats).await? {
+            HostResponse::Stats(stats) => Ok(stats),
+            other => Err(CoreError::runtime(format!("unexpected response from core process: {:?}", other))),
+        }
+    }
+
+    async fn scan_devices(&self) -> Result<Vec<DeviceInfo>, CoreError> {
+        match self.call(HostRequest::ScanDevices).await? {
+            HostResponse::Devices(devices) => Ok(devices),
+            other => Err(CoreError::runtime(format!("unexpected response from core process: {:?}", other))),
+        }
+    }
+
+    fn get_info(&self) -> &CoreInfo {
+        &self.info
+    }
+
+    fn get_capabilities(&self) -> &CoreCapabilities {
+        &self.capabilities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_frame_round_trip() {
+        let (mut parent, mut child) = UnixStream::pair().unwrap();
+        write_frame(&mut parent, &HostRequest::Stats).await.unwrap();
+        write_frame(&mut parent, &HostRequest::Start).await.unwrap();
+        assert!(matches!(read_frame::<HostRequest>(&mut child).await.unwrap(), Some(HostRequest::Stats)));
+        assert!(matches!(read_frame::<HostRequest>(&mut child).await.unwrap(), Some(HostRequest::Start)));
+
+        // 对端关闭时结束
+        drop(parent);
+        assert!(read_frame::<HostRequest>(&mut child).await.unwrap().is_none());
+
+        // 超长的长度字段不分配
+        let (mut parent, mut child) = UnixStream::pair().unwrap();
+        parent.write_u32(u32::MAX).await.unwrap();
+        assert!(read_frame::<HostRequest>(&mut child).await.is_err());
+    }
+}
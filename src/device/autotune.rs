@@ -0,0 +1,391 @@
+//! 按链自动调优
+//!
+//! 在温度上限内逐点扫描频率/电压，找到每瓦算力最高的工作点，按板卡标识保存，
+//! 下次启动时直接应用已保存的档位而不再扫描。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use super::{DeviceInfo, MiningDevice};
+
+/// 自动调优配置 (`[devices.auto_tune]`)，只对 `auto_tune = true` 的链生效
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoTuneConfig {
+    /// 是否启用自动调优
+    pub enabled: bool,
+    /// 扫描频率范围和步长 (MHz)
+    pub frequency_min: u32,
+    pub frequency_max: u32,
+    pub frequency_step: u32,
+    /// 扫描电压范围和步长 (mV)
+    pub voltage_min: u32,
+    pub voltage_max: u32,
+    pub voltage_step: u32,
+    /// 每个工作点的稳定测量时间 (秒)
+    pub dwell_secs: u64,
+    /// 调优温度上限 (°C)，超过后不再提高该电压下的频率
+    pub temperature_limit: f32,
+    /// 已找到档位的保存文件
+    pub profile_file: String,
+}
+
+impl Default for AutoTuneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frequency_min: 400,
+            frequency_max: 700,
+            frequency_step: 25,
+            voltage_min: 800,
+            voltage_max: 900,
+            voltage_step: 25,
+            dwell_secs: 120,
+            temperature_limit: 80.0,
+            profile_file: "./data/autotune-profiles.json".to_string(),
+        }
+    }
+}
+
+impl AutoTuneConfig {
+    /// 验证自动调优配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.frequency_step == 0 || self.voltage_step == 0 {
+            anyhow::bail!("Auto-tune frequency_step and voltage_step must be greater than 0");
+        }
+        if self.frequency_min > self.frequency_max || self.voltage_min > self.voltage_max {
+            anyhow::bail!("Auto-tune minimum must not exceed maximum");
+        }
+        if !crate::config::FREQUENCY_RANGE.contains(&self.frequency_min)
+            || !crate::config::FREQUENCY_RANGE.contains(&self.frequency_max)
+        {
+            anyhow::bail!("Auto-tune frequency range must be within {:?} MHz", crate::config::FREQUENCY_RANGE);
+        }
+        if !crate::config::VOLTAGE_RANGE.contains(&self.voltage_min)
+            || !crate::config::VOLTAGE_RANGE.contains(&self.voltage_max)
+        {
+            anyhow::bail!("Auto-tune voltage range must be within {:?} mV", crate::config::VOLTAGE_RANGE);
+        }
+        if self.dwell_secs == 0 {
+            anyhow::bail!("Auto-tune dwell_secs must be greater than 0");
+        }
+        Ok(())
+    }
+
+    /// 扫描的电压档位，从低到高
+    pub fn voltages(&self) -> Vec<u32> {
+        (self.voltage_min..=self.voltage_max).step_by(self.voltage_step.max(1) as usize).collect()
+    }
+
+    /// 扫描的频率档位，从低到高
+    pub fn frequencies(&self) -> Vec<u32> {
+        (self.frequency_min..=self.frequency_max).step_by(self.frequency_step.max(1) as usize).collect()
+    }
+
+    /// 扫描点总数
+    pub fn total_points(&self) -> usize {
+        self.voltages().len() * self.frequencies().len()
+    }
+}
+
+/// 估算链功耗 (W)：动态功耗与 芯片数 × 频率 × 电压² 成正比
+///
+/// 没有功率计的板卡只能用这个估算值比较不同工作点的能效。
+pub fn estimate_power(chip_count: u32, frequency: u32, voltage: u32) -> f64 {
+    const WATTS_PER_CHIP_MHZ_V2: f64 = 0.0045;
+    let volts = voltage as f64 / 1000.0;
+    chip_count.max(1) as f64 * frequency as f64 * volts * volts * WATTS_PER_CHIP_MHZ_V2
+}
+
+/// 一个测得的工作点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TunePoint {
+    pub frequency: u32,
+    pub voltage: u32,
+    /// 测得算力 (H/s)
+    pub hashrate: f64,
+    /// 功耗 (W)
+    pub power: f64,
+    pub temperature: f32,
+}
+
+impl TunePoint {
+    /// 每瓦算力 (H/J)
+    pub fn efficiency(&self) -> f64 {
+        if self.power > 0.0 { self.hashrate / self.power } else { 0.0 }
+    }
+}
+
+/// 在测得的工作点中选出温度上限内能效最高的点
+pub fn best_point(points: &[TunePoint], temperature_limit: f32) -> Option<TunePoint> {
+    points
+        .iter()
+        .filter(|point| point.hashrate > 0.0 && point.temperature <= temperature_limit)
+        .max_by(|a, b| a.efficiency().total_cmp(&b.efficiency()))
+        .cloned()
+}
+
+/// 板卡标识：优先使用板卡序列号，换槽后仍能对应；没有序列号时用设备名和链号
+pub fn board_key(info: &DeviceInfo) -> String {
+    match &info.serial_number {
+        Some(serial) => format!("sn:{}", serial),
+        None => format!("{}#chain{}", info.name, info.chain_id),
+    }
+}
+
+/// 已保存的调优档位
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TunedProfile {
+    pub frequency: u32,
+    pub voltage: u32,
+    pub hashrate: f64,
+    pub power: f64,
+    pub tuned_at: SystemTime,
+}
+
+/// 按板卡标识保存的调优档位文件
+pub struct TuneProfileStore {
+    path: String,
+    profiles: Mutex<HashMap<String, TunedProfile>>,
+}
+
+impl TuneProfileStore {
+    /// 读取档位文件，文件不存在或损坏时从空开始
+    pub fn load(path: &str) -> Self {
+        let profiles = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable auto-tune profiles {}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self { path: path.to_string(), profiles: Mutex::new(profiles) }
+    }
+
+    pub async fn get(&self, key: &str) -> Option<TunedProfile> {
+        self.profiles.lock().await.get(key).cloned()
+    }
+
+    pub async fn insert(&self, key: String, profile: TunedProfile) -> anyhow::Result<()> {
+        let mut profiles = self.profiles.lock().await;
+        profiles.insert(key, profile);
+        if let Some(parent) = Path::new(&self.path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&*profiles)?)?;
+        Ok(())
+    }
+}
+
+/// 调优阶段
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum AutoTunePhase {
+    /// 正在扫描
+    Sweeping,
+    /// 已应用保存的档位
+    Restored,
+    /// 扫描完成并已应用最优点
+    Completed,
+    /// 失败 (设备错误或没有可用工作点)
+    Failed(String),
+}
+
+/// 单条链的调优进度
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoTuneStatus {
+    pub device_id: u32,
+    pub board: String,
+    pub phase: AutoTunePhase,
+    pub tested_points: usize,
+    pub total_points: usize,
+    /// 当前最优点
+    pub best: Option<TunePoint>,
+    pub updated_at: SystemTime,
+}
+
+impl AutoTuneStatus {
+    fn new(device_id: u32, board: String, total_points: usize) -> Self {
+        Self {
+            device_id,
+            board,
+            phase: AutoTunePhase::Sweeping,
+            tested_points: 0,
+            total_points,
+            best: None,
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    /// 进度百分比
+    pub fn progress(&self) -> f32 {
+        if self.total_points == 0 {
+            100.0
+        } else {
+            self.tested_points as f32 / self.total_points as f32 * 100.0
+        }
+    }
+}
+
+/// 调优一条链：有保存的档位时直接应用，否则扫描并保存最优点
+///
+/// `info` 取自设备管理器的缓存，包含从 EEPROM 读到的序列号。
+pub async fn tune_device(
+    device: Arc<Mutex<Box<dyn MiningDevice>>>,
+    info: DeviceInfo,
+    config: AutoTuneConfig,
+    store: Arc<TuneProfileStore>,
+    statuses: Arc<RwLock<HashMap<u32, AutoTuneStatus>>>,
+) {
+    let device_id = info.id;
+    let key = board_key(&info);
+    let mut status = AutoTuneStatus::new(device_id, key.clone(), config.total_points());
+
+    if let Some(profile) = store.get(&key).await {
+        let mut device = device.lock().await;
+        let applied = match device.set_voltage(profile.voltage).await {
+            Ok(()) => device.set_frequency(profile.frequency).await,
+            Err(e) => Err(e),
+        };
+        status.phase = match applied {
+            Ok(()) => {
+                info!("Auto-tune: device {} restored {} MHz / {} mV from saved profile", device_id, profile.frequency, profile.voltage);
+                AutoTunePhase::Restored
+            }
+            Err(e) => AutoTunePhase::Failed(e.to_string()),
+        };
+        status.tested_points = status.total_points;
+        status.updated_at = SystemTime::now();
+        statuses.write().await.insert(device_id, status);
+        return;
+    }
+
+    info!("Auto-tune: sweeping {} points on device {} ({})", status.total_points, device_id, key);
+    statuses.write().await.insert(device_id, status.clone());
+
+    let dwell = Duration::from_secs(config.dwell_secs);
+    let mut points = Vec::new();
+    'voltages: for voltage in config.voltages() {
+        for frequency in config.frequencies() {
+            let measured = {
+                let mut device = device.lock().await;
+                match device.set_voltage(voltage).await {
+                    Ok(()) => device.set_frequency(frequency).await,
+                    Err(e) => Err(e),
+                }
+            };
+            if let Err(e) = measured {
+                status.phase = AutoTunePhase::Failed(e.to_string());
+                break 'voltages;
+            }
+
+            tokio::time::sleep(dwell).await;
+
+            let (hashrate, temperature) = {
+                let device = device.lock().await;
+                (device.get_hashrate().await.unwrap_or(0.0), device.get_temperature().await.unwrap_or(f32::MAX))
+            };
+            let point = TunePoint {
+                frequency,
+                voltage,
+                hashrate,
+                power: estimate_power(info.chip_count, frequency, voltage),
+                temperature,
+            };
+            debug!("Auto-tune: device {} {} MHz / {} mV -> {:.0} H/s, {:.1}°C", device_id, frequency, voltage, hashrate, temperature);
+
+            let too_hot = temperature > config.temperature_limit;
+            points.push(point);
+            status.tested_points += 1;
+            status.best = best_point(&points, config.temperature_limit);
+            status.updated_at = SystemTime::now();
+            statuses.write().await.insert(device_id, status.clone());
+
+            if too_hot {
+                // 该电压下更高的频率只会更热
+                status.tested_points += config.frequencies().iter().filter(|&&f| f > frequency).count();
+                continue 'voltages;
+            }
+        }
+    }
+
+    if status.phase == AutoTunePhase::Sweeping {
+        status.phase = match best_point(&points, config.temperature_limit) {
+            Some(best) => {
+                let mut device = device.lock().await;
+                let applied = match device.set_voltage(best.voltage).await {
+                    Ok(()) => device.set_frequency(best.frequency).await,
+                    Err(e) => Err(e),
+                };
+                match applied {
+                    Ok(()) => {
+                        info!("Auto-tune: device {} settled at {} MHz / {} mV ({:.0} H/W)", device_id, best.frequency, best.voltage, best.efficiency());
+                        let profile = TunedProfile {
+                            frequency: best.frequency,
+                            voltage: best.voltage,
+                            hashrate: best.hashrate,
+                            power: best.power,
+                            tuned_at: SystemTime::now(),
+                        };
+                        if let Err(e) = store.insert(key, profile).await {
+                            warn!("Auto-tune: failed to save profile: {}", e);
+                        }
+                        AutoTunePhase::Completed
+                    }
+                    Err(e) => AutoTunePhase::Failed(e.to_string()),
+                }
+            }
+            None => AutoTunePhase::Failed("no operating point within the temperature limit".to_string()),
+        };
+    }
+
+    status.updated_at = SystemTime::now();
+    statuses.write().await.insert(device_id, status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(frequency: u32, voltage: u32, hashrate: f64, temperature: f32) -> TunePoint {
+        TunePoint { frequency, voltage, hashrate, power: estimate_power(76, frequency, voltage), temperature }
+    }
+
+    #[test]
+    fn test_sweep_points() {
+        let config = AutoTuneConfig { enabled: true, frequency_min: 500, frequency_max: 600, frequency_step: 50, ..Default::default() };
+        assert_eq!(config.frequencies(), vec![500, 550, 600]);
+        assert_eq!(config.voltages(), vec![800, 825, 850, 875, 900]);
+        assert_eq!(config.total_points(), 15);
+        assert!(config.validate().is_ok());
+        assert!(AutoTuneConfig { enabled: true, frequency_step: 0, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_best_point_respects_temperature_limit() {
+        let points = vec![
+            point(500, 800, 10e12, 60.0),
+            // 能效最高但超温
+            point(550, 800, 13e12, 85.0),
+            point(600, 900, 12e12, 75.0),
+        ];
+        let best = best_point(&points, 80.0).unwrap();
+        assert_eq!((best.frequency, best.voltage), (500, 800));
+        assert!(best_point(&points[1..2], 80.0).is_none());
+    }
+
+    #[test]
+    fn test_board_key_prefers_serial() {
+        let mut info = DeviceInfo::new(1000, "Chain 0".to_string(), "asic".to_string(), 0);
+        assert_eq!(board_key(&info), "Chain 0#chain0");
+        info.serial_number = Some("L7HB2304001234".to_string());
+        assert_eq!(board_key(&info), "sn:L7HB2304001234");
+    }
+}
@@ -0,0 +1,526 @@
+//! Avalon (Canaan) A12/A13 链驱动 - 通过 AUC 转接器以 IIC 协议驱动 MM 模块
+//!
+//! 通过 `--features avalon` 启用 (仅 Linux)。每个 MM 模块 (一条链) 作为一个设备，工作、频率、
+//! 电压和风扇都用 40 字节的 `CN` 包下发，包经 AUC 转发到模块的 IIC 地址。底层读写走
+//! `HardwareInterface` 的 UART 通道，`chain_id` 即 AUC 上的模块序号，由 `[[devices.boards]]` 指定串口。
+
+use crate::config::{AvalonChainProfile, AvalonCoreConfig, AvalonModel};
+use crate::device::chips::{ChipStats, ChipTelemetry};
+use crate::device::traits::HardwareInterface;
+use crate::device::{nonce_result, DeviceConfig, DeviceInfo, DeviceStats, DeviceStatus, MiningDevice, MiningResult, Work};
+use crate::error::DeviceError;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Avalon 设备ID起始值
+pub const AVALON_DEVICE_ID_BASE: u32 = 9600;
+
+/// 每个 MM 模块的 PLL 数
+pub const PLL_COUNT: usize = 4;
+
+/// 难度1对应的期望哈希数
+const HASHES_PER_DIFF1: f64 = 4_294_967_296.0;
+
+/// 风扇 PWM 最大值
+const PWM_MAX: u32 = 0x3ff;
+
+/// 包头 "CN"
+const PACKET_HEAD: [u8; 2] = [b'C', b'N'];
+/// 包数据长度
+const PACKET_DATA_LEN: usize = 32;
+/// 完整包长度: 头(2) + 类型/选项/序号/总数(4) + 数据(32) + CRC(2)
+pub const PACKET_LEN: usize = 40;
+
+/// AUC 转发命令：写入后读回模块响应
+const AUC_CMD_XFER: u8 = 0xa5;
+/// AUC 帧头长度
+const AUC_HEADER_LEN: usize = 4;
+/// 模块 IIC 地址起始值
+const MM_IIC_ADDR_BASE: u8 = 0x10;
+
+/// `CN` 包类型
+pub mod packet_type {
+    pub const DETECT: u8 = 0x10;
+    pub const JOB_ID: u8 = 0x12;
+    pub const HEADER: u8 = 0x15;
+    pub const TARGET: u8 = 0x16;
+    pub const JOB_FIN: u8 = 0x17;
+    pub const SET: u8 = 0x20;
+    pub const SET_FIN: u8 = 0x21;
+    pub const SET_VOLT: u8 = 0x22;
+    pub const SET_PLL: u8 = 0x25;
+    pub const POLLING: u8 = 0x30;
+    pub const RSTMMTX: u8 = 0x33;
+    pub const ACKDETECT: u8 = 0x40;
+    pub const STATUS: u8 = 0x41;
+    pub const NONCE: u8 = 0x42;
+}
+
+/// CRC16 (XMODEM，多项式 0x1021，初值 0)，覆盖包数据部分
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, &byte| {
+        let mut crc = crc ^ ((byte as u16) << 8);
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+        crc
+    })
+}
+
+/// 一个 `CN` 包
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvalonPacket {
+    pub kind: u8,
+    pub opt: u8,
+    /// 分片序号，从 1 开始
+    pub idx: u8,
+    /// 分片总数
+    pub cnt: u8,
+    pub data: [u8; PACKET_DATA_LEN],
+}
+
+impl AvalonPacket {
+    pub fn new(kind: u8, idx: u8, cnt: u8, payload: &[u8]) -> Self {
+        let mut data = [0u8; PACKET_DATA_LEN];
+        let len = payload.len().min(PACKET_DATA_LEN);
+        data[..len].copy_from_slice(&payload[..len]);
+        Self { kind, opt: 0, idx, cnt, data }
+    }
+
+    /// 把较长的负载切成多个分片包
+    pub fn split(kind: u8, payload: &[u8]) -> Vec<Self> {
+        let chunks: Vec<&[u8]> = payload.chunks(PACKET_DATA_LEN).collect();
+        let cnt = chunks.len() as u8;
+        chunks.iter().enumerate().map(|(i, chunk)| Self::new(kind, i as u8 + 1, cnt, chunk)).collect()
+    }
+
+    pub fn encode(&self) -> [u8; PACKET_LEN] {
+        let mut buf = [0u8; PACKET_LEN];
+        buf[..2].copy_from_slice(&PACKET_HEAD);
+        buf[2] = self.kind;
+        buf[3] = self.opt;
+        buf[4] = self.idx;
+        buf[5] = self.cnt;
+        buf[6..38].copy_from_slice(&self.data);
+        buf[38..].copy_from_slice(&crc16(&self.data).to_be_bytes());
+        buf
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, String> {
+        if buf.len() < PACKET_LEN {
+            return Err(format!("short packet: {} bytes", buf.len()));
+        }
+        if buf[..2] != PACKET_HEAD {
+            return Err(format!("bad packet head {:02x?}", &buf[..2]));
+        }
+        let mut data = [0u8; PACKET_DATA_LEN];
+        data.copy_from_slice(&buf[6..38]);
+        let expected = u16::from_be_bytes([buf[38], buf[39]]);
+        let actual = crc16(&data);
+        if expected != actual {
+            return Err(format!("crc mismatch: expected {:04x}, got {:04x}", expected, actual));
+        }
+        Ok(Self { kind: buf[2], opt: buf[3], idx: buf[4], cnt: buf[5], data })
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        u32::from_be_bytes([self.data[offset], self.data[offset + 1], self.data[offset + 2], self.data[offset + 3]])
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_be_bytes([self.data[offset], self.data[offset + 1]])
+    }
+}
+
+/// 把包封装成 AUC 转发帧：命令、IIC 地址、包长度、保留字节
+pub fn auc_frame(iic_addr: u8, packet: &AvalonPacket) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(AUC_HEADER_LEN + PACKET_LEN);
+    frame.extend_from_slice(&[AUC_CMD_XFER, iic_addr, PACKET_LEN as u8, 0]);
+    frame.extend_from_slice(&packet.encode());
+    frame
+}
+
+/// `STATUS` 包中的模块状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleStatus {
+    pub temperature: f32,
+    pub fan_rpm: u32,
+    /// 各 PLL 实际频率 (MHz)
+    pub pll: [u32; PLL_COUNT],
+    pub hardware_errors: u32,
+}
+
+impl ModuleStatus {
+    fn parse(packet: &AvalonPacket) -> Self {
+        let mut pll = [0u32; PLL_COUNT];
+        for (i, freq) in pll.iter_mut().enumerate() {
+            *freq = packet.u16_at(8 + i * 2) as u32;
+        }
+        Self {
+            // 温度单位 0.1°C
+            temperature: packet.u16_at(0) as f32 / 10.0,
+            fan_rpm: packet.u32_at(2) & 0x00ff_ffff,
+            pll,
+            hardware_errors: packet.u32_at(16),
+        }
+    }
+}
+
+/// 一个 MM 模块 (一条链)
+pub struct AvalonChain {
+    device_id: u32,
+    chain_index: u8,
+    iic_addr: u8,
+    info: DeviceInfo,
+    hardware: Arc<dyn HardwareInterface>,
+    profile: AvalonChainProfile,
+    /// 固定风扇转速 (%)，为空时由应用层风扇控制决定
+    fan_speed: Option<u32>,
+    /// 按作业序号记录下发的工作，模块返回 nonce 时带回序号
+    jobs: [Option<(Uuid, f64)>; 256],
+    next_job: u8,
+    last_status: Option<ModuleStatus>,
+    stats: DeviceStats,
+    /// 按 nonce 包中的芯片号统计
+    chips: ChipTelemetry,
+    started_at: Option<Instant>,
+}
+
+impl AvalonChain {
+    pub fn new(
+        chain_index: u8,
+        model: AvalonModel,
+        profile: AvalonChainProfile,
+        fan_speed: Option<u32>,
+        hardware: Arc<dyn HardwareInterface>,
+    ) -> Self {
+        let device_id = AVALON_DEVICE_ID_BASE + chain_index as u32;
+        let mut info = DeviceInfo::new(
+            device_id,
+            format!("Avalon {} MM{}", model.name(), chain_index),
+            "avalon".to_string(),
+            chain_index,
+        );
+        info.chip_count = model.chips_per_chain();
+        info.frequency = profile.pll.iter().copied().max();
+        info.voltage = Some(profile.voltage);
+
+        Self {
+            device_id,
+            chain_index,
+            iic_addr: MM_IIC_ADDR_BASE + chain_index,
+            info,
+            hardware,
+            profile,
+            fan_speed,
+            jobs: [None; 256],
+            next_job: 0,
+            last_status: None,
+            stats: DeviceStats::new(),
+            chips: ChipTelemetry::new(model.chips_per_chain()),
+            started_at: None,
+        }
+    }
+
+    /// 为 `[cores.avalon]` 中每条链创建设备，未单独配置的链使用型号默认频率
+    pub fn create_all(config: &AvalonCoreConfig, hardware: Arc<dyn HardwareInterface>) -> Vec<AvalonChain> {
+        (0..config.chain_count as u8)
+            .map(|index| {
+                let profile = config.profile_for(index);
+                AvalonChain::new(index, config.model, profile, config.fan_speed, hardware.clone())
+            })
+            .collect()
+    }
+
+    fn comm_error(&self, error: impl Into<String>) -> DeviceError {
+        DeviceError::CommunicationError { device_id: self.device_id, error: error.into() }
+    }
+
+    /// 发送一个包并读回模块响应
+    async fn transfer(&self, packet: &AvalonPacket) -> Result<AvalonPacket, DeviceError> {
+        self.hardware.uart_write(self.chain_index, &auc_frame(self.iic_addr, packet)).await?;
+        let response = self.hardware.uart_read(self.chain_index, AUC_HEADER_LEN + PACKET_LEN).await?;
+        if response.len() < AUC_HEADER_LEN + PACKET_LEN {
+            return Err(self.comm_error(format!("short AUC response: {} bytes", response.len())));
+        }
+        AvalonPacket::decode(&response[AUC_HEADER_LEN..]).map_err(|e| self.comm_error(e))
+    }
+
+    /// 发送一个不关心响应内容的包
+    async fn send(&self, packet: &AvalonPacket) -> Result<(), DeviceError> {
+        self.transfer(packet).await.map(|_| ())
+    }
+
+    async fn apply_pll(&self) -> Result<(), DeviceError> {
+        let mut payload = Vec::with_capacity(PLL_COUNT * 4);
+        for freq in &self.profile.pll {
+            payload.extend_from_slice(&freq.to_be_bytes());
+        }
+        self.send(&AvalonPacket::new(packet_type::SET_PLL, 1, 1, &payload)).await
+    }
+
+    async fn apply_voltage(&self) -> Result<(), DeviceError> {
+        let payload = self.profile.voltage.to_be_bytes();
+        self.send(&AvalonPacket::new(packet_type::SET_VOLT, 1, 1, &payload)).await
+    }
+
+    async fn apply_fan(&self, percent: u32) -> Result<(), DeviceError> {
+        let pwm = PWM_MAX * percent.min(100) / 100;
+        self.send(&AvalonPacket::new(packet_type::SET, 1, 1, &pwm.to_be_bytes())).await?;
+        self.send(&AvalonPacket::new(packet_type::SET_FIN, 1, 1, &[])).await
+    }
+
+    fn record_status(&mut self, status: ModuleStatus) {
+        self.info.update_temperature(status.temperature);
+        self.stats.record_temperature(status.temperature);
+        self.info.hardware_errors = status.hardware_errors as u64;
+        self.stats.hardware_errors = status.hardware_errors as u64;
+        self.info.frequency = status.pll.iter().copied().max();
+        self.last_status = Some(status);
+    }
+}
+
+#[async_trait]
+impl MiningDevice for AvalonChain {
+    fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    async fn get_info(&self) -> Result<DeviceInfo, DeviceError> {
+        let mut info = self.info.clone();
+        if let Some(started) = self.started_at {
+            info.uptime = started.elapsed();
+        }
+        Ok(info)
+    }
+
+    async fn initialize(&mut self, config: DeviceConfig) -> Result<(), DeviceError> {
+        self.info.update_status(DeviceStatus::Initializing);
+
+        let ack = self.transfer(&AvalonPacket::new(packet_type::DETECT, 1, 1, &[])).await?;
+        if ack.kind != packet_type::ACKDETECT {
+            let reason = format!("MM{} did not acknowledge detect (type {:02x})", self.chain_index, ack.kind);
+            self.info.update_status(DeviceStatus::Error(reason.clone()));
+            return Err(DeviceError::InitializationFailed { device_id: self.device_id, reason });
+        }
+        // ACKDETECT 数据前 8 字节为模块 DNA，后面是以 NUL 结尾的固件版本
+        let version_end = ack.data[8..].iter().position(|&b| b == 0).map_or(PACKET_DATA_LEN, |p| p + 8);
+        let version = String::from_utf8_lossy(&ack.data[8..version_end]).to_string();
+        let dna = hex::encode(&ack.data[..8]);
+        info!("⛏️ Avalon MM{} detected, DNA {}, firmware {}", self.chain_index, dna, version);
+        // 模块 DNA 唯一，用作序列号
+        self.info.serial_number = Some(dna);
+        self.info.firmware_version = Some(version);
+
+        self.send(&AvalonPacket::new(packet_type::RSTMMTX, 1, 1, &[])).await?;
+        self.apply_voltage().await?;
+        self.apply_pll().await?;
+        if let Some(speed) = self.fan_speed.or(config.fan_speed) {
+            self.apply_fan(speed).await?;
+            self.info.fan_speed = Some(speed);
+        }
+
+        self.info.update_status(DeviceStatus::Idle);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<(), DeviceError> {
+        self.started_at = Some(Instant::now());
+        self.info.update_status(DeviceStatus::Mining);
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), DeviceError> {
+        // 清空模块的作业队列
+        self.send(&AvalonPacket::new(packet_type::RSTMMTX, 1, 1, &[])).await?;
+        self.jobs = [None; 256];
+        self.info.update_status(DeviceStatus::Idle);
+        Ok(())
+    }
+
+    async fn restart(&mut self) -> Result<(), DeviceError> {
+        self.info.update_status(DeviceStatus::Restarting);
+        self.stats.record_restart();
+        self.initialize(DeviceConfig::default()).await?;
+        self.start().await
+    }
+
+    async fn submit_work(&mut self, work: Arc<Work>) -> Result<(), DeviceError> {
+        let job = self.next_job;
+        self.next_job = self.next_job.wrapping_add(1);
+
+        self.send(&AvalonPacket::new(packet_type::JOB_ID, 1, 1, &[job])).await?;
+        for packet in AvalonPacket::split(packet_type::HEADER, &work.header) {
+            self.send(&packet).await?;
+        }
+        self.send(&AvalonPacket::new(packet_type::TARGET, 1, 1, &work.target)).await?;
+        self.send(&AvalonPacket::new(packet_type::JOB_FIN, 1, 1, &[job])).await?;
+
+        self.jobs[job as usize] = Some((work.id, work.difficulty));
+        debug!("Avalon MM{} job {} <- work {}", self.chain_index, job, work.id);
+        Ok(())
+    }
+
+    async fn get_result(&mut self) -> Result<Option<MiningResult>, DeviceError> {
+        let response = self.transfer(&AvalonPacket::new(packet_type::POLLING, 1, 1, &[])).await?;
+        match response.kind {
+            packet_type::NONCE => {
+                // 数据: 作业序号(1) + 芯片号(1) + 保留(2) + nonce(4)
+                let job = response.data[0];
+                let chip = response.data[1];
+                let nonce = response.u32_at(4);
+                match self.jobs[job as usize] {
+                    Some((work_id, difficulty)) => {
+                        self.chips.record_nonce(chip);
+                        self.stats.record_valid_nonce();
+                        self.stats.record_hash((difficulty * HASHES_PER_DIFF1) as u64);
+                        self.info.increment_accepted_shares();
+                        if let Some(started) = self.started_at {
+                            let elapsed = started.elapsed().as_secs_f64().max(1.0);
+                            self.info.update_hashrate(self.stats.total_hashes as f64 / elapsed);
+                        }
+                        Ok(Some(nonce_result(work_id, self.device_id, nonce, difficulty, true)))
+                    }
+                    None => {
+                        self.chips.record_error(chip);
+                        warn!("Avalon MM{} returned nonce {:08x} for unknown job {}", self.chain_index, nonce, job);
+                        Ok(None)
+                    }
+                }
+            }
+            packet_type::STATUS => {
+                self.record_status(ModuleStatus::parse(&response));
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_status(&self) -> Result<DeviceStatus, DeviceError> {
+        Ok(self.info.status.clone())
+    }
+
+    async fn get_temperature(&self) -> Result<f32, DeviceError> {
+        self.last_status
+            .as_ref()
+            .map(|status| status.temperature)
+            .ok_or_else(|| self.comm_error("no status reported yet"))
+    }
+
+    async fn get_hashrate(&self) -> Result<f64, DeviceError> {
+        Ok(self.info.hashrate)
+    }
+
+    async fn get_stats(&self) -> Result<DeviceStats, DeviceError> {
+        let mut stats = self.stats.clone();
+        stats.uptime_seconds = self.started_at.map(|s| s.elapsed().as_secs()).unwrap_or(0);
+        Ok(stats)
+    }
+
+    async fn set_frequency(&mut self, frequency: u32) -> Result<(), DeviceError> {
+        self.profile.pll = vec![frequency; PLL_COUNT];
+        self.apply_pll().await?;
+        self.info.frequency = Some(frequency);
+        Ok(())
+    }
+
+    async fn set_voltage(&mut self, voltage: u32) -> Result<(), DeviceError> {
+        self.profile.voltage = voltage;
+        self.apply_voltage().await?;
+        self.info.voltage = Some(voltage);
+        Ok(())
+    }
+
+    async fn set_fan_speed(&mut self, speed: u32) -> Result<(), DeviceError> {
+        self.apply_fan(speed).await?;
+        self.info.fan_speed = Some(speed);
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<bool, DeviceError> {
+        Ok(!matches!(self.info.status, DeviceStatus::Error(_)))
+    }
+
+    async fn reset_stats(&mut self) -> Result<(), DeviceError> {
+        self.stats = DeviceStats::new();
+        self.chips = ChipTelemetry::new(self.info.chip_count);
+        Ok(())
+    }
+
+    async fn get_chip_stats(&self) -> Result<Vec<ChipStats>, DeviceError> {
+        Ok(self.chips.snapshot())
+    }
+}
+
+impl AvalonModel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AvalonModel::A12 => "A12",
+            AvalonModel::A13 => "A13",
+        }
+    }
+
+    /// 每个 MM 模块的芯片数
+    pub fn chips_per_chain(&self) -> u32 {
+        match self {
+            AvalonModel::A12 => 120,
+            AvalonModel::A13 => 130,
+        }
+    }
+
+    /// 出厂默认的 PLL 频率 (MHz) 和电压 (mV)
+    pub fn default_profile(&self) -> (Vec<u32>, u32) {
+        match self {
+            AvalonModel::A12 => (vec![625, 650, 675, 700], 1200),
+            AvalonModel::A13 => (vec![650, 675, 700, 725], 1150),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_round_trip() {
+        let packet = AvalonPacket::new(packet_type::SET_PLL, 1, 1, &[0, 0, 2, 0x71]);
+        let encoded = packet.encode();
+        assert_eq!(&encoded[..2], b"CN");
+        assert_eq!(encoded.len(), PACKET_LEN);
+        assert_eq!(AvalonPacket::decode(&encoded).unwrap(), packet);
+
+        let mut corrupted = encoded;
+        corrupted[10] ^= 0xff;
+        assert!(AvalonPacket::decode(&corrupted).unwrap_err().starts_with("crc mismatch"));
+    }
+
+    #[test]
+    fn test_header_split_and_auc_frame() {
+        let packets = AvalonPacket::split(packet_type::HEADER, &[0xab; 80]);
+        assert_eq!(packets.len(), 3);
+        assert_eq!((packets[2].idx, packets[2].cnt), (3, 3));
+        assert_eq!(&packets[2].data[..16], &[0xab; 16]);
+        assert_eq!(&packets[2].data[16..], &[0u8; 16]);
+
+        let frame = auc_frame(0x11, &packets[0]);
+        assert_eq!(&frame[..4], &[AUC_CMD_XFER, 0x11, PACKET_LEN as u8, 0]);
+        assert_eq!(frame.len(), AUC_HEADER_LEN + PACKET_LEN);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let mut payload = vec![0x02, 0xd5, 0x00, 0x00, 0x0f, 0xa0, 0x00, 0x00];
+        for freq in [650u16, 675, 700, 725] {
+            payload.extend_from_slice(&freq.to_be_bytes());
+        }
+        payload.extend_from_slice(&7u32.to_be_bytes());
+        let status = ModuleStatus::parse(&AvalonPacket::new(packet_type::STATUS, 1, 1, &payload));
+        assert_eq!(status.temperature, 72.5);
+        assert_eq!(status.fan_rpm, 4000);
+        assert_eq!(status.pll, [650, 675, 700, 725]);
+        assert_eq!(status.hardware_errors, 7);
+    }
+}
@@ -1,12 +1,59 @@
 use crate::error::DeviceError;
+use crate::device::chips::{ChipStats, ChipTelemetry};
 use crate::device::traits::{ChainController, ChainStatus, HardwareInterface};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::{sleep, timeout};
 use tracing::{info, warn, debug};
 
+/// 链恢复配置 - CRC 错误率超过阈值时硬件复位链、重新枚举芯片并重新分配 nonce 区间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChainRecoveryConfig {
+    pub enabled: bool,
+    /// 触发恢复的 CRC 错误率 (%)
+    pub crc_error_threshold: f64,
+    /// 计算错误率前至少需要的响应数
+    pub min_samples: u32,
+    /// 链复位 GPIO 引脚，未设置时只发送软复位命令
+    pub reset_gpio: Option<u32>,
+    /// 复位脉冲宽度 (毫秒)
+    pub reset_pulse_ms: u64,
+    /// 两次恢复之间的最短间隔 (秒)
+    pub cooldown_secs: u64,
+}
+
+impl Default for ChainRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            crc_error_threshold: 5.0,
+            min_samples: 200,
+            reset_gpio: None,
+            reset_pulse_ms: 100,
+            cooldown_secs: 60,
+        }
+    }
+}
+
+/// 把 32 位 nonce 空间平均分给各芯片，返回每个芯片的 [起始, 结束] 区间
+pub fn nonce_ranges(chip_count: u32) -> Vec<(u32, u32)> {
+    if chip_count == 0 {
+        return Vec::new();
+    }
+    let span = (u32::MAX as u64 + 1) / chip_count as u64;
+    (0..chip_count as u64)
+        .map(|i| {
+            let start = i * span;
+            let end = if i + 1 == chip_count as u64 { u32::MAX as u64 } else { start + span - 1 };
+            (start as u32, end as u32)
+        })
+        .collect()
+}
+
 /// ASIC 链控制器实现
 pub struct AsicChainController {
     /// 链ID
@@ -33,6 +80,18 @@ pub struct AsicChainController {
     error_count: Arc<RwLock<u32>>,
     /// 重置计数
     reset_count: Arc<RwLock<u32>>,
+    /// 链恢复配置
+    recovery: ChainRecoveryConfig,
+    /// 自上次恢复以来收到的响应数和其中的 CRC 错误数
+    crc_samples: Arc<RwLock<(u32, u32)>>,
+    /// 各芯片的 nonce 区间
+    nonce_ranges: Arc<RwLock<Vec<(u32, u32)>>>,
+    /// 上次恢复时间
+    last_recovery: Arc<RwLock<Option<Instant>>>,
+    /// 恢复次数
+    recovery_count: Arc<RwLock<u32>>,
+    /// 各芯片的 nonce、错误和温度
+    chip_telemetry: Arc<RwLock<ChipTelemetry>>,
 }
 
 impl AsicChainController {
@@ -51,7 +110,140 @@ impl AsicChainController {
             result_queue: Arc::new(Mutex::new(Vec::new())),
             error_count: Arc::new(RwLock::new(0)),
             reset_count: Arc::new(RwLock::new(0)),
+            recovery: ChainRecoveryConfig::default(),
+            crc_samples: Arc::new(RwLock::new((0, 0))),
+            nonce_ranges: Arc::new(RwLock::new(Vec::new())),
+            last_recovery: Arc::new(RwLock::new(None)),
+            recovery_count: Arc::new(RwLock::new(0)),
+            chip_telemetry: Arc::new(RwLock::new(ChipTelemetry::default())),
+        }
+    }
+
+    /// 设置链恢复配置
+    pub fn with_recovery(mut self, recovery: ChainRecoveryConfig) -> Self {
+        self.recovery = recovery;
+        self
+    }
+
+    /// 检查响应的同步头，不匹配时计为 CRC 错误
+    async fn check_response(&self, response: &[u8], min_len: usize) -> bool {
+        let valid = response.len() >= min_len && response[0] == 0x55 && response[1] == 0xAA;
+        let mut samples = self.crc_samples.write().await;
+        samples.0 += 1;
+        if !valid {
+            samples.1 += 1;
+        }
+        valid
+    }
+
+    /// 自上次恢复以来的 CRC 错误率 (%)
+    pub async fn crc_error_rate(&self) -> f64 {
+        let (total, errors) = *self.crc_samples.read().await;
+        if total == 0 {
+            0.0
+        } else {
+            errors as f64 / total as f64 * 100.0
+        }
+    }
+
+    /// CRC 错误率超过阈值且不在冷却期内时需要恢复
+    async fn needs_recovery(&self) -> bool {
+        if !self.recovery.enabled {
+            return false;
+        }
+        let (total, _) = *self.crc_samples.read().await;
+        if total < self.recovery.min_samples {
+            return false;
+        }
+        if let Some(last) = *self.last_recovery.read().await {
+            if last.elapsed() < Duration::from_secs(self.recovery.cooldown_secs) {
+                return false;
+            }
+        }
+        self.crc_error_rate().await > self.recovery.crc_error_threshold
+    }
+
+    /// 把 nonce 空间重新分配给当前检测到的芯片
+    async fn assign_nonce_ranges(&self, chip_count: u32) -> Result<(), DeviceError> {
+        let ranges = nonce_ranges(chip_count);
+        for (chip_id, (start, end)) in ranges.iter().enumerate() {
+            let mut command = vec![
+                0x55, 0xAA, // 同步头
+                0x58, // nonce 区间命令
+                chip_id as u8, // 芯片ID
+            ];
+            command.extend_from_slice(&start.to_be_bytes());
+            command.extend_from_slice(&end.to_be_bytes());
+            self.send_command(&command).await?;
         }
+        *self.nonce_ranges.write().await = ranges;
+        Ok(())
+    }
+
+    /// 恢复链：硬件复位、重新枚举芯片、重新配置并重新分配 nonce 区间
+    pub async fn recover(&self) -> Result<u32, DeviceError> {
+        let error_rate = self.crc_error_rate().await;
+        warn!("Recovering chain {} (CRC error rate {:.1}%)", self.chain_id, error_rate);
+
+        *self.status.write().await = ChainStatus::Initializing;
+        *self.last_recovery.write().await = Some(Instant::now());
+        *self.recovery_count.write().await += 1;
+        *self.reset_count.write().await += 1;
+        self.work_queue.lock().await.clear();
+        self.result_queue.lock().await.clear();
+
+        match self.recovery.reset_gpio {
+            Some(pin) => {
+                // 复位线低有效
+                self.hardware.gpio_set(pin, false).await?;
+                sleep(Duration::from_millis(self.recovery.reset_pulse_ms)).await;
+                self.hardware.gpio_set(pin, true).await?;
+            }
+            None => {
+                let chip_count = *self.chip_count.read().await;
+                for chip_id in 0..chip_count as u8 {
+                    let _ = self.reset_chip(chip_id).await;
+                }
+            }
+        }
+        sleep(Duration::from_millis(500)).await;
+
+        let previous = *self.chip_count.read().await;
+        let chip_count = self.detect_chips().await?;
+        *self.chip_count.write().await = chip_count;
+        if chip_count == 0 {
+            *self.status.write().await = ChainStatus::Error("No chips detected after recovery".to_string());
+            return Err(DeviceError::ChainError {
+                chain_id: self.chain_id,
+                error: "No chips detected after recovery".to_string(),
+            });
+        }
+        if chip_count != previous {
+            warn!("Chain {} re-enumerated {} chips (was {})", self.chain_id, chip_count, previous);
+        }
+
+        for chip_id in 0..chip_count as u8 {
+            if let Err(e) = self.configure_chip(chip_id).await {
+                warn!("Failed to configure chip {} on chain {}: {}", chip_id, self.chain_id, e);
+            }
+        }
+        self.assign_nonce_ranges(chip_count).await?;
+        self.chip_telemetry.write().await.resize(chip_count);
+
+        *self.crc_samples.write().await = (0, 0);
+        *self.status.write().await = ChainStatus::Idle;
+        info!("Chain {} recovered with {} chips", self.chain_id, chip_count);
+        Ok(chip_count)
+    }
+
+    /// 获取恢复次数
+    pub async fn get_recovery_count(&self) -> u32 {
+        *self.recovery_count.read().await
+    }
+
+    /// 获取各芯片统计
+    pub async fn get_chip_stats(&self) -> Vec<ChipStats> {
+        self.chip_telemetry.read().await.snapshot()
     }
 
     /// 发送命令到链
@@ -94,7 +286,7 @@ impl AsicChainController {
         match self.send_command(&command).await {
             Ok(response) => {
                 // 检查响应是否有效
-                if response.len() >= 4 && response[0] == 0x55 && response[1] == 0xAA {
+                if self.check_response(&response, 4).await {
                     debug!("Chip {} detected on chain {}", chip_id, self.chain_id);
                     Ok(true)
                 } else {
@@ -177,7 +369,7 @@ impl AsicChainController {
 
         match self.send_command(&command).await {
             Ok(response) => {
-                if response.len() >= 8 && response[0] == 0x55 && response[1] == 0xAA {
+                if self.check_response(&response, 8).await {
                     // 解析nonce和工作ID
                     let nonce = u32::from_be_bytes([response[4], response[5], response[6], response[7]]);
                     let work_id = response[3];
@@ -185,8 +377,11 @@ impl AsicChainController {
                     if nonce != 0 {
                         debug!("Result from chip {} on chain {}: nonce={:08x}, work_id={}",
                                chip_id, self.chain_id, nonce, work_id);
+                        self.chip_telemetry.write().await.record_nonce(chip_id);
                         return Ok(Some((nonce, work_id)));
                     }
+                } else {
+                    self.chip_telemetry.write().await.record_error(chip_id);
                 }
                 Ok(None)
             }
@@ -226,12 +421,13 @@ impl AsicChainController {
 
         match self.send_command(&command).await {
             Ok(response) => {
-                if response.len() >= 6 && response[0] == 0x55 && response[1] == 0xAA {
+                if self.check_response(&response, 6).await {
                     // 解析温度数据（假设为16位整数，单位0.1°C）
                     let temp_raw = u16::from_be_bytes([response[4], response[5]]);
                     let temperature = temp_raw as f32 / 10.0;
 
                     debug!("Temperature from chip {} on chain {}: {:.1}°C", chip_id, self.chain_id, temperature);
+                    self.chip_telemetry.write().await.record_temperature(chip_id, temperature);
                     Ok(temperature)
                 } else {
                     Err(DeviceError::CommunicationError {
@@ -309,6 +505,11 @@ impl ChainController for AsicChainController {
             }
         }
 
+        self.assign_nonce_ranges(chip_count).await?;
+        *self.chip_telemetry.write().await = ChipTelemetry::new(chip_count);
+
+        // 枚举时链尾不存在的芯片不计入错误率
+        *self.crc_samples.write().await = (0, 0);
         *self.status.write().await = ChainStatus::Idle;
         info!("Chain {} initialized with {} chips", self.chain_id, chip_count);
         Ok(())
@@ -414,6 +615,11 @@ impl ChainController for AsicChainController {
             return Ok(None);
         }
 
+        // CRC 错误过多时先恢复链，而不是继续累积硬件错误
+        if self.needs_recovery().await {
+            self.recover().await?;
+        }
+
         // 检查结果队列
         let mut result_queue = self.result_queue.lock().await;
         if let Some(result) = result_queue.pop() {
@@ -516,3 +722,21 @@ impl ChainController for AsicChainController {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonce_ranges_cover_space() {
+        let ranges = nonce_ranges(3);
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[2].1, u32::MAX);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].1 as u64 + 1, pair[1].0 as u64);
+        }
+        assert_eq!(nonce_ranges(1), vec![(0, u32::MAX)]);
+        assert!(nonce_ranges(0).is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! 芯片级遥测和坏芯片表
+//!
+//! 链驱动按芯片统计 nonce 数、错误数和温度；设备管理器定期把错误率超过阈值的芯片
+//! 记入坏芯片表并写入文件，重启后仍然保留，供维修时定位需要返修的芯片。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::SystemTime;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// 芯片健康配置 (`[devices.chip_health]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChipHealthConfig {
+    /// 是否维护坏芯片表
+    pub enabled: bool,
+    /// 判定为坏芯片的错误率 (%)
+    pub error_rate_threshold: f64,
+    /// 判定前至少需要的样本数 (nonce + 错误)
+    pub min_samples: u64,
+    /// 坏芯片表文件
+    pub map_file: String,
+}
+
+impl Default for ChipHealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            error_rate_threshold: 10.0,
+            min_samples: 100,
+            map_file: "./data/bad-chips.json".to_string(),
+        }
+    }
+}
+
+impl ChipHealthConfig {
+    /// 验证芯片健康配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.error_rate_threshold <= 0.0 || self.error_rate_threshold > 100.0 {
+            anyhow::bail!("Chip error_rate_threshold must be within (0, 100] %");
+        }
+        if self.enabled && self.map_file.is_empty() {
+            anyhow::bail!("Chip map_file must not be empty");
+        }
+        Ok(())
+    }
+}
+
+/// 单个芯片的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChipStats {
+    pub chip_id: u8,
+    /// 有效 nonce 数
+    pub nonces: u64,
+    /// 错误数 (CRC 错误、无效 nonce)
+    pub errors: u64,
+    pub temperature: Option<f32>,
+}
+
+impl ChipStats {
+    /// 错误率 (%)
+    pub fn error_rate(&self) -> f64 {
+        let total = self.nonces + self.errors;
+        if total == 0 {
+            return 0.0;
+        }
+        self.errors as f64 / total as f64 * 100.0
+    }
+}
+
+/// 一条链上各芯片的计数器
+#[derive(Debug, Clone, Default)]
+pub struct ChipTelemetry {
+    chips: Vec<ChipStats>,
+}
+
+impl ChipTelemetry {
+    pub fn new(chip_count: u32) -> Self {
+        let mut telemetry = Self::default();
+        telemetry.resize(chip_count);
+        telemetry
+    }
+
+    /// 芯片数量变化时（重新枚举后）调整，已有芯片的计数保留
+    pub fn resize(&mut self, chip_count: u32) {
+        let chip_count = chip_count.min(256) as usize;
+        self.chips.truncate(chip_count);
+        while self.chips.len() < chip_count {
+            let chip_id = self.chips.len() as u8;
+            self.chips.push(ChipStats { chip_id, ..Default::default() });
+        }
+    }
+
+    fn chip_mut(&mut self, chip_id: u8) -> Option<&mut ChipStats> {
+        self.chips.get_mut(chip_id as usize)
+    }
+
+    pub fn record_nonce(&mut self, chip_id: u8) {
+        if let Some(chip) = self.chip_mut(chip_id) {
+            chip.nonces += 1;
+        }
+    }
+
+    pub fn record_error(&mut self, chip_id: u8) {
+        if let Some(chip) = self.chip_mut(chip_id) {
+            chip.errors += 1;
+        }
+    }
+
+    pub fn record_temperature(&mut self, chip_id: u8, temperature: f32) {
+        if let Some(chip) = self.chip_mut(chip_id) {
+            chip.temperature = Some(temperature);
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<ChipStats> {
+        self.chips.clone()
+    }
+}
+
+/// 错误率超过阈值的芯片
+pub fn find_bad_chips(chips: &[ChipStats], config: &ChipHealthConfig) -> Vec<u8> {
+    chips
+        .iter()
+        .filter(|chip| chip.nonces + chip.errors >= config.min_samples)
+        .filter(|chip| chip.error_rate() >= config.error_rate_threshold)
+        .map(|chip| chip.chip_id)
+        .collect()
+}
+
+/// 按板卡标识保存的坏芯片表
+pub struct BadChipMap {
+    path: String,
+    boards: Mutex<HashMap<String, BTreeSet<u8>>>,
+}
+
+impl BadChipMap {
+    /// 读取坏芯片表，文件不存在或损坏时从空开始
+    pub fn load(path: &str) -> Self {
+        let boards = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable bad chip map {}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self { path: path.to_string(), boards: Mutex::new(boards) }
+    }
+
+    pub async fn get(&self, board: &str) -> BTreeSet<u8> {
+        self.boards.lock().await.get(board).cloned().unwrap_or_default()
+    }
+
+    /// 记录坏芯片，返回新增的芯片；有新增时写回文件
+    pub async fn mark(&self, board: &str, chips: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut boards = self.boards.lock().await;
+        let entry = boards.entry(board.to_string()).or_default();
+        let added: Vec<u8> = chips.iter().copied().filter(|chip| entry.insert(*chip)).collect();
+        if !added.is_empty() {
+            if let Some(parent) = Path::new(&self.path).parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&self.path, serde_json::to_string_pretty(&*boards)?)?;
+        }
+        Ok(added)
+    }
+}
+
+/// 热力图中的一个芯片
+#[derive(Debug, Clone, Serialize)]
+pub struct ChipCell {
+    pub chip_id: u8,
+    pub nonces: u64,
+    pub errors: u64,
+    /// 错误率 (%)
+    pub error_rate: f64,
+    pub temperature: Option<f32>,
+    /// 在坏芯片表中
+    pub bad: bool,
+}
+
+/// 一条链的芯片热力图
+#[derive(Debug, Clone, Serialize)]
+pub struct ChipHeatmap {
+    pub device_id: u32,
+    pub board: String,
+    pub chips: Vec<ChipCell>,
+    pub updated_at: SystemTime,
+}
+
+impl ChipHeatmap {
+    pub fn new(device_id: u32, board: String, chips: &[ChipStats], bad: &BTreeSet<u8>) -> Self {
+        let chips = chips
+            .iter()
+            .map(|chip| ChipCell {
+                chip_id: chip.chip_id,
+                nonces: chip.nonces,
+                errors: chip.errors,
+                error_rate: chip.error_rate(),
+                temperature: chip.temperature,
+                bad: bad.contains(&chip.chip_id),
+            })
+            .collect();
+        Self { device_id, board, chips, updated_at: SystemTime::now() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_bad_chips() {
+        let mut telemetry = ChipTelemetry::new(3);
+        for _ in 0..90 {
+            telemetry.record_nonce(0);
+            telemetry.record_nonce(1);
+        }
+        for _ in 0..20 {
+            telemetry.record_error(1);
+            telemetry.record_error(2);
+        }
+        // 超出范围的芯片号被忽略
+        telemetry.record_error(7);
+
+        let config = ChipHealthConfig::default();
+        // 芯片 2 错误率 100% 但样本不足
+        assert_eq!(find_bad_chips(&telemetry.snapshot(), &config), vec![1]);
+    }
+
+    #[test]
+    fn test_resize_keeps_counts() {
+        let mut telemetry = ChipTelemetry::new(2);
+        telemetry.record_nonce(1);
+        telemetry.resize(4);
+        let chips = telemetry.snapshot();
+        assert_eq!(chips.len(), 4);
+        assert_eq!(chips[1].nonces, 1);
+        assert_eq!(chips[3].chip_id, 3);
+    }
+
+    #[tokio::test]
+    async fn test_bad_chip_map_persists() {
+        let path = std::env::temp_dir().join(format!("cgminer-bad-chips-{}.json", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+
+        let map = BadChipMap::load(path);
+        assert_eq!(map.mark("board#chain0", &[3, 5]).await.unwrap(), vec![3, 5]);
+        assert!(map.mark("board#chain0", &[3]).await.unwrap().is_empty());
+
+        let reloaded = BadChipMap::load(path);
+        assert_eq!(reloaded.get("board#chain0").await, BTreeSet::from([3, 5]));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -15,6 +15,9 @@ pub fn convert_core_to_device_info(core_info: cgminer_core::DeviceInfo) -> Devic
         device_type: core_info.device_type,
         chain_id: core_info.chain_id,
         chip_count: core_info.chip_count.unwrap_or(0), // Option<u32> -> u32
+        serial_number: core_info.serial_number,
+        firmware_version: core_info.firmware_version,
+        hardware_version: core_info.hardware_version,
         status: DeviceStatus::Uninitialized, // 默认状态，需要单独获取
         temperature: core_info.temperature,
         fan_speed: core_info.fan_speed,
@@ -39,9 +42,9 @@ pub fn convert_device_to_core_info(device_info: DeviceInfo) -> cgminer_core::Dev
         device_type: device_info.device_type,
         chain_id: device_info.chain_id,
         device_path: None,
-        serial_number: None,
-        firmware_version: None,
-        hardware_version: None,
+        serial_number: device_info.serial_number,
+        firmware_version: device_info.firmware_version,
+        hardware_version: device_info.hardware_version,
         chip_count: Some(device_info.chip_count), // u32 -> Option<u32>
         temperature: device_info.temperature,
         voltage: device_info.voltage,
@@ -88,15 +91,12 @@ pub fn convert_core_to_device_stats(core_stats: cgminer_core::DeviceStats) -> De
         valid_nonces: core_stats.accepted_work,
         invalid_nonces: core_stats.rejected_work,
         hardware_errors: core_stats.hardware_errors,
-        temperature_readings: if let Some(temp) = core_stats.temperature {
-            vec![temp.celsius]
-        } else {
-            Vec::new()
-        },
-        hashrate_history: vec![core_stats.current_hashrate.hashes_per_second],
+        temperature_readings: core_stats.temperature.map(|temp| temp.celsius).into_iter().collect(),
+        hashrate_history: std::iter::once(core_stats.current_hashrate.hashes_per_second).collect(),
         uptime_seconds: core_stats.uptime.as_secs(),
         restart_count: 0, // 默认值
         last_restart_time: None, // 默认值
+        throttle: None,
     }
 }
 
@@ -4,13 +4,14 @@
 
 use crate::error::DeviceError;
 use cgminer_core::{CoreInfo, DeviceInfo, CoreRegistry};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::info;
 
 /// 设备-核心映射信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DeviceCoreMapping {
     /// 设备ID
     pub device_id: u32,
@@ -277,7 +278,7 @@ impl DeviceCoreMapper {
 }
 
 /// 映射统计信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MappingStats {
     pub total_devices: usize,
     pub total_cores: usize,
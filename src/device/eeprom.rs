@@ -0,0 +1,139 @@
+//! 算力板 EEPROM 信息
+//!
+//! 每块算力板的 EEPROM 保存序列号、固件和硬件版本以及出厂校准的频率/电压。
+//! 序列号用作稳定的板卡标识（调优档位、坏芯片表按它保存），板卡换槽后仍能对应。
+//!
+//! 布局 (版本 1，多字节字段为大端)：
+//!
+//! | 偏移 | 长度 | 内容 |
+//! |------|------|------|
+//! | 0    | 1    | 布局版本 |
+//! | 1    | 16   | 序列号 (ASCII，NUL 填充) |
+//! | 17   | 16   | 固件版本 |
+//! | 33   | 8    | 硬件版本 |
+//! | 41   | 1    | 芯片数量 |
+//! | 42   | 2    | 校准频率 (MHz) |
+//! | 44   | 2    | 校准电压 (mV) |
+//! | 46   | 1    | 校验和：前 46 字节之和取反 |
+
+use serde::Serialize;
+
+/// EEPROM 信息区长度
+pub const EEPROM_LEN: usize = 47;
+
+/// 支持的布局版本
+const LAYOUT_VERSION: u8 = 1;
+
+/// 出厂校准数据
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoardCalibration {
+    pub chip_count: u32,
+    /// 校准频率 (MHz)
+    pub frequency: u32,
+    /// 校准电压 (mV)
+    pub voltage: u32,
+}
+
+/// 从 EEPROM 读出的板卡信息
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoardEeprom {
+    pub serial_number: String,
+    pub firmware_version: String,
+    pub hardware_version: String,
+    pub calibration: BoardCalibration,
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    !data.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+fn ascii_field(data: &[u8]) -> String {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).trim().to_string()
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u32 {
+    u16::from_be_bytes([data[offset], data[offset + 1]]) as u32
+}
+
+impl BoardEeprom {
+    /// 解析 EEPROM 内容
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.len() < EEPROM_LEN {
+            return Err(format!("EEPROM too short: {} bytes", data.len()));
+        }
+        if data[0] != LAYOUT_VERSION {
+            return Err(format!("unsupported EEPROM layout version {}", data[0]));
+        }
+        if checksum(&data[..EEPROM_LEN - 1]) != data[EEPROM_LEN - 1] {
+            return Err("EEPROM checksum mismatch".to_string());
+        }
+
+        let serial_number = ascii_field(&data[1..17]);
+        if serial_number.is_empty() {
+            return Err("EEPROM has no serial number".to_string());
+        }
+        Ok(Self {
+            serial_number,
+            firmware_version: ascii_field(&data[17..33]),
+            hardware_version: ascii_field(&data[33..41]),
+            calibration: BoardCalibration {
+                chip_count: data[41] as u32,
+                frequency: u16_at(data, 42),
+                voltage: u16_at(data, 44),
+            },
+        })
+    }
+
+    /// 编码为 EEPROM 内容（用于写入新板卡和测试）
+    pub fn encode(&self) -> Vec<u8> {
+        fn put(out: &mut [u8], value: &str) {
+            let bytes = value.as_bytes();
+            let len = bytes.len().min(out.len());
+            out[..len].copy_from_slice(&bytes[..len]);
+        }
+
+        let mut data = vec![0u8; EEPROM_LEN];
+        data[0] = LAYOUT_VERSION;
+        put(&mut data[1..17], &self.serial_number);
+        put(&mut data[17..33], &self.firmware_version);
+        put(&mut data[33..41], &self.hardware_version);
+        data[41] = self.calibration.chip_count.min(255) as u8;
+        data[42..44].copy_from_slice(&(self.calibration.frequency as u16).to_be_bytes());
+        data[44..46].copy_from_slice(&(self.calibration.voltage as u16).to_be_bytes());
+        data[EEPROM_LEN - 1] = checksum(&data[..EEPROM_LEN - 1]);
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> BoardEeprom {
+        BoardEeprom {
+            serial_number: "L7HB2304001234".to_string(),
+            firmware_version: "2.1.4".to_string(),
+            hardware_version: "HB-v3".to_string(),
+            calibration: BoardCalibration { chip_count: 120, frequency: 525, voltage: 870 },
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = sample().encode();
+        assert_eq!(data.len(), EEPROM_LEN);
+        assert_eq!(BoardEeprom::parse(&data).unwrap(), sample());
+    }
+
+    #[test]
+    fn test_rejects_corruption() {
+        let mut data = sample().encode();
+        data[5] ^= 0x01;
+        assert!(BoardEeprom::parse(&data).unwrap_err().contains("checksum"));
+
+        // 未烧录的 EEPROM 全为 0xff
+        assert!(BoardEeprom::parse(&[0xff; EEPROM_LEN]).is_err());
+        assert!(BoardEeprom::parse(&data[..10]).is_err());
+    }
+}
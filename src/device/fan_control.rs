@@ -0,0 +1,293 @@
+//! 风扇控制策略
+//!
+//! 根据设备温度区间计算风扇转速目标，通过 `MiningDevice::set_fan_speed` 下发到设备。
+//! 支持全局温度区间、按设备覆盖的区间，以及通过 API 设置的手动覆盖。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// 设备没有固件自动调速时，`Auto` 使用的固定转速 (%)
+pub const AUTO_FALLBACK_SPEED: u32 = 60;
+
+/// 风扇转速目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "FanSpeedRepr", into = "FanSpeedRepr")]
+pub enum FanSpeedTarget {
+    /// 交给设备/固件自行控制，没有自动调速的设备使用 `AUTO_FALLBACK_SPEED`
+    Auto,
+    /// 固定转速百分比 (0-100)
+    Percent(u32),
+}
+
+/// 配置文件中的转速表示：数字百分比或 "auto"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum FanSpeedRepr {
+    Percent(u32),
+    Keyword(String),
+}
+
+impl TryFrom<FanSpeedRepr> for FanSpeedTarget {
+    type Error = String;
+
+    fn try_from(repr: FanSpeedRepr) -> Result<Self, Self::Error> {
+        match repr {
+            FanSpeedRepr::Percent(p) if p <= 100 => Ok(FanSpeedTarget::Percent(p)),
+            FanSpeedRepr::Percent(p) => Err(format!("fan speed {} is out of range (0-100)", p)),
+            FanSpeedRepr::Keyword(k) if k.eq_ignore_ascii_case("auto") => Ok(FanSpeedTarget::Auto),
+            FanSpeedRepr::Keyword(k) => Err(format!("invalid fan speed '{}', expected 0-100 or \"auto\"", k)),
+        }
+    }
+}
+
+impl From<FanSpeedTarget> for FanSpeedRepr {
+    fn from(target: FanSpeedTarget) -> Self {
+        match target {
+            FanSpeedTarget::Auto => FanSpeedRepr::Keyword("auto".to_string()),
+            FanSpeedTarget::Percent(p) => FanSpeedRepr::Percent(p),
+        }
+    }
+}
+
+/// 温度区间：温度不高于 `max_temperature` 时使用 `speed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanBandConfig {
+    pub max_temperature: f32,
+    pub speed: FanSpeedTarget,
+}
+
+/// 单个设备的温度区间覆盖
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanDeviceOverride {
+    pub device_id: u32,
+    pub bands: Vec<FanBandConfig>,
+}
+
+/// 风扇控制配置 (`[devices.fan_control]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FanControlConfig {
+    /// 是否启用风扇策略
+    pub enabled: bool,
+    /// 全局温度区间
+    pub bands: Vec<FanBandConfig>,
+    /// 按设备ID覆盖的温度区间
+    pub device_overrides: Vec<FanDeviceOverride>,
+    /// 降档回滞 (°C)，避免在区间边界来回切换
+    pub hysteresis: f32,
+}
+
+impl Default for FanControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bands: vec![
+                FanBandConfig { max_temperature: 50.0, speed: FanSpeedTarget::Auto },
+                FanBandConfig { max_temperature: 70.0, speed: FanSpeedTarget::Percent(70) },
+                FanBandConfig { max_temperature: 80.0, speed: FanSpeedTarget::Percent(90) },
+                FanBandConfig { max_temperature: 200.0, speed: FanSpeedTarget::Percent(100) },
+            ],
+            device_overrides: Vec::new(),
+            hysteresis: 3.0,
+        }
+    }
+}
+
+impl FanControlConfig {
+    /// 验证风扇配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.hysteresis < 0.0 {
+            anyhow::bail!("Fan control hysteresis must not be negative");
+        }
+        if self.enabled && self.bands.is_empty() {
+            anyhow::bail!("Fan control is enabled but no temperature bands are configured");
+        }
+        Self::validate_bands("global", &self.bands)?;
+        for device in &self.device_overrides {
+            if device.bands.is_empty() {
+                anyhow::bail!("Fan control override for device {} has no temperature bands", device.device_id);
+            }
+            Self::validate_bands(&format!("device {}", device.device_id), &device.bands)?;
+        }
+        Ok(())
+    }
+
+    fn validate_bands(scope: &str, bands: &[FanBandConfig]) -> anyhow::Result<()> {
+        for band in bands {
+            if let FanSpeedTarget::Percent(p) = band.speed {
+                if p > 100 {
+                    anyhow::bail!("Fan control {} band speed {} is out of range (0-100)", scope, p);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取设备适用的温度区间（已按温度升序排列）
+    fn bands_for(&self, device_id: u32) -> Vec<FanBandConfig> {
+        let mut bands = self
+            .device_overrides
+            .iter()
+            .find(|o| o.device_id == device_id)
+            .map(|o| o.bands.clone())
+            .unwrap_or_else(|| self.bands.clone());
+        bands.sort_by(|a, b| a.max_temperature.total_cmp(&b.max_temperature));
+        bands
+    }
+}
+
+/// 设备风扇状态
+#[derive(Debug, Clone, Serialize)]
+pub struct FanStatus {
+    pub device_id: u32,
+    /// 当前生效的目标
+    pub target: Option<FanSpeedTarget>,
+    /// 手动覆盖
+    pub manual_override: Option<FanSpeedTarget>,
+    /// 策略是否启用
+    pub policy_enabled: bool,
+}
+
+/// 风扇控制器
+pub struct FanController {
+    config: FanControlConfig,
+    /// 手动覆盖（优先于温度策略）
+    manual_overrides: RwLock<HashMap<u32, FanSpeedTarget>>,
+    /// 每个设备最后一次下发的目标及其所在区间
+    applied: RwLock<HashMap<u32, (usize, FanSpeedTarget)>>,
+}
+
+impl FanController {
+    /// 创建风扇控制器
+    pub fn new(config: FanControlConfig) -> Self {
+        Self {
+            config,
+            manual_overrides: RwLock::new(HashMap::new()),
+            applied: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 策略是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 根据当前温度计算目标；仅当目标发生变化时返回 `Some`
+    pub async fn evaluate(&self, device_id: u32, temperature: f32) -> Option<FanSpeedTarget> {
+        if self.manual_overrides.read().await.contains_key(&device_id) {
+            return None;
+        }
+        if !self.config.enabled {
+            return None;
+        }
+
+        let bands = self.config.bands_for(device_id);
+        if bands.is_empty() {
+            return None;
+        }
+
+        let mut index = bands
+            .iter()
+            .position(|band| temperature <= band.max_temperature)
+            .unwrap_or(bands.len() - 1);
+
+        let mut applied = self.applied.write().await;
+        if let Some(&(prev_index, _)) = applied.get(&device_id) {
+            // 降档时需低于下一区间上限减去回滞值
+            if index < prev_index && prev_index < bands.len() {
+                let threshold = bands[prev_index - 1].max_temperature - self.config.hysteresis;
+                if temperature > threshold {
+                    index = prev_index;
+                }
+            }
+        }
+
+        let target = bands[index].speed;
+        match applied.insert(device_id, (index, target)) {
+            Some((_, prev)) if prev == target => None,
+            _ => Some(target),
+        }
+    }
+
+    /// 设置或清除手动覆盖
+    pub async fn set_override(&self, device_id: u32, target: Option<FanSpeedTarget>) {
+        let mut overrides = self.manual_overrides.write().await;
+        match target {
+            Some(target) => {
+                overrides.insert(device_id, target);
+            }
+            None => {
+                overrides.remove(&device_id);
+                // 清除后让策略在下一次扫描时重新下发
+                self.applied.write().await.remove(&device_id);
+            }
+        }
+    }
+
+    /// 获取设备风扇状态
+    pub async fn status(&self, device_id: u32) -> FanStatus {
+        let manual_override = self.manual_overrides.read().await.get(&device_id).copied();
+        let target = match manual_override {
+            Some(target) => Some(target),
+            None => self.applied.read().await.get(&device_id).map(|(_, target)| *target),
+        };
+        FanStatus {
+            device_id,
+            target,
+            manual_override,
+            policy_enabled: self.config.enabled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> FanController {
+        FanController::new(FanControlConfig { enabled: true, ..Default::default() })
+    }
+
+    #[tokio::test]
+    async fn test_band_selection() {
+        let fan = controller();
+        assert_eq!(fan.evaluate(0, 40.0).await, Some(FanSpeedTarget::Auto));
+        assert_eq!(fan.evaluate(1, 65.0).await, Some(FanSpeedTarget::Percent(70)));
+        assert_eq!(fan.evaluate(2, 250.0).await, Some(FanSpeedTarget::Percent(100)));
+        // 目标未变化时不重复下发
+        assert_eq!(fan.evaluate(1, 66.0).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_hysteresis() {
+        let fan = controller();
+        assert_eq!(fan.evaluate(0, 75.0).await, Some(FanSpeedTarget::Percent(90)));
+        // 68°C 仍在回滞范围内 (70 - 3)
+        assert_eq!(fan.evaluate(0, 68.0).await, None);
+        assert_eq!(fan.evaluate(0, 66.0).await, Some(FanSpeedTarget::Percent(70)));
+    }
+
+    #[tokio::test]
+    async fn test_manual_override() {
+        let fan = controller();
+        fan.set_override(0, Some(FanSpeedTarget::Percent(100))).await;
+        assert_eq!(fan.evaluate(0, 40.0).await, None);
+        assert_eq!(fan.status(0).await.target, Some(FanSpeedTarget::Percent(100)));
+
+        fan.set_override(0, None).await;
+        assert_eq!(fan.evaluate(0, 40.0).await, Some(FanSpeedTarget::Auto));
+    }
+
+    #[test]
+    fn test_speed_parsing() {
+        let cfg: FanControlConfig = toml::from_str(
+            "enabled = true\n[[bands]]\nmax_temperature = 60.0\nspeed = \"auto\"\n[[bands]]\nmax_temperature = 90.0\nspeed = 85\n",
+        ).unwrap();
+        assert_eq!(cfg.bands[0].speed, FanSpeedTarget::Auto);
+        assert_eq!(cfg.bands[1].speed, FanSpeedTarget::Percent(85));
+        assert!(cfg.validate().is_ok());
+
+        assert!(toml::from_str::<FanBandConfig>("max_temperature = 60.0\nspeed = 120").is_err());
+    }
+}
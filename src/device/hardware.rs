@@ -0,0 +1,352 @@
+//! Linux 硬件后端 - spidev、串口 (termios) 和 gpiochip
+//!
+//! 通过 `--features hardware` 启用，仅 Linux。每块板在 `[[devices.boards]]` 中选择自己的
+//! SPI/UART 设备和 GPIO 控制器，`HardwareInterface` 的 `chain_id` 按板配置路由。
+//! 所有阻塞 I/O 在 `spawn_blocking` 中执行并带超时；总线错误时关闭并重新打开设备后重试。
+
+use crate::config::BoardHardwareConfig;
+use crate::device::traits::HardwareInterface;
+use crate::error::DeviceError;
+use async_trait::async_trait;
+use gpio_cdev::{Chip, LineHandle, LineRequestFlags};
+use spidev::{SpiModeFlags, Spidev, SpidevOptions, SpidevTransfer};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// 串口波特率对应的 termios 常量
+fn baud_rate(baud: u32) -> Option<libc::speed_t> {
+    Some(match baud {
+        9600 => libc::B9600,
+        19200 => libc::B19200,
+        38400 => libc::B38400,
+        57600 => libc::B57600,
+        115200 => libc::B115200,
+        230400 => libc::B230400,
+        460800 => libc::B460800,
+        921600 => libc::B921600,
+        1500000 => libc::B1500000,
+        3000000 => libc::B3000000,
+        _ => return None,
+    })
+}
+
+/// 打开串口并设置为原始模式，读超时由 VTIME 控制 (0.1 秒为单位)
+fn open_uart(path: &str, baud: u32, timeout: Duration) -> std::io::Result<File> {
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    let fd = file.as_raw_fd();
+    let speed = baud_rate(baud)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("unsupported baud {}", baud)))?;
+
+    // SAFETY: fd 在 file 的生命周期内有效，termios 由 tcgetattr 完整初始化
+    unsafe {
+        let mut tty: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut tty) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        libc::cfmakeraw(&mut tty);
+        libc::cfsetspeed(&mut tty, speed);
+        tty.c_cflag |= libc::CLOCAL | libc::CREAD;
+        tty.c_cc[libc::VMIN] = 0;
+        tty.c_cc[libc::VTIME] = (timeout.as_millis() / 100).clamp(1, 255) as libc::cc_t;
+        if libc::tcsetattr(fd, libc::TCSANOW, &tty) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        libc::tcflush(fd, libc::TCIOFLUSH);
+    }
+    Ok(file)
+}
+
+fn open_spi(path: &str, speed_hz: u32) -> std::io::Result<Spidev> {
+    let mut spi = Spidev::open(path)?;
+    let options = SpidevOptions::new()
+        .bits_per_word(8)
+        .max_speed_hz(speed_hz)
+        .mode(SpiModeFlags::SPI_MODE_0)
+        .build();
+    spi.configure(&options)?;
+    Ok(spi)
+}
+
+/// 一块板打开的设备，出错后置空，下次使用时重新打开
+struct BoardHandles {
+    config: BoardHardwareConfig,
+    spi: Option<Spidev>,
+    uart: Option<File>,
+    gpio: Option<Chip>,
+    /// 已申请的 GPIO 输出线
+    lines: HashMap<u32, LineHandle>,
+}
+
+impl BoardHandles {
+    fn spi(&mut self) -> std::io::Result<&mut Spidev> {
+        if self.spi.is_none() {
+            let path = self.config.spi_device.as_deref()
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no spi_device configured"))?;
+            self.spi = Some(open_spi(path, self.config.spi_speed_hz)?);
+            debug!("Opened SPI {}", path);
+        }
+        Ok(self.spi.as_mut().expect("spi opened above"))
+    }
+
+    fn uart(&mut self) -> std::io::Result<&mut File> {
+        if self.uart.is_none() {
+            let path = self.config.uart_device.as_deref()
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no uart_device configured"))?;
+            self.uart = Some(open_uart(path, self.config.uart_baud, Duration::from_millis(self.config.timeout_ms))?);
+            debug!("Opened UART {} at {} baud", path, self.config.uart_baud);
+        }
+        Ok(self.uart.as_mut().expect("uart opened above"))
+    }
+
+    fn line(&mut self, pin: u32, flags: LineRequestFlags) -> Result<&LineHandle, gpio_cdev::Error> {
+        if self.gpio.is_none() {
+            let path = self.config.gpio_chip.clone().unwrap_or_else(|| "/dev/gpiochip0".to_string());
+            self.gpio = Some(Chip::new(path)?);
+        }
+        if !self.lines.contains_key(&pin) {
+            let chip = self.gpio.as_mut().expect("gpio chip opened above");
+            let handle = chip.get_line(pin)?.request(flags, 0, "cgminer-rs")?;
+            self.lines.insert(pin, handle);
+        }
+        Ok(&self.lines[&pin])
+    }
+
+    /// 总线错误后关闭设备，下次访问时重新打开
+    fn reset_bus(&mut self) {
+        self.spi = None;
+        self.uart = None;
+    }
+}
+
+/// Linux 硬件接口，按链ID路由到各板的设备
+pub struct LinuxHardware {
+    boards: Vec<Arc<Mutex<BoardHandles>>>,
+    chain_boards: HashMap<u8, usize>,
+}
+
+impl LinuxHardware {
+    pub fn new(boards: &[BoardHardwareConfig]) -> Self {
+        let mut chain_boards = HashMap::new();
+        let handles = boards
+            .iter()
+            .enumerate()
+            .map(|(index, config)| {
+                for chain in &config.chains {
+                    chain_boards.insert(*chain, index);
+                }
+                Arc::new(Mutex::new(BoardHandles {
+                    config: config.clone(),
+                    spi: None,
+                    uart: None,
+                    gpio: None,
+                    lines: HashMap::new(),
+                }))
+            })
+            .collect();
+        info!("Linux hardware backend: {} boards, chains {:?}", boards.len(), chain_boards.keys().collect::<Vec<_>>());
+        Self { boards: handles, chain_boards }
+    }
+
+    fn board(&self, chain_id: u8) -> Result<Arc<Mutex<BoardHandles>>, DeviceError> {
+        self.chain_boards
+            .get(&chain_id)
+            .map(|&index| self.boards[index].clone())
+            .ok_or_else(|| DeviceError::ChainError { chain_id, error: "no board configured for chain".to_string() })
+    }
+
+    /// GPIO 引脚不属于某条链，使用第一块配置了 gpio_chip 的板
+    fn gpio_board(&self) -> Result<Arc<Mutex<BoardHandles>>, DeviceError> {
+        self.boards
+            .iter()
+            .find(|board| board.lock().map(|b| b.config.gpio_chip.is_some()).unwrap_or(false))
+            .or_else(|| self.boards.first())
+            .cloned()
+            .ok_or_else(|| DeviceError::InvalidConfig { reason: "no boards configured".to_string() })
+    }
+
+    /// 在阻塞线程中执行总线操作，超时或出错时重置总线并按配置重试
+    async fn with_bus<T, F>(&self, chain_id: u8, op: F) -> Result<T, DeviceError>
+    where
+        T: Send + 'static,
+        F: Fn(&mut BoardHandles) -> std::io::Result<T> + Send + Sync + 'static,
+    {
+        let board = self.board(chain_id)?;
+        let (timeout, retries) = {
+            let board = board.lock().expect("board lock poisoned");
+            // 串口读在阻塞线程中按 timeout_ms 自行截止，外层超时留出同样的余量
+            (Duration::from_millis(board.config.timeout_ms * 2), board.config.max_retries)
+        };
+        let op = Arc::new(op);
+
+        let mut last_error = String::new();
+        for attempt in 0..=retries {
+            let board = board.clone();
+            let op = op.clone();
+            let task = tokio::task::spawn_blocking(move || {
+                let mut board = board.lock().expect("board lock poisoned");
+                let result = op(&mut board);
+                if result.is_err() {
+                    board.reset_bus();
+                }
+                result
+            });
+            match tokio::time::timeout(timeout, task).await {
+                Ok(Ok(Ok(value))) => return Ok(value),
+                Ok(Ok(Err(e))) => last_error = e.to_string(),
+                Ok(Err(e)) => last_error = e.to_string(),
+                Err(_) => last_error = format!("timed out after {:?}", timeout),
+            }
+            warn!("Chain {} bus error (attempt {}/{}): {}", chain_id, attempt + 1, retries + 1, last_error);
+        }
+        Err(DeviceError::CommunicationError { device_id: chain_id as u32, error: last_error })
+    }
+}
+
+#[async_trait]
+impl HardwareInterface for LinuxHardware {
+    async fn spi_transfer(&self, chain_id: u8, data: &[u8]) -> Result<Vec<u8>, DeviceError> {
+        let tx = data.to_vec();
+        self.with_bus(chain_id, move |board| {
+            let mut rx = vec![0u8; tx.len()];
+            let mut transfer = SpidevTransfer::read_write(&tx, &mut rx);
+            board.spi()?.transfer(&mut transfer)?;
+            Ok(rx)
+        })
+        .await
+    }
+
+    async fn uart_write(&self, chain_id: u8, data: &[u8]) -> Result<(), DeviceError> {
+        let data = data.to_vec();
+        self.with_bus(chain_id, move |board| {
+            let uart = board.uart()?;
+            uart.write_all(&data)?;
+            uart.flush()
+        })
+        .await
+    }
+
+    async fn uart_read(&self, chain_id: u8, len: usize) -> Result<Vec<u8>, DeviceError> {
+        self.with_bus(chain_id, move |board| {
+            let deadline = Instant::now() + Duration::from_millis(board.config.timeout_ms);
+            let uart = board.uart()?;
+            let mut buf = vec![0u8; len];
+            let mut filled = 0;
+            // VTIME 到期时 read 返回 0，直到读满或超过截止时间
+            while filled < len && Instant::now() < deadline {
+                filled += uart.read(&mut buf[filled..])?;
+            }
+            if filled < len {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("read {} of {} bytes", filled, len),
+                ));
+            }
+            Ok(buf)
+        })
+        .await
+    }
+
+    async fn gpio_set(&self, pin: u32, value: bool) -> Result<(), DeviceError> {
+        let board = self.gpio_board()?;
+        let mut board = board.lock().expect("board lock poisoned");
+        board
+            .line(pin, LineRequestFlags::OUTPUT)
+            .and_then(|line| line.set_value(value as u8))
+            .map_err(|e| DeviceError::CommunicationError { device_id: 0, error: format!("gpio {}: {}", pin, e) })
+    }
+
+    async fn gpio_get(&self, pin: u32) -> Result<bool, DeviceError> {
+        let board = self.gpio_board()?;
+        let mut board = board.lock().expect("board lock poisoned");
+        board
+            .line(pin, LineRequestFlags::INPUT)
+            .and_then(|line| line.get_value())
+            .map(|value| value != 0)
+            .map_err(|e| DeviceError::CommunicationError { device_id: 0, error: format!("gpio {}: {}", pin, e) })
+    }
+
+    async fn pwm_set_duty(&self, channel: u32, duty: f32) -> Result<(), DeviceError> {
+        // sysfs PWM: /sys/class/pwm/pwmchip0/pwm<channel>/{period,duty_cycle,enable}
+        let base = format!("/sys/class/pwm/pwmchip0/pwm{}", channel);
+        let period: u64 = std::fs::read_to_string(format!("{}/period", base))
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(40_000);
+        let duty_ns = (period as f64 * duty.clamp(0.0, 1.0) as f64) as u64;
+        std::fs::write(format!("{}/duty_cycle", base), duty_ns.to_string())
+            .and_then(|_| std::fs::write(format!("{}/enable", base), "1"))
+            .map_err(|e| DeviceError::CommunicationError { device_id: 0, error: format!("pwm {}: {}", channel, e) })
+    }
+
+    async fn read_temperature(&self, sensor_id: u8) -> Result<f32, DeviceError> {
+        // hwmon 传感器，单位为毫摄氏度
+        let path = format!("/sys/class/hwmon/hwmon{}/temp1_input", sensor_id);
+        std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| s.trim().parse::<f32>().ok())
+            .map(|millis| millis / 1000.0)
+            .ok_or_else(|| DeviceError::CommunicationError { device_id: 0, error: format!("cannot read {}", path) })
+    }
+
+    async fn read_power(&self, sensor_id: u8) -> Result<Option<f64>, DeviceError> {
+        // hwmon 功率传感器，单位为微瓦
+        let path = format!("/sys/class/hwmon/hwmon{}/power1_input", sensor_id);
+        std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| s.trim().parse::<f64>().ok())
+            .map(|micros| Some(micros / 1_000_000.0))
+            .ok_or_else(|| DeviceError::CommunicationError { device_id: 0, error: format!("cannot read {}", path) })
+    }
+
+    async fn read_eeprom(&self, chain_id: u8) -> Result<Vec<u8>, DeviceError> {
+        let board = self.board(chain_id)?;
+        let path = board.lock().expect("board lock poisoned").config.eeprom_path.clone()
+            .ok_or_else(|| DeviceError::ChainError { chain_id, error: "no eeprom_path configured".to_string() })?
+            .replace("{chain}", &chain_id.to_string());
+        let mut data = std::fs::read(&path)
+            .map_err(|e| DeviceError::CommunicationError { device_id: 0, error: format!("{}: {}", path, e) })?;
+        data.truncate(crate::device::eeprom::EEPROM_LEN);
+        Ok(data)
+    }
+
+    async fn set_voltage(&self, chain_id: u8, _voltage: u32) -> Result<(), DeviceError> {
+        // 电压由链协议或电源控制下发，总线后端不直接处理
+        Err(DeviceError::ChainError { chain_id, error: "voltage is set through the chain protocol".to_string() })
+    }
+
+    async fn set_frequency(&self, chain_id: u8, _frequency: u32) -> Result<(), DeviceError> {
+        Err(DeviceError::ChainError { chain_id, error: "frequency is set through the chain protocol".to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_baud_rates() {
+        for baud in crate::config::UART_BAUD_RATES {
+            assert!(baud_rate(*baud).is_some(), "no termios speed for {}", baud);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_chain() {
+        let hardware = LinuxHardware::new(&[BoardHardwareConfig {
+            chains: vec![0],
+            spi_device: Some("/nonexistent/spidev".to_string()),
+            max_retries: 0,
+            ..Default::default()
+        }]);
+        assert!(matches!(hardware.spi_transfer(3, &[0]).await, Err(DeviceError::ChainError { chain_id: 3, .. })));
+        assert!(matches!(
+            hardware.spi_transfer(0, &[0]).await,
+            Err(DeviceError::CommunicationError { .. })
+        ));
+    }
+}
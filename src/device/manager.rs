@@ -2,18 +2,28 @@ use crate::config::{DeviceConfig, Config};
 use crate::error::DeviceError;
 use cgminer_core::CoreRegistry;
 use crate::device::{
-    DeviceInfo, DeviceStats, Work, MiningResult,
+    DeviceInfo, DeviceStats, DeviceStatus, ThrottleState, Work, MiningResult,
     MiningDevice, DeviceCoreMapper,
+    fan_control::{FanController, FanSpeedTarget, FanStatus},
+    ramp_up::{RampUpPhase, RampUpStatus},
+    autotune::{self, AutoTuneStatus, TuneProfileStore},
+    thermal::{ThermalGuard, ThermalTransition},
+    power::{self, DevicePower, PowerStage, PowerStatus},
+    chips::{self, BadChipMap, ChipHeatmap},
+    eeprom::BoardEeprom,
+    traits::HardwareInterface,
     architecture::{UnifiedDeviceArchitecture, DeviceArchitectureConfig},
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{RwLock, Mutex, broadcast};
 use tokio::time::interval;
 use tracing::{info, warn, error, debug};
 use async_trait::async_trait;
 use crate::logging::formatter::format_hashrate;
+use crate::logging::i18n::msg;
+use crate::mining::MiningEvent;
 
 /// 设备算力详情
 #[derive(Debug, Clone)]
@@ -61,14 +71,22 @@ pub struct DeviceManager {
     device_info: Arc<RwLock<HashMap<u32, DeviceInfo>>>,
     /// 设备统计信息
     device_stats: Arc<RwLock<HashMap<u32, DeviceStats>>>,
+    /// 核心内温度降频状态 (核心工厂名 -> 状态)，监控时合并到该核心各设备的统计
+    throttle_states: Arc<RwLock<HashMap<String, ThrottleState>>>,
     /// 核心注册表（从工厂移入）
     core_registry: Arc<CoreRegistry>,
     /// 活跃核心ID列表（从工厂移入）
     active_core_ids: Vec<String>,
+    /// 活跃核心所属的工厂名 (核心 ID -> `CoreInfo::name`)
+    core_factories: HashMap<String, String>,
     /// 设备-核心映射器
     device_core_mapper: Arc<DeviceCoreMapper>,
     /// 统一设备架构管理器
     architecture_manager: Arc<UnifiedDeviceArchitecture>,
+    /// 风扇控制器
+    fan_controller: Arc<FanController>,
+    /// 温度上限保护
+    thermal_guard: Arc<ThermalGuard>,
     /// 配置
     config: DeviceConfig,
     /// 完整配置（用于访问核心配置中的设备数量）
@@ -76,6 +94,26 @@ pub struct DeviceManager {
 
     /// 监控任务句柄
     monitoring_handle: Option<tokio::task::JoinHandle<()>>,
+    /// 分批启动任务句柄
+    ramp_up_handle: Option<tokio::task::JoinHandle<()>>,
+    /// 分批启动状态
+    ramp_up_status: Arc<RwLock<RampUpStatus>>,
+    /// 自动调优任务句柄
+    auto_tune_handles: Vec<tokio::task::JoinHandle<()>>,
+    /// 各链自动调优进度
+    auto_tune_status: Arc<RwLock<HashMap<u32, AutoTuneStatus>>>,
+    /// `[[devices.boards]]` 的硬件后端，未配置或未启用 `hardware` 特性时为 `None`
+    board_hardware: Option<Arc<dyn HardwareInterface>>,
+    /// 电源级（配置了电源使能时）
+    power_stage: Option<Arc<PowerStage>>,
+    /// 功率上限状态
+    power_status: Arc<RwLock<PowerStatus>>,
+    /// 坏芯片表（启用芯片健康检查时）
+    bad_chips: Option<Arc<BadChipMap>>,
+    /// 各设备算力板的 EEPROM 信息
+    board_eeproms: Arc<RwLock<HashMap<u32, BoardEeprom>>>,
+    /// 事件广播（由挖矿管理器注入）
+    event_sender: Option<broadcast::Sender<MiningEvent>>,
     /// 运行状态
     running: Arc<RwLock<bool>>,
 }
@@ -86,13 +124,27 @@ impl Clone for DeviceManager {
             devices: self.devices.clone(),
             device_info: self.device_info.clone(),
             device_stats: self.device_stats.clone(),
+            throttle_states: self.throttle_states.clone(),
             core_registry: self.core_registry.clone(),
             active_core_ids: self.active_core_ids.clone(),
+            core_factories: self.core_factories.clone(),
             device_core_mapper: self.device_core_mapper.clone(),
             architecture_manager: self.architecture_manager.clone(),
+            fan_controller: self.fan_controller.clone(),
+            thermal_guard: self.thermal_guard.clone(),
             config: self.config.clone(),
             full_config: self.full_config.clone(),
             monitoring_handle: None, // Do not clone the handle
+            ramp_up_handle: None,
+            ramp_up_status: self.ramp_up_status.clone(),
+            auto_tune_handles: Vec::new(),
+            auto_tune_status: self.auto_tune_status.clone(),
+            board_hardware: self.board_hardware.clone(),
+            power_stage: self.power_stage.clone(),
+            power_status: self.power_status.clone(),
+            bad_chips: self.bad_chips.clone(),
+            board_eeproms: self.board_eeproms.clone(),
+            event_sender: self.event_sender.clone(),
             running: self.running.clone(),
         }
     }
@@ -106,26 +158,44 @@ impl DeviceManager {
         // 创建默认的架构配置
         let arch_config = DeviceArchitectureConfig::default();
         let architecture_manager = UnifiedDeviceArchitecture::new(arch_config, core_registry.clone());
+        let fan_controller = FanController::new(config.fan_control.clone());
+        let thermal_guard = ThermalGuard::new(config.thermal.clone());
+        let power_status = PowerStatus::new(config.power.enabled, config.power.power_limit_watts);
 
         Self {
             devices: Arc::new(RwLock::new(HashMap::new())),
             device_info: Arc::new(RwLock::new(HashMap::new())),
             device_stats: Arc::new(RwLock::new(HashMap::new())),
+            throttle_states: Arc::new(RwLock::new(HashMap::new())),
             core_registry,
             active_core_ids: Vec::new(),
+            core_factories: HashMap::new(),
             device_core_mapper: Arc::new(device_core_mapper),
             architecture_manager: Arc::new(architecture_manager),
+            fan_controller: Arc::new(fan_controller),
+            thermal_guard: Arc::new(thermal_guard),
             config,
             full_config: None,
             monitoring_handle: None,
+            ramp_up_handle: None,
+            ramp_up_status: Arc::new(RwLock::new(RampUpStatus::new())),
+            auto_tune_handles: Vec::new(),
+            auto_tune_status: Arc::new(RwLock::new(HashMap::new())),
+            board_hardware: None,
+            power_stage: None,
+            power_status: Arc::new(RwLock::new(power_status)),
+            bad_chips: None,
+            board_eeproms: Arc::new(RwLock::new(HashMap::new())),
+            event_sender: None,
             running: Arc::new(RwLock::new(false)),
         }
     }
 
-    /// 设置活跃核心ID列表
-    pub async fn set_active_cores(&mut self, core_ids: Vec<String>) {
+    /// 设置活跃核心ID列表和各核心所属的工厂
+    pub async fn set_active_cores(&mut self, core_ids: Vec<String>, core_factories: HashMap<String, String>) {
         self.active_core_ids = core_ids;
-        info!("🏭 设备管理器接收到活跃核心: {:?}", self.active_core_ids);
+        self.core_factories = core_factories;
+        info!("{}", msg::device_manager_active_cores(format!("{:?}", self.active_core_ids)));
     }
 
     /// 设置完整配置（用于访问核心配置）
@@ -133,12 +203,41 @@ impl DeviceManager {
         self.full_config = Some(config);
     }
 
+    /// 设置事件广播通道
+    pub fn set_event_sender(&mut self, event_sender: broadcast::Sender<MiningEvent>) {
+        self.event_sender = Some(event_sender);
+    }
+
     /// 初始化设备管理器
     pub async fn initialize(&mut self) -> Result<(), DeviceError> {
         debug!("🔧 初始化设备管理器");
 
+        // 先打开各链电源，再探测设备
+        self.board_hardware = self.create_board_hardware();
+        if self.config.power.enabled {
+            self.power_up().await?;
+        }
+        if self.config.chip_health.enabled {
+            self.bad_chips = Some(Arc::new(BadChipMap::load(&self.config.chip_health.map_file)));
+        }
+
+        // 创建模拟设备（不依赖外置核心）
+        #[cfg(feature = "sim")]
+        let sim_device_count = self.create_sim_devices().await?;
+        #[cfg(not(feature = "sim"))]
+        let sim_device_count = 0;
+        #[cfg(feature = "whatsminer")]
+        let whatsminer_device_count = self.create_whatsminer_devices().await?;
+        #[cfg(not(feature = "whatsminer"))]
+        let whatsminer_device_count = 0;
+        #[cfg(all(feature = "avalon", target_os = "linux"))]
+        let avalon_device_count = self.create_avalon_devices().await?;
+        #[cfg(not(all(feature = "avalon", target_os = "linux")))]
+        let avalon_device_count = 0;
+
         // 检查活跃核心
-        if self.active_core_ids.is_empty() {
+        let standalone_device_count = sim_device_count + whatsminer_device_count + avalon_device_count;
+        if self.active_core_ids.is_empty() && standalone_device_count == 0 {
             return Err(DeviceError::InitializationFailed {
                 device_id: 0,
                 reason: "没有可用的活跃核心".to_string(),
@@ -149,8 +248,9 @@ impl DeviceManager {
 
         // 创建设备
         self.create_devices().await?;
+        self.read_board_eeproms().await;
 
-        info!("✅ 设备管理器初始化成功");
+        info!("{}", msg::device_manager_initialized());
         Ok(())
     }
 
@@ -160,11 +260,11 @@ impl DeviceManager {
 
         // **修复**：只为活跃的核心创建设备，而不是所有注册的工厂
         if self.active_core_ids.is_empty() {
-            warn!("⚠️ 没有活跃的挖矿核心");
+            warn!("{}", msg::no_active_cores());
             return Ok(());
         }
 
-        info!("🏭 活跃核心列表: {:?}", self.active_core_ids);
+        info!("{}", msg::active_core_list(format!("{:?}", self.active_core_ids)));
 
         // 获取所有注册的核心工厂信息
         let all_factories = self.core_registry.list_factories().await.map_err(|e| {
@@ -188,7 +288,7 @@ impl DeviceManager {
         }
 
         if active_factories.is_empty() {
-            warn!("⚠️ 没有活跃核心对应的工厂");
+            warn!("{}", msg::no_factory_for_active_cores());
             return Ok(());
         }
 
@@ -198,20 +298,345 @@ impl DeviceManager {
         for core in active_factories {
             match self.create_devices_for_core(&core).await {
                 Ok(device_count) => {
-                    info!("✅ 核心工厂 {} 创建了 {} 个设备", core.name, device_count);
+                    info!("{}", msg::factory_created_devices(&core.name, device_count));
                 }
                 Err(e) => {
-                    error!("❌ 核心工厂 {} 设备创建失败: {}", core.name, e);
+                    error!("{}", msg::factory_create_failed(&core.name, e));
                 }
             }
         }
 
         let total_device_count = self.devices.read().await.len();
-        info!("🎯 设备初始化完成，共创建 {} 个挖矿设备", total_device_count);
+        info!("{}", msg::devices_initialized(total_device_count));
 
         Ok(())
     }
 
+    /// 根据 `[cores.sim]` 创建虚拟设备
+    #[cfg(feature = "sim")]
+    async fn create_sim_devices(&mut self) -> Result<u32, DeviceError> {
+        let sim_config = match self.full_config.as_ref().and_then(|c| c.cores.sim.clone()) {
+            Some(sim_config) if sim_config.enabled => sim_config,
+            _ => return Ok(0),
+        };
+
+        let mut created = 0u32;
+        for mut device in crate::device::virtual_device::VirtualDevice::create_all(&sim_config) {
+            let device_id = device.device_id();
+            device.initialize(crate::device::DeviceConfig {
+                chain_id: device_id as u8,
+                ..Default::default()
+            }).await?;
+
+            let info = device.get_info().await?;
+            let device: Box<dyn MiningDevice> = Box::new(device);
+            self.devices.write().await.insert(device_id, Arc::new(Mutex::new(device)));
+            self.device_info.write().await.insert(device_id, info);
+            self.device_stats.write().await.insert(device_id, DeviceStats::new());
+            created += 1;
+        }
+
+        // 模拟 ASIC 链：每条链一个设备，经链控制器走完整的芯片协议
+        if let Some(hardware) = self.board_hardware.clone().filter(|_| sim_config.asic.enabled) {
+            for mut device in crate::device::sim_hardware::SimAsicBoard::create_all(&sim_config.asic, hardware) {
+                let device_id = device.device_id();
+                let chain_id = (device_id - crate::device::sim_hardware::SIM_ASIC_DEVICE_ID_BASE) as u8;
+                device.initialize(crate::device::DeviceConfig { chain_id, ..Default::default() }).await?;
+
+                let info = device.get_info().await?;
+                let device: Box<dyn MiningDevice> = Box::new(device);
+                self.devices.write().await.insert(device_id, Arc::new(Mutex::new(device)));
+                self.device_info.write().await.insert(device_id, info);
+                self.device_stats.write().await.insert(device_id, DeviceStats::new());
+                created += 1;
+            }
+        }
+
+        info!("{}", msg::sim_devices_created(created));
+        Ok(created)
+    }
+
+    /// 根据 `[cores.whatsminer]` 为每块算力板创建监管设备
+    #[cfg(feature = "whatsminer")]
+    async fn create_whatsminer_devices(&mut self) -> Result<u32, DeviceError> {
+        let whatsminer_config = match self.full_config.as_ref().and_then(|c| c.cores.whatsminer.clone()) {
+            Some(whatsminer_config) if whatsminer_config.enabled => whatsminer_config,
+            _ => return Ok(0),
+        };
+
+        let mut created = 0u32;
+        for mut device in crate::device::whatsminer::WhatsminerBoard::create_all(&whatsminer_config).await {
+            let device_id = device.device_id();
+            device.initialize(crate::device::DeviceConfig::default()).await?;
+
+            let info = device.get_info().await?;
+            let device: Box<dyn MiningDevice> = Box::new(device);
+            self.devices.write().await.insert(device_id, Arc::new(Mutex::new(device)));
+            self.device_info.write().await.insert(device_id, info);
+            self.device_stats.write().await.insert(device_id, DeviceStats::new());
+            created += 1;
+        }
+
+        info!("{}", msg::whatsminer_devices_created(created));
+        Ok(created)
+    }
+
+    /// 创建算力板硬件后端：启用 `[cores.sim.asic]` 时为模拟硬件，否则按 `[[devices.boards]]`
+    fn create_board_hardware(&self) -> Option<Arc<dyn HardwareInterface>> {
+        #[cfg(feature = "sim")]
+        if let Some(sim_config) = self.full_config.as_ref()
+            .and_then(|c| c.cores.sim.as_ref())
+            .filter(|c| c.enabled && c.asic.enabled)
+        {
+            return Some(Arc::new(crate::device::sim_hardware::SimHardware::new(sim_config.asic.clone(), sim_config.seed)));
+        }
+        Self::create_linux_hardware(&self.config)
+    }
+
+    /// 按 `[[devices.boards]]` 创建硬件后端
+    #[cfg(all(feature = "hardware", target_os = "linux"))]
+    fn create_linux_hardware(config: &DeviceConfig) -> Option<Arc<dyn HardwareInterface>> {
+        if config.boards.is_empty() {
+            return None;
+        }
+        Some(Arc::new(crate::device::hardware::LinuxHardware::new(&config.boards)))
+    }
+
+    #[cfg(not(all(feature = "hardware", target_os = "linux")))]
+    fn create_linux_hardware(_config: &DeviceConfig) -> Option<Arc<dyn HardwareInterface>> {
+        None
+    }
+
+    /// 按顺序打开 `[devices.power]` 中的各路电源，并确定功率上限
+    async fn power_up(&mut self) -> Result<(), DeviceError> {
+        let limit = self.config.power.power_limit_watts.or_else(|| {
+            self.full_config.as_ref()
+                .and_then(|c| c.cores.maijie_l7.as_ref())
+                .filter(|c| c.enabled)
+                .map(|c| c.power_limit)
+        });
+        self.power_status.write().await.power_limit_watts = limit;
+
+        if self.config.power.rails.is_empty() {
+            return Ok(());
+        }
+        let hardware = self.board_hardware.clone().ok_or_else(|| DeviceError::InitializationFailed {
+            device_id: 0,
+            reason: "devices.power.rails needs [[devices.boards]] with a gpio_chip".to_string(),
+        })?;
+        let stage = Arc::new(PowerStage::new(self.config.power.clone(), hardware));
+        stage.power_up().await?;
+        info!("{}", msg::power_rails_enabled(self.config.power.rails.len()));
+        self.power_stage = Some(stage);
+        Ok(())
+    }
+
+    /// 软启动：把各链电压从最低值分步升到配置的目标电压
+    async fn soft_start_devices(&self) {
+        let start_voltage = *crate::config::VOLTAGE_RANGE.start();
+        let devices: Vec<(u32, Arc<Mutex<Box<dyn MiningDevice>>>)> =
+            self.devices.read().await.iter().map(|(id, device)| (*id, device.clone())).collect();
+
+        for (device_id, device) in devices {
+            let chain_id = match self.device_info.read().await.get(&device_id) {
+                Some(info) => info.chain_id,
+                None => continue,
+            };
+            let target = match self.config.chains.iter().find(|chain| chain.id == chain_id && chain.enabled) {
+                Some(chain) => chain.voltage,
+                None => continue,
+            };
+            let mut device = device.lock().await;
+            for voltage in power::soft_start_steps(start_voltage, target, self.config.power.soft_start_step_mv) {
+                if let Err(e) = device.set_voltage(voltage).await {
+                    debug!("Device {} does not support soft start: {}", device_id, e);
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(self.config.power.soft_start_step_ms)).await;
+            }
+        }
+    }
+
+    /// 读取或估算各设备功率，超过功率上限时降低功耗最高的设备的频率
+    async fn enforce_power_limit(&self) {
+        let limit = match self.power_status.read().await.power_limit_watts {
+            Some(limit) => limit,
+            None => return,
+        };
+
+        let infos: Vec<DeviceInfo> = self.device_info.read().await.values()
+            .filter(|info| info.is_healthy())
+            .cloned()
+            .collect();
+        let mut readings = Vec::with_capacity(infos.len());
+        for info in &infos {
+            let frequency = info.frequency.unwrap_or(0);
+            let measured = match &self.power_stage {
+                Some(stage) => stage.measure(info.chain_id).await,
+                None => None,
+            };
+            let (watts, measured) = match measured {
+                Some(watts) => (watts, true),
+                None => (autotune::estimate_power(info.chip_count, frequency, info.voltage.unwrap_or(0)), false),
+            };
+            readings.push(DevicePower { device_id: info.id, watts, measured, frequency });
+        }
+        readings.sort_by_key(|reading| reading.device_id);
+
+        let throttle = power::plan_throttle(&readings, limit, self.config.power.throttle_step_mhz, self.config.power.min_frequency);
+        let total_watts: f64 = readings.iter().map(|reading| reading.watts).sum();
+        {
+            let mut status = self.power_status.write().await;
+            status.total_watts = total_watts;
+            status.devices = readings;
+            status.updated_at = std::time::SystemTime::now();
+        }
+
+        if let Some((device_id, frequency)) = throttle {
+            warn!("{}", msg::power_limit_exceeded(total_watts, limit, device_id, frequency));
+            match self.set_device_frequency(device_id, frequency).await {
+                Ok(()) => {
+                    if let Some(info) = self.device_info.write().await.get_mut(&device_id) {
+                        info.frequency = Some(frequency);
+                    }
+                    self.power_status.write().await.throttle_count += 1;
+                }
+                Err(e) => warn!("Failed to throttle device {}: {}", device_id, e),
+            }
+        }
+    }
+
+    /// 从算力板 EEPROM 读取序列号、版本和校准数据，填入设备信息
+    async fn read_board_eeproms(&self) {
+        let Some(hardware) = self.board_hardware.clone() else { return };
+        let mut device_info = self.device_info.write().await;
+        let mut eeproms = self.board_eeproms.write().await;
+        for (device_id, info) in device_info.iter_mut() {
+            let data = match hardware.read_eeprom(info.chain_id).await {
+                Ok(data) => data,
+                Err(e) => {
+                    debug!("No EEPROM for device {} (chain {}): {}", device_id, info.chain_id, e);
+                    continue;
+                }
+            };
+            match BoardEeprom::parse(&data) {
+                Ok(eeprom) => {
+                    info!("{}", msg::board_eeprom_read(info.chain_id, &eeprom.serial_number, &eeprom.firmware_version));
+                    if eeprom.calibration.chip_count != 0 && eeprom.calibration.chip_count != info.chip_count {
+                        warn!("Chain {} EEPROM lists {} chips, device reports {}",
+                              info.chain_id, eeprom.calibration.chip_count, info.chip_count);
+                    }
+                    info.serial_number = Some(eeprom.serial_number.clone());
+                    info.firmware_version = Some(eeprom.firmware_version.clone());
+                    info.hardware_version = Some(eeprom.hardware_version.clone());
+                    eeproms.insert(*device_id, eeprom);
+                }
+                Err(e) => warn!("Chain {} EEPROM unreadable: {}", info.chain_id, e),
+            }
+        }
+    }
+
+    /// 获取设备算力板的 EEPROM 信息
+    pub async fn get_board_eeprom(&self, device_id: u32) -> Option<BoardEeprom> {
+        self.board_eeproms.read().await.get(&device_id).cloned()
+    }
+
+    /// 获取功率上限状态
+    pub async fn get_power_status(&self) -> PowerStatus {
+        self.power_status.read().await.clone()
+    }
+
+    /// 把错误率超过阈值的芯片记入坏芯片表
+    async fn update_bad_chips(&self) {
+        let bad_chips = match &self.bad_chips {
+            Some(bad_chips) => bad_chips,
+            None => return,
+        };
+        let devices: Vec<(u32, Arc<Mutex<Box<dyn MiningDevice>>>)> =
+            self.devices.read().await.iter().map(|(id, device)| (*id, device.clone())).collect();
+        for (device_id, device) in devices {
+            let stats = match device.lock().await.get_chip_stats().await {
+                Ok(stats) if !stats.is_empty() => stats,
+                _ => continue,
+            };
+            let Some(info) = self.device_info.read().await.get(&device_id).cloned() else { continue };
+            let bad = chips::find_bad_chips(&stats, &self.config.chip_health);
+            if bad.is_empty() {
+                continue;
+            }
+            let board = autotune::board_key(&info);
+            match bad_chips.mark(&board, &bad).await {
+                Ok(added) if !added.is_empty() => warn!("{}", msg::bad_chips_found(&board, format!("{:?}", added))),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to save bad chip map: {}", e),
+            }
+        }
+    }
+
+    /// 获取一个设备的芯片热力图，不能按芯片统计的设备返回 `None`
+    pub async fn get_chip_heatmap(&self, device_id: u32) -> Result<Option<ChipHeatmap>, DeviceError> {
+        let device = self.devices.read().await.get(&device_id).cloned()
+            .ok_or(DeviceError::NotFound { device_id })?;
+        let stats = device.lock().await.get_chip_stats().await?;
+        if stats.is_empty() {
+            return Ok(None);
+        }
+        let info = self.device_info.read().await.get(&device_id).cloned()
+            .ok_or(DeviceError::NotFound { device_id })?;
+        let board = autotune::board_key(&info);
+        let bad = match &self.bad_chips {
+            Some(bad_chips) => bad_chips.get(&board).await,
+            None => Default::default(),
+        };
+        Ok(Some(ChipHeatmap::new(device_id, board, &stats, &bad)))
+    }
+
+    /// 获取所有能按芯片统计的设备的热力图
+    pub async fn get_chip_heatmaps(&self) -> Vec<ChipHeatmap> {
+        let mut ids: Vec<u32> = self.devices.read().await.keys().copied().collect();
+        ids.sort_unstable();
+        let mut heatmaps = Vec::new();
+        for id in ids {
+            if let Ok(Some(heatmap)) = self.get_chip_heatmap(id).await {
+                heatmaps.push(heatmap);
+            }
+        }
+        heatmaps
+    }
+
+    /// 根据 `[cores.avalon]` 为每个 MM 模块创建设备，经 `[[devices.boards]]` 中的串口访问 AUC
+    #[cfg(all(feature = "avalon", target_os = "linux"))]
+    async fn create_avalon_devices(&mut self) -> Result<u32, DeviceError> {
+        let avalon_config = match self.full_config.as_ref().and_then(|c| c.cores.avalon.clone()) {
+            Some(avalon_config) if avalon_config.enabled => avalon_config,
+            _ => return Ok(0),
+        };
+
+        let hardware = self.board_hardware.clone().ok_or_else(|| DeviceError::InitializationFailed {
+            device_id: 0,
+            reason: "Avalon needs [[devices.boards]]".to_string(),
+        })?;
+
+        let mut created = 0u32;
+        for mut device in crate::device::avalon::AvalonChain::create_all(&avalon_config, hardware) {
+            let device_id = device.device_id();
+            if let Err(e) = device.initialize(crate::device::DeviceConfig::default()).await {
+                warn!("⚠️ Avalon device {} not detected, skipping: {}", device_id, e);
+                continue;
+            }
+
+            let info = device.get_info().await?;
+            let device: Box<dyn MiningDevice> = Box::new(device);
+            self.devices.write().await.insert(device_id, Arc::new(Mutex::new(device)));
+            self.device_info.write().await.insert(device_id, info);
+            self.device_stats.write().await.insert(device_id, DeviceStats::new());
+            created += 1;
+        }
+
+        info!("{}", msg::avalon_devices_created(created));
+        Ok(created)
+    }
+
     /// 为指定核心创建设备
     async fn create_devices_for_core(&mut self, core: &cgminer_core::CoreInfo) -> Result<u32, DeviceError> {
         debug!("🔍 为核心 {} 扫描设备", core.name);
@@ -228,12 +653,12 @@ impl DeviceManager {
         })?;
 
         if scanned_devices.is_empty() {
-            warn!("⚠️ 核心 {} 没有扫描到设备", core.name);
+            warn!("{}", msg::core_found_no_devices(&core.name));
             return Ok(0);
         }
 
         let requested_device_count = scanned_devices.len() as u32;
-        info!("📋 核心 {} 扫描到 {} 个设备", core.name, requested_device_count);
+        info!("{}", msg::core_found_devices(&core.name, requested_device_count));
 
         // 使用架构管理器验证设备配置
         let validated_device_count = self.architecture_manager
@@ -241,7 +666,7 @@ impl DeviceManager {
             .await?;
 
         if validated_device_count != requested_device_count {
-            info!("📋 架构管理器调整设备数量: {} -> {}", requested_device_count, validated_device_count);
+            info!("{}", msg::device_count_adjusted(requested_device_count, validated_device_count));
         }
 
         // 只使用验证后的设备数量
@@ -263,7 +688,7 @@ impl DeviceManager {
                     created_count += 1;
                 }
                 Err(e) => {
-                    error!("❌ 创建设备失败: {}", e);
+                    error!("{}", msg::device_create_failed(e));
                 }
             }
         }
@@ -273,22 +698,9 @@ impl DeviceManager {
 
     /// 查找对应工厂名称的活跃核心实例ID
     async fn find_active_core_for_factory(&self, factory_name: &str) -> Result<String, DeviceError> {
-        // 根据工厂名称映射到核心类型前缀
-        let core_prefix = match factory_name {
-            "Software Mining Core" => "cpu-btc",
-            "Maijie L7 Core" => "maijie-l7",
-            "GPU Mining Core Factory" => "gpu-btc",
-            _ => {
-                return Err(DeviceError::InitializationFailed {
-                    device_id: 0,
-                    reason: format!("未知的核心工厂: {}", factory_name),
-                });
-            }
-        };
-
-        // 在活跃核心列表中查找匹配的核心实例
+        // 按创建核心时记录的工厂名匹配，不从核心 ID 猜测
         let matching_cores: Vec<_> = self.active_core_ids.iter()
-            .filter(|core_id| core_id.starts_with(core_prefix))
+            .filter(|core_id| self.core_factories.get(*core_id).map(String::as_str) == Some(factory_name))
             .collect();
 
         if matching_cores.is_empty() {
@@ -311,15 +723,15 @@ impl DeviceManager {
 
     /// 从核心实例扫描设备（从factory移植）
     async fn scan_devices_from_core(&self, core_id: &str) -> Result<Vec<cgminer_core::DeviceInfo>, cgminer_core::CoreError> {
-        info!("从核心 {} 扫描设备", core_id);
+        info!("{}", msg::scanning_core(core_id));
 
         match self.core_registry.scan_devices(core_id).await {
             Ok(devices) => {
-                info!("核心 {} 扫描到 {} 个设备", core_id, devices.len());
+                info!("{}", msg::core_found_devices(core_id, devices.len()));
                 Ok(devices)
             }
             Err(e) => {
-                            warn!("核心 {} 扫描设备失败: {}", core_id, e);
+                            warn!("{}", msg::core_scan_failed(core_id, e));
             // 如果核心扫描失败，回退到生成设备信息的方式
             if core_id.starts_with("cpu-btc") {
                 self.generate_software_device_infos().await
@@ -339,7 +751,7 @@ impl DeviceManager {
         // 从完整配置中读取设备数量
         let device_count = if let Some(ref full_config) = self.full_config {
             if let Some(ref cpu_btc_config) = full_config.cores.cpu_btc {
-                cpu_btc_config.device_count
+                cpu_btc_config.resolved_device_count()
             } else {
                 4 // 默认值
             }
@@ -347,7 +759,7 @@ impl DeviceManager {
             4 // 默认值
         };
 
-        info!("🔧 生成 {} 个软件设备", device_count);
+        info!("{}", msg::generating_software_devices(device_count));
         let mut devices = Vec::new();
 
         for i in 0..device_count {
@@ -416,7 +828,7 @@ impl DeviceManager {
             1 // 默认值
         };
 
-        info!("🔧 生成 {} 个GPU设备", device_count);
+        info!("{}", msg::generating_gpu_devices(device_count));
         let mut devices = Vec::new();
 
         for i in 0..device_count {
@@ -457,18 +869,27 @@ impl DeviceManager {
     }
 
     /// 创建设备实例（从factory移植的核心功能）
-    async fn create_device_instance(&self, device_info: cgminer_core::DeviceInfo) -> Result<Box<dyn MiningDevice>, DeviceError> {
+    async fn create_device_instance(&self, device_id: u32, device_info: cgminer_core::DeviceInfo, core_name: &str) -> Result<Box<dyn MiningDevice>, DeviceError> {
         // 根据设备类型选择对应的核心
         let (core_id, device_config) = match device_info.device_type.as_str() {
+            // 插件核心的设备类型由插件决定，配置取设备自己报告的值
+            _ if crate::core_loader::plugin_for_factory(core_name).is_some() => {
+                let core_id = self.find_active_core_for_factory(core_name).await?;
+                let device_config = crate::device::DeviceConfig {
+                    chain_id: device_info.chain_id,
+                    enabled: true,
+                    frequency: device_info.frequency.unwrap_or(0),
+                    voltage: device_info.voltage.unwrap_or(0),
+                    auto_tune: false,
+                    chip_count: device_info.chip_count.unwrap_or(1),
+                    temperature_limit: self.config.thermal.limit_for(device_id),
+                    fan_speed: device_info.fan_speed,
+                };
+
+                (core_id, device_config)
+            }
             "software" => {
-                let core_id = self.active_core_ids.iter()
-                    .find(|id| id.contains("software") || id.contains("btc"))
-                    .ok_or_else(|| {
-                        DeviceError::InitializationFailed {
-                            device_id: device_info.id,
-                            reason: "BTC软算法核心不可用".to_string(),
-                        }
-                    })?;
+                let core_id = self.find_active_core_for_factory(core_name).await?;
 
                 let device_config = crate::device::DeviceConfig {
                     chain_id: device_info.chain_id,
@@ -477,22 +898,15 @@ impl DeviceManager {
                     voltage: 12,
                     auto_tune: false,
                     chip_count: 1,
-                    temperature_limit: 85.0,
+                    temperature_limit: self.config.thermal.limit_for(device_id),
                     fan_speed: None,
                 };
 
-                (core_id.clone(), device_config)
+                (core_id, device_config)
             }
             // GPU大类 - 包括所有GPU平台类型
             "gpu" | "mac-metal" | "nvidia-cuda" | "amd-opencl" | "intel-opencl" | "generic-opencl" => {
-                let core_id = self.active_core_ids.iter()
-                    .find(|id| id.contains("gpu") || id.contains("gpu-btc"))
-                    .ok_or_else(|| {
-                        DeviceError::InitializationFailed {
-                            device_id: device_info.id,
-                            reason: format!("GPU核心不可用 (设备类型: {})", device_info.device_type),
-                        }
-                    })?;
+                let core_id = self.find_active_core_for_factory(core_name).await?;
 
                 // 根据具体GPU平台调整配置
                 let (frequency, voltage, fan_speed) = match device_info.device_type.as_str() {
@@ -511,21 +925,14 @@ impl DeviceManager {
                     voltage,
                     auto_tune: true,   // GPU支持自动调优
                     chip_count: 1,     // GPU通常为1个处理单元
-                    temperature_limit: 85.0, // GPU温度限制
+                    temperature_limit: self.config.thermal.limit_for(device_id), // GPU温度限制
                     fan_speed,
                 };
 
-                (core_id.clone(), device_config)
+                (core_id, device_config)
             }
             "asic" => {
-                let core_id = self.active_core_ids.iter()
-                    .find(|id| id.contains("asic") || id.contains("maijie"))
-                    .ok_or_else(|| {
-                        DeviceError::InitializationFailed {
-                            device_id: device_info.id,
-                            reason: "Maijie L7核心不可用".to_string(),
-                        }
-                    })?;
+                let core_id = self.find_active_core_for_factory(core_name).await?;
 
                 let device_config = crate::device::DeviceConfig {
                     chain_id: device_info.chain_id,
@@ -534,11 +941,11 @@ impl DeviceManager {
                     voltage: 900,
                     auto_tune: true,
                     chip_count: 126,
-                    temperature_limit: 85.0,
+                    temperature_limit: self.config.thermal.limit_for(device_id),
                     fan_speed: Some(70),
                 };
 
-                (core_id.clone(), device_config)
+                (core_id, device_config)
             }
             _ => {
                 return Err(DeviceError::InvalidConfig {
@@ -547,6 +954,9 @@ impl DeviceManager {
             }
         };
 
+        // 温度保护按设备配置中的上限判断
+        self.thermal_guard.set_limit(device_id, device_config.temperature_limit).await;
+
         // 创建设备代理
         let device_proxy = CoreDeviceProxy::new_with_info(
             device_info,
@@ -581,7 +991,7 @@ impl DeviceManager {
                device_id, device_name, device_type, mapping.core_name);
 
         // 直接创建设备实例
-        let device = self.create_device_instance(device_info.clone()).await.map_err(|e| {
+        let device = self.create_device_instance(device_id, device_info.clone(), &mapping.core_name).await.map_err(|e| {
             DeviceError::InitializationFailed {
                 device_id,
                 reason: format!("创建设备实例失败: {}", e),
@@ -599,6 +1009,9 @@ impl DeviceManager {
             device_type: device_info.device_type,
             chain_id: device_info.chain_id,
             chip_count: device_info.chip_count.unwrap_or(1),
+            serial_number: device_info.serial_number,
+            firmware_version: device_info.firmware_version,
+            hardware_version: device_info.hardware_version,
             status: crate::device::DeviceStatus::Idle,
             temperature: device_info.temperature,
             fan_speed: device_info.fan_speed,
@@ -630,24 +1043,36 @@ impl DeviceManager {
 
     /// 启动设备管理器
     pub async fn start(&mut self) -> Result<(), DeviceError> {
-        info!("Starting device manager");
+        info!("{}", msg::device_manager_starting());
 
         // 设置运行状态
         *self.running.write().await = true;
 
-        // 启动所有设备
-        self.start_all_devices().await?;
+        if self.config.power.enabled {
+            self.soft_start_devices().await;
+        }
+
+        // 启动所有设备（启用分批启动时在后台按批次进行）
+        if self.config.ramp_up.enabled {
+            self.start_ramp_up().await;
+        } else {
+            self.start_all_devices().await?;
+        }
 
         // 启动监控任务
         self.start_monitoring().await?;
 
-        info!("Device manager started successfully");
+        if self.config.auto_tune.enabled {
+            self.start_auto_tune().await;
+        }
+
+        info!("{}", msg::device_manager_started());
         Ok(())
     }
 
     /// 停止设备管理器
     pub async fn stop(&mut self) -> Result<(), DeviceError> {
-        info!("Stopping device manager");
+        info!("{}", msg::device_manager_stopping());
 
         // 设置停止状态
         *self.running.write().await = false;
@@ -657,10 +1082,30 @@ impl DeviceManager {
             handle.abort();
         }
 
+        // 取消未完成的分批启动
+        if let Some(handle) = self.ramp_up_handle.take() {
+            handle.abort();
+            let mut status = self.ramp_up_status.write().await;
+            if status.phase == RampUpPhase::InProgress {
+                status.phase = RampUpPhase::Cancelled;
+            }
+        }
+
+        // 中止未完成的自动调优，已保存的档位下次启动时应用
+        for handle in self.auto_tune_handles.drain(..) {
+            handle.abort();
+        }
+
         // 停止所有设备
         self.stop_all_devices().await?;
 
-        info!("Device manager stopped successfully");
+        if let Some(stage) = &self.power_stage {
+            if let Err(e) = stage.power_down().await {
+                warn!("Failed to power down rails: {}", e);
+            }
+        }
+
+        info!("{}", msg::device_manager_stopped());
         Ok(())
     }
 
@@ -679,16 +1124,133 @@ impl DeviceManager {
                     started_devices += 1;
                 }
                 Err(e) => {
-                    error!("Failed to start device {}: {}", device_id, e);
+                    error!("{}", msg::device_start_failed(device_id, &e));
                     return Err(e);
                 }
             }
         }
 
-        info!("✅ 设备启动完成: {}/{} 个设备成功启动", started_devices, total_devices);
+        info!("{}", msg::devices_started(started_devices, total_devices));
         Ok(())
     }
 
+    /// 按批次启动设备，批次之间等待配置的间隔
+    async fn start_ramp_up(&mut self) {
+        let ramp_up = self.config.ramp_up.clone();
+        let mut device_ids: Vec<u32> = self.devices.read().await.keys().cloned().collect();
+        device_ids.sort_unstable();
+        let batches = ramp_up.batches(&device_ids);
+
+        {
+            let mut status = self.ramp_up_status.write().await;
+            *status = RampUpStatus::new();
+            status.phase = RampUpPhase::InProgress;
+            status.total_devices = device_ids.len();
+            status.total_batches = batches.len();
+            status.started_at = Some(std::time::SystemTime::now());
+        }
+
+        info!("{}", msg::ramp_up_begin(device_ids.len(), ramp_up.batch_size, ramp_up.batch_delay_secs));
+
+        let devices = self.devices.clone();
+        let status = self.ramp_up_status.clone();
+        let event_sender = self.event_sender.clone();
+        let running = self.running.clone();
+        let batch_delay = Duration::from_secs(ramp_up.batch_delay_secs);
+
+        let handle = tokio::spawn(async move {
+            for (index, batch) in batches.iter().enumerate() {
+                if index > 0 {
+                    tokio::time::sleep(batch_delay).await;
+                }
+                if !*running.read().await {
+                    status.write().await.phase = RampUpPhase::Cancelled;
+                    return;
+                }
+
+                for device_id in batch {
+                    let device = devices.read().await.get(device_id).cloned();
+                    let Some(device) = device else { continue };
+                    let result = device.lock().await.start().await;
+                    let mut status = status.write().await;
+                    match result {
+                        Ok(()) => status.started_devices += 1,
+                        Err(e) => {
+                            error!("{}", msg::ramp_up_start_failed(device_id, e));
+                            status.failed_devices.push(*device_id);
+                        }
+                    }
+                }
+
+                let snapshot = {
+                    let mut status = status.write().await;
+                    status.completed_batches = index + 1;
+                    status.clone()
+                };
+                info!("{}", msg::ramp_up_progress(
+                    snapshot.completed_batches, snapshot.total_batches,
+                    snapshot.started_devices, snapshot.total_devices,
+                ));
+
+                if let Some(sender) = &event_sender {
+                    let _ = sender.send(MiningEvent::DeviceRampUp {
+                        started_devices: snapshot.started_devices,
+                        total_devices: snapshot.total_devices,
+                        completed_batches: snapshot.completed_batches,
+                        total_batches: snapshot.total_batches,
+                        timestamp: std::time::SystemTime::now(),
+                    });
+                }
+            }
+
+            let mut status = status.write().await;
+            status.phase = RampUpPhase::Completed;
+            status.completed_at = Some(std::time::SystemTime::now());
+            info!("{}", msg::ramp_up_done(status.started_devices, status.total_devices));
+        });
+
+        self.ramp_up_handle = Some(handle);
+    }
+
+    /// 获取分批启动状态
+    pub async fn get_ramp_up_status(&self) -> RampUpStatus {
+        self.ramp_up_status.read().await.clone()
+    }
+
+    /// 为 `auto_tune = true` 的链启动自动调优，每条链一个任务
+    async fn start_auto_tune(&mut self) {
+        let tune_config = self.config.auto_tune.clone();
+        let tuned_chains: Vec<u8> = self.config.chains.iter()
+            .filter(|chain| chain.enabled && chain.auto_tune)
+            .map(|chain| chain.id)
+            .collect();
+        let store = Arc::new(TuneProfileStore::load(&tune_config.profile_file));
+
+        let device_info = self.device_info.read().await;
+        let devices = self.devices.read().await;
+        for (device_id, info) in device_info.iter() {
+            if !tuned_chains.contains(&info.chain_id) {
+                continue;
+            }
+            let Some(device) = devices.get(device_id).cloned() else { continue };
+            self.auto_tune_handles.push(tokio::spawn(autotune::tune_device(
+                device,
+                info.clone(),
+                tune_config.clone(),
+                store.clone(),
+                self.auto_tune_status.clone(),
+            )));
+        }
+        info!("{}", msg::auto_tune_started(self.auto_tune_handles.len()));
+    }
+
+    /// 获取各链自动调优进度
+    pub async fn get_auto_tune_status(&self) -> Vec<AutoTuneStatus> {
+        let mut statuses: Vec<AutoTuneStatus> = self.auto_tune_status.read().await.values().cloned().collect();
+        statuses.sort_by_key(|status| status.device_id);
+        statuses
+    }
+
     /// 停止所有设备
     async fn stop_all_devices(&self) -> Result<(), DeviceError> {
         let devices = self.devices.read().await;
@@ -697,10 +1259,10 @@ impl DeviceManager {
             let mut device = device.lock().await;
             match device.stop().await {
                 Ok(_) => {
-                    info!("Device {} stopped successfully", device_id);
+                    info!("{}", msg::device_stopped(device_id));
                 }
                 Err(e) => {
-                    warn!("Failed to stop device {}: {}", device_id, e);
+                    warn!("{}", msg::device_stop_failed(device_id, e));
                 }
             }
         }
@@ -713,6 +1275,7 @@ impl DeviceManager {
         let self_clone = self.clone();
         let device_info = self.device_info.clone();
         let device_stats = self.device_stats.clone();
+        let throttle_states = self.throttle_states.clone();
         let running = self.running.clone();
         let scan_interval = Duration::from_secs(self.config.scan_interval);
 
@@ -734,7 +1297,19 @@ impl DeviceManager {
                         }
                         device_stats.write().await.insert(id, core_stats.into());
                     }
+                    if let Some(mapping) = self_clone.device_core_mapper.get_device_mapping(id).await {
+                        if let Some(stats) = device_stats.write().await.get_mut(&id) {
+                            stats.throttle = throttle_states.read().await.get(&mapping.core_name).cloned();
+                        }
+                    }
+
+                    self_clone.apply_thermal_policies(id).await;
+                }
+
+                if self_clone.config.power.enabled {
+                    self_clone.enforce_power_limit().await;
                 }
+                self_clone.update_bad_chips().await;
             }
         });
 
@@ -742,6 +1317,117 @@ impl DeviceManager {
         Ok(())
     }
 
+    /// 读取设备温度，执行温度上限保护并按风扇策略调整转速
+    async fn apply_thermal_policies(&self, device_id: u32) {
+        let device = match self.devices.read().await.get(&device_id) {
+            Some(device) => device.clone(),
+            None => return,
+        };
+
+        let temperature = match device.lock().await.get_temperature().await {
+            Ok(temperature) => temperature,
+            Err(e) => {
+                debug!("Failed to read temperature for device {}: {}", device_id, e);
+                return;
+            }
+        };
+
+        let transition = {
+            let mut info_lock = self.device_info.write().await;
+            match info_lock.get_mut(&device_id) {
+                Some(info) => {
+                    info.update_temperature(temperature);
+                    let transition = self.thermal_guard.evaluate(device_id, temperature, &info.status).await;
+                    if let Some(transition) = &transition {
+                        let old_status = info.status.clone();
+                        match transition {
+                            ThermalTransition::Overheated { limit } => {
+                                warn!("{}", msg::device_overheated(device_id, temperature, limit));
+                                info.update_status(DeviceStatus::Overheated);
+                            }
+                            ThermalTransition::Recovered { restore } => {
+                                info!("{}", msg::device_cooled(device_id, temperature));
+                                info.update_status(restore.clone());
+                            }
+                        }
+                        Some((old_status, info.status.clone()))
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            }
+        };
+
+        if let (Some((old_status, new_status)), Some(sender)) = (transition, &self.event_sender) {
+            let _ = sender.send(MiningEvent::DeviceStateChanged {
+                device_id,
+                old_state: format!("{:?}", old_status),
+                new_state: format!("{:?}", new_status),
+                timestamp: std::time::SystemTime::now(),
+            });
+        }
+
+        if let Some(target) = self.fan_controller.evaluate(device_id, temperature).await {
+            if let Err(e) = self.push_fan_target(device_id, &device, target).await {
+                warn!("{}", msg::fan_policy_failed(device_id, e));
+            }
+        }
+    }
+
+    /// 下发风扇转速目标到设备
+    async fn push_fan_target(
+        &self,
+        device_id: u32,
+        device: &Arc<Mutex<Box<dyn MiningDevice>>>,
+        target: FanSpeedTarget,
+    ) -> Result<(), DeviceError> {
+        match target {
+            FanSpeedTarget::Percent(speed) => {
+                device.lock().await.set_fan_speed(speed).await?;
+                if let Some(info) = self.device_info.write().await.get_mut(&device_id) {
+                    info.fan_speed = Some(speed);
+                }
+                debug!("Device {} fan speed set to {}%", device_id, speed);
+            }
+            FanSpeedTarget::Auto => {
+                let applied = device.lock().await.set_fan_auto().await?;
+                if let (Some(speed), Some(info)) = (applied, self.device_info.write().await.get_mut(&device_id)) {
+                    info.fan_speed = Some(speed);
+                }
+                match applied {
+                    Some(speed) => debug!("Device {} has no automatic fan control, fan speed set to {}%", device_id, speed),
+                    None => debug!("Device {} fan returned to automatic control", device_id),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 设置或清除设备风扇手动覆盖
+    pub async fn set_fan_override(&self, device_id: u32, target: Option<FanSpeedTarget>) -> Result<(), DeviceError> {
+        let device = self.devices.read().await.get(&device_id).cloned()
+            .ok_or(DeviceError::NotFound { device_id })?;
+
+        self.fan_controller.set_override(device_id, target).await;
+        match target {
+            Some(target) => {
+                self.push_fan_target(device_id, &device, target).await?;
+                info!("{}", msg::fan_set(device_id, format!("{:?}", target)));
+            }
+            None => info!("{}", msg::fan_override_cleared(device_id)),
+        }
+        Ok(())
+    }
+
+    /// 获取设备风扇状态
+    pub async fn get_fan_status(&self, device_id: u32) -> Result<FanStatus, DeviceError> {
+        if !self.devices.read().await.contains_key(&device_id) {
+            return Err(DeviceError::NotFound { device_id });
+        }
+        Ok(self.fan_controller.status(device_id).await)
+    }
+
     pub async fn get_device_stats_core(&self, device_id: u32) -> Result<cgminer_core::DeviceStats, DeviceError> {
         if let Some(device) = self.devices.read().await.get(&device_id) {
             let stats_result = device.lock().await.get_stats().await;
@@ -752,7 +1438,7 @@ impl DeviceManager {
                 core_stats.rejected_work = stats.invalid_nonces;
                 core_stats.hardware_errors = stats.hardware_errors;
                 core_stats.uptime = std::time::Duration::from_secs(stats.uptime_seconds);
-                if let Some(last_hashrate) = stats.hashrate_history.iter().last() {
+                if let Some(last_hashrate) = stats.hashrate_history.last() {
                     core_stats.current_hashrate = cgminer_core::types::HashRate::new(*last_hashrate);
                 }
                 core_stats.average_hashrate = cgminer_core::types::HashRate::new(stats.get_average_hashrate().unwrap_or(0.0));
@@ -775,27 +1461,87 @@ impl DeviceManager {
         device_info.values().cloned().collect()
     }
 
+    /// 降频状态表，由挖矿管理器的 CPU 降频任务更新
+    pub fn throttle_states(&self) -> Arc<RwLock<HashMap<String, ThrottleState>>> {
+        self.throttle_states.clone()
+    }
+
     /// 获取设备统计信息
     pub async fn get_device_stats(&self, device_id: u32) -> Option<DeviceStats> {
         let device_stats = self.device_stats.read().await;
         device_stats.get(&device_id).cloned()
     }
 
+    /// 记录设备的硬件错误 (如结果未通过本地份额验证)
+    pub async fn record_hardware_error(&self, device_id: u32) {
+        if let Some(info) = self.device_info.write().await.get_mut(&device_id) {
+            info.increment_hardware_errors();
+        }
+        if let Some(stats) = self.device_stats.write().await.get_mut(&device_id) {
+            stats.record_hardware_error();
+        }
+    }
+
     /// 重启设备
     pub async fn restart_device(&self, device_id: u32) -> Result<(), DeviceError> {
         let devices = self.devices.read().await;
         if let Some(device) = devices.get(&device_id) {
             let mut device = device.lock().await;
             device.restart().await?;
-            info!("Device {} restarted successfully", device_id);
+            info!("{}", msg::device_restarted(device_id));
             Ok(())
         } else {
             Err(DeviceError::NotFound { device_id })
         }
     }
 
+    /// 启用或禁用设备：禁用时停止设备，启用时重新启动
+    pub async fn set_device_enabled(&self, device_id: u32, enabled: bool) -> Result<(), DeviceError> {
+        {
+            let devices = self.devices.read().await;
+            let device = devices.get(&device_id).ok_or(DeviceError::NotFound { device_id })?;
+            let mut device = device.lock().await;
+            if enabled {
+                device.start().await?;
+            } else {
+                device.stop().await?;
+            }
+        }
+
+        if let Some(info) = self.device_info.write().await.get_mut(&device_id) {
+            info.update_status(if enabled { DeviceStatus::Idle } else { DeviceStatus::Disabled });
+        }
+        if enabled {
+            info!("{}", msg::device_enabled(device_id));
+        } else {
+            info!("{}", msg::device_disabled(device_id));
+        }
+        Ok(())
+    }
+
+    /// 核心是否有设备处于过热保护中
+    pub async fn core_overheated(&self, core_id: &str) -> bool {
+        let Some(factory_name) = self.core_factories.get(core_id) else {
+            return false;
+        };
+        for device_id in self.device_core_mapper.get_core_devices(factory_name).await {
+            if self.thermal_guard.is_overheated(device_id).await {
+                return true;
+            }
+        }
+        false
+    }
+
     /// 提交工作到设备
-    pub async fn submit_work(&self, device_id: u32, work: Work) -> Result<(), DeviceError> {
+    pub async fn submit_work(&self, device_id: u32, work: Arc<Work>) -> Result<(), DeviceError> {
+        if self.thermal_guard.is_overheated(device_id).await {
+            let temperature = self.device_info.read().await
+                .get(&device_id)
+                .and_then(|info| info.temperature)
+                .unwrap_or(0.0);
+            return Err(DeviceError::Overheated { device_id, temperature });
+        }
+
         let devices = self.devices.read().await;
         if let Some(device) = devices.get(&device_id) {
             let mut device = device.lock().await;
@@ -818,13 +1564,30 @@ impl DeviceManager {
         }
     }
 
+    /// 从所有设备轮询结果（核心代理设备不产生结果，仅应用层设备如模拟设备会返回）
+    pub async fn collect_device_results(&self) -> Vec<MiningResult> {
+        let devices: Vec<_> = self.devices.read().await.values().cloned().collect();
+        let mut results = Vec::new();
+
+        for device in devices {
+            let mut device = device.lock().await;
+            match device.get_result().await {
+                Ok(Some(result)) => results.push(result),
+                Ok(None) => {}
+                Err(e) => debug!("Failed to get result from device {}: {}", device.device_id(), e),
+            }
+        }
+
+        results
+    }
+
     /// 设置设备频率
     pub async fn set_device_frequency(&self, device_id: u32, frequency: u32) -> Result<(), DeviceError> {
         let devices = self.devices.read().await;
         if let Some(device) = devices.get(&device_id) {
             let mut device = device.lock().await;
             device.set_frequency(frequency).await?;
-            info!("Device {} frequency set to {} MHz", device_id, frequency);
+            info!("{}", msg::frequency_set(device_id, frequency));
             Ok(())
         } else {
             Err(DeviceError::NotFound { device_id })
@@ -837,7 +1600,7 @@ impl DeviceManager {
         if let Some(device) = devices.get(&device_id) {
             let mut device = device.lock().await;
             device.set_voltage(voltage).await?;
-            info!("Device {} voltage set to {} mV", device_id, voltage);
+            info!("{}", msg::voltage_set(device_id, voltage));
             Ok(())
         } else {
             Err(DeviceError::NotFound { device_id })
@@ -946,10 +1709,11 @@ impl DeviceManager {
         }
 
         // 输出总体统计（使用自适应单位）
-        info!("📊 算力统计汇总 | 活跃设备: {} | 总算力: {} | 平均: {}",
-              stats.active_devices,
-              format_hashrate(stats.total_current_hashrate),
-              format_hashrate(stats.total_avg_hashrate / stats.active_devices as f64));
+        info!("{}", msg::hashrate_summary(
+            stats.active_devices,
+            format_hashrate(stats.total_current_hashrate),
+            format_hashrate(stats.total_avg_hashrate / stats.active_devices as f64),
+        ));
 
         // 输出设备详情（分组显示，每行最多5个设备，使用自适应单位）
         for chunk in stats.device_details.chunks(5) {
@@ -1053,6 +1817,9 @@ impl CoreDeviceProxy {
                 device_type: device_info.device_type,
                 chain_id: device_info.chain_id,
                 chip_count: device_info.chip_count.unwrap_or(1),
+                serial_number: device_info.serial_number,
+                firmware_version: device_info.firmware_version,
+                hardware_version: device_info.hardware_version,
                 status: crate::device::DeviceStatus::Idle,
                 temperature: device_info.temperature,
                 fan_speed: device_info.fan_speed,
@@ -1095,6 +1862,9 @@ impl MiningDevice for CoreDeviceProxy {
             device_type: "proxy".to_string(),
             chain_id: 0,
             chip_count: 1,
+            serial_number: None,
+            firmware_version: None,
+            hardware_version: None,
             status: crate::device::DeviceStatus::Idle,
             temperature: Some(45.0),
             fan_speed: Some(50),
@@ -1129,7 +1899,7 @@ impl MiningDevice for CoreDeviceProxy {
         Ok(())
     }
 
-    async fn submit_work(&mut self, _work: Work) -> Result<(), crate::error::DeviceError> {
+    async fn submit_work(&mut self, _work: Arc<Work>) -> Result<(), crate::error::DeviceError> {
         // 通过核心提交工作
         Ok(())
     }
@@ -1160,11 +1930,12 @@ impl MiningDevice for CoreDeviceProxy {
     }
 
     async fn get_temperature(&self) -> Result<f32, crate::error::DeviceError> {
-        Ok(45.0)
+        let cached = self.device_cache.read().await.as_ref().and_then(|info| info.temperature);
+        Ok(cached.unwrap_or(45.0))
     }
 
     async fn get_hashrate(&self) -> Result<f64, crate::error::DeviceError> {
-        // 尝试从核心获取算力统计
+        // 核心只提供汇总统计，按设备数平均
         match self.core_registry.get_core_stats(&self.core_id).await {
             Ok(core_stats) => {
                 // 如果核心有多个设备，计算平均算力
@@ -1185,8 +1956,13 @@ impl MiningDevice for CoreDeviceProxy {
         Ok(())
     }
 
+    /// 核心自行控制其设备的风扇
+    async fn set_fan_auto(&mut self) -> Result<Option<u32>, crate::error::DeviceError> {
+        Ok(None)
+    }
+
     async fn get_stats(&self) -> Result<crate::device::DeviceStats, crate::error::DeviceError> {
-        // 尝试从核心获取真实的统计数据
+        // 核心只提供汇总统计，按设备数平均
         match self.core_registry.get_core_stats(&self.core_id).await {
             Ok(core_stats) => {
                 let mut device_stats = crate::device::DeviceStats::new();
@@ -5,10 +5,28 @@ pub mod conversion;
 // factory模块已整合到manager中
 pub mod device_core_mapper;
 pub mod architecture;
+pub mod autotune;
+#[cfg(all(feature = "avalon", target_os = "linux"))]
+pub mod avalon;
+pub mod chips;
+pub mod eeprom;
+pub mod fan_control;
+#[cfg(all(feature = "hardware", target_os = "linux"))]
+pub mod hardware;
+pub mod power;
+pub mod ramp_up;
+#[cfg(feature = "sim")]
+pub mod sim_hardware;
+pub mod thermal;
+#[cfg(feature = "sim")]
+pub mod virtual_device;
+#[cfg(feature = "whatsminer")]
+pub mod whatsminer;
 
 #[cfg(test)]
 mod tests;
 
+use crate::utils::RingBuffer;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
@@ -17,6 +35,12 @@ pub use manager::DeviceManager;
 pub use traits::ChainController;
 pub use traits::MiningDevice;
 pub use device_core_mapper::{DeviceCoreMapper, DeviceCoreMapping, MappingStats};
+pub use fan_control::{FanController, FanControlConfig, FanSpeedTarget, FanStatus};
+pub use ramp_up::{RampUpConfig, RampUpPhase, RampUpStatus};
+pub use autotune::{AutoTuneConfig, AutoTunePhase, AutoTuneStatus};
+pub use chips::{ChipHealthConfig, ChipHeatmap};
+pub use power::{PowerConfig, PowerStatus};
+pub use thermal::{ThermalConfig, ThermalGuard};
 
 /// 设备状态枚举
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -53,6 +77,10 @@ pub struct DeviceInfo {
     pub device_type: String,
     pub chain_id: u8,
     pub chip_count: u32,
+    /// 板卡序列号（从 EEPROM 或设备上报读取）
+    pub serial_number: Option<String>,
+    pub firmware_version: Option<String>,
+    pub hardware_version: Option<String>,
     pub status: DeviceStatus,
     pub temperature: Option<f32>,
     pub fan_speed: Option<u32>,
@@ -77,6 +105,9 @@ impl DeviceInfo {
             device_type,
             chain_id,
             chip_count: 0,
+            serial_number: None,
+            firmware_version: None,
+            hardware_version: None,
             status: DeviceStatus::default(),
             temperature: None,
             fan_speed: None,
@@ -120,7 +151,6 @@ impl DeviceInfo {
         self.updated_at = SystemTime::now();
     }
 
-    #[allow(dead_code)]
     pub fn increment_hardware_errors(&mut self) {
         self.hardware_errors += 1;
         self.updated_at = SystemTime::now();
@@ -130,7 +160,6 @@ impl DeviceInfo {
         matches!(self.status, DeviceStatus::Idle | DeviceStatus::Mining)
     }
 
-    #[allow(dead_code)]
     pub fn is_overheated(&self) -> bool {
         matches!(self.status, DeviceStatus::Overheated)
     }
@@ -173,44 +202,15 @@ impl From<cgminer_core::DeviceStats> for DeviceStats {
     }
 }
 
-// Work结构现在从cgminer-core导入
-pub use cgminer_core::types::Work;
-
-/// 挖矿结果结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MiningResult {
-    pub work_id: Uuid,
-    pub device_id: u32,
-    pub nonce: u32,
-    pub extra_nonce: Option<u32>,
-    pub timestamp: SystemTime,
-    pub difficulty: f64,
-    pub is_valid: bool,
-}
-
-impl MiningResult {
-    pub fn new(work_id: Uuid, device_id: u32, nonce: u32, difficulty: f64) -> Self {
-        Self {
-            work_id,
-            device_id,
-            nonce,
-            extra_nonce: None,
-            timestamp: SystemTime::now(),
-            difficulty,
-            is_valid: false, // 需要验证后设置
-        }
-    }
-
-    #[allow(dead_code)]
-    pub fn with_extra_nonce(mut self, extra_nonce: u32) -> Self {
-        self.extra_nonce = Some(extra_nonce);
-        self
-    }
+// Work 和 MiningResult 都从 cgminer-core 导入，应用层不再保留自己的副本和转换
+pub use cgminer_core::types::{MiningResult, Work};
 
-    pub fn mark_valid(mut self) -> Self {
-        self.is_valid = true;
-        self
-    }
+/// 设备直接回报的 nonce。芯片不回传哈希，哈希留空，份额难度取工作的难度；
+/// 硬件错误 (`meets_target` 为 false) 只计入统计，不会提交到矿池
+pub fn nonce_result(work_id: Uuid, device_id: u32, nonce: u32, difficulty: f64, meets_target: bool) -> MiningResult {
+    let mut result = MiningResult::new(work_id, device_id, nonce, Vec::new(), meets_target);
+    result.share_difficulty = difficulty;
+    result
 }
 
 /// 设备配置结构
@@ -248,11 +248,29 @@ pub struct DeviceStats {
     pub valid_nonces: u64,
     pub invalid_nonces: u64,
     pub hardware_errors: u64,
-    pub temperature_readings: Vec<f32>,
-    pub hashrate_history: Vec<f64>,
+    /// 最近 100 个温度读数
+    pub temperature_readings: RingBuffer<f32>,
+    /// 最近 100 个算力读数
+    pub hashrate_history: RingBuffer<f64>,
     pub uptime_seconds: u64,
     pub restart_count: u32,
     pub last_restart_time: Option<SystemTime>,
+    /// 核心内温度降频状态，未启用降频时为 `None`
+    #[serde(default)]
+    pub throttle: Option<ThrottleState>,
+}
+
+/// 温度降频状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrottleState {
+    /// 是否正在降频
+    pub throttled: bool,
+    /// 当前占空比 (%)，100 为全速
+    pub duty_percent: u8,
+    /// 最近一次封装温度读数 (°C)
+    pub package_celsius: f32,
+    /// 降频方式: sleep 或 batch_size
+    pub action: String,
 }
 
 impl DeviceStats {
@@ -278,18 +296,10 @@ impl DeviceStats {
 
     pub fn record_temperature(&mut self, temp: f32) {
         self.temperature_readings.push(temp);
-        // 保持最近100个温度读数
-        if self.temperature_readings.len() > 100 {
-            self.temperature_readings.remove(0);
-        }
     }
 
     pub fn record_hashrate(&mut self, hashrate: f64) {
         self.hashrate_history.push(hashrate);
-        // 保持最近100个算力读数
-        if self.hashrate_history.len() > 100 {
-            self.hashrate_history.remove(0);
-        }
     }
 
     pub fn record_restart(&mut self) {
@@ -298,21 +308,11 @@ impl DeviceStats {
     }
 
     pub fn get_average_temperature(&self) -> Option<f32> {
-        if self.temperature_readings.is_empty() {
-            None
-        } else {
-            let sum: f32 = self.temperature_readings.iter().sum();
-            Some(sum / self.temperature_readings.len() as f32)
-        }
+        self.temperature_readings.mean().map(|mean| mean as f32)
     }
 
     pub fn get_average_hashrate(&self) -> Option<f64> {
-        if self.hashrate_history.is_empty() {
-            None
-        } else {
-            let sum: f64 = self.hashrate_history.iter().sum();
-            Some(sum / self.hashrate_history.len() as f64)
-        }
+        self.hashrate_history.mean()
     }
 
     pub fn get_error_rate(&self) -> f64 {
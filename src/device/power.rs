@@ -0,0 +1,218 @@
+//! 电源级控制和软启动
+//!
+//! 上电时按顺序逐路打开各链的电源使能，启动时把各链电压从最低值分步升到目标电压，
+//! 避免浪涌；运行时按板卡功率上限降低功耗最高的链的频率。配置了功率传感器的链
+//! 使用实测值，其余按芯片数、频率和电压估算。
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::time::sleep;
+use tracing::{debug, info};
+
+use super::traits::HardwareInterface;
+use crate::error::DeviceError;
+
+/// 一路电源：链ID和它的使能 GPIO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerRail {
+    pub chain_id: u8,
+    /// 电源使能 GPIO，高有效
+    pub enable_gpio: u32,
+    /// 功率传感器 (hwmon 编号)，未设置时按频率和电压估算
+    #[serde(default)]
+    pub power_sensor: Option<u8>,
+}
+
+/// 电源配置 (`[devices.power]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// 是否启用电源控制和功率上限
+    pub enabled: bool,
+    /// 板卡总功率上限 (W)，未设置时使用 cores.maijie_l7.power_limit
+    pub power_limit_watts: Option<f64>,
+    /// 按顺序打开的电源
+    pub rails: Vec<PowerRail>,
+    /// 相邻两路电源打开的间隔 (毫秒)
+    pub rail_delay_ms: u64,
+    /// 软启动电压步长 (mV)
+    pub soft_start_step_mv: u32,
+    /// 软启动每步间隔 (毫秒)
+    pub soft_start_step_ms: u64,
+    /// 超过功率上限时每次降低的频率 (MHz)
+    pub throttle_step_mhz: u32,
+    /// 降频下限 (MHz)
+    pub min_frequency: u32,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            power_limit_watts: None,
+            rails: Vec::new(),
+            rail_delay_ms: 500,
+            soft_start_step_mv: 25,
+            soft_start_step_ms: 50,
+            throttle_step_mhz: 25,
+            min_frequency: 300,
+        }
+    }
+}
+
+impl PowerConfig {
+    /// 验证电源配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.power_limit_watts.is_some_and(|limit| limit <= 0.0) {
+            anyhow::bail!("Power limit must be greater than 0");
+        }
+        if self.soft_start_step_mv == 0 {
+            anyhow::bail!("Power soft_start_step_mv must be greater than 0");
+        }
+        if self.throttle_step_mhz == 0 {
+            anyhow::bail!("Power throttle_step_mhz must be greater than 0");
+        }
+        let mut chains = std::collections::HashSet::new();
+        for rail in &self.rails {
+            if !chains.insert(rail.chain_id) {
+                anyhow::bail!("Chain {} has more than one power rail", rail.chain_id);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 软启动的电压序列：从 `start` 按步长升到 `target`，最后一步正好是 `target`
+pub fn soft_start_steps(start: u32, target: u32, step: u32) -> Vec<u32> {
+    if target <= start {
+        return vec![target];
+    }
+    let mut steps: Vec<u32> = (start..target).step_by(step.max(1) as usize).collect();
+    steps.push(target);
+    steps
+}
+
+/// 一个设备的功率读数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DevicePower {
+    pub device_id: u32,
+    /// 功率 (W)
+    pub watts: f64,
+    /// 是否为实测值
+    pub measured: bool,
+    pub frequency: u32,
+}
+
+/// 总功率超过上限时选出要降频的设备：功耗最高且还能降频的设备
+pub fn plan_throttle(readings: &[DevicePower], limit: f64, step: u32, min_frequency: u32) -> Option<(u32, u32)> {
+    let total: f64 = readings.iter().map(|r| r.watts).sum();
+    if total <= limit {
+        return None;
+    }
+    readings
+        .iter()
+        .filter(|r| r.frequency > min_frequency)
+        .max_by(|a, b| a.watts.total_cmp(&b.watts))
+        .map(|r| (r.device_id, r.frequency.saturating_sub(step).max(min_frequency)))
+}
+
+/// 功率上限状态
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerStatus {
+    pub enabled: bool,
+    pub power_limit_watts: Option<f64>,
+    pub total_watts: f64,
+    pub devices: Vec<DevicePower>,
+    /// 因功率上限降频的次数
+    pub throttle_count: u64,
+    pub updated_at: SystemTime,
+}
+
+impl PowerStatus {
+    pub fn new(enabled: bool, power_limit_watts: Option<f64>) -> Self {
+        Self {
+            enabled,
+            power_limit_watts,
+            total_watts: 0.0,
+            devices: Vec::new(),
+            throttle_count: 0,
+            updated_at: SystemTime::now(),
+        }
+    }
+}
+
+/// 电源级：按顺序打开和关闭各路电源，读取实测功率
+pub struct PowerStage {
+    config: PowerConfig,
+    hardware: Arc<dyn HardwareInterface>,
+}
+
+impl PowerStage {
+    pub fn new(config: PowerConfig, hardware: Arc<dyn HardwareInterface>) -> Self {
+        Self { config, hardware }
+    }
+
+    /// 逐路打开电源使能，每路之间等待 `rail_delay_ms` 让电源稳定
+    pub async fn power_up(&self) -> Result<(), DeviceError> {
+        for (index, rail) in self.config.rails.iter().enumerate() {
+            if index > 0 {
+                sleep(Duration::from_millis(self.config.rail_delay_ms)).await;
+            }
+            self.hardware.gpio_set(rail.enable_gpio, true).await?;
+            info!("Power rail for chain {} enabled", rail.chain_id);
+        }
+        Ok(())
+    }
+
+    /// 按相反顺序关闭各路电源
+    pub async fn power_down(&self) -> Result<(), DeviceError> {
+        for rail in self.config.rails.iter().rev() {
+            self.hardware.gpio_set(rail.enable_gpio, false).await?;
+            info!("Power rail for chain {} disabled", rail.chain_id);
+        }
+        Ok(())
+    }
+
+    /// 读取链的实测功率，没有传感器或读取失败时为 `None`
+    pub async fn measure(&self, chain_id: u8) -> Option<f64> {
+        let sensor = self.config.rails.iter().find(|rail| rail.chain_id == chain_id)?.power_sensor?;
+        match self.hardware.read_power(sensor).await {
+            Ok(watts) => watts,
+            Err(e) => {
+                debug!("Failed to read power sensor {} for chain {}: {}", sensor, chain_id, e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(device_id: u32, watts: f64, frequency: u32) -> DevicePower {
+        DevicePower { device_id, watts, measured: false, frequency }
+    }
+
+    #[test]
+    fn test_soft_start_steps() {
+        assert_eq!(soft_start_steps(600, 700, 25), vec![600, 625, 650, 675, 700]);
+        assert_eq!(soft_start_steps(600, 660, 25), vec![600, 625, 650, 660]);
+        assert_eq!(soft_start_steps(600, 600, 25), vec![600]);
+    }
+
+    #[test]
+    fn test_plan_throttle() {
+        let readings = vec![reading(1, 1200.0, 600), reading(2, 1500.0, 650), reading(3, 900.0, 500)];
+        assert_eq!(plan_throttle(&readings, 4000.0, 25, 300), None);
+        assert_eq!(plan_throttle(&readings, 3000.0, 25, 300), Some((2, 625)));
+
+        // 已到降频下限的设备被跳过
+        let readings = vec![reading(1, 1200.0, 600), reading(2, 1500.0, 300)];
+        assert_eq!(plan_throttle(&readings, 2000.0, 25, 300), Some((1, 575)));
+    }
+}
@@ -0,0 +1,115 @@
+//! 设备分批启动（预热/爬坡）
+//!
+//! 大型矿机同时上电会产生浪涌电流导致断路器跳闸，
+//! 按配置分批启动设备，每批之间等待指定时间。
+
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// 分批启动配置 (`[devices.ramp_up]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RampUpConfig {
+    /// 是否启用分批启动
+    pub enabled: bool,
+    /// 每批启动的设备数量
+    pub batch_size: usize,
+    /// 批次间隔 (秒)
+    pub batch_delay_secs: u64,
+}
+
+impl Default for RampUpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            batch_size: 2,
+            batch_delay_secs: 10,
+        }
+    }
+}
+
+impl RampUpConfig {
+    /// 验证分批启动配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.enabled && self.batch_size == 0 {
+            anyhow::bail!("Ramp-up batch_size must be greater than 0");
+        }
+        Ok(())
+    }
+
+    /// 将设备ID按批次切分
+    pub fn batches(&self, device_ids: &[u32]) -> Vec<Vec<u32>> {
+        device_ids
+            .chunks(self.batch_size.max(1))
+            .map(|chunk| chunk.to_vec())
+            .collect()
+    }
+}
+
+/// 分批启动阶段
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum RampUpPhase {
+    /// 尚未开始
+    Pending,
+    /// 正在分批启动
+    InProgress,
+    /// 全部批次已完成
+    Completed,
+    /// 已取消（设备管理器停止）
+    Cancelled,
+}
+
+/// 分批启动状态
+#[derive(Debug, Clone, Serialize)]
+pub struct RampUpStatus {
+    pub phase: RampUpPhase,
+    pub total_devices: usize,
+    pub started_devices: usize,
+    /// 启动失败的设备ID
+    pub failed_devices: Vec<u32>,
+    /// 已完成的批次数
+    pub completed_batches: usize,
+    pub total_batches: usize,
+    pub started_at: Option<SystemTime>,
+    pub completed_at: Option<SystemTime>,
+}
+
+impl RampUpStatus {
+    pub fn new() -> Self {
+        Self {
+            phase: RampUpPhase::Pending,
+            total_devices: 0,
+            started_devices: 0,
+            failed_devices: Vec::new(),
+            completed_batches: 0,
+            total_batches: 0,
+            started_at: None,
+            completed_at: None,
+        }
+    }
+}
+
+impl Default for RampUpStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches() {
+        let config = RampUpConfig { enabled: true, batch_size: 2, batch_delay_secs: 0 };
+        let batches = config.batches(&[1, 2, 3, 4, 5]);
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = RampUpConfig { enabled: true, batch_size: 0, batch_delay_secs: 10 };
+        assert!(config.validate().is_err());
+        assert!(RampUpConfig::default().validate().is_ok());
+    }
+}
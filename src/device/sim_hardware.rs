@@ -0,0 +1,529 @@
+//! 模拟 ASIC 硬件 - 在 SPI 上应答链控制器的芯片命令
+//!
+//! 通过 `--features sim` 和 `[cores.sim.asic]` 启用，代替 `[[devices.boards]]` 的硬件后端。
+//! 每条链有若干芯片，按频率和核心数以指数分布的间隔产出 nonce，可注入随机 CRC 错误、
+//! 链掉线、CRC 突发和温度突升，用于在 CI 中覆盖链初始化、结果读取和恢复逻辑。
+//! 每条链由一个 [`SimAsicBoard`] 设备通过真实的 [`AsicChainController`] 驱动。
+
+use crate::config::{SimAsicConfig, SimFault, SimFaultKind};
+use crate::device::chain::{AsicChainController, ChainRecoveryConfig};
+use crate::device::chips::ChipStats;
+use crate::device::eeprom::{BoardCalibration, BoardEeprom};
+use crate::device::traits::{ChainController, HardwareInterface};
+use crate::device::{nonce_result, DeviceConfig, DeviceInfo, DeviceStats, DeviceStatus, MiningDevice, MiningResult, Work};
+use crate::error::DeviceError;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// 模拟 ASIC 设备ID起始值，位于虚拟设备之后
+pub const SIM_ASIC_DEVICE_ID_BASE: u32 = 9100;
+
+/// 难度1对应的期望哈希数
+const HASHES_PER_DIFF1: f64 = 4_294_967_296.0;
+
+/// 满载时芯片温升 (°C)
+const LOAD_TEMPERATURE_RISE: f32 = 40.0;
+
+/// 环境温度 (°C)
+const AMBIENT_TEMPERATURE: f32 = 25.0;
+
+/// 单个模拟芯片
+struct SimChip {
+    frequency: u32,
+    voltage: u32,
+    /// 收到的工作数，低 8 位作为结果中的工作ID
+    jobs: u32,
+    nonce_range: (u32, u32),
+    next_nonce_at: Option<Instant>,
+}
+
+/// 单条模拟链
+struct SimChain {
+    chips: Vec<SimChip>,
+    /// 上次复位时间，复位会清除持续到复位的故障
+    last_reset: Option<Instant>,
+    voltage: u32,
+}
+
+struct SimState {
+    rng: fastrand::Rng,
+    chains: Vec<SimChain>,
+}
+
+/// 模拟 ASIC 硬件
+pub struct SimHardware {
+    config: SimAsicConfig,
+    started: Instant,
+    state: Mutex<SimState>,
+}
+
+impl SimHardware {
+    pub fn new(config: SimAsicConfig, seed: u64) -> Self {
+        let chains = (0..config.chain_count)
+            .map(|_| SimChain {
+                chips: (0..config.chips_per_chain)
+                    .map(|_| SimChip { frequency: 0, voltage: 0, jobs: 0, nonce_range: (0, u32::MAX), next_nonce_at: None })
+                    .collect(),
+                last_reset: None,
+                voltage: 0,
+            })
+            .collect();
+        info!("🧪 Simulated ASIC hardware: {} chains × {} chips, {} faults",
+              config.chain_count, config.chips_per_chain, config.faults.len());
+        Self {
+            config,
+            started: Instant::now(),
+            state: Mutex::new(SimState { rng: fastrand::Rng::with_seed(seed), chains }),
+        }
+    }
+
+    /// 故障当前是否生效
+    fn fault_active(&self, fault: &SimFault, chain: &SimChain, now: Instant) -> bool {
+        let begins = self.started + Duration::from_secs(fault.after_secs);
+        if now < begins {
+            return false;
+        }
+        if let Some(duration) = fault.duration_secs {
+            return now < begins + Duration::from_secs(duration);
+        }
+        // 持续到复位的故障：故障开始后的复位将其清除
+        fault.kind == SimFaultKind::TemperatureSpike || !chain.last_reset.is_some_and(|reset| reset >= begins)
+    }
+
+    fn active_faults<'a>(&'a self, chain_id: u8, chain: &'a SimChain, now: Instant) -> impl Iterator<Item = &'a SimFault> {
+        self.config
+            .faults
+            .iter()
+            .filter(move |fault| fault.chain_id == chain_id && self.fault_active(fault, chain, now))
+    }
+
+    /// 芯片每秒期望产出的难度1 nonce 数
+    fn nonce_rate(&self, frequency: u32) -> f64 {
+        frequency as f64 * 1e6 * self.config.cores_per_chip as f64 / HASHES_PER_DIFF1
+    }
+
+    fn chain_temperature(&self, chain_id: u8, chain: &SimChain, now: Instant) -> f32 {
+        let max_frequency = *crate::config::FREQUENCY_RANGE.end() as f32;
+        let load = chain.chips.iter().map(|chip| chip.frequency as f32).sum::<f32>()
+            / (chain.chips.len().max(1) as f32 * max_frequency);
+        let spike: f32 = self
+            .active_faults(chain_id, chain, now)
+            .filter(|fault| fault.kind == SimFaultKind::TemperatureSpike)
+            .map(|fault| fault.spike_celsius)
+            .sum();
+        AMBIENT_TEMPERATURE + LOAD_TEMPERATURE_RISE * load + spike
+    }
+
+    fn chain_error(chain_id: u8, error: &str) -> DeviceError {
+        DeviceError::ChainError { chain_id, error: error.to_string() }
+    }
+
+    /// 处理一条芯片命令：`55 AA cmd chip data...`
+    fn handle_command(&self, chain_id: u8, command: &[u8]) -> Result<Vec<u8>, DeviceError> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let SimState { rng, chains } = &mut *state;
+        let chain = chains.get_mut(chain_id as usize).ok_or_else(|| Self::chain_error(chain_id, "no such chain"))?;
+
+        let mut error_rate = self.config.crc_error_rate;
+        for fault in self.active_faults(chain_id, chain, now) {
+            match fault.kind {
+                SimFaultKind::ChainDropout => {
+                    return Err(DeviceError::CommunicationError {
+                        device_id: chain_id as u32,
+                        error: "simulated chain dropout".to_string(),
+                    });
+                }
+                SimFaultKind::CrcBurst => error_rate = error_rate.max(fault.burst_error_rate),
+                SimFaultKind::TemperatureSpike => {}
+            }
+        }
+
+        if command.len() < 4 || command[0] != 0x55 || command[1] != 0xAA {
+            return Ok(vec![0; 8]);
+        }
+        let (cmd, chip_id) = (command[2], command[3]);
+        let arg16 = |offset: usize| command.get(offset..offset + 2).map_or(0, |b| u16::from_be_bytes([b[0], b[1]]) as u32);
+        let arg32 = |offset: usize| command.get(offset..offset + 4).map_or(0, |b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+
+        // 链尾之后的芯片不应答
+        if chip_id as usize >= chain.chips.len() {
+            return Ok(vec![0; 8]);
+        }
+        if rng.f64() < error_rate {
+            return Ok(vec![0xFF; 8]);
+        }
+
+        let mut response = vec![0x55, 0xAA, cmd, chip_id, 0, 0, 0, 0];
+        match cmd {
+            // 检测
+            0x51 => {}
+            // PLL
+            0x52 => {
+                let chip = &mut chain.chips[chip_id as usize];
+                chip.frequency = arg16(4);
+                let rate = self.nonce_rate(chip.frequency);
+                chip.next_nonce_at = next_nonce_at(rng, rate, now);
+            }
+            // 电压
+            0x53 => chain.chips[chip_id as usize].voltage = arg16(4),
+            // 工作
+            0x54 => chain.chips[chip_id as usize].jobs += 1,
+            // 读取结果
+            0x55 => {
+                let rate = self.nonce_rate(chain.chips[chip_id as usize].frequency);
+                let chip = &mut chain.chips[chip_id as usize];
+                if chip.jobs > 0 && chip.next_nonce_at.is_some_and(|at| now >= at) {
+                    let (start, end) = chip.nonce_range;
+                    let nonce = rng.u32(start..=end).max(1);
+                    response[3] = chip.jobs as u8;
+                    response[4..8].copy_from_slice(&nonce.to_be_bytes());
+                    chip.next_nonce_at = next_nonce_at(rng, rate, now);
+                }
+            }
+            // 复位
+            0x56 => {
+                chain.last_reset = Some(now);
+                let chip = &mut chain.chips[chip_id as usize];
+                chip.jobs = 0;
+            }
+            // 温度，单位 0.1°C
+            0x57 => {
+                let temperature = self.chain_temperature(chain_id, chain, now) + rng.f32() - 0.5;
+                response[4..6].copy_from_slice(&((temperature.max(0.0) * 10.0) as u16).to_be_bytes());
+            }
+            // nonce 区间
+            0x58 => chain.chips[chip_id as usize].nonce_range = (arg32(4), arg32(8)),
+            _ => debug!("Simulated chain {} ignoring command {:02x}", chain_id, cmd),
+        }
+        Ok(response)
+    }
+}
+
+/// 按指数分布抽取下一个 nonce 的时间
+fn next_nonce_at(rng: &mut fastrand::Rng, rate: f64, now: Instant) -> Option<Instant> {
+    if rate <= 0.0 {
+        return None;
+    }
+    let u = rng.f64().max(f64::MIN_POSITIVE);
+    Some(now + Duration::from_secs_f64(-u.ln() / rate))
+}
+
+#[async_trait]
+impl HardwareInterface for SimHardware {
+    async fn spi_transfer(&self, chain_id: u8, data: &[u8]) -> Result<Vec<u8>, DeviceError> {
+        self.handle_command(chain_id, data)
+    }
+
+    async fn uart_write(&self, chain_id: u8, _data: &[u8]) -> Result<(), DeviceError> {
+        Err(Self::chain_error(chain_id, "simulated chains use SPI"))
+    }
+
+    async fn uart_read(&self, chain_id: u8, _len: usize) -> Result<Vec<u8>, DeviceError> {
+        Err(Self::chain_error(chain_id, "simulated chains use SPI"))
+    }
+
+    async fn gpio_set(&self, pin: u32, value: bool) -> Result<(), DeviceError> {
+        // 复位线低有效，释放（拉高）时完成复位
+        let Some(chain_id) = pin.checked_sub(self.config.reset_gpio_base) else { return Ok(()) };
+        if value {
+            let mut state = self.state.lock().unwrap();
+            if let Some(chain) = state.chains.get_mut(chain_id as usize) {
+                chain.last_reset = Some(Instant::now());
+                for chip in &mut chain.chips {
+                    chip.jobs = 0;
+                }
+                debug!("Simulated chain {} hardware reset", chain_id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn gpio_get(&self, _pin: u32) -> Result<bool, DeviceError> {
+        Ok(true)
+    }
+
+    async fn pwm_set_duty(&self, _channel: u32, _duty: f32) -> Result<(), DeviceError> {
+        Ok(())
+    }
+
+    async fn read_temperature(&self, sensor_id: u8) -> Result<f32, DeviceError> {
+        let state = self.state.lock().unwrap();
+        let chain = state.chains.get(sensor_id as usize).ok_or_else(|| Self::chain_error(sensor_id, "no such chain"))?;
+        Ok(self.chain_temperature(sensor_id, chain, Instant::now()))
+    }
+
+    async fn read_power(&self, sensor_id: u8) -> Result<Option<f64>, DeviceError> {
+        let state = self.state.lock().unwrap();
+        let chain = state.chains.get(sensor_id as usize).ok_or_else(|| Self::chain_error(sensor_id, "no such chain"))?;
+        let watts: f64 = chain
+            .chips
+            .iter()
+            .map(|chip| crate::device::autotune::estimate_power(1, chip.frequency, chip.voltage.max(chain.voltage)))
+            .sum();
+        Ok(Some(watts))
+    }
+
+    async fn read_eeprom(&self, chain_id: u8) -> Result<Vec<u8>, DeviceError> {
+        if chain_id as u32 >= self.config.chain_count {
+            return Err(Self::chain_error(chain_id, "no such chain"));
+        }
+        let eeprom = BoardEeprom {
+            serial_number: format!("SIM{:04}", chain_id),
+            firmware_version: env!("CARGO_PKG_VERSION").to_string(),
+            hardware_version: "sim".to_string(),
+            calibration: BoardCalibration { chip_count: self.config.chips_per_chain, frequency: 500, voltage: 850 },
+        };
+        Ok(eeprom.encode())
+    }
+
+    async fn set_voltage(&self, chain_id: u8, voltage: u32) -> Result<(), DeviceError> {
+        let mut state = self.state.lock().unwrap();
+        let chain = state.chains.get_mut(chain_id as usize).ok_or_else(|| Self::chain_error(chain_id, "no such chain"))?;
+        chain.voltage = voltage;
+        Ok(())
+    }
+
+    async fn set_frequency(&self, chain_id: u8, frequency: u32) -> Result<(), DeviceError> {
+        let now = Instant::now();
+        let rate = self.nonce_rate(frequency);
+        let mut state = self.state.lock().unwrap();
+        let SimState { rng, chains } = &mut *state;
+        let chain = chains.get_mut(chain_id as usize).ok_or_else(|| Self::chain_error(chain_id, "no such chain"))?;
+        for chip in &mut chain.chips {
+            chip.frequency = frequency;
+            chip.next_nonce_at = next_nonce_at(rng, rate, now);
+        }
+        Ok(())
+    }
+}
+
+/// 一条模拟链作为一个挖矿设备
+pub struct SimAsicBoard {
+    device_id: u32,
+    info: DeviceInfo,
+    chain: AsicChainController,
+    cores_per_chip: u32,
+    current_work: Option<Arc<Work>>,
+    started_at: Option<Instant>,
+    stats: DeviceStats,
+}
+
+impl SimAsicBoard {
+    pub fn new(chain_id: u8, config: &SimAsicConfig, hardware: Arc<dyn HardwareInterface>) -> Self {
+        let device_id = SIM_ASIC_DEVICE_ID_BASE + chain_id as u32;
+        let info = DeviceInfo::new(device_id, format!("Sim ASIC Chain {}", chain_id), "sim-asic".to_string(), chain_id);
+        let recovery = ChainRecoveryConfig {
+            reset_gpio: Some(config.reset_gpio_base + chain_id as u32),
+            ..Default::default()
+        };
+        Self {
+            device_id,
+            info,
+            chain: AsicChainController::new(chain_id, hardware).with_recovery(recovery),
+            cores_per_chip: config.cores_per_chip,
+            current_work: None,
+            started_at: None,
+            stats: DeviceStats::new(),
+        }
+    }
+
+    /// 为每条模拟链创建设备，共用同一个模拟硬件
+    pub fn create_all(config: &SimAsicConfig, hardware: Arc<dyn HardwareInterface>) -> Vec<SimAsicBoard> {
+        (0..config.chain_count).map(|chain| SimAsicBoard::new(chain as u8, config, hardware.clone())).collect()
+    }
+
+    fn nominal_hashrate(&self) -> f64 {
+        self.info.chip_count as f64 * self.info.frequency.unwrap_or(0) as f64 * 1e6 * self.cores_per_chip as f64
+    }
+}
+
+#[async_trait]
+impl MiningDevice for SimAsicBoard {
+    fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    async fn get_info(&self) -> Result<DeviceInfo, DeviceError> {
+        let mut info = self.info.clone();
+        if let Some(started) = self.started_at {
+            info.uptime = started.elapsed();
+        }
+        Ok(info)
+    }
+
+    async fn initialize(&mut self, config: DeviceConfig) -> Result<(), DeviceError> {
+        self.chain.initialize().await?;
+        self.chain.set_pll_frequency(config.frequency).await?;
+        self.chain.set_voltage(config.voltage).await?;
+        self.info.chip_count = self.chain.get_chip_stats().await.len() as u32;
+        self.info.frequency = Some(config.frequency);
+        self.info.voltage = Some(config.voltage);
+        self.info.update_status(DeviceStatus::Idle);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<(), DeviceError> {
+        self.started_at = Some(Instant::now());
+        self.info.update_status(DeviceStatus::Mining);
+        self.info.update_hashrate(self.nominal_hashrate());
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), DeviceError> {
+        self.info.update_status(DeviceStatus::Idle);
+        self.info.update_hashrate(0.0);
+        self.current_work = None;
+        Ok(())
+    }
+
+    async fn restart(&mut self) -> Result<(), DeviceError> {
+        self.stop().await?;
+        self.chain.reset().await?;
+        self.stats.record_restart();
+        self.start().await
+    }
+
+    async fn submit_work(&mut self, work: Arc<Work>) -> Result<(), DeviceError> {
+        self.chain.send_job(&work.header[..32]).await?;
+        self.current_work = Some(work);
+        Ok(())
+    }
+
+    async fn get_result(&mut self) -> Result<Option<MiningResult>, DeviceError> {
+        let (work_id, difficulty) = match &self.current_work {
+            Some(work) if matches!(self.info.status, DeviceStatus::Mining) => (work.id, work.difficulty),
+            _ => return Ok(None),
+        };
+        // 链恢复后芯片数可能变化
+        self.info.chip_count = self.chain.get_chip_stats().await.len() as u32;
+        match self.chain.read_result().await? {
+            Some((nonce, _)) => {
+                self.stats.record_valid_nonce();
+                self.info.increment_accepted_shares();
+                Ok(Some(nonce_result(work_id, self.device_id, nonce, difficulty, true)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn get_status(&self) -> Result<DeviceStatus, DeviceError> {
+        Ok(self.info.status.clone())
+    }
+
+    async fn get_temperature(&self) -> Result<f32, DeviceError> {
+        self.chain.get_temperature().await
+    }
+
+    async fn get_hashrate(&self) -> Result<f64, DeviceError> {
+        Ok(self.info.hashrate)
+    }
+
+    async fn get_stats(&self) -> Result<DeviceStats, DeviceError> {
+        let mut stats = self.stats.clone();
+        stats.record_temperature(self.chain.get_temperature().await?);
+        stats.record_hashrate(self.info.hashrate);
+        stats.uptime_seconds = self.started_at.map(|s| s.elapsed().as_secs()).unwrap_or(0);
+        Ok(stats)
+    }
+
+    async fn set_frequency(&mut self, frequency: u32) -> Result<(), DeviceError> {
+        self.chain.set_pll_frequency(frequency).await?;
+        self.info.frequency = Some(frequency);
+        if matches!(self.info.status, DeviceStatus::Mining) {
+            self.info.update_hashrate(self.nominal_hashrate());
+        }
+        Ok(())
+    }
+
+    async fn set_voltage(&mut self, voltage: u32) -> Result<(), DeviceError> {
+        self.chain.set_voltage(voltage).await?;
+        self.info.voltage = Some(voltage);
+        Ok(())
+    }
+
+    async fn set_fan_speed(&mut self, speed: u32) -> Result<(), DeviceError> {
+        self.info.fan_speed = Some(speed);
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<bool, DeviceError> {
+        Ok(self.chain.is_healthy().await)
+    }
+
+    async fn reset_stats(&mut self) -> Result<(), DeviceError> {
+        self.stats = DeviceStats::new();
+        Ok(())
+    }
+
+    async fn get_chip_stats(&self) -> Result<Vec<ChipStats>, DeviceError> {
+        Ok(self.chain.get_chip_stats().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(faults: Vec<SimFault>) -> SimAsicConfig {
+        SimAsicConfig { enabled: true, chain_count: 2, chips_per_chain: 3, faults, ..Default::default() }
+    }
+
+    fn fault(kind: SimFaultKind, chain_id: u8) -> SimFault {
+        SimFault { kind, chain_id, after_secs: 0, duration_secs: None, spike_celsius: 30.0, burst_error_rate: 1.0 }
+    }
+
+    #[tokio::test]
+    async fn test_chain_detects_simulated_chips() {
+        let hardware = Arc::new(SimHardware::new(config(Vec::new()), 1));
+        let mut chain = AsicChainController::new(0, hardware);
+        chain.initialize().await.unwrap();
+        assert_eq!(chain.get_chip_stats().await.len(), 3);
+        assert!(chain.is_healthy().await);
+    }
+
+    #[tokio::test]
+    async fn test_dropout_cleared_by_hardware_reset() {
+        let hardware = SimHardware::new(config(vec![fault(SimFaultKind::ChainDropout, 1)]), 1);
+        let detect = [0x55, 0xAA, 0x51, 0, 0, 0, 0, 0];
+        assert!(hardware.spi_transfer(0, &detect).await.is_ok());
+        assert!(hardware.spi_transfer(1, &detect).await.is_err());
+
+        hardware.gpio_set(21, false).await.unwrap();
+        hardware.gpio_set(21, true).await.unwrap();
+        assert!(hardware.spi_transfer(1, &detect).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_crc_burst_triggers_chain_recovery() {
+        let hardware = Arc::new(SimHardware::new(config(vec![fault(SimFaultKind::CrcBurst, 0)]), 1));
+        let recovery = ChainRecoveryConfig { min_samples: 3, cooldown_secs: 0, reset_gpio: Some(20), reset_pulse_ms: 1, ..Default::default() };
+        let mut chain = AsicChainController::new(0, hardware).with_recovery(recovery);
+
+        // 突发期间检测不到芯片
+        assert!(chain.initialize().await.is_err());
+        // 读取结果时按错误率触发恢复，硬件复位清除突发后重新枚举到全部芯片
+        chain.read_result().await.unwrap();
+        assert_eq!(chain.get_recovery_count().await, 1);
+        assert_eq!(chain.get_chip_stats().await.len(), 3);
+        assert_eq!(chain.crc_error_rate().await, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_temperature_spike() {
+        let hardware = SimHardware::new(config(vec![fault(SimFaultKind::TemperatureSpike, 1)]), 1);
+        let normal = hardware.read_temperature(0).await.unwrap();
+        let spiked = hardware.read_temperature(1).await.unwrap();
+        assert!((spiked - normal - 30.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_eeprom_parses() {
+        let hardware = SimHardware::new(config(Vec::new()), 1);
+        let eeprom = BoardEeprom::parse(&hardware.read_eeprom(1).await.unwrap()).unwrap();
+        assert_eq!(eeprom.serial_number, "SIM0001");
+        assert_eq!(eeprom.calibration.chip_count, 3);
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
 
-    use crate::device::{DeviceInfo, DeviceStatus, DeviceStats, Work, MiningResult, DeviceConfig};
+    use crate::device::{nonce_result, DeviceInfo, DeviceStatus, DeviceStats, Work, DeviceConfig};
     // VirtualDevice removed - using cgminer-cpu-btc-core instead
     use crate::device::traits::MiningDevice;
     use std::time::{Duration, SystemTime};
@@ -132,37 +132,19 @@ mod tests {
     }
 
     #[test]
-    fn test_mining_result_creation() {
+    fn test_nonce_result() {
         let work_id = Uuid::new_v4();
-        let result = MiningResult::new(work_id, 0, 0x12345678, 1024.0);
+        let result = nonce_result(work_id, 0, 0x12345678, 1024.0, true);
 
         assert_eq!(result.work_id, work_id);
         assert_eq!(result.device_id, 0);
         assert_eq!(result.nonce, 0x12345678);
-        assert_eq!(result.difficulty, 1024.0);
-        // 新创建的结果默认是无效的，需要验证后标记为有效
-        assert!(!result.is_valid);
+        assert_eq!(result.share_difficulty, 1024.0);
+        assert!(result.meets_target);
+        assert!(result.hash.is_empty());
 
-        // 标记为有效后应该是有效的
-        let valid_result = result.mark_valid();
-        assert!(valid_result.is_valid);
-    }
-
-    #[test]
-    fn test_mining_result_validation() {
-        let work_id = Uuid::new_v4();
-        let result = MiningResult::new(work_id, 0, 0x12345678, 1024.0);
-
-        // 测试结果创建
-        assert_eq!(result.work_id, work_id);
-        assert_eq!(result.device_id, 0);
-        assert_eq!(result.nonce, 0x12345678);
-        assert_eq!(result.difficulty, 1024.0);
-        assert!(!result.is_valid); // 默认为false，需要验证后设置
-
-        // 测试标记为有效
-        let valid_result = result.mark_valid();
-        assert!(valid_result.is_valid);
+        // 硬件错误的结果不满足目标
+        assert!(!nonce_result(work_id, 0, 0x12345678, 1024.0, false).meets_target);
     }
 
     #[test]
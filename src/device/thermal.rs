@@ -0,0 +1,180 @@
+//! 设备温度上限保护
+//!
+//! 设备温度超过其 `DeviceConfig.temperature_limit` 时标记为 `Overheated`，
+//! 并停止向该设备及其所属核心分发工作，温度降到 `上限 - 回滞值` 以下后自动恢复。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use super::{DeviceConfig, DeviceStatus};
+
+/// 单个设备的温度上限覆盖
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceTemperatureLimit {
+    pub device_id: u32,
+    pub temperature_limit: f32,
+}
+
+/// 温度保护配置 (`[devices.thermal]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThermalConfig {
+    /// 是否启用温度保护
+    pub enabled: bool,
+    /// 恢复回滞 (°C)
+    pub hysteresis: f32,
+    /// 按设备覆盖的温度上限，写入设备的 `DeviceConfig.temperature_limit`
+    pub device_limits: Vec<DeviceTemperatureLimit>,
+}
+
+impl Default for ThermalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            hysteresis: 10.0,
+            device_limits: Vec::new(),
+        }
+    }
+}
+
+impl ThermalConfig {
+    /// 验证温度保护配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.hysteresis < 0.0 {
+            anyhow::bail!("Thermal hysteresis must not be negative");
+        }
+        for limit in std::iter::once(DeviceConfig::default().temperature_limit)
+            .chain(self.device_limits.iter().map(|l| l.temperature_limit))
+        {
+            if limit <= 0.0 || limit > 150.0 {
+                anyhow::bail!("Temperature limit {} is out of range (1-150)", limit);
+            }
+            if self.hysteresis >= limit {
+                anyhow::bail!("Thermal hysteresis {} must be lower than temperature limit {}", self.hysteresis, limit);
+            }
+        }
+        Ok(())
+    }
+
+    /// 创建设备时使用的温度上限：有覆盖取覆盖值，否则为 `DeviceConfig` 的默认上限
+    pub fn limit_for(&self, device_id: u32) -> f32 {
+        self.device_limits
+            .iter()
+            .find(|l| l.device_id == device_id)
+            .map(|l| l.temperature_limit)
+            .unwrap_or_else(|| DeviceConfig::default().temperature_limit)
+    }
+}
+
+/// 温度状态转换
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThermalTransition {
+    /// 超过上限，进入过热保护
+    Overheated { limit: f32 },
+    /// 已降温，恢复到进入保护前的状态
+    Recovered { restore: DeviceStatus },
+}
+
+/// 温度保护器
+pub struct ThermalGuard {
+    config: ThermalConfig,
+    /// 设备创建时登记的 `DeviceConfig.temperature_limit`
+    limits: RwLock<HashMap<u32, f32>>,
+    /// 处于过热保护中的设备及其进入保护前的状态
+    overheated: RwLock<HashMap<u32, DeviceStatus>>,
+}
+
+impl ThermalGuard {
+    pub fn new(config: ThermalConfig) -> Self {
+        Self {
+            config,
+            limits: RwLock::new(HashMap::new()),
+            overheated: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 登记设备配置中的温度上限
+    pub async fn set_limit(&self, device_id: u32, temperature_limit: f32) {
+        self.limits.write().await.insert(device_id, temperature_limit);
+    }
+
+    /// 设备是否处于过热保护中
+    pub async fn is_overheated(&self, device_id: u32) -> bool {
+        self.overheated.read().await.contains_key(&device_id)
+    }
+
+    /// 根据当前温度判断是否需要状态转换
+    pub async fn evaluate(&self, device_id: u32, temperature: f32, current: &DeviceStatus) -> Option<ThermalTransition> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let limit = match self.limits.read().await.get(&device_id) {
+            Some(limit) => *limit,
+            None => self.config.limit_for(device_id),
+        };
+        let mut overheated = self.overheated.write().await;
+        if overheated.contains_key(&device_id) {
+            if temperature < limit - self.config.hysteresis {
+                let restore = overheated.remove(&device_id).unwrap_or(DeviceStatus::Idle);
+                return Some(ThermalTransition::Recovered { restore });
+            }
+            None
+        } else if temperature > limit {
+            overheated.insert(device_id, current.clone());
+            Some(ThermalTransition::Overheated { limit })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_overheat_and_recover() {
+        let guard = ThermalGuard::new(ThermalConfig::default());
+
+        assert_eq!(guard.evaluate(0, 80.0, &DeviceStatus::Mining).await, None);
+        assert_eq!(
+            guard.evaluate(0, 86.0, &DeviceStatus::Mining).await,
+            Some(ThermalTransition::Overheated { limit: 85.0 })
+        );
+        assert!(guard.is_overheated(0).await);
+
+        // 仍在回滞区间内
+        assert_eq!(guard.evaluate(0, 80.0, &DeviceStatus::Overheated).await, None);
+        assert_eq!(
+            guard.evaluate(0, 74.0, &DeviceStatus::Overheated).await,
+            Some(ThermalTransition::Recovered { restore: DeviceStatus::Mining })
+        );
+        assert!(!guard.is_overheated(0).await);
+    }
+
+    #[tokio::test]
+    async fn test_device_limit_override() {
+        let config = ThermalConfig {
+            device_limits: vec![DeviceTemperatureLimit { device_id: 1, temperature_limit: 70.0 }],
+            ..Default::default()
+        };
+        let guard = ThermalGuard::new(config);
+
+        assert_eq!(guard.evaluate(0, 75.0, &DeviceStatus::Idle).await, None);
+        assert!(guard.evaluate(1, 75.0, &DeviceStatus::Idle).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_registered_device_limit() {
+        let guard = ThermalGuard::new(ThermalConfig::default());
+        guard.set_limit(2, 95.0).await;
+
+        // 设备配置里的上限优先，没有登记的设备用默认上限
+        assert_eq!(guard.evaluate(2, 90.0, &DeviceStatus::Mining).await, None);
+        assert!(guard.evaluate(3, 90.0, &DeviceStatus::Mining).await.is_some());
+        assert!(guard.is_overheated(3).await);
+        assert!(!guard.is_overheated(2).await);
+    }
+}
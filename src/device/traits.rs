@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use crate::error::DeviceError;
 use super::{DeviceInfo, DeviceConfig, DeviceStats, Work, MiningResult};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// 挖矿设备特征
@@ -24,8 +25,8 @@ pub trait MiningDevice: Send + Sync {
     /// 重启设备
     async fn restart(&mut self) -> Result<(), DeviceError>;
     
-    /// 提交工作
-    async fn submit_work(&mut self, work: Work) -> Result<(), DeviceError>;
+    /// 提交工作，多台设备共享同一份工作数据
+    async fn submit_work(&mut self, work: Arc<Work>) -> Result<(), DeviceError>;
     
     /// 获取挖矿结果
     async fn get_result(&mut self) -> Result<Option<MiningResult>, DeviceError>;
@@ -50,12 +51,24 @@ pub trait MiningDevice: Send + Sync {
     
     /// 设置风扇速度
     async fn set_fan_speed(&mut self, speed: u32) -> Result<(), DeviceError>;
+
+    /// 把风扇交还给设备/固件自动控制，返回实际下发的固定转速。
+    /// 没有自动调速的设备默认改用 `fan_control::AUTO_FALLBACK_SPEED`
+    async fn set_fan_auto(&mut self) -> Result<Option<u32>, DeviceError> {
+        self.set_fan_speed(super::fan_control::AUTO_FALLBACK_SPEED).await?;
+        Ok(Some(super::fan_control::AUTO_FALLBACK_SPEED))
+    }
     
     /// 检查设备健康状态
     async fn health_check(&self) -> Result<bool, DeviceError>;
     
     /// 重置统计信息
     async fn reset_stats(&mut self) -> Result<(), DeviceError>;
+
+    /// 获取各芯片统计，不能按芯片统计的设备返回空列表
+    async fn get_chip_stats(&self) -> Result<Vec<super::chips::ChipStats>, DeviceError> {
+        Ok(Vec::new())
+    }
 }
 
 /// 设备驱动特征
@@ -156,6 +169,16 @@ pub trait HardwareInterface: Send + Sync {
     
     /// 温度读取
     async fn read_temperature(&self, sensor_id: u8) -> Result<f32, DeviceError>;
+
+    /// 功率读取 (W)，不支持功率测量的后端返回 `None`
+    async fn read_power(&self, _sensor_id: u8) -> Result<Option<f64>, DeviceError> {
+        Ok(None)
+    }
+
+    /// 读取链所在算力板的 EEPROM 内容
+    async fn read_eeprom(&self, chain_id: u8) -> Result<Vec<u8>, DeviceError> {
+        Err(DeviceError::ChainError { chain_id, error: "EEPROM is not supported by this backend".to_string() })
+    }
     
     /// 电压设置
     async fn set_voltage(&self, chain_id: u8, voltage: u32) -> Result<(), DeviceError>;
@@ -0,0 +1,366 @@
+//! 虚拟设备 - 确定性的模拟挖矿设备
+//!
+//! 通过 `--features sim` 启用。在没有真实硬件或外置核心的情况下驱动完整的
+//! 工作分发、结果收集和份额提交流程，用于 CI 和本地开发。
+//! 相同的种子和配置会产生相同的 nonce / 错误序列。
+//!
+//! 份额时机按算力和难度模拟。到达份额时在阻塞线程池里从随机位置开始搜索至多 `GRIND_BUDGET` 个 nonce，
+//! 只回报真正满足目标的 nonce，份额目标足够低 (regtest、测试矿池) 时才会有结果；
+//! 搜索不到时这个份额作废，不回报结果。
+
+use crate::config::SimCoreConfig;
+use crate::device::{nonce_result, DeviceConfig, DeviceInfo, DeviceStats, DeviceStatus, MiningDevice, MiningResult, Work};
+use crate::error::DeviceError;
+use crate::mining::midstate;
+use async_trait::async_trait;
+use sha2::digest::generic_array::GenericArray;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// 虚拟设备ID起始值，避免与核心分配的设备ID冲突
+pub const SIM_DEVICE_ID_BASE: u32 = 9000;
+
+/// 每个份额最多尝试的 nonce 数
+const GRIND_BUDGET: u32 = 1 << 14;
+
+/// 难度1对应的期望哈希数
+const HASHES_PER_DIFF1: f64 = 4_294_967_296.0;
+
+/// 模拟运行时状态
+struct SimState {
+    rng: fastrand::Rng,
+    temperature: f32,
+    last_update: Instant,
+    /// 自上次出块以来累积的哈希数
+    pending_hashes: f64,
+    /// 距下一个份额所需的哈希数
+    next_share_hashes: f64,
+    stats: DeviceStats,
+}
+
+/// 虚拟挖矿设备
+pub struct VirtualDevice {
+    device_id: u32,
+    info: DeviceInfo,
+    sim: SimCoreConfig,
+    current_work: Option<Arc<Work>>,
+    started_at: Option<Instant>,
+    state: Mutex<SimState>,
+}
+
+impl VirtualDevice {
+    /// 创建虚拟设备，`index` 为模拟核心内的设备序号
+    pub fn new(index: u32, sim: SimCoreConfig) -> Self {
+        let device_id = SIM_DEVICE_ID_BASE + index;
+        let mut info = DeviceInfo::new(device_id, format!("Sim Device {}", index), "sim".to_string(), index as u8);
+        info.chip_count = 1;
+        info.temperature = Some(sim.ambient_temperature);
+
+        let rng = fastrand::Rng::with_seed(sim.seed.wrapping_add(index as u64));
+        let state = SimState {
+            rng,
+            temperature: sim.ambient_temperature,
+            last_update: Instant::now(),
+            pending_hashes: 0.0,
+            next_share_hashes: 0.0,
+            stats: DeviceStats::new(),
+        };
+
+        Self {
+            device_id,
+            info,
+            sim,
+            current_work: None,
+            started_at: None,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// 根据配置创建一组虚拟设备
+    pub fn create_all(sim: &SimCoreConfig) -> Vec<VirtualDevice> {
+        (0..sim.device_count).map(|i| VirtualDevice::new(i, sim.clone())).collect()
+    }
+
+    fn is_mining(&self) -> bool {
+        matches!(self.info.status, DeviceStatus::Mining)
+    }
+
+    /// 是否已到达故障注入时间
+    fn failure_injected(&self) -> bool {
+        match (self.sim.fail_after_secs, self.started_at) {
+            (Some(secs), Some(started)) => started.elapsed() >= Duration::from_secs(secs),
+            _ => false,
+        }
+    }
+
+    /// 推进模拟时钟：温度按一阶曲线趋近目标，累积哈希数
+    fn advance(&self, state: &mut SimState) {
+        let now = Instant::now();
+        let dt = now.duration_since(state.last_update).as_secs_f64();
+        state.last_update = now;
+
+        let mining = self.is_mining();
+        let mut target = if mining { self.sim.load_temperature } else { self.sim.ambient_temperature };
+        if let Some(fan) = self.info.fan_speed {
+            // 风扇转速越高，稳态温度越接近环境温度
+            let headroom = target - self.sim.ambient_temperature;
+            target -= headroom * 0.3 * (fan.min(100) as f32 / 100.0);
+        }
+        let alpha = 1.0 - (-dt / self.sim.thermal_time_constant_secs).exp();
+        state.temperature += (target - state.temperature) * alpha as f32;
+
+        if mining {
+            let hashes = self.sim.hashrate * dt;
+            state.pending_hashes += hashes;
+            state.stats.record_hash(hashes as u64);
+        }
+    }
+
+    /// 抽取下一个份额所需的哈希数（指数分布）
+    fn draw_share_hashes(&self, state: &mut SimState, difficulty: f64) -> f64 {
+        let u = state.rng.f64().max(f64::MIN_POSITIVE);
+        -u.ln() * difficulty.max(f64::MIN_POSITIVE) * HASHES_PER_DIFF1
+    }
+}
+
+/// 从 `start` 开始搜索至多 `budget` 个 nonce，返回第一个满足工作目标的。
+/// 第一块的压缩结果取 midstate，每个 nonce 只需计算第二块和第二次 SHA256
+fn grind_nonce(work: &Work, start: u32, budget: u32) -> Option<u32> {
+    let midstate = midstate::midstate_words(&work.header)?;
+    let mut block = [0u8; 64];
+    block[..16].copy_from_slice(work.header.get(64..80)?);
+    block[16] = 0x80;
+    block[56..].copy_from_slice(&640u64.to_be_bytes());
+
+    (0..budget).map(|offset| start.wrapping_add(offset)).find(|nonce| {
+        block[12..16].copy_from_slice(&nonce.to_le_bytes());
+        let mut state = midstate;
+        sha2::compress256(&mut state, &[GenericArray::clone_from_slice(&block)]);
+        let first: Vec<u8> = state.iter().flat_map(|word| word.to_be_bytes()).collect();
+        let hash = Sha256::digest(&first);
+        // 哈希和目标都是小端序，从最高字节开始比较
+        hash.iter().rev().cmp(work.target.iter().rev()) != std::cmp::Ordering::Greater
+    })
+}
+
+#[async_trait]
+impl MiningDevice for VirtualDevice {
+    fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    async fn get_info(&self) -> Result<DeviceInfo, DeviceError> {
+        let mut info = self.info.clone();
+        info.temperature = Some(self.state.lock().unwrap().temperature);
+        if let Some(started) = self.started_at {
+            info.uptime = started.elapsed();
+        }
+        Ok(info)
+    }
+
+    async fn initialize(&mut self, config: DeviceConfig) -> Result<(), DeviceError> {
+        self.info.frequency = Some(config.frequency);
+        self.info.voltage = Some(config.voltage);
+        self.info.fan_speed = config.fan_speed;
+        self.info.update_status(DeviceStatus::Idle);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<(), DeviceError> {
+        self.started_at = Some(Instant::now());
+        self.state.lock().unwrap().last_update = Instant::now();
+        self.info.update_status(DeviceStatus::Mining);
+        self.info.update_hashrate(self.sim.hashrate);
+        info!("🧪 虚拟设备 {} 开始模拟挖矿 ({} H/s)", self.device_id, self.sim.hashrate);
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), DeviceError> {
+        self.info.update_status(DeviceStatus::Idle);
+        self.info.update_hashrate(0.0);
+        self.current_work = None;
+        Ok(())
+    }
+
+    async fn restart(&mut self) -> Result<(), DeviceError> {
+        self.stop().await?;
+        self.state.lock().unwrap().stats.record_restart();
+        self.start().await
+    }
+
+    async fn submit_work(&mut self, work: Arc<Work>) -> Result<(), DeviceError> {
+        if self.failure_injected() {
+            self.info.update_status(DeviceStatus::Error("injected failure".to_string()));
+            return Err(DeviceError::HardwareError { device_id: self.device_id, error_code: 0xdead });
+        }
+        if self.sim.work_latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.sim.work_latency_ms)).await;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        self.advance(&mut state);
+        state.pending_hashes = 0.0;
+        state.next_share_hashes = self.draw_share_hashes(&mut state, work.difficulty);
+        drop(state);
+
+        debug!("🧪 虚拟设备 {} 接收工作 {}", self.device_id, work.id);
+        self.current_work = Some(work);
+        Ok(())
+    }
+
+    async fn get_result(&mut self) -> Result<Option<MiningResult>, DeviceError> {
+        if self.sim.result_latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.sim.result_latency_ms)).await;
+        }
+
+        let work = match &self.current_work {
+            Some(work) if self.is_mining() => work.clone(),
+            _ => return Ok(None),
+        };
+        let (work_id, difficulty) = (work.id, work.difficulty);
+
+        let mut state = self.state.lock().unwrap();
+        self.advance(&mut state);
+        if state.pending_hashes < state.next_share_hashes {
+            return Ok(None);
+        }
+
+        state.pending_hashes -= state.next_share_hashes;
+        state.next_share_hashes = self.draw_share_hashes(&mut state, difficulty);
+
+        let start = state.rng.u32(..);
+        if state.rng.f64() < self.sim.hardware_error_rate {
+            state.stats.record_hardware_error();
+            self.info.increment_hardware_errors();
+            warn!("🧪 虚拟设备 {} 注入硬件错误 (nonce={:08x})", self.device_id, start);
+            return Ok(Some(nonce_result(work_id, self.device_id, start, difficulty, false)));
+        }
+        drop(state);
+
+        // 搜索是 CPU 密集的，放到阻塞线程池里，不占用异步运行时，也不持有状态锁
+        let nonce = tokio::task::spawn_blocking(move || grind_nonce(&work, start, GRIND_BUDGET))
+            .await
+            .map_err(|e| DeviceError::CommunicationError { device_id: self.device_id, error: e.to_string() })?;
+        let Some(nonce) = nonce else {
+            debug!("🧪 虚拟设备 {} 在搜索范围内未找到满足目标的 nonce", self.device_id);
+            return Ok(None);
+        };
+
+        self.state.lock().unwrap().stats.record_valid_nonce();
+        self.info.increment_accepted_shares();
+        Ok(Some(nonce_result(work_id, self.device_id, nonce, difficulty, true)))
+    }
+
+    async fn get_status(&self) -> Result<DeviceStatus, DeviceError> {
+        Ok(self.info.status.clone())
+    }
+
+    async fn get_temperature(&self) -> Result<f32, DeviceError> {
+        let mut state = self.state.lock().unwrap();
+        self.advance(&mut state);
+        Ok(state.temperature)
+    }
+
+    async fn get_hashrate(&self) -> Result<f64, DeviceError> {
+        Ok(if self.is_mining() { self.sim.hashrate } else { 0.0 })
+    }
+
+    async fn get_stats(&self) -> Result<DeviceStats, DeviceError> {
+        let mut state = self.state.lock().unwrap();
+        self.advance(&mut state);
+        let temperature = state.temperature;
+        state.stats.record_temperature(temperature);
+        state.stats.record_hashrate(if self.is_mining() { self.sim.hashrate } else { 0.0 });
+        state.stats.uptime_seconds = self.started_at.map(|s| s.elapsed().as_secs()).unwrap_or(0);
+        Ok(state.stats.clone())
+    }
+
+    async fn set_frequency(&mut self, frequency: u32) -> Result<(), DeviceError> {
+        self.info.frequency = Some(frequency);
+        Ok(())
+    }
+
+    async fn set_voltage(&mut self, voltage: u32) -> Result<(), DeviceError> {
+        self.info.voltage = Some(voltage);
+        Ok(())
+    }
+
+    async fn set_fan_speed(&mut self, speed: u32) -> Result<(), DeviceError> {
+        self.info.fan_speed = Some(speed);
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<bool, DeviceError> {
+        Ok(!self.failure_injected() && !matches!(self.info.status, DeviceStatus::Error(_)))
+    }
+
+    async fn reset_stats(&mut self) -> Result<(), DeviceError> {
+        self.state.lock().unwrap().stats = DeviceStats::new();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sim_config() -> SimCoreConfig {
+        SimCoreConfig {
+            enabled: true,
+            device_count: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_device_ids() {
+        let devices = VirtualDevice::create_all(&sim_config());
+        let ids: Vec<u32> = devices.iter().map(|d| d.device_id()).collect();
+        assert_eq!(ids, vec![SIM_DEVICE_ID_BASE, SIM_DEVICE_ID_BASE + 1]);
+    }
+
+    #[test]
+    fn test_deterministic_share_sequence() {
+        let a = VirtualDevice::new(0, sim_config());
+        let b = VirtualDevice::new(0, sim_config());
+        let draws_a: Vec<f64> = (0..5).map(|_| a.draw_share_hashes(&mut a.state.lock().unwrap(), 1.0)).collect();
+        let draws_b: Vec<f64> = (0..5).map(|_| b.draw_share_hashes(&mut b.state.lock().unwrap(), 1.0)).collect();
+        assert_eq!(draws_a, draws_b);
+    }
+
+    #[tokio::test]
+    async fn test_injected_failure() {
+        let mut config = sim_config();
+        config.fail_after_secs = Some(0);
+        let mut device = VirtualDevice::new(0, config);
+        device.start().await.unwrap();
+        assert!(!device.health_check().await.unwrap());
+    }
+
+    #[test]
+    fn test_grind_meets_low_target() {
+        use crate::mining::{Algorithm, Network};
+        use crate::pool::share_verify::header_difficulty;
+
+        let difficulty = 1e-6;
+        let target = Algorithm::Sha256d.target_for_difficulty(Network::Mainnet, difficulty);
+        let work = Work::new("sim".to_string(), target, [7u8; 80], difficulty);
+        let nonce = grind_nonce(&work, 0, GRIND_BUDGET).expect("nonce within the grind budget");
+        assert!(header_difficulty(&work.header, nonce, Algorithm::Sha256d, Network::Mainnet).unwrap() >= difficulty);
+
+        // 目标太难时搜索不到，设备不回报结果
+        let work = Work::new("sim".to_string(), [0u8; 32], [7u8; 80], 1e12);
+        assert_eq!(grind_nonce(&work, 0, 64), None);
+    }
+
+    #[tokio::test]
+    async fn test_fan_auto_uses_fallback_speed() {
+        let mut device = VirtualDevice::new(0, sim_config());
+        device.set_fan_speed(100).await.unwrap();
+        assert_eq!(device.set_fan_auto().await.unwrap(), Some(crate::device::fan_control::AUTO_FALLBACK_SPEED));
+        assert_eq!(device.get_info().await.unwrap().fan_speed, Some(crate::device::fan_control::AUTO_FALLBACK_SPEED));
+    }
+}
@@ -0,0 +1,430 @@
+//! Whatsminer 整机监管 - 通过 btminer API 读取运行原厂固件的 M3x 矿机
+//!
+//! 通过 `--features whatsminer` 启用。btminer 自己连接矿池并驱动算力板，这里只用只读的
+//! `summary`/`edevs` 命令把每块算力板的算力、份额、温度和频率映射成标准的 `DeviceInfo`/`DeviceStats`。
+//! 监管设备不接收工作；直接驱动算力板需要 ASIC 核心中的芯片驱动。
+
+use crate::config::WhatsminerCoreConfig;
+use crate::device::{DeviceConfig, DeviceInfo, DeviceStats, DeviceStatus, MiningDevice, MiningResult, Work};
+use crate::error::DeviceError;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Whatsminer 设备ID起始值，每台矿机占 8 个 ID (按算力板槽位)
+pub const WHATSMINER_DEVICE_ID_BASE: u32 = 9500;
+
+/// 每台矿机预留的设备ID数
+const SLOTS_PER_HOST: u32 = 8;
+
+/// btminer API 默认端口
+const DEFAULT_API_PORT: u16 = 4028;
+
+/// `edevs` 中一块算力板的状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardStats {
+    pub slot: u32,
+    pub alive: bool,
+    pub temperature: Option<f32>,
+    /// 芯片频率 (MHz)
+    pub frequency: Option<u32>,
+    /// 平均算力 (H/s)
+    pub hashrate_av: f64,
+    /// 5 秒算力 (H/s)
+    pub hashrate_5s: f64,
+    pub accepted: u64,
+    pub rejected: u64,
+    pub hardware_errors: u64,
+    pub chips: u32,
+}
+
+/// 一次轮询的结果
+#[derive(Debug, Clone)]
+struct Snapshot {
+    fetched_at: Instant,
+    boards: Vec<BoardStats>,
+    /// btminer 运行时间 (秒)
+    elapsed_secs: u64,
+}
+
+/// 一台矿机的 btminer API 客户端，多块算力板共享并缓存同一次轮询的结果
+pub struct BtminerClient {
+    address: String,
+    timeout: Duration,
+    poll_interval: Duration,
+    cache: Mutex<Option<Snapshot>>,
+}
+
+impl BtminerClient {
+    pub fn new(host: &str, config: &WhatsminerCoreConfig) -> Self {
+        Self {
+            address: api_address(host),
+            timeout: Duration::from_millis(config.timeout_ms),
+            poll_interval: Duration::from_secs(config.poll_interval_secs),
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// 发送一条命令，返回 JSON 响应
+    async fn command(&self, cmd: &str) -> Result<Value, String> {
+        let exchange = async {
+            let mut stream = TcpStream::connect(&self.address).await.map_err(|e| e.to_string())?;
+            let request = serde_json::json!({ "cmd": cmd }).to_string();
+            stream.write_all(request.as_bytes()).await.map_err(|e| e.to_string())?;
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).await.map_err(|e| e.to_string())?;
+            Ok::<_, String>(response)
+        };
+        let response = tokio::time::timeout(self.timeout, exchange)
+            .await
+            .map_err(|_| format!("{} timed out after {:?}", cmd, self.timeout))??;
+
+        // 固件在响应末尾附带 NUL
+        let text = String::from_utf8_lossy(&response);
+        let value: Value = serde_json::from_str(text.trim_end_matches('\0').trim())
+            .map_err(|e| format!("invalid {} response: {}", cmd, e))?;
+        check_status(&value)?;
+        Ok(value)
+    }
+
+    /// 最近一次轮询结果，超过轮询间隔时重新请求
+    async fn snapshot(&self) -> Result<Snapshot, String> {
+        let mut cache = self.cache.lock().await;
+        if let Some(snapshot) = cache.as_ref() {
+            if snapshot.fetched_at.elapsed() < self.poll_interval {
+                return Ok(snapshot.clone());
+            }
+        }
+
+        // 旧固件没有 edevs
+        let devs = match self.command("edevs").await {
+            Ok(devs) => devs,
+            Err(e) => {
+                debug!("btminer {} edevs failed ({}), falling back to devs", self.address, e);
+                self.command("devs").await?
+            }
+        };
+        let boards = parse_boards(&devs)?;
+        let elapsed_secs = match self.command("summary").await {
+            Ok(summary) => parse_elapsed(&summary).unwrap_or(0),
+            Err(e) => {
+                debug!("btminer {} summary failed: {}", self.address, e);
+                0
+            }
+        };
+
+        let snapshot = Snapshot { fetched_at: Instant::now(), boards, elapsed_secs };
+        *cache = Some(snapshot.clone());
+        Ok(snapshot)
+    }
+}
+
+/// `ip` 或 `ip:port`，没有端口时使用 4028
+fn api_address(host: &str) -> String {
+    let host = host.trim();
+    if host.rsplit_once(':').map_or(false, |(_, port)| port.parse::<u16>().is_ok()) {
+        host.to_string()
+    } else {
+        format!("{}:{}", host, DEFAULT_API_PORT)
+    }
+}
+
+/// 检查 `STATUS`：旧固件为 `[{"STATUS": "S", ...}]`，新固件为 `"STATUS": "S"` 加 `Msg`
+fn check_status(value: &Value) -> Result<(), String> {
+    let (status, message) = match value.get("STATUS") {
+        Some(Value::Array(entries)) => match entries.first() {
+            Some(entry) => (entry.get("STATUS"), entry.get("Msg")),
+            None => (None, None),
+        },
+        status => (status, value.get("Msg")),
+    };
+    match status.and_then(Value::as_str) {
+        Some("E") => Err(format!(
+            "btminer error: {}",
+            message.and_then(Value::as_str).unwrap_or("unknown error")
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 数值字段，兼容数字和数字字符串
+fn number(entry: &Value, key: &str) -> Option<f64> {
+    match entry.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 解析 `edevs`/`devs` 的 `DEVS` 列表
+pub fn parse_boards(value: &Value) -> Result<Vec<BoardStats>, String> {
+    let devs = match value.get("DEVS").or_else(|| value.get("Msg").and_then(|msg| msg.get("DEVS"))) {
+        Some(Value::Array(devs)) => devs,
+        _ => return Err("response has no DEVS list".to_string()),
+    };
+
+    Ok(devs
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let slot = number(entry, "Slot").or_else(|| number(entry, "ASC")).map_or(index as u32, |slot| slot as u32);
+            let alive = match entry.get("Status").and_then(Value::as_str) {
+                Some(status) => status.eq_ignore_ascii_case("alive"),
+                None => entry.get("Enabled").and_then(Value::as_str) == Some("Y"),
+            };
+            BoardStats {
+                slot,
+                alive,
+                temperature: number(entry, "Temperature").map(|t| t as f32),
+                frequency: number(entry, "Chip Frequency").or_else(|| number(entry, "Frequency")).map(|f| f as u32),
+                hashrate_av: number(entry, "MHS av").unwrap_or(0.0) * 1e6,
+                hashrate_5s: number(entry, "MHS 5s").unwrap_or(0.0) * 1e6,
+                accepted: number(entry, "Accepted").unwrap_or(0.0) as u64,
+                rejected: number(entry, "Rejected").unwrap_or(0.0) as u64,
+                hardware_errors: number(entry, "Hardware Errors").unwrap_or(0.0) as u64,
+                chips: number(entry, "Effective Chips").unwrap_or(0.0) as u32,
+            }
+        })
+        .collect())
+}
+
+/// `summary` 中的运行时间 (秒)
+fn parse_elapsed(value: &Value) -> Option<u64> {
+    let summary = match value.get("SUMMARY") {
+        Some(Value::Array(entries)) => entries.first()?,
+        _ => value.get("Msg")?,
+    };
+    number(summary, "Elapsed").map(|secs| secs as u64)
+}
+
+/// 由 btminer 驱动的一块算力板
+pub struct WhatsminerBoard {
+    device_id: u32,
+    slot: u32,
+    info: DeviceInfo,
+    client: Arc<BtminerClient>,
+    /// 重置统计时的计数基准，btminer 的计数从它启动开始累计
+    baseline: (u64, u64, u64),
+}
+
+impl WhatsminerBoard {
+    pub fn new(host_index: u32, board: &BoardStats, client: Arc<BtminerClient>) -> Self {
+        let device_id = WHATSMINER_DEVICE_ID_BASE + host_index * SLOTS_PER_HOST + board.slot % SLOTS_PER_HOST;
+        let mut info = DeviceInfo::new(
+            device_id,
+            format!("Whatsminer {} HB{}", client.address(), board.slot),
+            "whatsminer".to_string(),
+            board.slot as u8,
+        );
+        info.chip_count = board.chips;
+        info.frequency = board.frequency;
+        info.temperature = board.temperature;
+
+        Self { device_id, slot: board.slot, info, client, baseline: (0, 0, 0) }
+    }
+
+    /// 连接配置中的每台矿机，为扫描到的算力板创建设备；连不上的矿机跳过
+    pub async fn create_all(config: &WhatsminerCoreConfig) -> Vec<WhatsminerBoard> {
+        let mut boards = Vec::new();
+        for (host_index, host) in config.hosts.iter().enumerate() {
+            let client = Arc::new(BtminerClient::new(host, config));
+            match client.snapshot().await {
+                Ok(snapshot) => {
+                    info!("⛏️ Whatsminer {} reports {} hash boards", client.address(), snapshot.boards.len());
+                    for board in &snapshot.boards {
+                        boards.push(WhatsminerBoard::new(host_index as u32, board, client.clone()));
+                    }
+                }
+                Err(e) => warn!("⚠️ Whatsminer {} unreachable, skipping: {}", client.address(), e),
+            }
+        }
+        boards
+    }
+
+    async fn board(&self) -> Result<(BoardStats, u64), DeviceError> {
+        let snapshot = self.client.snapshot().await.map_err(|error| DeviceError::CommunicationError {
+            device_id: self.device_id,
+            error,
+        })?;
+        let board = snapshot.boards.iter().find(|board| board.slot == self.slot).cloned().ok_or_else(|| {
+            DeviceError::CommunicationError {
+                device_id: self.device_id,
+                error: format!("hash board {} missing from btminer response", self.slot),
+            }
+        })?;
+        Ok((board, snapshot.elapsed_secs))
+    }
+
+    fn unsupported(&self, action: &str) -> DeviceError {
+        DeviceError::InvalidState {
+            device_id: self.device_id,
+            state: format!("{} is managed by btminer", action),
+        }
+    }
+}
+
+#[async_trait]
+impl MiningDevice for WhatsminerBoard {
+    fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    async fn get_info(&self) -> Result<DeviceInfo, DeviceError> {
+        let mut info = self.info.clone();
+        match self.board().await {
+            Ok((board, elapsed_secs)) => {
+                info.temperature = board.temperature;
+                info.frequency = board.frequency;
+                info.chip_count = board.chips;
+                info.hashrate = board.hashrate_av;
+                info.accepted_shares = board.accepted.saturating_sub(self.baseline.0);
+                info.rejected_shares = board.rejected.saturating_sub(self.baseline.1);
+                info.hardware_errors = board.hardware_errors.saturating_sub(self.baseline.2);
+                info.uptime = Duration::from_secs(elapsed_secs);
+                if !board.alive {
+                    info.status = DeviceStatus::Error("hash board is not alive".to_string());
+                }
+            }
+            Err(e) => info.status = DeviceStatus::Error(e.to_string()),
+        }
+        Ok(info)
+    }
+
+    async fn initialize(&mut self, _config: DeviceConfig) -> Result<(), DeviceError> {
+        // 频率和电压由 btminer 管理，不下发配置
+        self.info.update_status(DeviceStatus::Idle);
+        Ok(())
+    }
+
+    async fn start(&mut self) -> Result<(), DeviceError> {
+        self.info.update_status(DeviceStatus::Mining);
+        info!("⛏️ Supervising {}", self.info.name);
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), DeviceError> {
+        // 只停止监管，btminer 自身继续挖矿
+        self.info.update_status(DeviceStatus::Idle);
+        Ok(())
+    }
+
+    async fn restart(&mut self) -> Result<(), DeviceError> {
+        Err(self.unsupported("restart"))
+    }
+
+    async fn submit_work(&mut self, _work: Arc<Work>) -> Result<(), DeviceError> {
+        Err(self.unsupported("work"))
+    }
+
+    async fn get_result(&mut self) -> Result<Option<MiningResult>, DeviceError> {
+        // 份额由 btminer 直接提交到它自己的矿池
+        Ok(None)
+    }
+
+    async fn get_status(&self) -> Result<DeviceStatus, DeviceError> {
+        Ok(self.info.status.clone())
+    }
+
+    async fn get_temperature(&self) -> Result<f32, DeviceError> {
+        let (board, _) = self.board().await?;
+        board.temperature.ok_or_else(|| DeviceError::CommunicationError {
+            device_id: self.device_id,
+            error: "btminer did not report a temperature".to_string(),
+        })
+    }
+
+    async fn get_hashrate(&self) -> Result<f64, DeviceError> {
+        Ok(self.board().await?.0.hashrate_av)
+    }
+
+    async fn get_stats(&self) -> Result<DeviceStats, DeviceError> {
+        let (board, elapsed_secs) = self.board().await?;
+        let mut stats = DeviceStats::new();
+        stats.total_hashes = (board.hashrate_av * elapsed_secs as f64) as u64;
+        stats.valid_nonces = board.accepted.saturating_sub(self.baseline.0);
+        stats.invalid_nonces = board.rejected.saturating_sub(self.baseline.1);
+        stats.hardware_errors = board.hardware_errors.saturating_sub(self.baseline.2);
+        if let Some(temperature) = board.temperature {
+            stats.record_temperature(temperature);
+        }
+        stats.record_hashrate(board.hashrate_av);
+        stats.record_hashrate(board.hashrate_5s);
+        stats.uptime_seconds = elapsed_secs;
+        Ok(stats)
+    }
+
+    async fn set_frequency(&mut self, _frequency: u32) -> Result<(), DeviceError> {
+        Err(self.unsupported("frequency"))
+    }
+
+    async fn set_voltage(&mut self, _voltage: u32) -> Result<(), DeviceError> {
+        Err(self.unsupported("voltage"))
+    }
+
+    async fn set_fan_speed(&mut self, _speed: u32) -> Result<(), DeviceError> {
+        Err(self.unsupported("fan speed"))
+    }
+
+    /// 风扇始终由 btminer 固件控制
+    async fn set_fan_auto(&mut self) -> Result<Option<u32>, DeviceError> {
+        Ok(None)
+    }
+
+    async fn health_check(&self) -> Result<bool, DeviceError> {
+        Ok(self.board().await.map(|(board, _)| board.alive).unwrap_or(false))
+    }
+
+    async fn reset_stats(&mut self) -> Result<(), DeviceError> {
+        let (board, _) = self.board().await?;
+        self.baseline = (board.accepted, board.rejected, board.hardware_errors);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_edevs() {
+        let response = serde_json::json!({
+            "STATUS": [{ "STATUS": "S", "Msg": "EDEVS" }],
+            "DEVS": [
+                { "ASC": 0, "Slot": 0, "Enabled": "Y", "Status": "Alive", "Temperature": 72.5,
+                  "Chip Frequency": 612, "MHS av": 33500000.25, "MHS 5s": "33900000.0",
+                  "Accepted": 1200, "Rejected": 3, "Hardware Errors": 7, "Effective Chips": 156 },
+                { "ASC": 1, "Slot": 2, "Enabled": "N", "Status": "Dead", "MHS av": 0 }
+            ]
+        });
+        let boards = parse_boards(&response).unwrap();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].slot, 0);
+        assert!(boards[0].alive);
+        assert_eq!(boards[0].temperature, Some(72.5));
+        assert_eq!(boards[0].frequency, Some(612));
+        assert!((boards[0].hashrate_5s - 33.9e12).abs() < 1.0);
+        assert_eq!((boards[0].accepted, boards[0].rejected, boards[0].hardware_errors), (1200, 3, 7));
+        assert_eq!(boards[1].slot, 2);
+        assert!(!boards[1].alive);
+        assert_eq!(boards[1].temperature, None);
+    }
+
+    #[test]
+    fn test_status_and_address() {
+        assert!(check_status(&serde_json::json!({ "STATUS": "S", "Msg": {} })).is_ok());
+        let error = check_status(&serde_json::json!({ "STATUS": [{ "STATUS": "E", "Msg": "invalid cmd" }] }));
+        assert_eq!(error.unwrap_err(), "btminer error: invalid cmd");
+        assert_eq!(api_address("10.0.0.5"), "10.0.0.5:4028");
+        assert_eq!(api_address("10.0.0.5:14028"), "10.0.0.5:14028");
+        assert_eq!(parse_elapsed(&serde_json::json!({ "SUMMARY": [{ "Elapsed": 3600 }] })), Some(3600));
+    }
+}
@@ -56,11 +56,15 @@ pub mod web;              // Web界面
 pub mod monitoring;       // 监控系统
 pub mod logging;          // 日志管理
 pub mod error;            // 错误处理
+pub mod security;         // 安全 (加密存储、API 密钥)
+pub mod service;          // Windows 服务
 
 // 支撑模块
 pub mod device;           // 设备管理 (应用层抽象)
 pub mod core_loader;      // 核心加载器
 pub mod performance;      // 性能监控 (应用层)
+#[cfg(feature = "tui")]
+pub mod tui;              // 终端界面
 
 pub mod utils;            // 工具函数
 
@@ -0,0 +1,241 @@
+//! 崩溃报告 (`[crash_report]`)
+//!
+//! panic 钩子把回溯、内存环形缓冲中最近的日志、脱敏后的配置和核心/设备状态写成一个 JSON 报告，
+//! 用户只需提交这个文件（或用 `--upload-crash-report` 上传），不再依赖终端里残留的输出。
+
+use crate::config::Config;
+use crate::error::MiningError;
+use crate::logging::redact;
+use crate::logging::shipper::FieldVisitor;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 崩溃报告配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashReportConfig {
+    /// 是否在 panic 时写入崩溃报告
+    pub enabled: bool,
+    /// 报告目录
+    pub dir: String,
+    /// 报告中保留的最近日志行数
+    pub log_lines: usize,
+    /// 目录中保留的报告数量
+    pub max_reports: usize,
+    /// `--upload-crash-report` 的上传地址
+    pub upload_url: String,
+}
+
+impl Default for CrashReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: "./crash-reports".to_string(),
+            log_lines: 200,
+            max_reports: 10,
+            upload_url: String::new(),
+        }
+    }
+}
+
+/// 崩溃报告内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub version: String,
+    pub timestamp: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// 脱敏后的生效配置
+    pub config: serde_json::Value,
+    /// 最近一次采集的核心/设备状态
+    pub state: serde_json::Value,
+    /// 最近的日志行
+    pub logs: Vec<String>,
+}
+
+/// 报告文件名前缀
+const REPORT_PREFIX: &str = "crash-";
+
+static LOG_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+static LOG_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static CONFIG_SUMMARY: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+static STATE: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+
+/// 保存最近日志行的 tracing 层
+pub struct RingBufferLayer;
+
+/// 创建环形缓冲日志层，`capacity` 为 0 时不记录
+pub fn ring_buffer_layer(capacity: usize) -> RingBufferLayer {
+    LOG_CAPACITY.store(capacity, Ordering::Relaxed);
+    RingBufferLayer
+}
+
+impl<S: Subscriber> Layer<S> for RingBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let capacity = LOG_CAPACITY.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let mut line = format!(
+            "{} {} {}: {}",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            metadata.level(),
+            metadata.target(),
+            visitor.message
+        );
+        for (key, value) in &visitor.fields {
+            line.push_str(&format!(" {}={}", key, value));
+        }
+
+        let mut lines = LOG_LINES.lock().unwrap_or_else(|e| e.into_inner());
+        while lines.len() >= capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+/// 更新报告中的核心/设备状态快照
+pub fn set_state(state: serde_json::Value) {
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some(state);
+}
+
+/// 安装 panic 钩子，写入报告后交给原有钩子输出 panic 信息
+pub fn install_panic_hook(report_config: &CrashReportConfig, config: &Config) {
+    if !report_config.enabled {
+        return;
+    }
+    *CONFIG_SUMMARY.lock().unwrap_or_else(|e| e.into_inner()) = Some(redact::to_redacted_json(config));
+
+    let dir = PathBuf::from(&report_config.dir);
+    let max_reports = report_config.max_reports;
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.payload().downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+
+        match write_report(&dir, message, location, max_reports) {
+            Ok(path) => eprintln!("💥 Crash report written to {}", path.display()),
+            Err(e) => eprintln!("💥 Failed to write crash report to {}: {}", dir.display(), e),
+        }
+        previous(info);
+    }));
+}
+
+/// panic 时可能正持有锁，只尝试获取，拿不到时跳过该部分
+fn snapshot<T: Clone>(mutex: &Mutex<T>) -> Option<T> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard.clone()),
+        Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner().clone()),
+        Err(std::sync::TryLockError::WouldBlock) => None,
+    }
+}
+
+fn write_report(dir: &Path, message: String, location: Option<String>, max_reports: usize) -> std::io::Result<PathBuf> {
+    let now = chrono::Utc::now();
+    let report = CrashReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        timestamp: now.to_rfc3339(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().unwrap_or("unnamed").to_string(),
+        message,
+        location,
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        config: snapshot(&CONFIG_SUMMARY).flatten().unwrap_or(serde_json::Value::Null),
+        state: snapshot(&STATE).flatten().unwrap_or(serde_json::Value::Null),
+        logs: snapshot(&LOG_LINES).map(Vec::from).unwrap_or_default(),
+    };
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}{}.json", REPORT_PREFIX, now.format("%Y%m%d-%H%M%S%.3f")));
+    std::fs::write(&path, serde_json::to_vec_pretty(&report)?)?;
+    prune_reports(dir, max_reports);
+    Ok(path)
+}
+
+/// 目录中的报告，按时间从旧到新
+fn list_reports(dir: &Path) -> Vec<PathBuf> {
+    let mut reports: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries.filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.file_name().and_then(|n| n.to_str())
+                        .map_or(false, |name| name.starts_with(REPORT_PREFIX) && name.ends_with(".json"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    reports.sort();
+    reports
+}
+
+fn prune_reports(dir: &Path, max_reports: usize) {
+    let reports = list_reports(dir);
+    if reports.len() > max_reports.max(1) {
+        for path in &reports[..reports.len() - max_reports.max(1)] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// 最新的崩溃报告
+pub fn latest_report(dir: &Path) -> Option<PathBuf> {
+    list_reports(dir).pop()
+}
+
+/// 上传崩溃报告（报告写入时已脱敏）
+pub async fn upload_report(path: &Path, url: &str) -> Result<(), MiningError> {
+    let content = tokio::fs::read(path).await
+        .map_err(|e| MiningError::System(format!("Failed to read crash report {}: {}", path.display(), e)))?;
+    let report: CrashReport = serde_json::from_slice(&content)
+        .map_err(|e| MiningError::System(format!("{} is not a crash report: {}", path.display(), e)))?;
+
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&report)
+        .send()
+        .await
+        .map_err(|e| MiningError::System(format!("Failed to upload crash report: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(MiningError::System(format!("Crash report upload rejected: HTTP {}", response.status())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_prune_reports() {
+        let dir = std::env::temp_dir().join(format!("cgminer-crash-{}", uuid::Uuid::new_v4()));
+        for i in 0..3 {
+            write_report(&dir, format!("boom {}", i), Some("src/main.rs:1:1".to_string()), 2).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let reports = list_reports(&dir);
+        assert_eq!(reports.len(), 2);
+        let latest: CrashReport = serde_json::from_slice(&std::fs::read(latest_report(&dir).unwrap()).unwrap()).unwrap();
+        assert_eq!(latest.message, "boom 2");
+        assert_eq!(latest.location.as_deref(), Some("src/main.rs:1:1"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
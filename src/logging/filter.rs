@@ -0,0 +1,165 @@
+//! 按目标覆盖日志级别 (`general.log_targets`)
+//!
+//! `pool=debug, device=warn, mining=info` 之类的覆盖项转换为 EnvFilter 指令，
+//! 调试 Stratum 时不必把全局级别调到 debug，设备日志也不会被淹没。
+//! 过滤器通过 reload 层安装，运行时可以经 API (`PUT /api/v1/logging`) 调整。
+
+use crate::error::MiningError;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::{filter::Directive, reload, EnvFilter, Registry};
+
+/// 本程序的模块路径前缀，`pool` 同时匹配 `pool` 目标和 `cgminer_rs::pool` 模块
+pub(crate) const CRATE_PREFIX: &str = "cgminer_rs";
+
+/// 支持的级别名
+const LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+/// 当前生效的日志过滤设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogFilterSettings {
+    /// 全局日志级别
+    pub level: String,
+    /// 按目标覆盖的级别，如 "pool=debug,device=warn"
+    pub targets: String,
+}
+
+/// 运行时日志过滤更新，未给出的字段保持不变
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogFilterUpdate {
+    pub level: Option<String>,
+    pub targets: Option<String>,
+}
+
+/// 单个目标的级别覆盖
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetLevel {
+    pub target: String,
+    pub level: String,
+}
+
+impl TargetLevel {
+    /// 对应的 EnvFilter 指令，本程序的短模块名同时覆盖完整模块路径
+    fn directives(&self) -> Vec<String> {
+        let mut directives = vec![format!("{}={}", self.target, self.level)];
+        if !self.target.contains("::") {
+            directives.push(format!("{}::{}={}", CRATE_PREFIX, self.target, self.level));
+        }
+        directives
+    }
+}
+
+/// 解析级别名（不区分大小写）
+pub fn parse_level(level: &str) -> Option<&'static str> {
+    let level = level.trim().to_ascii_lowercase();
+    let level = if level == "warning" { "warn".to_string() } else { level };
+    LEVELS.iter().copied().find(|l| *l == level)
+}
+
+/// 解析 `target=level` 列表，逗号分隔，空字符串表示没有覆盖
+pub fn parse_targets(spec: &str) -> Result<Vec<TargetLevel>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let (target, level) = item.split_once('=')
+                .ok_or_else(|| format!("'{}' is not a target=level pair", item))?;
+            let target = target.trim();
+            let valid_target = !target.is_empty()
+                && target.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':' || c == '-');
+            if !valid_target {
+                return Err(format!("Invalid log target '{}'", target));
+            }
+            let level = parse_level(level).ok_or_else(|| format!(
+                "Unknown log level '{}' for target '{}' (expected {})", level.trim(), target, LEVELS.join(", ")
+            ))?;
+            Ok(TargetLevel { target: target.to_string(), level: level.to_string() })
+        })
+        .collect()
+}
+
+/// 规范化的目标列表文本
+fn format_targets(targets: &[TargetLevel]) -> String {
+    targets.iter()
+        .map(|t| format!("{}={}", t.target, t.level))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 构造过滤器：RUST_LOG 为基础，叠加全局级别和按目标覆盖
+fn build_filter(level: &str, targets: &[TargetLevel]) -> EnvFilter {
+    let level = parse_level(level).unwrap_or("info");
+    let mut filter = EnvFilter::from_default_env();
+    let directives = std::iter::once(level.to_string()).chain(targets.iter().flat_map(TargetLevel::directives));
+    for directive in directives {
+        if let Ok(directive) = directive.parse::<Directive>() {
+            filter = filter.add_directive(directive);
+        }
+    }
+    filter
+}
+
+static HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+static CURRENT: Mutex<Option<LogFilterSettings>> = Mutex::new(None);
+
+/// 创建可重载的过滤层，由 `init_logging` 安装
+pub fn reloadable_filter(level: &str, targets: &str) -> Result<reload::Layer<EnvFilter, Registry>, MiningError> {
+    let targets = parse_targets(targets)
+        .map_err(|e| MiningError::configuration(format!("general.log_targets: {}", e)))?;
+
+    let (layer, handle) = reload::Layer::new(build_filter(level, &targets));
+    let _ = HANDLE.set(handle);
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(LogFilterSettings {
+        level: parse_level(level).unwrap_or("info").to_string(),
+        targets: format_targets(&targets),
+    });
+    Ok(layer)
+}
+
+/// 当前生效的过滤设置，日志系统未初始化时为空
+pub fn current() -> Option<LogFilterSettings> {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 运行时调整日志级别
+pub fn update(update: LogFilterUpdate) -> Result<LogFilterSettings, MiningError> {
+    let handle = HANDLE.get()
+        .ok_or_else(|| MiningError::System("Logging is not initialized".to_string()))?;
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = current.clone()
+        .ok_or_else(|| MiningError::System("Logging is not initialized".to_string()))?;
+
+    let level = match update.level {
+        Some(level) => parse_level(&level)
+            .ok_or_else(|| MiningError::configuration(format!("Unknown log level '{}'", level)))?
+            .to_string(),
+        None => previous.level,
+    };
+    let targets = parse_targets(update.targets.as_deref().unwrap_or(&previous.targets))
+        .map_err(MiningError::configuration)?;
+
+    handle.reload(build_filter(&level, &targets))
+        .map_err(|e| MiningError::System(format!("Failed to reload log filter: {}", e)))?;
+
+    let settings = LogFilterSettings { level, targets: format_targets(&targets) };
+    *current = Some(settings.clone());
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_targets() {
+        let targets = parse_targets(" pool=DEBUG, device=warn,,cgminer_rs::mining=info ").unwrap();
+        assert_eq!(format_targets(&targets), "pool=debug,device=warn,cgminer_rs::mining=info");
+        assert_eq!(targets[0].directives(), vec!["pool=debug", "cgminer_rs::pool=debug"]);
+        assert_eq!(targets[2].directives(), vec!["cgminer_rs::mining=info"]);
+
+        assert!(parse_targets("").unwrap().is_empty());
+        assert!(parse_targets("pool").is_err());
+        assert!(parse_targets("pool=loud").is_err());
+        assert!(parse_targets("po ol=debug").is_err());
+    }
+}
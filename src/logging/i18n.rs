@@ -0,0 +1,132 @@
+//! 日志语言选择 (`general.log_language`)
+//!
+//! 运行日志统一使用一种语言输出，便于 grep 和非中文用户排查问题。
+//! 消息目录中每条消息同时给出英文和中文文本，按全局语言设置选择。
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 日志语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLanguage {
+    #[serde(alias = "english")]
+    En = 0,
+    #[default]
+    #[serde(alias = "chinese", alias = "zh-cn")]
+    Zh = 1,
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(LogLanguage::Zh as u8);
+
+/// 设置日志语言，应在初始化日志系统前调用
+pub fn set_language(language: LogLanguage) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// 当前日志语言
+pub fn language() -> LogLanguage {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => LogLanguage::Zh,
+        _ => LogLanguage::En,
+    }
+}
+
+/// 定义消息目录：每条消息生成一个按当前语言格式化的函数
+macro_rules! catalog {
+    ($($name:ident($($arg:ident),*) => en: $en:literal, zh: $zh:literal;)*) => {
+        $(
+            #[allow(clippy::too_many_arguments)]
+            pub fn $name($($arg: impl std::fmt::Display),*) -> String {
+                match language() {
+                    LogLanguage::En => format!($en, $($arg),*),
+                    LogLanguage::Zh => format!($zh, $($arg),*),
+                }
+            }
+        )*
+    };
+}
+
+/// 消息目录
+pub mod msg {
+    use super::{language, LogLanguage};
+
+    catalog! {
+        // 设备管理器
+        device_manager_active_cores(cores) => en: "🏭 Device manager received active cores: {}", zh: "🏭 设备管理器接收到活跃核心: {}";
+        device_manager_initialized() => en: "✅ Device manager initialized", zh: "✅ 设备管理器初始化成功";
+        no_active_cores() => en: "⚠️ No active mining cores", zh: "⚠️ 没有活跃的挖矿核心";
+        active_core_list(cores) => en: "🏭 Active cores: {}", zh: "🏭 活跃核心列表: {}";
+        no_factory_for_active_cores() => en: "⚠️ No core factory matches the active cores", zh: "⚠️ 没有活跃核心对应的工厂";
+        factory_created_devices(core, count) => en: "✅ Core factory {} created {} devices", zh: "✅ 核心工厂 {} 创建了 {} 个设备";
+        factory_create_failed(core, error) => en: "❌ Core factory {} failed to create devices: {}", zh: "❌ 核心工厂 {} 设备创建失败: {}";
+        devices_initialized(count) => en: "🎯 Device initialization complete, {} mining devices created", zh: "🎯 设备初始化完成，共创建 {} 个挖矿设备";
+        sim_devices_created(count) => en: "🧪 Sim core created {} virtual devices", zh: "🧪 模拟核心创建了 {} 个虚拟设备";
+        whatsminer_devices_created(count) => en: "⛏️ Supervising {} Whatsminer hash boards", zh: "⛏️ 正在监管 {} 块 Whatsminer 算力板";
+        avalon_devices_created(count) => en: "⛏️ Avalon driver detected {} MM modules", zh: "⛏️ Avalon 驱动检测到 {} 个 MM 模块";
+        core_found_no_devices(core) => en: "⚠️ Core {} found no devices", zh: "⚠️ 核心 {} 没有扫描到设备";
+        core_found_devices(core, count) => en: "📋 Core {} found {} devices", zh: "📋 核心 {} 扫描到 {} 个设备";
+        device_count_adjusted(from, to) => en: "📋 Device architecture adjusted device count: {} -> {}", zh: "📋 架构管理器调整设备数量: {} -> {}";
+        device_create_failed(error) => en: "❌ Failed to create device: {}", zh: "❌ 创建设备失败: {}";
+        scanning_core(core) => en: "Scanning devices from core {}", zh: "从核心 {} 扫描设备";
+        core_scan_failed(core, error) => en: "Core {} device scan failed: {}", zh: "核心 {} 扫描设备失败: {}";
+        generating_software_devices(count) => en: "🔧 Generating {} software devices", zh: "🔧 生成 {} 个软件设备";
+        generating_gpu_devices(count) => en: "🔧 Generating {} GPU devices", zh: "🔧 生成 {} 个GPU设备";
+        device_manager_starting() => en: "Starting device manager", zh: "正在启动设备管理器";
+        device_manager_started() => en: "Device manager started successfully", zh: "设备管理器启动成功";
+        device_manager_stopping() => en: "Stopping device manager", zh: "正在停止设备管理器";
+        device_manager_stopped() => en: "Device manager stopped successfully", zh: "设备管理器已停止";
+        device_start_failed(device, error) => en: "Failed to start device {}: {}", zh: "设备 {} 启动失败: {}";
+        devices_started(started, total) => en: "✅ Device startup complete: {}/{} devices started", zh: "✅ 设备启动完成: {}/{} 个设备成功启动";
+        ramp_up_begin(total, batch, delay) => en: "🔌 Staged startup: {} devices, {} per batch, {}s apart", zh: "🔌 分批启动设备: {} 个设备，每批 {} 个，间隔 {} 秒";
+        ramp_up_start_failed(device, error) => en: "Failed to start device {} during ramp-up: {}", zh: "分批启动时设备 {} 启动失败: {}";
+        ramp_up_progress(batch, batches, started, total) => en: "🔌 Staged startup progress: batch {}/{}, {}/{} devices started", zh: "🔌 分批启动进度: 批次 {}/{}，已启动 {}/{} 个设备";
+        auto_tune_started(count) => en: "🎛️ Auto-tuning {} chains", zh: "🎛️ 开始自动调优 {} 条链";
+        board_eeprom_read(chain, serial, firmware) => en: "🏷️ Chain {} board S/N {}, firmware {}", zh: "🏷️ 链 {} 算力板序列号 {}，固件 {}";
+        bad_chips_found(board, chips) => en: "🩺 New bad chips on {}: {}", zh: "🩺 {} 发现新的坏芯片: {}";
+        power_rails_enabled(count) => en: "🔌 Enabled {} power rails", zh: "🔌 已打开 {} 路电源";
+        power_limit_exceeded(total, limit, id, freq) => en: "⚡ Board power {:.0} W exceeds limit {:.0} W, lowering device {} to {} MHz", zh: "⚡ 板卡功率 {:.0} W 超过上限 {:.0} W，设备 {} 降频到 {} MHz";
+        ramp_up_done(started, total) => en: "✅ Staged startup complete: {}/{} devices started", zh: "✅ 分批启动完成: {}/{} 个设备成功启动";
+        device_stopped(device) => en: "Device {} stopped successfully", zh: "设备 {} 已停止";
+        device_stop_failed(device, error) => en: "Failed to stop device {}: {}", zh: "设备 {} 停止失败: {}";
+        device_overheated(device, temperature, limit) => en: "🔥 Device {} at {:.1}°C exceeds the {:.1}°C limit, pausing work", zh: "🔥 设备 {} 温度 {:.1}°C 超过上限 {:.1}°C，暂停分发工作";
+        device_cooled(device, temperature) => en: "❄️ Device {} cooled to {:.1}°C, resuming work", zh: "❄️ 设备 {} 温度降至 {:.1}°C，恢复工作";
+        fan_policy_failed(device, error) => en: "Failed to apply fan policy to device {}: {}", zh: "设备 {} 风扇策略应用失败: {}";
+        fan_set(device, target) => en: "Device {} fan manually set to {}", zh: "设备 {} 风扇手动设置为 {}";
+        fan_override_cleared(device) => en: "Device {} fan override cleared", zh: "设备 {} 风扇手动设置已清除";
+        device_restarted(device) => en: "Device {} restarted successfully", zh: "设备 {} 重启成功";
+        device_enabled(device) => en: "Device {} enabled", zh: "设备 {} 已启用";
+        device_disabled(device) => en: "Device {} disabled", zh: "设备 {} 已禁用";
+        frequency_set(device, mhz) => en: "Device {} frequency set to {} MHz", zh: "设备 {} 频率设置为 {} MHz";
+        voltage_set(device, mv) => en: "Device {} voltage set to {} mV", zh: "设备 {} 电压设置为 {} mV";
+        hashrate_summary(active, total, average) => en: "📊 Hashrate summary | active devices: {} | total: {} | average: {}", zh: "📊 算力统计汇总 | 活跃设备: {} | 总算力: {} | 平均: {}";
+
+        // 核心
+        registering_cores() => en: "🔧 Registering all enabled mining cores", zh: "🔧 开始静态注册所有启用的挖矿核心";
+        cores_registered(count) => en: "✅ Core registration complete, {} core factories registered", zh: "✅ 静态核心注册完成，共注册 {} 个核心工厂";
+        registering_core(core) => en: "🔧 Registering {} core", zh: "🔧 注册 {} 核心";
+        core_registered(core, name, core_type) => en: "✅ {} core registered: {} ({})", zh: "✅ {} 核心注册成功: {} ({})";
+        core_register_failed(core, error) => en: "❌ Failed to register {} core: {}", zh: "❌ 注册 {} 核心失败: {}";
+        shutting_down_cores() => en: "🔧 Shutting down all cores", zh: "🔧 关闭所有核心";
+        cores_shut_down() => en: "✅ All cores shut down", zh: "✅ 所有核心已关闭";
+
+        // 安全
+        auth_failed() => en: "Authentication failed for request", zh: "请求认证失败";
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_language() {
+        set_language(LogLanguage::Zh);
+        assert_eq!(msg::device_restarted(3), "设备 3 重启成功");
+        set_language(LogLanguage::En);
+        assert_eq!(msg::device_restarted(3), "Device 3 restarted successfully");
+
+        let parsed: LogLanguage = serde_json::from_str("\"chinese\"").unwrap();
+        assert_eq!(parsed, LogLanguage::Zh);
+    }
+}
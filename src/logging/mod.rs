@@ -1,22 +1,34 @@
 //! 美化日志系统
 
+pub mod crash;
+pub mod filter;
 pub mod formatter;
+pub mod i18n;
+pub mod redact;
+pub mod rotation;
+pub mod sampling;
+pub mod shipper;
 
+use crate::config::Config;
 use crate::error::MiningError;
 use std::path::Path;
 use tracing_subscriber::{
     fmt::{self, format::FmtSpan},
     layer::SubscriberExt,
     util::SubscriberInitExt,
-    EnvFilter, Layer,
+    Layer,
 };
-use tracing_appender::{non_blocking, rolling};
+use tracing_appender::{non_blocking, non_blocking::WorkerGuard, rolling};
 
 /// 日志配置
 #[derive(Debug, Clone)]
 pub struct LogConfig {
     /// 日志级别
     pub level: String,
+    /// 按目标覆盖的级别，如 "pool=debug,device=warn"
+    pub targets: String,
+    /// 高频日志采样规则
+    pub sampling: Vec<sampling::LogSamplingRule>,
     /// 日志文件路径
     pub file_path: Option<String>,
     /// 是否启用彩色输出
@@ -31,6 +43,14 @@ pub struct LogConfig {
     pub pretty: bool,
     /// 日志轮转配置
     pub rotation: LogRotation,
+    /// 是否输出到控制台
+    pub console: bool,
+    /// 文件日志是否使用JSON格式
+    pub json_format: bool,
+    /// 远程日志推送，未启用时为空
+    pub shipping: Option<shipper::LogShippingConfig>,
+    /// 崩溃报告中保留的最近日志行数，0 表示不保留
+    pub crash_log_lines: usize,
 }
 
 /// 日志轮转配置
@@ -42,14 +62,23 @@ pub enum LogRotation {
     Hourly,
     /// 每天轮转
     Daily,
-    /// 按大小轮转 (MB)
-    Size(u64),
+    /// 按大小轮转
+    Size {
+        /// 单个文件最大字节数
+        max_bytes: u64,
+        /// 保留的已轮转文件数量
+        max_files: usize,
+        /// 是否 gzip 压缩已轮转文件
+        compress: bool,
+    },
 }
 
 impl Default for LogConfig {
     fn default() -> Self {
         Self {
             level: "info".to_string(),
+            targets: String::new(),
+            sampling: Vec::new(),
             file_path: None,
             colored: true,
             show_timestamp: true,
@@ -57,42 +86,110 @@ impl Default for LogConfig {
             show_target: false,
             pretty: true,
             rotation: LogRotation::Daily,
+            console: true,
+            json_format: true,
+            shipping: None,
+            crash_log_lines: 0,
         }
     }
 }
 
+/// 未配置 `max_size` 或无法解析时的轮转大小
+const DEFAULT_MAX_LOG_BYTES: u64 = 100 * 1024 * 1024;
+
+impl LogConfig {
+    /// 从配置文件生成日志配置
+    ///
+    /// 日志级别取 `general.log_level`（`--log-level` / `--debug` 会覆盖它），按目标覆盖取 `general.log_targets`，
+    /// `[logging]` 段存在时提供文件、轮转和输出格式，否则使用 `general.log_file` 和默认值。
+    pub fn from_config(config: &Config) -> Self {
+        let mut log_config = Self {
+            level: config.general.log_level.clone(),
+            targets: config.general.log_targets.clone(),
+            sampling: config.general.log_sampling.clone(),
+            file_path: config.general.log_file.as_ref().map(|p| p.display().to_string()),
+            shipping: config.log_shipping.enabled.then(|| config.log_shipping.clone()),
+            crash_log_lines: if config.crash_report.enabled { config.crash_report.log_lines } else { 0 },
+            ..Self::default()
+        };
+
+        if let Some(logging) = &config.logging {
+            if !logging.file.is_empty() {
+                log_config.file_path = Some(logging.file.clone());
+            }
+            log_config.console = logging.console;
+            log_config.json_format = logging.json_format;
+            log_config.rotation = match logging.rotation.to_ascii_lowercase().as_str() {
+                "never" => LogRotation::Never,
+                "hourly" => LogRotation::Hourly,
+                "size" => LogRotation::Size {
+                    max_bytes: rotation::parse_size(&logging.max_size).unwrap_or(DEFAULT_MAX_LOG_BYTES),
+                    max_files: logging.max_files as usize,
+                    compress: logging.compress,
+                },
+                _ => LogRotation::Daily,
+            };
+        }
+
+        log_config
+    }
+}
+
 /// 初始化日志系统
-pub fn init_logging(config: LogConfig) -> Result<(), MiningError> {
-    let level_filter = match config.level.to_lowercase().as_str() {
-        "trace" => tracing::Level::TRACE,
-        "debug" => tracing::Level::DEBUG,
-        "info" => tracing::Level::INFO,
-        "warn" => tracing::Level::WARN,
-        "error" => tracing::Level::ERROR,
-        _ => tracing::Level::INFO,
+///
+/// 写入文件时返回后台写入线程的守卫，必须在程序退出前一直持有，否则缓冲中的日志会丢失。
+pub fn init_logging(config: LogConfig) -> Result<Option<WorkerGuard>, MiningError> {
+    // 过滤层可在运行时重载，见 filter::update
+    let filter_layer = filter::reloadable_filter(&config.level, &config.targets)?;
+
+    // 远程推送层，后台任务需要在 tokio 运行时中启动
+    let shipper_layer = match config.shipping {
+        Some(shipping) => {
+            let runtime = tokio::runtime::Handle::try_current()
+                .map_err(|_| MiningError::System("Log shipping requires a tokio runtime".to_string()))?;
+            let (layer, worker) = shipper::create(shipping)?;
+            runtime.spawn(worker.run());
+            Some(layer)
+        }
+        None => None,
     };
 
-    let env_filter = EnvFilter::from_default_env()
-        .add_directive(level_filter.into());
+    // 采样层丢弃的事件不会到达后面的任何输出层
+    let sampling_layer = sampling::sampling_layer(&config.sampling);
+    if let Some(task) = sampling_layer.as_ref().and_then(sampling::SamplingLayer::summary_task) {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|_| MiningError::System("Log sampling summaries require a tokio runtime".to_string()))?;
+        runtime.spawn(task);
+    }
 
     let registry = tracing_subscriber::registry()
-        .with(env_filter);
+        .with(filter_layer)
+        .with(sampling_layer)
+        .with(crash::ring_buffer_layer(config.crash_log_lines))
+        .with(shipper_layer);
+
+    // tokio console (`tokio-console` 特性，需要以 `--cfg tokio_unstable` 编译)，默认监听 127.0.0.1:6669。
+    // 过滤层是全局的，`general.log_targets` 中须加入 `tokio=trace,runtime=trace` 才有任务数据
+    #[cfg(feature = "tokio-console")]
+    let registry = registry.with(console_subscriber::spawn());
 
     // 控制台输出层
-    let console_layer = if config.pretty {
-        fmt::layer()
+    let console_layer = if !config.console {
+        None
+    } else if config.pretty {
+        Some(fmt::layer()
             .with_ansi(config.colored)
             .with_target(false)  // 关闭目标模块显示，简化输出
             .with_thread_ids(false)  // 关闭线程ID显示，简化输出
             .with_span_events(FmtSpan::NONE)  // 关闭span事件，简化输出
             .event_format(formatter::CgminerFormatter::new(config.colored))  // 使用简洁格式化器
-            .boxed()
+            .boxed())
     } else {
-        fmt::layer()
+        Some(fmt::layer()
             .with_ansi(config.colored)
             .with_target(config.show_target)
             .with_thread_ids(config.show_thread_id)
-            .boxed()
+            .boxed())
     };
 
     // 文件输出层
@@ -103,7 +200,7 @@ pub fn init_logging(config: LogConfig) -> Result<(), MiningError> {
             .and_then(|n| n.to_str())
             .unwrap_or("cgminer.log");
 
-        let (non_blocking_appender, _guard) = match config.rotation {
+        let (non_blocking_appender, guard) = match config.rotation {
             LogRotation::Never => {
                 let file = std::fs::OpenOptions::new()
                     .create(true)
@@ -121,9 +218,9 @@ pub fn init_logging(config: LogConfig) -> Result<(), MiningError> {
                 let file_appender = rolling::daily(directory, file_name);
                 non_blocking(file_appender)
             }
-            LogRotation::Size(_size) => {
-                // 简化实现，使用每日轮转
-                let file_appender = rolling::daily(directory, file_name);
+            LogRotation::Size { max_bytes, max_files, compress } => {
+                let file_appender = rotation::SizeRotatingWriter::new(file_path, max_bytes, max_files, compress)
+                    .map_err(|e| MiningError::System(format!("Failed to open log file: {}", e)))?;
                 non_blocking(file_appender)
             }
         };
@@ -132,20 +229,28 @@ pub fn init_logging(config: LogConfig) -> Result<(), MiningError> {
             .with_writer(non_blocking_appender)
             .with_ansi(false)
             .with_target(true)
-            .with_thread_ids(true)
-            .json();
+            .with_thread_ids(true);
+        let file_layer = if config.json_format {
+            file_layer.json().boxed()
+        } else {
+            file_layer.boxed()
+        };
 
         registry
             .with(console_layer)
             .with(file_layer)
-            .init();
+            .try_init()
+            .map_err(|e| MiningError::System(format!("Failed to initialize logging: {}", e)))?;
+
+        Ok(Some(guard))
     } else {
         registry
             .with(console_layer)
-            .init();
-    }
+            .try_init()
+            .map_err(|e| MiningError::System(format!("Failed to initialize logging: {}", e)))?;
 
-    Ok(())
+        Ok(None)
+    }
 }
 
 /// 挖矿专用日志宏
@@ -0,0 +1,166 @@
+//! 按大小轮转的日志写入器
+//!
+//! 当前文件超过上限时依次重命名为 `<name>.1`、`<name>.2` ...，超出保留数量的旧文件被删除，
+//! 可选将轮转出的文件压缩为 `.gz`。嵌入式控制板的闪存很小，按时间轮转无法限制占用空间。
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 按大小轮转的文件写入器
+pub struct SizeRotatingWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    compress: bool,
+    file: File,
+    written: u64,
+}
+
+impl SizeRotatingWriter {
+    /// 打开日志文件，`max_files` 为保留的已轮转文件数量
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize, compress: bool) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = open_append(&path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+        Ok(Self {
+            path,
+            max_bytes: max_bytes.max(1),
+            max_files,
+            compress,
+            file,
+            written,
+        })
+    }
+
+    /// 第 `index` 个已轮转文件的路径
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        if self.compress {
+            name.push(".gz");
+        }
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            // 不保留历史文件，直接截断
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        let oldest = self.rotated_path(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+
+        let first = self.rotated_path(1);
+        if self.compress {
+            compress_file(&self.path, &first)?;
+            fs::remove_file(&self.path)?;
+        } else {
+            fs::rename(&self.path, &first)?;
+        }
+
+        self.file = open_append(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn compress_file(source: &Path, target: &Path) -> io::Result<()> {
+    let mut input = File::open(source)?;
+    let mut encoder = GzEncoder::new(File::create(target)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()
+}
+
+/// 解析 "10MB"、"512KB"、"1G" 之类的大小，纯数字按字节
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim().to_ascii_uppercase();
+    let digits_end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(digits_end);
+    let number: u64 = number.parse().ok()?;
+
+    let multiplier = match unit.trim().trim_end_matches('B').trim_end_matches('I') {
+        "" => 1,
+        "K" => 1024,
+        "M" => 1024 * 1024,
+        "G" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("100MB"), Some(100 * 1024 * 1024));
+        assert_eq!(parse_size("512 kb"), Some(512 * 1024));
+        assert_eq!(parse_size("1GiB"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("2048"), Some(2048));
+        assert_eq!(parse_size("ten"), None);
+    }
+
+    #[test]
+    fn test_rotation_and_retention() {
+        let dir = std::env::temp_dir().join(format!("cgminer-rotation-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("cgminer.log");
+        let mut writer = SizeRotatingWriter::new(&path, 10, 2, true).unwrap();
+
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "dddddddd\n");
+        assert!(dir.join("cgminer.log.1.gz").exists());
+        assert!(dir.join("cgminer.log.2.gz").exists());
+        assert!(!dir.join("cgminer.log.3.gz").exists());
+
+        let mut decoded = String::new();
+        let file = File::open(dir.join("cgminer.log.1.gz")).unwrap();
+        io::Read::read_to_string(&mut flate2::read::GzDecoder::new(file), &mut decoded).unwrap();
+        assert_eq!(decoded, "cccccccc\n");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
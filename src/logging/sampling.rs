@@ -0,0 +1,185 @@
+//! 高频日志采样 (`general.log_sampling`)
+//!
+//! Stratum 客户端在 debug 级别逐条记录每个消息，繁忙的矿机开启 debug 后日志很快占满磁盘。
+//! 采样规则对指定目标只保留每 N 条中的 1 条，被丢弃的数量按周期汇总输出一条摘要。
+//! 只作用于规则级别及更详细的事件，warn / error 永远不会被采样丢弃。
+
+use crate::logging::filter::CRATE_PREFIX;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 采样规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSamplingRule {
+    /// 目标或模块路径，如 "pool::stratum"
+    pub target: String,
+    /// 采样的最高级别，该级别及更详细的事件参与采样
+    pub level: String,
+    /// 每 N 条保留 1 条，0 表示全部丢弃只输出摘要
+    pub every: u64,
+    /// 摘要输出间隔 (秒)，0 表示不输出摘要
+    pub summary_secs: u64,
+}
+
+impl Default for LogSamplingRule {
+    fn default() -> Self {
+        Self {
+            target: String::new(),
+            level: "debug".to_string(),
+            every: 100,
+            summary_secs: 10,
+        }
+    }
+}
+
+impl LogSamplingRule {
+    /// 检查规则，返回错误说明
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target.trim().is_empty() {
+            return Err("Log sampling rule target cannot be empty".to_string());
+        }
+        if self.level.parse::<Level>().is_err() {
+            return Err(format!("Unknown log sampling level '{}' for target '{}'", self.level, self.target));
+        }
+        if self.every == 0 && self.summary_secs == 0 {
+            return Err(format!(
+                "Log sampling rule for '{}' drops every event without a summary; set every or summary_secs",
+                self.target
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 规则运行状态
+struct RuleState {
+    target: String,
+    crate_target: String,
+    level: Level,
+    every: u64,
+    summary_secs: u64,
+    seen: AtomicU64,
+    suppressed: AtomicU64,
+}
+
+impl RuleState {
+    fn matches(&self, metadata: &Metadata<'_>) -> bool {
+        if *metadata.level() < self.level {
+            return false;
+        }
+        let target = metadata.target();
+        [&self.target, &self.crate_target].iter().any(|prefix| {
+            target == prefix.as_str()
+                || (target.starts_with(prefix.as_str()) && target[prefix.len()..].starts_with("::"))
+        })
+    }
+
+    /// 计数并决定是否保留该事件
+    fn sample(&self) -> bool {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        let keep = self.every > 0 && seen % self.every == 0;
+        if !keep {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+        }
+        keep
+    }
+}
+
+/// 采样层，在事件分发给各输出层之前丢弃未被采中的事件
+pub struct SamplingLayer {
+    rules: Arc<Vec<RuleState>>,
+}
+
+/// 创建采样层，规则为空时返回 None
+pub fn sampling_layer(rules: &[LogSamplingRule]) -> Option<SamplingLayer> {
+    let rules: Vec<RuleState> = rules.iter()
+        .filter(|rule| rule.validate().is_ok())
+        .map(|rule| {
+            let target = rule.target.trim().to_string();
+            RuleState {
+                crate_target: format!("{}::{}", CRATE_PREFIX, target),
+                target,
+                level: rule.level.parse().unwrap_or(Level::DEBUG),
+                every: rule.every,
+                summary_secs: rule.summary_secs,
+                seen: AtomicU64::new(0),
+                suppressed: AtomicU64::new(0),
+            }
+        })
+        .collect();
+
+    if rules.is_empty() {
+        None
+    } else {
+        Some(SamplingLayer { rules: Arc::new(rules) })
+    }
+}
+
+impl SamplingLayer {
+    /// 输出丢弃摘要的后台任务，没有规则需要摘要时为空。
+    /// 摘要不能在订阅者回调内产生，否则会被 tracing 的重入保护丢弃
+    pub fn summary_task(&self) -> Option<impl std::future::Future<Output = ()> + Send + 'static> {
+        let tick = self.rules.iter()
+            .filter(|rule| rule.summary_secs > 0)
+            .map(|rule| rule.summary_secs)
+            .min()?;
+        let rules = self.rules.clone();
+
+        Some(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(tick));
+            let mut elapsed = 0u64;
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                elapsed += tick;
+                for rule in rules.iter().filter(|rule| rule.summary_secs > 0 && elapsed % rule.summary_secs == 0) {
+                    let suppressed = rule.suppressed.swap(0, Ordering::Relaxed);
+                    if suppressed > 0 {
+                        info!(
+                            "Log sampling suppressed {} {} events from '{}' in the last {}s",
+                            suppressed, rule.level, rule.target, rule.summary_secs
+                        );
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SamplingLayer {
+    fn event_enabled(&self, event: &Event<'_>, _ctx: Context<'_, S>) -> bool {
+        let metadata = event.metadata();
+        match self.rules.iter().find(|rule| rule.matches(metadata)) {
+            Some(rule) => rule.sample(),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_rule() {
+        let layer = sampling_layer(&[LogSamplingRule {
+            target: "pool::stratum".to_string(),
+            every: 3,
+            ..Default::default()
+        }]).unwrap();
+        let rule = &layer.rules[0];
+
+        let kept: Vec<bool> = (0..7).map(|_| rule.sample()).collect();
+        assert_eq!(kept, vec![true, false, false, true, false, false, true]);
+        assert_eq!(rule.suppressed.load(Ordering::Relaxed), 4);
+
+        let invalid = LogSamplingRule { target: "pool".to_string(), every: 0, summary_secs: 0, ..Default::default() };
+        assert!(invalid.validate().is_err());
+        assert!(sampling_layer(&[invalid]).is_none());
+    }
+}
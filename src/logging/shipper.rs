@@ -0,0 +1,457 @@
+//! 远程日志推送 (`[log_shipping]`)
+//!
+//! 日志事件经有界通道交给后台任务，按批推送到 Grafana Loki 或通用 HTTP 批量接口，
+//! 附带矿机名和版本标签。通道满时丢弃事件而不阻塞挖矿线程；端点不可达时批次暂存到本地磁盘，
+//! 恢复后补发。
+
+use crate::error::MiningError;
+use crate::logging::redact;
+use crate::logging::rotation::parse_size;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{info, warn, Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 推送目标类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShippingBackend {
+    /// Grafana Loki push API (`/loki/api/v1/push`)
+    Loki,
+    /// 通用 HTTP 批量接口，POST `{"labels": {...}, "events": [...]}`
+    Http,
+}
+
+/// 远程日志推送配置
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogShippingConfig {
+    /// 是否启用远程推送
+    pub enabled: bool,
+    /// 推送目标类型: loki / http
+    pub backend: ShippingBackend,
+    /// 推送地址，Loki 填写服务地址（如 http://loki:3100）
+    pub url: String,
+    /// Bearer 认证令牌
+    pub auth_token: Option<String>,
+    /// 矿机名标签，为空时使用主机名
+    pub rig_name: String,
+    /// 推送的最低级别
+    pub min_level: String,
+    /// 单批最多事件数
+    pub batch_size: usize,
+    /// 最长推送间隔 (毫秒)
+    pub flush_interval_ms: u64,
+    /// 请求超时 (秒)
+    pub timeout_secs: u64,
+    /// 端点不可达时暂存批次的目录
+    pub spill_dir: String,
+    /// 暂存文件最大大小，超过后丢弃新批次
+    pub max_spill_size: String,
+    /// 附加标签
+    pub labels: BTreeMap<String, String>,
+}
+
+impl Default for LogShippingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: ShippingBackend::Loki,
+            url: String::new(),
+            auth_token: None,
+            rig_name: String::new(),
+            min_level: "info".to_string(),
+            batch_size: 500,
+            flush_interval_ms: 2000,
+            timeout_secs: 10,
+            spill_dir: "./logs/spill".to_string(),
+            max_spill_size: "50MB".to_string(),
+            labels: BTreeMap::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for LogShippingConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogShippingConfig")
+            .field("enabled", &self.enabled)
+            .field("backend", &self.backend)
+            .field("url", &redact::redact_url(&self.url))
+            .field("auth_token", &redact::mask_option(&self.auth_token))
+            .field("rig_name", &self.rig_name)
+            .field("min_level", &self.min_level)
+            .field("batch_size", &self.batch_size)
+            .field("flush_interval_ms", &self.flush_interval_ms)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("spill_dir", &self.spill_dir)
+            .field("max_spill_size", &self.max_spill_size)
+            .field("labels", &self.labels)
+            .finish()
+    }
+}
+
+/// 通道容量，按批大小的倍数留出余量
+const CHANNEL_BATCHES: usize = 8;
+
+/// 暂存文件名
+const SPILL_FILE: &str = "log-shipping.jsonl";
+
+/// 一条待推送的日志事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// Unix 时间戳 (纳秒)
+    pub timestamp_ns: u128,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 采集日志事件的 tracing 层
+pub struct ShipperLayer {
+    sender: mpsc::Sender<LogRecord>,
+    min_level: Level,
+    dropped: Arc<AtomicU64>,
+}
+
+/// 推送器自身及 HTTP 客户端的事件不推送，避免反馈循环
+fn is_internal_target(target: &str) -> bool {
+    target.starts_with(module_path!())
+        || ["reqwest", "hyper", "h2", "rustls", "native_tls"].iter().any(|t| target.starts_with(t))
+}
+
+impl<S: Subscriber> Layer<S> for ShipperLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() > self.min_level || is_internal_target(metadata.target()) {
+            return;
+        }
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let record = LogRecord {
+            timestamp_ns: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0),
+            level: metadata.level().as_str().to_ascii_lowercase(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        };
+        if self.sender.try_send(record).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 收集事件字段，敏感字段遮蔽
+#[derive(Default)]
+pub(crate) struct FieldVisitor {
+    pub(crate) message: String,
+    pub(crate) fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        let value = if redact::is_secret_key(field.name()) {
+            serde_json::Value::String(redact::MASK.to_string())
+        } else {
+            value
+        };
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.insert(field, serde_json::Value::String(format!("{:?}", value)));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, serde_json::Value::String(value.to_string()));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+}
+
+/// 后台推送任务
+pub struct ShipperWorker {
+    config: LogShippingConfig,
+    receiver: mpsc::Receiver<LogRecord>,
+    client: reqwest::Client,
+    labels: BTreeMap<String, String>,
+    spill_path: PathBuf,
+    max_spill_bytes: u64,
+    dropped: Arc<AtomicU64>,
+    online: bool,
+}
+
+/// 创建采集层和后台推送任务
+pub fn create(config: LogShippingConfig) -> Result<(ShipperLayer, ShipperWorker), MiningError> {
+    let min_level: Level = config.min_level.parse()
+        .map_err(|_| MiningError::configuration(format!("log_shipping.min_level: unknown level '{}'", config.min_level)))?;
+    let max_spill_bytes = parse_size(&config.max_spill_size)
+        .ok_or_else(|| MiningError::configuration(format!("log_shipping.max_spill_size: invalid size '{}'", config.max_spill_size)))?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+        .build()
+        .map_err(|e| MiningError::System(format!("Failed to create log shipping client: {}", e)))?;
+
+    let rig_name = if config.rig_name.is_empty() {
+        crate::config::profiles::local_hostname().unwrap_or_else(|| "unknown".to_string())
+    } else {
+        config.rig_name.clone()
+    };
+    let mut labels = config.labels.clone();
+    labels.insert("job".to_string(), "cgminer-rs".to_string());
+    labels.insert("rig".to_string(), rig_name);
+    labels.insert("version".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+    let (sender, receiver) = mpsc::channel(config.batch_size.max(1) * CHANNEL_BATCHES);
+    let dropped = Arc::new(AtomicU64::new(0));
+    let layer = ShipperLayer { sender, min_level, dropped: dropped.clone() };
+    let worker = ShipperWorker {
+        spill_path: PathBuf::from(&config.spill_dir).join(SPILL_FILE),
+        config,
+        receiver,
+        client,
+        labels,
+        max_spill_bytes,
+        dropped,
+        online: true,
+    };
+    Ok((layer, worker))
+}
+
+impl ShipperWorker {
+    /// 运行推送循环，所有采集层被释放后推送剩余事件并退出
+    pub async fn run(mut self) {
+        let batch_size = self.config.batch_size.max(1);
+        let mut ticker = tokio::time::interval(Duration::from_millis(self.config.flush_interval_ms.max(100)));
+        let mut batch = Vec::with_capacity(batch_size);
+
+        loop {
+            tokio::select! {
+                record = self.receiver.recv() => match record {
+                    Some(record) => {
+                        batch.push(record);
+                        if batch.len() >= batch_size {
+                            self.flush(&mut batch).await;
+                        }
+                    }
+                    None => {
+                        self.flush(&mut batch).await;
+                        break;
+                    }
+                },
+                _ = ticker.tick() => self.flush(&mut batch).await,
+            }
+        }
+    }
+
+    async fn flush(&mut self, batch: &mut Vec<LogRecord>) {
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            warn!("Log shipping queue full, dropped {} events", dropped);
+        }
+
+        if !batch.is_empty() {
+            let records = std::mem::take(batch);
+            match self.push(&records).await {
+                Ok(()) => self.set_online(true, None),
+                Err(e) => {
+                    self.set_online(false, Some(&e));
+                    self.spill(&records).await;
+                    return;
+                }
+            }
+        }
+
+        self.replay_spill().await;
+    }
+
+    fn set_online(&mut self, online: bool, error: Option<&str>) {
+        if online == self.online {
+            return;
+        }
+        self.online = online;
+        match error {
+            Some(e) => warn!("Log shipping endpoint {} unreachable, spilling to {}: {}", self.config.url, self.spill_path.display(), e),
+            None => info!("Log shipping endpoint {} reachable again", self.config.url),
+        }
+    }
+
+    async fn push(&self, records: &[LogRecord]) -> Result<(), String> {
+        let (url, body) = match self.config.backend {
+            ShippingBackend::Loki => (loki_push_url(&self.config.url), loki_payload(&self.labels, records)),
+            ShippingBackend::Http => (
+                self.config.url.clone(),
+                serde_json::json!({ "labels": self.labels, "events": records }),
+            ),
+        };
+
+        let mut request = self.client.post(&url).json(&body);
+        if let Some(token) = &self.config.auth_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+
+    /// 批次追加到暂存文件，超过上限时丢弃
+    async fn spill(&self, records: &[LogRecord]) {
+        let mut data = Vec::new();
+        for record in records {
+            if let Ok(line) = serde_json::to_vec(record) {
+                data.extend_from_slice(&line);
+                data.push(b'\n');
+            }
+        }
+
+        let current = tokio::fs::metadata(&self.spill_path).await.map(|m| m.len()).unwrap_or(0);
+        if current + data.len() as u64 > self.max_spill_bytes {
+            warn!("Log shipping spill file is full, dropped {} events", records.len());
+            return;
+        }
+
+        let result = async {
+            if let Some(parent) = self.spill_path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&self.spill_path).await?;
+            file.write_all(&data).await?;
+            file.flush().await
+        }.await;
+        if let Err(e) = result {
+            warn!("Failed to spill {} log events to {}: {}", records.len(), self.spill_path.display(), e);
+        }
+    }
+
+    /// 端点恢复后补发暂存的事件，失败时保留未发送的部分
+    async fn replay_spill(&mut self) {
+        let content = match tokio::fs::read_to_string(&self.spill_path).await {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+        let records: Vec<LogRecord> = content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+
+        let batch_size = self.config.batch_size.max(1);
+        for (index, chunk) in records.chunks(batch_size).enumerate() {
+            if let Err(e) = self.push(chunk).await {
+                self.set_online(false, Some(&e));
+                let mut remaining = Vec::new();
+                for record in &records[index * batch_size..] {
+                    if let Ok(line) = serde_json::to_vec(record) {
+                        remaining.extend_from_slice(&line);
+                        remaining.push(b'\n');
+                    }
+                }
+                if let Err(e) = tokio::fs::write(&self.spill_path, remaining).await {
+                    warn!("Failed to rewrite log shipping spill file: {}", e);
+                }
+                return;
+            }
+        }
+
+        self.set_online(true, None);
+        if let Err(e) = tokio::fs::remove_file(&self.spill_path).await {
+            warn!("Failed to remove log shipping spill file: {}", e);
+        } else if !records.is_empty() {
+            info!("Replayed {} spilled log events", records.len());
+        }
+    }
+}
+
+/// Loki push API 地址，已包含路径时原样使用
+fn loki_push_url(base: &str) -> String {
+    if base.contains("/loki/api/") {
+        base.to_string()
+    } else {
+        format!("{}/loki/api/v1/push", base.trim_end_matches('/'))
+    }
+}
+
+/// Loki 推送请求体，每个级别一个流
+fn loki_payload(labels: &BTreeMap<String, String>, records: &[LogRecord]) -> serde_json::Value {
+    let mut streams: BTreeMap<&str, Vec<serde_json::Value>> = BTreeMap::new();
+    for record in records {
+        let line = serde_json::json!({
+            "target": record.target,
+            "message": record.message,
+            "fields": record.fields,
+        });
+        streams.entry(record.level.as_str())
+            .or_default()
+            .push(serde_json::json!([record.timestamp_ns.to_string(), line.to_string()]));
+    }
+
+    let streams: Vec<serde_json::Value> = streams.into_iter()
+        .map(|(level, values)| {
+            let mut stream = labels.clone();
+            stream.insert("level".to_string(), level.to_string());
+            serde_json::json!({ "stream": stream, "values": values })
+        })
+        .collect();
+    serde_json::json!({ "streams": streams })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loki_payload() {
+        let record = |level: &str, message: &str| LogRecord {
+            timestamp_ns: 1_700_000_000_000_000_000,
+            level: level.to_string(),
+            target: "pool".to_string(),
+            message: message.to_string(),
+            fields: serde_json::Map::new(),
+        };
+        let labels = BTreeMap::from([("rig".to_string(), "rack-01".to_string())]);
+        let payload = loki_payload(&labels, &[record("info", "a"), record("warn", "b"), record("info", "c")]);
+
+        let streams = payload["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0]["stream"]["level"], "info");
+        assert_eq!(streams[0]["stream"]["rig"], "rack-01");
+        assert_eq!(streams[0]["values"].as_array().unwrap().len(), 2);
+        assert_eq!(streams[0]["values"][0][0], "1700000000000000000");
+
+        assert_eq!(loki_push_url("http://loki:3100/"), "http://loki:3100/loki/api/v1/push");
+        assert_eq!(loki_push_url("http://loki:3100/loki/api/v1/push"), "http://loki:3100/loki/api/v1/push");
+    }
+}
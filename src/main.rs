@@ -1,9 +1,7 @@
-use anyhow::Result;
 use clap::Parser;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{info, error, warn, debug};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, fmt::format::FmtSpan};
 
 mod config;
 mod device;
@@ -16,63 +14,407 @@ mod core_loader;
 mod web;
 mod logging;
 mod performance;
+mod security;
+mod service;
+#[cfg(feature = "tui")]
+mod tui;
+mod utils;
 
 
 use config::{Config, Args};
 use mining::MiningManager;
+use api::server::{ApiServer, ServerConfig};
 use core_loader::StaticCoreRegistry;
+use security::backup::{self, BackupBundle, DeviceIdentity};
+use security::{ConfigGuard, SecurityConfig, TamperPolicy};
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let start_time = Instant::now();
 
-    // 初始化日志系统
-    if let Err(e) = init_logging() {
-        eprintln!("❌ Failed to initialize logging: {}", e);
+    // 解析命令行参数（日志系统在加载配置后初始化）
+    let args = Args::parse();
+
+    // 命令行客户端：通过 API 操作正在运行的实例
+    if let Some(command) = &args.command {
+        std::process::exit(run_client(command, &args));
+    }
+
+    // Windows 服务的安装和卸载
+    if args.install_service || args.uninstall_service {
+        let result = if args.install_service {
+            std::fs::canonicalize(&args.config)
+                .map_err(|e| format!("Configuration file {} not found: {}", args.config, e))
+                .and_then(|path| service::install(&path, args.profile.as_deref()))
+        } else {
+            service::uninstall()
+        };
+        match result {
+            Ok(()) if args.install_service => println!("✅ Service {} installed; start it with: sc start {}", service::SERVICE_NAME, service::SERVICE_NAME),
+            Ok(()) => println!("✅ Service {} removed", service::SERVICE_NAME),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
         return;
     }
 
-    // 显示启动横幅
-    print_startup_banner();
+    // 由服务控制管理器启动：在服务线程中运行，收到停止控制后有序停机
+    if args.run_as_service {
+        if let Err(e) = service::run(move || run_blocking(args, start_time)) {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    // 解析命令行参数
-    let args = Args::parse();
-    debug!("📝 Command line arguments parsed successfully");
+    run_blocking(args, start_time);
+}
 
-    // 加载配置
-    let mut config = match Config::load(&args.config) {
-        Ok(cfg) => {
-            info!("📋 Configuration loaded from: {}", args.config);
-            cfg
-        },
+/// 执行客户端子命令，返回退出码
+fn run_client(command: &api::client::ClientCommand, args: &Args) -> i32 {
+    use api::client::{self, ApiClient, Endpoint};
+
+    let api_config = match client::api_config_from_file(&args.config, args.profile.as_deref()) {
+        Ok(api_config) => api_config,
         Err(e) => {
-            error!("❌ Failed to load configuration file '{}': {}", args.config, e);
-            error!("💡 Please check if the file exists and has valid TOML syntax");
-            return;
+            eprintln!("❌ Failed to read {}: {:#}", args.config, e);
+            return 1;
+        }
+    };
+    let endpoint = Endpoint::resolve(command.options(), api_config.as_ref());
+    let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ Failed to start the async runtime: {}", e);
+            return 1;
+        }
+    };
+    let result = runtime.block_on(async {
+        let client = ApiClient::new(endpoint)?;
+        client::run(command, &client).await
+    });
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ {:#}", e);
+            1
+        }
+    }
+}
+
+/// 运行模拟矿池直到 Ctrl+C，返回退出码
+async fn run_mock_pool(port: u16) -> i32 {
+    use pool::mock::{MockPool, MockPoolOptions};
+
+    // 演示时定期换作业，让矿机的工作重启路径也跑起来
+    let options = MockPoolOptions { job_interval: Some(Duration::from_secs(30)), ..Default::default() };
+    let mock_pool = match MockPool::start(("0.0.0.0", port), options).await {
+        Ok(mock_pool) => mock_pool,
+        Err(e) => {
+            eprintln!("❌ Failed to start mock pool on port {}: {}", port, e);
+            return 1;
+        }
+    };
+    println!("🧪 Mock pool listening on {} (any username/password, Ctrl+C to stop)", mock_pool.url());
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        eprintln!("❌ Failed to wait for Ctrl+C: {}", e);
+    }
+    let stats = mock_pool.stats();
+    println!("🧪 Mock pool stopped: {} connections, {} shares accepted, {} rejected", stats.connections, stats.accepted, stats.rejected);
+    0
+}
+
+/// 构建异步运行时并运行矿机直到停机
+fn run_blocking(args: Args, start_time: Instant) {
+    // 按 [runtime] 手动构建异步运行时，小型控制板上不按 CPU 数创建工作线程
+    let runtime_config = config::runtime::RuntimeConfig::from_config_file(&args.config, args.profile.as_deref());
+    let (runtime, runtime_description) = match runtime_config.build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ Failed to start the async runtime: {}", e);
+            std::process::exit(1);
+        }
+    };
+    runtime.block_on(run(args, start_time, runtime_description));
+}
+
+async fn run(args: Args, start_time: Instant, runtime_description: String) {
+
+    #[cfg(not(feature = "tui"))]
+    if args.tui {
+        eprintln!("❌ This build has no terminal UI; rebuild with --features=tui to use --tui");
+        std::process::exit(1);
+    }
+
+    // 插件核心子进程：只承载插件，不初始化矿机
+    if let (Some(plugin), Some(socket)) = (&args.core_host, &args.core_socket) {
+        run_core_host(plugin, socket).await;
+    }
+
+    // 生成默认配置文件
+    if let Some(path) = &args.generate_config {
+        match config::template::write_default_config(path) {
+            Ok(()) => {
+                println!("✅ Default configuration written to {}", path);
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("❌ {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 生成 Web 登录密码哈希
+    if args.hash_password {
+        eprint!("Password: ");
+        let mut password = String::new();
+        if let Err(e) = std::io::stdin().read_line(&mut password) {
+            eprintln!("❌ Failed to read password: {}", e);
+            std::process::exit(1);
+        }
+        let password = password.trim_end_matches(['\r', '\n']);
+        if password.is_empty() {
+            eprintln!("❌ Password cannot be empty");
+            std::process::exit(1);
+        }
+        match web::auth::hash_password(password) {
+            Ok(hash) => {
+                println!("{}", hash);
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 结果流水线压力测试：不需要配置文件、矿池和核心
+    if let Some(results) = args.load_test {
+        let config = mining::load_test::LoadTestConfig { results, ..Default::default() };
+        let report = mining::load_test::run(&config).await;
+        println!("{}", report);
+        if let Some(min_rate) = args.load_test_min_rate {
+            if report.throughput() < min_rate {
+                eprintln!("❌ Throughput {:.0} results/s is below the required {:.0} results/s", report.throughput(), min_rate);
+                std::process::exit(1);
+            }
+        }
+        std::process::exit(0);
+    }
+
+    // 模拟矿池：不需要配置文件，供演示和测试时把矿工指向本机
+    if let Some(port) = args.mock_pool {
+        std::process::exit(run_mock_pool(port).await);
+    }
+
+    // 从备份包恢复（配置文件此时可能还不存在）
+    if let Some(path) = &args.restore_backup {
+        let result = std::fs::read(path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))
+            .and_then(|data| {
+                let passphrase = read_backup_passphrase()?;
+                BackupBundle::open(&data, &passphrase).map_err(|e| e.to_string())
+            })
+            .and_then(|bundle| bundle.restore().map(|files| (bundle, files)).map_err(|e| e.to_string()));
+        match result {
+            Ok((bundle, files)) => {
+                for file in &files {
+                    println!("   ✓ {}", file.display());
+                }
+                println!("✅ Restored {} file(s) and {} device identities from {} (created on {})",
+                         files.len(), bundle.devices.len(), path, bundle.hostname.as_deref().unwrap_or("unknown host"));
+                println!("💡 Start with --config {}", bundle.config_path);
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 导入原版 cgminer 配置
+    if let Some(source) = &args.import_cgminer_conf {
+        if std::path::Path::new(&args.config).exists() {
+            eprintln!("❌ Refusing to overwrite existing file: {}", args.config);
+            std::process::exit(1);
+        }
+        let result = config::import::import_cgminer_conf(source)
+            .and_then(|result| {
+                let content = result.to_toml(source)?;
+                std::fs::write(&args.config, content)?;
+                Ok(result)
+            });
+        match result {
+            Ok(result) => {
+                println!("✅ Imported {} into {} ({} pool(s))", source, args.config, result.config.pools.pools.len());
+                for key in &result.unmapped {
+                    println!("   ⚠️ Not imported: {}", key);
+                }
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to import {}: {:#}", source, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 仅检查配置
+    if args.check_config {
+        let report = config::check::check_config_file(&args.config, args.profile.as_deref());
+        println!("{}", report);
+        std::process::exit(if report.has_errors() { 1 } else { 0 });
+    }
+
+    // 加载配置；通过 -o 指定矿池且配置文件不存在时使用默认配置
+    let use_defaults = !args.pool.is_empty() && !std::path::Path::new(&args.config).exists();
+    let mut config = if use_defaults {
+        let mut cfg = Config::default();
+        cfg.pools.pools.clear();
+        cfg
+    } else {
+        match Config::load_with_profile(&args.config, args.profile.as_deref()) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                eprintln!("❌ Failed to load configuration file '{}': {:#}", args.config, e);
+                eprintln!("💡 Please check if the file exists and has valid TOML/JSON/YAML syntax");
+                std::process::exit(1);
+            }
         }
     };
 
     // 应用CLI参数覆盖配置
     if let Err(e) = config.apply_cli_args(&args) {
-        error!("❌ Failed to apply CLI arguments: {}", e);
-        error!("💡 Please check your command line arguments");
-        return;
+        eprintln!("❌ Failed to apply CLI arguments: {}", e);
+        eprintln!("💡 Please check your command line arguments");
+        std::process::exit(1);
+    }
+
+    // 生成备份包
+    if let Some(path) = &args.create_backup {
+        let result = BackupBundle::collect(&args.config, &config)
+            .map_err(|e| e.to_string())
+            .and_then(|bundle| {
+                let passphrase = read_backup_passphrase()?;
+                let sealed = bundle.seal(&passphrase).map_err(|e| e.to_string())?;
+                std::fs::write(path, sealed).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+                Ok(bundle)
+            });
+        match result {
+            Ok(bundle) => {
+                println!("✅ Wrote {} file(s) and {} device identities to {}", bundle.files.len(), bundle.devices.len(), path);
+                println!("💡 Keep the passphrase: the bundle cannot be restored without it");
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 上传崩溃报告
+    if let Some(path) = &args.upload_crash_report {
+        let report = if path.is_empty() {
+            logging::crash::latest_report(std::path::Path::new(&config.crash_report.dir))
+        } else {
+            Some(std::path::PathBuf::from(path))
+        };
+        let report = match report {
+            Some(report) => report,
+            None => {
+                eprintln!("❌ No crash reports found in {}", config.crash_report.dir);
+                std::process::exit(1);
+            }
+        };
+        if config.crash_report.upload_url.is_empty() {
+            eprintln!("❌ crash_report.upload_url is not set");
+            std::process::exit(1);
+        }
+        match logging::crash::upload_report(&report, &config.crash_report.upload_url).await {
+            Ok(()) => {
+                println!("✅ Uploaded crash report {}", report.display());
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // 按配置初始化日志系统，守卫需要持有到程序退出
+    logging::i18n::set_language(config.general.log_language);
+    let mut log_config = logging::LogConfig::from_config(&config);
+    if args.tui {
+        // 终端界面占用整个屏幕，日志只写文件
+        log_config.console = false;
+    }
+    let _log_guard = match logging::init_logging(log_config) {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("❌ Failed to initialize logging: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // panic 时写入崩溃报告
+    logging::crash::install_panic_hook(&config.crash_report, &config);
+    let crash_reports_enabled = config.crash_report.enabled;
+
+    // 显示启动横幅
+    print_startup_banner();
+    info!("🧵 Async runtime: {}", runtime_description);
+    #[cfg(feature = "alloc-audit")]
+    spawn_alloc_audit_reporter();
+
+    if use_defaults {
+        info!("📋 Configuration file '{}' not found, using defaults with CLI pools", args.config);
+    } else {
+        info!("📋 Configuration loaded from: {}", args.config);
     }
 
     // 如果有CLI覆盖，显示相关信息
-    if args.proxy.is_some() || args.pool.is_some() || args.user.is_some() {
+    if args.proxy.is_some() || !args.pool.is_empty() || !args.user.is_empty() {
         info!("🔧 CLI arguments applied to configuration");
         if let Some(proxy) = &args.proxy {
-            info!("   🌐 Proxy: {}", proxy);
+            info!("   🌐 Proxy: {}", logging::redact::redact_url(proxy));
         }
-        if let Some(pool) = &args.pool {
+        for pool in &args.pool {
             info!("   🏊 Pool: {}", pool);
         }
-        if let Some(user) = &args.user {
+        for user in &args.user {
             info!("   👤 User: {}", user);
         }
     }
 
+    // CLI 覆盖后重新验证（-o 可能是唯一的矿池来源）
+    if let Err(e) = config.validate() {
+        error!("❌ Invalid configuration: {}", e);
+        return;
+    }
+    if config.pools.network != mining::Network::Mainnet {
+        warn!("🧪 Mining on {}: difficulty 1 targets and wallet address checks follow this network", config.pools.network);
+    }
+
+    // pools.address_check = "warn" 时地址问题不阻止启动，但要足够醒目
+    let wallet_issues = config.wallet_address_issues();
+    if !wallet_issues.is_empty() {
+        warn!("═══════════════════════════════════════════════════════════");
+        for (_, message) in &wallet_issues {
+            warn!("⚠️ {}", message);
+        }
+        warn!("⚠️ Shares for these pools may be credited to someone else's wallet");
+        warn!("═══════════════════════════════════════════════════════════");
+    }
+
     // 显示配置摘要
     print_config_summary(&config);
 
@@ -87,6 +429,8 @@ async fn main() {
         }
     };
 
+    core_registry.load_plugins(&config.cores.plugins).await;
+
     // 显示注册的核心信息
     match core_registry.get_registry_stats().await {
         Ok(stats) => {
@@ -110,9 +454,21 @@ async fn main() {
 
     // 创建挖矿管理器
     info!("⚙️ Initializing mining manager...");
+    let server_config = ServerConfig::from_config(&config);
+    // 使用默认配置时没有可守护的配置文件
+    let config_guard = if use_defaults {
+        None
+    } else {
+        create_config_guard(&args.config, &server_config.security).await
+    };
+    let device_map_file = config.security.device_map_file.clone();
     let mining_manager = match MiningManager::new(config, core_registry.registry()).await {
-        Ok(manager) => {
+        Ok(mut manager) => {
             info!("✅ Mining manager initialized successfully");
+            manager.set_config_path(args.config.clone());
+            if let Some(guard) = &config_guard {
+                manager.set_config_guard(guard.clone());
+            }
             Arc::new(manager)
         },
         Err(e) => {
@@ -122,6 +478,27 @@ async fn main() {
         }
     };
 
+    // 核心自检：只启动核心，不连接矿池
+    if args.self_test {
+        match mining_manager.run_self_test(mining::self_test::VECTOR_TIMEOUT).await {
+            Ok(report) => {
+                println!("{}", report);
+                std::process::exit(if report.passed() { 0 } else { 1 });
+            }
+            Err(e) => {
+                eprintln!("❌ Self-test failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if crash_reports_enabled {
+        spawn_crash_state_updater(mining_manager.clone());
+    }
+    if let Some(guard) = config_guard {
+        guard.start(mining_manager.get_monitoring_system());
+    }
+
     // 设置信号处理
     debug!("🔧 Setting up signal handlers...");
     if let Err(e) = setup_signal_handlers(mining_manager.clone(), core_registry).await {
@@ -140,13 +517,34 @@ async fn main() {
         Ok(_) => {
             info!("✅ Mining operations started successfully!");
             info!("💎 CGMiner-RS is now mining Bitcoin...");
-            info!("📊 Monitor your mining progress through the API or logs");
-            info!("🔗 API available at: http://127.0.0.1:4028");
+            if !device_map_file.is_empty() {
+                record_device_map(&mining_manager, std::path::Path::new(&device_map_file)).await;
+            }
+
+            // 启动API服务器（同时提供Web仪表板）
+            let api_enabled = server_config.api.enabled;
+            let api_server = ApiServer::new(server_config, mining_manager.clone());
+            match api_server.start().await {
+                Ok(_) if api_enabled => {
+                    info!("📊 Monitor your mining progress through the API or logs");
+                    info!("🔗 API available at: {}", api_server.get_url());
+                }
+                Ok(_) => info!("📊 Monitor your mining progress through the logs"),
+                Err(e) => error!("❌ Failed to start API server: {}", e),
+            }
             info!("═══════════════════════════════════════════════════════════");
             info!("🎯 Press Ctrl+C to stop mining gracefully");
 
-            // 保持程序运行
-            if let Err(e) = tokio::signal::ctrl_c().await {
+            // 保持程序运行；零算力恢复请求退出时同样有序停机，最后以其退出码退出
+            let mut exit_code = None;
+            let waited = tokio::select! {
+                result = wait_for_shutdown(args.tui, mining_manager.clone()) => result,
+                code = mining_manager.recovery_exit_requested() => {
+                    exit_code = Some(code);
+                    Ok(())
+                }
+            };
+            if let Err(e) = waited {
                 error!("❌ Error waiting for shutdown signal: {}", e);
                 return;
             }
@@ -155,6 +553,11 @@ async fn main() {
             info!("🛑 Shutdown signal received - stopping mining operations...");
 
             // 优雅关闭
+            if api_enabled {
+                if let Err(e) = api_server.stop().await {
+                    warn!("⚠️ Error stopping API server: {}", e);
+                }
+            }
             if let Err(e) = mining_manager.stop().await {
                 error!("❌ Error during mining shutdown: {}", e);
             } else {
@@ -163,6 +566,10 @@ async fn main() {
 
             let total_runtime = start_time.elapsed();
             info!("⏱️  Total runtime: {:.2}s", total_runtime.as_secs_f64());
+            if let Some(code) = exit_code {
+                error!("🔁 Exiting with code {} after zero-hashrate recovery", code);
+                std::process::exit(code);
+            }
             info!("👋 CGMiner-RS shutdown completed. Thank you for mining!");
         }
         Err(e) => {
@@ -172,65 +579,213 @@ async fn main() {
     }
 }
 
-fn init_logging() -> Result<()> {
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "cgminer_rs=info".into()),
-        )
-        .with(
-            tracing_subscriber::fmt::layer()
-                .with_target(false)
-                .with_thread_ids(false)
-                .with_thread_names(false)
-                .with_span_events(FmtSpan::NONE)
-                .with_ansi(true)
-        )
-        .init();
+/// 分配审计构建：每 10 秒输出一次各子系统每秒的分配
+#[cfg(feature = "alloc-audit")]
+fn spawn_alloc_audit_reporter() {
+    info!("🔬 Allocation audit enabled");
+    tokio::spawn(async move {
+        let mut monitor = performance::PerformanceMonitor::new();
+        let mut ticker = tokio::time::interval(Duration::from_secs(10));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let rates = monitor.allocation_rates().unwrap_or_default();
+            let summary: Vec<String> = rates.iter()
+                .map(|rate| format!("{:?} {:.0} allocs/s ({:.1} KB/s)", rate.subsystem, rate.allocations_per_sec, rate.bytes_per_sec / 1024.0))
+                .collect();
+            info!("🔬 Allocations: {}", summary.join(", "));
+        }
+    });
+}
+
+async fn setup_signal_handlers(mining_manager: Arc<MiningManager>, core_registry: StaticCoreRegistry) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::spawn(async move {
+            sigterm.recv().await;
+            shutdown_and_exit(mining_manager, core_registry, "SIGTERM signal").await;
+        });
+    }
+    #[cfg(windows)]
+    {
+        // 服务由服务控制管理器的停止/关机控制停机，也不能因为用户注销而退出
+        if service::is_running_as_service() {
+            return Ok(());
+        }
+        use tokio::signal::windows::{ctrl_break, ctrl_close, ctrl_logoff, ctrl_shutdown};
+        let mut ctrl_break = ctrl_break()?;
+        let mut ctrl_close = ctrl_close()?;
+        let mut ctrl_logoff = ctrl_logoff()?;
+        let mut ctrl_shutdown = ctrl_shutdown()?;
+        tokio::spawn(async move {
+            // 关闭控制台窗口时 Windows 只给几秒钟，停机超时过长时剩余步骤会被系统终止
+            let reason = tokio::select! {
+                _ = ctrl_break.recv() => "Ctrl-Break",
+                _ = ctrl_close.recv() => "console close",
+                _ = ctrl_logoff.recv() => "user logoff",
+                _ = ctrl_shutdown.recv() => "system shutdown",
+            };
+            shutdown_and_exit(mining_manager, core_registry, reason).await;
+        });
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (mining_manager, core_registry);
+        warn!("⚠️ Advanced signal handling not available on this platform");
+        info!("💡 Use Ctrl+C to stop the miner");
+    }
 
     Ok(())
 }
 
-async fn setup_signal_handlers(mining_manager: Arc<MiningManager>, core_registry: StaticCoreRegistry) -> anyhow::Result<()> {
-    let manager = mining_manager.clone();
-    tokio::spawn(async move {
-        #[cfg(unix)]
-        {
-            use tokio::signal::unix::{signal, SignalKind};
-            let mut sigterm = signal(SignalKind::terminate())
-                .expect("Failed to create SIGTERM handler");
-
-            tokio::select! {
-                _ = sigterm.recv() => {
-                    info!("🛑 Received SIGTERM signal - initiating graceful shutdown...");
-                    if let Err(e) = manager.stop().await {
-                        error!("❌ Error during mining shutdown: {}", e);
-                    } else {
-                        info!("✅ Mining operations stopped successfully");
-                    }
-
-                    // 关闭所有核心
-                    info!("🔧 Shutting down mining cores...");
-                    if let Err(e) = core_registry.shutdown().await {
-                        error!("❌ Error shutting down cores: {}", e);
-                    } else {
-                        info!("✅ Mining cores shutdown completed");
-                    }
-
-                    info!("👋 CGMiner-RS terminated gracefully");
-                    std::process::exit(0);
-                }
+/// 收到终止信号/控制台事件后有序停机并退出进程
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+async fn shutdown_and_exit(manager: Arc<MiningManager>, core_registry: StaticCoreRegistry, reason: &str) -> ! {
+    info!("🛑 Received {} - initiating graceful shutdown...", reason);
+    if let Err(e) = manager.stop().await {
+        error!("❌ Error during mining shutdown: {}", e);
+    } else {
+        info!("✅ Mining operations stopped successfully");
+    }
+
+    // 关闭所有核心
+    info!("🔧 Shutting down mining cores...");
+    if let Err(e) = core_registry.shutdown().await {
+        error!("❌ Error shutting down cores: {}", e);
+    } else {
+        info!("✅ Mining cores shutdown completed");
+    }
+
+    info!("👋 CGMiner-RS terminated gracefully");
+    std::process::exit(0);
+}
+
+/// 等待退出：终端界面模式下等用户退出界面，否则等 Ctrl+C
+#[cfg(all(feature = "dynamic-loading", unix))]
+async fn run_core_host(plugin: &str, socket: &str) -> ! {
+    // 子进程的 stderr 继承自主进程，插件日志直接写到那里
+    tracing_subscriber::fmt().with_writer(std::io::stderr).with_env_filter("info").init();
+    match core_loader::sandbox::run_host(std::path::Path::new(plugin), std::path::Path::new(socket)).await {
+        Ok(()) => std::process::exit(0),
+        Err(e) => {
+            eprintln!("❌ Core host for {} failed: {}", plugin, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(all(feature = "dynamic-loading", unix)))]
+async fn run_core_host(_plugin: &str, _socket: &str) -> ! {
+    eprintln!("❌ This build cannot host core plugins; rebuild with --features=dynamic-loading on a unix system");
+    std::process::exit(1);
+}
+
+#[cfg(feature = "tui")]
+async fn wait_for_shutdown(tui: bool, mining_manager: Arc<MiningManager>) -> std::io::Result<()> {
+    if tui {
+        tui::run(mining_manager).await
+    } else {
+        wait_for_stop_request().await
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+async fn wait_for_shutdown(_tui: bool, _mining_manager: Arc<MiningManager>) -> std::io::Result<()> {
+    wait_for_stop_request().await
+}
+
+/// Ctrl+C，或作为 Windows 服务运行时服务控制管理器的停止请求
+async fn wait_for_stop_request() -> std::io::Result<()> {
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result,
+        _ = service::stop_requested() => Ok(()),
+    }
+}
+
+/// 记录配置文件的基准摘要并备份，失败时只关闭检测，不影响启动
+async fn create_config_guard(path: &str, security: &SecurityConfig) -> Option<Arc<ConfigGuard>> {
+    if security.on_tamper == TamperPolicy::Ignore {
+        return None;
+    }
+    let files = match config::include::layered_files(std::path::Path::new(path)) {
+        Ok(files) => files,
+        Err(e) => {
+            warn!("⚠️ Config tamper detection disabled: {:#}", e);
+            return None;
+        }
+    };
+    let interval = Duration::from_secs(security.tamper_check_interval_secs);
+    match ConfigGuard::new(security.on_tamper, interval, &security.backup_dir, files).await {
+        Ok(guard) => Some(Arc::new(guard)),
+        Err(e) => {
+            warn!("⚠️ Config tamper detection disabled: {}", e);
+            None
+        }
+    }
+}
+
+/// 崩溃报告状态快照的采集间隔
+const CRASH_STATE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 定期采集核心/设备状态，panic 时无法再异步查询
+/// 备份口令：优先取环境变量，否则从标准输入读取
+fn read_backup_passphrase() -> Result<String, String> {
+    if let Ok(passphrase) = std::env::var(config::BACKUP_PASSPHRASE_ENV) {
+        if !passphrase.is_empty() {
+            return Ok(passphrase);
+        }
+    }
+    eprint!("Backup passphrase: ");
+    let mut passphrase = String::new();
+    std::io::stdin().read_line(&mut passphrase).map_err(|e| format!("Failed to read passphrase: {}", e))?;
+    let passphrase = passphrase.trim_end_matches(['\r', '\n']).to_string();
+    if passphrase.is_empty() {
+        return Err("Passphrase cannot be empty".to_string());
+    }
+    Ok(passphrase)
+}
+
+/// 记录本次启动的设备身份映射，与上次不同时告警
+async fn record_device_map(mining_manager: &MiningManager, path: &std::path::Path) {
+    let mut devices = Vec::new();
+    for info in mining_manager.get_all_device_info().await {
+        let Some(mapping) = mining_manager.get_device_mapping(info.id).await else {
+            continue;
+        };
+        devices.push(DeviceIdentity {
+            device_id: info.id,
+            name: info.name,
+            device_type: info.device_type,
+            chain_id: info.chain_id,
+            core_name: mapping.core_name,
+            device_index: mapping.device_index,
+        });
+    }
+
+    match backup::load_device_map(path) {
+        Ok(previous) if !previous.is_empty() => {
+            for change in backup::device_map_changes(&previous, &devices) {
+                warn!("⚠️ {}", change);
             }
         }
-        #[cfg(not(unix))]
-        {
-            // Windows 或其他平台的处理
-            warn!("⚠️ Advanced signal handling not available on this platform");
-            info!("💡 Use Ctrl+C to stop the miner");
+        Ok(_) => {}
+        Err(e) => warn!("⚠️ {}", e),
+    }
+    if let Err(e) = backup::save_device_map(path, &devices).await {
+        warn!("⚠️ Failed to save device map: {}", e);
+    }
+}
+
+fn spawn_crash_state_updater(mining_manager: Arc<MiningManager>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(CRASH_STATE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            logging::crash::set_state(mining_manager.crash_state().await);
         }
     });
-
-    Ok(())
 }
 
 /// 显示启动横幅
@@ -0,0 +1,137 @@
+//! 挖矿算法 (`pools.pools.algorithm`)
+//!
+//! cgminer-core 的 `Work`/`MiningResult` 按 SHA256d 计算目标和份额难度。矿池声明算法后，
+//! 工作的目标按该算法的难度 1 目标重新计算，`WorkItem` 带上算法标记，结果的份额难度也按同一算法换算，
+//! 这样 scrypt 设备 (LTC/DOGE 合并挖矿，如 Maijie L7) 可以共用同一套工作分发和结果处理。
+//! regtest 网络下难度 1 目标改为 regtest 的工作量证明上限，见 [`network`](super::network)。
+
+use super::network::Network;
+use serde::{Deserialize, Serialize};
+
+/// 挖矿算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    /// 比特币 (BTC/BCH)
+    #[default]
+    Sha256d,
+    /// 莱特币 (LTC/DOGE)
+    Scrypt,
+}
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::Sha256d => "sha256d",
+            Algorithm::Scrypt => "scrypt",
+        }
+    }
+
+    /// 难度 1 对应的目标值：SHA256d 为 0xffff << 208，scrypt 为 0xffff << 224，regtest 为 0x7fffff << 232
+    pub fn diff1_target_on(&self, network: Network) -> f64 {
+        if let Some(target) = network.diff1_target_override() {
+            return target;
+        }
+        let shift = match self {
+            Algorithm::Sha256d => 208,
+            Algorithm::Scrypt => 224,
+        };
+        65535.0 * 2f64.powi(shift)
+    }
+
+    /// 难度对应的 256 位目标，小端序 (与 `Work::target` 和哈希的字节序一致)
+    pub fn target_for_difficulty(&self, network: Network, difficulty: f64) -> [u8; 32] {
+        let mut target = [0xffu8; 32];
+        if !difficulty.is_finite() || difficulty <= 0.0 {
+            return target;
+        }
+        let mut remaining = self.diff1_target_on(network) / difficulty;
+        if remaining >= 2f64.powi(256) {
+            return target;
+        }
+        // 从最高的 64 位开始逐段取整
+        for limb in (0..4).rev() {
+            let scale = 2f64.powi(64 * limb as i32);
+            let value = (remaining / scale).floor().min(u64::MAX as f64);
+            remaining -= value * scale;
+            target[limb * 8..limb * 8 + 8].copy_from_slice(&(value as u64).to_le_bytes());
+        }
+        target
+    }
+
+    /// 哈希 (小端序) 对应的份额难度，长度不是 32 字节时返回 None
+    pub fn hash_difficulty(&self, network: Network, hash: &[u8]) -> Option<f64> {
+        if hash.len() != 32 {
+            return None;
+        }
+        let value = hash.iter().rev().fold(0.0f64, |acc, &byte| acc * 256.0 + byte as f64);
+        if value == 0.0 {
+            return Some(f64::INFINITY);
+        }
+        Some(self.diff1_target_on(network) / value)
+    }
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff1_targets() {
+        let sha = Algorithm::Sha256d.target_for_difficulty(Network::Mainnet, 1.0);
+        assert_eq!(&sha[26..28], &[0xff, 0xff]);
+        assert!(sha[..26].iter().chain(&sha[28..]).all(|&b| b == 0));
+
+        let scrypt = Algorithm::Scrypt.target_for_difficulty(Network::Mainnet, 1.0);
+        assert_eq!(&scrypt[28..30], &[0xff, 0xff]);
+        assert!(scrypt[..28].iter().chain(&scrypt[30..]).all(|&b| b == 0));
+
+        // 无效难度使用最宽松的目标
+        assert_eq!(Algorithm::Scrypt.target_for_difficulty(Network::Mainnet, 0.0), [0xff; 32]);
+    }
+
+    #[test]
+    fn test_difficulty_round_trip() {
+        for algorithm in [Algorithm::Sha256d, Algorithm::Scrypt] {
+            for difficulty in [1.0, 1024.0, 65536.0 * 3.5] {
+                let target = algorithm.target_for_difficulty(Network::Mainnet, difficulty);
+                let back = algorithm.hash_difficulty(Network::Mainnet, &target).unwrap();
+                assert!((back - difficulty).abs() / difficulty < 1e-9, "{} {} -> {}", algorithm, difficulty, back);
+            }
+        }
+
+        // 同一哈希在 scrypt 下的难度是 SHA256d 的 65536 倍
+        let hash = Algorithm::Sha256d.target_for_difficulty(Network::Mainnet, 2.0);
+        let scrypt = Algorithm::Scrypt.hash_difficulty(Network::Mainnet, &hash).unwrap();
+        assert!((scrypt - 2.0 * 65536.0).abs() < 1e-6);
+        assert_eq!(Algorithm::Scrypt.hash_difficulty(Network::Mainnet, &[0u8; 8]), None);
+    }
+
+    #[test]
+    fn test_network_diff1() {
+        let mainnet = Algorithm::Sha256d.diff1_target_on(Network::Mainnet);
+        assert_eq!(Algorithm::Sha256d.diff1_target_on(Network::Testnet), mainnet);
+        // regtest 区块 (nBits 0x207fffff) 的难度是 1，而不是主网换算下的约 4.7e-10
+        let regtest = Algorithm::Sha256d.diff1_target_on(Network::Regtest);
+        assert!((regtest / mainnet - 8_388_607.0 * 2f64.powi(24) / 65535.0).abs() < 1.0);
+        assert_eq!(Algorithm::Scrypt.diff1_target_on(Network::Regtest), regtest);
+
+        // 同一个目标在不同网络下对应不同的难度
+        let target = Algorithm::Sha256d.target_for_difficulty(Network::Regtest, 1.0);
+        assert!((Algorithm::Sha256d.hash_difficulty(Network::Regtest, &target).unwrap() - 1.0).abs() < 1e-9);
+        assert!(Algorithm::Sha256d.hash_difficulty(Network::Mainnet, &target).unwrap() < 1e-9);
+    }
+
+    #[test]
+    fn test_serde_names() {
+        let parsed: Algorithm = serde_json::from_str("\"scrypt\"").unwrap();
+        assert_eq!(parsed, Algorithm::Scrypt);
+        assert_eq!(serde_json::to_string(&Algorithm::Sha256d).unwrap(), "\"sha256d\"");
+    }
+}
@@ -0,0 +1,203 @@
+//! 核心生命周期事件 (创建/启动/停止/移除/错误)
+//!
+//! 挖矿管理器通过 `CoreLifecycle` 调用 `CoreRegistry` 的生命周期方法，每次调用的结果作为
+//! `CoreLifecycleEvent` 广播。转发任务把事件写入日志、作为 `MiningEvent::CoreLifecycle` 推给 WebSocket
+//! 订阅者，并在核心创建或启动失败时告警。
+
+use crate::mining::MiningEvent;
+use crate::monitoring::alerts::{Alert, AlertSeverity, AlertType};
+use crate::monitoring::MonitoringSystem;
+use cgminer_core::{CoreConfig, CoreError, CoreRegistry};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::sync::broadcast;
+use tracing::{error, info};
+
+/// 生命周期阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoreLifecycleStage {
+    Created,
+    Started,
+    Stopped,
+    Removed,
+    /// 创建或启动失败
+    Error,
+}
+
+/// 核心生命周期事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoreLifecycleEvent {
+    /// 核心 ID；创建失败时为核心类型
+    pub core_id: String,
+    /// 注册表中的核心类型 (如 `gpu-btc`)
+    pub core_type: String,
+    pub stage: CoreLifecycleStage,
+    pub error: Option<String>,
+    pub timestamp: SystemTime,
+}
+
+/// 发布生命周期事件的 `CoreRegistry` 包装
+#[derive(Clone)]
+pub struct CoreLifecycle {
+    registry: Arc<CoreRegistry>,
+    events: broadcast::Sender<CoreLifecycleEvent>,
+    /// 核心 ID -> 核心类型
+    core_types: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl CoreLifecycle {
+    pub fn new(registry: Arc<CoreRegistry>) -> Self {
+        let (events, _) = broadcast::channel(256);
+        Self { registry, events, core_types: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CoreLifecycleEvent> {
+        self.events.subscribe()
+    }
+
+    pub async fn create_core(&self, core_type: &str, config: CoreConfig) -> Result<String, CoreError> {
+        match self.registry.create_core(core_type, config).await {
+            Ok(core_id) => {
+                self.core_types().insert(core_id.clone(), core_type.to_string());
+                self.publish(&core_id, core_type, CoreLifecycleStage::Created, None);
+                Ok(core_id)
+            }
+            Err(e) => {
+                self.publish(core_type, core_type, CoreLifecycleStage::Error, Some(format!("create failed: {}", e)));
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn start_core(&self, core_id: &str) -> Result<(), CoreError> {
+        let result = self.registry.start_core(core_id).await;
+        let core_type = self.core_type(core_id);
+        match &result {
+            Ok(()) => self.publish(core_id, &core_type, CoreLifecycleStage::Started, None),
+            Err(e) => self.publish(core_id, &core_type, CoreLifecycleStage::Error, Some(format!("start failed: {}", e))),
+        }
+        result
+    }
+
+    /// 停止核心；未启动的核心停止失败是正常的，不发布错误事件
+    pub async fn stop_core(&self, core_id: &str) -> Result<(), CoreError> {
+        self.registry.stop_core(core_id).await?;
+        self.publish(core_id, &self.core_type(core_id), CoreLifecycleStage::Stopped, None);
+        Ok(())
+    }
+
+    pub async fn remove_core(&self, core_id: &str) -> Result<(), CoreError> {
+        self.registry.remove_core(core_id).await?;
+        let core_type = self.core_types().remove(core_id).unwrap_or_default();
+        self.publish(core_id, &core_type, CoreLifecycleStage::Removed, None);
+        Ok(())
+    }
+
+    /// 用新配置重建核心：先创建新核心，原核心在运行时启动新核心，成功后停止并卸载原核心
+    ///
+    /// 核心没有就地更新配置的接口，参数变化只能换一个核心实例。新核心创建或启动失败时原核心保持不变。
+    pub async fn recreate_core(&self, core_id: &str, config: CoreConfig) -> Result<String, CoreError> {
+        let core_type = self.core_type(core_id);
+        let was_active = self.registry.list_active_cores().await
+            .map(|active| active.iter().any(|id| id == core_id))
+            .unwrap_or(false);
+
+        let new_core_id = self.create_core(&core_type, config).await?;
+        if was_active {
+            if let Err(e) = self.start_core(&new_core_id).await {
+                let _ = self.remove_core(&new_core_id).await;
+                return Err(e);
+            }
+            let _ = self.stop_core(core_id).await;
+        }
+        if let Err(e) = self.remove_core(core_id).await {
+            error!("Failed to remove core {} after recreating it as {}: {}", core_id, new_core_id, e);
+        }
+        Ok(new_core_id)
+    }
+
+    fn core_types(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.core_types.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn core_type(&self, core_id: &str) -> String {
+        self.core_types().get(core_id).cloned().unwrap_or_default()
+    }
+
+    fn publish(&self, core_id: &str, core_type: &str, stage: CoreLifecycleStage, error: Option<String>) {
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.events.send(CoreLifecycleEvent {
+            core_id: core_id.to_string(),
+            core_type: core_type.to_string(),
+            stage,
+            error,
+            timestamp: SystemTime::now(),
+        });
+    }
+}
+
+/// 记录日志、转发给挖矿事件订阅者并对错误告警，`CoreLifecycle` 全部释放后结束
+pub fn spawn_forwarder(
+    mut events: broadcast::Receiver<CoreLifecycleEvent>,
+    monitoring: Arc<MonitoringSystem>,
+    mining_events: broadcast::Sender<MiningEvent>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if event.stage == CoreLifecycleStage::Error {
+                let error = event.error.as_deref().unwrap_or("unknown error");
+                error!("❌ Core {} ({}) error: {}", event.core_id, event.core_type, error);
+                let alert = Alert::new(
+                    AlertType::Mining,
+                    AlertSeverity::Warning,
+                    "Core Error".to_string(),
+                    format!("Core {} ({}): {}", event.core_id, event.core_type, error),
+                    "mining".to_string(),
+                )
+                .with_label("core".to_string(), event.core_id.clone());
+                monitoring.raise_alert(alert).await;
+            } else {
+                info!("🧩 Core {} ({}) {:?}", event.core_id, event.core_type, event.stage);
+            }
+
+            let _ = mining_events.send(MiningEvent::CoreLifecycle {
+                core_id: event.core_id,
+                core_type: event.core_type,
+                stage: event.stage,
+                error: event.error,
+                timestamp: event.timestamp,
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_failure_is_published() {
+        let lifecycle = CoreLifecycle::new(Arc::new(CoreRegistry::new()));
+        let mut events = lifecycle.subscribe();
+        let config = CoreConfig {
+            name: "missing".to_string(),
+            enabled: true,
+            devices: vec![],
+            custom_params: HashMap::new(),
+        };
+
+        assert!(lifecycle.create_core("missing-core", config).await.is_err());
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.stage, CoreLifecycleStage::Error);
+        assert_eq!(event.core_id, "missing-core");
+        assert!(event.error.unwrap().starts_with("create failed"));
+    }
+}
@@ -0,0 +1,80 @@
+//! 核心结果
+//!
+//! 主程序按 `result_poll` 的自适应间隔调用 `CoreRegistry::collect_results_from_core` 轮询核心的结果，
+//! 每个结果在这里换算份额难度、经份额验证器检查并按份额目标预过滤，通过的结果交给提交流水线。
+
+use crate::mining::{Algorithm, Network, ShareTargetFilter, StatsCounters};
+use crate::pool::share_verify::ShareCheck;
+use crate::pool::PoolManager;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// 记录一个核心结果：按工作的算法计算份额难度，经矿池管理器的份额验证器 (`[share_verify]`)
+/// 重新计算哈希，预过滤低于份额目标的结果，统计有效份额
+///
+/// 返回有效份额 (已计算份额难度)，被过滤、验证失败或不满足目标时返回 None。
+/// `pool_manager` 为 None 时不验证 (压力测试的合成结果)
+pub async fn record_core_result(
+    core_id: &str,
+    core_result: cgminer_core::MiningResult,
+    stats: &StatsCounters,
+    share_filter: &ShareTargetFilter,
+    network: Network,
+    pool_manager: Option<&Mutex<PoolManager>>,
+) -> Option<cgminer_core::types::MiningResult> {
+    // 非 SHA256d 工作和 regtest 的份额难度按各自的难度 1 目标换算
+    let algorithm = share_filter.algorithm_for(&core_result.work_id).await;
+    let algorithm_difficulty = match (algorithm, network.diff1_target_override()) {
+        (Algorithm::Sha256d, None) => None,
+        _ => algorithm.hash_difficulty(network, &core_result.hash),
+    };
+
+    // 转换核心结果到本地格式（work_id已经是UUID）
+    let mut mining_result = cgminer_core::types::MiningResult::new(
+        core_result.work_id,
+        core_result.device_id,
+        core_result.nonce,
+        core_result.hash,
+        core_result.meets_target,
+    );
+
+    // 设置extranonce2
+    if core_result.extranonce2.len() >= 4 {
+        mining_result = mining_result.with_extranonce2(core_result.extranonce2);
+    }
+
+    // 计算份额难度
+    if let Some(difficulty) = algorithm_difficulty {
+        mining_result.share_difficulty = difficulty;
+    } else if let Err(e) = mining_result.calculate_share_difficulty() {
+        warn!("Failed to calculate share difficulty: {}", e);
+    }
+
+    // 本地重新计算的哈希不满足份额目标：和应用层设备的结果一样计为硬件错误，不计入有效份额
+    if let (true, Some(pool_manager)) = (core_result.meets_target, pool_manager) {
+        let checks = pool_manager.lock().await.verify_mining_results(std::slice::from_ref(&mining_result)).await;
+        if let Some(ShareCheck::Invalid { difficulty }) = checks.first() {
+            warn!(
+                "Core {} device {} hardware error: nonce {:08x} hashes to difficulty {:.4}, below the share target",
+                core_id, mining_result.device_id, mining_result.nonce, difficulty
+            );
+            stats.record_hardware_error();
+            return None;
+        }
+    }
+
+    // 验证前预过滤低于份额目标的结果
+    if !share_filter.passes(&mining_result.work_id, mining_result.share_difficulty).await {
+        stats.record_below_target();
+        return None;
+    }
+
+    // 注意：大部分哈希结果都不会满足目标难度，这是正常的，只有极少数结果会成为有效份额
+    if core_result.meets_target {
+        info!("Valid share found from core {}, device {}", core_id, core_result.device_id);
+        stats.record_accepted_share(mining_result.share_difficulty);
+        Some(mining_result)
+    } else {
+        None
+    }
+}
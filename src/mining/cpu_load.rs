@@ -0,0 +1,165 @@
+//! CPU 负载目标 (`[cores.cpu_btc.load_target]`) - 只用空闲的 CPU 挖矿
+//!
+//! 定期从 `/proc/stat` 读取整机 CPU 使用率，从 `/proc/self/stat` 读取本进程的使用率，
+//! 两者之差是其他进程的负载。挖矿线程数按 `目标负载 - 其他进程负载` 计算，
+//! 通过核心参数 `active_threads` 暂停或恢复 CPU 核心的工作线程，工作站上有其他任务时让出 CPU。
+
+use serde::{Deserialize, Serialize};
+
+/// CPU 负载目标配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuLoadTargetConfig {
+    pub enabled: bool,
+    /// 整机 CPU 使用率目标 (%)
+    pub target_percent: f64,
+    /// 采样间隔 (秒)
+    pub interval_secs: u64,
+    /// 至少保持运行的线程数
+    pub min_threads: u32,
+}
+
+impl Default for CpuLoadTargetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_percent: 80.0,
+            interval_secs: 5,
+            min_threads: 0,
+        }
+    }
+}
+
+impl CpuLoadTargetConfig {
+    /// 验证负载目标配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.target_percent <= 0.0 || self.target_percent > 100.0 {
+            anyhow::bail!("CPU load target_percent must be within (0, 100]");
+        }
+        if self.interval_secs == 0 {
+            anyhow::bail!("CPU load interval_secs must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+/// `/proc/stat` 第一行的累计时间 (jiffies)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CpuTimes {
+    pub busy: u64,
+    pub total: u64,
+}
+
+/// 解析 `/proc/stat` 的 `cpu` 汇总行，idle 和 iowait 计为空闲
+pub fn parse_proc_stat(content: &str) -> Option<CpuTimes> {
+    let line = content.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line.split_whitespace().skip(1).filter_map(|field| field.parse().ok()).collect();
+    if fields.len() < 4 {
+        return None;
+    }
+    // guest 时间已计入 user/nice，只取前 8 列
+    let total: u64 = fields.iter().take(8).sum();
+    let idle = fields[3] + fields.get(4).copied().unwrap_or(0);
+    Some(CpuTimes { busy: total - idle, total })
+}
+
+/// 解析 `/proc/self/stat` 中本进程的 utime + stime (jiffies)
+pub fn parse_process_ticks(content: &str) -> Option<u64> {
+    // 进程名可能包含空格，从最后一个 ')' 之后开始数字段；之后第 12、13 个字段为 utime、stime
+    let rest = &content[content.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// 其他进程负载为 `other_percent` 时应运行的挖矿线程数
+///
+/// 每个线程最多占用整机 `100 / logical_cpus` % 的 CPU。
+pub fn plan_active_threads(other_percent: f64, config: &CpuLoadTargetConfig, device_count: u32, logical_cpus: u32) -> u32 {
+    let per_thread = 100.0 / logical_cpus.max(1) as f64;
+    let budget = (config.target_percent - other_percent).max(0.0);
+    let threads = (budget / per_thread).floor() as u32;
+    threads.clamp(config.min_threads.min(device_count), device_count)
+}
+
+/// 两次采样之间的负载
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadSample {
+    /// 整机 CPU 使用率 (%)
+    pub system_percent: f64,
+    /// 其他进程的 CPU 使用率 (%)
+    pub other_percent: f64,
+}
+
+/// 负载采样器，保存上一次的累计值
+#[derive(Debug, Default)]
+pub struct LoadSampler {
+    last: Option<(CpuTimes, u64)>,
+}
+
+impl LoadSampler {
+    /// 由新的累计值计算负载，第一次调用只记录基准
+    pub fn update(&mut self, times: CpuTimes, process_ticks: u64) -> Option<LoadSample> {
+        let previous = self.last.replace((times, process_ticks));
+        let (last_times, last_ticks) = previous?;
+        let total = times.total.saturating_sub(last_times.total);
+        if total == 0 {
+            return None;
+        }
+        let busy = times.busy.saturating_sub(last_times.busy);
+        let own = process_ticks.saturating_sub(last_ticks).min(busy);
+        Some(LoadSample {
+            system_percent: busy as f64 / total as f64 * 100.0,
+            other_percent: (busy - own) as f64 / total as f64 * 100.0,
+        })
+    }
+
+    /// 读取 `/proc` 采样，不可读 (非 Linux) 时返回 None
+    pub fn sample(&mut self) -> Option<LoadSample> {
+        let times = parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?)?;
+        let ticks = parse_process_ticks(&std::fs::read_to_string("/proc/self/stat").ok()?)?;
+        self.update(times, ticks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let content = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_proc_stat(content), Some(CpuTimes { busy: 150, total: 1000 }));
+        assert_eq!(parse_proc_stat("intr 1 2 3"), None);
+    }
+
+    #[test]
+    fn test_parse_process_ticks() {
+        let content = "1234 (cgminer rs) S 1 1234 1234 0 -1 4194560 100 0 0 0 250 30 0 0 20 0 8 0 100";
+        assert_eq!(parse_process_ticks(content), Some(280));
+    }
+
+    #[test]
+    fn test_sampler_separates_own_load() {
+        let mut sampler = LoadSampler::default();
+        assert_eq!(sampler.update(CpuTimes { busy: 0, total: 0 }, 0), None);
+        let sample = sampler.update(CpuTimes { busy: 600, total: 1000 }, 400).unwrap();
+        assert_eq!(sample.system_percent, 60.0);
+        assert_eq!(sample.other_percent, 20.0);
+    }
+
+    #[test]
+    fn test_plan_active_threads() {
+        let config = CpuLoadTargetConfig { enabled: true, target_percent: 75.0, ..Default::default() };
+        // 8 个逻辑 CPU，每个线程 12.5%
+        assert_eq!(plan_active_threads(0.0, &config, 8, 8), 6);
+        assert_eq!(plan_active_threads(50.0, &config, 8, 8), 2);
+        assert_eq!(plan_active_threads(90.0, &config, 8, 8), 0);
+        let config = CpuLoadTargetConfig { min_threads: 1, ..config };
+        assert_eq!(plan_active_threads(90.0, &config, 8, 8), 1);
+    }
+}
@@ -0,0 +1,196 @@
+//! CPU 温度降频 (`[cores.cpu_btc.thermal_throttle]`)
+//!
+//! 定期从 hwmon 读取 CPU 封装温度 (coretemp/k10temp)。温度达到上限时按步长降低占空比，
+//! 降到 `上限 - 回滞值` 以下后逐步恢复，两者之间保持不变，避免在上限附近反复切换。
+//! 占空比通过核心参数 `duty_percent` 下发，CPU 核心按 `throttle_action` 缩小批次或在批次间插入休眠；
+//! 降频状态写入各 CPU 设备的 `DeviceStats::throttle`。
+
+use crate::device::ThrottleState;
+use serde::{Deserialize, Serialize};
+
+/// CPU 核心的降频方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleAction {
+    /// 在批次之间插入休眠
+    #[default]
+    Sleep,
+    /// 缩小每批计算的 nonce 数
+    BatchSize,
+}
+
+impl ThrottleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleAction::Sleep => "sleep",
+            ThrottleAction::BatchSize => "batch_size",
+        }
+    }
+}
+
+/// CPU 温度降频配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuThermalThrottleConfig {
+    pub enabled: bool,
+    /// 封装温度上限 (°C)
+    pub temperature_limit: f32,
+    /// 恢复回滞 (°C)
+    pub hysteresis: f32,
+    pub action: ThrottleAction,
+    /// 每次调整的占空比步长 (%)
+    pub step_percent: u8,
+    /// 最低占空比 (%)
+    pub min_duty_percent: u8,
+    /// 采样间隔 (秒)
+    pub interval_secs: u64,
+}
+
+impl Default for CpuThermalThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            temperature_limit: 85.0,
+            hysteresis: 5.0,
+            action: ThrottleAction::Sleep,
+            step_percent: 25,
+            min_duty_percent: 25,
+            interval_secs: 2,
+        }
+    }
+}
+
+impl CpuThermalThrottleConfig {
+    /// 验证降频配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.temperature_limit <= 0.0 {
+            anyhow::bail!("CPU thermal throttle temperature_limit must be greater than 0");
+        }
+        if self.hysteresis < 0.0 || self.hysteresis >= self.temperature_limit {
+            anyhow::bail!("CPU thermal throttle hysteresis must be within [0, temperature_limit)");
+        }
+        if self.step_percent == 0 || self.step_percent > 100 {
+            anyhow::bail!("CPU thermal throttle step_percent must be within [1, 100]");
+        }
+        if self.min_duty_percent == 0 || self.min_duty_percent > 100 {
+            anyhow::bail!("CPU thermal throttle min_duty_percent must be within [1, 100]");
+        }
+        if self.interval_secs == 0 {
+            anyhow::bail!("CPU thermal throttle interval_secs must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+/// 带回滞的降频状态机
+#[derive(Debug)]
+pub struct ThermalThrottle {
+    config: CpuThermalThrottleConfig,
+    duty_percent: u8,
+}
+
+impl ThermalThrottle {
+    pub fn new(config: CpuThermalThrottleConfig) -> Self {
+        Self { config, duty_percent: 100 }
+    }
+
+    pub fn duty_percent(&self) -> u8 {
+        self.duty_percent
+    }
+
+    /// 按新的温度读数调整占空比，返回调整后的状态
+    pub fn update(&mut self, package_celsius: f32) -> ThrottleState {
+        let step = self.config.step_percent;
+        if package_celsius >= self.config.temperature_limit {
+            self.duty_percent = self.duty_percent.saturating_sub(step).max(self.config.min_duty_percent);
+        } else if package_celsius <= self.config.temperature_limit - self.config.hysteresis {
+            self.duty_percent = self.duty_percent.saturating_add(step).min(100);
+        }
+        ThrottleState {
+            throttled: self.duty_percent < 100,
+            duty_percent: self.duty_percent,
+            package_celsius,
+            action: self.config.action.as_str().to_string(),
+        }
+    }
+}
+
+/// hwmon 中提供 CPU 封装温度的驱动
+const CPU_HWMON_DRIVERS: [&str; 4] = ["coretemp", "k10temp", "zenpower", "cpu_thermal"];
+
+/// 解析 hwmon `temp*_input` 的千分之一摄氏度读数
+pub fn parse_millidegrees(content: &str) -> Option<f32> {
+    content.trim().parse::<i64>().ok().map(|millis| millis as f32 / 1000.0)
+}
+
+/// 读取 CPU 封装温度 (多路时取最高值)，没有 CPU hwmon 时返回 None
+pub fn read_package_temperature() -> Option<f32> {
+    std::fs::read_dir("/sys/class/hwmon")
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            std::fs::read_to_string(entry.path().join("name"))
+                .is_ok_and(|name| CPU_HWMON_DRIVERS.contains(&name.trim()))
+        })
+        // coretemp 的 temp1 为 Package id，k10temp 的 temp1 为 Tctl
+        .filter_map(|entry| parse_millidegrees(&std::fs::read_to_string(entry.path().join("temp1_input")).ok()?))
+        .reduce(f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle() -> ThermalThrottle {
+        ThermalThrottle::new(CpuThermalThrottleConfig {
+            enabled: true,
+            temperature_limit: 85.0,
+            hysteresis: 5.0,
+            step_percent: 25,
+            min_duty_percent: 25,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_throttle_steps_down_to_minimum() {
+        let mut throttle = throttle();
+        assert!(!throttle.update(70.0).throttled);
+        assert_eq!(throttle.update(86.0).duty_percent, 75);
+        assert_eq!(throttle.update(88.0).duty_percent, 50);
+        assert_eq!(throttle.update(90.0).duty_percent, 25);
+        assert_eq!(throttle.update(92.0).duty_percent, 25);
+    }
+
+    #[test]
+    fn test_hysteresis_holds_between_thresholds() {
+        let mut throttle = throttle();
+        throttle.update(86.0);
+        throttle.update(86.0);
+        // 80°C 到 85°C 之间保持
+        let state = throttle.update(82.0);
+        assert_eq!((state.duty_percent, state.throttled), (50, true));
+        assert_eq!(throttle.update(80.0).duty_percent, 75);
+        let state = throttle.update(79.0);
+        assert_eq!((state.duty_percent, state.throttled), (100, false));
+        assert_eq!(state.action, "sleep");
+    }
+
+    #[test]
+    fn test_parse_millidegrees() {
+        assert_eq!(parse_millidegrees("54250\n"), Some(54.25));
+        assert_eq!(parse_millidegrees("n/a"), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(CpuThermalThrottleConfig::default().validate().is_ok());
+        let config = CpuThermalThrottleConfig { enabled: true, hysteresis: 90.0, ..Default::default() };
+        assert!(config.validate().is_err());
+        let config = CpuThermalThrottleConfig { enabled: true, min_duty_percent: 0, ..Default::default() };
+        assert!(config.validate().is_err());
+    }
+}
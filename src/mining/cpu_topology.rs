@@ -0,0 +1,267 @@
+//! CPU 拓扑检测和 CPU 核心的设备数量 (`cores.cpu_btc.device_count = "auto"`)
+//!
+//! 每个 CPU 设备占用一个挖矿线程。自动模式按物理核心数确定线程数，在容器中不超过
+//! cgroup 的 CPU 配额，并为应用自身 (矿池连接、API、监控) 保留 `reserved_cores` 个核心。
+//!
+//! 多路服务器上 `cpu_affinity.strategy = "numa"` 把设备按块分配到各 NUMA 节点，
+//! 每个设备的线程只在本节点的 CPU 上运行，缓冲区也在本节点分配，避免跨节点访存。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::OnceLock;
+
+/// CPU 设备数量：固定值或 `"auto"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeviceCount {
+    #[default]
+    Auto,
+    Fixed(u32),
+}
+
+impl Serialize for DeviceCount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            DeviceCount::Auto => serializer.serialize_str("auto"),
+            DeviceCount::Fixed(count) => serializer.serialize_u32(*count),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceCount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Count(u32),
+            Keyword(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Count(count) => Ok(DeviceCount::Fixed(count)),
+            Raw::Keyword(keyword) if keyword.eq_ignore_ascii_case("auto") => Ok(DeviceCount::Auto),
+            Raw::Keyword(keyword) => Err(serde::de::Error::custom(format!(
+                "invalid device_count {:?}, expected a number or \"auto\"", keyword
+            ))),
+        }
+    }
+}
+
+impl DeviceCount {
+    /// 实际的设备数量，自动模式按本机拓扑计算
+    pub fn resolve(&self, reserved_cores: u32) -> u32 {
+        match self {
+            DeviceCount::Fixed(count) => *count,
+            DeviceCount::Auto => ThreadSizing::plan(CpuTopology::detect(), reserved_cores).device_count,
+        }
+    }
+}
+
+/// 本机 CPU 拓扑
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CpuTopology {
+    pub logical_cpus: u32,
+    pub physical_cores: u32,
+    /// cgroup CPU 配额 (可用 CPU 数)，未限制时为 `None`
+    pub cgroup_quota: Option<f64>,
+}
+
+impl CpuTopology {
+    /// 检测本机拓扑，结果在进程内缓存
+    pub fn detect() -> &'static CpuTopology {
+        static TOPOLOGY: OnceLock<CpuTopology> = OnceLock::new();
+        TOPOLOGY.get_or_init(|| CpuTopology {
+            logical_cpus: num_cpus::get() as u32,
+            physical_cores: num_cpus::get_physical() as u32,
+            cgroup_quota: read_cgroup_quota(),
+        })
+    }
+}
+
+/// 读取 cgroup CPU 配额：先 cgroup v2 的 cpu.max，再 cgroup v1 的 cfs 配额
+fn read_cgroup_quota() -> Option<f64> {
+    if let Ok(content) = std::fs::read_to_string("/sys/fs/cgroup/cpu.max") {
+        return parse_cpu_max(&content);
+    }
+    let quota = std::fs::read_to_string("/sys/fs/cgroup/cpu/cpu.cfs_quota_us").ok()?;
+    let period = std::fs::read_to_string("/sys/fs/cgroup/cpu/cpu.cfs_period_us").ok()?;
+    parse_cfs_quota(&quota, &period)
+}
+
+/// 解析 cgroup v2 的 `cpu.max`：`"<quota> <period>"`，quota 为 `max` 表示不限制
+pub fn parse_cpu_max(content: &str) -> Option<f64> {
+    let mut fields = content.split_whitespace();
+    let quota = fields.next()?;
+    let period = fields.next().unwrap_or("100000");
+    parse_cfs_quota(quota, period)
+}
+
+/// 解析 cgroup v1 的 cfs 配额和周期，配额为 -1 表示不限制
+pub fn parse_cfs_quota(quota: &str, period: &str) -> Option<f64> {
+    let quota: f64 = quota.trim().parse().ok()?;
+    let period: f64 = period.trim().parse().ok()?;
+    if quota <= 0.0 || period <= 0.0 {
+        return None;
+    }
+    Some(quota / period)
+}
+
+/// NUMA 节点及其 CPU
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NumaNode {
+    pub id: u32,
+    pub cpus: Vec<usize>,
+}
+
+/// 解析 sysfs 的 CPU 列表格式，如 `"0-3,8,10-11"`
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => cpus.extend(part.parse::<usize>().ok()),
+        }
+    }
+    cpus
+}
+
+/// 读取 `/sys/devices/system/node` 下的 NUMA 节点，不可读时视为一个包含全部 CPU 的节点
+pub fn numa_nodes() -> Vec<NumaNode> {
+    let mut nodes: Vec<NumaNode> = std::fs::read_dir("/sys/devices/system/node")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.strip_prefix("node")?.parse().ok()?;
+            let cpus = parse_cpu_list(&std::fs::read_to_string(entry.path().join("cpulist")).ok()?);
+            (!cpus.is_empty()).then_some(NumaNode { id, cpus })
+        })
+        .collect();
+    if nodes.is_empty() {
+        nodes.push(NumaNode { id: 0, cpus: (0..num_cpus::get()).collect() });
+    }
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+/// 一个 CPU 设备的放置：所在 NUMA 节点和允许运行的 CPU
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DevicePlacement {
+    /// 核心内的设备序号
+    pub device: u32,
+    pub numa_node: u32,
+    pub cpus: Vec<usize>,
+}
+
+/// 把设备按连续的块平均分到各节点：节点 0 得到前几个设备，节点 1 得到接下来的，依此类推
+pub fn plan_numa_placement(nodes: &[NumaNode], device_count: u32) -> Vec<DevicePlacement> {
+    if nodes.is_empty() {
+        return Vec::new();
+    }
+    (0..device_count)
+        .map(|device| {
+            let node = &nodes[(device as usize * nodes.len()) / device_count as usize];
+            DevicePlacement { device, numa_node: node.id, cpus: node.cpus.clone() }
+        })
+        .collect()
+}
+
+/// 自动确定的 CPU 设备数量及依据
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThreadSizing {
+    pub device_count: u32,
+    pub topology: CpuTopology,
+    /// 可用于挖矿和应用的 CPU 数 (物理核心数和 cgroup 配额中较小者)
+    pub usable_cpus: u32,
+    pub reserved_cores: u32,
+}
+
+impl ThreadSizing {
+    /// 设备数 = min(物理核心数, cgroup 配额向下取整) - 保留核心数，至少为 1
+    pub fn plan(topology: &CpuTopology, reserved_cores: u32) -> Self {
+        let physical = topology.physical_cores.max(1);
+        let usable_cpus = match topology.cgroup_quota {
+            Some(quota) => physical.min((quota.floor() as u32).max(1)),
+            None => physical,
+        };
+        Self {
+            device_count: usable_cpus.saturating_sub(reserved_cores).max(1),
+            topology: *topology,
+            usable_cpus,
+            reserved_cores,
+        }
+    }
+}
+
+impl std::fmt::Display for ThreadSizing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} devices ({} physical cores, {} logical CPUs",
+               self.device_count, self.topology.physical_cores, self.topology.logical_cpus)?;
+        if let Some(quota) = self.topology.cgroup_quota {
+            write!(f, ", cgroup quota {:.1} CPUs", quota)?;
+        }
+        write!(f, ", {} reserved)", self.reserved_cores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topology(physical_cores: u32, cgroup_quota: Option<f64>) -> CpuTopology {
+        CpuTopology { logical_cpus: physical_cores * 2, physical_cores, cgroup_quota }
+    }
+
+    #[test]
+    fn test_parse_cgroup_quota() {
+        assert_eq!(parse_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cpu_max("250000 100000\n"), Some(2.5));
+        assert_eq!(parse_cfs_quota("-1\n", "100000\n"), None);
+        assert_eq!(parse_cfs_quota("400000\n", "100000\n"), Some(4.0));
+    }
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(ThreadSizing::plan(&topology(8, None), 1).device_count, 7);
+        // 容器配额 2.5 CPU：可用 2 个，保留 1 个
+        let sizing = ThreadSizing::plan(&topology(16, Some(2.5)), 1);
+        assert_eq!((sizing.usable_cpus, sizing.device_count), (2, 1));
+        // 至少保留一个挖矿线程
+        assert_eq!(ThreadSizing::plan(&topology(2, None), 4).device_count, 1);
+    }
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_numa_placement() {
+        let nodes = vec![
+            NumaNode { id: 0, cpus: vec![0, 1, 2, 3] },
+            NumaNode { id: 1, cpus: vec![4, 5, 6, 7] },
+        ];
+        let placement = plan_numa_placement(&nodes, 5);
+        let by_node: Vec<u32> = placement.iter().map(|p| p.numa_node).collect();
+        assert_eq!(by_node, vec![0, 0, 0, 1, 1]);
+        assert_eq!(placement[4].cpus, vec![4, 5, 6, 7]);
+        assert!(plan_numa_placement(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_device_count_serde() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            device_count: DeviceCount,
+        }
+        let parse = |s: &str| toml::from_str::<Wrapper>(s).map(|w| w.device_count);
+        assert_eq!(parse("device_count = 4").unwrap(), DeviceCount::Fixed(4));
+        assert_eq!(parse("device_count = \"auto\"").unwrap(), DeviceCount::Auto);
+        assert!(parse("device_count = \"many\"").is_err());
+        assert_eq!(serde_json::to_string(&DeviceCount::Auto).unwrap(), "\"auto\"");
+        assert_eq!(DeviceCount::Fixed(3).resolve(1), 3);
+    }
+}
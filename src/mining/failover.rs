@@ -0,0 +1,187 @@
+//! 核心选择优先级 (`cores.priority`)、健康检查和自动故障转移 (`[cores.failover]`)
+//!
+//! 核心按创建时记录的类型信息 (`CoreSpec`) 排序，不再从核心 ID 猜测类型。定期通过
+//! `MiningCore::get_stats` 检查选中的核心，连续几次取不到统计或算力为 0 (如 ASIC 核心的链全部掉线)
+//! 时，按优先级切换到启动时未被选中的下一个核心 (GPU/CPU) 并告警。
+
+use cgminer_core::{CoreConfig, CoreType};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 故障转移配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoreFailoverConfig {
+    pub enabled: bool,
+    /// 健康检查间隔 (秒)
+    pub check_interval_secs: u64,
+    /// 连续多少次检查不健康后切换
+    pub unhealthy_checks: u32,
+    /// 核心启动后多久内不判定为不健康 (秒)，留给 ASIC 链初始化
+    pub startup_grace_secs: u64,
+}
+
+impl Default for CoreFailoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            check_interval_secs: 30,
+            unhealthy_checks: 3,
+            startup_grace_secs: 120,
+        }
+    }
+}
+
+impl CoreFailoverConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.check_interval_secs == 0 {
+            errors.push(("cores.failover.check_interval_secs", "cores.failover.check_interval_secs must be greater than 0".to_string()));
+        }
+        if self.unhealthy_checks == 0 {
+            errors.push(("cores.failover.unhealthy_checks", "cores.failover.unhealthy_checks must be greater than 0".to_string()));
+        }
+        errors
+    }
+}
+
+/// 核心实例的元数据：创建时所用的类型、工厂和配置，备用核心故障转移时据此重新创建
+#[derive(Debug, Clone)]
+pub struct CoreSpec {
+    /// 注册表中的核心类型 (如 `gpu-btc`)
+    pub core_type: String,
+    /// 工厂名 (`CoreInfo::name`)
+    pub factory_name: String,
+    /// 工厂声明的核心类别
+    pub kind: CoreType,
+    pub config: CoreConfig,
+}
+
+/// 核心的选择顺序，越小越优先
+///
+/// `cores.priority` 为空时 ASIC 核心 > `gpu-btc` > `cpu-btc` > 其他核心；否则按核心类型在列表中
+/// 的位置，未列出的 ASIC 核心排在列出的核心之后，其余未列出的核心再往后。没有元数据的核心最后。
+pub fn core_rank(priority: &[String], spec: Option<&CoreSpec>) -> usize {
+    let Some(spec) = spec else {
+        return usize::MAX;
+    };
+    if priority.is_empty() {
+        return match spec.core_type.as_str() {
+            _ if spec.kind == CoreType::Asic => 0,
+            "gpu-btc" => 1,
+            "cpu-btc" => 2,
+            _ => 3,
+        };
+    }
+    match priority.iter().position(|core_type| *core_type == spec.core_type) {
+        Some(position) => position,
+        None if spec.kind == CoreType::Asic => priority.len(),
+        None => priority.len() + 1,
+    }
+}
+
+/// 统计选中核心连续不健康的检查次数
+#[derive(Debug)]
+pub struct HealthTracker {
+    threshold: u32,
+    grace: Duration,
+    since: Instant,
+    strikes: u32,
+}
+
+impl HealthTracker {
+    pub fn new(config: &CoreFailoverConfig, now: Instant) -> Self {
+        Self {
+            threshold: config.unhealthy_checks.max(1),
+            grace: Duration::from_secs(config.startup_grace_secs),
+            since: now,
+            strikes: 0,
+        }
+    }
+
+    /// 记录一次检查结果，达到阈值时返回 true
+    pub fn observe(&mut self, healthy: bool, now: Instant) -> bool {
+        if healthy {
+            self.strikes = 0;
+            return false;
+        }
+        if now.saturating_duration_since(self.since) < self.grace {
+            return false;
+        }
+        self.strikes += 1;
+        self.strikes >= self.threshold
+    }
+
+    pub fn strikes(&self) -> u32 {
+        self.strikes
+    }
+
+    /// 切换到新核心后重新开始计数和宽限期
+    pub fn reset(&mut self, now: Instant) {
+        self.since = now;
+        self.strikes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(core_type: &str, kind: CoreType) -> CoreSpec {
+        CoreSpec {
+            core_type: core_type.to_string(),
+            factory_name: format!("{} factory", core_type),
+            kind,
+            config: CoreConfig {
+                name: core_type.to_string(),
+                enabled: true,
+                devices: vec![],
+                custom_params: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_core_rank() {
+        let gpu = spec("gpu-btc", CoreType::Custom("gpu".to_string()));
+        let cpu = spec("cpu-btc", CoreType::CpuBtc);
+        let asic_plugin = spec("acme-x1", CoreType::Asic);
+        let other_plugin = spec("vendor", CoreType::Custom("vendor".to_string()));
+
+        // 默认 ASIC > GPU > CPU，GPU 核心 ID 里的 "btc" 不再影响排序
+        assert!(core_rank(&[], Some(&asic_plugin)) < core_rank(&[], Some(&gpu)));
+        assert!(core_rank(&[], Some(&gpu)) < core_rank(&[], Some(&cpu)));
+        assert!(core_rank(&[], Some(&cpu)) < core_rank(&[], Some(&other_plugin)));
+        assert_eq!(core_rank(&[], None), usize::MAX);
+
+        let priority = vec!["cpu-btc".to_string(), "vendor".to_string()];
+        assert_eq!(core_rank(&priority, Some(&cpu)), 0);
+        assert_eq!(core_rank(&priority, Some(&other_plugin)), 1);
+        assert!(core_rank(&priority, Some(&asic_plugin)) < core_rank(&priority, Some(&gpu)));
+    }
+
+    #[test]
+    fn test_unhealthy_after_grace_and_threshold() {
+        let config = CoreFailoverConfig { unhealthy_checks: 2, startup_grace_secs: 60, ..Default::default() };
+        let start = Instant::now();
+        let mut tracker = HealthTracker::new(&config, start);
+
+        // 宽限期内不计数
+        assert!(!tracker.observe(false, start + Duration::from_secs(30)));
+        assert_eq!(tracker.strikes(), 0);
+
+        let later = start + Duration::from_secs(90);
+        assert!(!tracker.observe(false, later));
+        // 中间恢复一次则重新计数
+        assert!(!tracker.observe(true, later));
+        assert!(!tracker.observe(false, later));
+        assert!(tracker.observe(false, later));
+
+        tracker.reset(later);
+        assert!(!tracker.observe(false, later + Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,133 @@
+//! CPU 哈希后端 (`cores.cpu_btc.hash_backend`)
+//!
+//! 本仓库不包含双 SHA256 内核 (SHA-NI、AVX2 4 路、AVX-512 8 路、NEON)，内核由
+//! cgminer-cpu-btc-core 实现。这里只在运行时检测 CPU 特性：`auto` 解析为本机最快的后端，
+//! 显式指定本机不支持的后端时配置验证失败。解析结果作为 `hash_backend` 参数传给核心，
+//! 核心配置 API 的 `custom_params` 中也是这个请求值；核心实际运行哪个内核由核心决定，
+//! 本仓库不上报，没有对应内核的核心忽略该参数。
+
+use serde::{Deserialize, Serialize};
+
+/// 双 SHA256 哈希后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashBackend {
+    /// 按 CPU 特性自动选择
+    #[default]
+    Auto,
+    /// x86 SHA 扩展
+    ShaNi,
+    /// AVX-512，8 路并行
+    Avx512,
+    /// AVX2，4 路并行
+    Avx2,
+    /// ARM NEON，4 路并行
+    Neon,
+    /// 纯软件实现
+    Generic,
+}
+
+impl HashBackend {
+    /// 具体后端，按速度从快到慢排列
+    pub const PREFERENCE: [HashBackend; 5] = [
+        HashBackend::ShaNi,
+        HashBackend::Avx512,
+        HashBackend::Avx2,
+        HashBackend::Neon,
+        HashBackend::Generic,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashBackend::Auto => "auto",
+            HashBackend::ShaNi => "sha_ni",
+            HashBackend::Avx512 => "avx512",
+            HashBackend::Avx2 => "avx2",
+            HashBackend::Neon => "neon",
+            HashBackend::Generic => "generic",
+        }
+    }
+
+    /// 每次内核调用并行计算的 nonce 数
+    pub fn lanes(&self) -> u32 {
+        match self {
+            HashBackend::Avx512 => 8,
+            HashBackend::Avx2 | HashBackend::Neon => 4,
+            _ => 1,
+        }
+    }
+
+    /// 本机 CPU 是否支持该后端
+    pub fn is_supported(&self) -> bool {
+        match self {
+            HashBackend::Auto | HashBackend::Generic => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            HashBackend::ShaNi => std::arch::is_x86_feature_detected!("sha") && std::arch::is_x86_feature_detected!("sse4.1"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            HashBackend::Avx512 => std::arch::is_x86_feature_detected!("avx512f"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            HashBackend::Avx2 => std::arch::is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "aarch64")]
+            HashBackend::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            _ => false,
+        }
+    }
+
+    /// 本机支持的具体后端，按速度从快到慢排列
+    pub fn available() -> Vec<HashBackend> {
+        Self::PREFERENCE.into_iter().filter(|backend| backend.is_supported()).collect()
+    }
+
+    /// 解析为具体后端：`auto` 选本机最快的，显式指定的后端必须被本机支持
+    pub fn resolve(self) -> Result<HashBackend, String> {
+        if self == HashBackend::Auto {
+            return Ok(Self::available().into_iter().next().unwrap_or(HashBackend::Generic));
+        }
+        if !self.is_supported() {
+            let available: Vec<&str> = Self::available().iter().map(|backend| backend.as_str()).collect();
+            return Err(format!("Hash backend {} is not supported by this CPU (available: {})", self, available.join(", ")));
+        }
+        Ok(self)
+    }
+}
+
+impl std::fmt::Display for HashBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_resolves_to_fastest_available() {
+        let resolved = HashBackend::Auto.resolve().unwrap();
+        assert_ne!(resolved, HashBackend::Auto);
+        assert_eq!(Some(&resolved), HashBackend::available().first());
+        // 纯软件实现总是可用
+        assert_eq!(HashBackend::available().last(), Some(&HashBackend::Generic));
+        assert_eq!(HashBackend::Generic.resolve(), Ok(HashBackend::Generic));
+    }
+
+    #[test]
+    fn test_unsupported_override_rejected() {
+        for backend in HashBackend::PREFERENCE {
+            match backend.resolve() {
+                Ok(resolved) => assert_eq!(resolved, backend),
+                Err(e) => {
+                    assert!(!backend.is_supported());
+                    assert!(e.contains(backend.as_str()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_serde_names() {
+        let parsed: HashBackend = serde_json::from_str("\"sha_ni\"").unwrap();
+        assert_eq!(parsed, HashBackend::ShaNi);
+        assert_eq!(serde_json::to_string(&HashBackend::Avx512).unwrap(), "\"avx512\"");
+    }
+}
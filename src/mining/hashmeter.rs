@@ -19,6 +19,9 @@ pub struct HashmeterConfig {
     pub per_device_stats: bool,
     /// 是否启用控制台输出
     pub console_output: bool,
+    /// 输出格式: standard / classic / json
+    #[serde(default)]
+    pub format: HashmeterFormat,
     /// 算力单位 (自动适应，无需配置)
     #[serde(skip)]
     pub hashrate_unit: String,
@@ -31,11 +34,25 @@ impl Default for HashmeterConfig {
             log_interval: 5, // 5秒间隔，更频繁的统计
             per_device_stats: true,
             console_output: true,
+            format: HashmeterFormat::default(),
             hashrate_unit: "AUTO".to_string(),
         }
     }
 }
 
+/// 算力计量器输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashmeterFormat {
+    /// 自适应单位的状态行
+    #[default]
+    Standard,
+    /// 原版 cgminer 状态行，兼容现有的日志解析脚本
+    Classic,
+    /// 每次输出一行 JSON
+    Json,
+}
+
 /// 算力统计数据
 #[derive(Debug, Clone)]
 pub struct HashrateStats {
@@ -76,7 +93,11 @@ pub struct DeviceHashrateStats {
 /// 算力计量器
 pub struct Hashmeter {
     config: HashmeterConfig,
+    /// 输出间隔 (秒)，可在运行时调整
+    log_interval: Arc<RwLock<u64>>,
     start_time: Instant,
+    /// 总平均算力的采样次数
+    total_samples: Arc<RwLock<u64>>,
     last_log_time: Arc<RwLock<Instant>>,
     total_stats: Arc<RwLock<HashrateStats>>,
     device_stats: Arc<RwLock<HashMap<u32, DeviceHashrateStats>>>,
@@ -90,8 +111,10 @@ impl Hashmeter {
         let start_time = Instant::now();
 
         Self {
+            log_interval: Arc::new(RwLock::new(config.log_interval)),
             config,
             start_time,
+            total_samples: Arc::new(RwLock::new(0)),
             last_log_time: Arc::new(RwLock::new(start_time)),
             total_stats: Arc::new(RwLock::new(HashrateStats {
                 current_hashrate: 0.0,
@@ -112,23 +135,39 @@ impl Hashmeter {
         }
     }
 
+    /// 调整输出间隔，在下一个周期生效
+    pub async fn set_log_interval(&self, secs: u64) {
+        *self.log_interval.write().await = secs.max(1);
+    }
+
     /// 启动算力计量器
     pub async fn start(&self) -> Result<(), MiningError> {
         *self.running.write().await = true;
 
         let running = self.running.clone();
-        let config = self.config.clone();
+        let mut config = self.config.clone();
         let last_log_time = self.last_log_time.clone();
         let total_stats = self.total_stats.clone();
         let device_stats = self.device_stats.clone();
         let start_time = self.start_time;
+        let log_interval = self.log_interval.clone();
+        let mut period = *log_interval.read().await;
 
         let handle = tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(config.log_interval));
+            let mut interval = interval(Duration::from_secs(period));
 
             while *running.read().await {
                 interval.tick().await;
 
+                // 输出间隔在运行时被修改后重建定时器
+                let current = *log_interval.read().await;
+                if current != period {
+                    period = current;
+                    config.log_interval = period;
+                    interval = tokio::time::interval(Duration::from_secs(period));
+                    interval.tick().await;
+                }
+
                 // 更新运行时间
                 let uptime = start_time.elapsed();
                 {
@@ -189,6 +228,13 @@ impl Hashmeter {
             stats.avg_15m = stats.avg_15m * (1.0 - alpha_15m) + mining_metrics.total_hashrate * alpha_15m;
         }
 
+        // 总平均算力: 全部采样的累计平均
+        {
+            let mut samples = self.total_samples.write().await;
+            *samples += 1;
+            stats.avg_total += (mining_metrics.total_hashrate - stats.avg_total) / *samples as f64;
+        }
+
         stats.accepted_shares = mining_metrics.accepted_shares;
         stats.rejected_shares = mining_metrics.rejected_shares;
         stats.hardware_errors = mining_metrics.hardware_errors;
@@ -242,7 +288,113 @@ impl Hashmeter {
         let stats = total_stats.read().await;
         let devices = device_stats.read().await;
 
-        Self::output_traditional_format(&stats, &devices, config).await;
+        match config.format {
+            HashmeterFormat::Standard => Self::output_traditional_format(&stats, &devices, config).await,
+            HashmeterFormat::Classic => {
+                info!("{}", Self::classic_line(&stats, config.log_interval));
+                if config.per_device_stats {
+                    let mut devices: Vec<&DeviceHashrateStats> = devices.values().collect();
+                    devices.sort_by_key(|d| d.device_id);
+                    for device in devices {
+                        info!("{}", Self::classic_device_line(device));
+                    }
+                }
+            }
+            HashmeterFormat::Json => info!("{}", Self::json_line(&stats, &devices, config)),
+        }
+    }
+
+    /// 原版 cgminer 状态行:
+    /// `(5s):16.90Mh/s (1m):12.37Mh/s (5m):9.649Mh/s (15m):9.054Mh/s (avg):10.21Mh/s | A:782  R:0  HW:0  WU:3.1/m`
+    fn classic_line(stats: &HashrateStats, log_interval: u64) -> String {
+        format!(
+            "({}s):{} (1m):{} (5m):{} (15m):{} (avg):{} | A:{}  R:{}  HW:{}  WU:{:.1}/m",
+            log_interval,
+            Self::format_hashrate_classic(stats.avg_5s),
+            Self::format_hashrate_classic(stats.avg_1m),
+            Self::format_hashrate_classic(stats.avg_5m),
+            Self::format_hashrate_classic(stats.avg_15m),
+            Self::format_hashrate_classic(stats.avg_total),
+            stats.accepted_shares,
+            stats.rejected_shares,
+            stats.hardware_errors,
+            stats.work_utility
+        )
+    }
+
+    /// 原版 cgminer 设备行: `DEV 0: 65.0C | 3.420Gh/s / 3.410Gh/s | A:123 R:0 HW:0`
+    fn classic_device_line(device: &DeviceHashrateStats) -> String {
+        format!(
+            "DEV {}: {:.1}C | {} / {} | A:{} R:{} HW:{}",
+            device.device_id,
+            device.temperature,
+            Self::format_hashrate_classic(device.stats.avg_5s),
+            Self::format_hashrate_classic(device.stats.avg_total),
+            device.stats.accepted_shares,
+            device.stats.rejected_shares,
+            device.stats.hardware_errors
+        )
+    }
+
+    /// JSON 行，算力单位为 H/s
+    fn json_line(stats: &HashrateStats, devices: &HashMap<u32, DeviceHashrateStats>, config: &HashmeterConfig) -> String {
+        let mut line = serde_json::json!({
+            "interval": config.log_interval,
+            "hashrate": {
+                "5s": stats.avg_5s,
+                "1m": stats.avg_1m,
+                "5m": stats.avg_5m,
+                "15m": stats.avg_15m,
+                "avg": stats.avg_total,
+            },
+            "accepted": stats.accepted_shares,
+            "rejected": stats.rejected_shares,
+            "hardware_errors": stats.hardware_errors,
+            "work_utility": stats.work_utility,
+            "uptime_secs": stats.uptime.as_secs(),
+            "device_count": devices.len(),
+        });
+
+        if config.per_device_stats {
+            let mut device_lines: Vec<&DeviceHashrateStats> = devices.values().collect();
+            device_lines.sort_by_key(|d| d.device_id);
+            line["devices"] = device_lines.iter()
+                .map(|device| serde_json::json!({
+                    "id": device.device_id,
+                    "hashrate_5s": device.stats.avg_5s,
+                    "hashrate_1m": device.stats.avg_1m,
+                    "hashrate_5m": device.stats.avg_5m,
+                    "accepted": device.stats.accepted_shares,
+                    "rejected": device.stats.rejected_shares,
+                    "hardware_errors": device.stats.hardware_errors,
+                    "temperature": device.temperature,
+                    "fan_speed": device.fan_speed,
+                }))
+                .collect();
+        }
+
+        line.to_string()
+    }
+
+    /// 原版 cgminer 的算力写法：4 位有效数字，单位紧跟数值 (如 `16.90Mh/s`)
+    fn format_hashrate_classic(hashrate: f64) -> String {
+        const UNITS: &[&str] = &["", "K", "M", "G", "T", "P", "E"];
+
+        let mut value = hashrate.max(0.0);
+        let mut unit = 0;
+        while value >= 1000.0 && unit < UNITS.len() - 1 {
+            value /= 1000.0;
+            unit += 1;
+        }
+
+        let digits = if value >= 100.0 {
+            1
+        } else if value >= 10.0 {
+            2
+        } else {
+            3
+        };
+        format!("{:.*}{}h/s", digits, value, UNITS[unit])
     }
 
     /// 传统格式输出 (类似原版cgminer，显示滑动窗口算力)
@@ -414,6 +566,39 @@ mod tests {
         assert_eq!(Hashmeter::format_hashrate(0.007399, ""), "0.007399 H/s");
     }
 
+    #[test]
+    fn test_classic_format() {
+        assert_eq!(Hashmeter::format_hashrate_classic(16_896_000.0), "16.90Mh/s");
+        assert_eq!(Hashmeter::format_hashrate_classic(9_649_000.0), "9.649Mh/s");
+        assert_eq!(Hashmeter::format_hashrate_classic(110_000_000_000_000.0), "110.0Th/s");
+        assert_eq!(Hashmeter::format_hashrate_classic(0.0), "0.000h/s");
+
+        let stats = HashrateStats {
+            current_hashrate: 16_896_000.0,
+            avg_5s: 16_896_000.0,
+            avg_1m: 12_374_000.0,
+            avg_5m: 9_649_000.0,
+            avg_15m: 9_054_000.0,
+            avg_total: 10_210_000.0,
+            accepted_shares: 782,
+            rejected_shares: 0,
+            hardware_errors: 0,
+            work_utility: 3.14,
+            uptime: Duration::from_secs(600),
+        };
+        assert_eq!(
+            Hashmeter::classic_line(&stats, 5),
+            "(5s):16.90Mh/s (1m):12.37Mh/s (5m):9.649Mh/s (15m):9.054Mh/s (avg):10.21Mh/s | A:782  R:0  HW:0  WU:3.1/m"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(
+            &Hashmeter::json_line(&stats, &HashMap::new(), &HashmeterConfig::default())
+        ).unwrap();
+        assert_eq!(json["accepted"], 782);
+        assert_eq!(json["hashrate"]["5m"], 9_649_000.0);
+        assert_eq!(json["devices"], serde_json::json!([]));
+    }
+
     #[test]
     fn test_hashrate_unit_independence() {
         // 测试单位参数被忽略，都使用自动选择
@@ -0,0 +1,259 @@
+//! 结果流水线压力测试 (`--load-test`)
+//!
+//! 不连接矿池、不启动核心，把合成的核心结果送过实际的结果流水线：验证 (份额难度、份额目标预过滤、
+//! 统计) → 创建份额 → 模拟提交 (构造并序列化 `mining.submit` 请求)，报告吞吐量和各阶段的延迟分布。
+//! 发版前运行，可以发现流水线的性能退化；`benches/result_pipeline_benchmark.rs` 用同样的阶段做基准测试。
+
+use crate::mining::{core_results, Algorithm, Network, ShareTargetFilter, StatsCounters};
+use crate::pool::stratum::submit_message;
+use crate::pool::Share;
+use cgminer_core::Work;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 流水线阶段名称
+pub const STAGES: [&str; 3] = ["validate", "create_share", "submit"];
+
+/// 合成工作的数量，结果轮流属于这些工作
+const SYNTHETIC_WORKS: usize = 64;
+
+/// 压力测试配置
+#[derive(Debug, Clone)]
+pub struct LoadTestConfig {
+    /// 合成结果总数
+    pub results: u64,
+    /// 并发任务数
+    pub concurrency: usize,
+}
+
+impl Default for LoadTestConfig {
+    fn default() -> Self {
+        Self {
+            results: 1_000_000,
+            concurrency: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+        }
+    }
+}
+
+/// 结果流水线：持有各阶段共享的状态
+pub struct ResultPipeline {
+    stats: StatsCounters,
+    share_filter: ShareTargetFilter,
+    works: Vec<Work>,
+    message_id: AtomicU64,
+    submitted_bytes: AtomicU64,
+}
+
+impl ResultPipeline {
+    /// 创建流水线并登记合成工作的份额目标 (难度 1)
+    pub async fn new() -> Self {
+        let share_filter = ShareTargetFilter::new();
+        let mut works = Vec::with_capacity(SYNTHETIC_WORKS);
+        for i in 0..SYNTHETIC_WORKS {
+            let mut work = Work::new(format!("load-{:04x}", i), [0xff; 32], [i as u8; 80], 1.0);
+            work.ntime = 0x504e86b9 + i as u32;
+            share_filter.record_work(work.id, 1.0, Algorithm::Sha256d).await;
+            works.push(work);
+        }
+        Self {
+            stats: StatsCounters::new(),
+            share_filter,
+            works,
+            message_id: AtomicU64::new(1),
+            submitted_bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// 第 `index` 个合成结果：哈希最高 4 字节为零 (难度不低于 1)，每 8 个结果有 1 个低于份额目标
+    pub fn synthetic_result(&self, index: u64) -> cgminer_core::MiningResult {
+        let work = &self.works[index as usize % self.works.len()];
+        let mut hash = [0u8; 32];
+        hash[..8].copy_from_slice(&index.to_le_bytes());
+        hash[8..16].copy_from_slice(&index.rotate_left(17).to_le_bytes());
+        if index % 8 == 7 {
+            hash[31] = 0xff;
+        }
+        let mut result = cgminer_core::MiningResult::new(work.id, (index % 16) as u32, index as u32, hash.to_vec(), true);
+        result.extranonce2 = (index as u32).to_le_bytes().to_vec();
+        result
+    }
+
+    /// 验证阶段：计算份额难度、按份额目标预过滤并统计；合成哈希不是真实哈希，不经份额验证器
+    pub async fn validate(&self, result: cgminer_core::MiningResult) -> Option<cgminer_core::types::MiningResult> {
+        core_results::record_core_result("load-test", result, &self.stats, &self.share_filter, Network::Mainnet, None).await
+    }
+
+    /// 创建份额阶段
+    pub fn create_share(&self, result: &cgminer_core::types::MiningResult) -> Option<Share> {
+        let work = self.works.iter().find(|work| work.id == result.work_id)?;
+        Share::from_mining_result(0, work, result).ok()
+    }
+
+    /// 模拟提交阶段：构造并序列化 `mining.submit` 请求，不发送
+    pub fn submit(&self, share: &Share) -> usize {
+        let id = self.message_id.fetch_add(1, Ordering::Relaxed);
+        let line = serde_json::to_string(&submit_message(id, "load-test.worker", share)).unwrap_or_default();
+        self.submitted_bytes.fetch_add(line.len() as u64 + 1, Ordering::Relaxed);
+        line.len()
+    }
+
+    /// 按顺序处理一个结果，返回是否提交了份额
+    pub async fn process(&self, result: cgminer_core::MiningResult) -> bool {
+        match self.validate(result).await.and_then(|result| self.create_share(&result)) {
+            Some(share) => {
+                self.submit(&share);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 单个阶段的延迟分布
+#[derive(Debug, Clone, Serialize)]
+pub struct StageReport {
+    pub name: &'static str,
+    pub count: usize,
+    pub mean_ns: u64,
+    pub p50_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+impl StageReport {
+    fn from_samples(name: &'static str, mut samples: Vec<u64>) -> Self {
+        samples.sort_unstable();
+        let count = samples.len();
+        let percentile = |p: usize| if count == 0 { 0 } else { samples[((count - 1) * p) / 100] };
+        Self {
+            name,
+            count,
+            mean_ns: if count == 0 { 0 } else { samples.iter().sum::<u64>() / count as u64 },
+            p50_ns: percentile(50),
+            p99_ns: percentile(99),
+            max_ns: samples.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// 压力测试报告
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadTestReport {
+    pub results: u64,
+    pub shares_submitted: u64,
+    pub below_target: u64,
+    pub concurrency: usize,
+    pub elapsed: Duration,
+    pub stages: Vec<StageReport>,
+}
+
+impl LoadTestReport {
+    /// 每秒处理的结果数
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 { self.results as f64 / secs } else { 0.0 }
+    }
+}
+
+impl std::fmt::Display for LoadTestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Result pipeline load test: {} results, {} tasks", self.results, self.concurrency)?;
+        writeln!(f, "  throughput: {:.0} results/s ({:.2}s)", self.throughput(), self.elapsed.as_secs_f64())?;
+        writeln!(f, "  submitted: {}  below target: {}", self.shares_submitted, self.below_target)?;
+        write!(f, "  {:<14}{:>10}{:>10}{:>10}{:>10}", "stage", "mean", "p50", "p99", "max")?;
+        for stage in &self.stages {
+            write!(f, "\n  {:<14}{:>8}ns{:>8}ns{:>8}ns{:>8}ns", stage.name, stage.mean_ns, stage.p50_ns, stage.p99_ns, stage.max_ns)?;
+        }
+        Ok(())
+    }
+}
+
+/// 运行压力测试
+pub async fn run(config: &LoadTestConfig) -> LoadTestReport {
+    let pipeline = Arc::new(ResultPipeline::new().await);
+    let concurrency = config.concurrency.max(1);
+    let started = Instant::now();
+
+    let mut tasks = Vec::with_capacity(concurrency);
+    for task in 0..concurrency as u64 {
+        let pipeline = pipeline.clone();
+        let total = config.results;
+        let step = concurrency as u64;
+        tasks.push(tokio::spawn(async move {
+            let mut samples: [Vec<u64>; 3] = Default::default();
+            let mut index = task;
+            while index < total {
+                let result = pipeline.synthetic_result(index);
+                index += step;
+
+                let at = Instant::now();
+                let valid = pipeline.validate(result).await;
+                samples[0].push(at.elapsed().as_nanos() as u64);
+                let Some(valid) = valid else { continue };
+
+                let at = Instant::now();
+                let share = pipeline.create_share(&valid);
+                samples[1].push(at.elapsed().as_nanos() as u64);
+                let Some(share) = share else { continue };
+
+                let at = Instant::now();
+                pipeline.submit(&share);
+                samples[2].push(at.elapsed().as_nanos() as u64);
+            }
+            samples
+        }));
+    }
+
+    let mut merged: [Vec<u64>; 3] = Default::default();
+    for task in tasks {
+        if let Ok(samples) = task.await {
+            for (all, task_samples) in merged.iter_mut().zip(samples) {
+                all.extend(task_samples);
+            }
+        }
+    }
+    let elapsed = started.elapsed();
+
+    let stats = pipeline.stats.snapshot();
+    LoadTestReport {
+        results: config.results,
+        shares_submitted: merged[2].len() as u64,
+        below_target: stats.below_target_results,
+        concurrency,
+        elapsed,
+        stages: STAGES.iter().zip(merged).map(|(name, samples)| StageReport::from_samples(name, samples)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pipeline_processes_every_result() {
+        let report = run(&LoadTestConfig { results: 800, concurrency: 4 }).await;
+        assert_eq!(report.stages[0].count, 800);
+        // 每 8 个结果有 1 个低于份额目标
+        assert_eq!(report.below_target, 100);
+        assert_eq!(report.shares_submitted, 700);
+        assert!(report.throughput() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_submit_builds_stratum_request() {
+        let pipeline = ResultPipeline::new().await;
+        let valid = pipeline.validate(pipeline.synthetic_result(1)).await.unwrap();
+        let share = pipeline.create_share(&valid).unwrap();
+        assert_eq!(share.job_id, "load-0001");
+        assert_eq!(share.extra_nonce2, "01000000");
+        assert!(pipeline.submit(&share) > 0);
+    }
+
+    #[test]
+    fn test_stage_percentiles() {
+        let report = StageReport::from_samples("validate", (1..=100).rev().collect());
+        assert_eq!((report.p50_ns, report.p99_ns, report.max_ns, report.mean_ns), (50, 99, 100, 50));
+    }
+}
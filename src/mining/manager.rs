@@ -1,14 +1,33 @@
 use crate::config::Config;
-use crate::error::MiningError;
-use crate::device::{DeviceManager, DeviceCoreMapper};
-use crate::pool::PoolManager;
-use crate::monitoring::{MonitoringSystem, MiningMetrics};
-use crate::mining::{MiningState, MiningStats, MiningConfig, MiningEvent, WorkItem, ResultItem, Hashmeter};
+use crate::error::{MiningError, PoolError};
+use crate::device::{DeviceManager, DeviceCoreMapper, DeviceCoreMapping, DeviceInfo, MappingStats, FanSpeedTarget, FanStatus, RampUpStatus, AutoTuneStatus, PowerStatus, ChipHeatmap};
+use crate::device::eeprom::BoardEeprom;
+use crate::pool::{PoolDeviceStats, PoolManager};
+use crate::pool::share_audit::ShareAuditLog;
+use crate::pool::share_verify::{ShareCheck, ShareVerifier};
+use crate::pool::switcher::{fetch_profitability, ProfitSwitcher};
+use crate::security::ConfigGuard;
+use crate::monitoring::{MonitoringSystem, MiningMetrics, RecentShare};
+use crate::mining::core_results;
+use crate::mining::core_lifecycle::{self, CoreLifecycle};
+use crate::mining::{cpu_load, cpu_thermal, cpu_topology, queue, self_test, shutdown, startup};
+use crate::mining::shutdown::{ShutdownSequence, ShutdownTokens};
+use crate::mining::session_report::{self, ReportReason, SessionReport, SessionReporter, SessionSnapshot, ShareCounts};
+use crate::mining::stats_file::{self, StatsSnapshot};
+use crate::mining::result_poll::{AdaptivePoll, CORE_STATS_INTERVAL};
+use crate::mining::failover::{core_rank, CoreSpec, HealthTracker};
+use crate::monitoring::alerts::{Alert, AlertSeverity, AlertType};
+use crate::monitoring::recovery::{RecoveryAction, ZeroHashrateWatchdog};
+use crate::mining::{MiningState, MiningStats, StatsCounters, MiningEvent, WorkItem, ResultItem, Hashmeter, ShareTargetFilter, MiningConfig, RuntimeSettings, RuntimeSettingsUpdate, HashBackend, CpuTopology, DeviceCount, ThreadSizing, QueueStats, StartupTimings};
 use crate::logging::formatter::format_duration;
-use cgminer_core::{CoreRegistry, CoreType, CoreConfig};
+use crate::performance::alloc_audit::{self, Subsystem};
+use crate::performance::task_watch;
+use cgminer_core::{CoreRegistry, CoreType, CoreConfig, Work};
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-use tokio::sync::{RwLock, Mutex, mpsc, broadcast};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{RwLock, Mutex, broadcast, watch};
 use tokio::time::interval;
 use tracing::{info, warn, error, debug};
 
@@ -16,6 +35,14 @@ use tracing::{info, warn, error, debug};
 pub struct MiningManager {
     /// 核心注册表
     core_registry: Arc<CoreRegistry>,
+    /// 核心生命周期调用，创建/启动/停止/移除都经由此处以便广播事件
+    core_lifecycle: CoreLifecycle,
+    /// 各核心的类型和当前配置 (核心 ID -> 配置)，运行时更新时在此基础上合并
+    core_configs: Arc<RwLock<HashMap<String, CoreSpec>>>,
+    /// 当前选中 (正在挖矿) 的核心
+    selected_core: Arc<RwLock<Option<String>>>,
+    /// 启动时未被选中的核心，按优先级排列，供故障转移使用
+    standby_cores: Arc<RwLock<Vec<CoreSpec>>>,
     /// 设备管理器
     device_manager: Arc<Mutex<DeviceManager>>,
     /// 设备-核心映射器（从协调器移入）
@@ -23,24 +50,30 @@ pub struct MiningManager {
     /// 矿池管理器
     pool_manager: Arc<Mutex<PoolManager>>,
     /// 监控系统
-    monitoring_system: Arc<Mutex<MonitoringSystem>>,
+    monitoring_system: Arc<MonitoringSystem>,
     /// 算力计量器
     hashmeter: Arc<Mutex<Option<Hashmeter>>>,
     /// 完整配置
     full_config: Config,
-    /// 挖矿配置
-    config: MiningConfig,
     /// 挖矿状态
     state: Arc<RwLock<MiningState>>,
     /// 挖矿统计
-    stats: Arc<RwLock<MiningStats>>,
+    stats: Arc<StatsCounters>,
+    /// 份额目标过滤器
+    share_filter: Arc<ShareTargetFilter>,
+    /// 运行时可调整的通用设置
+    runtime_settings: Arc<RwLock<RuntimeSettings>>,
+    /// 配置文件路径（用于保存运行时设置）
+    config_path: Option<String>,
+    /// 配置文件防篡改，保存运行时设置后更新基准
+    config_guard: Option<Arc<ConfigGuard>>,
 
     /// 工作分发通道
-    work_sender: Arc<Mutex<Option<mpsc::UnboundedSender<WorkItem>>>>,
-    work_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<WorkItem>>>>,
+    work_sender: Arc<Mutex<Option<queue::QueueSender<WorkItem>>>>,
+    work_receiver: Arc<Mutex<Option<queue::QueueReceiver<WorkItem>>>>,
     /// 结果收集通道
-    result_sender: Arc<Mutex<Option<mpsc::UnboundedSender<ResultItem>>>>,
-    result_receiver: Arc<Mutex<Option<mpsc::UnboundedReceiver<ResultItem>>>>,
+    result_sender: Arc<Mutex<Option<queue::QueueSender<ResultItem>>>>,
+    result_receiver: Arc<Mutex<Option<queue::QueueReceiver<ResultItem>>>>,
     /// 事件广播
     event_sender: broadcast::Sender<MiningEvent>,
     /// 主循环任务句柄
@@ -53,8 +86,30 @@ pub struct MiningManager {
     hashmeter_update_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     /// 核心结果收集任务句柄
     core_result_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 核心健康检查任务句柄
+    core_health_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// CPU 负载目标任务句柄
+    cpu_load_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// CPU 温度降频任务句柄
+    cpu_thermal_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 按收益切换矿池任务句柄
+    profit_switch_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 会话报告器，挖矿启动时创建，停机时写入最后一份报告后清除
+    session_reporter: Arc<Mutex<Option<SessionReporter>>>,
+    /// 会话报告采样任务句柄
+    session_report_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 统计文件刷新任务句柄
+    stats_file_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 零算力恢复任务句柄
+    recovery_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 零算力恢复请求退出进程时的退出码
+    recovery_exit: Arc<watch::Sender<Option<i32>>>,
     /// 运行状态
-    running: Arc<RwLock<bool>>,
+    running: Arc<AtomicBool>,
+    /// 最近一次启动的各阶段耗时
+    startup_timings: Arc<RwLock<StartupTimings>>,
+    /// 后台任务的停机令牌
+    shutdown_tokens: Arc<RwLock<ShutdownTokens>>,
 }
 
 impl MiningManager {
@@ -74,17 +129,28 @@ impl MiningManager {
         Self::register_drivers_for_cores(&mut device_manager, &config.cores).await?;
 
         // 创建矿池管理器
-        let pool_manager = PoolManager::new(config.pools.clone()).await?;
+        let mut pool_manager = PoolManager::new(config.pools.clone()).await?;
+        if config.share_audit.enabled {
+            let audit = ShareAuditLog::open(&config.share_audit).map_err(|e| {
+                MiningError::System(format!("Failed to open share audit log {}: {}", config.share_audit.file, e))
+            })?;
+            pool_manager.set_share_audit(Arc::new(audit));
+            info!("📝 Share audit log enabled: {}", config.share_audit.file);
+        }
+        if config.share_verify.enabled {
+            pool_manager.set_share_verifier(Arc::new(ShareVerifier::new(&config.share_verify, config.pools.network)));
+            info!("🔍 Local share verification enabled (sample rate {})", config.share_verify.sample_rate);
+        }
 
         // 创建监控系统
-        let monitoring_system = MonitoringSystem::new(config.monitoring.clone()).await?;
-
-        // 创建通道
-        let (work_sender, work_receiver) = mpsc::unbounded_channel();
-        let (result_sender, result_receiver) = mpsc::unbounded_channel();
-        let (event_sender, _) = broadcast::channel(1000);
+        let monitoring_system = Arc::new(MonitoringSystem::new(config.monitoring.clone()).await?);
 
+        // 按挖矿配置创建有界队列
         let mining_config = MiningConfig::from(&config);
+        let (work_sender, work_receiver) = queue::bounded("work", mining_config.max_work_queue_size, mining_config.work_queue_overflow);
+        let (result_sender, result_receiver) = queue::bounded("result", mining_config.max_result_queue_size, mining_config.result_queue_overflow);
+        let (event_sender, _) = broadcast::channel(1000);
+        device_manager.set_event_sender(event_sender.clone());
 
         // 创建算力计量器
         let hashmeter = if config.hashmeter.enabled && config.hashmeter.log_interval > 0 {
@@ -93,17 +159,29 @@ impl MiningManager {
             None
         };
 
+        let runtime_settings = RuntimeSettings::from_config(&config);
+
+        let core_lifecycle = CoreLifecycle::new(core_registry.clone());
+        core_lifecycle::spawn_forwarder(core_lifecycle.subscribe(), monitoring_system.clone(), event_sender.clone());
+
         Ok(Self {
             core_registry,
+            core_lifecycle,
+            core_configs: Arc::new(RwLock::new(HashMap::new())),
+            selected_core: Arc::new(RwLock::new(None)),
+            standby_cores: Arc::new(RwLock::new(Vec::new())),
             device_manager: Arc::new(Mutex::new(device_manager)),
             device_core_mapper: Arc::new(device_core_mapper),
             pool_manager: Arc::new(Mutex::new(pool_manager)),
-            monitoring_system: Arc::new(Mutex::new(monitoring_system)),
+            monitoring_system,
             hashmeter: Arc::new(Mutex::new(hashmeter)),
             full_config: config,
-            config: mining_config,
             state: Arc::new(RwLock::new(MiningState::Stopped)),
-            stats: Arc::new(RwLock::new(MiningStats::new())),
+            stats: Arc::new(StatsCounters::new()),
+            share_filter: Arc::new(ShareTargetFilter::new()),
+            runtime_settings: Arc::new(RwLock::new(runtime_settings)),
+            config_path: None,
+            config_guard: None,
 
             work_sender: Arc::new(Mutex::new(Some(work_sender))),
             work_receiver: Arc::new(Mutex::new(Some(work_receiver))),
@@ -115,7 +193,18 @@ impl MiningManager {
             result_process_handle: Arc::new(Mutex::new(None)),
             hashmeter_update_handle: Arc::new(Mutex::new(None)),
             core_result_handle: Arc::new(Mutex::new(None)),
-            running: Arc::new(RwLock::new(false)),
+            core_health_handle: Arc::new(Mutex::new(None)),
+            cpu_load_handle: Arc::new(Mutex::new(None)),
+            cpu_thermal_handle: Arc::new(Mutex::new(None)),
+            profit_switch_handle: Arc::new(Mutex::new(None)),
+            session_reporter: Arc::new(Mutex::new(None)),
+            session_report_handle: Arc::new(Mutex::new(None)),
+            stats_file_handle: Arc::new(Mutex::new(None)),
+            recovery_handle: Arc::new(Mutex::new(None)),
+            recovery_exit: Arc::new(watch::channel(None).0),
+            running: Arc::new(AtomicBool::new(false)),
+            startup_timings: Arc::new(RwLock::new(StartupTimings::new())),
+            shutdown_tokens: Arc::new(RwLock::new(ShutdownTokens::new())),
         })
     }
 
@@ -128,12 +217,19 @@ impl MiningManager {
         Ok(())
     }
 
-    /// 创建挖矿核心
-    pub async fn create_core(&self, core_type: &str, config: CoreConfig) -> Result<String, MiningError> {
+    /// 用工厂创建挖矿核心，记录核心的类型信息
+    pub async fn create_core(&self, factory: &cgminer_core::CoreInfo, core_type: &str, config: CoreConfig) -> Result<String, MiningError> {
         debug!("Creating mining core: {}", core_type);
 
-        let core_id = self.core_registry.create_core(core_type, config).await
+        let core_id = self.core_lifecycle.create_core(core_type, config.clone()).await
             .map_err(|e| MiningError::CoreError(format!("创建核心失败: {}", e)))?;
+        let spec = CoreSpec {
+            core_type: core_type.to_string(),
+            factory_name: factory.name.clone(),
+            kind: factory.core_type.clone(),
+            config,
+        };
+        self.core_configs.write().await.insert(core_id.clone(), spec);
 
         debug!("Core created successfully: {}", core_id);
         Ok(core_id)
@@ -155,13 +251,57 @@ impl MiningManager {
     pub async fn remove_core(&self, core_id: &str) -> Result<(), MiningError> {
         debug!("Removing mining core: {}", core_id);
 
-        self.core_registry.remove_core(core_id).await
+        self.core_lifecycle.remove_core(core_id).await
             .map_err(|e| MiningError::CoreError(format!("移除核心失败: {}", e)))?;
+        self.core_configs.write().await.remove(core_id);
 
         debug!("Core removed successfully: {}", core_id);
         Ok(())
     }
 
+    /// 获取核心当前的配置
+    pub async fn get_core_config(&self, core_id: &str) -> Option<CoreConfig> {
+        self.core_configs.read().await.get(core_id).map(|spec| spec.config.clone())
+    }
+
+    /// 获取核心自身上报的统计
+    pub async fn get_core_stats(&self, core_id: &str) -> Result<cgminer_core::CoreStats, MiningError> {
+        self.core_registry.get_core_stats(core_id).await
+            .map_err(|e| MiningError::CoreError(format!("获取核心统计失败: {}", e)))
+    }
+
+    /// 更新核心的配置参数，返回核心的新 ID 和合并后的配置
+    ///
+    /// `custom_params` 合并到当前参数中，值为 null 的键被删除。核心没有就地更新配置的接口，
+    /// 由 `CoreLifecycle::recreate_core` 用新配置重建核心；新核心创建或启动失败时保留原核心和原配置。
+    pub async fn update_core_config(
+        &self,
+        core_id: &str,
+        custom_params: HashMap<String, serde_json::Value>,
+        enabled: Option<bool>,
+    ) -> Result<(String, CoreConfig), MiningError> {
+        let mut spec = self.core_configs.read().await.get(core_id).cloned()
+            .ok_or_else(|| MiningError::CoreError(format!("Core {} not found", core_id)))?;
+
+        for (key, value) in custom_params {
+            if value.is_null() {
+                spec.config.custom_params.remove(&key);
+            } else {
+                spec.config.custom_params.insert(key, value);
+            }
+        }
+        if let Some(enabled) = enabled {
+            spec.config.enabled = enabled;
+        }
+
+        let updated = spec.config.clone();
+        let new_core_id = Self::replace_core(&self.core_lifecycle, &self.core_configs, &self.selected_core, &self.device_core_mapper, core_id, spec).await
+            .map_err(|e| MiningError::CoreError(format!("更新核心配置失败: {}", e)))?;
+
+        info!("⚙️ Core {} configuration updated, now running as {}", core_id, new_core_id);
+        Ok((new_core_id, updated))
+    }
+
     /// 注册核心（为示例程序提供接口）
     pub async fn register_core(&self, core_info: cgminer_core::CoreInfo) -> Result<String, MiningError> {
         debug!("Registering core: {}", core_info.name);
@@ -179,11 +319,11 @@ impl MiningManager {
         // 直接使用cgminer-core的Work类型创建WorkItem
         let work_item = WorkItem::new(work);
 
-        if let Ok(work_sender_guard) = self.work_sender.try_lock() {
-            if let Some(sender) = work_sender_guard.as_ref() {
-                sender.send(work_item)
-                    .map_err(|e| MiningError::WorkError(format!("提交工作失败: {}", e)))?;
-            }
+        // 克隆发送端后释放锁，队列满需要等待时不会挡住其他提交方
+        let sender = self.work_sender.lock().await.clone();
+        if let Some(sender) = sender {
+            sender.send(work_item).await
+                .map_err(|e| MiningError::WorkError(format!("提交工作失败: {}", e)))?;
         }
 
         Ok(())
@@ -195,17 +335,34 @@ impl MiningManager {
         Ok(vec![])
     }
 
+    /// 只启动最优核心并运行测试向量 (`--self-test`)，不连接矿池；结束后停止该核心
+    pub async fn run_self_test(&self, timeout: Duration) -> Result<self_test::SelfTestReport, MiningError> {
+        self.start_cores().await?;
+        let core_id = self.selected_core.read().await.clone()
+            .ok_or_else(|| MiningError::CoreError("No mining core available for the self-test".to_string()))?;
+
+        info!("Running {} test vectors on core {}", self_test::TEST_VECTORS.len(), core_id);
+        let report = self_test::run_vectors(&self.core_registry, &core_id, timeout).await;
+
+        if let Err(e) = self.core_lifecycle.stop_core(&core_id).await {
+            warn!("Failed to stop core {} after the self-test: {}", core_id, e);
+        }
+        Ok(report)
+    }
+
     /// 启动挖矿
     pub async fn start(&self) -> Result<(), MiningError> {
         // 检查是否已经在运行
-        if *self.running.read().await {
+        if self.running.load(Ordering::Acquire) {
             warn!("cgminer already running");
             return Ok(());
         }
 
         // 更新状态
         *self.state.write().await = MiningState::Starting;
-        *self.running.write().await = true;
+        self.running.store(true, Ordering::Release);
+        // 令牌取消后不能复用，每次启动重新创建
+        *self.shutdown_tokens.write().await = ShutdownTokens::new();
 
         // 发送状态变更事件
         self.send_event(MiningEvent::StateChanged {
@@ -214,46 +371,63 @@ impl MiningManager {
             timestamp: SystemTime::now(),
         }).await;
 
-        // 启动核心组件
-        let mut started_components = Vec::new();
-
-        // 先启动挖矿核心（创建核心实例）
-        self.start_cores().await?;
-        started_components.push("cores");
-
-        // 初始化设备管理器（使用协调器功能）
-        self.initialize_device_manager().await?;
-        started_components.push("devices");
-
-        // 启动矿池管理器
-        {
-            let pool_manager = self.pool_manager.lock().await;
-            pool_manager.start().await?;
-            started_components.push("pools");
-        }
-
-        // 启动监控系统
-        {
-            let monitoring_system = self.monitoring_system.lock().await;
-            monitoring_system.start().await?;
-            started_components.push("monitoring");
-        }
+        // 启动核心组件：核心 → 设备有依赖，其余各路互相独立，并发启动
+        let startup_started = Instant::now();
+        let mut timings = StartupTimings::new();
+
+        let cores_and_devices = async {
+            // 先启动挖矿核心（创建核心实例），设备管理器需要已启动的核心
+            let (cores, cores_elapsed) = startup::timed(self.start_cores()).await;
+            cores?;
+            let (devices, devices_elapsed) = startup::timed(self.initialize_device_manager()).await;
+            devices?;
+            Ok::<_, MiningError>((cores_elapsed, devices_elapsed))
+        };
+        let pools = async {
+            let (result, elapsed) = startup::timed(async {
+                self.pool_manager.lock().await.start().await
+            }).await;
+            result?;
+            Ok::<_, MiningError>(elapsed)
+        };
+        let monitoring = async {
+            let (result, elapsed) = startup::timed(self.monitoring_system.start()).await;
+            result?;
+            Ok::<_, MiningError>(elapsed)
+        };
+        let hashmeter = async {
+            let (result, elapsed) = startup::timed(self.start_hashmeter()).await;
+            result?;
+            Ok::<_, MiningError>(elapsed)
+        };
 
-                // 启动算力计量器
-        self.start_hashmeter().await?;
-        started_components.push("hashmeter");
+        let ((cores_elapsed, devices_elapsed), pools_elapsed, monitoring_elapsed, hashmeter_elapsed) =
+            tokio::try_join!(cores_and_devices, pools, monitoring, hashmeter)?;
+        timings.record("cores", cores_elapsed);
+        timings.record("devices", devices_elapsed);
+        timings.record("pools", pools_elapsed);
+        timings.record("monitoring", monitoring_elapsed);
+        timings.record("hashmeter", hashmeter_elapsed);
 
         // 启动各个任务
+        let workers_started = Instant::now();
         self.start_main_loop().await?;
         self.start_work_dispatch().await?;
         self.start_result_processing().await?;
         self.start_core_result_collection().await?;
+        self.start_core_health_check().await;
+        self.start_cpu_load_target().await;
+        self.start_cpu_thermal_throttle().await;
+        self.start_profit_switch().await;
+        self.start_session_report().await;
+        self.start_stats_file().await;
+        self.start_zero_hashrate_recovery().await;
         self.start_hashmeter_updates().await?;
-        started_components.push("workers");
+        timings.record("workers", workers_started.elapsed());
 
         // 更新状态和统计
         *self.state.write().await = MiningState::Running;
-        self.stats.write().await.start();
+        self.stats.start();
 
         // 发送状态变更事件
         self.send_event(MiningEvent::StateChanged {
@@ -262,11 +436,11 @@ impl MiningManager {
             timestamp: SystemTime::now(),
         }).await;
 
-        // 等待设备完全启动后再显示总结
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        timings.total = startup_started.elapsed();
+        *self.startup_timings.write().await = timings.clone();
 
         // 显示启动总结
-        info!("Started cgminer {}", env!("CARGO_PKG_VERSION"));
+        info!("Started cgminer {} in {}", env!("CARGO_PKG_VERSION"), timings);
         let first_pool = self.full_config.pools.pools.first();
         info!("Mining to {} with {} pools",
               first_pool.map(|p| p.url.as_str()).unwrap_or("unknown"),
@@ -288,7 +462,7 @@ impl MiningManager {
                     "Software Mining Core" => {
                         // CPU核心：使用配置的device_count
                         if let Some(cpu_btc_config) = &self.full_config.cores.cpu_btc {
-                            total_devices += cpu_btc_config.device_count;
+                            total_devices += cpu_btc_config.resolved_device_count();
                         } else {
                             total_devices += 4; // 默认4个CPU设备
                         }
@@ -338,41 +512,72 @@ impl MiningManager {
     /// 停止挖矿
     pub async fn stop(&self) -> Result<(), MiningError> {
         // 检查是否已经停止
-        if !*self.running.read().await {
+        if !self.running.load(Ordering::Acquire) {
             warn!("cgminer already stopped");
             return Ok(());
         }
 
         // 更新状态
-        *self.state.write().await = MiningState::Stopping;
-        *self.running.write().await = false;
+        let old_state = std::mem::replace(&mut *self.state.write().await, MiningState::Stopping);
+        self.running.store(false, Ordering::Release);
 
         // 发送状态变更事件
         self.send_event(MiningEvent::StateChanged {
-            old_state: MiningState::Running,
+            old_state,
             new_state: MiningState::Stopping,
             timestamp: SystemTime::now(),
         }).await;
 
-        // 停止各个任务
-        self.stop_tasks().await;
+        // 按顺序停机：分发 → 结果 → 在途份额 → 矿池 → 核心，整体不超过 shutdown_timeout_secs
+        let timeout = match self.full_config.general.shutdown_timeout_secs {
+            0 => shutdown::DEFAULT_SHUTDOWN_TIMEOUT,
+            secs => Duration::from_secs(secs),
+        };
+        let mut sequence = ShutdownSequence::new(timeout);
+        let tokens = self.shutdown_tokens.read().await.clone();
 
-        // 停止监控系统
-        {
-            let monitoring_system = self.monitoring_system.lock().await;
+        tokens.dispatch.cancel();
+        let started = Instant::now();
+        let main_loop = shutdown::join_until(self.main_loop_handle.lock().await.take(), sequence.deadline()).await;
+        let dispatch = shutdown::join_until(self.work_dispatch_handle.lock().await.take(), sequence.deadline()).await;
+        sequence.record("dispatch", started.elapsed(), main_loop && dispatch);
+
+        tokens.results.cancel();
+        let started = Instant::now();
+        let collection = shutdown::join_until(self.core_result_handle.lock().await.take(), sequence.deadline()).await;
+        let processing = shutdown::join_until(self.result_process_handle.lock().await.take(), sequence.deadline()).await;
+        sequence.record("results", started.elapsed(), collection && processing);
+
+        let pool_manager = self.pool_manager.clone();
+        sequence.run("shares", async move {
+            pool_manager.lock().await.wait_for_pending_submissions().await;
+        }).await;
+
+        // 其余后台任务不影响份额，直接中止
+        self.stop_tasks().await;
+        let monitoring_system = self.monitoring_system.clone();
+        let pool_manager = self.pool_manager.clone();
+        let pools = sequence.run("pools", async move {
             monitoring_system.stop().await?;
-        }
+            pool_manager.lock().await.stop().await?;
+            Ok::<(), MiningError>(())
+        }).await;
 
-        // 停止矿池管理器
-        {
-            let pool_manager = self.pool_manager.lock().await;
-            pool_manager.stop().await?;
-        }
+        let device_manager = self.device_manager.clone();
+        let cores = sequence.run("cores", async move {
+            device_manager.lock().await.stop().await
+        }).await;
 
-        // 停止设备管理器
-        {
-            let mut device_manager = self.device_manager.lock().await;
-            device_manager.stop().await?;
+        if sequence.completed() {
+            info!("Shutdown sequence finished in {}", sequence);
+        } else {
+            warn!("Shutdown sequence timed out after {}", sequence);
+        }
+        if let Some(result) = pools {
+            result?;
+        }
+        if let Some(result) = cores {
+            result?;
         }
 
         // 更新状态
@@ -385,6 +590,8 @@ impl MiningManager {
             timestamp: SystemTime::now(),
         }).await;
 
+        self.write_shutdown_report().await;
+
         // 显示停止总结
         let stats = self.get_stats().await;
         info!("Shutdown complete after {} runtime",
@@ -396,6 +603,41 @@ impl MiningManager {
         Ok(())
     }
 
+    /// 暂停挖矿：主循环不再从矿池取新工作，设备做完已分发的工作后空闲，矿池连接保持不变
+    pub async fn pause(&self) -> Result<(), MiningError> {
+        self.set_paused(true).await
+    }
+
+    /// 恢复暂停的挖矿
+    pub async fn resume(&self) -> Result<(), MiningError> {
+        self.set_paused(false).await
+    }
+
+    async fn set_paused(&self, paused: bool) -> Result<(), MiningError> {
+        let (from, to) = if paused {
+            (MiningState::Running, MiningState::Paused)
+        } else {
+            (MiningState::Paused, MiningState::Running)
+        };
+        {
+            let mut state = self.state.write().await;
+            if *state == to {
+                return Ok(());
+            }
+            if *state != from {
+                return Err(MiningError::System(format!("Cannot {} mining while {:?}", if paused { "pause" } else { "resume" }, *state)));
+            }
+            *state = to.clone();
+        }
+        info!("Mining {}", if paused { "paused" } else { "resumed" });
+        self.send_event(MiningEvent::StateChanged {
+            old_state: from,
+            new_state: to,
+            timestamp: SystemTime::now(),
+        }).await;
+        Ok(())
+    }
+
     /// 获取挖矿状态
     pub async fn get_state(&self) -> MiningState {
         self.state.read().await.clone()
@@ -403,16 +645,8 @@ impl MiningManager {
 
     /// 获取挖矿统计
     pub async fn get_stats(&self) -> MiningStats {
-        let mut stats = self.stats.write().await;
-        stats.update_uptime();
-
-        // 更新当前算力
-        if let Ok(device_manager) = self.device_manager.try_lock() {
-            let hashrate = device_manager.get_total_hashrate().await;
-            stats.update_hashrate(hashrate);
-        }
-
-        stats.clone()
+        // 算力由主循环和结果收集任务更新，这里只读取快照，不取任何锁
+        self.stats.snapshot()
     }
 
     /// 获取系统状态
@@ -437,6 +671,30 @@ impl MiningManager {
         }
     }
 
+    /// 获取按 (矿池, 设备) 统计的份额
+    pub async fn get_pool_device_stats(&self) -> Vec<PoolDeviceStats> {
+        self.pool_manager.lock().await.get_pool_device_stats().await
+    }
+
+    /// 崩溃报告使用的核心/设备状态快照
+    pub async fn crash_state(&self) -> serde_json::Value {
+        let status = self.get_system_status().await;
+        let device_manager = self.device_manager.lock().await;
+
+        serde_json::json!({
+            "state": format!("{:?}", status.state),
+            "uptime_secs": status.uptime.as_secs(),
+            "total_hashrate": status.total_hashrate,
+            "accepted_shares": status.accepted_shares,
+            "rejected_shares": status.rejected_shares,
+            "hardware_errors": status.hardware_errors,
+            "active_devices": status.active_devices,
+            "connected_pools": status.connected_pools,
+            "devices_by_core": device_manager.get_device_count_by_core().await,
+            "devices": device_manager.get_all_device_info().await,
+        })
+    }
+
     /// 订阅事件
     pub fn subscribe_events(&self) -> broadcast::Receiver<MiningEvent> {
         self.event_sender.subscribe()
@@ -452,81 +710,130 @@ impl MiningManager {
     /// 启动主循环
     async fn start_main_loop(&self) -> Result<(), MiningError> {
         let running = self.running.clone();
+        let state = self.state.clone();
         let stats = self.stats.clone();
         let device_manager = self.device_manager.clone();
         let pool_manager = self.pool_manager.clone();
         let _monitoring_system = self.monitoring_system.clone();
         let _event_sender = self.event_sender.clone();
         let work_sender = self.work_sender.clone();
-        let scan_interval = self.config.scan_interval;
-
-        let handle = tokio::spawn(async move {
-            let mut interval = interval(scan_interval);
+        let runtime_settings = self.runtime_settings.clone();
+        let mut scan_time = runtime_settings.read().await.scan_time;
+        let dispatch_token = self.shutdown_tokens.read().await.dispatch.clone();
+
+        let handle = tokio::spawn(task_watch::track("main-loop", async move {
+            let mut interval = interval(Duration::from_secs(scan_time));
+            let mut work_dropped = 0u64;
+            // 轮流为各设备取工作的位置
+            let mut next_device = 0usize;
+            // 队列发送端可以克隆，循环中不再每次锁 work_sender
+            let sender = work_sender.lock().await.clone();
+
+            while running.load(Ordering::Acquire) {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = dispatch_token.cancelled() => break,
+                }
 
-            while *running.read().await {
-                interval.tick().await;
+                // 扫描间隔在运行时被修改后重建定时器
+                let current = runtime_settings.read().await.scan_time;
+                if current != scan_time {
+                    scan_time = current;
+                    interval = tokio::time::interval(Duration::from_secs(scan_time));
+                    interval.tick().await;
+                }
 
-                // 更新统计信息
-                {
-                    let mut stats = stats.write().await;
-                    stats.update_uptime();
+                // 更新算力并取出可用设备：设备管理器的锁只在读取期间持有，不再因锁被占用而跳过
+                let (hashrate, device_ids) = {
+                    let device_manager = device_manager.lock().await;
+                    (device_manager.get_total_hashrate().await, Self::healthy_device_ids(&device_manager).await)
+                };
+                stats.update_hashrate(hashrate);
 
-                    // 获取设备算力
-                    if let Ok(device_manager) = device_manager.try_lock() {
-                        let hashrate = device_manager.get_total_hashrate().await;
-                        stats.update_hashrate(hashrate);
-                    }
+                // 暂停时不取新工作
+                if *state.read().await == MiningState::Paused {
+                    continue;
                 }
 
-                // 检查设备健康状态
-                if let Ok(_device_manager) = device_manager.try_lock() {
-                    // 这里可以添加设备健康检查逻辑
-                }
+                // 从矿池获取工作，发送到工作分发器之前释放矿池管理器的锁
+                let work_item = {
+                    let pool_manager = pool_manager.lock().await;
+                    match Self::next_work_item(&pool_manager, &device_ids, &mut next_device).await {
+                        Ok(work_item) => Some(work_item),
+                        Err(e) => {
+                            debug!("Failed to get work from pool: {}", e);
+                            None
+                        }
+                    }
+                };
 
-                // 检查矿池连接状态并获取工作
-                if let Ok(pool_manager) = pool_manager.try_lock() {
-                    // 获取工作并发送到工作分发器
-                    if let Ok(work_sender_guard) = work_sender.try_lock() {
-                        if let Some(sender) = work_sender_guard.as_ref() {
-                            // 尝试从矿池获取工作
-                                                    match pool_manager.get_work().await {
-                            Ok(work) => {
-                                let work_item = WorkItem {
-                                    work,
-                                    assigned_device: None, // 让工作分发器决定分配给哪个设备
-                                    created_at: SystemTime::now(),
-                                    priority: 1,
-                                    retry_count: 0,
-                                };
+                if let (Some(work_item), Some(sender)) = (work_item, sender.as_ref()) {
+                    if let Err(e) = sender.send(work_item).await {
+                        debug!("Failed to send work to dispatcher: {}", e);
+                    } else {
+                        debug!("Work sent to dispatcher");
+                    }
 
-                                if let Err(e) = sender.send(work_item) {
-                                    debug!("Failed to send work to dispatcher: {}", e);
-                                } else {
-                                    debug!("Work sent to dispatcher");
-                                }
-                            }
-                            Err(e) => {
-                                debug!("Failed to get work from pool: {}", e);
-                            }
-                        }
-                        }
+                    // 分发器跟不上时队列丢弃工作
+                    let dropped = sender.stats().dropped;
+                    if dropped > work_dropped {
+                        warn!("Work queue full, dropped {} work items ({} total)", dropped - work_dropped, dropped);
+                        work_dropped = dropped;
                     }
                 }
             }
-        });
+        }));
 
         *self.main_loop_handle.lock().await = Some(handle);
         Ok(())
     }
 
+    /// 健康设备的 ID，升序
+    async fn healthy_device_ids(device_manager: &DeviceManager) -> Vec<u32> {
+        let mut device_ids: Vec<u32> = device_manager.get_all_device_info().await
+            .into_iter()
+            .filter(|info| info.is_healthy())
+            .map(|info| info.id)
+            .collect();
+        device_ids.sort_unstable();
+        device_ids
+    }
+
+    /// 轮流为下一个设备取工作：Stratum 工作的 extranonce2 取自该设备的子区间
+    /// (`pools.extranonce2_partitions`)，工作指定给该设备。没有设备时按设备 0 取工作，由分发器决定去向
+    async fn next_work_item(pool_manager: &PoolManager, device_ids: &[u32], next_device: &mut usize) -> Result<WorkItem, PoolError> {
+        let assigned_device = match device_ids.len() {
+            0 => None,
+            len => {
+                let device_id = device_ids[*next_device % len];
+                *next_device = next_device.wrapping_add(1);
+                Some(device_id)
+            }
+        };
+        let work = pool_manager.get_work_for_device(assigned_device.unwrap_or(0)).await?;
+        Ok(WorkItem {
+            work,
+            assigned_device,
+            created_at: SystemTime::now(),
+            priority: 1,
+            retry_count: 0,
+            share_difficulty: None,
+            algorithm: pool_manager.active_algorithm().await,
+        })
+    }
+
     /// 启动统一工作分发器
     async fn start_work_dispatch(&self) -> Result<(), MiningError> {
         let running = self.running.clone();
         let device_manager = self.device_manager.clone();
         let core_registry = self.core_registry.clone();
         let work_receiver = self.work_receiver.clone();
+        let share_filter = self.share_filter.clone();
+        let core_configs = self.core_configs.clone();
+        let priority = self.full_config.cores.priority.clone();
+        let dispatch_token = self.shutdown_tokens.read().await.dispatch.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = tokio::spawn(task_watch::track("work-dispatch", alloc_audit::instrument(Subsystem::WorkDispatch, async move {
             let receiver = work_receiver.lock().await.take();
             if let Some(mut receiver) = receiver {
                 debug!("Work dispatcher started");
@@ -535,20 +842,39 @@ impl MiningManager {
                 let work_dispatcher = UnifiedWorkDispatcher::new(
                     core_registry.clone(),
                     device_manager.clone(),
+                    share_filter.clone(),
+                    core_configs,
+                    priority,
                 );
 
-                while *running.read().await {
-                    match receiver.recv().await {
+                while running.load(Ordering::Acquire) {
+                    // 停机时不再取新工作，正在分发的一批先分发完
+                    let next = tokio::select! {
+                        next = receiver.recv() => next,
+                        _ = dispatch_token.cancelled() => break,
+                    };
+                    match next {
                         Some(work_item) => {
                             debug!("Received work item: {}", work_item.work.id);
 
-                            // 使用统一的工作分发逻辑
-                            match work_dispatcher.dispatch_work(work_item).await {
-                                Ok(target) => {
-                                    debug!("Work dispatched to: {}", target);
+                            // 顺带取出已排队的工作，一次唤醒分发整批
+                            let mut batch = vec![work_item];
+                            while batch.len() < MAX_DISPATCH_BATCH {
+                                match receiver.try_recv() {
+                                    Some(item) => batch.push(item),
+                                    None => break,
                                 }
-                                Err(e) => {
-                                    debug!("Work dispatch failed: {}", e);
+                            }
+
+                            // 使用统一的工作分发逻辑
+                            for result in work_dispatcher.dispatch_batch(batch).await {
+                                match result {
+                                    Ok(target) => {
+                                        debug!("Work dispatched to: {}", target);
+                                    }
+                                    Err(e) => {
+                                        debug!("Work dispatch failed: {}", e);
+                                    }
                                 }
                             }
                         }
@@ -563,7 +889,7 @@ impl MiningManager {
             } else {
                 error!("Cannot get work receiver");
             }
-        });
+        })));
 
         *self.work_dispatch_handle.lock().await = Some(handle);
         Ok(())
@@ -572,29 +898,25 @@ impl MiningManager {
     /// 启动结果处理
     async fn start_result_processing(&self) -> Result<(), MiningError> {
         let running = self.running.clone();
-        let pool_manager = self.pool_manager.clone();
         let stats = self.stats.clone();
         let result_receiver = self.result_receiver.clone();
         let event_sender = self.event_sender.clone();
+        let results_token = self.shutdown_tokens.read().await.results.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = tokio::spawn(task_watch::track("result-processing", async move {
             let receiver = result_receiver.lock().await.take();
             if let Some(mut receiver) = receiver {
-                while *running.read().await {
-                    match receiver.recv().await {
+                while running.load(Ordering::Acquire) {
+                    let next = tokio::select! {
+                        next = receiver.recv() => next,
+                        _ = results_token.cancelled() => break,
+                    };
+                    match next {
                         Some(result_item) => {
                             // 处理挖矿结果
                             if result_item.is_valid() {
-                                // 提交到矿池
-                                if let Ok(_pool_manager) = pool_manager.try_lock() {
-                                    // 这里需要实现份额提交逻辑
-                                }
-
                                 // 更新统计
-                                {
-                                    let mut stats = stats.write().await;
-                                    stats.record_accepted_share(result_item.result.share_difficulty);
-                                }
+                                stats.record_accepted_share(result_item.result.share_difficulty);
 
                                 // 发送事件
                                 let _ = event_sender.send(MiningEvent::ShareAccepted {
@@ -608,7 +930,7 @@ impl MiningManager {
                     }
                 }
             }
-        });
+        }));
 
         *self.result_process_handle.lock().await = Some(handle);
         Ok(())
@@ -616,25 +938,46 @@ impl MiningManager {
 
     /// 启动核心结果收集
     async fn start_core_result_collection(&self) -> Result<(), MiningError> {
-        let running = self.running.clone();
         let core_registry = self.core_registry.clone();
         let _result_sender = self.result_sender.clone(); // 暂时不使用，因为我们不创建假的WorkItem
         let stats = self.stats.clone();
-        let _pool_manager = self.pool_manager.clone(); // 暂时不使用，因为缺少工作数据
+        let pool_manager = self.pool_manager.clone();
+        let device_manager = self.device_manager.clone();
+        let share_filter = self.share_filter.clone();
+        let network = self.full_config.pools.network;
+        let monitoring_system = self.monitoring_system.clone();
+        let event_sender = self.event_sender.clone();
         let core_result_handle = self.core_result_handle.clone();
-        let result_collection_interval = self.config.result_collection_interval;
-
-        let handle = tokio::spawn(async move {
-            // 确保间隔不为零，最小值为1毫秒
-            let safe_interval = if result_collection_interval.is_zero() {
-                Duration::from_millis(20) // 默认20毫秒
-            } else {
-                result_collection_interval
-            };
-            let mut interval = interval(safe_interval); // 使用安全的结果收集间隔
+        let runtime_settings = self.runtime_settings.clone();
+        let mut collection_ms = runtime_settings.read().await.result_collection_interval_ms;
+        let max_collection_ms = self.full_config.general.result_collection_max_interval_ms;
+        let results_token = self.shutdown_tokens.read().await.results.clone();
+
+        let handle = tokio::spawn(task_watch::track("result-collection", alloc_audit::instrument(Subsystem::ResultCollection, async move {
+            // 空闲时轮询间隔逐步放宽，有结果时回到 result_collection_interval_ms
+            let mut poll = AdaptivePoll::new(collection_ms, max_collection_ms);
+            let mut core_stats_at: Option<Instant> = None;
+
+            loop {
+                // 停机时立即再收集一轮，提交其中的份额后退出
+                let draining = tokio::select! {
+                    _ = tokio::time::sleep(poll.current()) => results_token.is_cancelled(),
+                    _ = results_token.cancelled() => true,
+                };
+
+                // 结果收集间隔在运行时被修改后按新的基础间隔轮询
+                let current = runtime_settings.read().await.result_collection_interval_ms;
+                if current != collection_ms {
+                    collection_ms = current;
+                    poll.set_base(collection_ms);
+                }
 
-            while *running.read().await {
-                interval.tick().await;
+                // 本轮收集到的结果数，决定下一次的轮询间隔
+                let mut collected = 0usize;
+                // 本轮通过过滤的份额一起流水线提交，低难度高结果率时不再逐个等待矿池响应
+                let mut pending_shares = Vec::new();
+                // 核心算力统计按固定间隔刷新，不跟随结果轮询
+                let refresh_core_stats = core_stats_at.map_or(true, |at| at.elapsed() >= CORE_STATS_INTERVAL);
 
                 // 从核心注册表获取所有活跃核心并收集结果
                 match core_registry.list_active_cores().await {
@@ -643,42 +986,17 @@ impl MiningManager {
                             debug!("Collecting results from {} active cores", active_core_ids.len());
                         }
                         for core_id in active_core_ids {
-                            // 从核心注册表收集结果
+                            // 从核心注册表轮询结果，有效份额和应用层设备的份额一起提交
                             match core_registry.collect_results_from_core(&core_id).await {
                                 Ok(results) => {
+                                    collected += results.len();
                                     for core_result in results {
-                                        // 转换核心结果到本地格式（work_id已经是UUID）
-                                        let mut mining_result = cgminer_core::types::MiningResult::new(
-                                            core_result.work_id,
-                                            core_result.device_id,
-                                            core_result.nonce,
-                                            core_result.hash,
-                                            core_result.meets_target,
-                                        );
-
-                                        // 设置extranonce2
-                                        if core_result.extranonce2.len() >= 4 {
-                                            mining_result = mining_result.with_extranonce2(core_result.extranonce2);
-                                        }
-
-                                        // 计算份额难度
-                                        if let Err(e) = mining_result.calculate_share_difficulty() {
-                                            warn!("Failed to calculate share difficulty: {}", e);
-                                        }
-
-                                                                // 处理真实挖矿结果
-                        if core_result.meets_target {
-                            info!("Valid share found from core {}, device {}", core_id, core_result.device_id);
-
-                            // 记录找到的有效份额（只有真正找到时才记录）
-                            {
-                                let mut stats_guard = stats.write().await;
-                                stats_guard.record_accepted_share(mining_result.share_difficulty);
-                            }
-                        }
-                        // 注意：大部分哈希结果都不会满足目标难度，这是正常的
-                        // 只有极少数结果会满足难度要求并成为有效份额
+                                        if let Some(share) = core_results::record_core_result(
+                                            &core_id, core_result, &stats, &share_filter, network, Some(&pool_manager),
+                                        ).await {
+                                            pending_shares.push(share);
                                         }
+                                    }
                                 }
                                 Err(e) => {
                                     debug!("No results from core {}: {}", core_id, e);
@@ -686,25 +1004,89 @@ impl MiningManager {
                             }
 
                             // 获取核心的算力统计
-                            match core_registry.get_core_stats(&core_id).await {
-                                Ok(core_stats) => {
-                                    // 更新总体算力统计
-                                    let mut stats_guard = stats.write().await;
-                                    stats_guard.current_hashrate = core_stats.total_hashrate;
-                                    stats_guard.average_hashrate = core_stats.average_hashrate;
-                                }
-                                Err(e) => {
-                                    debug!("Failed to get stats from core {}: {}", core_id, e);
+                            if refresh_core_stats {
+                                match core_registry.get_core_stats(&core_id).await {
+                                    Ok(core_stats) => {
+                                        // 更新总体算力统计
+                                        stats.set_hashrates(core_stats.total_hashrate, core_stats.average_hashrate);
+                                    }
+                                    Err(e) => {
+                                        debug!("Failed to get stats from core {}: {}", core_id, e);
+                                    }
                                 }
                             }
                         }
+                        if refresh_core_stats {
+                            core_stats_at = Some(Instant::now());
+                        }
                     }
                     Err(e) => {
                         debug!("Failed to list active cores: {}", e);
                     }
                 }
+
+                // 收集应用层设备（如模拟设备）直接产生的结果，锁只在收集期间持有
+                let device_results = device_manager.lock().await.collect_device_results().await;
+                collected += device_results.len();
+                let checks = pool_manager.lock().await.verify_mining_results(&device_results).await;
+                for (mining_result, check) in device_results.into_iter().zip(checks) {
+                    if !mining_result.meets_target {
+                        continue;
+                    }
+
+                    // 本地重新计算的哈希不满足份额目标：计为设备硬件错误，不提交
+                    if let ShareCheck::Invalid { difficulty } = check {
+                        let error = format!("nonce {:08x} hashes to difficulty {:.4}, below the share target", mining_result.nonce, difficulty);
+                        warn!("Device {} hardware error: {}", mining_result.device_id, error);
+                        stats.record_hardware_error();
+                        device_manager.lock().await.record_hardware_error(mining_result.device_id).await;
+                        let _ = event_sender.send(MiningEvent::HardwareError {
+                            device_id: mining_result.device_id,
+                            error,
+                            timestamp: SystemTime::now(),
+                        });
+                        continue;
+                    }
+
+                    if !share_filter.passes(&mining_result.work_id, mining_result.share_difficulty).await {
+                        stats.record_below_target();
+                        continue;
+                    }
+
+                    stats.record_accepted_share(mining_result.share_difficulty);
+                    pending_shares.push(mining_result);
+                }
+
+                if !pending_shares.is_empty() {
+                    let outcomes = pool_manager.lock().await.submit_mining_results(&pending_shares).await;
+                    for (mining_result, outcome) in pending_shares.iter().zip(outcomes) {
+                        let (accepted, reason) = match outcome {
+                            Ok(accepted) => {
+                                debug!("Device {} share submitted, accepted: {}", mining_result.device_id, accepted);
+                                (accepted, None)
+                            }
+                            Err(e) => {
+                                debug!("Failed to submit share from device {}: {}", mining_result.device_id, e);
+                                (false, Some(e.to_string()))
+                            }
+                        };
+                        monitoring_system.record_share(RecentShare {
+                            timestamp: SystemTime::now(),
+                            device_id: mining_result.device_id,
+                            difficulty: mining_result.share_difficulty,
+                            accepted,
+                            reason,
+                        }).await;
+                    }
+                }
+
+                poll.record(collected);
+                if draining {
+                    debug!("Result collection drained {} results before shutdown", collected);
+                    break;
+                }
             }
-        });
+        })));
 
         // 存储任务句柄
         *core_result_handle.lock().await = Some(handle);
@@ -724,12 +1106,13 @@ impl MiningManager {
                     // 按照优先级选择最优核心：asic > gpu > cpu
                     let selected_core = self.select_optimal_core(&active_cores).await?;
 
-                    info!("Selected optimal core: {} (priority: asic > gpu > cpu)", selected_core);
+                    info!("Selected optimal core: {}", selected_core);
 
                     // 只启动选中的最优核心
-                    match self.core_registry.start_core(&selected_core).await {
+                    match self.core_lifecycle.start_core(&selected_core).await {
                         Ok(()) => {
                             info!("Started 1 mining core: {}", selected_core);
+                            *self.selected_core.write().await = Some(selected_core);
                             return Ok(());
                         }
                         Err(e) => {
@@ -776,19 +1159,54 @@ impl MiningManager {
                         custom_params: {
                             let mut params = std::collections::HashMap::new();
                             if let Some(cpu_btc_config) = &self.full_config.cores.cpu_btc {
-                                params.insert("device_count".to_string(), serde_json::Value::Number(serde_json::Number::from(cpu_btc_config.device_count)));
+                                let device_count = match cpu_btc_config.device_count {
+                                    DeviceCount::Fixed(count) => count,
+                                    DeviceCount::Auto => {
+                                        let sizing = ThreadSizing::plan(CpuTopology::detect(), cpu_btc_config.reserved_cores);
+                                        info!("🧮 CPU core auto-sized to {}", sizing);
+                                        sizing.device_count
+                                    }
+                                };
+                                params.insert("device_count".to_string(), serde_json::Value::Number(serde_json::Number::from(device_count)));
+                                if let Some(affinity) = cpu_btc_config.cpu_affinity.as_ref().filter(|a| a.enabled) {
+                                    let mut affinity_param = serde_json::to_value(affinity).unwrap_or_default();
+                                    if affinity.strategy == "numa" {
+                                        // 每个设备的线程绑定到所在节点的 CPU，缓冲区在本节点分配
+                                        let nodes = cpu_topology::numa_nodes();
+                                        let placement = cpu_topology::plan_numa_placement(&nodes, device_count);
+                                        for node in &nodes {
+                                            let devices: Vec<u32> = placement.iter()
+                                                .filter(|p| p.numa_node == node.id)
+                                                .map(|p| p.device)
+                                                .collect();
+                                            info!("🧩 NUMA node {} ({} CPUs): devices {:?}", node.id, node.cpus.len(), devices);
+                                        }
+                                        affinity_param["numa_placement"] = serde_json::to_value(&placement).unwrap_or_default();
+                                        affinity_param["numa_local_alloc"] = serde_json::Value::Bool(true);
+                                    }
+                                    params.insert("cpu_affinity".to_string(), affinity_param);
+                                }
                                 params.insert("min_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(cpu_btc_config.min_hashrate).unwrap()));
                                 params.insert("max_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(cpu_btc_config.max_hashrate).unwrap()));
                                 params.insert("error_rate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(cpu_btc_config.error_rate).unwrap()));
                                 params.insert("batch_size".to_string(), serde_json::Value::Number(serde_json::Number::from(cpu_btc_config.batch_size)));
                                 params.insert("work_timeout_ms".to_string(), serde_json::Value::Number(serde_json::Number::from(cpu_btc_config.work_timeout_ms)));
+                                let backend = cpu_btc_config.hash_backend.resolve()
+                                    .map_err(MiningError::ConfigError)?;
+                                info!("🔢 CPU hash backend requested: {} ({})", backend,
+                                      if cpu_btc_config.hash_backend == HashBackend::Auto { "auto-detected" } else { "configured" });
+                                params.insert("hash_backend".to_string(), serde_json::Value::String(backend.to_string()));
+                                if !cpu_btc_config.worker_priority.is_default() {
+                                    info!("🪶 CPU worker threads: {}", cpu_btc_config.worker_priority);
+                                    params.extend(cpu_btc_config.worker_priority.core_params());
+                                }
                             }
                             params
                         },
                     };
 
                     // 创建软算法核心（不启动）
-                    let core_id = self.create_core("cpu-btc", core_config).await?;
+                    let core_id = self.create_core(factory_info, "cpu-btc", core_config).await?;
 
                     // 检查核心是否创建成功
                     if self.core_registry.get_core(&core_id).await
@@ -805,32 +1223,13 @@ impl MiningManager {
                         name: "gpu_core".to_string(),
                         enabled: true,
                         devices: vec![],
-                        custom_params: {
-                            let mut params = std::collections::HashMap::new();
-                            if let Some(gpu_btc_config) = &self.full_config.cores.gpu_btc {
-                                params.insert("device_count".to_string(), serde_json::Value::Number(serde_json::Number::from(gpu_btc_config.device_count)));
-                                params.insert("max_hashrate".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(gpu_btc_config.max_hashrate).unwrap()));
-                                params.insert("work_size".to_string(), serde_json::Value::Number(serde_json::Number::from(gpu_btc_config.work_size)));
-                                params.insert("work_timeout_ms".to_string(), serde_json::Value::Number(serde_json::Number::from(gpu_btc_config.work_timeout_ms)));
-
-                                // 平台特定配置
-                                #[cfg(target_os = "macos")]
-                                {
-                                    params.insert("backend".to_string(), serde_json::Value::String("metal".to_string()));
-                                    params.insert("threads_per_threadgroup".to_string(), serde_json::Value::Number(serde_json::Number::from(512)));
-                                }
-
-                                #[cfg(not(target_os = "macos"))]
-                                {
-                                    params.insert("backend".to_string(), serde_json::Value::String("opencl".to_string()));
-                                }
-                            }
-                            params
-                        },
+                        custom_params: self.full_config.cores.gpu_btc.as_ref()
+                            .map(|gpu_btc_config| gpu_btc_config.core_params())
+                            .unwrap_or_default(),
                     };
 
                     // 创建GPU核心（不启动）
-                    let core_id = self.create_core("gpu-btc", core_config).await?;
+                    let core_id = self.create_core(factory_info, "gpu-btc", core_config).await?;
 
                     // 检查核心是否创建成功
                     if self.core_registry.get_core(&core_id).await
@@ -861,7 +1260,7 @@ impl MiningManager {
                             };
 
                             // 创建ASIC核心（不启动）
-                            let core_id = self.create_core("maijie-l7", core_config).await?;
+                            let core_id = self.create_core(factory_info, "maijie-l7", core_config).await?;
 
                             if self.core_registry.get_core(&core_id).await
                                 .map_err(|e| MiningError::CoreError(format!("获取核心失败: {}", e)))?.is_some() {
@@ -872,7 +1271,25 @@ impl MiningManager {
                     }
                 }
                 _ => {
-                    debug!("Unknown core factory: {}", factory_info.name);
+                    let Some(plugin) = crate::core_loader::plugin_for_factory(&factory_info.name) else {
+                        debug!("Unknown core factory: {}", factory_info.name);
+                        continue;
+                    };
+                    debug!("Creating plugin core {} from {}", plugin.key, plugin.path.display());
+
+                    let core_config = CoreConfig {
+                        name: format!("{}_core", plugin.key),
+                        enabled: true,
+                        devices: vec![],
+                        custom_params: self.full_config.cores.plugins.params.get(&plugin.key).cloned().unwrap_or_default(),
+                    };
+                    let core_id = self.create_core(factory_info, &plugin.key, core_config).await?;
+
+                    if self.core_registry.get_core(&core_id).await
+                        .map_err(|e| MiningError::CoreError(format!("获取核心失败: {}", e)))?.is_some() {
+                        debug!("Plugin core created: {}", core_id);
+                        created_cores.push(core_id);
+                    }
                 }
             }
         }
@@ -884,9 +1301,10 @@ impl MiningManager {
             if created_cores.len() == 1 {
                 // 只有一个核心，直接启动
                 let core_id = &created_cores[0];
-                match self.core_registry.start_core(core_id).await {
+                match self.core_lifecycle.start_core(core_id).await {
                     Ok(()) => {
                         info!("Started mining core: {}", core_id);
+                        *self.selected_core.write().await = Some(core_id.clone());
                     }
                     Err(e) => {
                         error!("Failed to start core {}: {}", core_id, e);
@@ -897,25 +1315,29 @@ impl MiningManager {
                 // 多个核心，使用优先级选择
                 let selected_core = self.select_optimal_core(&created_cores).await?;
 
-                info!("Selected optimal core: {} (priority: asic > gpu > cpu)", selected_core);
+                info!("Selected optimal core: {}", selected_core);
 
                 // **关键修复**：移除未选中的核心，避免工作分发到错误的核心
                 info!("🧹 开始卸载未选中的核心，确保资源完全释放");
 
                 let mut removed_cores = Vec::new();
+                let mut standby = Vec::new();
                 for core_id in &created_cores {
                     if core_id != &selected_core {
                         info!("🗑️  正在卸载未选中的核心: {}", core_id);
 
                         // 1. 先停止核心（如果已启动）
-                        if let Err(e) = self.core_registry.stop_core(core_id).await {
+                        if let Err(e) = self.core_lifecycle.stop_core(core_id).await {
                             debug!("核心 {} 停止失败（可能未启动）: {}", core_id, e);
                         }
 
-                        // 2. 从注册表中完全移除核心
-                        match self.core_registry.remove_core(core_id).await {
+                        // 2. 从注册表中完全移除核心，保留其配置作为故障转移的备用核心
+                        match self.core_lifecycle.remove_core(core_id).await {
                             Ok(()) => {
                                 info!("✅ 成功卸载核心: {}", core_id);
+                                if let Some(spec) = self.core_configs.write().await.remove(core_id) {
+                                    standby.push((core_rank(&self.full_config.cores.priority, Some(&spec)), spec));
+                                }
                                 removed_cores.push(core_id.clone());
                             }
                             Err(e) => {
@@ -950,10 +1372,13 @@ impl MiningManager {
 
                 info!("🎯 核心选择完成 - 已选择: {}, 已卸载: {} 个多余核心",
                       selected_core, removed_cores.len());
+                standby.sort_by_key(|(priority, _)| *priority);
+                *self.standby_cores.write().await = standby.into_iter().map(|(_, spec)| spec).collect();
 
-                match self.core_registry.start_core(&selected_core).await {
+                match self.core_lifecycle.start_core(&selected_core).await {
                     Ok(()) => {
                         info!("Started optimal mining core: {}", selected_core);
+                        *self.selected_core.write().await = Some(selected_core.clone());
                     }
                     Err(e) => {
                         error!("Failed to start selected core {}: {}", selected_core, e);
@@ -968,54 +1393,32 @@ impl MiningManager {
         Ok(())
     }
 
-    /// 按照优先级选择最优核心：asic > gpu > cpu
+    /// 按 `cores.priority` 和核心的类型信息选择最优核心
     async fn select_optimal_core(&self, active_cores: &[String]) -> Result<String, MiningError> {
         debug!("Selecting optimal core from {} candidates", active_cores.len());
 
-        // 定义核心类型优先级（数字越小优先级越高）
-        let get_core_priority = |core_id: &str| -> u8 {
-            if core_id.contains("asic") || core_id.contains("maijie") || core_id.contains("l7") {
-                1 // ASIC 最高优先级
-            } else if core_id.contains("gpu") {
-                2 // GPU 中等优先级 - 必须在CPU判断之前！
-            } else if core_id.contains("cpu") || core_id.contains("software") {
-                3 // CPU 最低优先级 - 移除了"btc"关键字避免与GPU冲突
-            } else {
-                4 // 未知类型，最低优先级
-            }
-        };
-
-        // 按优先级排序核心
-        let mut sorted_cores: Vec<(String, u8)> = active_cores
+        let specs = self.core_configs.read().await;
+        let priority = &self.full_config.cores.priority;
+        let mut sorted_cores: Vec<(&String, usize)> = active_cores
             .iter()
-            .map(|core_id| (core_id.clone(), get_core_priority(core_id)))
+            .map(|core_id| (core_id, core_rank(priority, specs.get(core_id))))
             .collect();
+        // 稳定排序，同一优先级保持注册表顺序
+        sorted_cores.sort_by_key(|(_, rank)| *rank);
 
-        sorted_cores.sort_by_key(|(_, priority)| *priority);
-
-        // 输出优先级信息
-        for (core_id, priority) in &sorted_cores {
-            let core_type = match priority {
-                1 => "ASIC",
-                2 => "GPU",
-                3 => "CPU",
-                _ => "Unknown",
-            };
-            debug!("Core: {} -> Type: {} (Priority: {})", core_id, core_type, priority);
+        let core_label = |core_id: &str| {
+            specs.get(core_id).map_or_else(|| "unknown".to_string(), |spec| format!("{} ({:?})", spec.core_type, spec.kind))
+        };
+        for (core_id, rank) in &sorted_cores {
+            debug!("Core: {} -> Type: {} (Rank: {})", core_id, core_label(core_id), rank);
         }
 
-        // 选择最高优先级的核心
-        if let Some((selected_core, priority)) = sorted_cores.first() {
-            let core_type = match priority {
-                1 => "ASIC",
-                2 => "GPU",
-                3 => "CPU",
-                _ => "Unknown",
-            };
-            info!("Selected {} core: {} (highest priority)", core_type, selected_core);
-            Ok(selected_core.clone())
-        } else {
-            Err(MiningError::CoreError("No cores available for selection".to_string()))
+        match sorted_cores.first() {
+            Some((selected_core, _)) => {
+                info!("Selected {} core: {} (highest priority)", core_label(selected_core), selected_core);
+                Ok((*selected_core).clone())
+            }
+            None => Err(MiningError::CoreError("No cores available for selection".to_string())),
         }
     }
 
@@ -1037,74 +1440,632 @@ impl MiningManager {
         let _monitoring_system = self.monitoring_system.clone();
         let running = self.running.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = tokio::spawn(task_watch::track("hashmeter", async move {
             let mut interval = interval(Duration::from_secs(5)); // 每5秒更新一次数据
 
-            while *running.read().await {
+            while running.load(Ordering::Acquire) {
                 interval.tick().await;
 
-                // 检查是否有hashmeter
-                let hashmeter_guard = hashmeter.lock().await;
-                if let Some(hashmeter) = hashmeter_guard.as_ref() {
-                    // 获取挖矿统计数据
-                    let stats_guard = stats.read().await;
-
-                    // 获取活跃设备数量（从设备管理器获取真实数量）
-                    let active_devices = if let Ok(device_mgr) = device_manager.try_lock() {
-                        device_mgr.get_active_device_count().await
-                    } else {
-                        0 // 如果设备管理器被锁定，显示0
-                    };
+                // 没有算力计量器时不收集数据
+                if hashmeter.lock().await.is_none() {
+                    continue;
+                }
 
-                    // 获取连接的矿池数量
-                    let connected_pools = 1; // 暂时固定为1，表示有活跃的矿池连接
-
-                    let mining_metrics = MiningMetrics {
-                        timestamp: SystemTime::now(),
-                        total_hashrate: stats_guard.current_hashrate,
-                        accepted_shares: stats_guard.accepted_shares,
-                        rejected_shares: stats_guard.rejected_shares,
-                        hardware_errors: stats_guard.hardware_errors,
-                        stale_shares: stats_guard.stale_shares,
-                        best_share: stats_guard.best_share,
-                        current_difficulty: stats_guard.current_difficulty,
-                        network_difficulty: stats_guard.network_difficulty,
-                        blocks_found: stats_guard.blocks_found,
-                        efficiency: stats_guard.efficiency,
-                        active_devices,
-                        connected_pools,
-                    };
+                // 统计快照不取锁；设备管理器的锁只在读取设备数据期间持有，不再因锁被占用而显示 0 台设备
+                let stats_snapshot = stats.snapshot();
+                let (active_devices, device_stats) = {
+                    let device_mgr = device_manager.lock().await;
+                    let active_devices = device_mgr.get_active_device_count().await;
+                    let mut device_stats = Vec::new();
+                    for device_info in device_mgr.get_all_device_info().await {
+                        if let Ok(device_stats_core) = device_mgr.get_device_stats_core(device_info.id).await {
+                            device_stats.push((device_info.id, device_stats_core));
+                        }
+                    }
+                    (active_devices, device_stats)
+                };
+
+                // 获取连接的矿池数量
+                let connected_pools = 1; // 暂时固定为1，表示有活跃的矿池连接
+
+                let mining_metrics = MiningMetrics {
+                    timestamp: SystemTime::now(),
+                    total_hashrate: stats_snapshot.current_hashrate,
+                    accepted_shares: stats_snapshot.accepted_shares,
+                    rejected_shares: stats_snapshot.rejected_shares,
+                    hardware_errors: stats_snapshot.hardware_errors,
+                    stale_shares: stats_snapshot.stale_shares,
+                    best_share: stats_snapshot.best_share,
+                    current_difficulty: stats_snapshot.current_difficulty,
+                    network_difficulty: stats_snapshot.network_difficulty,
+                    blocks_found: stats_snapshot.blocks_found,
+                    efficiency: stats_snapshot.efficiency,
+                    active_devices,
+                    connected_pools,
+                };
 
+                let hashmeter_guard = hashmeter.lock().await;
+                if let Some(hashmeter) = hashmeter_guard.as_ref() {
                     // 更新总体统计
                     if let Err(e) = hashmeter.update_total_stats(&mining_metrics).await {
                         warn!("Failed to update hashmeter total stats: {}", e);
                     }
 
-                    // 更新设备级统计数据 - 从设备管理器获取真实的设备统计
-                    if let Ok(device_mgr) = device_manager.try_lock() {
-                        // 获取所有设备信息
-                        let device_infos = device_mgr.get_all_device_info().await;
-
-                        // 为每个设备更新统计信息
-                        for device_info in device_infos {
-                            // 尝试获取设备的核心统计信息
-                            if let Ok(device_stats_core) = device_mgr.get_device_stats_core(device_info.id).await {
-                                // 更新设备统计到算力计量器
-                                if let Err(e) = hashmeter.update_device_stats(&device_stats_core).await {
-                                    debug!("Failed to update device {} stats: {}", device_info.id, e);
-                                }
-                            }
+                    // 更新设备级统计数据
+                    for (device_id, device_stats_core) in &device_stats {
+                        if let Err(e) = hashmeter.update_device_stats(device_stats_core).await {
+                            debug!("Failed to update device {} stats: {}", device_id, e);
                         }
                     }
                 }
             }
-        });
+        }));
 
         *self.hashmeter_update_handle.lock().await = Some(handle);
         Ok(())
     }
 
-    /// 停止所有任务
+    /// 启动核心健康检查，选中的核心不健康时切换到下一优先级的备用核心
+    async fn start_core_health_check(&self) {
+        let failover = self.full_config.cores.failover.clone();
+        if !failover.enabled {
+            return;
+        }
+        let core_registry = self.core_registry.clone();
+        let core_lifecycle = self.core_lifecycle.clone();
+        let core_configs = self.core_configs.clone();
+        let selected_core = self.selected_core.clone();
+        let standby_cores = self.standby_cores.clone();
+        let device_core_mapper = self.device_core_mapper.clone();
+        let monitoring_system = self.monitoring_system.clone();
+        let running = self.running.clone();
+
+        let handle = tokio::spawn(task_watch::track("core-health-check", async move {
+            let mut interval = interval(Duration::from_secs(failover.check_interval_secs.max(1)));
+            let mut tracker = HealthTracker::new(&failover, Instant::now());
+            // 没有可用的备用核心时只告警一次，核心恢复后重新告警
+            let mut alerted = false;
+
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                let Some(core_id) = selected_core.read().await.clone() else {
+                    continue;
+                };
+
+                // 取不到统计或算力为 0 (如 ASIC 链全部掉线) 视为不健康
+                let healthy = match core_registry.get_core_stats(&core_id).await {
+                    Ok(core_stats) => core_stats.total_hashrate > 0.0,
+                    Err(e) => {
+                        debug!("Health check of core {} failed: {}", core_id, e);
+                        false
+                    }
+                };
+                if !tracker.observe(healthy, Instant::now()) {
+                    alerted &= !healthy;
+                    continue;
+                }
+
+                let strikes = tracker.strikes();
+                let Some(replacement) = Self::start_standby_core(&core_lifecycle, &core_configs, &standby_cores).await else {
+                    if !alerted {
+                        error!("❌ Core {} is unhealthy after {} checks and no standby core is available", core_id, strikes);
+                        let alert = Alert::new(
+                            AlertType::Mining,
+                            AlertSeverity::Critical,
+                            "Core Unhealthy".to_string(),
+                            format!("Core {} failed {} health checks and there is no standby core to fail over to", core_id, strikes),
+                            "mining".to_string(),
+                        )
+                        .with_label("core".to_string(), core_id.clone());
+                        monitoring_system.raise_alert(alert).await;
+                        alerted = true;
+                    }
+                    continue;
+                };
+
+                // 新核心已启动，卸载不健康的核心，避免工作继续分发给它
+                if let Err(e) = core_lifecycle.stop_core(&core_id).await {
+                    debug!("核心 {} 停止失败: {}", core_id, e);
+                }
+                if let Err(e) = core_lifecycle.remove_core(&core_id).await {
+                    warn!("❌ 核心 {} 卸载失败: {}", core_id, e);
+                }
+                core_configs.write().await.remove(&core_id);
+                if let Err(e) = device_core_mapper.cleanup_core_mappings(&core_id).await {
+                    warn!("清理核心 {} 的设备映射失败: {}", core_id, e);
+                }
+                *selected_core.write().await = Some(replacement.clone());
+                tracker.reset(Instant::now());
+                alerted = false;
+
+                warn!("🔀 Core {} failed {} health checks, failed over to {}", core_id, strikes, replacement);
+                let alert = Alert::new(
+                    AlertType::Mining,
+                    AlertSeverity::Critical,
+                    "Core Failover".to_string(),
+                    format!("Core {} failed {} health checks; mining continues on {}", core_id, strikes, replacement),
+                    "mining".to_string(),
+                )
+                .with_label("core".to_string(), core_id)
+                .with_label("replacement".to_string(), replacement);
+                monitoring_system.raise_alert(alert).await;
+            }
+        }));
+
+        *self.core_health_handle.lock().await = Some(handle);
+    }
+
+    /// 按 `monitoring.recovery` 检查总算力，运行中持续为 0 时依次重启核心、重启设备，最后请求退出进程
+    async fn start_zero_hashrate_recovery(&self) {
+        let config = self.full_config.monitoring.recovery.clone();
+        if !self.full_config.monitoring.enabled || !config.enabled {
+            return;
+        }
+        let stats = self.stats.clone();
+        let state = self.state.clone();
+        let core_lifecycle = self.core_lifecycle.clone();
+        let core_configs = self.core_configs.clone();
+        let device_manager = self.device_manager.clone();
+        let monitoring_system = self.monitoring_system.clone();
+        let recovery_exit = self.recovery_exit.clone();
+        let running = self.running.clone();
+
+        let handle = tokio::spawn(task_watch::track("zero-hashrate-recovery", async move {
+            let mut interval = interval(Duration::from_secs(config.check_interval_secs.max(1)));
+            let mut watchdog = ZeroHashrateWatchdog::new(&config);
+
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                let mining = *state.read().await == MiningState::Running;
+                let Some(action) = watchdog.observe(stats.snapshot().current_hashrate, mining, Instant::now()) else {
+                    continue;
+                };
+
+                let zero_for = config.zero_hashrate_secs * watchdog.attempts() as u64;
+                error!("❌ Hashrate has been zero for {}s, recovery step {}: {}", zero_for, watchdog.attempts(), action.as_str());
+                let alert = Alert::new(
+                    AlertType::Mining,
+                    AlertSeverity::Critical,
+                    "Zero Hashrate".to_string(),
+                    format!("Total hashrate has been zero for {}s while mining; recovery step {}: {}",
+                            zero_for, watchdog.attempts(), action.as_str()),
+                    "mining".to_string(),
+                )
+                .with_label("action".to_string(), action.as_str().to_string());
+                monitoring_system.raise_alert(alert).await;
+
+                match action {
+                    RecoveryAction::RestartCores => {
+                        let core_ids: Vec<String> = core_configs.read().await.keys().cloned().collect();
+                        for core_id in core_ids {
+                            if let Err(e) = core_lifecycle.stop_core(&core_id).await {
+                                debug!("核心 {} 停止失败: {}", core_id, e);
+                            }
+                            if let Err(e) = core_lifecycle.start_core(&core_id).await {
+                                warn!("Failed to restart core {}: {}", core_id, e);
+                            }
+                        }
+                    }
+                    RecoveryAction::RestartDevices => {
+                        let device_manager = device_manager.lock().await;
+                        for device in device_manager.get_all_device_info().await {
+                            if let Err(e) = device_manager.restart_device(device.id).await {
+                                warn!("Failed to restart device {}: {}", device.id, e);
+                            }
+                        }
+                    }
+                    RecoveryAction::Exit => {
+                        error!("🛑 Exiting with code {} so the supervisor restarts the miner", config.exit_code);
+                        recovery_exit.send_replace(Some(config.exit_code));
+                        break;
+                    }
+                }
+            }
+        }));
+
+        *self.recovery_handle.lock().await = Some(handle);
+    }
+
+    /// 等待零算力恢复请求退出进程，返回退出码；调用方有序停机后以该退出码退出
+    pub async fn recovery_exit_requested(&self) -> i32 {
+        let mut receiver = self.recovery_exit.subscribe();
+        // 发送端由管理器持有，等待期间不会关闭
+        match receiver.wait_for(Option::is_some).await {
+            Ok(code) => code.unwrap_or(1),
+            Err(_) => std::future::pending().await,
+        }
+    }
+
+    /// 按 `cores.cpu_btc.load_target` 调整 CPU 核心运行的线程数，其他进程需要 CPU 时暂停部分线程
+    async fn start_cpu_load_target(&self) {
+        let Some(load_target) = self.full_config.cores.cpu_btc.as_ref()
+            .filter(|c| c.enabled && c.load_target.enabled)
+            .map(|c| c.load_target.clone())
+        else {
+            return;
+        };
+        let core_lifecycle = self.core_lifecycle.clone();
+        let core_configs = self.core_configs.clone();
+        let selected_core = self.selected_core.clone();
+        let device_core_mapper = self.device_core_mapper.clone();
+        let running = self.running.clone();
+
+        let handle = tokio::spawn(task_watch::track("cpu-load-target", async move {
+            let logical_cpus = CpuTopology::detect().logical_cpus;
+            let mut interval = interval(Duration::from_secs(load_target.interval_secs));
+            let mut sampler = cpu_load::LoadSampler::default();
+            let mut active_threads = None;
+
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                let Some(sample) = sampler.sample() else {
+                    continue;
+                };
+
+                let Some((core_id, mut spec)) = Self::find_core_spec(&core_configs, "cpu-btc").await else {
+                    continue;
+                };
+                let device_count = spec.config.custom_params.get("device_count")
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or(1) as u32;
+                let threads = cpu_load::plan_active_threads(sample.other_percent, &load_target, device_count, logical_cpus);
+                if active_threads == Some(threads) {
+                    continue;
+                }
+
+                spec.config.custom_params.insert("active_threads".to_string(), serde_json::Value::from(threads));
+                if let Err(e) = Self::replace_core(&core_lifecycle, &core_configs, &selected_core, &device_core_mapper, &core_id, spec).await {
+                    warn!("Failed to set active CPU threads on core {}: {}", core_id, e);
+                    continue;
+                }
+                active_threads = Some(threads);
+                info!("⚖️ CPU load {:.0}% ({:.0}% from other processes), running {}/{} mining threads",
+                      sample.system_percent, sample.other_percent, threads, device_count);
+            }
+        }));
+
+        *self.cpu_load_handle.lock().await = Some(handle);
+    }
+
+    /// 按 `cores.cpu_btc.thermal_throttle` 在封装温度过高时降低 CPU 核心的占空比，回滞后恢复
+    async fn start_cpu_thermal_throttle(&self) {
+        let Some(config) = self.full_config.cores.cpu_btc.as_ref()
+            .filter(|c| c.enabled && c.thermal_throttle.enabled)
+            .map(|c| c.thermal_throttle.clone())
+        else {
+            return;
+        };
+        if cpu_thermal::read_package_temperature().is_none() {
+            warn!("CPU thermal throttling is enabled but no CPU package temperature sensor was found in hwmon");
+            return;
+        }
+        let core_lifecycle = self.core_lifecycle.clone();
+        let core_configs = self.core_configs.clone();
+        let selected_core = self.selected_core.clone();
+        let device_core_mapper = self.device_core_mapper.clone();
+        let throttle_states = self.device_manager.lock().await.throttle_states();
+        let running = self.running.clone();
+
+        let handle = tokio::spawn(task_watch::track("cpu-thermal-throttle", async move {
+            let mut interval = interval(Duration::from_secs(config.interval_secs));
+            let mut throttle = cpu_thermal::ThermalThrottle::new(config.clone());
+
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                let Some(celsius) = cpu_thermal::read_package_temperature() else {
+                    continue;
+                };
+                let previous_duty = throttle.duty_percent();
+                let state = throttle.update(celsius);
+
+                let Some((core_id, mut spec)) = Self::find_core_spec(&core_configs, "cpu-btc").await else {
+                    continue;
+                };
+                throttle_states.write().await.insert(spec.factory_name.clone(), state.clone());
+                if state.duty_percent == previous_duty {
+                    continue;
+                }
+
+                spec.config.custom_params.insert("duty_percent".to_string(), serde_json::Value::from(state.duty_percent));
+                spec.config.custom_params.insert("throttle_action".to_string(), serde_json::Value::from(config.action.as_str()));
+                let new_core_id = match Self::replace_core(&core_lifecycle, &core_configs, &selected_core, &device_core_mapper, &core_id, spec).await {
+                    Ok(new_core_id) => new_core_id,
+                    Err(e) => {
+                        warn!("Failed to set CPU duty cycle on core {}: {}", core_id, e);
+                        continue;
+                    }
+                };
+                if state.throttled {
+                    warn!("🌡️ CPU package at {:.1}°C (limit {:.1}°C), throttling core {} to {}% ({})",
+                          celsius, config.temperature_limit, new_core_id, state.duty_percent, state.action);
+                } else {
+                    info!("🌡️ CPU package at {:.1}°C, core {} back to full speed", celsius, new_core_id);
+                }
+            }
+        }));
+
+        *self.cpu_thermal_handle.lock().await = Some(handle);
+    }
+
+    /// 按 `pools.profit_switch` 定期比较各矿池的收益，其它矿池持续领先时切换活跃矿池
+    async fn start_profit_switch(&self) {
+        let config = self.full_config.pools.profit_switch.clone();
+        if !config.enabled {
+            return;
+        }
+        let pool_manager = self.pool_manager.clone();
+        let running = self.running.clone();
+
+        let handle = tokio::spawn(task_watch::track("profit-switch", async move {
+            let http = reqwest::Client::new();
+            let mut interval = interval(Duration::from_secs(config.interval_secs));
+            let mut switcher = ProfitSwitcher::new(config.clone());
+
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                // 收益数据在锁外获取，请求较慢时不阻塞份额提交
+                let quotes = match &config.source_url {
+                    Some(url) => match fetch_profitability(&http, url).await {
+                        Ok(quotes) => Some(quotes),
+                        Err(e) => {
+                            warn!("Failed to fetch pool profitability from {}: {}", url, e);
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+
+                let pool_manager = pool_manager.lock().await;
+                let Some(current) = pool_manager.get_active_pool_id().await else {
+                    continue;
+                };
+                let profits = pool_manager.pool_profitability(quotes.as_ref()).await;
+                let Some(target) = switcher.evaluate(current, &profits, Instant::now()) else {
+                    continue;
+                };
+
+                info!("💱 Pool {} is {:.1}% more profitable than pool {}, switching",
+                      target, (profits[&target] / profits[&current] - 1.0) * 100.0, current);
+                match pool_manager.switch_to_pool(target).await {
+                    Ok(()) => switcher.record_switch(Instant::now()),
+                    Err(e) => warn!("Failed to switch to pool {}: {}", target, e),
+                }
+            }
+        }));
+
+        *self.profit_switch_handle.lock().await = Some(handle);
+    }
+
+    /// 按 `[session_report]` 定期采样算力和温度，到每天的切换时刻写入一份报告
+    async fn start_session_report(&self) {
+        let config = self.full_config.session_report.clone();
+        if !config.enabled {
+            return;
+        }
+        *self.session_reporter.lock().await = Some(SessionReporter::new(chrono::Local::now()));
+        let rollover = config.rollover().ok().flatten();
+        let reporter = self.session_reporter.clone();
+        let stats = self.stats.clone();
+        let pool_manager = self.pool_manager.clone();
+        let device_manager = self.device_manager.clone();
+        let pools = self.full_config.pools.pools.iter().map(|pool| pool.url.clone()).collect::<Vec<_>>();
+        let running = self.running.clone();
+
+        let handle = tokio::spawn(task_watch::track("session-report", async move {
+            let mut interval = interval(SESSION_SAMPLE_INTERVAL);
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                let devices = device_manager.lock().await.get_all_device_info().await;
+                let temperatures: Vec<(u32, f32)> = devices.iter()
+                    .filter_map(|device| device.temperature.map(|celsius| (device.id, celsius)))
+                    .collect();
+
+                let mut guard = reporter.lock().await;
+                let Some(reporter) = guard.as_mut() else {
+                    break;
+                };
+                reporter.sample(stats.snapshot().current_hashrate, &temperatures, Instant::now());
+
+                let now = chrono::Local::now();
+                let due = rollover
+                    .and_then(|time| session_report::next_rollover(&reporter.period_start(), time))
+                    .is_some_and(|at| now >= at);
+                if due {
+                    let snapshot = Self::session_snapshot(&stats, &pool_manager, &devices, &pools).await;
+                    let report = reporter.report(ReportReason::Rollover, snapshot, now);
+                    Self::publish_session_report(&config, &report).await;
+                }
+            }
+        }));
+
+        *self.session_report_handle.lock().await = Some(handle);
+    }
+
+    /// 按 `stats_file` 定期写入农场代理读取的统计文件
+    async fn start_stats_file(&self) {
+        let config = self.full_config.stats_file.clone();
+        if !config.enabled {
+            return;
+        }
+        let stats = self.stats.clone();
+        let pool_manager = self.pool_manager.clone();
+        let device_manager = self.device_manager.clone();
+        let pools = self.full_config.pools.pools.clone();
+        let running = self.running.clone();
+        info!("📄 Writing {} stats file to {}", config.format.as_str(), config.path);
+
+        let handle = tokio::spawn(task_watch::track("stats-file", async move {
+            let mut interval = interval(Duration::from_secs(config.interval_secs.max(1)));
+            // 写入失败只记录一次，恢复后重新记录
+            let mut failing = false;
+
+            while running.load(Ordering::Acquire) {
+                interval.tick().await;
+                let devices = device_manager.lock().await.get_all_device_info().await;
+                let active_pool = pool_manager.lock().await.get_active_pool_id().await
+                    .and_then(|id| pools.get(id as usize))
+                    .or(pools.first());
+                let snapshot = stats.snapshot();
+                let content = stats_file::render(config.format, &StatsSnapshot {
+                    stats: &snapshot,
+                    devices: &devices,
+                    algorithm: active_pool.map(|pool| pool.algorithm).unwrap_or_default(),
+                    pool_url: active_pool.map(|pool| pool.url.as_str()),
+                });
+
+                match stats_file::write_stats_file(std::path::Path::new(&config.path), &content) {
+                    Ok(()) => failing = false,
+                    Err(e) if !failing => {
+                        warn!("Failed to write stats file {}: {}", config.path, e);
+                        failing = true;
+                    }
+                    Err(_) => {}
+                }
+            }
+        }));
+
+        *self.stats_file_handle.lock().await = Some(handle);
+    }
+
+    /// 停机时写入最后一份会话报告
+    async fn write_shutdown_report(&self) {
+        let Some(mut reporter) = self.session_reporter.lock().await.take() else {
+            return;
+        };
+        let devices = self.device_manager.lock().await.get_all_device_info().await;
+        let temperatures: Vec<(u32, f32)> = devices.iter()
+            .filter_map(|device| device.temperature.map(|celsius| (device.id, celsius)))
+            .collect();
+        reporter.sample(self.stats.snapshot().current_hashrate, &temperatures, Instant::now());
+
+        let pools: Vec<String> = self.full_config.pools.pools.iter().map(|pool| pool.url.clone()).collect();
+        let snapshot = Self::session_snapshot(&self.stats, &self.pool_manager, &devices, &pools).await;
+        let report = reporter.report(ReportReason::Shutdown, snapshot, chrono::Local::now());
+        Self::publish_session_report(&self.full_config.session_report, &report).await;
+    }
+
+    /// 当前的累计计数：份额按 (矿池, 设备) 统计汇总，硬件错误取自设备信息
+    async fn session_snapshot(
+        stats: &StatsCounters,
+        pool_manager: &Mutex<PoolManager>,
+        devices: &[DeviceInfo],
+        pool_urls: &[String],
+    ) -> SessionSnapshot {
+        let mining_stats = stats.snapshot();
+        let mut snapshot = SessionSnapshot {
+            uptime: mining_stats.uptime,
+            totals: ShareCounts {
+                accepted_shares: mining_stats.accepted_shares,
+                rejected_shares: mining_stats.rejected_shares,
+                hardware_errors: mining_stats.hardware_errors,
+                accepted_difficulty: 0.0,
+            },
+            stale_shares: mining_stats.stale_shares,
+            best_share: mining_stats.best_share,
+            ..Default::default()
+        };
+        for device in devices {
+            let entry = snapshot.devices.entry(device.id).or_insert_with(|| (device.name.clone(), ShareCounts::default()));
+            entry.1.hardware_errors = device.hardware_errors;
+        }
+        for share_stats in pool_manager.lock().await.get_pool_device_stats().await {
+            let url = pool_urls.get(share_stats.pool_id as usize).cloned().unwrap_or_default();
+            let pool = &mut snapshot.pools.entry(share_stats.pool_id)
+                .or_insert_with(|| (url, ShareCounts::default())).1;
+            pool.accepted_shares += share_stats.accepted_shares;
+            pool.rejected_shares += share_stats.rejected_shares;
+            pool.accepted_difficulty += share_stats.accepted_difficulty;
+
+            let device = &mut snapshot.devices.entry(share_stats.device_id)
+                .or_insert_with(|| (format!("device-{}", share_stats.device_id), ShareCounts::default())).1;
+            device.accepted_shares += share_stats.accepted_shares;
+            device.rejected_shares += share_stats.rejected_shares;
+            device.accepted_difficulty += share_stats.accepted_difficulty;
+
+            snapshot.totals.accepted_difficulty += share_stats.accepted_difficulty;
+        }
+        snapshot
+    }
+
+    /// 写入报告文件，设置了 webhook 时同时发送
+    async fn publish_session_report(config: &session_report::SessionReportConfig, report: &SessionReport) {
+        match session_report::write_report(std::path::Path::new(&config.dir), report, config.max_reports) {
+            Ok(path) => info!("📝 Session report written to {}", path.display()),
+            Err(e) => warn!("Failed to write session report to {}: {}", config.dir, e),
+        }
+        if !config.webhook_url.is_empty() {
+            if let Err(e) = session_report::post_report(&config.webhook_url, report).await {
+                warn!("{}", e);
+            }
+        }
+    }
+
+    /// 按优先级创建并启动备用核心，返回新核心 ID；没有能启动的备用核心时返回 None
+    async fn start_standby_core(
+        core_lifecycle: &CoreLifecycle,
+        core_configs: &RwLock<HashMap<String, CoreSpec>>,
+        standby_cores: &RwLock<Vec<CoreSpec>>,
+    ) -> Option<String> {
+        loop {
+            let spec = {
+                let mut standby = standby_cores.write().await;
+                if standby.is_empty() {
+                    return None;
+                }
+                standby.remove(0)
+            };
+            let core_id = match core_lifecycle.create_core(&spec.core_type, spec.config.clone()).await {
+                Ok(core_id) => core_id,
+                Err(e) => {
+                    warn!("Failed to create standby {} core: {}", spec.core_type, e);
+                    continue;
+                }
+            };
+            if let Err(e) = core_lifecycle.start_core(&core_id).await {
+                warn!("Failed to start standby core {}: {}", core_id, e);
+                if let Err(e) = core_lifecycle.remove_core(&core_id).await {
+                    debug!("核心 {} 卸载失败: {}", core_id, e);
+                }
+                continue;
+            }
+            core_configs.write().await.insert(core_id.clone(), spec);
+            return Some(core_id);
+        }
+    }
+
+    /// 第一个指定类型的核心及其配置
+    async fn find_core_spec(core_configs: &RwLock<HashMap<String, CoreSpec>>, core_type: &str) -> Option<(String, CoreSpec)> {
+        core_configs.read().await.iter()
+            .find(|(_, spec)| spec.core_type == core_type)
+            .map(|(core_id, spec)| (core_id.clone(), spec.clone()))
+    }
+
+    /// 用 `spec` 的配置重建核心，更新核心配置表、选中的核心和设备映射，返回新核心 ID
+    async fn replace_core(
+        core_lifecycle: &CoreLifecycle,
+        core_configs: &RwLock<HashMap<String, CoreSpec>>,
+        selected_core: &RwLock<Option<String>>,
+        device_core_mapper: &DeviceCoreMapper,
+        core_id: &str,
+        spec: CoreSpec,
+    ) -> Result<String, cgminer_core::CoreError> {
+        let new_core_id = core_lifecycle.recreate_core(core_id, spec.config.clone()).await?;
+        {
+            let mut configs = core_configs.write().await;
+            configs.remove(core_id);
+            configs.insert(new_core_id.clone(), spec);
+        }
+        if let Err(e) = device_core_mapper.cleanup_core_mappings(core_id).await {
+            warn!("清理核心 {} 的设备映射失败: {}", core_id, e);
+        }
+        let mut selected = selected_core.write().await;
+        if selected.as_deref() == Some(core_id) {
+            *selected = Some(new_core_id.clone());
+        }
+        Ok(new_core_id)
+    }
+
+    /// 中止剩余的后台任务；分发和结果任务在 `stop` 中先按顺序退出，这里只中止仍未退出的
     async fn stop_tasks(&self) {
         // 停止算力计量器
         {
@@ -1140,6 +2101,31 @@ impl MiningManager {
         if let Some(handle) = self.core_result_handle.lock().await.take() {
             handle.abort();
         }
+
+        // 停止核心健康检查
+        if let Some(handle) = self.core_health_handle.lock().await.take() {
+            handle.abort();
+        }
+
+        // 停止 CPU 负载目标
+        if let Some(handle) = self.cpu_load_handle.lock().await.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.cpu_thermal_handle.lock().await.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.profit_switch_handle.lock().await.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.session_report_handle.lock().await.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.stats_file_handle.lock().await.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.recovery_handle.lock().await.take() {
+            handle.abort();
+        }
     }
 
     /// 初始化设备管理器（从协调器移植）
@@ -1149,8 +2135,13 @@ impl MiningManager {
         let active_core_ids = self.core_registry.list_active_cores().await
             .map_err(|e| MiningError::CoreError(format!("获取活跃核心列表失败: {}", e)))?;
 
+        let core_factories = self.core_configs.read().await
+            .iter()
+            .map(|(core_id, spec)| (core_id.clone(), spec.factory_name.clone()))
+            .collect();
+
         let mut device_manager = self.device_manager.lock().await;
-        device_manager.set_active_cores(active_core_ids).await;
+        device_manager.set_active_cores(active_core_ids, core_factories).await;
         device_manager.initialize().await?;
         device_manager.start().await?;
 
@@ -1164,27 +2155,214 @@ impl MiningManager {
     /// 提交工作（从协调器移植）
     pub async fn submit_work(&self, work: crate::device::Work) -> Result<(), MiningError> {
         let work_item = WorkItem {
-            work,
+            work: Arc::new(work),
             assigned_device: None,
             created_at: SystemTime::now(),
             priority: 1,
             retry_count: 0,
+            share_difficulty: None,
+            algorithm: self.pool_manager.lock().await.active_algorithm().await,
         };
 
-        if let Ok(work_sender_guard) = self.work_sender.try_lock() {
-            if let Some(sender) = work_sender_guard.as_ref() {
-                sender.send(work_item)
-                    .map_err(|e| MiningError::WorkError(format!("提交工作失败: {}", e)))?;
-            }
+        // 克隆发送端后释放锁，队列满需要等待时不会挡住其他提交方
+        let sender = self.work_sender.lock().await.clone();
+        if let Some(sender) = sender {
+            sender.send(work_item).await
+                .map_err(|e| MiningError::WorkError(format!("提交工作失败: {}", e)))?;
         }
 
         Ok(())
     }
 
+    /// 最近一次启动的各阶段耗时
+    pub async fn startup_timings(&self) -> StartupTimings {
+        self.startup_timings.read().await.clone()
+    }
+
+    /// 获取工作/结果队列的统计
+    pub async fn get_queue_stats(&self) -> Vec<QueueStats> {
+        let mut stats = Vec::new();
+        if let Some(sender) = self.work_sender.lock().await.as_ref() {
+            stats.push(sender.stats());
+        }
+        if let Some(sender) = self.result_sender.lock().await.as_ref() {
+            stats.push(sender.stats());
+        }
+        stats
+    }
+
+    /// 获取监控系统
+    pub fn get_monitoring_system(&self) -> Arc<MonitoringSystem> {
+        self.monitoring_system.clone()
+    }
+
     /// 获取设备-核心映射器
     pub fn get_device_core_mapper(&self) -> Arc<DeviceCoreMapper> {
         self.device_core_mapper.clone()
     }
+
+    /// 获取设备的核心映射
+    pub async fn get_device_mapping(&self, device_id: u32) -> Option<DeviceCoreMapping> {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_device_core_mapping(device_id).await
+    }
+
+    /// 获取核心下所有设备的映射
+    pub async fn get_core_device_mappings(&self, core_name: &str) -> Vec<DeviceCoreMapping> {
+        let device_manager = self.device_manager.lock().await;
+        let mut mappings = Vec::new();
+        for device_id in device_manager.get_core_devices(core_name).await {
+            if let Some(mapping) = device_manager.get_device_core_mapping(device_id).await {
+                mappings.push(mapping);
+            }
+        }
+        mappings
+    }
+
+    /// 获取设备映射统计
+    pub async fn get_mapping_stats(&self) -> MappingStats {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_mapping_stats().await
+    }
+
+    /// 获取设备分批启动状态
+    pub async fn get_ramp_up_status(&self) -> RampUpStatus {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_ramp_up_status().await
+    }
+
+    /// 获取各链自动调优进度
+    pub async fn get_auto_tune_status(&self) -> Vec<AutoTuneStatus> {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_auto_tune_status().await
+    }
+
+    /// 获取板卡功率和功率上限状态
+    pub async fn get_power_status(&self) -> PowerStatus {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_power_status().await
+    }
+
+    /// 获取设备算力板的 EEPROM 信息
+    pub async fn get_board_eeprom(&self, device_id: u32) -> Option<BoardEeprom> {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_board_eeprom(device_id).await
+    }
+
+    /// 获取所有设备的芯片热力图
+    pub async fn get_chip_heatmaps(&self) -> Vec<ChipHeatmap> {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_chip_heatmaps().await
+    }
+
+    /// 获取单个设备的芯片热力图
+    pub async fn get_chip_heatmap(&self, device_id: u32) -> Result<Option<ChipHeatmap>, MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.get_chip_heatmap(device_id).await?)
+    }
+
+    /// 设置配置文件路径，保存运行时设置时写回该文件
+    pub fn set_config_path(&mut self, path: impl Into<String>) {
+        self.config_path = Some(path.into());
+    }
+
+    /// 设置配置文件守护
+    pub fn set_config_guard(&mut self, guard: Arc<ConfigGuard>) {
+        self.config_guard = Some(guard);
+    }
+
+    /// 获取当前生效的配置（包含运行时修改的设置）
+    pub async fn get_config(&self) -> Config {
+        let settings = self.runtime_settings.read().await.clone();
+        let mut config = self.full_config.clone();
+        config.general.scan_time = settings.scan_time;
+        config.general.work_restart_timeout = settings.work_restart_timeout;
+        config.general.result_collection_interval_ms = settings.result_collection_interval_ms;
+        config.hashmeter.log_interval = settings.hashmeter_log_interval;
+        config
+    }
+
+    /// 获取当前生效的运行时设置
+    pub async fn get_runtime_settings(&self) -> RuntimeSettings {
+        self.runtime_settings.read().await.clone()
+    }
+
+    /// 更新运行时设置，新的间隔在对应任务的下一个周期生效
+    pub async fn update_runtime_settings(&self, update: RuntimeSettingsUpdate) -> Result<RuntimeSettings, MiningError> {
+        let updated = {
+            let mut settings = self.runtime_settings.write().await;
+            let updated = settings.apply(&update).map_err(MiningError::ConfigError)?;
+            *settings = updated.clone();
+            updated
+        };
+
+        if let Some(hashmeter) = self.hashmeter.lock().await.as_ref() {
+            hashmeter.set_log_interval(updated.hashmeter_log_interval).await;
+        }
+
+        info!("🔧 运行时设置已更新: scan_time={}s, work_restart_timeout={}s, result_collection_interval={}ms, hashmeter_log_interval={}s",
+              updated.scan_time, updated.work_restart_timeout,
+              updated.result_collection_interval_ms, updated.hashmeter_log_interval);
+        Ok(updated)
+    }
+
+    /// 将当前运行时设置写回配置文件，保留原有注释
+    pub async fn save_runtime_settings(&self) -> Result<String, MiningError> {
+        let path = self.config_path.clone()
+            .ok_or_else(|| MiningError::ConfigError("No config file path is set".to_string()))?;
+        let settings = self.get_runtime_settings().await;
+
+        crate::config::persist::save_keys(&path, &settings.config_keys())
+            .map_err(|e| MiningError::ConfigError(format!("{:#}", e)))?;
+        if let Some(guard) = &self.config_guard {
+            guard.accept(&path).await?;
+        }
+
+        info!("💾 运行时设置已保存到 {}", path);
+        Ok(path)
+    }
+
+    /// 获取设备风扇状态
+    pub async fn get_device_fan_status(&self, device_id: u32) -> Result<FanStatus, MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.get_fan_status(device_id).await?)
+    }
+
+    /// 获取所有设备信息
+    pub async fn get_all_device_info(&self) -> Vec<DeviceInfo> {
+        let device_manager = self.device_manager.lock().await;
+        device_manager.get_all_device_info().await
+    }
+
+    /// 重启设备
+    pub async fn restart_device(&self, device_id: u32) -> Result<(), MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.restart_device(device_id).await?)
+    }
+
+    /// 启用或禁用设备
+    pub async fn set_device_enabled(&self, device_id: u32, enabled: bool) -> Result<(), MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.set_device_enabled(device_id, enabled).await?)
+    }
+
+    /// 设置设备频率 (MHz)
+    pub async fn set_device_frequency(&self, device_id: u32, frequency: u32) -> Result<(), MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.set_device_frequency(device_id, frequency).await?)
+    }
+
+    /// 设置设备电压 (mV)
+    pub async fn set_device_voltage(&self, device_id: u32, voltage: u32) -> Result<(), MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.set_device_voltage(device_id, voltage).await?)
+    }
+
+    /// 设置或清除设备风扇手动覆盖
+    pub async fn set_device_fan_override(&self, device_id: u32, target: Option<FanSpeedTarget>) -> Result<(), MiningError> {
+        let device_manager = self.device_manager.lock().await;
+        Ok(device_manager.set_fan_override(device_id, target).await?)
+    }
 }
 
 /// 系统状态
@@ -1204,11 +2382,25 @@ pub struct SystemStatus {
     pub power_consumption: f64,
 }
 
+/// 会话报告的算力和温度采样间隔
+const SESSION_SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
 /// 统一工作分发器
 /// 负责将工作统一分发到核心或设备，避免分发逻辑的重复和不一致
+/// 分发器一次从队列取出的最多工作数
+const MAX_DISPATCH_BATCH: usize = 64;
+
 pub struct UnifiedWorkDispatcher {
     core_registry: Arc<CoreRegistry>,
     device_manager: Arc<Mutex<DeviceManager>>,
+    /// 设备轮询分发位置
+    next_device: AtomicUsize,
+    /// 份额目标过滤器
+    share_filter: Arc<ShareTargetFilter>,
+    /// 核心的类型信息，用于按优先级排序
+    core_configs: Arc<RwLock<HashMap<String, CoreSpec>>>,
+    /// `cores.priority`
+    priority: Vec<String>,
 }
 
 impl UnifiedWorkDispatcher {
@@ -1216,20 +2408,71 @@ impl UnifiedWorkDispatcher {
     pub fn new(
         core_registry: Arc<CoreRegistry>,
         device_manager: Arc<Mutex<DeviceManager>>,
+        share_filter: Arc<ShareTargetFilter>,
+        core_configs: Arc<RwLock<HashMap<String, CoreSpec>>>,
+        priority: Vec<String>,
     ) -> Self {
         Self {
             core_registry,
             device_manager,
+            next_device: AtomicUsize::new(0),
+            share_filter,
+            core_configs,
+            priority,
         }
     }
 
     /// 分发工作
     /// 优先级：活跃核心 > 指定设备 > 任意可用设备
-    pub async fn dispatch_work(&self, work_item: WorkItem) -> Result<String, String> {
+    pub async fn dispatch_work(&self, mut work_item: WorkItem) -> Result<String, String> {
         debug!("Dispatching work: {}", work_item.work.id);
+        self.record_share_target(&mut work_item).await;
+        self.route_work(&work_item).await
+    }
+
+    /// 分发一批工作，结果与输入顺序一致
+    ///
+    /// cgminer-core 没有批量提交接口，每个工作仍经一次 `submit_work_to_core`；一次取出整批只省去分发任务的唤醒。
+    pub async fn dispatch_batch(&self, work_items: Vec<WorkItem>) -> Vec<Result<String, String>> {
+        let mut results = Vec::with_capacity(work_items.len());
+        for work_item in work_items {
+            results.push(self.dispatch_work(work_item).await);
+        }
+        results
+    }
+
+    /// 附加矿池份额目标，供结果收集预过滤
+    async fn record_share_target(&self, work_item: &mut WorkItem) {
+        let share_difficulty = *work_item.share_difficulty.get_or_insert(work_item.work.difficulty);
+        self.share_filter.record_work(work_item.work.id, share_difficulty, work_item.algorithm).await;
+    }
+
+    /// 按 `cores.priority` 和核心的类型信息排序的活跃核心
+    async fn sorted_active_cores(&self) -> Result<Vec<String>, String> {
+        let mut cores = self.core_registry.list_active_cores().await
+            .map_err(|e| format!("Failed to list active cores: {}", e))?;
+        // 核心自行在其设备间分配工作，有设备处于过热保护时整个核心不再接收新工作
+        {
+            let device_manager = self.device_manager.lock().await;
+            let mut available = Vec::with_capacity(cores.len());
+            for core_id in cores {
+                if device_manager.core_overheated(&core_id).await {
+                    debug!("Skipping core {}: a device is in thermal protection", core_id);
+                } else {
+                    available.push(core_id);
+                }
+            }
+            cores = available;
+        }
+        let specs = self.core_configs.read().await;
+        cores.sort_by_key(|core_id| core_rank(&self.priority, specs.get(core_id)));
+        Ok(cores)
+    }
 
+    /// 按优先级把一个工作交给核心或设备
+    async fn route_work(&self, work_item: &WorkItem) -> Result<String, String> {
         // 1. 优先尝试分发到活跃的核心
-        match self.dispatch_to_cores(&work_item).await {
+        match self.dispatch_to_cores(work_item).await {
             Ok(target) => {
                 debug!("Work dispatched to: {}", target);
                 return Ok(target);
@@ -1240,7 +2483,7 @@ impl UnifiedWorkDispatcher {
         }
 
         // 2. 如果核心分发失败，尝试分发到设备
-        match self.dispatch_to_devices(&work_item).await {
+        match self.dispatch_to_devices(work_item).await {
             Ok(target) => {
                 debug!("Work dispatched to: {}", target);
                 return Ok(target);
@@ -1258,33 +2501,18 @@ impl UnifiedWorkDispatcher {
     async fn dispatch_to_cores(&self, work_item: &WorkItem) -> Result<String, String> {
         debug!("Dispatching work to cores");
 
-        let active_core_ids = self.core_registry.list_active_cores().await
-            .map_err(|e| format!("Failed to list active cores: {}", e))?;
+        let sorted_cores = self.sorted_active_cores().await?;
 
-        debug!("Found {} active cores", active_core_ids.len());
+        debug!("Found {} active cores", sorted_cores.len());
 
-        if active_core_ids.is_empty() {
+        if sorted_cores.is_empty() {
             return Err("No active cores available".to_string());
         }
 
-        // **优化**：按优先级排序核心，优先向GPU核心分发工作
-        let mut sorted_cores = active_core_ids.clone();
-        sorted_cores.sort_by_key(|core_id| {
-            if core_id.contains("gpu") {
-                1 // GPU最高优先级
-            } else if core_id.contains("asic") || core_id.contains("maijie") {
-                2 // ASIC中等优先级
-            } else if core_id.contains("cpu") || core_id.contains("software") {
-                3 // CPU最低优先级
-            } else {
-                4 // 未知类型
-            }
-        });
-
         // 使用优先级排序后的核心进行分发
         for core_id in &sorted_cores {
             debug!("Trying to submit work to core: {}", core_id);
-            match self.core_registry.submit_work_to_core(core_id, work_item.work.clone().into()).await {
+            match self.core_registry.submit_work_to_core(core_id, Work::clone(&work_item.work)).await {
                 Ok(()) => {
                     debug!("Work submitted to core: {}", core_id);
                     return Ok(format!("core:{}", core_id));
@@ -1302,12 +2530,11 @@ impl UnifiedWorkDispatcher {
 
     /// 分发工作到设备
     async fn dispatch_to_devices(&self, work_item: &WorkItem) -> Result<String, String> {
-        let device_manager = self.device_manager.try_lock()
-            .map_err(|_| "Device manager is busy".to_string())?;
+        let device_manager = self.device_manager.lock().await;
 
         // 如果指定了设备，优先分发到该设备
         if let Some(device_id) = work_item.assigned_device {
-            match device_manager.submit_work(device_id, work_item.work.clone()).await {
+            match device_manager.submit_work(device_id, work_item.shared_work()).await {
                 Ok(()) => {
                     return Ok(format!("device:{}", device_id));
                 }
@@ -1317,9 +2544,62 @@ impl UnifiedWorkDispatcher {
             }
         }
 
-        // 如果没有指定设备或指定设备失败，尝试分发到任意可用设备
-        // 这里需要从设备管理器获取可用设备列表
-        // 由于当前DeviceManager没有提供获取所有设备的方法，我们暂时返回错误
-        Err("No available devices for work dispatch".to_string())
+        // 如果没有指定设备或指定设备失败，轮询分发到任意可用设备
+        let device_ids = MiningManager::healthy_device_ids(&device_manager).await;
+
+        if device_ids.is_empty() {
+            return Err("No available devices for work dispatch".to_string());
+        }
+
+        let start = self.next_device.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..device_ids.len() {
+            let device_id = device_ids[(start + offset) % device_ids.len()];
+            match device_manager.submit_work(device_id, work_item.shared_work()).await {
+                Ok(()) => return Ok(format!("device:{}", device_id)),
+                Err(e) => debug!("Failed to submit work to device {}: {}", device_id, e),
+            }
+        }
+
+        Err("All devices rejected the work".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::mock::{MockPool, MockPoolOptions};
+
+    #[tokio::test]
+    async fn test_main_loop_fetches_work_per_device() {
+        let pool = MockPool::start("127.0.0.1:0", MockPoolOptions::default()).await.unwrap();
+        let mut config = Config::default().pools;
+        let template = config.pools[0].clone();
+        config.pools = vec![crate::config::PoolInfo { url: pool.url(), priority: 0, enabled: true, ..template }];
+        config.extranonce2_partitions = 2;
+
+        let pool_manager = PoolManager::new(config).await.unwrap();
+        pool_manager.connect_to_pools().await.unwrap();
+        for _ in 0..100 {
+            if pool_manager.get_work_for_device(0).await.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        // 主循环每次为下一个设备取工作，extranonce2 落在该设备的子区间
+        let mut next_device = 0;
+        let first = MiningManager::next_work_item(&pool_manager, &[0, 1], &mut next_device).await.unwrap();
+        let second = MiningManager::next_work_item(&pool_manager, &[0, 1], &mut next_device).await.unwrap();
+        let third = MiningManager::next_work_item(&pool_manager, &[0, 1], &mut next_device).await.unwrap();
+        assert_eq!(first.assigned_device, Some(0));
+        assert_eq!(second.assigned_device, Some(1));
+        assert_eq!(third.assigned_device, Some(0));
+        assert_eq!(second.work.extranonce2, vec![0, 0, 0, 0x80]);
+        assert_ne!(first.work.extranonce2, third.work.extranonce2);
+        assert!(third.work.extranonce2[3] < 0x80);
+
+        // 没有可用设备时不指定设备，交给分发器
+        let unassigned = MiningManager::next_work_item(&pool_manager, &[], &mut next_device).await.unwrap();
+        assert_eq!(unassigned.assigned_device, None);
     }
 }
@@ -0,0 +1,95 @@
+//! SHA256 中间状态 (midstate)
+//!
+//! 区块头 80 字节，SHA256 分两个 64 字节块处理。第一块 (版本、前一区块哈希、merkle 根的前 28 字节)
+//! 在同一个工作内不随 nonce 变化，压缩一次后的内部状态就是 midstate。应用层在创建工作时计算一次写入
+//! `Work::midstate`，CPU/GPU/ASIC 核心直接从它开始处理第二块，作业频繁切换时不必各自重算。
+//!
+//! 格式：8 个状态字按大端序排列 (与 SHA256 摘要的字节序相同)。全零表示未提供 (非 SHA256d 算法)。
+
+use crate::device::Work;
+use crate::mining::Algorithm;
+use sha2::digest::generic_array::GenericArray;
+
+/// SHA256 初始哈希值
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// 区块头第一个 64 字节块压缩后的状态字；区块头不足 64 字节时为 None
+pub fn midstate_words(header: &[u8]) -> Option<[u32; 8]> {
+    let block = header.get(..64)?;
+    let mut state = SHA256_IV;
+    sha2::compress256(&mut state, &[GenericArray::clone_from_slice(block)]);
+    Some(state)
+}
+
+/// 区块头的 midstate (大端序)；区块头不足 64 字节时为 None
+pub fn sha256_midstate(header: &[u8]) -> Option<[u8; 32]> {
+    let words = midstate_words(header)?;
+    let mut midstate = [0u8; 32];
+    for (chunk, word) in midstate.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    Some(midstate)
+}
+
+/// 按当前区块头重新计算工作的 midstate。区块头变化后 (merkle 根、extranonce2) 都要调用；
+/// 非 SHA256d 算法不使用 midstate，保持全零
+pub fn refresh(work: &mut Work, algorithm: Algorithm) {
+    work.midstate = match algorithm {
+        Algorithm::Sha256d => sha256_midstate(&work.header).unwrap_or_default(),
+        _ => [0u8; 32],
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn sample_header() -> [u8; 80] {
+        let mut header = [0u8; 80];
+        for (i, byte) in header.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(37).wrapping_add(11);
+        }
+        header
+    }
+
+    #[test]
+    fn test_midstate_completes_to_full_hash() {
+        let header = sample_header();
+        let mut state = midstate_words(&header).unwrap();
+
+        // 从 midstate 继续处理第二块 (剩余 16 字节 + 填充 + 640 位长度)，结果应与完整 SHA256 相同
+        let mut tail = [0u8; 64];
+        tail[..16].copy_from_slice(&header[64..]);
+        tail[16] = 0x80;
+        tail[56..].copy_from_slice(&(80u64 * 8).to_be_bytes());
+        sha2::compress256(&mut state, &[GenericArray::clone_from_slice(&tail)]);
+
+        let digest: Vec<u8> = state.iter().flat_map(|word| word.to_be_bytes()).collect();
+        assert_eq!(digest, Sha256::digest(header).to_vec());
+    }
+
+    #[test]
+    fn test_midstate_ignores_nonce() {
+        let mut header = sample_header();
+        let before = sha256_midstate(&header).unwrap();
+        header[76..80].copy_from_slice(&0xdeadbeefu32.to_le_bytes());
+        assert_eq!(sha256_midstate(&header).unwrap(), before);
+        header[40] ^= 1;
+        assert_ne!(sha256_midstate(&header).unwrap(), before);
+        assert_eq!(sha256_midstate(&header[..63]), None);
+    }
+
+    #[test]
+    fn test_refresh_sets_work_midstate() {
+        let mut work = Work::new("job".to_string(), [0xff; 32], sample_header(), 1.0);
+        assert_eq!(work.midstate, [0u8; 32]);
+        refresh(&mut work, Algorithm::Sha256d);
+        assert_eq!(Some(work.midstate), sha256_midstate(&work.header));
+        refresh(&mut work, Algorithm::Scrypt);
+        assert_eq!(work.midstate, [0u8; 32]);
+    }
+}
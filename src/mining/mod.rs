@@ -1,17 +1,53 @@
 pub mod manager;
 pub mod work_queue;
 pub mod hashmeter;
+pub mod share_filter;
+pub mod failover;
+pub mod core_results;
+pub mod algorithm;
+pub mod network;
+pub mod hash_backend;
+pub mod cpu_topology;
+pub mod cpu_load;
+pub mod cpu_thermal;
+pub mod self_test;
+pub mod worker_priority;
+pub mod queue;
+pub mod stats_counters;
+pub mod result_poll;
+pub mod load_test;
+pub mod startup;
+pub mod core_lifecycle;
+pub mod midstate;
+pub mod shutdown;
+pub mod session_report;
+pub mod stats_file;
 
 use crate::config::Config;
 use cgminer_core::Work;
 use cgminer_core::types::MiningResult;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
 pub use manager::MiningManager;
 
-pub use hashmeter::{Hashmeter, HashmeterConfig};
+pub use hashmeter::{Hashmeter, HashmeterConfig, HashmeterFormat};
+pub use share_filter::ShareTargetFilter;
+pub use failover::CoreFailoverConfig;
+pub use algorithm::Algorithm;
+pub use network::Network;
+pub use hash_backend::HashBackend;
+pub use cpu_topology::{CpuTopology, DeviceCount, ThreadSizing};
+pub use cpu_load::CpuLoadTargetConfig;
+pub use cpu_thermal::{CpuThermalThrottleConfig, ThrottleAction};
+pub use self_test::SelfTestReport;
+pub use worker_priority::WorkerPriorityConfig;
+pub use queue::{OverflowPolicy, QueueStats};
+pub use stats_counters::StatsCounters;
+pub use startup::StartupTimings;
+pub use core_lifecycle::{CoreLifecycle, CoreLifecycleStage};
 
 /// 挖矿状态
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -40,6 +76,8 @@ pub struct MiningStats {
     pub rejected_shares: u64,
     pub hardware_errors: u64,
     pub stale_shares: u64,
+    /// 低于份额目标被预过滤的结果数
+    pub below_target_results: u64,
     pub best_share: f64,
     pub current_difficulty: f64,
     pub network_difficulty: f64,
@@ -88,6 +126,10 @@ impl MiningStats {
         self.stale_shares += 1;
     }
 
+    pub fn record_below_target(&mut self) {
+        self.below_target_results += 1;
+    }
+
     pub fn update_hashrate(&mut self, hashrate: f64) {
         self.current_hashrate = hashrate;
 
@@ -157,8 +199,14 @@ pub struct MiningConfig {
     /// 结果收集间隔 - 从配置文件读取
     pub result_collection_interval: Duration,
     pub work_distribution_strategy: WorkDistributionStrategy,
+    /// 工作队列容量，MiningManager 按它创建有界队列
     pub max_work_queue_size: usize,
+    /// 结果队列容量
     pub max_result_queue_size: usize,
+    /// 工作队列满时的处理方式
+    pub work_queue_overflow: OverflowPolicy,
+    /// 结果队列满时的处理方式
+    pub result_queue_overflow: OverflowPolicy,
     pub batch_size: usize,
     pub enable_auto_tuning: bool,
     pub target_temperature: f32,
@@ -174,6 +222,8 @@ impl Default for MiningConfig {
             work_distribution_strategy: WorkDistributionStrategy::LoadBalance,
             max_work_queue_size: 1000,
             max_result_queue_size: 1000,
+            work_queue_overflow: OverflowPolicy::DropOldest,
+            result_queue_overflow: OverflowPolicy::Block,
             batch_size: 100,
             enable_auto_tuning: true,
             target_temperature: 75.0,
@@ -189,8 +239,10 @@ impl From<&Config> for MiningConfig {
             scan_interval: Duration::from_secs(config.general.scan_time),
             result_collection_interval: Duration::from_millis(config.general.result_collection_interval_ms),
             work_distribution_strategy: WorkDistributionStrategy::LoadBalance,
-            max_work_queue_size: 1000, // 可以从配置中读取
-            max_result_queue_size: 1000,
+            max_work_queue_size: config.general.max_work_queue_size,
+            max_result_queue_size: config.general.max_result_queue_size,
+            work_queue_overflow: config.general.work_queue_overflow,
+            result_queue_overflow: config.general.result_queue_overflow,
             batch_size: 100,
             enable_auto_tuning: config.devices.auto_detect, // 临时映射
             target_temperature: 75.0,
@@ -199,27 +251,118 @@ impl From<&Config> for MiningConfig {
     }
 }
 
+/// 可在运行时调整的通用设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    /// 扫描时间间隔 (秒)
+    pub scan_time: u64,
+    /// 工作重启超时时间 (秒)
+    pub work_restart_timeout: u64,
+    /// 结果收集间隔 (毫秒)
+    pub result_collection_interval_ms: u64,
+    /// 算力计量器输出间隔 (秒)
+    pub hashmeter_log_interval: u64,
+}
+
+/// 运行时设置更新，未提供的字段保持不变
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuntimeSettingsUpdate {
+    pub scan_time: Option<u64>,
+    pub work_restart_timeout: Option<u64>,
+    pub result_collection_interval_ms: Option<u64>,
+    pub hashmeter_log_interval: Option<u64>,
+}
+
+impl RuntimeSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            scan_time: config.general.scan_time,
+            work_restart_timeout: config.general.work_restart_timeout,
+            result_collection_interval_ms: config.general.result_collection_interval_ms,
+            hashmeter_log_interval: config.hashmeter.log_interval,
+        }
+    }
+
+    /// 应用更新并验证，失败时不修改当前设置
+    pub fn apply(&self, update: &RuntimeSettingsUpdate) -> Result<Self, String> {
+        let updated = Self {
+            scan_time: update.scan_time.unwrap_or(self.scan_time),
+            work_restart_timeout: update.work_restart_timeout.unwrap_or(self.work_restart_timeout),
+            result_collection_interval_ms: update.result_collection_interval_ms.unwrap_or(self.result_collection_interval_ms),
+            hashmeter_log_interval: update.hashmeter_log_interval.unwrap_or(self.hashmeter_log_interval),
+        };
+
+        if updated.scan_time == 0 || updated.scan_time > 3600 {
+            return Err(format!("scan_time {} is out of range (1-3600)", updated.scan_time));
+        }
+        if updated.work_restart_timeout == 0 {
+            return Err("work_restart_timeout must be greater than 0".to_string());
+        }
+        if updated.result_collection_interval_ms == 0 || updated.result_collection_interval_ms > 60_000 {
+            return Err(format!(
+                "result_collection_interval_ms {} is out of range (1-60000)",
+                updated.result_collection_interval_ms
+            ));
+        }
+        if updated.hashmeter_log_interval == 0 {
+            return Err("hashmeter_log_interval must be greater than 0".to_string());
+        }
+
+        Ok(updated)
+    }
+
+    /// 对应的配置文件键
+    pub fn config_keys(&self) -> Vec<crate::config::persist::KeyUpdate<'static>> {
+        vec![
+            ("general", "scan_time", toml::Value::Integer(self.scan_time as i64)),
+            ("general", "work_restart_timeout", toml::Value::Integer(self.work_restart_timeout as i64)),
+            ("general", "result_collection_interval_ms", toml::Value::Integer(self.result_collection_interval_ms as i64)),
+            ("hashmeter", "log_interval", toml::Value::Integer(self.hashmeter_log_interval as i64)),
+        ]
+    }
+}
+
 /// 工作项
+///
+/// `work` 在矿池、队列、分发器和设备之间共享，克隆工作项不复制区块头、coinbase 和 merkle 分支。
 #[derive(Debug, Clone)]
 pub struct WorkItem {
-    pub work: Work,
+    pub work: Arc<Work>,
     pub assigned_device: Option<u32>,
     pub created_at: SystemTime,
     pub priority: u8,
     pub retry_count: u32,
+    /// 矿池份额目标难度（由分发器附加）
+    pub share_difficulty: Option<f64>,
+    /// 工作所属矿池的算法
+    pub algorithm: Algorithm,
 }
 
 impl WorkItem {
-    pub fn new(work: Work) -> Self {
+    pub fn new(work: impl Into<Arc<Work>>) -> Self {
         Self {
-            work,
+            work: work.into(),
             assigned_device: None,
             created_at: SystemTime::now(),
             priority: 0,
             retry_count: 0,
+            share_difficulty: None,
+            algorithm: Algorithm::default(),
         }
     }
 
+    /// 交给设备的工作，与本工作项共享同一份数据
+    pub fn shared_work(&self) -> Arc<Work> {
+        self.work.clone()
+    }
+
+    /// 设置本工作项的 extranonce2；数据仍被其他工作项共享时先复制一份 (写时复制)
+    pub fn set_extranonce2(&mut self, extranonce2: Vec<u8>) {
+        let work = Arc::make_mut(&mut self.work);
+        work.set_extranonce2(extranonce2);
+        midstate::refresh(work, self.algorithm);
+    }
+
     pub fn with_device(mut self, device_id: u32) -> Self {
         self.assigned_device = Some(device_id);
         self
@@ -230,6 +373,16 @@ impl WorkItem {
         self
     }
 
+    pub fn with_share_difficulty(mut self, share_difficulty: f64) -> Self {
+        self.share_difficulty = Some(share_difficulty);
+        self
+    }
+
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
     pub fn increment_retry(&mut self) {
         self.retry_count += 1;
     }
@@ -320,11 +473,13 @@ impl WorkItem {
     /// 创建工作的深拷贝（确保数据完整性）
     pub fn deep_clone(&self) -> Self {
         Self {
-            work: self.work.clone(),
+            work: Arc::new(Work::clone(&self.work)),
             assigned_device: self.assigned_device,
             created_at: self.created_at,
             priority: self.priority,
             retry_count: self.retry_count,
+            share_difficulty: self.share_difficulty,
+            algorithm: self.algorithm,
         }
     }
 }
@@ -436,6 +591,22 @@ pub enum MiningEvent {
         connected: bool,
         timestamp: SystemTime,
     },
+    /// 设备分批启动进度
+    DeviceRampUp {
+        started_devices: usize,
+        total_devices: usize,
+        completed_batches: usize,
+        total_batches: usize,
+        timestamp: SystemTime,
+    },
+    /// 核心创建/启动/停止/移除/出错
+    CoreLifecycle {
+        core_id: String,
+        core_type: String,
+        stage: CoreLifecycleStage,
+        error: Option<String>,
+        timestamp: SystemTime,
+    },
 }
 
 impl MiningEvent {
@@ -449,6 +620,8 @@ impl MiningEvent {
             MiningEvent::HardwareError { timestamp, .. } => *timestamp,
             MiningEvent::DeviceStateChanged { timestamp, .. } => *timestamp,
             MiningEvent::PoolConnectionChanged { timestamp, .. } => *timestamp,
+            MiningEvent::DeviceRampUp { timestamp, .. } => *timestamp,
+            MiningEvent::CoreLifecycle { timestamp, .. } => *timestamp,
         }
     }
 
@@ -462,6 +635,28 @@ impl MiningEvent {
             MiningEvent::HardwareError { .. } => "hardware_error",
             MiningEvent::DeviceStateChanged { .. } => "device_state_changed",
             MiningEvent::PoolConnectionChanged { .. } => "pool_connection_changed",
+            MiningEvent::DeviceRampUp { .. } => "device_ramp_up",
+            MiningEvent::CoreLifecycle { .. } => "core_lifecycle",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_item_shares_work_until_written() {
+        let item = WorkItem::new(Work::new("job".to_string(), [0xff; 32], [1; 80], 1.0));
+        let device_work = item.shared_work();
+        assert!(Arc::ptr_eq(&item.work, &device_work));
+
+        // 修改 extranonce2 时复制，原工作项和已交给设备的工作不受影响
+        let mut per_device = item.clone();
+        per_device.set_extranonce2(vec![0, 0, 0, 1]);
+        assert!(!Arc::ptr_eq(&per_device.work, &item.work));
+        assert_eq!(per_device.work.extranonce2, vec![0, 0, 0, 1]);
+        assert_eq!(Some(per_device.work.midstate), midstate::sha256_midstate(&per_device.work.header));
+        assert_eq!(item.work.extranonce2, device_work.extranonce2);
+    }
+}
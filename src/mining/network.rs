@@ -0,0 +1,85 @@
+//! 比特币网络 (`pools.network`)
+//!
+//! 测试网与主网使用相同的难度 1 目标，只是地址前缀不同。regtest 的工作量证明上限是
+//! 0x7fffff << 232，按主网的难度 1 目标换算，一个区块的难度只有约 4.7e-10，份额难度无法表示；
+//! 因此 regtest 下难度 1 对应 regtest 的工作量证明上限，本地 regtest 节点上几次哈希就能出块，
+//! 可以用来端到端测试从取工作到提交份额的完整流程。
+//!
+//! 网络从 `pools.network` 显式传给矿池客户端、份额验证和核心结果处理，
+//! 由它们传给 [`Algorithm::diff1_target_on`](super::Algorithm::diff1_target_on)。
+
+use serde::{Deserialize, Serialize};
+
+/// 比特币网络
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    #[default]
+    #[serde(alias = "main")]
+    Mainnet,
+    #[serde(alias = "test", alias = "testnet3", alias = "testnet4")]
+    Testnet,
+    Regtest,
+}
+
+impl Network {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Regtest => "regtest",
+        }
+    }
+
+    /// regtest 的工作量证明上限 0x7fffff << 232，作为该网络上难度 1 的目标；
+    /// 其它网络使用算法自己的难度 1 目标
+    pub fn diff1_target_override(&self) -> Option<f64> {
+        match self {
+            Network::Regtest => Some(8_388_607.0 * 2f64.powi(232)),
+            Network::Mainnet | Network::Testnet => None,
+        }
+    }
+
+    /// bech32 地址的人类可读前缀
+    pub fn bech32_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+
+    /// base58check 地址的 P2PKH / P2SH 版本字节 (测试网和 regtest 相同)
+    pub fn base58_versions(&self) -> [u8; 2] {
+        match self {
+            Network::Mainnet => [0x00, 0x05],
+            Network::Testnet | Network::Regtest => [0x6f, 0xc4],
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_names() {
+        let parsed: Network = serde_json::from_str("\"testnet4\"").unwrap();
+        assert_eq!(parsed, Network::Testnet);
+        assert_eq!(serde_json::to_string(&Network::Regtest).unwrap(), "\"regtest\"");
+    }
+
+    #[test]
+    fn test_regtest_pow_limit() {
+        // 0x7fffff << 232 的最高字节是 0x7f
+        let limit = Network::Regtest.diff1_target_override().unwrap();
+        assert_eq!((limit / 2f64.powi(248)).floor(), 127.0);
+        assert_eq!(Network::Testnet.diff1_target_override(), None);
+    }
+}
@@ -0,0 +1,276 @@
+//! 有界、带统计的工作/结果队列，容量和溢出策略取自 [`MiningConfig`](super::MiningConfig)
+//!
+//! 取代无界的 mpsc 通道：消费者停滞时内存不再无限增长。队列满时按溢出策略处理：
+//! 丢弃最旧的条目 (工作队列默认，旧工作很快就会过期)、丢弃新条目，或让发送方等待 (结果队列默认，
+//! 不丢份额)。深度、最高水位、入队和丢弃计数通过 `QueueStats` 导出。
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// 队列满时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// 丢弃队首最旧的条目，放入新条目
+    DropOldest,
+    /// 丢弃新条目
+    DropNewest,
+    /// 发送方等待消费者腾出空间 (背压)
+    Block,
+}
+
+impl OverflowPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverflowPolicy::DropOldest => "drop_oldest",
+            OverflowPolicy::DropNewest => "drop_newest",
+            OverflowPolicy::Block => "block",
+        }
+    }
+}
+
+/// 发送失败的原因
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SendError {
+    #[error("queue {0} is closed")]
+    Closed(&'static str),
+    #[error("queue {0} is full, item dropped")]
+    Full(&'static str),
+}
+
+/// 队列统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueueStats {
+    pub name: &'static str,
+    pub depth: usize,
+    pub capacity: usize,
+    pub overflow: OverflowPolicy,
+    /// 启动以来的最大深度
+    pub high_water: usize,
+    pub enqueued: u64,
+    pub dropped: u64,
+}
+
+struct Shared<T> {
+    name: &'static str,
+    capacity: usize,
+    policy: OverflowPolicy,
+    items: Mutex<VecDeque<T>>,
+    not_empty: Notify,
+    not_full: Notify,
+    senders: AtomicUsize,
+    receiver_closed: AtomicBool,
+    enqueued: AtomicU64,
+    dropped: AtomicU64,
+    high_water: AtomicUsize,
+}
+
+impl<T> Shared<T> {
+    fn stats(&self) -> QueueStats {
+        QueueStats {
+            name: self.name,
+            depth: self.items.lock().unwrap().len(),
+            capacity: self.capacity,
+            overflow: self.policy,
+            high_water: self.high_water.load(Ordering::Relaxed),
+            enqueued: self.enqueued.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    fn pushed(&self, depth: usize) {
+        self.enqueued.fetch_add(1, Ordering::Relaxed);
+        self.high_water.fetch_max(depth, Ordering::Relaxed);
+        self.not_empty.notify_one();
+    }
+}
+
+/// 创建有界队列
+pub fn bounded<T>(name: &'static str, capacity: usize, policy: OverflowPolicy) -> (QueueSender<T>, QueueReceiver<T>) {
+    let shared = Arc::new(Shared {
+        name,
+        capacity: capacity.max(1),
+        policy,
+        items: Mutex::new(VecDeque::with_capacity(capacity.max(1))),
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+        senders: AtomicUsize::new(1),
+        receiver_closed: AtomicBool::new(false),
+        enqueued: AtomicU64::new(0),
+        dropped: AtomicU64::new(0),
+        high_water: AtomicUsize::new(0),
+    });
+    (QueueSender { shared: shared.clone() }, QueueReceiver { shared })
+}
+
+/// 队列发送端
+pub struct QueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueSender<T> {
+    /// 按溢出策略放入一个条目；`Block` 策略下队列满时等待
+    pub async fn send(&self, item: T) -> Result<(), SendError> {
+        let mut item = Some(item);
+        loop {
+            match self.try_push(&mut item) {
+                Err(SendError::Full(_)) if self.shared.policy == OverflowPolicy::Block => {
+                    self.shared.not_full.notified().await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 不等待的发送：`Block` 策略下队列满时返回 `Full`，条目不计入丢弃
+    pub fn try_send(&self, item: T) -> Result<(), SendError> {
+        self.try_push(&mut Some(item))
+    }
+
+    fn try_push(&self, item: &mut Option<T>) -> Result<(), SendError> {
+        let shared = &self.shared;
+        if shared.receiver_closed.load(Ordering::Acquire) {
+            return Err(SendError::Closed(shared.name));
+        }
+        let mut items = shared.items.lock().unwrap();
+        if items.len() >= shared.capacity {
+            match shared.policy {
+                OverflowPolicy::DropOldest => {
+                    items.pop_front();
+                    shared.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                OverflowPolicy::DropNewest => {
+                    item.take();
+                    shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Err(SendError::Full(shared.name));
+                }
+                OverflowPolicy::Block => return Err(SendError::Full(shared.name)),
+            }
+        }
+        if let Some(item) = item.take() {
+            items.push_back(item);
+        }
+        let depth = items.len();
+        drop(items);
+        shared.pushed(depth);
+        Ok(())
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        self.shared.stats()
+    }
+}
+
+impl<T> Clone for QueueSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self { shared: self.shared.clone() }
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // 最后一个发送端：唤醒接收端结束
+            self.shared.not_empty.notify_one();
+        }
+    }
+}
+
+/// 队列接收端
+pub struct QueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> QueueReceiver<T> {
+    /// 取出一个条目，队列为空且所有发送端都已释放时返回 None
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            if let Some(item) = self.try_recv() {
+                return Some(item);
+            }
+            if self.shared.senders.load(Ordering::Acquire) == 0 {
+                return self.try_recv();
+            }
+            self.shared.not_empty.notified().await;
+        }
+    }
+
+    /// 不等待地取出一个条目
+    pub fn try_recv(&mut self) -> Option<T> {
+        let item = self.shared.items.lock().unwrap().pop_front();
+        if item.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        item
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        self.shared.stats()
+    }
+}
+
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        // 唤醒等待空间的发送端，它们随后返回 Closed
+        self.shared.receiver_closed.store(true, Ordering::Release);
+        self.shared.not_full.notify_waiters();
+        self.shared.not_full.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_drop_oldest() {
+        let (sender, mut receiver) = bounded("work", 2, OverflowPolicy::DropOldest);
+        for i in 0..3 {
+            sender.send(i).await.unwrap();
+        }
+        assert_eq!(receiver.try_recv(), Some(1));
+        assert_eq!(receiver.try_recv(), Some(2));
+        let stats = sender.stats();
+        assert_eq!((stats.enqueued, stats.dropped, stats.high_water, stats.depth), (3, 1, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest() {
+        let (sender, mut receiver) = bounded("work", 1, OverflowPolicy::DropNewest);
+        sender.send(1).await.unwrap();
+        assert_eq!(sender.send(2).await, Err(SendError::Full("work")));
+        assert_eq!(receiver.try_recv(), Some(1));
+        assert_eq!(receiver.stats().dropped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_consumer() {
+        let (sender, mut receiver) = bounded("result", 1, OverflowPolicy::Block);
+        sender.send(1).await.unwrap();
+        assert_eq!(sender.try_send(2), Err(SendError::Full("result")));
+
+        let blocked = tokio::spawn(async move {
+            sender.send(2).await.unwrap();
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+        assert_eq!(receiver.recv().await, Some(1));
+        blocked.await.unwrap();
+        assert_eq!(receiver.recv().await, Some(2));
+        // 发送端已释放
+        assert_eq!(receiver.recv().await, None);
+        assert_eq!(receiver.stats().dropped, 0);
+    }
+
+    #[tokio::test]
+    async fn test_closed_receiver() {
+        let (sender, receiver) = bounded::<u32>("work", 1, OverflowPolicy::DropOldest);
+        drop(receiver);
+        assert_eq!(sender.try_send(1), Err(SendError::Closed("work")));
+    }
+}
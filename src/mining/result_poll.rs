@@ -0,0 +1,91 @@
+//! 自适应结果轮询间隔
+//!
+//! 核心和应用层设备的结果都靠轮询收集 (见 `core_results`)。空闲时每 20ms 轮询一次在低功耗设备上
+//! 浪费 CPU，这里按结果到达情况调整间隔：
+//! 有结果时回到 `result_collection_interval_ms`，连续空轮询时间隔翻倍，直到
+//! `result_collection_max_interval_ms`。
+
+use std::time::Duration;
+
+/// 未配置 `result_collection_max_interval_ms` 时的空闲轮询上限 (毫秒)
+pub const DEFAULT_MAX_POLL_MS: u64 = 1000;
+
+/// 未配置 `result_collection_interval_ms` 时的基础轮询间隔 (毫秒)
+pub const DEFAULT_POLL_MS: u64 = 20;
+
+/// 核心算力统计的刷新间隔，与结果轮询无关
+pub const CORE_STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 自适应轮询间隔
+#[derive(Debug, Clone)]
+pub struct AdaptivePoll {
+    base: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl AdaptivePoll {
+    /// 基础间隔和上限，0 表示使用默认值；上限不小于基础间隔
+    pub fn new(base_ms: u64, max_ms: u64) -> Self {
+        let base = Duration::from_millis(if base_ms == 0 { DEFAULT_POLL_MS } else { base_ms });
+        let max = Duration::from_millis(if max_ms == 0 { DEFAULT_MAX_POLL_MS } else { max_ms }).max(base);
+        Self { base, max, current: base }
+    }
+
+    /// 运行时修改基础间隔，当前间隔回到新的基础间隔
+    pub fn set_base(&mut self, base_ms: u64) {
+        let max_ms = self.max.as_millis() as u64;
+        *self = Self::new(base_ms, max_ms);
+    }
+
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    pub fn current(&self) -> Duration {
+        self.current
+    }
+
+    /// 按本轮收集到的结果数调整并返回下一次的间隔
+    pub fn record(&mut self, results: usize) -> Duration {
+        self.current = if results > 0 { self.base } else { (self.current * 2).min(self.max) };
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backs_off_when_idle_and_resets_on_results() {
+        let mut poll = AdaptivePoll::new(20, 150);
+        assert_eq!(poll.current(), Duration::from_millis(20));
+        assert_eq!(poll.record(0), Duration::from_millis(40));
+        assert_eq!(poll.record(0), Duration::from_millis(80));
+        assert_eq!(poll.record(0), Duration::from_millis(150));
+        assert_eq!(poll.record(0), Duration::from_millis(150));
+        assert_eq!(poll.record(3), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_defaults_and_bounds() {
+        let poll = AdaptivePoll::new(0, 0);
+        assert_eq!(poll.base(), Duration::from_millis(DEFAULT_POLL_MS));
+        // 上限小于基础间隔时按基础间隔固定轮询
+        let mut poll = AdaptivePoll::new(500, 100);
+        assert_eq!(poll.record(0), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_set_base_keeps_max() {
+        let mut poll = AdaptivePoll::new(20, 400);
+        poll.record(0);
+        poll.set_base(50);
+        assert_eq!(poll.current(), Duration::from_millis(50));
+        for _ in 0..10 {
+            poll.record(0);
+        }
+        assert_eq!(poll.current(), Duration::from_millis(400));
+    }
+}
@@ -0,0 +1,222 @@
+//! 核心自检 (`--self-test`)
+//!
+//! 用一组已知的区块头 (主网早期区块) 和它们的黄金 nonce 检查核心：每个区块头的 nonce
+//! 字段设置为黄金 nonce 之前 `SCAN_WINDOW` 处，目标为该区块的 nBits，核心从该位置向上扫描，
+//! 必须在超时前返回黄金 nonce。修改 SIMD 内核、GPU 内核或 ASIC 数据通路之后运行，
+//! 可以在连接矿池之前发现算错哈希或丢失 nonce 的问题。
+
+use crate::mining::{midstate, Algorithm, Network};
+use cgminer_core::{CoreRegistry, Work};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+/// 核心需要扫描的 nonce 数 (黄金 nonce 之前)
+pub const SCAN_WINDOW: u32 = 1 << 20;
+
+/// 每个测试向量的超时
+pub const VECTOR_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 已知区块头及其黄金 nonce
+#[derive(Debug, Clone, Copy)]
+pub struct TestVector {
+    pub name: &'static str,
+    /// 80 字节区块头 (十六进制，含黄金 nonce)
+    pub header_hex: &'static str,
+    pub golden_nonce: u32,
+}
+
+/// 主网区块 0、1、2 和 100000
+pub const TEST_VECTORS: [TestVector; 4] = [
+    TestVector {
+        name: "block 0 (genesis)",
+        header_hex: "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c",
+        golden_nonce: 2_083_236_893,
+    },
+    TestVector {
+        name: "block 1",
+        header_hex: "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299",
+        golden_nonce: 2_573_394_689,
+    },
+    TestVector {
+        name: "block 2",
+        header_hex: "010000004860eb18bf1b1620e37e9490fc8a427514416fd75159ab86688e9a8300000000d5fdcc541e25de1c7a5addedf24858b8bb665c9f36ef744ee42c316022c90f9bb0bc6649ffff001d08d2bd61",
+        golden_nonce: 1_639_830_024,
+    },
+    TestVector {
+        name: "block 100000",
+        header_hex: "0100000050120119172a610421a6c3011dd330d9df07b63616c2cc1f1cd00200000000006657a9252aacd5c0b2940996ecff952228c3067cc38d4885efb5a4ac4247e9f337221b4d4c86041b0f2b5710",
+        golden_nonce: 274_148_111,
+    },
+];
+
+impl TestVector {
+    /// 区块头，nonce 字段为 `nonce`
+    pub fn header_with_nonce(&self, nonce: u32) -> [u8; 80] {
+        let mut header = [0u8; 80];
+        hex::decode_to_slice(self.header_hex, &mut header).expect("test vector header is 80 bytes of hex");
+        header[76..80].copy_from_slice(&nonce.to_le_bytes());
+        header
+    }
+
+    /// 区块头中的 nBits
+    pub fn bits(&self) -> u32 {
+        let header = self.header_with_nonce(self.golden_nonce);
+        u32::from_le_bytes([header[72], header[73], header[74], header[75]])
+    }
+
+    /// 交给核心的工作：nonce 从黄金 nonce 之前 `SCAN_WINDOW` 处开始，目标为区块的 nBits
+    pub fn work(&self) -> Work {
+        let target = target_from_bits(self.bits());
+        let difficulty = Algorithm::Sha256d.hash_difficulty(Network::Mainnet, &target).unwrap_or(1.0);
+        let header = self.header_with_nonce(self.golden_nonce.wrapping_sub(SCAN_WINDOW));
+        let mut work = Work::new(format!("self-test {}", self.name), target, header, difficulty);
+        midstate::refresh(&mut work, Algorithm::Sha256d);
+        work
+    }
+}
+
+/// 区块头的双 SHA256 (小端序)
+pub fn double_sha256(header: &[u8; 80]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(header)).into()
+}
+
+/// nBits 对应的 256 位目标，小端序
+pub fn target_from_bits(bits: u32) -> [u8; 32] {
+    let mut target = [0u8; 32];
+    let exponent = (bits >> 24) as usize;
+    let mantissa = (bits & 0x00ff_ffff).to_le_bytes();
+    for (i, byte) in mantissa.iter().take(3).enumerate() {
+        if let Some(position) = (exponent + i).checked_sub(3).filter(|p| *p < 32) {
+            target[position] = *byte;
+        }
+    }
+    target
+}
+
+/// 哈希 (小端序) 是否不大于目标
+pub fn meets_target(hash: &[u8; 32], target: &[u8; 32]) -> bool {
+    hash.iter().rev().cmp(target.iter().rev()) != std::cmp::Ordering::Greater
+}
+
+/// 单个测试向量的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum VectorOutcome {
+    Passed { elapsed_ms: u64 },
+    /// 核心返回了不满足目标的 nonce
+    WrongNonce { nonce: u32 },
+    Timeout,
+    Error { message: String },
+}
+
+/// 一个核心的自检报告
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub core_id: String,
+    pub vectors: Vec<(String, VectorOutcome)>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.vectors.iter().all(|(_, outcome)| matches!(outcome, VectorOutcome::Passed { .. }))
+    }
+}
+
+impl std::fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Self-test for core {}:", self.core_id)?;
+        for (name, outcome) in &self.vectors {
+            match outcome {
+                VectorOutcome::Passed { elapsed_ms } => writeln!(f, "  ✅ {} ({} ms)", name, elapsed_ms)?,
+                VectorOutcome::WrongNonce { nonce } => writeln!(f, "  ❌ {}: nonce {:#010x} does not meet the target", name, nonce)?,
+                VectorOutcome::Timeout => writeln!(f, "  ❌ {}: golden nonce not found in time", name)?,
+                VectorOutcome::Error { message } => writeln!(f, "  ❌ {}: {}", name, message)?,
+            }
+        }
+        let passed = self.vectors.iter().filter(|(_, outcome)| matches!(outcome, VectorOutcome::Passed { .. })).count();
+        write!(f, "{}/{} vectors passed", passed, self.vectors.len())
+    }
+}
+
+/// 在已启动的核心上逐个运行测试向量
+pub async fn run_vectors(core_registry: &CoreRegistry, core_id: &str, timeout: Duration) -> SelfTestReport {
+    let mut vectors = Vec::new();
+    for vector in &TEST_VECTORS {
+        let outcome = run_vector(core_registry, core_id, vector, timeout).await;
+        vectors.push((vector.name.to_string(), outcome));
+    }
+    SelfTestReport { core_id: core_id.to_string(), vectors }
+}
+
+async fn run_vector(core_registry: &CoreRegistry, core_id: &str, vector: &TestVector, timeout: Duration) -> VectorOutcome {
+    let work = vector.work();
+    let work_id = work.id;
+    let started = Instant::now();
+    if let Err(e) = core_registry.submit_work_to_core(core_id, work).await {
+        return VectorOutcome::Error { message: e.to_string() };
+    }
+
+    while started.elapsed() < timeout {
+        match core_registry.collect_results_from_core(core_id).await {
+            Ok(results) => {
+                for result in results.into_iter().filter(|result| result.work_id == work_id) {
+                    // 按 nonce 重新计算哈希，不信任核心报告的哈希
+                    let hash = double_sha256(&vector.header_with_nonce(result.nonce));
+                    if !meets_target(&hash, &target_from_bits(vector.bits())) {
+                        return VectorOutcome::WrongNonce { nonce: result.nonce };
+                    }
+                    if result.nonce == vector.golden_nonce {
+                        return VectorOutcome::Passed { elapsed_ms: started.elapsed().as_millis() as u64 };
+                    }
+                }
+            }
+            Err(e) => return VectorOutcome::Error { message: e.to_string() },
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    VectorOutcome::Timeout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_nonces_meet_target() {
+        for vector in &TEST_VECTORS {
+            let hash = double_sha256(&vector.header_with_nonce(vector.golden_nonce));
+            assert!(meets_target(&hash, &target_from_bits(vector.bits())), "{}", vector.name);
+            // 换一个 nonce 就不再满足目标
+            let hash = double_sha256(&vector.header_with_nonce(vector.golden_nonce.wrapping_add(1)));
+            assert!(!meets_target(&hash, &target_from_bits(vector.bits())), "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_genesis_hash() {
+        let genesis = &TEST_VECTORS[0];
+        let mut hash = double_sha256(&genesis.header_with_nonce(genesis.golden_nonce));
+        hash.reverse();
+        assert_eq!(hex::encode(hash), "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f");
+    }
+
+    #[test]
+    fn test_target_from_bits() {
+        let target = target_from_bits(0x1d00ffff);
+        // 难度 1：0x00000000ffff0000...
+        assert_eq!(&target[26..28], &[0xff, 0xff]);
+        assert!(target[28..].iter().all(|byte| *byte == 0));
+        assert!(target[..26].iter().all(|byte| *byte == 0));
+        let difficulty = Algorithm::Sha256d.hash_difficulty(Network::Mainnet, &target).unwrap();
+        assert!((difficulty - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_work_starts_before_golden_nonce() {
+        let vector = &TEST_VECTORS[1];
+        let work = vector.work();
+        let start = u32::from_le_bytes(work.header[76..80].try_into().unwrap());
+        assert_eq!(vector.golden_nonce.wrapping_sub(start), SCAN_WINDOW);
+    }
+}
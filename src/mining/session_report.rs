@@ -0,0 +1,383 @@
+//! 会话报告 (`[session_report]`)
+//!
+//! 停机时和每天的 `rollover_time` (本地时间) 把这段时间的挖矿汇总写成一个 JSON 报告：总哈希数、
+//! 份额 A/R/HW、最佳份额、按矿池和按设备的明细、运行时长和平均温度。报告写入 `dir`，设置了
+//! `webhook_url` 时同时 POST 到该地址。每份报告只统计上一份报告之后的部分；份额计数器不记录时间，
+//! 最佳份额取整个会话的最佳值。总哈希数和平均温度由每分钟一次的算力、温度采样累计得到。
+
+use crate::error::MiningError;
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 会话报告配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionReportConfig {
+    /// 是否写入会话报告
+    pub enabled: bool,
+    /// 报告目录
+    pub dir: String,
+    /// 每天切换报告的本地时间 (HH:MM)，为空时只在停机时写入
+    pub rollover_time: String,
+    /// 报告的 POST 地址，为空时不发送
+    pub webhook_url: String,
+    /// 目录中保留的报告数量
+    pub max_reports: usize,
+}
+
+impl Default for SessionReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: "./reports".to_string(),
+            rollover_time: "00:00".to_string(),
+            webhook_url: String::new(),
+            max_reports: 90,
+        }
+    }
+}
+
+impl SessionReportConfig {
+    /// 解析 `rollover_time`，为空时返回 Ok(None)
+    pub fn rollover(&self) -> Result<Option<NaiveTime>, String> {
+        if self.rollover_time.trim().is_empty() {
+            return Ok(None);
+        }
+        NaiveTime::parse_from_str(self.rollover_time.trim(), "%H:%M")
+            .map(Some)
+            .map_err(|_| format!("Session report rollover_time '{}' must be HH:MM", self.rollover_time))
+    }
+}
+
+/// 报告原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportReason {
+    /// 停机
+    Shutdown,
+    /// 每日切换
+    Rollover,
+}
+
+impl ReportReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportReason::Shutdown => "shutdown",
+            ReportReason::Rollover => "rollover",
+        }
+    }
+}
+
+/// 累计份额计数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShareCounts {
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub hardware_errors: u64,
+    pub accepted_difficulty: f64,
+}
+
+impl ShareCounts {
+    /// 相对 `earlier` 的增量，计数器在两次快照之间重置 (设备重建) 时按零处理
+    fn since(&self, earlier: &ShareCounts) -> ShareCounts {
+        ShareCounts {
+            accepted_shares: self.accepted_shares.saturating_sub(earlier.accepted_shares),
+            rejected_shares: self.rejected_shares.saturating_sub(earlier.rejected_shares),
+            hardware_errors: self.hardware_errors.saturating_sub(earlier.hardware_errors),
+            accepted_difficulty: (self.accepted_difficulty - earlier.accepted_difficulty).max(0.0),
+        }
+    }
+}
+
+/// 某一时刻的累计计数，报告取两次快照之差
+#[derive(Debug, Clone, Default)]
+pub struct SessionSnapshot {
+    /// 挖矿开始后的运行时长
+    pub uptime: Duration,
+    pub totals: ShareCounts,
+    pub stale_shares: u64,
+    pub best_share: f64,
+    /// 矿池 ID → (地址, 计数)
+    pub pools: BTreeMap<u32, (String, ShareCounts)>,
+    /// 设备 ID → (名称, 计数)
+    pub devices: BTreeMap<u32, (String, ShareCounts)>,
+}
+
+/// 矿池明细
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolReport {
+    pub pool_id: u32,
+    pub url: String,
+    #[serde(flatten)]
+    pub shares: ShareCounts,
+}
+
+/// 设备明细
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceReport {
+    pub device_id: u32,
+    pub name: String,
+    #[serde(flatten)]
+    pub shares: ShareCounts,
+    pub average_temperature: Option<f32>,
+}
+
+/// 会话报告内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub version: String,
+    pub reason: ReportReason,
+    pub period_start: String,
+    pub period_end: String,
+    /// 报告覆盖的时长 (秒)
+    pub period_secs: u64,
+    /// 挖矿开始后的运行时长 (秒)
+    pub uptime_secs: u64,
+    pub total_hashes: u64,
+    /// 报告时段内的平均算力 (H/s)
+    pub average_hashrate: f64,
+    #[serde(flatten)]
+    pub shares: ShareCounts,
+    pub stale_shares: u64,
+    pub best_share: f64,
+    pub average_temperature: Option<f32>,
+    pub pools: Vec<PoolReport>,
+    pub devices: Vec<DeviceReport>,
+}
+
+/// 温度均值
+#[derive(Debug, Clone, Copy, Default)]
+struct TemperatureAverage {
+    sum: f64,
+    count: u64,
+}
+
+impl TemperatureAverage {
+    fn add(&mut self, celsius: f32) {
+        self.sum += celsius as f64;
+        self.count += 1;
+    }
+
+    fn value(&self) -> Option<f32> {
+        (self.count > 0).then(|| (self.sum / self.count as f64) as f32)
+    }
+}
+
+/// 会话报告器：保存上一份报告时的快照，并在两份报告之间累计哈希数和温度采样
+pub struct SessionReporter {
+    period_start: DateTime<Local>,
+    baseline: SessionSnapshot,
+    hashes: f64,
+    last_sample: Option<Instant>,
+    temperature: TemperatureAverage,
+    device_temperatures: BTreeMap<u32, TemperatureAverage>,
+}
+
+impl SessionReporter {
+    pub fn new(now: DateTime<Local>) -> Self {
+        Self {
+            period_start: now,
+            baseline: SessionSnapshot::default(),
+            hashes: 0.0,
+            last_sample: None,
+            temperature: TemperatureAverage::default(),
+            device_temperatures: BTreeMap::new(),
+        }
+    }
+
+    pub fn period_start(&self) -> DateTime<Local> {
+        self.period_start
+    }
+
+    /// 记录一次采样：按上次采样以来的时间累计哈希数，温度计入时段均值
+    pub fn sample(&mut self, hashrate: f64, temperatures: &[(u32, f32)], now: Instant) {
+        if let Some(last) = self.last_sample {
+            self.hashes += hashrate.max(0.0) * now.duration_since(last).as_secs_f64();
+        }
+        self.last_sample = Some(now);
+        for &(device_id, celsius) in temperatures {
+            self.temperature.add(celsius);
+            self.device_temperatures.entry(device_id).or_default().add(celsius);
+        }
+    }
+
+    /// 生成 `period_start` 到 `now` 的报告，之后从 `now` 开始新的时段
+    pub fn report(&mut self, reason: ReportReason, snapshot: SessionSnapshot, now: DateTime<Local>) -> SessionReport {
+        let period_secs = (now - self.period_start).num_seconds().max(0) as u64;
+        let baseline = &self.baseline;
+        let pools = snapshot.pools.iter().map(|(pool_id, (url, counts))| PoolReport {
+            pool_id: *pool_id,
+            url: url.clone(),
+            shares: counts.since(baseline.pools.get(pool_id).map(|(_, c)| c).unwrap_or(&ShareCounts::default())),
+        }).collect();
+        let devices = snapshot.devices.iter().map(|(device_id, (name, counts))| DeviceReport {
+            device_id: *device_id,
+            name: name.clone(),
+            shares: counts.since(baseline.devices.get(device_id).map(|(_, c)| c).unwrap_or(&ShareCounts::default())),
+            average_temperature: self.device_temperatures.get(device_id).and_then(TemperatureAverage::value),
+        }).collect();
+
+        let report = SessionReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            reason,
+            period_start: self.period_start.to_rfc3339(),
+            period_end: now.to_rfc3339(),
+            period_secs,
+            uptime_secs: snapshot.uptime.as_secs(),
+            total_hashes: self.hashes as u64,
+            average_hashrate: if period_secs > 0 { self.hashes / period_secs as f64 } else { 0.0 },
+            shares: snapshot.totals.since(&baseline.totals),
+            stale_shares: snapshot.stale_shares.saturating_sub(baseline.stale_shares),
+            best_share: snapshot.best_share,
+            average_temperature: self.temperature.value(),
+            pools,
+            devices,
+        };
+
+        self.period_start = now;
+        self.baseline = snapshot;
+        self.hashes = 0.0;
+        self.temperature = TemperatureAverage::default();
+        self.device_temperatures.clear();
+        report
+    }
+}
+
+/// `after` 之后的下一个切换时刻
+pub fn next_rollover<Tz: TimeZone>(after: &DateTime<Tz>, time: NaiveTime) -> Option<DateTime<Tz>> {
+    let date = after.date_naive();
+    let today = after.timezone().from_local_datetime(&date.and_time(time)).earliest()?;
+    if today > *after {
+        return Some(today);
+    }
+    let tomorrow = date.succ_opt()?.and_time(time);
+    after.timezone().from_local_datetime(&tomorrow).earliest()
+}
+
+/// 报告文件名前缀
+const REPORT_PREFIX: &str = "session-";
+
+/// 写入报告，并只保留最近的 `max_reports` 份
+pub fn write_report(dir: &Path, report: &SessionReport, max_reports: usize) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let name = format!("{}{}-{}.json", REPORT_PREFIX, Local::now().format("%Y%m%d-%H%M%S%.3f"), report.reason.as_str());
+    let path = dir.join(name);
+    std::fs::write(&path, serde_json::to_vec_pretty(report)?)?;
+    prune_reports(dir, max_reports);
+    Ok(path)
+}
+
+fn prune_reports(dir: &Path, max_reports: usize) {
+    let mut reports: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries.filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.file_name().and_then(|n| n.to_str())
+                        .is_some_and(|name| name.starts_with(REPORT_PREFIX) && name.ends_with(".json"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    reports.sort();
+    let keep = max_reports.max(1);
+    if reports.len() > keep {
+        for path in &reports[..reports.len() - keep] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// webhook 请求超时，停机时不会因为 webhook 无响应而卡住
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 把报告 POST 到 webhook
+pub async fn post_report(url: &str, report: &SessionReport) -> Result<(), MiningError> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(report)
+        .send()
+        .await
+        .map_err(|e| MiningError::System(format!("Failed to post session report: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(MiningError::System(format!("Session report webhook rejected: HTTP {}", response.status())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn snapshot(accepted: u64, rejected: u64, hardware_errors: u64) -> SessionSnapshot {
+        let counts = ShareCounts { accepted_shares: accepted, rejected_shares: rejected, hardware_errors, accepted_difficulty: accepted as f64 * 512.0 };
+        SessionSnapshot {
+            uptime: Duration::from_secs(3600),
+            totals: counts.clone(),
+            stale_shares: 0,
+            best_share: 4096.0,
+            pools: BTreeMap::from([(0, ("stratum+tcp://pool.example.com:3333".to_string(), counts.clone()))]),
+            devices: BTreeMap::from([(2, ("chain-2".to_string(), counts))]),
+        }
+    }
+
+    #[test]
+    fn test_report_covers_period_since_last_report() {
+        let start = Local::now();
+        let mut reporter = SessionReporter::new(start);
+        let sampled = Instant::now();
+        reporter.sample(1000.0, &[(2, 60.0)], sampled);
+        reporter.sample(1000.0, &[(2, 70.0)], sampled + Duration::from_secs(60));
+
+        let first = reporter.report(ReportReason::Rollover, snapshot(10, 1, 2), start + chrono::Duration::seconds(60));
+        assert_eq!(first.total_hashes, 60_000);
+        assert_eq!(first.average_hashrate, 1000.0);
+        assert_eq!(first.shares.accepted_shares, 10);
+        assert_eq!(first.average_temperature, Some(65.0));
+        assert_eq!(first.devices[0].average_temperature, Some(65.0));
+        assert_eq!(first.pools[0].shares.accepted_difficulty, 5120.0);
+
+        // 第二份报告只包含之后的增量
+        let second = reporter.report(ReportReason::Shutdown, snapshot(15, 1, 3), start + chrono::Duration::seconds(120));
+        assert_eq!(second.period_secs, 60);
+        assert_eq!(second.total_hashes, 0);
+        assert_eq!(second.shares, ShareCounts { accepted_shares: 5, rejected_shares: 0, hardware_errors: 1, accepted_difficulty: 2560.0 });
+        assert_eq!(second.devices[0].shares.accepted_shares, 5);
+        assert_eq!(second.average_temperature, None);
+        assert_eq!(second.best_share, 4096.0);
+    }
+
+    #[test]
+    fn test_next_rollover() {
+        let time = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+        let evening = Utc.with_ymd_and_hms(2026, 3, 1, 22, 30, 0).unwrap();
+        assert_eq!(next_rollover(&evening, time), Some(Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap()));
+
+        let time = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+        assert_eq!(next_rollover(&evening, time), Some(Utc.with_ymd_and_hms(2026, 3, 1, 23, 0, 0).unwrap()));
+
+        let config = SessionReportConfig { rollover_time: "25:00".to_string(), ..Default::default() };
+        assert!(config.rollover().is_err());
+        let config = SessionReportConfig { rollover_time: String::new(), ..Default::default() };
+        assert_eq!(config.rollover(), Ok(None));
+    }
+
+    #[test]
+    fn test_write_and_prune_reports() {
+        let dir = std::env::temp_dir().join(format!("cgminer-session-{}", uuid::Uuid::new_v4()));
+        let mut reporter = SessionReporter::new(Local::now());
+        for _ in 0..3 {
+            let report = reporter.report(ReportReason::Rollover, snapshot(1, 0, 0), Local::now());
+            write_report(&dir, &report, 2).unwrap();
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        let count = std::fs::read_dir(&dir).unwrap().count();
+        assert_eq!(count, 2);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
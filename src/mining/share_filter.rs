@@ -0,0 +1,124 @@
+//! 份额目标过滤
+//!
+//! 分发器在下发工作时记录每个工作的矿池份额难度，结果收集在验证之前
+//! 丢弃低于份额目标的结果，避免异常核心产生的低难度噪声淹没结果管道。
+//! 同时记录工作的算法，结果的份额难度据此换算。
+
+use super::Algorithm;
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// 默认记录的工作数量上限
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// 份额目标记录
+struct TargetTable {
+    targets: HashMap<Uuid, (f64, Algorithm)>,
+    order: VecDeque<Uuid>,
+    capacity: usize,
+}
+
+/// 份额目标过滤器
+pub struct ShareTargetFilter {
+    table: Mutex<TargetTable>,
+}
+
+impl ShareTargetFilter {
+    /// 创建过滤器
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// 创建指定容量的过滤器，超出容量时淘汰最早的工作
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            table: Mutex::new(TargetTable {
+                targets: HashMap::new(),
+                order: VecDeque::new(),
+                capacity: capacity.max(1),
+            }),
+        }
+    }
+
+    /// 记录工作的份额难度
+    pub async fn record(&self, work_id: Uuid, share_difficulty: f64) {
+        self.record_work(work_id, share_difficulty, Algorithm::default()).await;
+    }
+
+    /// 记录工作的份额难度和算法
+    pub async fn record_work(&self, work_id: Uuid, share_difficulty: f64, algorithm: Algorithm) {
+        let mut table = self.table.lock().await;
+        if table.targets.insert(work_id, (share_difficulty, algorithm)).is_none() {
+            table.order.push_back(work_id);
+        }
+        while table.order.len() > table.capacity {
+            if let Some(oldest) = table.order.pop_front() {
+                table.targets.remove(&oldest);
+            }
+        }
+    }
+
+    /// 获取工作的份额难度
+    pub async fn target_for(&self, work_id: &Uuid) -> Option<f64> {
+        self.table.lock().await.targets.get(work_id).map(|(target, _)| *target)
+    }
+
+    /// 工作的算法；未知工作按 SHA256d 处理
+    pub async fn algorithm_for(&self, work_id: &Uuid) -> Algorithm {
+        self.table.lock().await.targets.get(work_id).map(|(_, algorithm)| *algorithm).unwrap_or_default()
+    }
+
+    /// 结果是否达到份额目标；未知工作不过滤，交给后续验证处理
+    pub async fn passes(&self, work_id: &Uuid, share_difficulty: f64) -> bool {
+        match self.target_for(work_id).await {
+            Some(target) => share_difficulty >= target,
+            None => true,
+        }
+    }
+}
+
+impl Default for ShareTargetFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_filter_below_target() {
+        let filter = ShareTargetFilter::new();
+        let work_id = Uuid::new_v4();
+        filter.record(work_id, 1024.0).await;
+
+        assert!(!filter.passes(&work_id, 512.0).await);
+        assert!(filter.passes(&work_id, 1024.0).await);
+        assert!(filter.passes(&Uuid::new_v4(), 1.0).await);
+    }
+
+    #[tokio::test]
+    async fn test_capacity_eviction() {
+        let filter = ShareTargetFilter::with_capacity(2);
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for id in &ids {
+            filter.record(*id, 8.0).await;
+        }
+
+        assert_eq!(filter.target_for(&ids[0]).await, None);
+        assert_eq!(filter.target_for(&ids[2]).await, Some(8.0));
+    }
+
+    #[tokio::test]
+    async fn test_records_algorithm() {
+        let filter = ShareTargetFilter::new();
+        let scrypt_work = Uuid::new_v4();
+        filter.record_work(scrypt_work, 65536.0, Algorithm::Scrypt).await;
+
+        assert_eq!(filter.algorithm_for(&scrypt_work).await, Algorithm::Scrypt);
+        assert_eq!(filter.target_for(&scrypt_work).await, Some(65536.0));
+        assert_eq!(filter.algorithm_for(&Uuid::new_v4()).await, Algorithm::Sha256d);
+    }
+}
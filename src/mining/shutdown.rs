@@ -0,0 +1,166 @@
+//! 有序停机
+//!
+//! 原来 `stop_tasks` 按任意顺序 `abort` 所有后台任务，正在收集的结果会丢失，Stratum 连接可能停在半条消息上。
+//! 现在 `MiningManager::stop` 按固定顺序停机，每一步都在总超时内等待任务自己退出：
+//!
+//! 1. dispatch — 取消分发令牌，主循环和工作分发器不再取新工作
+//! 2. results  — 取消结果令牌，结果收集再收集一轮并提交其中的份额后退出
+//! 3. shares   — 等待已发出的份额提交收到矿池响应
+//! 4. pools    — 停止其余后台任务、监控系统，断开矿池
+//! 5. cores    — 停止设备和核心
+//!
+//! 超过总超时后剩余的任务直接 `abort`，后面的步骤仍然执行但不再等待。
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// `general.shutdown_timeout_secs` 为 0 时的总超时
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 后台任务的停机令牌，每次启动时重新创建
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownTokens {
+    /// 主循环和工作分发器
+    pub dispatch: CancellationToken,
+    /// 结果收集和结果处理
+    pub results: CancellationToken,
+}
+
+impl ShutdownTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// 单个停机步骤
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownStep {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    /// 是否在超时前完成；为 false 时该步骤的任务被中止
+    pub completed: bool,
+}
+
+/// 停机过程
+#[derive(Debug, Clone)]
+pub struct ShutdownSequence {
+    started: Instant,
+    deadline: Instant,
+    pub steps: Vec<ShutdownStep>,
+}
+
+impl ShutdownSequence {
+    pub fn new(timeout: Duration) -> Self {
+        let started = Instant::now();
+        Self {
+            started,
+            deadline: started + timeout,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// 距离总超时的剩余时间
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    pub fn record(&mut self, name: &'static str, elapsed: Duration, completed: bool) {
+        self.steps.push(ShutdownStep { name, elapsed, completed });
+    }
+
+    /// 在剩余时间内执行一个步骤；超时时放弃等待并记录为未完成。
+    /// 步骤的 future 至少会被 poll 一次，即使总超时已过
+    pub async fn run<T>(&mut self, name: &'static str, step: impl std::future::Future<Output = T>) -> Option<T> {
+        let started = Instant::now();
+        let output = tokio::time::timeout(self.remaining(), step).await.ok();
+        self.record(name, started.elapsed(), output.is_some());
+        output
+    }
+
+    /// 所有步骤都在超时前完成
+    pub fn completed(&self) -> bool {
+        self.steps.iter().all(|step| step.completed)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl std::fmt::Display for ShutdownSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ms", self.elapsed().as_millis())?;
+        if !self.steps.is_empty() {
+            let steps: Vec<String> = self.steps.iter()
+                .map(|step| {
+                    let suffix = if step.completed { "" } else { " timed out" };
+                    format!("{} {}ms{}", step.name, step.elapsed.as_millis(), suffix)
+                })
+                .collect();
+            write!(f, " ({})", steps.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// 等待任务在 `deadline` 前自行退出，否则中止；返回任务是否自行退出
+pub async fn join_until(handle: Option<JoinHandle<()>>, deadline: Instant) -> bool {
+    let Some(mut handle) = handle else {
+        return true;
+    };
+    match tokio::time::timeout_at(deadline.into(), &mut handle).await {
+        Ok(_) => true,
+        Err(_) => {
+            handle.abort();
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_join_until_waits_for_cancelled_task() {
+        let token = CancellationToken::new();
+        let task_token = token.clone();
+        let handle = tokio::spawn(async move {
+            task_token.cancelled().await;
+            // 收到取消后还有收尾工作
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        });
+        token.cancel();
+        assert!(join_until(Some(handle), Instant::now() + Duration::from_secs(1)).await);
+        assert!(join_until(None, Instant::now()).await);
+    }
+
+    #[tokio::test]
+    async fn test_join_until_aborts_after_deadline() {
+        let handle = tokio::spawn(std::future::pending::<()>());
+        let started = Instant::now();
+        assert!(!join_until(Some(handle), Instant::now() + Duration::from_millis(30)).await);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_is_bounded() {
+        let mut sequence = ShutdownSequence::new(Duration::from_millis(50));
+        assert_eq!(sequence.run("dispatch", async { 1 }).await, Some(1));
+        assert_eq!(sequence.run("shares", std::future::pending::<()>()).await, None);
+        // 总超时已过，已就绪的步骤仍然执行
+        assert_eq!(sequence.run("pools", async { 2 }).await, Some(2));
+        assert!(!sequence.completed());
+        assert!(sequence.elapsed() < Duration::from_secs(1));
+
+        let summary = sequence.to_string();
+        assert!(summary.contains("shares") && summary.contains("timed out"));
+        assert!(!summary.contains("dispatch 0ms timed out"));
+    }
+}
@@ -0,0 +1,89 @@
+//! 启动阶段计时
+//!
+//! 核心创建 → 设备扫描、矿池连接、监控系统、算力计量器之间没有依赖，`MiningManager::start`
+//! 并发启动它们 (`tokio::try_join!`)，启动总耗时取决于最慢的一路而不是各阶段之和。
+//! 每个阶段的耗时记录在这里，在启动总结中输出，也可以通过 `MiningManager::startup_timings` 查询。
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// 单个启动阶段的耗时
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupPhase {
+    pub name: &'static str,
+    pub elapsed: Duration,
+}
+
+/// 启动各阶段耗时
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupTimings {
+    pub phases: Vec<StartupPhase>,
+    /// 从开始启动到进入运行状态的总耗时
+    pub total: Duration,
+}
+
+impl StartupTimings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+        self.phases.push(StartupPhase { name, elapsed });
+    }
+
+    pub fn phase(&self, name: &str) -> Option<Duration> {
+        self.phases.iter().find(|phase| phase.name == name).map(|phase| phase.elapsed)
+    }
+
+    /// 各阶段耗时之和；并发启动时大于 `total`
+    pub fn sequential_total(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.elapsed).sum()
+    }
+}
+
+impl std::fmt::Display for StartupTimings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ms", self.total.as_millis())?;
+        if !self.phases.is_empty() {
+            let phases: Vec<String> = self.phases.iter()
+                .map(|phase| format!("{} {}ms", phase.name, phase.elapsed.as_millis()))
+                .collect();
+            write!(f, " ({})", phases.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// 执行一个启动阶段并返回结果和耗时
+pub async fn timed<T>(future: impl std::future::Future<Output = T>) -> (T, Duration) {
+    let started = Instant::now();
+    let output = future.await;
+    (output, started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_lists_phases() {
+        let mut timings = StartupTimings::new();
+        timings.record("cores", Duration::from_millis(120));
+        timings.record("pools", Duration::from_millis(300));
+        timings.total = Duration::from_millis(310);
+        assert_eq!(timings.to_string(), "310ms (cores 120ms, pools 300ms)");
+        assert_eq!(timings.phase("pools"), Some(Duration::from_millis(300)));
+        assert_eq!(timings.sequential_total(), Duration::from_millis(420));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_phases_overlap() {
+        let started = Instant::now();
+        let ((_, a), (_, b)) = tokio::join!(
+            timed(tokio::time::sleep(Duration::from_millis(50))),
+            timed(tokio::time::sleep(Duration::from_millis(50))),
+        );
+        assert!(a >= Duration::from_millis(50) && b >= Duration::from_millis(50));
+        assert!(started.elapsed() < a + b);
+    }
+}
@@ -0,0 +1,216 @@
+//! 无锁挖矿统计计数器
+//!
+//! 结果收集、推送事件、主循环、算力计量器和 API 都会更新或读取挖矿统计。以前它们共用一个
+//! `RwLock<MiningStats>`，每个份额都要取写锁，API 查询又要取写锁刷新运行时间。这里把热计数器
+//! 换成原子量：记录份额只是一次 `fetch_add`，读取方通过 `snapshot()` 得到 `MiningStats` 快照。
+
+use crate::mining::MiningStats;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 以位模式存放在 `AtomicU64` 中的 f64
+#[derive(Debug, Default)]
+struct AtomicF64(AtomicU64);
+
+impl AtomicF64 {
+    fn load(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn store(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// 用 `f` 更新，返回新值
+    fn update(&self, f: impl Fn(f64) -> f64) -> f64 {
+        let mut current = self.0.load(Ordering::Relaxed);
+        loop {
+            let new = f(f64::from_bits(current));
+            match self.0.compare_exchange_weak(current, new.to_bits(), Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return new,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+/// 挖矿统计的共享计数器
+#[derive(Debug, Default)]
+pub struct StatsCounters {
+    /// 启动时间 (Unix 毫秒)，0 表示未启动
+    start_ms: AtomicU64,
+    total_hashes: AtomicU64,
+    accepted_shares: AtomicU64,
+    rejected_shares: AtomicU64,
+    hardware_errors: AtomicU64,
+    stale_shares: AtomicU64,
+    below_target_results: AtomicU64,
+    best_share: AtomicF64,
+    current_difficulty: AtomicF64,
+    network_difficulty: AtomicF64,
+    blocks_found: AtomicU32,
+    /// 最近份额时间 (Unix 毫秒)，0 表示还没有份额
+    last_share_ms: AtomicU64,
+    average_hashrate: AtomicF64,
+    current_hashrate: AtomicF64,
+    efficiency: AtomicF64,
+    power_consumption: AtomicF64,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn time_from_ms(ms: u64) -> Option<SystemTime> {
+    (ms != 0).then(|| UNIX_EPOCH + Duration::from_millis(ms))
+}
+
+impl StatsCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&self) {
+        self.start_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    pub fn record_accepted_share(&self, difficulty: f64) {
+        self.accepted_shares.fetch_add(1, Ordering::Relaxed);
+        self.last_share_ms.store(now_ms(), Ordering::Relaxed);
+        self.best_share.update(|best| best.max(difficulty));
+    }
+
+    pub fn record_rejected_share(&self) {
+        self.rejected_shares.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_hardware_error(&self) {
+        self.hardware_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_stale_share(&self) {
+        self.stale_shares.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_below_target(&self) {
+        self.below_target_results.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_hashes(&self, hashes: u64) {
+        self.total_hashes.fetch_add(hashes, Ordering::Relaxed);
+    }
+
+    /// 更新当前算力，平均算力按指数移动平均计算
+    pub fn update_hashrate(&self, hashrate: f64) {
+        self.current_hashrate.store(hashrate);
+        self.average_hashrate.update(|average| if average == 0.0 { hashrate } else { average * 0.9 + hashrate * 0.1 });
+    }
+
+    /// 直接设置核心报告的当前和平均算力
+    pub fn set_hashrates(&self, current: f64, average: f64) {
+        self.current_hashrate.store(current);
+        self.average_hashrate.store(average);
+    }
+
+    pub fn update_power_consumption(&self, power: f64) {
+        self.power_consumption.store(power);
+        // 计算效率 (MH/J)
+        if power > 0.0 {
+            self.efficiency.store(self.current_hashrate.load() / power * 1000.0);
+        }
+    }
+
+    pub fn set_difficulty(&self, current: f64, network: f64) {
+        self.current_difficulty.store(current);
+        self.network_difficulty.store(network);
+    }
+
+    pub fn record_block_found(&self) {
+        self.blocks_found.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 当前统计的快照，运行时间计算到此刻
+    pub fn snapshot(&self) -> MiningStats {
+        let start_time = time_from_ms(self.start_ms.load(Ordering::Relaxed));
+        let uptime = start_time
+            .and_then(|start| SystemTime::now().duration_since(start).ok())
+            .unwrap_or(Duration::from_secs(0));
+        MiningStats {
+            start_time,
+            uptime,
+            total_hashes: self.total_hashes.load(Ordering::Relaxed),
+            accepted_shares: self.accepted_shares.load(Ordering::Relaxed),
+            rejected_shares: self.rejected_shares.load(Ordering::Relaxed),
+            hardware_errors: self.hardware_errors.load(Ordering::Relaxed),
+            stale_shares: self.stale_shares.load(Ordering::Relaxed),
+            below_target_results: self.below_target_results.load(Ordering::Relaxed),
+            best_share: self.best_share.load(),
+            current_difficulty: self.current_difficulty.load(),
+            network_difficulty: self.network_difficulty.load(),
+            blocks_found: self.blocks_found.load(Ordering::Relaxed),
+            last_share_time: time_from_ms(self.last_share_ms.load(Ordering::Relaxed)),
+            average_hashrate: self.average_hashrate.load(),
+            current_hashrate: self.current_hashrate.load(),
+            efficiency: self.efficiency.load(),
+            power_consumption: self.power_consumption.load(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_snapshot_counts() {
+        let counters = StatsCounters::new();
+        assert!(counters.snapshot().start_time.is_none());
+        counters.start();
+        counters.record_accepted_share(8.0);
+        counters.record_accepted_share(2.0);
+        counters.record_rejected_share();
+        counters.record_hardware_error();
+        counters.record_below_target();
+
+        let stats = counters.snapshot();
+        assert!(stats.start_time.is_some());
+        assert!(stats.last_share_time.is_some());
+        assert_eq!((stats.accepted_shares, stats.rejected_shares, stats.hardware_errors), (2, 1, 1));
+        assert_eq!(stats.below_target_results, 1);
+        assert_eq!(stats.best_share, 8.0);
+    }
+
+    #[test]
+    fn test_hashrate_average_and_efficiency() {
+        let counters = StatsCounters::new();
+        counters.update_hashrate(100.0);
+        counters.update_hashrate(200.0);
+        counters.update_power_consumption(50.0);
+        let stats = counters.snapshot();
+        assert_eq!(stats.current_hashrate, 200.0);
+        assert!((stats.average_hashrate - 110.0).abs() < 1e-9);
+        assert!((stats.efficiency - 4000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let counters = Arc::new(StatsCounters::new());
+        let threads: Vec<_> = (0..8)
+            .map(|t| {
+                let counters = counters.clone();
+                std::thread::spawn(move || {
+                    for i in 0..1000 {
+                        counters.record_accepted_share((t * 1000 + i) as f64);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let stats = counters.snapshot();
+        assert_eq!(stats.accepted_shares, 8000);
+        assert_eq!(stats.best_share, 7999.0);
+    }
+}
@@ -0,0 +1,222 @@
+//! 农场代理统计文件 (`[stats_file]`)
+//!
+//! Hive OS、minerstat 等农场代理通过自定义矿机脚本读取矿机统计。启用后每隔 `interval_secs`
+//! 把总算力、各设备的算力/温度/风扇、份额和运行时长按 `format` 写入 `path`，代理的脚本只需读取
+//! 这个文件，不必再包装一层脚本调用 API。文件先写临时文件再改名，代理不会读到写了一半的内容。
+//!
+//! - `hiveos`：`{"khs": 总算力, "stats": {...}}`，`stats` 即 Hive OS `h-stats.sh` 中的 `stats`
+//!   变量 (`hs` 以 kH/s 为单位，`ar` 为 接受/拒绝/无效 份额)
+//! - `minerstat`：算力以 H/s 为单位的通用布局，包含矿池地址和各设备明细
+
+use crate::device::DeviceInfo;
+use crate::mining::{Algorithm, MiningStats};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// 统计文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsFileFormat {
+    /// Hive OS 自定义矿机 (`h-stats.sh`)
+    #[default]
+    Hiveos,
+    /// minerstat 自定义矿机
+    Minerstat,
+}
+
+impl StatsFileFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsFileFormat::Hiveos => "hiveos",
+            StatsFileFormat::Minerstat => "minerstat",
+        }
+    }
+}
+
+/// 统计文件配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsFileConfig {
+    /// 是否写入统计文件
+    pub enabled: bool,
+    /// 文件路径
+    pub path: String,
+    pub format: StatsFileFormat,
+    /// 刷新间隔 (秒)
+    pub interval_secs: u64,
+}
+
+impl Default for StatsFileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "./data/miner-stats.json".to_string(),
+            format: StatsFileFormat::Hiveos,
+            interval_secs: 10,
+        }
+    }
+}
+
+impl StatsFileConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.path.trim().is_empty() {
+            errors.push(("stats_file.path", "Stats file path must not be empty".to_string()));
+        }
+        if self.interval_secs == 0 {
+            errors.push(("stats_file.interval_secs", "Stats file interval_secs must be greater than 0".to_string()));
+        }
+        errors
+    }
+}
+
+/// 统计文件的内容来源
+pub struct StatsSnapshot<'a> {
+    pub stats: &'a MiningStats,
+    pub devices: &'a [DeviceInfo],
+    pub algorithm: Algorithm,
+    /// 活跃矿池地址
+    pub pool_url: Option<&'a str>,
+}
+
+/// 按格式生成统计文件内容
+pub fn render(format: StatsFileFormat, snapshot: &StatsSnapshot) -> Value {
+    let stats = snapshot.stats;
+    let devices = snapshot.devices;
+    match format {
+        StatsFileFormat::Hiveos => json!({
+            "khs": stats.current_hashrate / 1000.0,
+            "stats": {
+                "hs": devices.iter().map(|device| device.hashrate / 1000.0).collect::<Vec<_>>(),
+                "hs_units": "khs",
+                "temp": devices.iter().map(|device| device.temperature.map_or(0, |celsius| celsius.round() as i64)).collect::<Vec<_>>(),
+                "fan": devices.iter().map(|device| device.fan_speed.unwrap_or(0)).collect::<Vec<_>>(),
+                "uptime": stats.uptime.as_secs(),
+                "ver": env!("CARGO_PKG_VERSION"),
+                "ar": [stats.accepted_shares, stats.rejected_shares, stats.hardware_errors],
+                "algo": hive_algorithm(snapshot.algorithm),
+            },
+        }),
+        StatsFileFormat::Minerstat => json!({
+            "miner": "cgminer-rs",
+            "version": env!("CARGO_PKG_VERSION"),
+            "uptime": stats.uptime.as_secs(),
+            "algorithm": snapshot.algorithm.as_str(),
+            "pool": snapshot.pool_url,
+            "hashrate": {
+                "total": stats.current_hashrate,
+                "unit": "H/s",
+            },
+            "shares": {
+                "accepted": stats.accepted_shares,
+                "rejected": stats.rejected_shares,
+                "invalid": stats.hardware_errors,
+            },
+            "devices": devices.iter().map(|device| json!({
+                "id": device.id,
+                "name": device.name,
+                "hashrate": device.hashrate,
+                "temperature": device.temperature,
+                "fan": device.fan_speed,
+                "accepted": device.accepted_shares,
+                "rejected": device.rejected_shares,
+                "invalid": device.hardware_errors,
+            })).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+/// Hive OS 使用的算法名
+fn hive_algorithm(algorithm: Algorithm) -> &'static str {
+    match algorithm {
+        Algorithm::Sha256d => "sha256",
+        Algorithm::Scrypt => "scrypt",
+    }
+}
+
+/// 先写临时文件再改名
+pub fn write_stats_file(path: &Path, content: &Value) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    std::fs::write(&tmp, serde_json::to_vec(content)?)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn snapshot_parts() -> (MiningStats, Vec<DeviceInfo>) {
+        let stats = MiningStats {
+            uptime: Duration::from_secs(3600),
+            current_hashrate: 2.5e9,
+            accepted_shares: 120,
+            rejected_shares: 3,
+            hardware_errors: 1,
+            ..Default::default()
+        };
+        let mut first = DeviceInfo::new(0, "Chain 0".to_string(), "asic".to_string(), 0);
+        first.hashrate = 1.5e9;
+        first.temperature = Some(64.6);
+        first.fan_speed = Some(70);
+        let mut second = DeviceInfo::new(1, "Chain 1".to_string(), "asic".to_string(), 1);
+        second.hashrate = 1.0e9;
+        (stats, vec![first, second])
+    }
+
+    #[test]
+    fn test_hiveos_layout() {
+        let (stats, devices) = snapshot_parts();
+        let snapshot = StatsSnapshot { stats: &stats, devices: &devices, algorithm: Algorithm::Sha256d, pool_url: None };
+        let value = render(StatsFileFormat::Hiveos, &snapshot);
+
+        assert_eq!(value["khs"], json!(2.5e6));
+        assert_eq!(value["stats"]["hs"], json!([1.5e6, 1.0e6]));
+        assert_eq!(value["stats"]["hs_units"], "khs");
+        // 没有温度/风扇读数的设备写 0，数组和 hs 一一对应
+        assert_eq!(value["stats"]["temp"], json!([65, 0]));
+        assert_eq!(value["stats"]["fan"], json!([70, 0]));
+        assert_eq!(value["stats"]["ar"], json!([120, 3, 1]));
+        assert_eq!(value["stats"]["uptime"], 3600);
+        assert_eq!(value["stats"]["algo"], "sha256");
+    }
+
+    #[test]
+    fn test_minerstat_layout() {
+        let (stats, devices) = snapshot_parts();
+        let snapshot = StatsSnapshot {
+            stats: &stats,
+            devices: &devices,
+            algorithm: Algorithm::Sha256d,
+            pool_url: Some("stratum+tcp://pool.example.com:3333"),
+        };
+        let value = render(StatsFileFormat::Minerstat, &snapshot);
+
+        assert_eq!(value["hashrate"]["total"], json!(2.5e9));
+        assert_eq!(value["pool"], "stratum+tcp://pool.example.com:3333");
+        assert_eq!(value["shares"]["invalid"], 1);
+        assert_eq!(value["devices"][1]["temperature"], Value::Null);
+        assert_eq!(value["devices"][0]["fan"], 70);
+    }
+
+    #[test]
+    fn test_write_replaces_file() {
+        let dir = std::env::temp_dir().join(format!("cgminer-stats-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("hive").join("stats.json");
+        write_stats_file(&path, &json!({"khs": 1.0})).unwrap();
+        write_stats_file(&path, &json!({"khs": 2.0})).unwrap();
+
+        let value: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(value["khs"], json!(2.0));
+        assert!(!dir.join("hive").join("stats.json.tmp").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
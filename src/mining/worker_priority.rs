@@ -0,0 +1,144 @@
+//! CPU 核心工作线程的调度优先级 (`[cores.cpu_btc.worker_priority]`)
+//!
+//! 共享主机上挖矿线程应当让出 CPU 给延迟敏感的任务。Linux/macOS 上按 nice 值和调度策略
+//! (`SCHED_BATCH`/`SCHED_IDLE`) 设置，Windows 上按线程优先级类别设置。设置只作用于核心的
+//! 工作线程，矿池连接、API 等应用线程保持默认优先级。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Linux 调度策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedPolicy {
+    /// SCHED_OTHER
+    #[default]
+    Normal,
+    /// SCHED_BATCH：不抢占交互任务
+    Batch,
+    /// SCHED_IDLE：只在 CPU 空闲时运行
+    Idle,
+}
+
+impl SchedPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchedPolicy::Normal => "normal",
+            SchedPolicy::Batch => "batch",
+            SchedPolicy::Idle => "idle",
+        }
+    }
+}
+
+/// Windows 优先级类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowsPriorityClass {
+    Idle,
+    BelowNormal,
+    #[default]
+    Normal,
+}
+
+impl WindowsPriorityClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WindowsPriorityClass::Idle => "idle",
+            WindowsPriorityClass::BelowNormal => "below_normal",
+            WindowsPriorityClass::Normal => "normal",
+        }
+    }
+}
+
+/// 工作线程优先级配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkerPriorityConfig {
+    /// nice 值 (-20 到 19)，越大越让出 CPU；负值需要 root 或 CAP_SYS_NICE
+    pub nice: i32,
+    /// Linux 调度策略
+    pub sched_policy: SchedPolicy,
+    /// Windows 优先级类别
+    pub windows_priority_class: WindowsPriorityClass,
+}
+
+impl WorkerPriorityConfig {
+    /// 验证优先级配置
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(-20..=19).contains(&self.nice) {
+            anyhow::bail!("worker_priority nice must be between -20 and 19, got {}", self.nice);
+        }
+        if self.sched_policy != SchedPolicy::Normal && !cfg!(target_os = "linux") {
+            anyhow::bail!("worker_priority sched_policy {} is only supported on Linux", self.sched_policy.as_str());
+        }
+        Ok(())
+    }
+
+    /// 是否与操作系统默认值相同
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 本平台上传给核心的参数：Windows 为 `worker_priority_class`，其他为 `worker_nice` 和 `worker_sched_policy`
+    pub fn core_params(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        if cfg!(windows) {
+            params.insert("worker_priority_class".to_string(), serde_json::Value::from(self.windows_priority_class.as_str()));
+        } else {
+            params.insert("worker_nice".to_string(), serde_json::Value::from(self.nice));
+            if cfg!(target_os = "linux") {
+                params.insert("worker_sched_policy".to_string(), serde_json::Value::from(self.sched_policy.as_str()));
+            }
+        }
+        params
+    }
+}
+
+impl std::fmt::Display for WorkerPriorityConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if cfg!(windows) {
+            write!(f, "priority class {}", self.windows_priority_class.as_str())
+        } else {
+            write!(f, "nice {}, policy {}", self.nice, self.sched_policy.as_str())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_nice_range() {
+        assert!(WorkerPriorityConfig::default().validate().is_ok());
+        assert!(WorkerPriorityConfig { nice: 19, ..Default::default() }.validate().is_ok());
+        assert!(WorkerPriorityConfig { nice: 20, ..Default::default() }.validate().is_err());
+        assert!(WorkerPriorityConfig { nice: -21, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_core_params() {
+        let config = WorkerPriorityConfig {
+            nice: 10,
+            sched_policy: SchedPolicy::Batch,
+            windows_priority_class: WindowsPriorityClass::BelowNormal,
+        };
+        assert!(!config.is_default());
+        let params = config.core_params();
+        if cfg!(windows) {
+            assert_eq!(params["worker_priority_class"], "below_normal");
+        } else {
+            assert_eq!(params["worker_nice"], 10);
+        }
+        if cfg!(target_os = "linux") {
+            assert_eq!(params["worker_sched_policy"], "batch");
+        }
+    }
+
+    #[test]
+    fn test_serde_names() {
+        let config: WorkerPriorityConfig = toml::from_str("nice = 5\nsched_policy = \"idle\"\nwindows_priority_class = \"below_normal\"").unwrap();
+        assert_eq!(config.sched_policy, SchedPolicy::Idle);
+        assert_eq!(config.windows_priority_class, WindowsPriorityClass::BelowNormal);
+    }
+}
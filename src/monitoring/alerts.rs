@@ -1,6 +1,7 @@
 use crate::config::AlertThresholds;
 use crate::error::MiningError;
-use crate::monitoring::{SystemMetrics, DeviceMetrics};
+use crate::monitoring::{SystemMetrics, DeviceMetrics, PoolMetrics};
+use crate::utils::RingBuffer;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
@@ -20,8 +21,8 @@ pub enum AlertType {
     Mining,
 }
 
-/// 告警严重程度
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// 告警严重程度，按从轻到重排序
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum AlertSeverity {
     /// 信息
     Info,
@@ -199,14 +200,12 @@ impl AlertCondition {
 pub struct AlertManager {
     /// 活跃告警
     active_alerts: HashMap<String, Alert>,
-    /// 告警历史
-    alert_history: Vec<Alert>,
+    /// 告警历史，最多保留 1000 条
+    alert_history: RingBuffer<Alert>,
     /// 告警规则
     alert_rules: Vec<AlertRule>,
     /// 告警阈值配置
     thresholds: AlertThresholds,
-    /// 最大历史记录数
-    max_history: usize,
 }
 
 impl AlertManager {
@@ -214,10 +213,9 @@ impl AlertManager {
     pub fn new(thresholds: AlertThresholds) -> Self {
         let mut manager = Self {
             active_alerts: HashMap::new(),
-            alert_history: Vec::new(),
+            alert_history: RingBuffer::new(1000),
             alert_rules: Vec::new(),
             thresholds,
-            max_history: 1000,
         };
 
         // 初始化默认告警规则
@@ -365,6 +363,23 @@ impl AlertManager {
             alerts.push(alert);
         }
 
+        // 检查设备过热
+        if self.thresholds.temperature_critical > 0.0 && metrics.temperature >= self.thresholds.temperature_critical {
+            let alert = Alert::new(
+                AlertType::Device,
+                AlertSeverity::Critical,
+                "Device Overheated".to_string(),
+                format!("Device {} temperature is {:.1}°C, at or above the critical threshold of {}°C",
+                       metrics.device_id, metrics.temperature, self.thresholds.temperature_critical),
+                format!("device_{}", metrics.device_id),
+            )
+            .with_label("device_id".to_string(), metrics.device_id.to_string())
+            .with_label("metric".to_string(), "temperature".to_string())
+            .with_values(metrics.temperature as f64, self.thresholds.temperature_critical as f64);
+
+            alerts.push(alert);
+        }
+
         // 检查设备错误率
         if metrics.error_rate > self.thresholds.max_error_rate as f64 {
             let alert = Alert::new(
@@ -407,6 +422,31 @@ impl AlertManager {
         Ok(alerts)
     }
 
+    /// 检查矿池告警：所有矿池都断开时触发严重告警
+    pub async fn check_pool_alerts(&mut self, pools: &[&PoolMetrics]) -> Result<Vec<Alert>, MiningError> {
+        let mut alerts = Vec::new();
+
+        if !pools.is_empty() && pools.iter().all(|pool| !pool.connected) {
+            let alert = Alert::new(
+                AlertType::Pool,
+                AlertSeverity::Critical,
+                "All Pools Down".to_string(),
+                format!("None of the {} configured pools is connected", pools.len()),
+                "pools".to_string(),
+            )
+            .with_label("metric".to_string(), "connected_pools".to_string())
+            .with_values(0.0, 1.0);
+
+            alerts.push(alert);
+        }
+
+        for alert in &alerts {
+            self.process_alert(alert.clone()).await?;
+        }
+
+        Ok(alerts)
+    }
+
     /// 处理告警
     async fn process_alert(&mut self, alert: Alert) -> Result<(), MiningError> {
         let alert_key = format!("{}_{}", alert.source, alert.title.replace(" ", "_").to_lowercase());
@@ -429,6 +469,11 @@ impl AlertManager {
         Ok(())
     }
 
+    /// 添加其他模块触发的告警 (例如配置文件被篡改)
+    pub async fn add_alert(&mut self, alert: Alert) -> Result<(), MiningError> {
+        self.process_alert(alert).await
+    }
+
     /// 解决告警
     pub async fn resolve_alert(&mut self, alert_id: &str) -> Result<(), MiningError> {
         if let Some(mut alert) = self.active_alerts.remove(alert_id) {
@@ -456,7 +501,7 @@ impl AlertManager {
     }
 
     /// 获取告警历史
-    pub fn get_alert_history(&self) -> &Vec<Alert> {
+    pub fn get_alert_history(&self) -> &RingBuffer<Alert> {
         &self.alert_history
     }
 
@@ -477,14 +522,14 @@ impl AlertManager {
         }
     }
 
+    /// 修改告警历史保留的条数，超出的旧记录立即丢弃
+    pub fn set_max_history(&mut self, max_history: usize) {
+        self.alert_history.set_capacity(max_history);
+    }
+
     /// 添加到历史记录
     fn add_to_history(&mut self, alert: Alert) {
         self.alert_history.push(alert);
-
-        // 限制历史记录大小
-        if self.alert_history.len() > self.max_history {
-            self.alert_history.remove(0);
-        }
     }
 
     /// 清理已解决的告警
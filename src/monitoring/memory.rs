@@ -0,0 +1,176 @@
+//! 内存预算
+//!
+//! 256MB 的控制板上，长时间运行的指标历史、告警历史会慢慢吃掉可用内存。启用后定期读取进程的
+//! 常驻内存 (RSS)：超过预算的 `warn_percent` 时发出警告告警；超过 `shrink_percent` 时发出严重告警，
+//! 并把内存中的指标历史和告警历史缩减到 `reduced_history_entries` 条。RSS 回到警告线以下后恢复
+//! 原来的历史长度。
+
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use sysinfo::{Pid, System};
+
+/// 内存中指标历史和告警历史的默认长度
+pub const DEFAULT_HISTORY_ENTRIES: usize = 1000;
+
+/// 内存预算配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryBudgetConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 进程常驻内存预算 (MB)
+    pub budget_mb: u64,
+    /// 检查间隔 (秒)
+    pub check_interval_secs: u64,
+    /// 超过预算的百分比时发出警告
+    pub warn_percent: f64,
+    /// 超过预算的百分比时缩减内存中的历史
+    pub shrink_percent: f64,
+    /// 缩减后保留的历史条数
+    pub reduced_history_entries: usize,
+}
+
+impl Default for MemoryBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget_mb: 200,
+            check_interval_secs: 30,
+            warn_percent: 80.0,
+            shrink_percent: 90.0,
+            reduced_history_entries: 120,
+        }
+    }
+}
+
+impl MemoryBudgetConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.budget_mb == 0 {
+            errors.push(("monitoring.memory.budget_mb", "Memory budget_mb must be greater than 0".to_string()));
+        }
+        if self.check_interval_secs == 0 {
+            errors.push(("monitoring.memory.check_interval_secs", "Memory check_interval_secs must be greater than 0".to_string()));
+        }
+        if !(self.warn_percent > 0.0 && self.warn_percent <= 100.0) {
+            errors.push(("monitoring.memory.warn_percent", "Memory warn_percent must be between 0 and 100".to_string()));
+        }
+        if !(self.shrink_percent >= self.warn_percent && self.shrink_percent <= 100.0) {
+            errors.push(("monitoring.memory.shrink_percent", "Memory shrink_percent must be between warn_percent and 100".to_string()));
+        }
+        if self.reduced_history_entries == 0 || self.reduced_history_entries > DEFAULT_HISTORY_ENTRIES {
+            errors.push((
+                "monitoring.memory.reduced_history_entries",
+                format!("Memory reduced_history_entries must be between 1 and {}", DEFAULT_HISTORY_ENTRIES),
+            ));
+        }
+        errors
+    }
+
+    pub fn budget_bytes(&self) -> u64 {
+        self.budget_mb * 1024 * 1024
+    }
+
+    /// 常驻内存对应的压力级别
+    pub fn pressure(&self, rss_bytes: u64) -> MemoryPressure {
+        let percent = rss_bytes as f64 / self.budget_bytes().max(1) as f64 * 100.0;
+        if percent >= self.shrink_percent {
+            MemoryPressure::Critical
+        } else if percent >= self.warn_percent {
+            MemoryPressure::Warning
+        } else {
+            MemoryPressure::Normal
+        }
+    }
+}
+
+/// 内存压力级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryPressure {
+    #[default]
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// 内存预算状态，供 API 查询
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemoryStatus {
+    pub enabled: bool,
+    /// 最近一次读取的常驻内存 (字节)，未读取时为 0
+    pub rss_bytes: u64,
+    pub budget_bytes: u64,
+    pub pressure: MemoryPressure,
+    /// 当前内存中指标历史的长度
+    pub history_entries: usize,
+    pub checked_at: Option<SystemTime>,
+}
+
+/// 读取本进程的常驻内存
+pub struct RssSampler {
+    system: System,
+    pid: Option<Pid>,
+}
+
+impl RssSampler {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            pid: sysinfo::get_current_pid().ok(),
+        }
+    }
+
+    /// 常驻内存 (字节)，平台不支持时为 None
+    pub fn sample(&mut self) -> Option<u64> {
+        let pid = self.pid?;
+        if !self.system.refresh_process(pid) {
+            return None;
+        }
+        self.system.process(pid).map(|process| process.memory())
+    }
+}
+
+impl Default for RssSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressure_levels() {
+        let config = MemoryBudgetConfig { enabled: true, budget_mb: 100, ..Default::default() };
+        let mb = 1024 * 1024;
+        assert_eq!(config.pressure(50 * mb), MemoryPressure::Normal);
+        assert_eq!(config.pressure(80 * mb), MemoryPressure::Warning);
+        assert_eq!(config.pressure(95 * mb), MemoryPressure::Critical);
+        assert_eq!(config.pressure(200 * mb), MemoryPressure::Critical);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(MemoryBudgetConfig::default().validate().is_empty());
+        let config = MemoryBudgetConfig { enabled: true, ..Default::default() };
+        assert!(config.validate().is_empty());
+
+        let config = MemoryBudgetConfig { enabled: true, budget_mb: 0, warn_percent: 95.0, ..Default::default() };
+        let keys: Vec<_> = config.validate().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["monitoring.memory.budget_mb", "monitoring.memory.shrink_percent"]);
+    }
+
+    #[test]
+    fn test_sampler_reads_own_rss() {
+        let mut sampler = RssSampler::new();
+        if cfg!(any(target_os = "linux", target_os = "macos", target_os = "windows")) {
+            assert!(sampler.sample().unwrap_or(0) > 0);
+        }
+    }
+}
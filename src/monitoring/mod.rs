@@ -2,13 +2,22 @@ pub mod system;
 pub mod metrics;
 pub mod alerts;
 pub mod simple_web;
+pub mod push;
+pub mod store;
+pub mod memory;
+pub mod recovery;
 
+use crate::utils::RingBuffer;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
 
 pub use system::MonitoringSystem;
 pub use alerts::Alert;
+pub use push::{PushConfig, PushNotifier};
+pub use store::{HistoryConfig, MetricsStore};
+pub use memory::{MemoryBudgetConfig, MemoryPressure, MemoryStatus};
+pub use recovery::RecoveryConfig;
 
 /// 系统指标
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +159,20 @@ impl PoolMetrics {
     }
 }
 
+/// 最近提交的份额
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentShare {
+    pub timestamp: SystemTime,
+    pub device_id: u32,
+    pub difficulty: f64,
+    pub accepted: bool,
+    /// 拒绝原因或提交错误
+    pub reason: Option<String>,
+}
+
+/// 保留的最近份额数量
+pub const RECENT_SHARES_LIMIT: usize = 50;
+
 /// 监控事件
 #[derive(Debug, Clone)]
 pub enum MonitoringEvent {
@@ -185,6 +208,11 @@ pub enum MonitoringEvent {
         alert_id: String,
         timestamp: SystemTime,
     },
+    /// 份额提交结果
+    ShareRecorded {
+        share: RecentShare,
+        timestamp: SystemTime,
+    },
 }
 
 impl MonitoringEvent {
@@ -196,6 +224,7 @@ impl MonitoringEvent {
             MonitoringEvent::PoolMetricsUpdate { timestamp, .. } => *timestamp,
             MonitoringEvent::AlertTriggered { timestamp, .. } => *timestamp,
             MonitoringEvent::AlertResolved { timestamp, .. } => *timestamp,
+            MonitoringEvent::ShareRecorded { timestamp, .. } => *timestamp,
         }
     }
 
@@ -207,6 +236,7 @@ impl MonitoringEvent {
             MonitoringEvent::PoolMetricsUpdate { .. } => "pool_metrics_update",
             MonitoringEvent::AlertTriggered { .. } => "alert_triggered",
             MonitoringEvent::AlertResolved { .. } => "alert_resolved",
+            MonitoringEvent::ShareRecorded { .. } => "share_recorded",
         }
     }
 }
@@ -229,54 +259,61 @@ pub enum MonitoringState {
 /// 指标历史记录
 #[derive(Debug, Clone)]
 pub struct MetricsHistory {
-    pub system_metrics: Vec<SystemMetrics>,
-    pub mining_metrics: Vec<MiningMetrics>,
-    pub device_metrics: HashMap<u32, Vec<DeviceMetrics>>,
-    pub pool_metrics: HashMap<u32, Vec<PoolMetrics>>,
+    pub system_metrics: RingBuffer<SystemMetrics>,
+    pub mining_metrics: RingBuffer<MiningMetrics>,
+    pub device_metrics: HashMap<u32, RingBuffer<DeviceMetrics>>,
+    pub pool_metrics: HashMap<u32, RingBuffer<PoolMetrics>>,
+    pub recent_shares: RingBuffer<RecentShare>,
     pub max_entries: usize,
 }
 
 impl MetricsHistory {
     pub fn new(max_entries: usize) -> Self {
         Self {
-            system_metrics: Vec::new(),
-            mining_metrics: Vec::new(),
+            system_metrics: RingBuffer::new(max_entries),
+            mining_metrics: RingBuffer::new(max_entries),
             device_metrics: HashMap::new(),
             pool_metrics: HashMap::new(),
+            recent_shares: RingBuffer::new(RECENT_SHARES_LIMIT),
             max_entries,
         }
     }
 
     pub fn add_system_metrics(&mut self, metrics: SystemMetrics) {
         self.system_metrics.push(metrics);
-        if self.system_metrics.len() > self.max_entries {
-            self.system_metrics.remove(0);
-        }
     }
 
     pub fn add_mining_metrics(&mut self, metrics: MiningMetrics) {
         self.mining_metrics.push(metrics);
-        if self.mining_metrics.len() > self.max_entries {
-            self.mining_metrics.remove(0);
-        }
     }
 
     pub fn add_device_metrics(&mut self, device_id: u32, metrics: DeviceMetrics) {
-        let device_history = self.device_metrics.entry(device_id).or_insert_with(Vec::new);
-        device_history.push(metrics);
-        if device_history.len() > self.max_entries {
-            device_history.remove(0);
-        }
+        let max_entries = self.max_entries;
+        self.device_metrics.entry(device_id).or_insert_with(|| RingBuffer::new(max_entries)).push(metrics);
     }
 
     pub fn add_pool_metrics(&mut self, pool_id: u32, metrics: PoolMetrics) {
-        let pool_history = self.pool_metrics.entry(pool_id).or_insert_with(Vec::new);
-        pool_history.push(metrics);
-        if pool_history.len() > self.max_entries {
-            pool_history.remove(0);
+        let max_entries = self.max_entries;
+        self.pool_metrics.entry(pool_id).or_insert_with(|| RingBuffer::new(max_entries)).push(metrics);
+    }
+
+    /// 修改保留的条数，超出的旧记录立即丢弃
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.max_entries = max_entries;
+        self.system_metrics.set_capacity(max_entries);
+        self.mining_metrics.set_capacity(max_entries);
+        for history in self.device_metrics.values_mut() {
+            history.set_capacity(max_entries);
+        }
+        for history in self.pool_metrics.values_mut() {
+            history.set_capacity(max_entries);
         }
     }
 
+    pub fn add_share(&mut self, share: RecentShare) {
+        self.recent_shares.push(share);
+    }
+
     pub fn get_latest_system_metrics(&self) -> Option<&SystemMetrics> {
         self.system_metrics.last()
     }
@@ -298,6 +335,7 @@ impl MetricsHistory {
         self.mining_metrics.clear();
         self.device_metrics.clear();
         self.pool_metrics.clear();
+        self.recent_shares.clear();
     }
 }
 
@@ -0,0 +1,357 @@
+//! Web Push 告警通知 (`[monitoring.push]`)
+//!
+//! 仪表板注册 service worker 后把推送订阅交给服务端，订阅保存在 `subscriptions_file` 中。
+//! 达到 `min_severity` 的告警（设备过热、所有矿池断开等）用 VAPID 签名推送给所有订阅，
+//! 浏览器标签页关闭时也能收到。同一告警在 `repeat_interval_secs` 内只推送一次。
+
+use crate::error::MiningError;
+use crate::monitoring::alerts::{Alert, AlertSeverity};
+use crate::monitoring::MonitoringEvent;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tracing::{debug, info, warn};
+use web_push::{
+    ContentEncoding, HyperWebPushClient, PartialVapidSignatureBuilder, SubscriptionInfo,
+    VapidSignatureBuilder, WebPushClient, WebPushError, WebPushMessageBuilder,
+};
+
+/// Web Push 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// VAPID 私钥 (P-256 PEM)
+    pub vapid_key_file: String,
+    /// VAPID 联系方式，mailto: 或 https: 地址
+    pub subject: String,
+    /// 订阅保存文件
+    pub subscriptions_file: String,
+    /// 推送的最低告警级别
+    pub min_severity: AlertSeverity,
+    /// 同一告警再次推送的最短间隔 (秒)
+    pub repeat_interval_secs: u64,
+    /// 推送服务保留未送达消息的时间 (秒)
+    pub ttl_secs: u32,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            vapid_key_file: "/etc/cgminer/vapid.pem".to_string(),
+            subject: "mailto:admin@localhost".to_string(),
+            subscriptions_file: "./data/push-subscriptions.json".to_string(),
+            min_severity: AlertSeverity::Critical,
+            repeat_interval_secs: 3600,
+            ttl_secs: 3600,
+        }
+    }
+}
+
+impl PushConfig {
+    /// 检查配置，返回 (配置键, 错误说明)
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.vapid_key_file.is_empty() {
+            errors.push(("monitoring.push.vapid_key_file", "Web push is enabled but vapid_key_file is not set".to_string()));
+        }
+        if !self.subject.starts_with("mailto:") && !self.subject.starts_with("https://") {
+            errors.push(("monitoring.push.subject", format!(
+                "Web push subject '{}' must be a mailto: or https:// address", self.subject
+            )));
+        }
+        if self.subscriptions_file.is_empty() {
+            errors.push(("monitoring.push.subscriptions_file", "Web push subscriptions_file cannot be empty".to_string()));
+        }
+        errors
+    }
+}
+
+/// 推送给浏览器的内容，由 service worker 显示为通知
+#[derive(Debug, Clone, Serialize)]
+pub struct PushPayload {
+    pub title: String,
+    pub body: String,
+    /// 相同 tag 的通知在浏览器中相互替换
+    pub tag: String,
+    pub severity: AlertSeverity,
+    /// 点击通知时打开的页面
+    pub url: String,
+}
+
+impl PushPayload {
+    pub fn from_alert(alert: &Alert) -> Self {
+        Self {
+            title: format!("⚠️ {}", alert.title),
+            body: alert.description.clone(),
+            tag: alert_key(alert),
+            severity: alert.severity.clone(),
+            url: "/".to_string(),
+        }
+    }
+}
+
+/// 一次推送的结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PushReport {
+    pub sent: usize,
+    pub failed: usize,
+    /// 推送服务报告已失效、被删除的订阅数
+    pub removed: usize,
+}
+
+/// 保存的订阅
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredSubscription {
+    subscription: SubscriptionInfo,
+    /// 订阅时间 (Unix 秒)
+    created_at: u64,
+}
+
+/// 告警去重键，与告警管理器合并告警的方式一致
+fn alert_key(alert: &Alert) -> String {
+    format!("{}_{}", alert.source, alert.title.replace(' ', "_").to_lowercase())
+}
+
+/// Web Push 推送器
+pub struct PushNotifier {
+    config: PushConfig,
+    vapid: PartialVapidSignatureBuilder,
+    public_key: String,
+    client: HyperWebPushClient,
+    subscriptions: RwLock<Vec<StoredSubscription>>,
+    /// 各告警最近一次推送的时间
+    last_sent: Mutex<HashMap<String, Instant>>,
+}
+
+impl PushNotifier {
+    /// 读取 VAPID 私钥和已保存的订阅
+    pub fn new(config: PushConfig) -> Result<Self, MiningError> {
+        let key_file = std::fs::File::open(&config.vapid_key_file)
+            .map_err(|e| MiningError::System(format!("Failed to open VAPID key {}: {}", config.vapid_key_file, e)))?;
+        let vapid = VapidSignatureBuilder::from_pem_no_sub(key_file)
+            .map_err(|e| MiningError::System(format!("Invalid VAPID key {}: {}", config.vapid_key_file, e)))?;
+        let public_key = URL_SAFE_NO_PAD.encode(vapid.get_public_key());
+        let subscriptions = load_subscriptions(Path::new(&config.subscriptions_file))?;
+
+        info!("🔔 Web push enabled with {} saved subscriptions", subscriptions.len());
+        Ok(Self {
+            config,
+            vapid,
+            public_key,
+            client: HyperWebPushClient::new(),
+            subscriptions: RwLock::new(subscriptions),
+            last_sent: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 浏览器订阅时使用的 applicationServerKey (base64url)
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// 当前订阅数
+    pub async fn subscription_count(&self) -> usize {
+        self.subscriptions.read().await.len()
+    }
+
+    /// 添加订阅，同一 endpoint 重复订阅时更新密钥
+    pub async fn subscribe(&self, subscription: SubscriptionInfo) -> Result<(), MiningError> {
+        let mut subscriptions = self.subscriptions.write().await;
+        subscriptions.retain(|s| s.subscription.endpoint != subscription.endpoint);
+        subscriptions.push(StoredSubscription { subscription, created_at: unix_now() });
+        save_subscriptions(Path::new(&self.config.subscriptions_file), &subscriptions).await?;
+        info!("🔔 Web push subscription added ({} total)", subscriptions.len());
+        Ok(())
+    }
+
+    /// 删除订阅，返回是否存在
+    pub async fn unsubscribe(&self, endpoint: &str) -> Result<bool, MiningError> {
+        let mut subscriptions = self.subscriptions.write().await;
+        let before = subscriptions.len();
+        subscriptions.retain(|s| s.subscription.endpoint != endpoint);
+        if subscriptions.len() == before {
+            return Ok(false);
+        }
+        save_subscriptions(Path::new(&self.config.subscriptions_file), &subscriptions).await?;
+        info!("🔕 Web push subscription removed ({} total)", subscriptions.len());
+        Ok(true)
+    }
+
+    /// 告警达到推送级别且不在重复间隔内时推送
+    pub async fn notify_alert(&self, alert: &Alert) -> Option<PushReport> {
+        if alert.severity < self.config.min_severity {
+            return None;
+        }
+        let key = alert_key(alert);
+        {
+            let mut last_sent = self.last_sent.lock().await;
+            let repeat = Duration::from_secs(self.config.repeat_interval_secs);
+            if last_sent.get(&key).is_some_and(|at| at.elapsed() < repeat) {
+                return None;
+            }
+            last_sent.insert(key, Instant::now());
+        }
+        Some(self.send_all(&PushPayload::from_alert(alert)).await)
+    }
+
+    /// 推送给所有订阅，删除推送服务报告已失效的订阅
+    pub async fn send_all(&self, payload: &PushPayload) -> PushReport {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to encode web push payload: {}", e);
+                return PushReport::default();
+            }
+        };
+
+        let subscriptions = self.subscriptions.read().await.clone();
+        let mut report = PushReport::default();
+        let mut gone = Vec::new();
+        for stored in &subscriptions {
+            match self.send(&stored.subscription, &body).await {
+                Ok(()) => report.sent += 1,
+                Err(WebPushError::EndpointNotValid(_)) | Err(WebPushError::EndpointNotFound(_)) => {
+                    gone.push(stored.subscription.endpoint.clone());
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    warn!("Failed to send web push notification: {}", e);
+                }
+            }
+        }
+
+        for endpoint in &gone {
+            match self.unsubscribe(endpoint).await {
+                Ok(true) => report.removed += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to remove expired web push subscription: {}", e),
+            }
+        }
+        debug!("Web push '{}': {} sent, {} failed, {} removed", payload.title, report.sent, report.failed, report.removed);
+        report
+    }
+
+    async fn send(&self, subscription: &SubscriptionInfo, body: &[u8]) -> Result<(), WebPushError> {
+        let mut signature = self.vapid.clone().add_sub_info(subscription);
+        signature.add_claim("sub", self.config.subject.as_str());
+
+        let mut message = WebPushMessageBuilder::new(subscription);
+        message.set_payload(ContentEncoding::Aes128Gcm, body);
+        message.set_vapid_signature(signature.build()?);
+        message.set_ttl(self.config.ttl_secs);
+        self.client.send(message.build()?).await
+    }
+
+    /// 订阅监控事件，推送触发的告警
+    pub fn start(self: &Arc<Self>, mut events: broadcast::Receiver<MonitoringEvent>) -> tokio::task::JoinHandle<()> {
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(MonitoringEvent::AlertTriggered { alert, .. }) => {
+                        notifier.notify_alert(&alert).await;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Web push notifier skipped {} monitoring events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+fn load_subscriptions(path: &Path) -> Result<Vec<StoredSubscription>, MiningError> {
+    match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data)
+            .map_err(|e| MiningError::System(format!("Invalid web push subscriptions file {}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(MiningError::System(format!("Failed to read {}: {}", path.display(), e))),
+    }
+}
+
+/// 先写临时文件再改名，避免写到一半时丢失全部订阅
+async fn save_subscriptions(path: &Path, subscriptions: &[StoredSubscription]) -> Result<(), MiningError> {
+    let data = serde_json::to_vec_pretty(subscriptions)
+        .map_err(|e| MiningError::System(format!("Failed to encode web push subscriptions: {}", e)))?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await
+            .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    tokio::fs::write(&tmp, data).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", tmp.display(), e)))?;
+    tokio::fs::rename(&tmp, path).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::alerts::AlertType;
+
+    #[test]
+    fn test_severity_order() {
+        assert!(AlertSeverity::Info < AlertSeverity::Warning);
+        assert!(AlertSeverity::Warning < AlertSeverity::Error);
+        assert!(AlertSeverity::Error < AlertSeverity::Critical);
+    }
+
+    #[test]
+    fn test_payload_from_alert() {
+        let alert = Alert::new(
+            AlertType::Pool,
+            AlertSeverity::Critical,
+            "All Pools Down".to_string(),
+            "None of the 2 configured pools is connected".to_string(),
+            "pools".to_string(),
+        );
+        let payload = PushPayload::from_alert(&alert);
+        assert_eq!(payload.tag, "pools_all_pools_down");
+        assert_eq!(payload.body, alert.description);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_round_trip() {
+        let path = std::env::temp_dir().join(format!("cgminer-push-{}", std::process::id())).join("subs.json");
+        assert!(load_subscriptions(&path).unwrap().is_empty());
+
+        let subscription: SubscriptionInfo = serde_json::from_value(serde_json::json!({
+            "endpoint": "https://push.example.com/send/abc",
+            "expirationTime": null,
+            "keys": { "p256dh": "BNcRdreALRFXTkOOUHK1EtK2wtaz5Ry4YfYCA_0QTpQtUbVlUls0VJXg7A8u-Ts1XbjhazAkj7I99e8QcYP7DkM", "auth": "tBHItJI5svbpez7KI4CCXg" }
+        })).unwrap();
+        save_subscriptions(&path, &[StoredSubscription { subscription, created_at: 1 }]).await.unwrap();
+
+        let loaded = load_subscriptions(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].subscription.endpoint, "https://push.example.com/send/abc");
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PushConfig::default().validate().is_empty());
+        let config = PushConfig { enabled: true, subject: "admin@example.com".to_string(), ..PushConfig::default() };
+        let errors = config.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "monitoring.push.subject");
+    }
+}
@@ -0,0 +1,203 @@
+//! 零算力恢复 (`[monitoring.recovery]`)
+//!
+//! 挖矿处于运行状态但总算力持续为 0 时 (核心卡死、设备全部掉线、驱动异常)，单个核心的故障转移
+//! 无能为力。启用后每隔 `check_interval_secs` 检查一次总算力，连续 `zero_hashrate_secs` 秒为 0 时
+//! 发出严重告警并按 `actions` 依次执行恢复动作：重启所有核心、重启所有设备，最后以 `exit_code`
+//! 有序退出进程，由 systemd 等守护进程重新拉起。每执行一级动作后重新计时，算力恢复后回到第一级；
+//! 列表用完后重复最后一级。
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 恢复动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// 停止并重新启动所有核心
+    RestartCores,
+    /// 重启所有设备
+    RestartDevices,
+    /// 有序停机后以非零退出码退出进程
+    Exit,
+}
+
+impl RecoveryAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoveryAction::RestartCores => "restart_cores",
+            RecoveryAction::RestartDevices => "restart_devices",
+            RecoveryAction::Exit => "exit",
+        }
+    }
+}
+
+/// 零算力恢复配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoveryConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 总算力持续为 0 多久后执行下一级动作 (秒)
+    pub zero_hashrate_secs: u64,
+    /// 检查间隔 (秒)
+    pub check_interval_secs: u64,
+    /// 依次执行的恢复动作
+    pub actions: Vec<RecoveryAction>,
+    /// `exit` 动作的进程退出码
+    pub exit_code: i32,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            zero_hashrate_secs: 600,
+            check_interval_secs: 30,
+            actions: vec![RecoveryAction::RestartCores, RecoveryAction::RestartDevices, RecoveryAction::Exit],
+            exit_code: 3,
+        }
+    }
+}
+
+impl RecoveryConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.zero_hashrate_secs == 0 {
+            errors.push(("monitoring.recovery.zero_hashrate_secs", "Recovery zero_hashrate_secs must be greater than 0".to_string()));
+        }
+        if self.check_interval_secs == 0 || self.check_interval_secs > self.zero_hashrate_secs {
+            errors.push((
+                "monitoring.recovery.check_interval_secs",
+                "Recovery check_interval_secs must be between 1 and zero_hashrate_secs".to_string(),
+            ));
+        }
+        if self.actions.is_empty() {
+            errors.push(("monitoring.recovery.actions", "Recovery actions must not be empty".to_string()));
+        }
+        if !(1..=255).contains(&self.exit_code) {
+            errors.push(("monitoring.recovery.exit_code", "Recovery exit_code must be between 1 and 255".to_string()));
+        }
+        errors
+    }
+}
+
+/// 跟踪总算力为 0 的时长，决定下一级恢复动作
+#[derive(Debug)]
+pub struct ZeroHashrateWatchdog {
+    period: Duration,
+    actions: Vec<RecoveryAction>,
+    /// 开始为 0 (或上一级动作执行) 的时间
+    zero_since: Option<Instant>,
+    /// 本轮已执行的动作数
+    attempts: usize,
+}
+
+impl ZeroHashrateWatchdog {
+    pub fn new(config: &RecoveryConfig) -> Self {
+        Self {
+            period: Duration::from_secs(config.zero_hashrate_secs),
+            actions: config.actions.clone(),
+            zero_since: None,
+            attempts: 0,
+        }
+    }
+
+    /// 记录一次检查；`running` 为 false (暂停、停止中) 时不计时。需要恢复时返回本次的动作
+    pub fn observe(&mut self, hashrate: f64, running: bool, now: Instant) -> Option<RecoveryAction> {
+        if !running || hashrate > 0.0 {
+            self.zero_since = None;
+            self.attempts = 0;
+            return None;
+        }
+        let since = *self.zero_since.get_or_insert(now);
+        if now.saturating_duration_since(since) < self.period {
+            return None;
+        }
+        let action = *self.actions.get(self.attempts).or(self.actions.last())?;
+        self.attempts += 1;
+        self.zero_since = Some(now);
+        Some(action)
+    }
+
+    /// 算力为 0 以来已执行的动作数
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers_escalate_while_zero() {
+        let config = RecoveryConfig { enabled: true, zero_hashrate_secs: 60, ..Default::default() };
+        let mut watchdog = ZeroHashrateWatchdog::new(&config);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(watchdog.observe(0.0, true, at(0)), None);
+        assert_eq!(watchdog.observe(0.0, true, at(59)), None);
+        assert_eq!(watchdog.observe(0.0, true, at(60)), Some(RecoveryAction::RestartCores));
+        // 每一级之后重新计时
+        assert_eq!(watchdog.observe(0.0, true, at(90)), None);
+        assert_eq!(watchdog.observe(0.0, true, at(120)), Some(RecoveryAction::RestartDevices));
+        assert_eq!(watchdog.observe(0.0, true, at(180)), Some(RecoveryAction::Exit));
+        assert_eq!(watchdog.observe(0.0, true, at(240)), Some(RecoveryAction::Exit));
+    }
+
+    #[test]
+    fn test_recovery_resets_tiers() {
+        let config = RecoveryConfig {
+            enabled: true,
+            zero_hashrate_secs: 60,
+            actions: vec![RecoveryAction::RestartCores, RecoveryAction::RestartDevices],
+            ..Default::default()
+        };
+        let mut watchdog = ZeroHashrateWatchdog::new(&config);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        watchdog.observe(0.0, true, at(0));
+        assert_eq!(watchdog.observe(0.0, true, at(60)), Some(RecoveryAction::RestartCores));
+        assert_eq!(watchdog.attempts(), 1);
+
+        // 算力恢复后回到第一级
+        assert_eq!(watchdog.observe(1.0e12, true, at(70)), None);
+        assert_eq!(watchdog.attempts(), 0);
+        watchdog.observe(0.0, true, at(80));
+        assert_eq!(watchdog.observe(0.0, true, at(140)), Some(RecoveryAction::RestartCores));
+
+        // 暂停期间不计时
+        assert_eq!(watchdog.observe(0.0, false, at(150)), None);
+        assert_eq!(watchdog.observe(0.0, true, at(200)), None);
+        assert_eq!(watchdog.observe(0.0, true, at(260)), Some(RecoveryAction::RestartCores));
+        assert_eq!(watchdog.observe(0.0, true, at(320)), Some(RecoveryAction::RestartDevices));
+        assert_eq!(watchdog.observe(0.0, true, at(380)), Some(RecoveryAction::RestartDevices));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RecoveryConfig::default().validate().is_empty());
+        let config = RecoveryConfig {
+            enabled: true,
+            zero_hashrate_secs: 60,
+            check_interval_secs: 120,
+            actions: Vec::new(),
+            exit_code: 0,
+        };
+        let keys: Vec<&str> = config.validate().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![
+            "monitoring.recovery.check_interval_secs",
+            "monitoring.recovery.actions",
+            "monitoring.recovery.exit_code",
+        ]);
+
+        let config: RecoveryConfig = toml::from_str("enabled = true\nactions = [\"restart_devices\", \"exit\"]").unwrap();
+        assert_eq!(config.actions, vec![RecoveryAction::RestartDevices, RecoveryAction::Exit]);
+    }
+}
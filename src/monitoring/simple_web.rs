@@ -5,26 +5,31 @@
 
 use crate::monitoring::{SystemMetrics, MiningMetrics, DeviceMetrics, PoolMetrics};
 use crate::error::MiningError;
+use crate::utils::RingBuffer;
 use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use warp::{Filter, Reply};
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse, Json};
+use axum::routing::get;
+use axum::Router;
 use tracing::{info, error};
 
 /// 简化的指标历史记录
 #[derive(Debug, Clone)]
 pub struct SimpleMetricsHistory {
     /// 系统指标历史
-    pub system_metrics: Vec<SystemMetrics>,
+    pub system_metrics: RingBuffer<SystemMetrics>,
     /// 挖矿指标历史
-    pub mining_metrics: Vec<MiningMetrics>,
+    pub mining_metrics: RingBuffer<MiningMetrics>,
     /// 设备指标历史
-    pub device_metrics: HashMap<u32, Vec<DeviceMetrics>>,
+    pub device_metrics: HashMap<u32, RingBuffer<DeviceMetrics>>,
     /// 矿池指标历史
-    pub pool_metrics: HashMap<u32, Vec<PoolMetrics>>,
+    pub pool_metrics: HashMap<u32, RingBuffer<PoolMetrics>>,
     /// 最大记录数
     max_records: usize,
 }
@@ -32,8 +37,8 @@ pub struct SimpleMetricsHistory {
 impl SimpleMetricsHistory {
     pub fn new(max_records: usize) -> Self {
         Self {
-            system_metrics: Vec::new(),
-            mining_metrics: Vec::new(),
+            system_metrics: RingBuffer::new(max_records),
+            mining_metrics: RingBuffer::new(max_records),
             device_metrics: HashMap::new(),
             pool_metrics: HashMap::new(),
             max_records,
@@ -42,32 +47,20 @@ impl SimpleMetricsHistory {
 
     pub fn add_system_metrics(&mut self, metrics: SystemMetrics) {
         self.system_metrics.push(metrics);
-        if self.system_metrics.len() > self.max_records {
-            self.system_metrics.remove(0);
-        }
     }
 
     pub fn add_mining_metrics(&mut self, metrics: MiningMetrics) {
         self.mining_metrics.push(metrics);
-        if self.mining_metrics.len() > self.max_records {
-            self.mining_metrics.remove(0);
-        }
     }
 
     pub fn add_device_metrics(&mut self, device_id: u32, metrics: DeviceMetrics) {
-        let device_history = self.device_metrics.entry(device_id).or_insert_with(Vec::new);
-        device_history.push(metrics);
-        if device_history.len() > self.max_records {
-            device_history.remove(0);
-        }
+        let max_records = self.max_records;
+        self.device_metrics.entry(device_id).or_insert_with(|| RingBuffer::new(max_records)).push(metrics);
     }
 
     pub fn add_pool_metrics(&mut self, pool_id: u32, metrics: PoolMetrics) {
-        let pool_history = self.pool_metrics.entry(pool_id).or_insert_with(Vec::new);
-        pool_history.push(metrics);
-        if pool_history.len() > self.max_records {
-            pool_history.remove(0);
-        }
+        let max_records = self.max_records;
+        self.pool_metrics.entry(pool_id).or_insert_with(|| RingBuffer::new(max_records)).push(metrics);
     }
 
     pub fn get_latest_system_metrics(&self) -> Option<&SystemMetrics> {
@@ -183,56 +176,39 @@ impl SimpleWebMonitor {
 
         let metrics_history = self.metrics_history.clone();
 
-        // 主页路由 - 返回HTML页面
-        let index_route = warp::path::end()
-            .and(warp::get())
-            .map(|| warp::reply::html(get_dashboard_html()));
-
-        // API路由 - 返回JSON数据
-        let api_route = warp::path("api")
-            .and(warp::path("dashboard"))
-            .and(warp::get())
-            .and_then(move || {
+        let routes = Router::new()
+            // 主页路由 - 返回HTML页面
+            .route("/", get(|| async { Html(get_dashboard_html()) }))
+            // API路由 - 返回JSON数据
+            .route("/api/dashboard", get(move || {
                 let metrics_history = metrics_history.clone();
                 async move {
                     match generate_dashboard_data(metrics_history).await {
-                        Ok(data) => Ok(warp::reply::json(&data)),
+                        Ok(data) => Json(data).into_response(),
                         Err(e) => {
                             error!("生成仪表板数据失败: {}", e);
-                            Err(warp::reject::custom(ApiError))
+                            StatusCode::INTERNAL_SERVER_ERROR.into_response()
                         }
                     }
                 }
-            });
-
-        // 静态资源路由（CSS/JS）
-        let static_route = warp::path("static")
-            .and(warp::path::param())
-            .and(warp::get())
-            .map(|file: String| {
+            }))
+            // 静态资源路由（CSS/JS）
+            .route("/static/:file", get(|Path(file): Path<String>| async move {
                 match file.as_str() {
-                    "style.css" => warp::reply::with_header(
-                        get_dashboard_css(),
-                        "content-type",
-                        "text/css",
-                    ).into_response(),
-                    "script.js" => warp::reply::with_header(
-                        get_dashboard_js(),
-                        "content-type",
-                        "application/javascript",
-                    ).into_response(),
-                    _ => warp::reply::with_status(
-                        "Not Found",
-                        warp::http::StatusCode::NOT_FOUND,
-                    ).into_response(),
+                    "style.css" => ([(header::CONTENT_TYPE, "text/css")], get_dashboard_css()).into_response(),
+                    "script.js" => ([(header::CONTENT_TYPE, "application/javascript")], get_dashboard_js()).into_response(),
+                    _ => (StatusCode::NOT_FOUND, "Not Found").into_response(),
                 }
-            });
-
-        let routes = index_route.or(api_route).or(static_route);
+            }));
 
         // 启动服务器
-        let server = warp::serve(routes)
-            .run(([0, 0, 0, 0], self.port));
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", self.port)).await
+            .map_err(|e| MiningError::System(format!("Failed to bind web monitor port {}: {}", self.port, e)))?;
+        let server = async move {
+            if let Err(e) = axum::serve(listener, routes).await {
+                error!("Web监控服务器错误: {}", e);
+            }
+        };
 
         let handle = tokio::spawn(server);
         *self.server_handle.write().await = Some(handle);
@@ -415,12 +391,6 @@ async fn generate_dashboard_data(
     })
 }
 
-/// 自定义错误类型
-#[derive(Debug)]
-struct ApiError;
-
-impl warp::reject::Reject for ApiError {}
-
 /// 获取仪表板HTML
 fn get_dashboard_html() -> &'static str {
     include_str!("../../web/dashboard.html")
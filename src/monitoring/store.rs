@@ -0,0 +1,558 @@
+//! 指标历史存储 (`[monitoring.history]`)
+//!
+//! 订阅监控事件，把总体、每台设备和每个矿池的指标降采样为 1 分钟、5 分钟和 1 小时三档，
+//! 分别保留 2 小时、2 天和 8 天，定期写入 `path` 指定的文件，重启后继续累积。
+//! 历史图表页面按时间范围查询合适的一档，不读取内存中的 `MetricsHistory`。
+
+use crate::error::MiningError;
+use crate::monitoring::{DeviceMetrics, MiningMetrics, MonitoringEvent, PoolMetrics};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info, warn};
+
+/// 降采样档位：(桶宽, 保留时间)，单位秒
+const TIERS: [(u64, u64); 3] = [(60, 2 * 3600), (300, 2 * 86400), (3600, 8 * 86400)];
+
+/// 指标历史配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 历史数据文件，为空时只保存在内存中
+    pub path: String,
+    /// 写入文件的间隔 (秒)
+    pub save_interval_secs: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "./data/metrics-history.json".to_string(),
+            save_interval_secs: 300,
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if self.enabled && !self.path.is_empty() && self.save_interval_secs == 0 {
+            errors.push(("monitoring.history.save_interval_secs", "History save_interval_secs must be greater than 0".to_string()));
+        }
+        errors
+    }
+}
+
+/// 查询的时间范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HistoryRange {
+    #[serde(rename = "1h")]
+    Hour,
+    #[default]
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+}
+
+impl HistoryRange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HistoryRange::Hour => "1h",
+            HistoryRange::Day => "24h",
+            HistoryRange::Week => "7d",
+        }
+    }
+
+    pub fn duration(self) -> Duration {
+        Duration::from_secs(match self {
+            HistoryRange::Hour => 3600,
+            HistoryRange::Day => 86400,
+            HistoryRange::Week => 7 * 86400,
+        })
+    }
+
+    /// 使用的降采样档位
+    fn tier(self) -> usize {
+        match self {
+            HistoryRange::Hour => 0,
+            HistoryRange::Day => 1,
+            HistoryRange::Week => 2,
+        }
+    }
+
+    /// 数据点的桶宽 (秒)
+    pub fn bucket_secs(self) -> u64 {
+        TIERS[self.tier()].0
+    }
+}
+
+/// 一条曲线：总体、某台设备或某个矿池，文本形式为 `total` / `device:<id>` / `pool:<id>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesId {
+    Total,
+    Device(u32),
+    Pool(u32),
+}
+
+impl fmt::Display for SeriesId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeriesId::Total => write!(f, "total"),
+            SeriesId::Device(id) => write!(f, "device:{}", id),
+            SeriesId::Pool(id) => write!(f, "pool:{}", id),
+        }
+    }
+}
+
+impl FromStr for SeriesId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid series '{}', expected total, device:<id> or pool:<id>", s);
+        match s.split_once(':') {
+            None if s == "total" => Ok(SeriesId::Total),
+            Some(("device", id)) => id.parse().map(SeriesId::Device).map_err(|_| invalid()),
+            Some(("pool", id)) => id.parse().map(SeriesId::Pool).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// 一个时间桶的汇总
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// 桶的起始时间 (Unix 秒)
+    pub timestamp: u64,
+    /// 平均算力 (GH/s)，矿池没有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hashrate: Option<f64>,
+    /// 平均温度 (°C)，只有设备有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// 桶内接受的份额
+    pub accepted: u64,
+    /// 桶内拒绝的份额
+    pub rejected: u64,
+    /// 桶内的硬件错误，矿池为过期份额
+    pub errors: u64,
+    /// 在线时间比例 (0-1)，只有矿池有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online: Option<f32>,
+}
+
+/// 份额计数，采样里是累计值，桶里是增量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+struct Counters {
+    accepted: u64,
+    rejected: u64,
+    errors: u64,
+}
+
+impl Counters {
+    /// 相对上一次采样的增量，计数器回退（设备或矿池重启）时从 0 重新累计
+    fn since(self, last: Option<Counters>) -> Counters {
+        let Some(last) = last else { return self };
+        let delta = |now: u64, before: u64| if now >= before { now - before } else { now };
+        Counters {
+            accepted: delta(self.accepted, last.accepted),
+            rejected: delta(self.rejected, last.rejected),
+            errors: delta(self.errors, last.errors),
+        }
+    }
+}
+
+/// 一次采样
+#[derive(Debug, Clone, Copy, Default)]
+struct Sample {
+    hashrate: Option<f64>,
+    temperature: Option<f32>,
+    online: Option<bool>,
+    counters: Counters,
+}
+
+impl From<&MiningMetrics> for Sample {
+    fn from(m: &MiningMetrics) -> Self {
+        Self {
+            hashrate: Some(m.total_hashrate),
+            counters: Counters { accepted: m.accepted_shares, rejected: m.rejected_shares, errors: m.hardware_errors },
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&DeviceMetrics> for Sample {
+    fn from(m: &DeviceMetrics) -> Self {
+        Self {
+            hashrate: Some(m.hashrate),
+            // 读不到温度时为 0
+            temperature: (m.temperature > 0.0).then_some(m.temperature),
+            counters: Counters { accepted: m.accepted_shares, rejected: m.rejected_shares, errors: m.hardware_errors },
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&PoolMetrics> for Sample {
+    fn from(m: &PoolMetrics) -> Self {
+        Self {
+            online: Some(m.connected),
+            counters: Counters { accepted: m.accepted_shares, rejected: m.rejected_shares, errors: m.stale_shares },
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Mean {
+    sum: f64,
+    count: u32,
+}
+
+impl Mean {
+    fn add(&mut self, value: Option<f64>) {
+        if let Some(value) = value {
+            self.sum += value;
+            self.count += 1;
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+/// 正在累积的时间桶
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Bucket {
+    start: u64,
+    hashrate: Mean,
+    temperature: Mean,
+    online: Mean,
+    counters: Counters,
+}
+
+impl Bucket {
+    fn add(&mut self, sample: &Sample, delta: Counters) {
+        self.hashrate.add(sample.hashrate);
+        self.temperature.add(sample.temperature.map(f64::from));
+        self.online.add(sample.online.map(|online| if online { 1.0 } else { 0.0 }));
+        self.counters.accepted += delta.accepted;
+        self.counters.rejected += delta.rejected;
+        self.counters.errors += delta.errors;
+    }
+
+    fn point(&self) -> HistoryPoint {
+        HistoryPoint {
+            timestamp: self.start,
+            hashrate: self.hashrate.value(),
+            temperature: self.temperature.value().map(|t| t as f32),
+            accepted: self.counters.accepted,
+            rejected: self.counters.rejected,
+            errors: self.counters.errors,
+            online: self.online.value().map(|o| o as f32),
+        }
+    }
+}
+
+/// 一档降采样数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Tier {
+    points: VecDeque<HistoryPoint>,
+    current: Option<Bucket>,
+}
+
+impl Tier {
+    fn record(&mut self, now: u64, (width, retention): (u64, u64), sample: &Sample, delta: Counters) {
+        let start = now - now % width;
+        if matches!(&self.current, Some(bucket) if bucket.start != start) {
+            if let Some(bucket) = self.current.take() {
+                self.points.push_back(bucket.point());
+            }
+        }
+        self.prune(now, retention);
+        self.current.get_or_insert_with(|| Bucket { start, ..Bucket::default() }).add(sample, delta);
+    }
+
+    fn prune(&mut self, now: u64, retention: u64) {
+        let cutoff = now.saturating_sub(retention);
+        while self.points.front().is_some_and(|p| p.timestamp < cutoff) {
+            self.points.pop_front();
+        }
+        if self.current.as_ref().is_some_and(|bucket| bucket.start < cutoff) {
+            self.current = None;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.points.is_empty() && self.current.is_none()
+    }
+}
+
+/// 一条曲线的各档数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Series {
+    tiers: [Tier; 3],
+    /// 上一次采样的累计计数，重启后重新开始
+    #[serde(skip)]
+    last: Option<Counters>,
+}
+
+impl Series {
+    fn record(&mut self, now: u64, sample: Sample) {
+        let delta = sample.counters.since(self.last);
+        self.last = Some(sample.counters);
+        for (tier, &config) in self.tiers.iter_mut().zip(TIERS.iter()) {
+            tier.record(now, config, &sample, delta);
+        }
+    }
+
+    /// `since` 之后的数据点，包括正在累积的桶
+    fn points(&self, range: HistoryRange, since: u64) -> Vec<HistoryPoint> {
+        let tier = &self.tiers[range.tier()];
+        tier.points.iter()
+            .filter(|p| p.timestamp >= since)
+            .cloned()
+            .chain(tier.current.as_ref().map(Bucket::point))
+            .collect()
+    }
+}
+
+/// 全部曲线，整体序列化到历史文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HistoryData {
+    total: Series,
+    devices: BTreeMap<u32, Series>,
+    pools: BTreeMap<u32, Series>,
+}
+
+impl HistoryData {
+    fn record(&mut self, event: &MonitoringEvent) {
+        match event {
+            MonitoringEvent::MiningMetricsUpdate { metrics, .. } => {
+                self.total.record(unix_secs(metrics.timestamp), metrics.into());
+            }
+            MonitoringEvent::DeviceMetricsUpdate { device_id, metrics, .. } => {
+                self.devices.entry(*device_id).or_default().record(unix_secs(metrics.timestamp), metrics.into());
+            }
+            MonitoringEvent::PoolMetricsUpdate { pool_id, metrics, .. } => {
+                self.pools.entry(*pool_id).or_default().record(unix_secs(metrics.timestamp), metrics.into());
+            }
+            _ => {}
+        }
+    }
+
+    fn series(&self, id: SeriesId) -> Option<&Series> {
+        match id {
+            SeriesId::Total => Some(&self.total),
+            SeriesId::Device(device_id) => self.devices.get(&device_id),
+            SeriesId::Pool(pool_id) => self.pools.get(&pool_id),
+        }
+    }
+
+    /// 丢弃过期的数据点和已经没有数据的设备、矿池
+    fn prune(&mut self, now: u64) {
+        let prune = |series: &mut Series| {
+            for (tier, &(_, retention)) in series.tiers.iter_mut().zip(TIERS.iter()) {
+                tier.prune(now, retention);
+            }
+            series.tiers.iter().any(|tier| !tier.is_empty())
+        };
+        prune(&mut self.total);
+        self.devices.retain(|_, series| prune(series));
+        self.pools.retain(|_, series| prune(series));
+    }
+}
+
+/// 降采样的指标历史存储
+pub struct MetricsStore {
+    config: HistoryConfig,
+    data: RwLock<HistoryData>,
+}
+
+impl MetricsStore {
+    /// 创建存储并读取已有的历史文件
+    pub fn new(config: HistoryConfig) -> Result<Self, MiningError> {
+        let mut data = if config.path.is_empty() {
+            HistoryData::default()
+        } else {
+            load_history(Path::new(&config.path))?
+        };
+        data.prune(unix_secs(SystemTime::now()));
+        Ok(Self { config, data: RwLock::new(data) })
+    }
+
+    /// 有数据的曲线
+    pub async fn series(&self) -> Vec<SeriesId> {
+        let data = self.data.read().await;
+        std::iter::once(SeriesId::Total)
+            .chain(data.devices.keys().map(|id| SeriesId::Device(*id)))
+            .chain(data.pools.keys().map(|id| SeriesId::Pool(*id)))
+            .collect()
+    }
+
+    /// 查询一条曲线最近 `range` 内的数据，曲线不存在时返回 None
+    pub async fn query(&self, series: SeriesId, range: HistoryRange) -> Option<Vec<HistoryPoint>> {
+        let since = unix_secs(SystemTime::now()).saturating_sub(range.duration().as_secs());
+        self.data.read().await.series(series).map(|s| s.points(range, since))
+    }
+
+    /// 写入历史文件
+    pub async fn save(&self) -> Result<(), MiningError> {
+        if self.config.path.is_empty() {
+            return Ok(());
+        }
+        let encoded = {
+            let mut data = self.data.write().await;
+            data.prune(unix_secs(SystemTime::now()));
+            serde_json::to_vec(&*data)
+                .map_err(|e| MiningError::System(format!("Failed to encode metrics history: {}", e)))?
+        };
+        save_history(Path::new(&self.config.path), encoded).await
+    }
+
+    /// 订阅监控事件，并按 `save_interval_secs` 定期写入文件
+    pub fn start(self: &Arc<Self>, mut events: broadcast::Receiver<MonitoringEvent>) -> tokio::task::JoinHandle<()> {
+        let store = self.clone();
+        let save_interval = Duration::from_secs(self.config.save_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + save_interval, save_interval);
+            loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Ok(event) => store.data.write().await.record(&event),
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            debug!("Metrics store skipped {} monitoring events", skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = ticker.tick() => {
+                        if let Err(e) = store.save().await {
+                            warn!("Failed to save metrics history: {}", e);
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn load_history(path: &Path) -> Result<HistoryData, MiningError> {
+    match std::fs::read(path) {
+        Ok(data) => {
+            let history = serde_json::from_slice(&data)
+                .map_err(|e| MiningError::System(format!("Invalid metrics history file {}: {}", path.display(), e)))?;
+            info!("📈 Loaded metrics history from {}", path.display());
+            Ok(history)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HistoryData::default()),
+        Err(e) => Err(MiningError::System(format!("Failed to read {}: {}", path.display(), e))),
+    }
+}
+
+/// 先写临时文件再改名，避免写到一半时丢失全部历史
+async fn save_history(path: &Path, data: Vec<u8>) -> Result<(), MiningError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await
+            .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    tokio::fs::write(&tmp, data).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", tmp.display(), e)))?;
+    tokio::fs::rename(&tmp, path).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device_event(device_id: u32, at: u64, hashrate: f64, accepted: u64) -> MonitoringEvent {
+        let mut metrics = DeviceMetrics::new(device_id);
+        metrics.timestamp = UNIX_EPOCH + Duration::from_secs(at);
+        metrics.hashrate = hashrate;
+        metrics.temperature = 60.0;
+        metrics.accepted_shares = accepted;
+        MonitoringEvent::DeviceMetricsUpdate { device_id, metrics, timestamp: SystemTime::now() }
+    }
+
+    #[test]
+    fn test_series_id() {
+        for id in [SeriesId::Total, SeriesId::Device(3), SeriesId::Pool(0)] {
+            assert_eq!(id.to_string().parse::<SeriesId>(), Ok(id));
+        }
+        assert!("device:x".parse::<SeriesId>().is_err());
+        assert!("chain:1".parse::<SeriesId>().is_err());
+    }
+
+    #[test]
+    fn test_downsampling() {
+        let mut data = HistoryData::default();
+        // 两个 1 分钟桶，第二个桶内计数器回退
+        data.record(&device_event(0, 6000, 100.0, 10));
+        data.record(&device_event(0, 6030, 200.0, 16));
+        data.record(&device_event(0, 6060, 300.0, 4));
+
+        let series = data.series(SeriesId::Device(0)).unwrap();
+        let minutes = series.points(HistoryRange::Hour, 0);
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[0].timestamp, 6000);
+        assert_eq!(minutes[0].hashrate, Some(150.0));
+        assert_eq!(minutes[0].accepted, 16);
+        assert_eq!(minutes[1].accepted, 4);
+        assert_eq!(minutes[1].temperature, Some(60.0));
+
+        // 同一个 5 分钟桶
+        let buckets = series.points(HistoryRange::Day, 0);
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].hashrate, Some(200.0));
+        assert_eq!(buckets[0].accepted, 20);
+        assert!(data.series(SeriesId::Pool(0)).is_none());
+    }
+
+    #[test]
+    fn test_retention() {
+        let mut data = HistoryData::default();
+        data.record(&device_event(1, 0, 100.0, 0));
+        data.record(&device_event(1, 3 * 3600, 100.0, 0));
+
+        let series = data.series(SeriesId::Device(1)).unwrap();
+        assert_eq!(series.points(HistoryRange::Hour, 0).len(), 1);
+        assert_eq!(series.points(HistoryRange::Week, 0).len(), 2);
+
+        data.prune(30 * 86400);
+        assert!(data.series(SeriesId::Device(1)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("cgminer-history-{}", std::process::id())).join("history.json");
+        let config = HistoryConfig { path: path.display().to_string(), ..HistoryConfig::default() };
+        let now = unix_secs(SystemTime::now());
+
+        let store = MetricsStore::new(config.clone()).unwrap();
+        store.data.write().await.record(&device_event(2, now, 50.0, 5));
+        store.save().await.unwrap();
+
+        let loaded = MetricsStore::new(config).unwrap();
+        assert_eq!(loaded.series().await, vec![SeriesId::Total, SeriesId::Device(2)]);
+        let points = loaded.query(SeriesId::Device(2), HistoryRange::Hour).await.unwrap();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].accepted, 5);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
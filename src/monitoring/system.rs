@@ -2,16 +2,19 @@ use crate::config::MonitoringConfig;
 use crate::error::MiningError;
 use crate::monitoring::{
     SystemMetrics, MiningMetrics, DeviceMetrics, PoolMetrics, MetricsHistory,
-    MonitoringState, MonitoringEvent, PerformanceStats
+    MonitoringState, MonitoringEvent, PerformanceStats, RecentShare
 };
 use crate::monitoring::metrics::MetricsCollector;
-use crate::monitoring::alerts::AlertManager;
+use crate::monitoring::alerts::{Alert, AlertManager, AlertSeverity, AlertType};
 use crate::monitoring::simple_web::SimpleWebMonitor;
+use crate::monitoring::push::PushNotifier;
+use crate::monitoring::store::MetricsStore;
+use crate::monitoring::memory::{MemoryPressure, MemoryStatus, RssSampler, DEFAULT_HISTORY_ENTRIES};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, Mutex, broadcast};
 use tokio::time::interval;
-use tracing::{info, warn, debug};
+use tracing::{info, warn, error, debug};
 
 /// 监控系统（简化版本）
 pub struct MonitoringSystem {
@@ -37,6 +40,18 @@ pub struct MonitoringSystem {
     alert_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     /// 清理任务句柄
     cleanup_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// Web Push 推送器，未启用或初始化失败时为空
+    push_notifier: Option<Arc<PushNotifier>>,
+    /// 推送任务句柄
+    push_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 降采样的指标历史，未启用或读取失败时为空
+    metrics_store: Option<Arc<MetricsStore>>,
+    /// 指标历史任务句柄
+    store_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    /// 内存预算状态
+    memory_status: Arc<RwLock<MemoryStatus>>,
+    /// 内存预算检查任务句柄
+    memory_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     /// 运行标志
     running: Arc<RwLock<bool>>,
 }
@@ -48,7 +63,7 @@ impl MonitoringSystem {
 
         let metrics_collector = MetricsCollector::new();
         let alert_manager = AlertManager::new(config.alert_thresholds.clone());
-        let metrics_history = MetricsHistory::new(1000); // 保留最近1000条记录
+        let metrics_history = MetricsHistory::new(DEFAULT_HISTORY_ENTRIES); // 保留最近1000条记录
         let (event_sender, _) = broadcast::channel(1000);
 
         // 创建简单Web监控器（如果启用）
@@ -58,6 +73,39 @@ impl MonitoringSystem {
             None
         };
 
+        // 告警推送不影响挖矿，初始化失败时只记录错误
+        let push_notifier = if config.enabled && config.push.enabled {
+            match PushNotifier::new(config.push.clone()) {
+                Ok(notifier) => Some(Arc::new(notifier)),
+                Err(e) => {
+                    error!("❌ Web push notifications disabled: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // 历史文件损坏时不影响挖矿，只是没有历史图表
+        let metrics_store = if config.enabled && config.history.enabled {
+            match MetricsStore::new(config.history.clone()) {
+                Ok(store) => Some(Arc::new(store)),
+                Err(e) => {
+                    error!("❌ Metrics history disabled: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let memory_status = MemoryStatus {
+            enabled: config.memory.enabled,
+            budget_bytes: config.memory.budget_bytes(),
+            history_entries: DEFAULT_HISTORY_ENTRIES,
+            ..Default::default()
+        };
+
         Ok(Self {
             config,
             state: Arc::new(RwLock::new(MonitoringState::Stopped)),
@@ -70,6 +118,12 @@ impl MonitoringSystem {
             collection_handle: Arc::new(Mutex::new(None)),
             alert_handle: Arc::new(Mutex::new(None)),
             cleanup_handle: Arc::new(Mutex::new(None)),
+            push_notifier,
+            push_handle: Arc::new(Mutex::new(None)),
+            metrics_store,
+            store_handle: Arc::new(Mutex::new(None)),
+            memory_status: Arc::new(RwLock::new(memory_status)),
+            memory_handle: Arc::new(Mutex::new(None)),
             running: Arc::new(RwLock::new(false)),
         })
     }
@@ -102,6 +156,21 @@ impl MonitoringSystem {
         // 启动清理任务
         self.start_cleanup_task().await?;
 
+        // 检查内存预算
+        if self.config.memory.enabled {
+            self.start_memory_budget_check().await;
+        }
+
+        // 推送严重告警
+        if let Some(notifier) = &self.push_notifier {
+            *self.push_handle.lock().await = Some(notifier.start(self.event_sender.subscribe()));
+        }
+
+        // 记录降采样的指标历史
+        if let Some(store) = &self.metrics_store {
+            *self.store_handle.lock().await = Some(store.start(self.event_sender.subscribe()));
+        }
+
         // 启动简单Web监控器
         if let Some(ref monitor) = self.web_monitor {
             monitor.lock().await.start().await?;
@@ -138,6 +207,13 @@ impl MonitoringSystem {
         // 停止所有任务
         self.stop_tasks().await;
 
+        // 保存指标历史
+        if let Some(store) = &self.metrics_store {
+            if let Err(e) = store.save().await {
+                warn!("Failed to save metrics history: {}", e);
+            }
+        }
+
         // 停止简单Web监控器
         if let Some(ref monitor) = self.web_monitor {
             monitor.lock().await.stop().await?;
@@ -180,11 +256,77 @@ impl MonitoringSystem {
         history.get_latest_pool_metrics(pool_id).cloned()
     }
 
+    /// 获取所有设备的最新指标，按设备ID排序
+    pub async fn get_all_device_metrics(&self) -> Vec<DeviceMetrics> {
+        let history = self.metrics_history.read().await;
+        let mut devices: Vec<DeviceMetrics> = history.device_metrics.values()
+            .filter_map(|h| h.last().cloned())
+            .collect();
+        devices.sort_by_key(|m| m.device_id);
+        devices
+    }
+
+    /// 获取所有矿池的最新指标，按矿池ID排序
+    pub async fn get_all_pool_metrics(&self) -> Vec<PoolMetrics> {
+        let history = self.metrics_history.read().await;
+        let mut pools: Vec<PoolMetrics> = history.pool_metrics.values()
+            .filter_map(|h| h.last().cloned())
+            .collect();
+        pools.sort_by_key(|m| m.pool_id);
+        pools
+    }
+
+    /// 获取最近的挖矿指标历史，最多 `limit` 条，从旧到新
+    pub async fn get_mining_history(&self, limit: usize) -> Vec<MiningMetrics> {
+        let history = self.metrics_history.read().await;
+        history.mining_metrics.recent(limit).cloned().collect()
+    }
+
+    /// 记录份额提交结果
+    pub async fn record_share(&self, share: RecentShare) {
+        self.metrics_history.write().await.add_share(share.clone());
+        let _ = self.event_sender.send(MonitoringEvent::ShareRecorded {
+            share,
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    /// 获取最近的份额，从新到旧
+    pub async fn get_recent_shares(&self) -> Vec<RecentShare> {
+        self.metrics_history.read().await.recent_shares.iter().rev().cloned().collect()
+    }
+
     /// 获取性能统计
     pub async fn get_performance_stats(&self) -> PerformanceStats {
         self.performance_stats.read().await.clone()
     }
 
+    /// 获取活跃告警
+    pub async fn get_active_alerts(&self) -> Vec<Alert> {
+        self.alert_manager.lock().await.get_active_alerts().into_iter().cloned().collect()
+    }
+
+    /// 触发告警：加入活跃告警并广播 (推送通知等订阅者会收到)
+    pub async fn raise_alert(&self, alert: Alert) {
+        if let Err(e) = self.alert_manager.lock().await.add_alert(alert.clone()).await {
+            warn!("Failed to record alert: {}", e);
+        }
+        let _ = self.event_sender.send(MonitoringEvent::AlertTriggered {
+            alert,
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    /// Web Push 推送器
+    pub fn push_notifier(&self) -> Option<Arc<PushNotifier>> {
+        self.push_notifier.clone()
+    }
+
+    /// 指标历史存储
+    pub fn metrics_store(&self) -> Option<Arc<MetricsStore>> {
+        self.metrics_store.clone()
+    }
+
     /// 订阅监控事件
     pub fn subscribe_events(&self) -> broadcast::Receiver<MonitoringEvent> {
         self.event_sender.subscribe()
@@ -350,6 +492,17 @@ impl MonitoringSystem {
                         }
                     }
 
+                    // 检查矿池告警
+                    let pools: Vec<&PoolMetrics> = history.pool_metrics.values().filter_map(|m| m.last()).collect();
+                    if let Ok(alerts) = manager.check_pool_alerts(&pools).await {
+                        for alert in alerts {
+                            let _ = event_sender.send(MonitoringEvent::AlertTriggered {
+                                alert,
+                                timestamp: SystemTime::now(),
+                            });
+                        }
+                    }
+
                     // 检查设备告警
                     for device_id in 0..2u32 {
                         if let Some(device_metrics) = history.get_latest_device_metrics(device_id) {
@@ -399,6 +552,100 @@ impl MonitoringSystem {
         Ok(())
     }
 
+    /// 启动内存预算检查任务
+    async fn start_memory_budget_check(&self) {
+        let running = self.running.clone();
+        let config = self.config.memory.clone();
+        let metrics_history = self.metrics_history.clone();
+        let alert_manager = self.alert_manager.clone();
+        let memory_status = self.memory_status.clone();
+        let event_sender = self.event_sender.clone();
+
+        info!("Memory budget: {} MB (warn at {}%, shrink histories at {}%)",
+              config.budget_mb, config.warn_percent, config.shrink_percent);
+
+        let handle = tokio::spawn(async move {
+            let mut sampler = RssSampler::new();
+            let mut interval = interval(Duration::from_secs(config.check_interval_secs));
+            let mut last_pressure = MemoryPressure::Normal;
+
+            while *running.read().await {
+                interval.tick().await;
+
+                let Some(rss_bytes) = sampler.sample() else {
+                    debug!("Process RSS is not available on this platform");
+                    continue;
+                };
+                let pressure = config.pressure(rss_bytes);
+                let rss_mb = rss_bytes / (1024 * 1024);
+
+                if pressure != last_pressure {
+                    let alert = match pressure {
+                        MemoryPressure::Critical => {
+                            warn!("Memory use {} MB is over {}% of the {} MB budget, shrinking in-memory histories to {} entries",
+                                  rss_mb, config.shrink_percent, config.budget_mb, config.reduced_history_entries);
+                            metrics_history.write().await.set_max_entries(config.reduced_history_entries);
+                            alert_manager.lock().await.set_max_history(config.reduced_history_entries);
+                            Some(Alert::new(
+                                AlertType::System,
+                                AlertSeverity::Critical,
+                                "Memory budget nearly exhausted".to_string(),
+                                format!("Resident memory {} MB of {} MB budget; in-memory histories reduced to {} entries",
+                                        rss_mb, config.budget_mb, config.reduced_history_entries),
+                                "memory_budget".to_string(),
+                            ))
+                        }
+                        MemoryPressure::Warning if last_pressure == MemoryPressure::Normal => {
+                            warn!("Memory use {} MB is over {}% of the {} MB budget", rss_mb, config.warn_percent, config.budget_mb);
+                            Some(Alert::new(
+                                AlertType::System,
+                                AlertSeverity::Warning,
+                                "Memory budget warning".to_string(),
+                                format!("Resident memory {} MB of {} MB budget", rss_mb, config.budget_mb),
+                                "memory_budget".to_string(),
+                            ))
+                        }
+                        // 从严重降到警告时保持缩减后的历史长度，避免在阈值附近反复增减
+                        MemoryPressure::Warning => None,
+                        MemoryPressure::Normal => {
+                            info!("Memory use {} MB is back under the budget warning level, restoring history length", rss_mb);
+                            metrics_history.write().await.set_max_entries(DEFAULT_HISTORY_ENTRIES);
+                            alert_manager.lock().await.set_max_history(DEFAULT_HISTORY_ENTRIES);
+                            None
+                        }
+                    };
+                    if let Some(alert) = alert {
+                        if let Err(e) = alert_manager.lock().await.add_alert(alert.clone()).await {
+                            warn!("Failed to record alert: {}", e);
+                        }
+                        let _ = event_sender.send(MonitoringEvent::AlertTriggered {
+                            alert,
+                            timestamp: SystemTime::now(),
+                        });
+                    }
+                    last_pressure = pressure;
+                }
+
+                let history_entries = metrics_history.read().await.max_entries;
+                *memory_status.write().await = MemoryStatus {
+                    enabled: true,
+                    rss_bytes,
+                    budget_bytes: config.budget_bytes(),
+                    pressure,
+                    history_entries,
+                    checked_at: Some(SystemTime::now()),
+                };
+            }
+        });
+
+        *self.memory_handle.lock().await = Some(handle);
+    }
+
+    /// 内存预算状态
+    pub async fn memory_status(&self) -> MemoryStatus {
+        self.memory_status.read().await.clone()
+    }
+
     /// 停止所有任务
     async fn stop_tasks(&self) {
         // 停止指标收集任务
@@ -415,6 +662,21 @@ impl MonitoringSystem {
         if let Some(handle) = self.cleanup_handle.lock().await.take() {
             handle.abort();
         }
+
+        // 停止告警推送
+        if let Some(handle) = self.push_handle.lock().await.take() {
+            handle.abort();
+        }
+
+        // 停止指标历史记录
+        if let Some(handle) = self.store_handle.lock().await.take() {
+            handle.abort();
+        }
+
+        // 停止内存预算检查
+        if let Some(handle) = self.memory_handle.lock().await.take() {
+            handle.abort();
+        }
     }
 
     /// 重置指标历史
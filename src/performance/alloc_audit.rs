@@ -0,0 +1,285 @@
+//! 热路径分配审计 (`alloc-audit` 特性)
+//!
+//! 启用特性后安装一个计数的全局分配器，按子系统 (工作分发、结果收集、Stratum 解析) 统计分配次数和字节数，
+//! 用来核实"减少了分配"之类的优化声明。当前子系统记录在线程局部变量中：同步代码用 [`enter`] 返回的守卫，
+//! 异步任务用 [`instrument`] 包装，每次 poll 时设置子系统，任务在线程间迁移也能正确归属。
+//!
+//! 未启用特性时 [`enter`] 和 [`instrument`] 不做任何事，调用点无需 `cfg`。
+
+use serde::Serialize;
+use std::future::Future;
+use std::time::Instant;
+
+/// 参与统计的子系统
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    WorkDispatch,
+    ResultCollection,
+    StratumParse,
+    /// 不在任何审计范围内的分配
+    Other,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 4] = [
+        Subsystem::WorkDispatch,
+        Subsystem::ResultCollection,
+        Subsystem::StratumParse,
+        Subsystem::Other,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// 某一时刻各子系统的累计分配
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocSnapshot {
+    pub allocations: [u64; 4],
+    pub bytes: [u64; 4],
+}
+
+impl AllocSnapshot {
+    pub fn allocations(&self, subsystem: Subsystem) -> u64 {
+        self.allocations[subsystem.index()]
+    }
+
+    pub fn bytes(&self, subsystem: Subsystem) -> u64 {
+        self.bytes[subsystem.index()]
+    }
+}
+
+/// 单个子系统每秒的分配速率
+#[derive(Debug, Clone, Serialize)]
+pub struct SubsystemAllocRate {
+    pub subsystem: Subsystem,
+    pub allocations_per_sec: f64,
+    pub bytes_per_sec: f64,
+    /// 启动以来的累计分配次数
+    pub total_allocations: u64,
+}
+
+/// 特性是否启用 (未启用时所有计数为 0)
+pub const fn is_enabled() -> bool {
+    cfg!(feature = "alloc-audit")
+}
+
+#[cfg(feature = "alloc-audit")]
+mod counting {
+    use super::{AllocSnapshot, Subsystem};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+    static ALLOCATIONS: [AtomicU64; 4] = [ZERO; 4];
+    static BYTES: [AtomicU64; 4] = [ZERO; 4];
+
+    thread_local! {
+        // const 初始化且没有析构函数，在分配器中访问不会再次分配
+        static CURRENT: Cell<Subsystem> = const { Cell::new(Subsystem::Other) };
+    }
+
+    /// 计数的全局分配器，实际分配交给系统分配器
+    pub struct CountingAllocator;
+
+    impl CountingAllocator {
+        #[inline]
+        fn record(size: usize) {
+            let index = CURRENT.try_with(|current| current.get()).unwrap_or(Subsystem::Other).index();
+            ALLOCATIONS[index].fetch_add(1, Ordering::Relaxed);
+            BYTES[index].fetch_add(size as u64, Ordering::Relaxed);
+        }
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            Self::record(layout.size());
+            System.alloc(layout)
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            Self::record(layout.size());
+            System.alloc_zeroed(layout)
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            Self::record(new_size);
+            System.realloc(ptr, layout, new_size)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// 设置当前线程的子系统，返回之前的子系统
+    pub fn set_current(subsystem: Subsystem) -> Subsystem {
+        CURRENT.try_with(|current| current.replace(subsystem)).unwrap_or(Subsystem::Other)
+    }
+
+    pub fn snapshot() -> AllocSnapshot {
+        let mut snapshot = AllocSnapshot::default();
+        for i in 0..4 {
+            snapshot.allocations[i] = ALLOCATIONS[i].load(Ordering::Relaxed);
+            snapshot.bytes[i] = BYTES[i].load(Ordering::Relaxed);
+        }
+        snapshot
+    }
+}
+
+/// 各子系统的累计分配；未启用特性时全为 0
+pub fn snapshot() -> AllocSnapshot {
+    #[cfg(feature = "alloc-audit")]
+    {
+        counting::snapshot()
+    }
+    #[cfg(not(feature = "alloc-audit"))]
+    {
+        AllocSnapshot::default()
+    }
+}
+
+/// 同步代码的审计范围，守卫释放时恢复之前的子系统
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct AllocScope {
+    #[cfg(feature = "alloc-audit")]
+    previous: Subsystem,
+}
+
+impl Drop for AllocScope {
+    fn drop(&mut self) {
+        #[cfg(feature = "alloc-audit")]
+        counting::set_current(self.previous);
+    }
+}
+
+/// 进入审计范围。不要跨越 `.await` 持有守卫，异步代码使用 [`instrument`]
+pub fn enter(subsystem: Subsystem) -> AllocScope {
+    #[cfg(feature = "alloc-audit")]
+    {
+        AllocScope { previous: counting::set_current(subsystem) }
+    }
+    #[cfg(not(feature = "alloc-audit"))]
+    {
+        let _ = subsystem;
+        AllocScope {}
+    }
+}
+
+/// 把异步任务的分配归属到 `subsystem`
+#[cfg(feature = "alloc-audit")]
+pub fn instrument<F: Future>(subsystem: Subsystem, future: F) -> impl Future<Output = F::Output> {
+    let mut future = Box::pin(future);
+    std::future::poll_fn(move |cx| {
+        let _scope = enter(subsystem);
+        future.as_mut().poll(cx)
+    })
+}
+
+/// 把异步任务的分配归属到 `subsystem` (未启用特性，原样返回)
+#[cfg(not(feature = "alloc-audit"))]
+pub fn instrument<F: Future>(_subsystem: Subsystem, future: F) -> F {
+    future
+}
+
+/// 按两次采样的差值计算每秒分配速率
+#[derive(Debug, Clone)]
+pub struct AllocRateTracker {
+    last: AllocSnapshot,
+    last_at: Instant,
+}
+
+impl AllocRateTracker {
+    pub fn new() -> Self {
+        Self {
+            last: snapshot(),
+            last_at: Instant::now(),
+        }
+    }
+
+    /// 从上次采样以来各子系统的每秒分配
+    pub fn sample(&mut self) -> Vec<SubsystemAllocRate> {
+        let now = Instant::now();
+        let current = snapshot();
+        let rates = Self::rates(&self.last, &current, now.duration_since(self.last_at).as_secs_f64());
+        self.last = current;
+        self.last_at = now;
+        rates
+    }
+
+    fn rates(previous: &AllocSnapshot, current: &AllocSnapshot, elapsed_secs: f64) -> Vec<SubsystemAllocRate> {
+        let elapsed = elapsed_secs.max(f64::EPSILON);
+        Subsystem::ALL.iter().map(|&subsystem| SubsystemAllocRate {
+            subsystem,
+            allocations_per_sec: current.allocations(subsystem).saturating_sub(previous.allocations(subsystem)) as f64 / elapsed,
+            bytes_per_sec: current.bytes(subsystem).saturating_sub(previous.bytes(subsystem)) as f64 / elapsed,
+            total_allocations: current.allocations(subsystem),
+        }).collect()
+    }
+}
+
+impl Default for AllocRateTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rates_from_snapshots() {
+        let previous = AllocSnapshot::default();
+        let mut current = AllocSnapshot::default();
+        current.allocations[Subsystem::StratumParse.index()] = 200;
+        current.bytes[Subsystem::StratumParse.index()] = 8000;
+        let rates = AllocRateTracker::rates(&previous, &current, 2.0);
+        let parse = rates.iter().find(|rate| rate.subsystem == Subsystem::StratumParse).unwrap();
+        assert_eq!(parse.allocations_per_sec, 100.0);
+        assert_eq!(parse.bytes_per_sec, 4000.0);
+        assert_eq!(parse.total_allocations, 200);
+        assert_eq!(rates.len(), Subsystem::ALL.len());
+    }
+
+    #[cfg(feature = "alloc-audit")]
+    #[test]
+    fn test_scope_attributes_allocations() {
+        let before = snapshot();
+        {
+            let _scope = enter(Subsystem::WorkDispatch);
+            let data: Vec<u8> = std::hint::black_box(vec![0u8; 4096]);
+            drop(data);
+        }
+        let after = snapshot();
+        assert!(after.allocations(Subsystem::WorkDispatch) > before.allocations(Subsystem::WorkDispatch));
+        assert!(after.bytes(Subsystem::WorkDispatch) >= before.bytes(Subsystem::WorkDispatch) + 4096);
+    }
+
+    #[cfg(feature = "alloc-audit")]
+    #[tokio::test]
+    async fn test_instrumented_task() {
+        let before = snapshot();
+        instrument(Subsystem::ResultCollection, async {
+            tokio::task::yield_now().await;
+            std::hint::black_box(vec![1u64; 128]);
+        }).await;
+        assert!(snapshot().allocations(Subsystem::ResultCollection) > before.allocations(Subsystem::ResultCollection));
+    }
+
+    #[cfg(not(feature = "alloc-audit"))]
+    #[test]
+    fn test_disabled_is_noop() {
+        let _scope = enter(Subsystem::WorkDispatch);
+        let _data = std::hint::black_box(vec![0u8; 64]);
+        assert_eq!(snapshot(), AllocSnapshot::default());
+        assert!(!is_enabled());
+    }
+}
@@ -4,6 +4,10 @@
 
 // 注意：复杂的性能优化功能已移除，只保留基础监控
 
+pub mod alloc_audit;
+pub mod task_watch;
+
+use alloc_audit::{AllocRateTracker, SubsystemAllocRate};
 use std::time::{Duration, Instant};
 
 /// 简化的性能监控器
@@ -12,6 +16,8 @@ pub struct PerformanceMonitor {
     start_time: Instant,
     /// 是否启用
     enabled: bool,
+    /// 热路径分配速率 (`alloc-audit` 特性)
+    alloc_tracker: AllocRateTracker,
 }
 
 impl PerformanceMonitor {
@@ -20,6 +26,7 @@ impl PerformanceMonitor {
         Self {
             start_time: Instant::now(),
             enabled: true,
+            alloc_tracker: AllocRateTracker::new(),
         }
     }
 
@@ -37,6 +44,14 @@ impl PerformanceMonitor {
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
+
+    /// 从上次调用以来各子系统每秒的分配次数和字节数；未启用 `alloc-audit` 特性时为 None
+    pub fn allocation_rates(&mut self) -> Option<Vec<SubsystemAllocRate>> {
+        if !alloc_audit::is_enabled() {
+            return None;
+        }
+        Some(self.alloc_tracker.sample())
+    }
 }
 
 impl Default for PerformanceMonitor {
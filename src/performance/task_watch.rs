@@ -0,0 +1,247 @@
+//! 后台任务监视 (`profiling` 特性)
+//!
+//! 生产环境中的挂起通常是某个后台任务停住了：在 `try_lock` 上反复失败的循环一直占着工作线程，
+//! 或者在一把永远拿不到的锁上等待、再也不被唤醒。[`track`] 包装的任务在每次 poll 前后记录时间，
+//! `/debug/tasks` 据此列出所有任务，把单次 poll 超过阈值 (阻塞了工作线程) 或超过阈值没有被 poll
+//! (等待的事件一直没有发生) 的任务标记为卡住。
+//!
+//! 被监视的任务都是按固定间隔醒来的循环 (最长的是 30 秒的矿池心跳)，阈值应大于其中最长的间隔。更细的 waker 级信息用
+//! `tokio-console` 特性接入 tokio console。未启用特性时 [`track`] 原样返回 future，列表为空。
+
+use serde::Serialize;
+use std::future::Future;
+use std::time::Duration;
+
+/// 默认的卡住阈值
+pub const DEFAULT_STUCK_AFTER: Duration = Duration::from_secs(60);
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    /// 正在某个工作线程上执行 poll
+    Running,
+    /// 等待被唤醒
+    Idle,
+}
+
+/// 单个任务的快照
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSnapshot {
+    pub id: u64,
+    pub name: &'static str,
+    pub state: TaskState,
+    /// 任务创建以来的时间 (毫秒)
+    pub age_ms: u64,
+    pub polls: u64,
+    /// poll 累计耗时 (毫秒)
+    pub busy_ms: u64,
+    /// Running 时为当前 poll 已持续的时间，Idle 时为上次 poll 结束以来的时间 (毫秒)
+    pub state_ms: u64,
+    pub stuck: bool,
+}
+
+impl TaskSnapshot {
+    /// 两种状态使用同一个阈值：Running 太久说明阻塞了工作线程，Idle 太久说明等待的事件没有发生
+    fn is_stuck(state_ms: u64, stuck_after: Duration) -> bool {
+        state_ms >= stuck_after.as_millis() as u64
+    }
+}
+
+/// 特性是否启用 (未启用时任务列表为空)
+pub const fn is_enabled() -> bool {
+    cfg!(feature = "profiling")
+}
+
+#[cfg(feature = "profiling")]
+mod registry {
+    use super::{TaskSnapshot, TaskState};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+
+    /// 时间戳的零点；时间戳存为距零点的纳秒数加 1，0 表示未设置
+    fn epoch() -> Instant {
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        *EPOCH.get_or_init(Instant::now)
+    }
+
+    fn now_nanos() -> u64 {
+        epoch().elapsed().as_nanos() as u64 + 1
+    }
+
+    pub struct TaskEntry {
+        id: u64,
+        name: &'static str,
+        created: u64,
+        polls: AtomicU64,
+        busy_nanos: AtomicU64,
+        /// 当前 poll 的开始时间，0 表示不在 poll 中
+        poll_started: AtomicU64,
+        last_poll_end: AtomicU64,
+    }
+
+    impl TaskEntry {
+        pub fn poll_start(&self) -> u64 {
+            let now = now_nanos();
+            self.poll_started.store(now, Ordering::Release);
+            now
+        }
+
+        pub fn poll_end(&self, started: u64) {
+            let now = now_nanos();
+            self.poll_started.store(0, Ordering::Release);
+            self.last_poll_end.store(now, Ordering::Release);
+            self.polls.fetch_add(1, Ordering::Relaxed);
+            self.busy_nanos.fetch_add(now.saturating_sub(started), Ordering::Relaxed);
+        }
+
+        fn snapshot(&self, now: u64, stuck_after: Duration) -> TaskSnapshot {
+            let millis = |nanos: u64| nanos / 1_000_000;
+            let poll_started = self.poll_started.load(Ordering::Acquire);
+            let (state, since) = if poll_started != 0 {
+                (TaskState::Running, poll_started)
+            } else {
+                let last = self.last_poll_end.load(Ordering::Acquire);
+                (TaskState::Idle, if last != 0 { last } else { self.created })
+            };
+            let state_ms = millis(now.saturating_sub(since));
+            TaskSnapshot {
+                id: self.id,
+                name: self.name,
+                state,
+                age_ms: millis(now.saturating_sub(self.created)),
+                polls: self.polls.load(Ordering::Relaxed),
+                busy_ms: millis(self.busy_nanos.load(Ordering::Relaxed)),
+                state_ms,
+                stuck: TaskSnapshot::is_stuck(state_ms, stuck_after),
+            }
+        }
+    }
+
+    fn tasks() -> &'static Mutex<Vec<Arc<TaskEntry>>> {
+        static TASKS: OnceLock<Mutex<Vec<Arc<TaskEntry>>>> = OnceLock::new();
+        TASKS.get_or_init(|| Mutex::new(Vec::new()))
+    }
+
+    /// 任务的注册项，释放时 (任务结束或被中止) 从列表中移除
+    pub struct Registration(pub Arc<TaskEntry>);
+
+    impl Registration {
+        pub fn new(name: &'static str) -> Self {
+            static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+            let entry = Arc::new(TaskEntry {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                name,
+                created: now_nanos(),
+                polls: AtomicU64::new(0),
+                busy_nanos: AtomicU64::new(0),
+                poll_started: AtomicU64::new(0),
+                last_poll_end: AtomicU64::new(0),
+            });
+            tasks().lock().unwrap_or_else(|e| e.into_inner()).push(entry.clone());
+            Self(entry)
+        }
+    }
+
+    impl Drop for Registration {
+        fn drop(&mut self) {
+            tasks().lock().unwrap_or_else(|e| e.into_inner()).retain(|entry| entry.id != self.0.id);
+        }
+    }
+
+    pub fn list(stuck_after: Duration) -> Vec<TaskSnapshot> {
+        let now = now_nanos();
+        let tasks = tasks().lock().unwrap_or_else(|e| e.into_inner());
+        tasks.iter().map(|entry| entry.snapshot(now, stuck_after)).collect()
+    }
+}
+
+/// 把任务登记到监视列表，任务结束后自动移除
+#[cfg(feature = "profiling")]
+pub fn track<F: Future>(name: &'static str, future: F) -> impl Future<Output = F::Output> {
+    let registration = registry::Registration::new(name);
+    let mut future = Box::pin(future);
+    std::future::poll_fn(move |cx| {
+        let started = registration.0.poll_start();
+        let output = future.as_mut().poll(cx);
+        registration.0.poll_end(started);
+        output
+    })
+}
+
+/// 把任务登记到监视列表 (未启用特性，原样返回)
+#[cfg(not(feature = "profiling"))]
+pub fn track<F: Future>(_name: &'static str, future: F) -> F {
+    future
+}
+
+/// 当前被监视的任务，卡住的排在前面
+pub fn list(stuck_after: Duration) -> Vec<TaskSnapshot> {
+    #[cfg(feature = "profiling")]
+    let mut tasks = registry::list(stuck_after);
+    #[cfg(not(feature = "profiling"))]
+    let mut tasks: Vec<TaskSnapshot> = {
+        let _ = stuck_after;
+        Vec::new()
+    };
+    tasks.sort_by(|a, b| b.stuck.cmp(&a.stuck).then(b.state_ms.cmp(&a.state_ms)));
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stuck_threshold() {
+        assert!(TaskSnapshot::is_stuck(60_000, DEFAULT_STUCK_AFTER));
+        assert!(TaskSnapshot::is_stuck(90_000, DEFAULT_STUCK_AFTER));
+        assert!(!TaskSnapshot::is_stuck(59_999, DEFAULT_STUCK_AFTER));
+    }
+
+    #[cfg(feature = "profiling")]
+    #[tokio::test]
+    async fn test_tracked_task_lifecycle() {
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(track("test-waiter", async move {
+            let _ = released.await;
+        }));
+        tokio::task::yield_now().await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let tasks = list(Duration::from_millis(10));
+        let waiter = tasks.iter().find(|task| task.name == "test-waiter").unwrap();
+        assert_eq!(waiter.state, TaskState::Idle);
+        assert!(waiter.polls >= 1);
+        assert!(waiter.stuck);
+        assert!(list(DEFAULT_STUCK_AFTER).iter().all(|task| task.name != "test-waiter" || !task.stuck));
+
+        release.send(()).unwrap();
+        handle.await.unwrap();
+        assert!(list(DEFAULT_STUCK_AFTER).iter().all(|task| task.name != "test-waiter"));
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_blocking_poll_is_running() {
+        let (seen, observed) = std::sync::mpsc::channel();
+        let future = track("test-blocking", async move {
+            std::thread::sleep(Duration::from_millis(20));
+            seen.send(list(Duration::from_millis(10))).unwrap();
+        });
+        futures::executor::block_on(future);
+        let tasks = observed.recv().unwrap();
+        let blocking = tasks.iter().find(|task| task.name == "test-blocking").unwrap();
+        assert_eq!(blocking.state, TaskState::Running);
+        assert!(blocking.stuck);
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    #[test]
+    fn test_disabled_is_empty() {
+        let _ = track("test-disabled", async {});
+        assert!(list(Duration::ZERO).is_empty());
+        assert!(!is_enabled());
+    }
+}
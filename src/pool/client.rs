@@ -0,0 +1,136 @@
+//! 矿池客户端：按 `[[pools.pools]]` 的 `protocol` 选择 Stratum 或 getwork，
+//! `PoolManager` 只通过这里的方法访问客户端
+
+use crate::config::{PoolInfo, PoolProtocol};
+use crate::device::Work;
+use crate::error::PoolError;
+use crate::mining::{Algorithm, Network};
+use crate::pool::getwork::GetworkClient;
+use crate::pool::quirks::PoolQuirks;
+use crate::pool::stratum::StratumClient;
+use crate::pool::Share;
+
+pub enum PoolClient {
+    Stratum(StratumClient),
+    Getwork(GetworkClient),
+}
+
+impl PoolClient {
+    /// 按矿池配置创建客户端
+    pub async fn for_pool(pool_id: u32, pool_info: &PoolInfo) -> Result<Self, PoolError> {
+        let mut client = match pool_info.protocol {
+            PoolProtocol::Stratum => {
+                let mut client = StratumClient::new(
+                    pool_info.url.clone(),
+                    pool_info.username.clone(),
+                    pool_info.password.clone(),
+                    pool_id,
+                    false, // 默认不启用详细日志
+                    pool_info.proxy.clone(), // 传递代理配置
+                ).await?;
+                client.set_quirks(PoolQuirks::resolve(&pool_info.url, pool_info.quirks.as_ref()));
+                PoolClient::Stratum(client)
+            }
+            PoolProtocol::Getwork => PoolClient::Getwork(GetworkClient::new(
+                pool_info.url.clone(),
+                pool_info.username.clone(),
+                pool_info.password.clone(),
+                pool_id,
+            )?),
+        };
+        client.set_algorithm(pool_info.algorithm);
+        Ok(client)
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        match self {
+            PoolClient::Stratum(client) => client.set_algorithm(algorithm),
+            PoolClient::Getwork(client) => client.set_algorithm(algorithm),
+        }
+    }
+
+    pub fn set_network(&mut self, network: Network) {
+        match self {
+            PoolClient::Stratum(client) => client.set_network(network),
+            PoolClient::Getwork(client) => client.set_network(network),
+        }
+    }
+
+    /// 把 extranonce2 空间分成设备子区间；getwork 没有 extranonce2，忽略
+    pub fn set_extranonce2_partitions(&mut self, partitions: u32) {
+        if let PoolClient::Stratum(client) = self {
+            client.set_extranonce2_partitions(partitions);
+        }
+    }
+
+    pub async fn connect(&mut self) -> Result<(), PoolError> {
+        match self {
+            PoolClient::Stratum(client) => client.connect().await,
+            PoolClient::Getwork(client) => client.connect().await,
+        }
+    }
+
+    pub async fn disconnect(&mut self) -> Result<(), PoolError> {
+        match self {
+            PoolClient::Stratum(client) => client.disconnect().await,
+            PoolClient::Getwork(client) => client.disconnect().await,
+        }
+    }
+
+    pub async fn get_work(&self) -> Result<Work, PoolError> {
+        match self {
+            PoolClient::Stratum(client) => client.get_work().await,
+            PoolClient::Getwork(client) => client.get_work().await,
+        }
+    }
+
+    /// 为设备获取工作；getwork 的工作不区分设备
+    pub async fn get_work_for_device(&self, device_id: u32) -> Result<Work, PoolError> {
+        match self {
+            PoolClient::Stratum(client) => client.get_work_for_device(device_id).await,
+            PoolClient::Getwork(client) => client.get_work().await,
+        }
+    }
+
+    pub async fn submit_share(&self, share: &Share) -> Result<bool, PoolError> {
+        match self {
+            PoolClient::Stratum(client) => client.submit_share(share).await,
+            PoolClient::Getwork(client) => client.submit_share(share).await,
+        }
+    }
+
+    pub async fn submit_shares(&self, shares: &[Share]) -> Vec<Result<bool, PoolError>> {
+        match self {
+            PoolClient::Stratum(client) => client.submit_shares(shares).await,
+            PoolClient::Getwork(client) => client.submit_shares(shares).await,
+        }
+    }
+
+    pub async fn is_connected(&self) -> bool {
+        match self {
+            PoolClient::Stratum(client) => client.is_connected().await,
+            PoolClient::Getwork(client) => client.is_connected().await,
+        }
+    }
+
+    pub async fn pending_request_count(&self) -> usize {
+        match self {
+            PoolClient::Stratum(client) => client.pending_request_count().await,
+            PoolClient::Getwork(client) => client.pending_request_count().await,
+        }
+    }
+
+    pub async fn ping(&self) -> Result<(), PoolError> {
+        match self {
+            PoolClient::Stratum(client) => client.ping().await,
+            PoolClient::Getwork(client) => client.ping().await,
+        }
+    }
+
+    pub async fn get_current_difficulty(&self) -> f64 {
+        match self {
+            PoolClient::Stratum(client) => client.get_current_difficulty().await,
+            PoolClient::Getwork(client) => client.get_current_difficulty().await,
+        }
+    }
+}
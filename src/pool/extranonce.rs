@@ -0,0 +1,152 @@
+//! extranonce2 计数器
+//!
+//! 每个 Stratum 连接一个计数器，每次构造工作取下一个值，同一会话内不会重复 (随机生成在
+//! 高工作速率下会碰撞，重复的 extranonce2 产生重复份额)。计数器只在新连接或 extranonce2
+//! 长度变化时归零，作业切换和设备重启工作不会让它回退。
+//!
+//! extranonce2 空间按 `pools.extranonce2_partitions` 均分为若干子区间，设备 `device_id`
+//! 使用第 `device_id % partitions` 个子区间，自己滚动 extranonce2 的设备不会和其它设备重叠。
+//! 子区间数超过 extranonce2 的取值个数 (256^长度) 时按取值个数截断，每个子区间至少一个值，
+//! 不会越出 extranonce2 的长度。
+//! 取值按小端序写入 (与原版 cgminer 一致)，超过 8 字节的部分为零。
+
+use std::ops::RangeInclusive;
+
+/// extranonce2 计数器
+#[derive(Debug, Clone)]
+pub struct Extranonce2Counter {
+    size: usize,
+    /// 配置的子区间数，换长度时按它重新截断
+    configured: u32,
+    /// 实际使用的子区间数，不超过 extranonce2 的取值个数
+    partitions: u32,
+    /// 每个子区间的下一个值 (相对子区间起点)
+    next: Vec<u64>,
+}
+
+impl Extranonce2Counter {
+    pub fn new(size: usize, partitions: u32) -> Self {
+        let configured = partitions.max(1);
+        let partitions = (configured as u128).min(Self::space_for(size)) as u32;
+        Self {
+            size,
+            configured,
+            partitions,
+            next: vec![0; partitions as usize],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 实际使用的子区间数，小于配置值表示被 extranonce2 长度截断
+    pub fn partitions(&self) -> u32 {
+        self.partitions
+    }
+
+    /// 配置的子区间数是否被 extranonce2 长度截断
+    pub fn is_clamped(&self) -> bool {
+        self.partitions < self.configured
+    }
+
+    /// 新会话：按新的长度从零开始
+    pub fn reset(&mut self, size: usize) {
+        *self = Self::new(size, self.configured);
+    }
+
+    /// 长度为 `size` 的 extranonce2 可取值的个数 (最多 2^64)
+    fn space_for(size: usize) -> u128 {
+        1u128 << (8 * size.min(8))
+    }
+
+    fn space(&self) -> u128 {
+        Self::space_for(self.size)
+    }
+
+    /// 每个子区间的取值个数
+    fn width(&self) -> u128 {
+        (self.space() / self.partitions as u128).max(1)
+    }
+
+    /// 设备使用的子区间
+    pub fn range(&self, device_id: u32) -> RangeInclusive<u64> {
+        let start = (device_id % self.partitions) as u128 * self.width();
+        start as u64..=(start + self.width() - 1) as u64
+    }
+
+    /// 设备子区间内的下一个 extranonce2，长度变化时先归零；子区间用完后从头开始
+    pub fn next(&mut self, device_id: u32, size: usize) -> Vec<u8> {
+        if size != self.size {
+            self.reset(size);
+        }
+        let partition = (device_id % self.partitions) as usize;
+        let width = self.width();
+        let offset = self.next[partition] as u128 % width;
+        self.next[partition] = ((offset + 1) % width) as u64;
+
+        let value = *self.range(device_id).start() + offset as u64;
+        let mut bytes = vec![0u8; size];
+        let le = value.to_le_bytes();
+        let len = size.min(8);
+        bytes[..len].copy_from_slice(&le[..len]);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_is_monotonic() {
+        let mut counter = Extranonce2Counter::new(4, 1);
+        assert_eq!(counter.next(0, 4), vec![0, 0, 0, 0]);
+        assert_eq!(counter.next(7, 4), vec![1, 0, 0, 0]);
+        assert_eq!(counter.next(0, 4), vec![2, 0, 0, 0]);
+
+        // 长度变化 (新会话) 后归零
+        assert_eq!(counter.next(0, 2), vec![0, 0]);
+        assert_eq!(counter.size(), 2);
+    }
+
+    #[test]
+    fn test_device_sub_ranges() {
+        let mut counter = Extranonce2Counter::new(1, 4);
+        assert_eq!(counter.range(0), 0..=63);
+        assert_eq!(counter.range(5), 64..=127);
+        assert_eq!(counter.next(1, 1), vec![64]);
+        assert_eq!(counter.next(1, 1), vec![65]);
+        assert_eq!(counter.next(3, 1), vec![192]);
+
+        // 子区间用完后在区间内回绕，不会进入相邻设备的区间
+        for _ in 0..62 {
+            counter.next(1, 1);
+        }
+        assert_eq!(counter.next(1, 1), vec![64]);
+    }
+
+    #[test]
+    fn test_wide_extranonce2() {
+        let mut counter = Extranonce2Counter::new(8, 2);
+        assert_eq!(counter.range(1), 1 << 63..=u64::MAX);
+        assert_eq!(counter.next(1, 12), [&[0, 0, 0, 0, 0, 0, 0, 0x80][..], &[0; 4]].concat());
+    }
+
+    #[test]
+    fn test_partitions_clamped_to_space() {
+        // 1 字节只有 256 个值，1024 个子区间截断为 256 个，每个子区间一个值
+        let mut counter = Extranonce2Counter::new(1, 1024);
+        assert_eq!(counter.partitions(), 256);
+        assert!(counter.is_clamped());
+        assert_eq!(counter.range(255), 255..=255);
+        assert_eq!(counter.range(300), 44..=44);
+        assert_eq!(counter.next(255, 1), vec![255]);
+        assert_eq!(counter.next(255, 1), vec![255]);
+
+        // 换成更长的 extranonce2 后恢复配置的子区间数
+        assert_eq!(counter.next(1000, 2), vec![0x00, 0xfa]);
+        assert_eq!(counter.partitions(), 1024);
+        assert!(!counter.is_clamped());
+    }
+}
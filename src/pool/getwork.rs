@@ -0,0 +1,417 @@
+//! Getwork 协议 (HTTP JSON-RPC + 长轮询)
+//!
+//! 一些私有矿池和测试工具仍然只支持 getwork：每次 `getwork` 请求返回一个完整的区块头 (`data`) 和目标，
+//! 提交时把填入 nonce 的 `data` 原样发回。`data` 是 128 字节 (区块头 80 字节加 SHA256 填充)，
+//! 按 32 位字交换了字节序；`target` 是小端序的 256 位整数，与 `Work::target` 相同。
+//!
+//! 矿池在响应头 `X-Long-Polling` 中给出长轮询地址时，后台任务在该地址上挂起请求，出现新区块时
+//! 矿池返回新工作，下一次 `get_work` 优先下发它。在 `[[pools.pools]]` 中设置 `protocol = "getwork"` 启用。
+//!
+//! bitcoind 自 0.10 起移除了 `getwork`，这里也不实现 `getblocktemplate`/`submitblock`：
+//! 直接连接节点 (包括本地 regtest 节点) 时，需要在节点前面运行一个把 `getblocktemplate`
+//! 转换为 getwork 的代理。
+
+use crate::device::Work;
+use crate::error::PoolError;
+use crate::mining::{midstate, Algorithm, Network};
+use crate::pool::Share;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 普通请求的超时
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 长轮询请求的超时，超时后重新挂起
+const LONG_POLL_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// 长轮询失败后的重试间隔
+const LONG_POLL_RETRY: Duration = Duration::from_secs(5);
+
+/// 保留最近下发的工作数，提交份额时按作业 ID 取回原始 `data`
+const ISSUED_HISTORY: usize = 64;
+
+/// `data` 的字节数
+const DATA_LEN: usize = 128;
+
+/// `getwork` 返回的工作
+#[derive(Debug, Clone, Deserialize)]
+struct GetworkData {
+    data: String,
+    /// 旧的服务端可能不返回目标，此时按难度 1
+    target: Option<String>,
+}
+
+/// 一次 RPC 调用的结果
+#[derive(Debug)]
+struct RpcResponse {
+    result: Value,
+    /// `X-Long-Polling` 响应头
+    long_poll: Option<String>,
+    /// `X-Reject-Reason` 响应头
+    reject_reason: Option<String>,
+}
+
+/// 发送 JSON-RPC 请求需要的连接信息，长轮询任务持有一份副本
+#[derive(Clone)]
+struct RpcEndpoint {
+    http: reqwest::Client,
+    url: String,
+    username: String,
+    password: String,
+    next_id: Arc<AtomicU64>,
+}
+
+impl RpcEndpoint {
+    async fn call(&self, url: &str, params: Vec<Value>, timeout: Duration) -> Result<RpcResponse, PoolError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self.http.post(url)
+            .basic_auth(&self.username, Some(&self.password))
+            .header("X-Mining-Extensions", "longpoll")
+            .timeout(timeout)
+            .json(&json!({ "method": "getwork", "params": params, "id": id }))
+            .send()
+            .await
+            .map_err(|e| if e.is_timeout() {
+                PoolError::Timeout { url: self.url.clone() }
+            } else {
+                PoolError::ConnectionFailed { url: self.url.clone(), error: e.to_string() }
+            })?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED || response.status() == reqwest::StatusCode::FORBIDDEN {
+            return Err(PoolError::AuthenticationFailed { url: self.url.clone() });
+        }
+        let header = |name: &str| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let long_poll = header("X-Long-Polling");
+        let reject_reason = header("X-Reject-Reason");
+        let status = response.status();
+
+        let body: Value = response.json().await.map_err(|e| PoolError::ProtocolError {
+            url: self.url.clone(),
+            error: format!("Invalid JSON-RPC response (HTTP {}): {}", status, e),
+        })?;
+        if let Some(error) = body.get("error").filter(|error| !error.is_null()) {
+            let message = error.get("message").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| error.to_string());
+            return Err(PoolError::ProtocolError { url: self.url.clone(), error: message });
+        }
+        Ok(RpcResponse {
+            result: body.get("result").cloned().unwrap_or(Value::Null),
+            long_poll,
+            reject_reason,
+        })
+    }
+}
+
+/// Getwork 客户端，接口与 `StratumClient` 相同
+pub struct GetworkClient {
+    endpoint: RpcEndpoint,
+    pool_id: u32,
+    algorithm: Algorithm,
+    network: Network,
+    connected: Arc<RwLock<bool>>,
+    long_poll_url: Arc<RwLock<Option<String>>>,
+    /// 长轮询收到的新区块工作
+    pushed: Arc<Mutex<Option<GetworkData>>>,
+    /// 最近下发的 (作业 ID, data)
+    issued: Arc<Mutex<VecDeque<(String, [u8; DATA_LEN])>>>,
+    difficulty: Arc<RwLock<f64>>,
+    next_job: AtomicU64,
+    pending: Arc<AtomicUsize>,
+    long_poll_handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl GetworkClient {
+    pub fn new(url: String, username: String, password: String, pool_id: u32) -> Result<Self, PoolError> {
+        match url::Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            _ => return Err(PoolError::InvalidUrl { url }),
+        }
+        let http = reqwest::Client::builder()
+            .user_agent(format!("cgminer-rs/{}", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| PoolError::ConnectionFailed { url: url.clone(), error: e.to_string() })?;
+        Ok(Self {
+            endpoint: RpcEndpoint { http, url, username, password, next_id: Arc::new(AtomicU64::new(1)) },
+            pool_id,
+            algorithm: Algorithm::default(),
+            network: Network::default(),
+            connected: Arc::new(RwLock::new(false)),
+            long_poll_url: Arc::new(RwLock::new(None)),
+            pushed: Arc::new(Mutex::new(None)),
+            issued: Arc::new(Mutex::new(VecDeque::with_capacity(ISSUED_HISTORY))),
+            difficulty: Arc::new(RwLock::new(1.0)),
+            next_job: AtomicU64::new(1),
+            pending: Arc::new(AtomicUsize::new(0)),
+            long_poll_handle: Mutex::new(None),
+        })
+    }
+
+    /// 设置矿池的挖矿算法
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    /// 设置矿池所在的比特币网络
+    pub fn set_network(&mut self, network: Network) {
+        self.network = network;
+    }
+
+    /// 取一次工作确认矿池可用，矿池支持长轮询时启动长轮询任务
+    pub async fn connect(&mut self) -> Result<(), PoolError> {
+        info!("Connecting to getwork pool {} ({})", self.pool_id, self.endpoint.url);
+        let response = self.endpoint.call(&self.endpoint.url, vec![], RPC_TIMEOUT).await?;
+        let data: GetworkData = self.parse_work(response.result)?;
+        *self.pushed.lock().await = Some(data);
+        *self.connected.write().await = true;
+
+        if let Some(url) = response.long_poll.as_deref().and_then(|header| resolve_long_poll_url(&self.endpoint.url, header)) {
+            info!("Pool {} supports long polling at {}", self.pool_id, url);
+            *self.long_poll_url.write().await = Some(url);
+            self.start_long_poll().await;
+        }
+        Ok(())
+    }
+
+    pub async fn disconnect(&mut self) -> Result<(), PoolError> {
+        if let Some(handle) = self.long_poll_handle.lock().await.take() {
+            handle.abort();
+        }
+        *self.connected.write().await = false;
+        *self.long_poll_url.write().await = None;
+        self.pushed.lock().await.take();
+        Ok(())
+    }
+
+    async fn start_long_poll(&self) {
+        let endpoint = self.endpoint.clone();
+        let long_poll_url = self.long_poll_url.clone();
+        let pushed = self.pushed.clone();
+        let connected = self.connected.clone();
+        let pool_id = self.pool_id;
+
+        let handle = tokio::spawn(async move {
+            while *connected.read().await {
+                let Some(url) = long_poll_url.read().await.clone() else {
+                    break;
+                };
+                match endpoint.call(&url, vec![], LONG_POLL_TIMEOUT).await {
+                    Ok(response) => match serde_json::from_value::<GetworkData>(response.result) {
+                        Ok(data) => {
+                            info!("Pool {} long poll: new block, restarting work", pool_id);
+                            *pushed.lock().await = Some(data);
+                        }
+                        Err(e) => warn!("Pool {} long poll returned invalid work: {}", pool_id, e),
+                    },
+                    Err(PoolError::Timeout { .. }) => {}
+                    Err(e) => {
+                        debug!("Pool {} long poll failed: {}", pool_id, e);
+                        tokio::time::sleep(LONG_POLL_RETRY).await;
+                    }
+                }
+            }
+        });
+
+        if let Some(previous) = self.long_poll_handle.lock().await.replace(handle) {
+            previous.abort();
+        }
+    }
+
+    fn parse_work(&self, result: Value) -> Result<GetworkData, PoolError> {
+        serde_json::from_value(result).map_err(|e| PoolError::ProtocolError {
+            url: self.endpoint.url.clone(),
+            error: format!("Invalid getwork result: {}", e),
+        })
+    }
+
+    /// 获取工作：优先使用长轮询推送的新区块工作，否则请求一次 getwork
+    pub async fn get_work(&self) -> Result<Work, PoolError> {
+        if !self.is_connected().await {
+            return Err(PoolError::ProtocolError {
+                url: self.endpoint.url.clone(),
+                error: "Not connected".to_string(),
+            });
+        }
+        let pushed = self.pushed.lock().await.take();
+        let data = match pushed {
+            Some(data) => data,
+            None => {
+                let response = self.endpoint.call(&self.endpoint.url, vec![], RPC_TIMEOUT).await?;
+                self.parse_work(response.result)?
+            }
+        };
+
+        let job_id = format!("gw{}", self.next_job.fetch_add(1, Ordering::Relaxed));
+        let (work, raw) = work_from_getwork(&job_id, &data, self.algorithm, self.network)
+            .map_err(|error| PoolError::ProtocolError { url: self.endpoint.url.clone(), error })?;
+        *self.difficulty.write().await = work.difficulty;
+
+        let mut issued = self.issued.lock().await;
+        if issued.len() >= ISSUED_HISTORY {
+            issued.pop_front();
+        }
+        issued.push_back((job_id, raw));
+        Ok(work)
+    }
+
+    pub async fn submit_share(&self, share: &Share) -> Result<bool, PoolError> {
+        self.submit_shares(std::slice::from_ref(share)).await
+            .pop()
+            .unwrap_or(Err(PoolError::NoPoolsAvailable))
+    }
+
+    /// 并发提交多个份额，结果与 `shares` 顺序一一对应
+    pub async fn submit_shares(&self, shares: &[Share]) -> Vec<Result<bool, PoolError>> {
+        futures::future::join_all(shares.iter().map(|share| self.submit_one(share))).await
+    }
+
+    async fn submit_one(&self, share: &Share) -> Result<bool, PoolError> {
+        let raw = self.issued.lock().await.iter()
+            .rev()
+            .find(|(job_id, _)| *job_id == share.job_id)
+            .map(|(_, raw)| *raw)
+            .ok_or_else(|| PoolError::ShareRejected { reason: format!("unknown getwork job {}", share.job_id) })?;
+
+        debug!("Pool {} submitting getwork share from device {}: job_id={}, nonce={:08x}",
+               self.pool_id, share.device_id, share.job_id, share.nonce);
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        let response = self.endpoint.call(&self.endpoint.url, vec![json!(submit_data(&raw, share.nonce))], RPC_TIMEOUT).await;
+        self.pending.fetch_sub(1, Ordering::AcqRel);
+
+        let response = response?;
+        let accepted = response.result.as_bool().unwrap_or(false);
+        if accepted {
+            info!("Accepted share from device {}", share.device_id);
+        } else {
+            let reason = response.reject_reason.unwrap_or_else(|| "no reason given".to_string());
+            info!("Rejected share from device {}: {}", share.device_id, reason);
+        }
+        Ok(accepted)
+    }
+
+    pub async fn is_connected(&self) -> bool {
+        *self.connected.read().await
+    }
+
+    /// 已发出但还没收到响应的份额提交数
+    pub async fn pending_request_count(&self) -> usize {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// getwork 没有心跳请求，连接状态以请求结果为准
+    pub async fn ping(&self) -> Result<(), PoolError> {
+        Ok(())
+    }
+
+    /// 最近一次下发工作的难度
+    pub async fn get_current_difficulty(&self) -> f64 {
+        *self.difficulty.read().await
+    }
+}
+
+/// 长轮询地址可以是绝对 URL，也可以是相对于矿池地址的路径
+fn resolve_long_poll_url(base: &str, header: &str) -> Option<String> {
+    let base = url::Url::parse(base).ok()?;
+    base.join(header.trim()).ok().map(String::from)
+}
+
+/// 按 32 位字交换字节序 (getwork `data` 与区块头之间的转换)
+fn swap_words(bytes: &mut [u8]) {
+    for word in bytes.chunks_exact_mut(4) {
+        word.reverse();
+    }
+}
+
+/// 把 getwork 响应转换成工作，同时返回原始 `data` 用于提交
+fn work_from_getwork(job_id: &str, data: &GetworkData, algorithm: Algorithm, network: Network) -> Result<(Work, [u8; DATA_LEN]), String> {
+    let bytes = hex::decode(data.data.trim()).map_err(|e| format!("Invalid getwork data: {}", e))?;
+    let raw: [u8; DATA_LEN] = bytes.get(..DATA_LEN)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("getwork data must be at least {} bytes, got {}", DATA_LEN, bytes.len()))?;
+
+    let mut header = [0u8; 80];
+    header.copy_from_slice(&raw[..80]);
+    swap_words(&mut header);
+
+    let target = match &data.target {
+        Some(target) => hex::decode(target.trim()).ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes.as_slice()).ok())
+            .ok_or_else(|| format!("Invalid getwork target: {}", target))?,
+        None => algorithm.target_for_difficulty(network, 1.0),
+    };
+    let difficulty = algorithm.hash_difficulty(network, &target).unwrap_or(1.0);
+
+    let mut work = Work::new(job_id.to_string(), target, header, difficulty);
+    work.ntime = u32::from_le_bytes([header[68], header[69], header[70], header[71]]);
+    midstate::refresh(&mut work, algorithm);
+    Ok((work, raw))
+}
+
+/// 填入 nonce 后的 `data` (hex)。区块头中的 nonce 是小端序，交换字节序后在 `data` 中是大端序
+fn submit_data(raw: &[u8; DATA_LEN], nonce: u32) -> String {
+    let mut data = *raw;
+    data[76..80].copy_from_slice(&nonce.to_be_bytes());
+    hex::encode(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 区块 #1 的 getwork data (带 SHA256 填充)
+    const BLOCK_1_DATA: &str = concat!(
+        "01000000", "6fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000",
+        "982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e",
+        "61bc6649", "ffff001d", "01e36299",
+        "800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000280",
+    );
+
+    fn block_1_getwork() -> GetworkData {
+        // 字节序交换后才是 getwork 的格式
+        let mut raw = hex::decode(BLOCK_1_DATA).unwrap();
+        swap_words(&mut raw);
+        GetworkData { data: hex::encode(raw), target: None }
+    }
+
+    #[test]
+    fn test_work_from_getwork() {
+        let (work, raw) = work_from_getwork("gw1", &block_1_getwork(), Algorithm::Sha256d, Network::Mainnet).unwrap();
+        assert_eq!(hex::encode(&work.header[..]), &BLOCK_1_DATA[..160]);
+        assert_eq!(work.ntime, 0x4966bc61);
+        assert_eq!(work.difficulty, 1.0);
+        assert_eq!(work.target, Algorithm::Sha256d.target_for_difficulty(Network::Mainnet, 1.0));
+        assert_eq!(Some(work.midstate), midstate::sha256_midstate(&work.header));
+
+        // 区块 #1 的真实 nonce 填回 data 后与原始数据一致
+        assert_eq!(submit_data(&raw, 0x9962e301), hex::encode(raw));
+        assert_ne!(submit_data(&raw, 1), hex::encode(raw));
+    }
+
+    #[test]
+    fn test_getwork_target_and_errors() {
+        let mut data = block_1_getwork();
+        let target = Algorithm::Sha256d.target_for_difficulty(Network::Mainnet, 16.0);
+        data.target = Some(hex::encode(target));
+        let (work, _) = work_from_getwork("gw2", &data, Algorithm::Sha256d, Network::Mainnet).unwrap();
+        assert_eq!(work.target, target);
+        assert!((work.difficulty - 16.0).abs() < 1e-6);
+
+        data.target = Some("zz".to_string());
+        assert!(work_from_getwork("gw3", &data, Algorithm::Sha256d, Network::Mainnet).is_err());
+        let mut data = block_1_getwork();
+        data.data.truncate(100);
+        assert!(work_from_getwork("gw4", &data, Algorithm::Sha256d, Network::Mainnet).is_err());
+    }
+
+    #[test]
+    fn test_long_poll_url() {
+        assert_eq!(resolve_long_poll_url("http://pool:8332/", "/LP").as_deref(), Some("http://pool:8332/LP"));
+        assert_eq!(resolve_long_poll_url("http://pool:8332/", "http://lp.pool:9000/lp").as_deref(), Some("http://lp.pool:9000/lp"));
+        assert!(GetworkClient::new("stratum+tcp://pool:3333".to_string(), "u".to_string(), "p".to_string(), 0).is_err());
+    }
+}
@@ -1,8 +1,14 @@
 use crate::config::PoolConfig;
 use crate::error::PoolError;
-use crate::pool::{Pool, PoolStatus, Share, PoolStats, PoolEvent};
-use crate::pool::stratum::StratumClient;
+use crate::pool::{Pool, PoolStatus, Share, PoolStats, PoolDeviceStats, PoolEvent};
+use crate::pool::client::PoolClient;
+use crate::pool::share_audit::{ShareAuditLog, ShareAuditRecord};
+use crate::pool::share_verify::{ShareCheck, ShareVerifier};
+use crate::pool::switcher::pool_profitability;
 use crate::device::Work;
+use crate::mining::Algorithm;
+use crate::utils::RingBuffer;
+use crate::performance::task_watch;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -10,14 +16,22 @@ use tokio::sync::{RwLock, Mutex, mpsc, broadcast};
 use tokio::time::interval;
 use tracing::{info, warn, error, debug};
 
+/// 未配置 `pools.submit_pipeline_depth` 时每次流水线发送的份额数
+pub const DEFAULT_SUBMIT_PIPELINE_DEPTH: usize = 16;
+
+/// 保留最近下发的工作数，超过后更早工作的结果不再提交
+const ISSUED_WORK_HISTORY: usize = 64;
+
 /// 矿池管理器
 pub struct PoolManager {
     /// 矿池列表
     pools: Arc<RwLock<HashMap<u32, Arc<Mutex<Pool>>>>>,
-    /// Stratum 客户端
-    stratum_clients: Arc<RwLock<HashMap<u32, Arc<Mutex<StratumClient>>>>>,
+    /// 矿池客户端 (Stratum 或 getwork)
+    stratum_clients: Arc<RwLock<HashMap<u32, Arc<Mutex<PoolClient>>>>>,
     /// 矿池统计
     pool_stats: Arc<RwLock<HashMap<u32, PoolStats>>>,
+    /// 按 (矿池, 设备) 统计的份额
+    device_stats: Arc<RwLock<HashMap<(u32, u32), PoolDeviceStats>>>,
     /// 当前活跃矿池
     active_pool: Arc<RwLock<Option<u32>>>,
     /// 配置
@@ -31,6 +45,12 @@ pub struct PoolManager {
     heartbeat_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     /// 运行状态
     running: Arc<RwLock<bool>>,
+    /// 份额审计日志
+    share_audit: Option<Arc<ShareAuditLog>>,
+    /// 提交前的本地份额验证
+    share_verifier: Option<Arc<ShareVerifier>>,
+    /// 最近下发的工作，提交份额时按结果的 work_id 取回作业 ID 和 ntime
+    issued_work: Arc<RwLock<RingBuffer<Arc<Work>>>>,
 }
 
 impl PoolManager {
@@ -54,15 +74,10 @@ impl PoolManager {
                 pool_info.enabled,
             );
 
-            // 创建 Stratum 客户端
-            let stratum_client = StratumClient::new(
-                pool_info.url.clone(),
-                pool_info.username.clone(),
-                pool_info.password.clone(),
-                pool_id,
-                false, // 默认不启用详细日志
-                pool_info.proxy.clone(), // 传递代理配置
-            ).await?;
+            // 按协议创建客户端
+            let mut stratum_client = PoolClient::for_pool(pool_id, pool_info).await?;
+            stratum_client.set_network(config.network);
+            stratum_client.set_extranonce2_partitions(config.extranonce2_partitions);
 
             pools.insert(pool_id, Arc::new(Mutex::new(pool)));
             stratum_clients.insert(pool_id, Arc::new(Mutex::new(stratum_client)));
@@ -77,6 +92,7 @@ impl PoolManager {
             pools: Arc::new(RwLock::new(pools)),
             stratum_clients: Arc::new(RwLock::new(stratum_clients)),
             pool_stats: Arc::new(RwLock::new(pool_stats)),
+            device_stats: Arc::new(RwLock::new(HashMap::new())),
             active_pool: Arc::new(RwLock::new(None)),
             config,
 
@@ -84,9 +100,22 @@ impl PoolManager {
             connection_handle: Arc::new(Mutex::new(None)),
             heartbeat_handle: Arc::new(Mutex::new(None)),
             running: Arc::new(RwLock::new(false)),
+            share_audit: None,
+            share_verifier: None,
+            issued_work: Arc::new(RwLock::new(RingBuffer::new(ISSUED_WORK_HISTORY))),
         })
     }
 
+    /// 设置份额审计日志，之后每个提交的份额及矿池响应都会被记录
+    pub fn set_share_audit(&mut self, audit: Arc<ShareAuditLog>) {
+        self.share_audit = Some(audit);
+    }
+
+    /// 设置份额验证器，之后 `verify_mining_results` 按下发的工作重新计算结果的哈希
+    pub fn set_share_verifier(&mut self, verifier: Arc<ShareVerifier>) {
+        self.share_verifier = Some(verifier);
+    }
+
     /// 启动矿池管理器
     pub async fn start(&self) -> Result<(), PoolError> {
         info!("Starting pool manager");
@@ -162,7 +191,7 @@ impl PoolManager {
     async fn connect_failover_pools(
         &self,
         pools: &HashMap<u32, Arc<Mutex<Pool>>>,
-        stratum_clients: &HashMap<u32, Arc<Mutex<StratumClient>>>,
+        stratum_clients: &HashMap<u32, Arc<Mutex<PoolClient>>>,
     ) -> Result<(), PoolError> {
         // 按优先级排序，只包含启用的矿池
         let mut pool_priorities: Vec<(u32, u8)> = Vec::new();
@@ -205,26 +234,35 @@ impl PoolManager {
     async fn connect_all_enabled_pools(
         &self,
         pools: &HashMap<u32, Arc<Mutex<Pool>>>,
-        stratum_clients: &HashMap<u32, Arc<Mutex<StratumClient>>>,
+        stratum_clients: &HashMap<u32, Arc<Mutex<PoolClient>>>,
     ) -> Result<(), PoolError> {
-        let mut connected_count = 0;
-
+        let mut enabled_pools = Vec::new();
         for (pool_id, pool) in pools.iter() {
-            let enabled = pool.lock().await.enabled;
-            if enabled {
+            if pool.lock().await.enabled {
                 if let Some(stratum_client) = stratum_clients.get(pool_id) {
-                    match self.connect_single_pool(*pool_id, stratum_client.clone()).await {
-                        Ok(_) => {
-                            connected_count += 1;
-                            if self.active_pool.read().await.is_none() {
-                                *self.active_pool.write().await = Some(*pool_id);
-                            }
-                        }
-                        Err(e) => {
-                            warn!("Failed to connect to pool {}: {}", pool_id, e);
-                        }
+                    enabled_pools.push((*pool_id, stratum_client.clone()));
+                }
+            }
+        }
+
+        // 各矿池的连接互不依赖，并发连接，启动耗时取决于最慢的矿池而不是各矿池之和
+        let results = futures::future::join_all(enabled_pools.into_iter().map(|(pool_id, stratum_client)| async move {
+            (pool_id, self.connect_single_pool(pool_id, stratum_client).await)
+        })).await;
+
+        let mut connected_count = 0;
+        for (pool_id, result) in results {
+            match result {
+                Ok(_) => {
+                    connected_count += 1;
+                    let mut active_pool = self.active_pool.write().await;
+                    if active_pool.is_none() {
+                        *active_pool = Some(pool_id);
                     }
                 }
+                Err(e) => {
+                    warn!("Failed to connect to pool {}: {}", pool_id, e);
+                }
             }
         }
 
@@ -240,7 +278,7 @@ impl PoolManager {
     async fn connect_single_pool(
         &self,
         pool_id: u32,
-        stratum_client: Arc<Mutex<StratumClient>>,
+        stratum_client: Arc<Mutex<PoolClient>>,
     ) -> Result<(), PoolError> {
         info!("Connecting to pool {}", pool_id);
 
@@ -317,7 +355,7 @@ impl PoolManager {
     async fn disconnect_single_pool(
         &self,
         pool_id: u32,
-        stratum_client: Arc<Mutex<StratumClient>>,
+        stratum_client: Arc<Mutex<PoolClient>>,
     ) -> Result<(), PoolError> {
         info!("Disconnecting from pool {}", pool_id);
 
@@ -373,21 +411,11 @@ impl PoolManager {
                 }).await;
 
                 // 提交份额
-                match client.submit_share(&share).await {
-                    Ok(accepted) => {
-                        // 更新矿池统计
-                        {
-                            let pools = self.pools.read().await;
-                            if let Some(pool) = pools.get(&pool_id) {
-                                let mut pool = pool.lock().await;
-                                if accepted {
-                                    pool.record_accepted_share(share.difficulty);
-                                } else {
-                                    pool.record_rejected_share();
-                                }
-                            }
-                        }
+                let outcome = client.submit_share(&share).await;
+                self.record_share_outcome(pool_id, &share, &outcome).await;
 
+                match outcome {
+                    Ok(accepted) => {
                         // 发送份额响应事件
                         self.send_event(PoolEvent::ShareResponse {
                             pool_id,
@@ -414,63 +442,135 @@ impl PoolManager {
 
     /// 从挖矿结果提交份额
     pub async fn submit_mining_result(&self, mining_result: &cgminer_core::types::MiningResult) -> Result<bool, PoolError> {
-        let active_pool_id = self.active_pool.read().await;
+        self.submit_mining_results(std::slice::from_ref(mining_result)).await
+            .pop()
+            .unwrap_or(Err(PoolError::NoPoolsAvailable))
+    }
 
-        if let Some(pool_id) = *active_pool_id {
-            // 创建临时的Share对象用于提交
-            // 注意：这里我们缺少一些必要的信息（如job_id和ntime），
-            // 在实际实现中，这些信息应该从工作管理器或其他地方获取
-            let share = Share {
-                id: uuid::Uuid::new_v4(),
-                pool_id,
-                work_id: mining_result.work_id,
-                device_id: mining_result.device_id,
-                job_id: format!("unknown_job_{}", mining_result.work_id), // 临时job_id
-                extra_nonce2: hex::encode(&mining_result.extranonce2),
-                nonce: mining_result.nonce,
-                ntime: 0, // 临时ntime，应该从工作数据获取
-                timestamp: mining_result.timestamp,
-                difficulty: mining_result.share_difficulty,
-                status: crate::pool::ShareStatus::Pending,
-            };
+    /// 批量提交挖矿结果：每 `submit_pipeline_depth` 个份额流水线发送一次，不再逐个等待矿池响应。
+    /// 结果与 `mining_results` 顺序一一对应。
+    pub async fn submit_mining_results(&self, mining_results: &[cgminer_core::types::MiningResult]) -> Vec<Result<bool, PoolError>> {
+        let active_pool_id = *self.active_pool.read().await;
+        let Some(pool_id) = active_pool_id else {
+            return mining_results.iter().map(|_| Err(PoolError::NoPoolsAvailable)).collect();
+        };
+
+        // 作业 ID 和 ntime 取自下发的工作；找不到工作的结果 (工作早已过期) 不再提交
+        let mut outcomes: Vec<Option<Result<bool, PoolError>>> = Vec::with_capacity(mining_results.len());
+        let mut shares = Vec::with_capacity(mining_results.len());
+        {
+            let issued_work = self.issued_work.read().await;
+            for mining_result in mining_results {
+                match issued_work.iter().rev().find(|work| work.id == mining_result.work_id) {
+                    Some(work) => {
+                        shares.push(Share::for_work(pool_id, work, mining_result));
+                        outcomes.push(None);
+                    }
+                    None => {
+                        warn!("Dropping share from device {}: work {} is no longer known", mining_result.device_id, mining_result.work_id);
+                        outcomes.push(Some(Err(PoolError::ShareRejected {
+                            reason: format!("unknown work {}", mining_result.work_id),
+                        })));
+                    }
+                }
+            }
+        }
 
-            let stratum_clients = self.stratum_clients.read().await;
-            if let Some(stratum_client) = stratum_clients.get(&pool_id) {
-                let client = stratum_client.lock().await;
+        let stratum_client = match self.stratum_clients.read().await.get(&pool_id) {
+            Some(stratum_client) => stratum_client.clone(),
+            None => return mining_results.iter().map(|_| Err(PoolError::NoPoolsAvailable)).collect(),
+        };
+        let client = stratum_client.lock().await;
+
+        let mut submitted = Vec::with_capacity(shares.len());
+        for batch in shares.chunks(self.submit_pipeline_depth()) {
+            for (share, outcome) in batch.iter().zip(client.submit_shares(batch).await) {
+                self.record_share_outcome(pool_id, share, &outcome).await;
+                if let Err(e) = &outcome {
+                    error!("Failed to submit mining result to pool {}: {}", pool_id, e);
+                }
+                submitted.push(outcome);
+            }
+        }
 
-                // 提交份额并返回是否被接受
-                match client.submit_share(&share).await {
-                    Ok(accepted) => {
-                        // 更新矿池统计
-                        {
-                            let pools = self.pools.read().await;
-                            if let Some(pool) = pools.get(&pool_id) {
-                                let mut pool = pool.lock().await;
-                                if accepted {
-                                    pool.record_accepted_share(share.difficulty);
-                                } else {
-                                    pool.record_rejected_share();
-                                }
-                            }
-                        }
+        // 按输入顺序合并未提交的结果和提交结果
+        let mut submitted = submitted.into_iter();
+        outcomes.into_iter()
+            .map(|outcome| outcome.unwrap_or_else(|| submitted.next().unwrap_or(Err(PoolError::NoPoolsAvailable))))
+            .collect()
+    }
 
-                        Ok(accepted)
-                    }
-                    Err(e) => {
-                        error!("Failed to submit mining result to pool {}: {}", pool_id, e);
-                        Err(e)
-                    }
+    /// 提交前验证挖矿结果，结论与 `mining_results` 顺序一一对应；
+    /// 未设置验证器或找不到工作时为 `Unchecked`
+    pub async fn verify_mining_results(&self, mining_results: &[cgminer_core::types::MiningResult]) -> Vec<ShareCheck> {
+        let Some(verifier) = &self.share_verifier else {
+            return vec![ShareCheck::Unchecked; mining_results.len()];
+        };
+
+        let algorithm = self.active_algorithm().await;
+        let issued_work = self.issued_work.read().await;
+        mining_results.iter().map(|mining_result| {
+            match issued_work.iter().rev().find(|work| work.id == mining_result.work_id) {
+                Some(work) => verifier.check(work, mining_result, algorithm),
+                None => ShareCheck::Unchecked,
+            }
+        }).collect()
+    }
+
+    /// 每次流水线发送的份额数
+    fn submit_pipeline_depth(&self) -> usize {
+        match self.config.submit_pipeline_depth {
+            0 => DEFAULT_SUBMIT_PIPELINE_DEPTH,
+            depth => depth,
+        }
+    }
+
+    /// 记录份额提交结果：审计日志、矿池统计和按设备的矿池统计
+    async fn record_share_outcome(&self, pool_id: u32, share: &Share, outcome: &Result<bool, PoolError>) {
+        self.audit_share(pool_id, share, outcome).await;
+
+        self.device_stats.write().await
+            .entry((pool_id, share.device_id))
+            .or_insert_with(|| PoolDeviceStats::new(pool_id, share.device_id))
+            .record(share, outcome);
+
+        if let Ok(accepted) = outcome {
+            let pools = self.pools.read().await;
+            if let Some(pool) = pools.get(&pool_id) {
+                let mut pool = pool.lock().await;
+                if *accepted {
+                    pool.record_accepted_share(share.difficulty);
+                } else {
+                    pool.record_rejected_share();
                 }
-            } else {
-                Err(PoolError::NoPoolsAvailable)
             }
-        } else {
-            Err(PoolError::NoPoolsAvailable)
         }
     }
 
+    /// 写入份额审计记录
+    async fn audit_share(&self, pool_id: u32, share: &Share, outcome: &Result<bool, PoolError>) {
+        let audit = match &self.share_audit {
+            Some(audit) => audit,
+            None => return,
+        };
+
+        let (url, worker) = match self.pools.read().await.get(&pool_id) {
+            Some(pool) => {
+                let pool = pool.lock().await;
+                (pool.url.clone(), pool.user.clone())
+            }
+            None => (String::new(), String::new()),
+        };
+        audit.record(&ShareAuditRecord::new(share, &url, &worker, outcome));
+    }
+
     /// 获取工作
-    pub async fn get_work(&self) -> Result<Work, PoolError> {
+    pub async fn get_work(&self) -> Result<Arc<Work>, PoolError> {
+        self.get_work_for_device(0).await
+    }
+
+    /// 为设备获取工作，Stratum 工作的 extranonce2 取自该设备的子区间
+    pub async fn get_work_for_device(&self, device_id: u32) -> Result<Arc<Work>, PoolError> {
         let active_pool_id = self.active_pool.read().await;
 
         if let Some(pool_id) = *active_pool_id {
@@ -478,8 +578,10 @@ impl PoolManager {
             if let Some(stratum_client) = stratum_clients.get(&pool_id) {
                 let client = stratum_client.lock().await;
 
-                match client.get_work().await {
+                match client.get_work_for_device(device_id).await {
                     Ok(work) => {
+                        let work = Arc::new(work);
+                        self.issued_work.write().await.push(work.clone());
                         // 发送工作接收事件
                         self.send_event(PoolEvent::WorkReceived {
                             pool_id,
@@ -502,6 +604,66 @@ impl PoolManager {
         }
     }
 
+    /// 切换活跃矿池：目标矿池未连接时先连接，故障转移策略下随后断开原矿池。
+    /// 原矿池下发的工作不再提交，之后收到的结果按工作未知丢弃
+    pub async fn switch_to_pool(&self, pool_id: u32) -> Result<(), PoolError> {
+        let previous = *self.active_pool.read().await;
+        if previous == Some(pool_id) {
+            return Ok(());
+        }
+        let client = self.stratum_clients.read().await.get(&pool_id).cloned()
+            .ok_or(PoolError::NoPoolsAvailable)?;
+        let connected = match self.pools.read().await.get(&pool_id) {
+            Some(pool) => pool.lock().await.is_connected(),
+            None => return Err(PoolError::NoPoolsAvailable),
+        };
+        if !connected {
+            self.connect_single_pool(pool_id, client).await?;
+        }
+
+        *self.active_pool.write().await = Some(pool_id);
+        *self.issued_work.write().await = RingBuffer::new(ISSUED_WORK_HISTORY);
+        info!("Switched active pool from {:?} to {}", previous, pool_id);
+
+        if let (Some(previous), crate::config::PoolStrategy::Failover) = (previous, &self.config.strategy) {
+            self.wait_for_pending_submissions().await;
+            let client = self.stratum_clients.read().await.get(&previous).cloned();
+            if let Some(client) = client {
+                if let Err(e) = self.disconnect_single_pool(previous, client).await {
+                    warn!("Failed to disconnect from pool {}: {}", previous, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 启用且与活跃矿池算法相同的矿池扣除费率后的相对收益，`quotes` 为外部收益数据
+    pub async fn pool_profitability(&self, quotes: Option<&HashMap<String, f64>>) -> HashMap<u32, f64> {
+        pool_profitability(&self.config.pools, self.active_algorithm().await, quotes)
+    }
+
+    /// 当前活跃矿池的挖矿算法，没有活跃矿池时为 SHA256d
+    pub async fn active_algorithm(&self) -> Algorithm {
+        let active_pool_id = *self.active_pool.read().await;
+        active_pool_id
+            .and_then(|pool_id| self.config.pools.get(pool_id as usize))
+            .map(|pool_info| pool_info.algorithm)
+            .unwrap_or_default()
+    }
+
+    /// 等待已发出的份额提交收到矿池响应 (停机时断开连接之前调用)。
+    /// 正在提交的批次持有客户端的锁，先拿到锁，再等待剩余的在途请求
+    pub async fn wait_for_pending_submissions(&self) {
+        let clients: Vec<_> = self.stratum_clients.read().await.values().cloned().collect();
+        for client in clients {
+            let client = client.lock().await;
+            // 连接已断开时响应不会再到达
+            while client.is_connected().await && client.pending_request_count().await > 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }
+    }
+
     /// 获取连接的矿池数量
     pub async fn get_connected_pool_count(&self) -> u32 {
         let pools = self.pools.read().await;
@@ -522,6 +684,13 @@ impl PoolManager {
         stats.get(&pool_id).cloned()
     }
 
+    /// 获取按 (矿池, 设备) 统计的份额，按矿池、设备排序
+    pub async fn get_pool_device_stats(&self) -> Vec<PoolDeviceStats> {
+        let mut stats: Vec<PoolDeviceStats> = self.device_stats.read().await.values().cloned().collect();
+        stats.sort_by_key(|s| (s.pool_id, s.device_id));
+        stats
+    }
+
     /// 订阅事件
     pub fn subscribe_events(&self) -> broadcast::Receiver<PoolEvent> {
         self.event_sender.subscribe()
@@ -542,7 +711,7 @@ impl PoolManager {
         let _active_pool = self.active_pool.clone();
         let config = self.config.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = tokio::spawn(task_watch::track("pool-connection", async move {
             let mut interval = interval(Duration::from_secs(config.retry_interval));
 
             while *running.read().await {
@@ -551,7 +720,7 @@ impl PoolManager {
                 // 检查连接状态并重连
                 // 这里可以添加连接检查和重连逻辑
             }
-        });
+        }));
 
         *self.connection_handle.lock().await = Some(handle);
         Ok(())
@@ -563,7 +732,7 @@ impl PoolManager {
         let pools = self.pools.clone();
         let stratum_clients = self.stratum_clients.clone();
 
-        let handle = tokio::spawn(async move {
+        let handle = tokio::spawn(task_watch::track("pool-heartbeat", async move {
             let mut interval = interval(Duration::from_secs(30));
 
             while *running.read().await {
@@ -641,7 +810,7 @@ impl PoolManager {
                     }
                 }
             }
-        });
+        }));
 
         *self.heartbeat_handle.lock().await = Some(handle);
         Ok(())
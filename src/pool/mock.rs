@@ -0,0 +1,461 @@
+//! 模拟 Stratum 矿池 (`--mock-pool [port]`)
+//!
+//! 用于集成测试和演示：不需要外网和真实矿池，作业和 extranonce1 都是确定的，
+//! 相同的操作序列在每次运行中得到相同的结果。
+//!
+//! - 作业：第 n 个作业的 ID 为 `n` 的十六进制，ntime 为固定值加 n，其余字段取自 Stratum 协议文档的示例作业
+//! - extranonce1：第 n 个连接为 `n` 的 8 位十六进制
+//! - 份额：作业 ID 已下发过且 (作业, extranonce2, ntime, nonce) 未提交过即接受，不校验哈希；
+//!   未知作业返回错误 21，重复份额返回错误 22
+//! - 故障注入：[`MockPool::inject`] 对所有连接断开、发送非法 JSON 或下发离谱的难度
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// 命令行 `--mock-pool` 不带端口时使用的端口
+pub const DEFAULT_PORT: u16 = 3333;
+
+/// 示例作业 (Stratum 协议文档)
+const PREVIOUS_HASH: &str = "4d16b6f85af6e2198f44ae2a6de67f78487ae5611b77c6c0440b921e00000000";
+const COINBASE1: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff20020862062f503253482f04b8864e5008";
+const COINBASE2: &str = "072f736c7573682f000000000100f2052a010000001976a914d23fcdf86f7e756a64a7a9688ef9903327048ed988ac00000000";
+const VERSION: &str = "00000002";
+const NBITS: &str = "1c2ac4af";
+const BASE_NTIME: u32 = 0x504e_86b9;
+
+/// 模拟矿池选项
+#[derive(Debug, Clone)]
+pub struct MockPoolOptions {
+    /// 授权后下发的难度
+    pub difficulty: f64,
+    pub extranonce2_size: usize,
+    /// 定期下发新作业 (clean_jobs)，None 时只在 [`MockPool::new_job`] 时下发
+    pub job_interval: Option<Duration>,
+    /// 是否接受 `mining.authorize`，false 时拒绝所有矿工 (测试故障转移)
+    pub authorize: bool,
+}
+
+impl Default for MockPoolOptions {
+    fn default() -> Self {
+        Self {
+            difficulty: 1.0,
+            extranonce2_size: 4,
+            job_interval: None,
+            authorize: true,
+        }
+    }
+}
+
+/// 注入的故障，作用于注入时所有已建立的连接
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// 关闭连接
+    Disconnect,
+    /// 发送一行无法解析的 JSON
+    MalformedJson,
+    /// 下发指定的难度 (如 0、负数或 1e300)，非有限值按 JSON 编码为 null
+    AbsurdDifficulty(f64),
+}
+
+/// 模拟矿池计数
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MockPoolStats {
+    pub connections: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+    /// 当前作业序号
+    pub job: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Command {
+    NewJob,
+    Fault(Fault),
+}
+
+#[derive(Default)]
+struct Shared {
+    job: AtomicU64,
+    connections: AtomicU64,
+    accepted: AtomicU64,
+    rejected: AtomicU64,
+    /// 已接受的 (作业, extranonce1, extranonce2, ntime, nonce)
+    submitted: Mutex<HashSet<(String, String, String, String, String)>>,
+}
+
+/// 正在运行的模拟矿池，释放时停止监听并断开所有连接
+pub struct MockPool {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    commands: broadcast::Sender<Command>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockPool {
+    /// 在指定地址上启动 (端口 0 由系统分配)
+    pub async fn start(addr: impl ToSocketAddrs, options: MockPoolOptions) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared { job: AtomicU64::new(1), ..Default::default() });
+        let (commands, _) = broadcast::channel(64);
+
+        let mut tasks = Vec::new();
+        {
+            let shared = shared.clone();
+            let commands = commands.clone();
+            let options = options.clone();
+            tasks.push(tokio::spawn(async move {
+                loop {
+                    let (stream, peer) = match listener.accept().await {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            debug!("Mock pool accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let session = shared.connections.fetch_add(1, Ordering::Relaxed) + 1;
+                    debug!("Mock pool session {} from {}", session, peer);
+                    tokio::spawn(Session {
+                        extranonce1: format!("{:08x}", session),
+                        options: options.clone(),
+                        shared: shared.clone(),
+                        authorized: false,
+                    }.run(stream, commands.subscribe()));
+                }
+            }));
+        }
+        if let Some(job_interval) = options.job_interval {
+            let shared = shared.clone();
+            let commands = commands.clone();
+            tasks.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(job_interval);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    shared.job.fetch_add(1, Ordering::Relaxed);
+                    let _ = commands.send(Command::NewJob);
+                }
+            }));
+        }
+
+        info!("Mock pool listening on {}", addr);
+        Ok(Self { addr, shared, commands, tasks })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 连接地址；监听所有地址时使用回环地址
+    pub fn url(&self) -> String {
+        let host = if self.addr.ip().is_unspecified() {
+            if self.addr.is_ipv4() { "127.0.0.1".to_string() } else { "[::1]".to_string() }
+        } else if self.addr.is_ipv6() {
+            format!("[{}]", self.addr.ip())
+        } else {
+            self.addr.ip().to_string()
+        };
+        format!("stratum+tcp://{}:{}", host, self.addr.port())
+    }
+
+    /// 下发新作业 (clean_jobs)，返回作业序号
+    pub fn new_job(&self) -> u64 {
+        let job = self.shared.job.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = self.commands.send(Command::NewJob);
+        job
+    }
+
+    pub fn inject(&self, fault: Fault) {
+        info!("Mock pool injecting fault: {:?}", fault);
+        let _ = self.commands.send(Command::Fault(fault));
+    }
+
+    pub fn stats(&self) -> MockPoolStats {
+        MockPoolStats {
+            connections: self.shared.connections.load(Ordering::Relaxed),
+            accepted: self.shared.accepted.load(Ordering::Relaxed),
+            rejected: self.shared.rejected.load(Ordering::Relaxed),
+            job: self.shared.job.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for MockPool {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        // 被中止的任务不一定已经释放各自的发送端，显式通知会话断开
+        let _ = self.commands.send(Command::Fault(Fault::Disconnect));
+    }
+}
+
+/// 第 `job` 个作业的 `mining.notify` 参数
+fn job_params(job: u64, clean_jobs: bool) -> Value {
+    let ntime = BASE_NTIME.wrapping_add(job as u32);
+    json!([format!("{:x}", job), PREVIOUS_HASH, COINBASE1, COINBASE2, [], VERSION, NBITS, format!("{:08x}", ntime), clean_jobs])
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "id": null, "method": method, "params": params })
+}
+
+fn response(id: &Value, result: Value) -> Value {
+    json!({ "id": id, "result": result, "error": null })
+}
+
+fn error_response(id: &Value, code: i32, message: &str) -> Value {
+    json!({ "id": id, "result": null, "error": [code, message, null] })
+}
+
+struct Session {
+    extranonce1: String,
+    options: MockPoolOptions,
+    shared: Arc<Shared>,
+    authorized: bool,
+}
+
+impl Session {
+    async fn run(mut self, stream: TcpStream, mut commands: broadcast::Receiver<Command>) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        loop {
+            let outgoing: Vec<String> = tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) if line.trim().is_empty() => continue,
+                    Ok(Some(line)) => self.handle(&line).into_iter().map(|message| message.to_string()).collect(),
+                    _ => break,
+                },
+                command = commands.recv() => match command {
+                    Ok(Command::Fault(Fault::Disconnect)) | Err(broadcast::error::RecvError::Closed) => break,
+                    Ok(Command::Fault(Fault::MalformedJson)) => vec![r#"{"id": null, "method": "mining.notify", "params": ["#.to_string()],
+                    Ok(Command::Fault(Fault::AbsurdDifficulty(difficulty))) => {
+                        vec![notification("mining.set_difficulty", json!([difficulty])).to_string()]
+                    }
+                    Ok(Command::NewJob) if self.authorized => {
+                        let job = self.shared.job.load(Ordering::Relaxed);
+                        vec![notification("mining.notify", job_params(job, true)).to_string()]
+                    }
+                    Ok(Command::NewJob) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                },
+            };
+
+            let mut buffer = String::new();
+            for message in outgoing {
+                buffer.push_str(&message);
+                buffer.push('\n');
+            }
+            if writer.write_all(buffer.as_bytes()).await.is_err() || writer.flush().await.is_err() {
+                break;
+            }
+        }
+        debug!("Mock pool session {} closed", self.extranonce1);
+    }
+
+    /// 处理一条请求，返回要发送的消息 (响应及随后的通知)
+    fn handle(&mut self, line: &str) -> Vec<Value> {
+        let Ok(request) = serde_json::from_str::<Value>(line) else {
+            return vec![error_response(&Value::Null, 20, "Malformed JSON")];
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let params = request.get("params").and_then(Value::as_array).cloned().unwrap_or_default();
+
+        match request.get("method").and_then(Value::as_str).unwrap_or("") {
+            "mining.subscribe" => {
+                let subscriptions = json!([["mining.set_difficulty", self.extranonce1], ["mining.notify", self.extranonce1]]);
+                vec![response(&id, json!([subscriptions, self.extranonce1, self.options.extranonce2_size]))]
+            }
+            "mining.authorize" if !self.options.authorize => vec![response(&id, json!(false))],
+            "mining.authorize" => {
+                self.authorized = true;
+                let job = self.shared.job.load(Ordering::Relaxed);
+                vec![
+                    response(&id, json!(true)),
+                    notification("mining.set_difficulty", json!([self.options.difficulty])),
+                    notification("mining.notify", job_params(job, true)),
+                ]
+            }
+            "mining.ping" => vec![response(&id, json!("pong"))],
+            "mining.submit" => vec![self.submit(&id, &params)],
+            _ => vec![error_response(&id, 20, "Unsupported method")],
+        }
+    }
+
+    fn submit(&self, id: &Value, params: &[Value]) -> Value {
+        let field = |index: usize| params.get(index).and_then(Value::as_str).map(str::to_string);
+        let (Some(job_id), Some(extranonce2), Some(ntime), Some(nonce)) = (field(1), field(2), field(3), field(4)) else {
+            self.shared.rejected.fetch_add(1, Ordering::Relaxed);
+            return error_response(id, 20, "Invalid submit parameters");
+        };
+        if !self.authorized {
+            self.shared.rejected.fetch_add(1, Ordering::Relaxed);
+            return error_response(id, 24, "Unauthorized worker");
+        }
+
+        let current = self.shared.job.load(Ordering::Relaxed);
+        if !u64::from_str_radix(&job_id, 16).is_ok_and(|job| (1..=current).contains(&job)) {
+            self.shared.rejected.fetch_add(1, Ordering::Relaxed);
+            return error_response(id, 21, "Job not found");
+        }
+        let key = (job_id, self.extranonce1.clone(), extranonce2, ntime, nonce);
+        if !self.shared.submitted.lock().unwrap_or_else(|e| e.into_inner()).insert(key) {
+            self.shared.rejected.fetch_add(1, Ordering::Relaxed);
+            return error_response(id, 22, "Duplicate share");
+        }
+        self.shared.accepted.fetch_add(1, Ordering::Relaxed);
+        response(id, json!(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::error::PoolError;
+    use crate::pool::stratum::StratumClient;
+    use crate::pool::{PoolManager, Share, ShareStatus};
+
+    async fn connected_client(pool: &MockPool) -> StratumClient {
+        let mut client = StratumClient::new(pool.url(), "worker".to_string(), "x".to_string(), 0, false, None).await.unwrap();
+        client.connect().await.unwrap();
+        client
+    }
+
+    /// 等待客户端收到作业
+    async fn wait_for_job(client: &StratumClient, job_id: &str) -> crate::device::Work {
+        for _ in 0..100 {
+            if let Ok(work) = client.get_work().await {
+                if work.job_id == job_id {
+                    return work;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("job {} not received", job_id);
+    }
+
+    fn share(work: &crate::device::Work, nonce: u32) -> Share {
+        Share {
+            id: uuid::Uuid::new_v4(),
+            pool_id: 0,
+            work_id: work.id,
+            device_id: 0,
+            job_id: work.job_id.clone(),
+            extra_nonce2: hex::encode(&work.extranonce2),
+            nonce,
+            ntime: work.ntime,
+            timestamp: std::time::SystemTime::now(),
+            difficulty: work.difficulty,
+            status: ShareStatus::Pending,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_jobs_and_shares() {
+        let pool = MockPool::start("127.0.0.1:0", MockPoolOptions { difficulty: 8.0, ..Default::default() }).await.unwrap();
+        let client = connected_client(&pool).await;
+
+        let work = wait_for_job(&client, "1").await;
+        assert_eq!(work.ntime, BASE_NTIME + 1);
+        assert_eq!(work.difficulty, 8.0);
+        assert_eq!(client.get_extranonce_info().await, (Some("00000001".to_string()), 4));
+
+        assert!(client.submit_share(&share(&work, 7)).await.unwrap());
+        let duplicate = client.submit_share(&share(&work, 7)).await;
+        assert!(matches!(duplicate, Err(PoolError::ShareRejected { reason }) if reason == "Duplicate share"));
+        let unknown = client.submit_share(&Share { job_id: "ff".to_string(), ..share(&work, 8) }).await;
+        assert!(matches!(unknown, Err(PoolError::ShareRejected { reason }) if reason == "Job not found"));
+
+        assert_eq!(pool.new_job(), 2);
+        let work = wait_for_job(&client, "2").await;
+        assert_eq!(work.ntime, BASE_NTIME + 2);
+        assert_eq!(pool.stats(), MockPoolStats { connections: 1, accepted: 1, rejected: 2, job: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_fault_injection() {
+        let pool = MockPool::start("127.0.0.1:0", MockPoolOptions::default()).await.unwrap();
+        let client = connected_client(&pool).await;
+        wait_for_job(&client, "1").await;
+
+        // 同一连接上的消息按注入顺序发送，收到新作业时前面的故障消息已经处理完
+        pool.inject(Fault::MalformedJson);
+        for difficulty in [0.0, -1.0, 1e300] {
+            pool.inject(Fault::AbsurdDifficulty(difficulty));
+        }
+        pool.new_job();
+        let work = wait_for_job(&client, "2").await;
+
+        // 非法 JSON 被丢弃，离谱的难度不生效
+        assert!(client.is_connected().await);
+        client.ping().await.unwrap();
+        assert_eq!(client.get_current_difficulty().await, 1.0);
+        assert_eq!(work.difficulty, 1.0);
+
+        pool.inject(Fault::Disconnect);
+        for _ in 0..100 {
+            if !client.is_connected().await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!client.is_connected().await);
+    }
+
+    #[tokio::test]
+    async fn test_failover_skips_pool_rejecting_authorization() {
+        let primary = MockPool::start("127.0.0.1:0", MockPoolOptions { authorize: false, ..Default::default() }).await.unwrap();
+        let backup = MockPool::start("127.0.0.1:0", MockPoolOptions::default()).await.unwrap();
+
+        let mut config = Config::default().pools;
+        let template = config.pools[0].clone();
+        config.pools = [&primary, &backup].iter().enumerate().map(|(index, pool)| crate::config::PoolInfo {
+            url: pool.url(),
+            priority: index as u8,
+            enabled: true,
+            ..template.clone()
+        }).collect();
+
+        let manager = PoolManager::new(config).await.unwrap();
+        manager.connect_to_pools().await.unwrap();
+        assert_eq!(manager.get_active_pool_id().await, Some(1));
+        assert_eq!(primary.stats().connections, 1);
+        assert_eq!(backup.stats().connections, 1);
+    }
+
+    #[tokio::test]
+    async fn test_switch_to_pool_replaces_failover_connection() {
+        let primary = MockPool::start("127.0.0.1:0", MockPoolOptions::default()).await.unwrap();
+        let backup = MockPool::start("127.0.0.1:0", MockPoolOptions::default()).await.unwrap();
+
+        let mut config = Config::default().pools;
+        let template = config.pools[0].clone();
+        config.pools = [&primary, &backup].iter().enumerate().map(|(index, pool)| crate::config::PoolInfo {
+            url: pool.url(),
+            priority: index as u8,
+            enabled: true,
+            ..template.clone()
+        }).collect();
+
+        let manager = PoolManager::new(config).await.unwrap();
+        manager.connect_to_pools().await.unwrap();
+        assert_eq!(manager.get_active_pool_id().await, Some(0));
+        assert_eq!(backup.stats().connections, 0);
+
+        manager.switch_to_pool(1).await.unwrap();
+        assert_eq!(manager.get_active_pool_id().await, Some(1));
+        assert_eq!(backup.stats().connections, 1);
+        assert_eq!(manager.get_connected_pool_count().await, 1);
+    }
+}
@@ -4,11 +4,21 @@ pub mod connection;
 pub mod scheduler;
 pub mod switcher;
 pub mod proxy;
+pub mod share_audit;
+pub mod share_verify;
+pub mod client;
+pub mod getwork;
+pub mod mock;
+pub mod quirks;
+pub mod extranonce;
 
 use crate::error::PoolError;
 use crate::device::Work;
+use crate::logging::redact;
 use cgminer_core::types::MiningResult;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
@@ -16,7 +26,7 @@ pub use manager::PoolManager;
 
 
 /// 矿池信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Pool {
     pub id: u32,
     pub url: String,
@@ -35,6 +45,28 @@ pub struct Pool {
     pub ping: Option<Duration>,
 }
 
+impl std::fmt::Debug for Pool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pool")
+            .field("id", &self.id)
+            .field("url", &self.url)
+            .field("user", &self.user)
+            .field("password", &redact::mask(&self.password))
+            .field("priority", &self.priority)
+            .field("quota", &self.quota)
+            .field("enabled", &self.enabled)
+            .field("status", &self.status)
+            .field("connected_at", &self.connected_at)
+            .field("last_share_time", &self.last_share_time)
+            .field("accepted_shares", &self.accepted_shares)
+            .field("rejected_shares", &self.rejected_shares)
+            .field("stale_shares", &self.stale_shares)
+            .field("difficulty", &self.difficulty)
+            .field("ping", &self.ping)
+            .finish()
+    }
+}
+
 impl Pool {
     pub fn new(id: u32, url: String, user: String, password: String, priority: u8, enabled: bool) -> Self {
         Self {
@@ -198,9 +230,6 @@ impl Share {
         // 计算实际份额难度
         let actual_difficulty = Self::calculate_share_difficulty(&result.hash)?;
 
-        // 转换extranonce2为十六进制字符串
-        let extra_nonce2 = hex::encode(&result.extranonce2);
-
         // 验证挖矿结果数据完整性
         // TODO: 重新启用验证 - DataValidator::validate_mining_result(result)
         //     .map_err(|e| format!("Mining result validation failed: {}", e))?;
@@ -209,25 +238,37 @@ impl Share {
         // TODO: 重新启用验证 - DataValidator::validate_work_result_consistency(work, result)
         //     .map_err(|e| format!("Work-result consistency check failed: {}", e))?;
 
-        let share = Self {
+        let mut share = Self::for_work(pool_id, work, result);
+        share.difficulty = actual_difficulty;
+
+        // 验证创建的份额数据
+        // TODO: 重新启用验证 - DataValidator::validate_share(&share)
+        //     .map_err(|e| format!("Share validation failed: {}", e))?;
+
+        Ok(share)
+    }
+
+    /// 为已验证的结果创建份额：作业 ID 和 ntime 取自结果对应的工作 (不是结果的时间戳)，
+    /// 份额难度取结果中已计算的难度。设备直接回报的结果没有 extranonce2 时使用工作的 extranonce2
+    pub fn for_work(pool_id: u32, work: &Work, result: &MiningResult) -> Self {
+        let extranonce2 = if result.extranonce2.is_empty() {
+            &work.extranonce2
+        } else {
+            &result.extranonce2
+        };
+        Self {
             id: Uuid::new_v4(),
             pool_id,
             work_id: work.id,
             device_id: result.device_id,
             job_id: work.job_id.clone(),
-            extra_nonce2,
+            extra_nonce2: hex::encode(extranonce2),
             nonce: result.nonce,
-            ntime: work.ntime,  // 使用工作数据中的ntime而不是结果时间戳
+            ntime: work.ntime,
             timestamp: result.timestamp,
-            difficulty: actual_difficulty,
+            difficulty: result.share_difficulty,
             status: ShareStatus::Pending,
-        };
-
-        // 验证创建的份额数据
-        // TODO: 重新启用验证 - DataValidator::validate_share(&share)
-        //     .map_err(|e| format!("Share validation failed: {}", e))?;
-
-        Ok(share)
+        }
     }
 
     /// 计算份额难度
@@ -403,6 +444,58 @@ impl PoolStats {
     }
 }
 
+/// 按 (矿池, 设备) 统计的份额，用于定位某个矿池上拒绝率偏高的链
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PoolDeviceStats {
+    pub pool_id: u32,
+    pub device_id: u32,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub accepted_difficulty: f64,
+    pub rejected_difficulty: f64,
+    pub last_share_time: Option<SystemTime>,
+    /// 矿池给出的拒绝原因及次数，未给出原因时记为 "unknown"
+    pub reject_reasons: BTreeMap<String, u64>,
+}
+
+impl PoolDeviceStats {
+    pub fn new(pool_id: u32, device_id: u32) -> Self {
+        Self {
+            pool_id,
+            device_id,
+            ..Default::default()
+        }
+    }
+
+    /// 记录矿池对份额的响应；没有得到矿池答复的提交错误不计入
+    pub fn record(&mut self, share: &Share, outcome: &Result<bool, PoolError>) {
+        let reason = match outcome {
+            Ok(true) => {
+                self.accepted_shares += 1;
+                self.accepted_difficulty += share.difficulty;
+                self.last_share_time = Some(share.timestamp);
+                return;
+            }
+            Ok(false) => "unknown".to_string(),
+            Err(PoolError::ShareRejected { reason }) => reason.clone(),
+            Err(_) => return,
+        };
+        self.rejected_shares += 1;
+        self.rejected_difficulty += share.difficulty;
+        self.last_share_time = Some(share.timestamp);
+        *self.reject_reasons.entry(reason).or_insert(0) += 1;
+    }
+
+    pub fn get_reject_rate(&self) -> f64 {
+        let total = self.accepted_shares + self.rejected_shares;
+        if total == 0 {
+            0.0
+        } else {
+            self.rejected_shares as f64 / total as f64 * 100.0
+        }
+    }
+}
+
 /// 矿池事件
 #[derive(Debug, Clone)]
 pub enum PoolEvent {
@@ -416,7 +509,7 @@ pub enum PoolEvent {
     /// 新工作接收
     WorkReceived {
         pool_id: u32,
-        work: Work,
+        work: Arc<Work>,
         timestamp: SystemTime,
     },
     /// 份额提交
@@ -510,6 +603,27 @@ mod tests {
         assert!(difficulty > 0.0 && difficulty.is_finite());
     }
 
+    #[test]
+    fn test_pool_device_stats() {
+        let share = Share::new(2, Uuid::new_v4(), 3, "job".to_string(), "00000001".to_string(), 1, 0, 64.0);
+        let mut stats = PoolDeviceStats::new(2, 3);
+
+        stats.record(&share, &Ok(true));
+        stats.record(&share, &Ok(false));
+        stats.record(&share, &Err(PoolError::ShareRejected { reason: "Duplicate share".to_string() }));
+        stats.record(&share, &Err(PoolError::ShareRejected { reason: "Duplicate share".to_string() }));
+        // 超时等没有矿池答复的错误不计入
+        stats.record(&share, &Err(PoolError::Timeout { url: "stratum+tcp://pool:3333".to_string() }));
+
+        assert_eq!(stats.accepted_shares, 1);
+        assert_eq!(stats.rejected_shares, 3);
+        assert_eq!(stats.accepted_difficulty, 64.0);
+        assert_eq!(stats.rejected_difficulty, 192.0);
+        assert_eq!(stats.reject_reasons.get("Duplicate share"), Some(&2));
+        assert_eq!(stats.reject_reasons.get("unknown"), Some(&1));
+        assert_eq!(stats.get_reject_rate(), 75.0);
+    }
+
     #[test]
     fn test_share_validation() {
         let share = Share::new(
@@ -619,4 +733,20 @@ mod tests {
         assert_eq!(share.nonce, 12345);
         assert_eq!(share.extra_nonce2, "deadbeef");
     }
+
+    #[test]
+    fn test_share_for_device_result_uses_work_fields() {
+        let mut work = Work::new("job-42".to_string(), [0xff; 32], [1u8; 80], 512.0);
+        work.ntime = 0x5f5e1000;
+        work.set_extranonce2(vec![0, 0, 0, 7]);
+
+        // 设备回报的结果只有 nonce，没有哈希和 extranonce2
+        let result = crate::device::nonce_result(work.id, 3, 0xabcd, 512.0, true);
+        let share = Share::for_work(0, &work, &result);
+        assert_eq!(share.job_id, "job-42");
+        assert_eq!(share.ntime, 0x5f5e1000);
+        assert_eq!(share.extra_nonce2, "00000007");
+        assert_eq!(share.difficulty, 512.0);
+        assert_eq!(share.device_id, 3);
+    }
 }
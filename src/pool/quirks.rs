@@ -0,0 +1,184 @@
+//! 矿池协议差异 (`[[pools.pools]] quirks`)
+//!
+//! 各矿池对 Stratum 的实现并不一致：有的只接受特定的 user agent，有的订阅响应中没有
+//! extranonce2_size，有的不应答 `mining.ping` (心跳超时后连接被误判为断开)，有的需要
+//! `mining.extranonce.subscribe` 才会在会话中途下发新的 extranonce。每个矿池使用一个
+//! 差异配置，未设置时按地址自动识别，`StratumClient` 按解析后的 [`PoolQuirks`] 工作。
+
+use serde::{Deserialize, Serialize};
+
+/// 内置的差异配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuirkProfile {
+    /// 按地址识别
+    #[default]
+    Auto,
+    /// 标准 Stratum，发送 `mining.ping` 心跳
+    Standard,
+    /// 与原版 cgminer 相同的 user agent，不发送 `mining.ping`，用于按 user agent 放行
+    /// 或不应答未知方法的矿池
+    Classic,
+    /// NiceHash：订阅 extranonce 变更，不发送 `mining.ping`
+    Nicehash,
+}
+
+impl QuirkProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuirkProfile::Auto => "auto",
+            QuirkProfile::Standard => "standard",
+            QuirkProfile::Classic => "classic",
+            QuirkProfile::Nicehash => "nicehash",
+        }
+    }
+
+    /// 按矿池地址识别差异配置
+    pub fn detect(url: &str) -> QuirkProfile {
+        let host = url::Url::parse(url).ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default();
+        if host == "nicehash.com" || host.ends_with(".nicehash.com") {
+            QuirkProfile::Nicehash
+        } else {
+            QuirkProfile::Standard
+        }
+    }
+}
+
+/// 矿池的差异配置：选择内置配置，再逐项覆盖
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolQuirksConfig {
+    pub profile: QuirkProfile,
+    /// `mining.subscribe` 中的 user agent
+    pub user_agent: Option<String>,
+    /// 订阅响应中没有 extranonce2_size 时使用的值 (1-16)
+    pub extranonce2_size: Option<usize>,
+    /// 是否发送 `mining.ping` 心跳，关闭时以连接状态判断矿池是否在线
+    pub ping: Option<bool>,
+    /// 授权后是否发送 `mining.extranonce.subscribe`
+    pub extranonce_subscribe: Option<bool>,
+}
+
+/// 解析后的协议行为
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolQuirks {
+    pub profile: QuirkProfile,
+    pub user_agent: String,
+    pub extranonce2_size: usize,
+    pub ping: bool,
+    pub extranonce_subscribe: bool,
+}
+
+/// 原版 cgminer 最后一个版本的 user agent
+const CLASSIC_USER_AGENT: &str = "cgminer/4.12.1";
+
+impl Default for PoolQuirks {
+    fn default() -> Self {
+        Self::for_profile(QuirkProfile::Standard)
+    }
+}
+
+impl PoolQuirks {
+    /// 内置配置的行为 (`Auto` 按 `Standard` 处理)
+    pub fn for_profile(profile: QuirkProfile) -> Self {
+        let standard = Self {
+            profile: QuirkProfile::Standard,
+            user_agent: format!("cgminer-rs/{}", env!("CARGO_PKG_VERSION")),
+            extranonce2_size: 4,
+            ping: true,
+            extranonce_subscribe: false,
+        };
+        match profile {
+            QuirkProfile::Auto | QuirkProfile::Standard => standard,
+            QuirkProfile::Classic => Self {
+                profile,
+                user_agent: CLASSIC_USER_AGENT.to_string(),
+                ping: false,
+                ..standard
+            },
+            QuirkProfile::Nicehash => Self {
+                profile,
+                ping: false,
+                extranonce_subscribe: true,
+                ..standard
+            },
+        }
+    }
+
+    /// 按矿池地址和配置解析；地址带 `#xnsub` 时 (sgminer 的约定) 订阅 extranonce 变更
+    pub fn resolve(url: &str, config: Option<&PoolQuirksConfig>) -> Self {
+        let config = config.cloned().unwrap_or_default();
+        let profile = match config.profile {
+            QuirkProfile::Auto => QuirkProfile::detect(url),
+            profile => profile,
+        };
+        let mut quirks = Self::for_profile(profile);
+        if url.ends_with("#xnsub") {
+            quirks.extranonce_subscribe = true;
+        }
+
+        if let Some(user_agent) = config.user_agent {
+            quirks.user_agent = user_agent;
+        }
+        if let Some(extranonce2_size) = config.extranonce2_size {
+            quirks.extranonce2_size = extranonce2_size;
+        }
+        if let Some(ping) = config.ping {
+            quirks.ping = ping;
+        }
+        if let Some(extranonce_subscribe) = config.extranonce_subscribe {
+            quirks.extranonce_subscribe = extranonce_subscribe;
+        }
+        quirks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_url() {
+        assert_eq!(QuirkProfile::detect("stratum+tcp://sha256.auto.nicehash.com:9200"), QuirkProfile::Nicehash);
+        assert_eq!(QuirkProfile::detect("stratum+tcp://btc.f2pool.com:1314"), QuirkProfile::Standard);
+        assert_eq!(QuirkProfile::detect("stratum+tcp://notnicehash.com:3333"), QuirkProfile::Standard);
+
+        let quirks = PoolQuirks::resolve("stratum+tcp://sha256.auto.nicehash.com:9200", None);
+        assert!(quirks.extranonce_subscribe);
+        assert!(!quirks.ping);
+
+        let quirks = PoolQuirks::resolve("stratum+tcp://pool.example.com:3333#xnsub", None);
+        assert_eq!(quirks.profile, QuirkProfile::Standard);
+        assert!(quirks.extranonce_subscribe);
+    }
+
+    #[test]
+    fn test_overrides_apply_on_top_of_profile() {
+        let config = PoolQuirksConfig {
+            profile: QuirkProfile::Classic,
+            extranonce2_size: Some(8),
+            ping: Some(true),
+            ..Default::default()
+        };
+        let quirks = PoolQuirks::resolve("stratum+tcp://pool.example.com:3333", Some(&config));
+        assert_eq!(quirks.user_agent, CLASSIC_USER_AGENT);
+        assert_eq!(quirks.extranonce2_size, 8);
+        assert!(quirks.ping);
+        assert!(!quirks.extranonce_subscribe);
+
+        // 手动选择的配置不会被地址识别覆盖
+        let config = PoolQuirksConfig { profile: QuirkProfile::Standard, ..Default::default() };
+        let quirks = PoolQuirks::resolve("stratum+tcp://sha256.auto.nicehash.com:9200", Some(&config));
+        assert!(quirks.ping);
+    }
+
+    #[test]
+    fn test_serde_names() {
+        let config: PoolQuirksConfig = toml::from_str("profile = \"nicehash\"\nuser_agent = \"bmminer/2.0.0\"").unwrap();
+        assert_eq!(config.profile, QuirkProfile::Nicehash);
+        assert_eq!(config.user_agent.as_deref(), Some("bmminer/2.0.0"));
+        assert_eq!(config.ping, None);
+    }
+}
@@ -0,0 +1,171 @@
+//! 份额审计日志 (`[share_audit]`)
+//!
+//! 每个提交的份额以一行 JSON 追加写入独立文件，记录 job_id、nonce、ntime、extranonce2、
+//! 难度、矿池及矿池响应，与矿池发生收益争议时可以用本地记录对账。
+//! 审计日志有自己的按大小轮转策略，不受运行日志配置影响。
+
+use crate::error::PoolError;
+use crate::logging::rotation::{parse_size, SizeRotatingWriter};
+use crate::pool::Share;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// 份额审计日志配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareAuditConfig {
+    /// 是否启用份额审计日志
+    pub enabled: bool,
+    /// 审计文件路径 (JSONL)
+    pub file: String,
+    /// 单个文件最大大小，如 "10MB"
+    pub max_size: String,
+    /// 保留的已轮转文件数量
+    pub max_files: u32,
+    /// 压缩已轮转的文件 (gzip)
+    pub compress: bool,
+}
+
+impl Default for ShareAuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: "./logs/shares.jsonl".to_string(),
+            max_size: "10MB".to_string(),
+            max_files: 10,
+            compress: true,
+        }
+    }
+}
+
+/// 矿池对份额的响应
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareResponse {
+    Accepted,
+    Rejected,
+    /// 提交失败（连接或协议错误），矿池未给出结论
+    Error,
+}
+
+/// 一条审计记录
+#[derive(Debug, Serialize)]
+pub struct ShareAuditRecord<'a> {
+    /// 记录时间 (RFC 3339)
+    pub timestamp: String,
+    /// 份额产生时间 (RFC 3339)
+    pub found_at: String,
+    pub share_id: String,
+    pub pool_id: u32,
+    pub pool_url: &'a str,
+    pub worker: &'a str,
+    pub device_id: u32,
+    pub job_id: &'a str,
+    pub extranonce2: &'a str,
+    /// 与 mining.submit 参数一致的十六进制
+    pub ntime: String,
+    pub nonce: String,
+    pub difficulty: f64,
+    pub response: ShareResponse,
+    /// 拒绝原因或错误信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl<'a> ShareAuditRecord<'a> {
+    /// 根据提交结果生成记录
+    pub fn new(share: &'a Share, pool_url: &'a str, worker: &'a str, outcome: &Result<bool, PoolError>) -> Self {
+        let (response, reason) = match outcome {
+            Ok(true) => (ShareResponse::Accepted, None),
+            Ok(false) => (ShareResponse::Rejected, None),
+            Err(PoolError::ShareRejected { reason }) => (ShareResponse::Rejected, Some(reason.clone())),
+            Err(e) => (ShareResponse::Error, Some(e.to_string())),
+        };
+
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            found_at: chrono::DateTime::<chrono::Utc>::from(share.timestamp).to_rfc3339(),
+            share_id: share.id.to_string(),
+            pool_id: share.pool_id,
+            pool_url,
+            worker,
+            device_id: share.device_id,
+            job_id: &share.job_id,
+            extranonce2: &share.extra_nonce2,
+            ntime: format!("{:08x}", share.ntime),
+            nonce: format!("{:08x}", share.nonce),
+            difficulty: share.difficulty,
+            response,
+            reason,
+        }
+    }
+}
+
+/// 份额审计日志写入器
+pub struct ShareAuditLog {
+    writer: Mutex<SizeRotatingWriter>,
+}
+
+impl ShareAuditLog {
+    /// 按配置打开审计文件
+    pub fn open(config: &ShareAuditConfig) -> io::Result<Self> {
+        let max_bytes = parse_size(&config.max_size).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("invalid share_audit.max_size '{}'", config.max_size))
+        })?;
+        let writer = SizeRotatingWriter::new(&config.file, max_bytes, config.max_files as usize, config.compress)?;
+        Ok(Self { writer: Mutex::new(writer) })
+    }
+
+    /// 追加一条记录，每条记录立即落盘，写入失败只告警不影响挖矿
+    pub fn record(&self, record: &ShareAuditRecord<'_>) {
+        if let Err(e) = self.write_line(record) {
+            warn!("Failed to write share audit record {}: {}", record.share_id, e);
+        }
+    }
+
+    fn write_line(&self, record: &ShareAuditRecord<'_>) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_all(&line)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_share_audit_lines() {
+        let dir = std::env::temp_dir().join(format!("cgminer-share-audit-{}", Uuid::new_v4()));
+        let config = ShareAuditConfig {
+            enabled: true,
+            file: dir.join("shares.jsonl").to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let log = ShareAuditLog::open(&config).unwrap();
+
+        let share = Share::new(0, Uuid::new_v4(), 3, "job1".to_string(), "0000002a".to_string(), 0xdeadbeef, 0x5f5e100, 8.0);
+        log.record(&ShareAuditRecord::new(&share, "stratum+tcp://pool:3333", "wallet.1", &Ok(true)));
+        let rejected = Err(PoolError::ShareRejected { reason: "Duplicate share".to_string() });
+        log.record(&ShareAuditRecord::new(&share, "stratum+tcp://pool:3333", "wallet.1", &rejected));
+
+        let text = std::fs::read_to_string(&config.file).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["response"], "accepted");
+        assert_eq!(lines[0]["nonce"], "deadbeef");
+        assert_eq!(lines[0]["ntime"], "05f5e100");
+        assert_eq!(lines[0]["extranonce2"], "0000002a");
+        assert!(lines[0].get("reason").is_none());
+        assert_eq!(lines[1]["response"], "rejected");
+        assert_eq!(lines[1]["reason"], "Duplicate share");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
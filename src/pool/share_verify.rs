@@ -0,0 +1,149 @@
+//! 提交前的本地份额验证 (`[share_verify]`)
+//!
+//! 芯片只回报 nonce，不回传哈希，份额难度直接取工作难度。提交前把 nonce 填回下发工作的区块头
+//! 重新计算哈希，确认它确实满足份额目标；不满足的结果计为该设备的硬件错误，不发给矿池，
+//! 避免有问题的核心或芯片拉高矿池端的拒绝率。低功耗设备可以只抽样验证一部分结果。
+//!
+//! 只能验证 SHA256d 工作，且结果使用工作自己的 extranonce2 (区块头的 merkle root 依赖它)；
+//! 其它结果不验证，照常提交。
+
+use crate::device::{MiningResult, Work};
+use crate::mining::{Algorithm, Network};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 份额验证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareVerifyConfig {
+    /// 是否在提交前验证份额
+    pub enabled: bool,
+    /// 抽样比例 (0-1]，1 表示验证每个份额
+    pub sample_rate: f64,
+}
+
+impl Default for ShareVerifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+/// 单个结果的验证结论
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShareCheck {
+    /// 哈希满足份额目标
+    Valid,
+    /// 哈希不满足份额目标，`difficulty` 为重新计算的难度
+    Invalid { difficulty: f64 },
+    /// 未抽中或无法验证
+    Unchecked,
+}
+
+/// 份额验证器
+pub struct ShareVerifier {
+    sample_rate: f64,
+    /// 比特币网络，决定难度 1 目标
+    network: Network,
+    /// 已见过的结果数，按比例均匀抽样
+    seen: AtomicU64,
+}
+
+impl ShareVerifier {
+    pub fn new(config: &ShareVerifyConfig, network: Network) -> Self {
+        Self {
+            sample_rate: config.sample_rate.clamp(0.0, 1.0),
+            network,
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    /// 验证结果是否满足工作的份额难度
+    pub fn check(&self, work: &Work, result: &MiningResult, algorithm: Algorithm) -> ShareCheck {
+        if !self.sample() {
+            return ShareCheck::Unchecked;
+        }
+        if !result.extranonce2.is_empty() && result.extranonce2 != work.extranonce2 {
+            return ShareCheck::Unchecked;
+        }
+        match header_difficulty(&work.header, result.nonce, algorithm, self.network) {
+            Some(difficulty) if difficulty >= work.difficulty => ShareCheck::Valid,
+            Some(difficulty) => ShareCheck::Invalid { difficulty },
+            None => ShareCheck::Unchecked,
+        }
+    }
+
+    /// 第 n 个结果在 floor((n+1)·rate) 增加时被抽中，比例为 0.25 时每 4 个验证 1 个
+    fn sample(&self) -> bool {
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+        let n = self.seen.fetch_add(1, Ordering::Relaxed) as f64;
+        ((n + 1.0) * self.sample_rate).floor() > (n * self.sample_rate).floor()
+    }
+}
+
+/// 区块头填入 nonce 后的份额难度；不是 SHA256d 时返回 None
+pub fn header_difficulty(header: &[u8], nonce: u32, algorithm: Algorithm, network: Network) -> Option<f64> {
+    if algorithm != Algorithm::Sha256d || header.len() != 80 {
+        return None;
+    }
+    let mut header = header.to_vec();
+    header[76..80].copy_from_slice(&nonce.to_le_bytes());
+    let hash = Sha256::digest(Sha256::digest(&header));
+    algorithm.hash_difficulty(network, &hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::nonce_result;
+
+    /// 工作难度很低 (约 4096 次哈希满足一次)，很快能找到满足和不满足目标的 nonce
+    fn work_and_nonces() -> (Work, u32, u32) {
+        let mut work = Work::new("verify".to_string(), [0xff; 32], [7u8; 80], 1e-6);
+        work.extranonce2 = vec![0, 0, 0, 1];
+        let mut good = None;
+        let mut bad = None;
+        for nonce in 0..u32::MAX {
+            let difficulty = header_difficulty(&work.header, nonce, Algorithm::Sha256d, Network::Mainnet).unwrap();
+            if difficulty >= work.difficulty {
+                good.get_or_insert(nonce);
+            } else {
+                bad.get_or_insert(nonce);
+            }
+            if let (Some(good), Some(bad)) = (good, bad) {
+                return (work, good, bad);
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_check_recomputes_hash() {
+        let (work, good, bad) = work_and_nonces();
+        let verifier = ShareVerifier::new(&ShareVerifyConfig { enabled: true, sample_rate: 1.0 }, Network::Mainnet);
+
+        // 芯片结果的份额难度取自工作难度，只有重新计算哈希才能发现错误
+        let result = nonce_result(work.id, 3, good, work.difficulty, true);
+        assert_eq!(verifier.check(&work, &result, Algorithm::Sha256d), ShareCheck::Valid);
+        let result = nonce_result(work.id, 3, bad, work.difficulty, true);
+        assert!(matches!(verifier.check(&work, &result, Algorithm::Sha256d), ShareCheck::Invalid { .. }));
+
+        // scrypt 和换了 extranonce2 的结果无法验证
+        assert_eq!(verifier.check(&work, &result, Algorithm::Scrypt), ShareCheck::Unchecked);
+        let mut result = nonce_result(work.id, 3, bad, work.difficulty, true);
+        result.extranonce2 = vec![0, 0, 0, 2];
+        assert_eq!(verifier.check(&work, &result, Algorithm::Sha256d), ShareCheck::Unchecked);
+    }
+
+    #[test]
+    fn test_sample_rate() {
+        let verifier = ShareVerifier::new(&ShareVerifyConfig { enabled: true, sample_rate: 0.25 }, Network::Mainnet);
+        let sampled = (0..100).filter(|_| verifier.sample()).count();
+        assert_eq!(sampled, 25);
+    }
+}
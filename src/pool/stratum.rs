@@ -2,7 +2,12 @@ use crate::error::PoolError;
 use crate::device::Work;
 use crate::pool::Share;
 use crate::pool::proxy::ProxyConnector;
+use crate::pool::extranonce::Extranonce2Counter;
+use crate::pool::quirks::PoolQuirks;
 use crate::config::ProxyConfig;
+use crate::logging::redact;
+use crate::mining::{midstate, Algorithm, Network};
+use crate::performance::alloc_audit::{self, Subsystem};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
@@ -25,6 +30,25 @@ pub struct StratumMessage {
     pub error: Option<StratumError>,
 }
 
+/// 构造 `mining.submit` 请求
+///
+/// 按照Stratum协议格式提交份额，参数顺序：[username, job_id, extranonce2, ntime, nonce]
+pub fn submit_message(id: u64, username: &str, share: &Share) -> StratumMessage {
+    StratumMessage {
+        id: Some(id),
+        method: Some("mining.submit".to_string()),
+        params: Some(json!([
+            username,
+            share.job_id,
+            share.extra_nonce2,
+            format!("{:08x}", share.ntime),  // 使用工作数据中的ntime
+            format!("{:08x}", share.nonce)
+        ])),
+        result: None,
+        error: None,
+    }
+}
+
 /// Stratum 错误
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StratumError {
@@ -55,6 +79,8 @@ pub struct StratumClient {
     extra_nonce1: Arc<RwLock<Option<String>>>,
     /// Extra nonce 2 大小
     extra_nonce2_size: Arc<RwLock<usize>>,
+    /// Extra nonce 2 计数器，构造工作时同步访问
+    extranonce2_counter: Arc<std::sync::Mutex<Extranonce2Counter>>,
     /// 当前难度
     difficulty: Arc<RwLock<f64>>,
     /// 当前作业
@@ -65,7 +91,12 @@ pub struct StratumClient {
     pending_requests: Arc<RwLock<HashMap<u64, tokio::sync::oneshot::Sender<StratumMessage>>>>,
     /// 矿池ID
     pool_id: u32,
-
+    /// 挖矿算法，决定由难度换算的工作目标
+    algorithm: Algorithm,
+    /// 比特币网络，决定难度 1 目标
+    network: Network,
+    /// 矿池协议差异
+    quirks: PoolQuirks,
 }
 
 /// Stratum 作业
@@ -82,6 +113,14 @@ pub struct StratumJob {
     pub clean_jobs: bool,
 }
 
+/// 构造工作时使用的会话状态
+#[derive(Debug, Clone)]
+struct SessionSnapshot {
+    extranonce1: String,
+    extranonce2_size: usize,
+    difficulty: f64,
+}
+
 impl StratumClient {
     /// 创建新的 Stratum 客户端
     pub async fn new(url: String, username: String, password: String, pool_id: u32, _verbose: bool, proxy_config: Option<ProxyConfig>) -> Result<Self, PoolError> {
@@ -96,18 +135,42 @@ impl StratumClient {
             subscription_id: Arc::new(RwLock::new(None)),
             extra_nonce1: Arc::new(RwLock::new(None)),
             extra_nonce2_size: Arc::new(RwLock::new(4)),
+            extranonce2_counter: Arc::new(std::sync::Mutex::new(Extranonce2Counter::new(4, 1))),
             difficulty: Arc::new(RwLock::new(1.0)),
             current_job: Arc::new(RwLock::new(None)),
             message_id: Arc::new(RwLock::new(1)),
             pending_requests: Arc::new(RwLock::new(HashMap::new())),
             pool_id,
-
+            algorithm: Algorithm::default(),
+            network: Network::default(),
+            quirks: PoolQuirks::default(),
         })
     }
 
+    /// 设置矿池的挖矿算法
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    /// 设置矿池所在的比特币网络
+    pub fn set_network(&mut self, network: Network) {
+        self.network = network;
+    }
+
+    /// 设置矿池的协议差异，在连接前调用
+    pub fn set_quirks(&mut self, quirks: PoolQuirks) {
+        self.quirks = quirks;
+    }
+
+    /// 把 extranonce2 空间分成 `partitions` 个设备子区间
+    pub fn set_extranonce2_partitions(&mut self, partitions: u32) {
+        // 计数器在第一次取值时按会话的 extranonce2 长度归零
+        self.extranonce2_counter = Arc::new(std::sync::Mutex::new(Extranonce2Counter::new(self.quirks.extranonce2_size, partitions)));
+    }
+
     /// 连接到矿池
     pub async fn connect(&mut self) -> Result<(), PoolError> {
-        info!("Connecting to Stratum pool: {}", self.url);
+        info!("Connecting to Stratum pool: {} (quirk profile: {})", self.url, self.quirks.profile.as_str());
         debug!("🔗 [Pool {}] 开始连接到矿池: {}", self.pool_id, self.url);
 
         // 创建代理连接器（支持TLS配置）
@@ -176,6 +239,13 @@ impl StratumClient {
         debug!("🔗 [Pool {}] 发送认证请求", self.pool_id);
         self.authorize().await?;
 
+        // extranonce 变更订阅失败不影响挖矿，只是会话中途无法切换 extranonce
+        if self.quirks.extranonce_subscribe {
+            if let Err(e) = self.subscribe_extranonce().await {
+                warn!("Pool {} rejected mining.extranonce.subscribe: {}", self.pool_id, e);
+            }
+        }
+
         info!("Pool {} connected successfully", self.pool_id);
         info!("Successfully connected to Stratum pool");
         debug!("🔗 [Pool {}] 完整连接流程完成", self.pool_id);
@@ -214,7 +284,7 @@ impl StratumClient {
         let message = StratumMessage {
             id: Some(self.next_message_id().await),
             method: Some("mining.subscribe".to_string()),
-            params: Some(json!(["cgminer-rs/1.0.0"])),
+            params: Some(json!([self.quirks.user_agent])),
             result: None,
             error: None,
         };
@@ -290,14 +360,14 @@ impl StratumClient {
                         *self.extra_nonce2_size.write().await = extra_nonce2_size as usize;
                         debug!("✅ [Pool {}] extranonce2_size 设置成功: {}", self.pool_id, extra_nonce2_size);
                     } else {
-                        debug!("⚠️ [Pool {}] 无法获取 extranonce2_size，使用默认值 4，第三个元素: {:?}", self.pool_id, array.get(2));
+                        debug!("⚠️ [Pool {}] 无法获取 extranonce2_size，使用默认值 {}，第三个元素: {:?}",
+                               self.pool_id, self.quirks.extranonce2_size, array.get(2));
                         // 使用默认值而不是报错，因为有些矿池可能不提供这个参数
-                        *self.extra_nonce2_size.write().await = 4;
+                        *self.extra_nonce2_size.write().await = self.quirks.extranonce2_size;
                     }
                 } else {
-                    debug!("⚠️ [Pool {}] 响应中没有 extranonce2_size，使用默认值 4", self.pool_id);
-                    // 使用默认值
-                    *self.extra_nonce2_size.write().await = 4;
+                    debug!("⚠️ [Pool {}] 响应中没有 extranonce2_size，使用默认值 {}", self.pool_id, self.quirks.extranonce2_size);
+                    *self.extra_nonce2_size.write().await = self.quirks.extranonce2_size;
                 }
             } else {
                 debug!("❌ [Pool {}] 响应结果不是数组格式: {:?}", self.pool_id, result);
@@ -320,6 +390,18 @@ impl StratumClient {
             });
         }
 
+        // 新会话的 extranonce2 从零开始
+        let size = *self.extra_nonce2_size.read().await;
+        if let Ok(mut counter) = self.extranonce2_counter.lock() {
+            counter.reset(size);
+            if counter.is_clamped() {
+                warn!(
+                    "Pool {} extranonce2_size {} only fits {} extranonce2_partitions; devices beyond that share sub-ranges",
+                    self.pool_id, size, counter.partitions()
+                );
+            }
+        }
+
         debug!("✅ [Pool {}] 挖矿订阅成功完成", self.pool_id);
         Ok(())
     }
@@ -355,45 +437,78 @@ impl StratumClient {
         }
     }
 
-    /// 提交份额
-    pub async fn submit_share(&self, share: &Share) -> Result<bool, PoolError> {
-        // 记录份额提交详情
-        debug!("Pool {} submitting share from device {}", self.pool_id, share.device_id);
-
-        debug!("Submitting share: job_id={}, nonce={:08x}, ntime={:08x}",
-               share.job_id, share.nonce, share.ntime);
-
-        // 验证份额数据完整性
-        // TODO: 重新启用验证 - DataValidator::validate_share(share)?;
+    /// 订阅 extranonce 变更 (`mining.set_extranonce`)
+    async fn subscribe_extranonce(&self) -> Result<(), PoolError> {
+        debug!("Sending mining.extranonce.subscribe");
 
-        // 确保extranonce2格式正确（应该已经是十六进制字符串）
-        let extranonce2_hex = if share.extra_nonce2.is_empty() {
-            return Err(PoolError::ProtocolError {
-                url: self.url.clone(),
-                error: "Extranonce2 is empty".to_string(),
-            });
-        } else {
-            share.extra_nonce2.clone()
-        };
-
-        // 按照Stratum协议格式提交份额
-        // 参数顺序：[username, job_id, extranonce2, ntime, nonce]
         let message = StratumMessage {
             id: Some(self.next_message_id().await),
-            method: Some("mining.submit".to_string()),
-            params: Some(json!([
-                self.username,
-                share.job_id,
-                extranonce2_hex,
-                format!("{:08x}", share.ntime),  // 使用工作数据中的ntime
-                format!("{:08x}", share.nonce)
-            ])),
+            method: Some("mining.extranonce.subscribe".to_string()),
+            params: Some(json!([])),
             result: None,
             error: None,
         };
 
         let response = self.send_request(message).await?;
+        match response.error {
+            Some(error) => Err(PoolError::StratumError {
+                error_code: error.code,
+                message: error.message,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// 提交份额
+    pub async fn submit_share(&self, share: &Share) -> Result<bool, PoolError> {
+        self.submit_shares(std::slice::from_ref(share)).await
+            .pop()
+            .unwrap_or(Err(PoolError::NoPoolsAvailable))
+    }
+
+    /// 流水线提交多个份额：一次写入全部 `mining.submit` 请求，再按各自的 ID 等待矿池响应。
+    /// 结果与 `shares` 顺序一一对应。
+    pub async fn submit_shares(&self, shares: &[Share]) -> Vec<Result<bool, PoolError>> {
+        let mut outcomes: Vec<Option<Result<bool, PoolError>>> = Vec::with_capacity(shares.len());
+        let mut messages = Vec::with_capacity(shares.len());
+
+        for share in shares {
+            debug!("Pool {} submitting share from device {}: job_id={}, nonce={:08x}, ntime={:08x}",
+                   self.pool_id, share.device_id, share.job_id, share.nonce, share.ntime);
+
+            // 确保extranonce2格式正确（应该已经是十六进制字符串）
+            if share.extra_nonce2.is_empty() {
+                outcomes.push(Some(Err(PoolError::ProtocolError {
+                    url: self.url.clone(),
+                    error: "Extranonce2 is empty".to_string(),
+                })));
+                continue;
+            }
+
+            outcomes.push(None);
+            messages.push(submit_message(self.next_message_id().await, &self.username, share));
+        }
+
+        if messages.len() > 1 {
+            debug!("Pool {} pipelining {} share submissions", self.pool_id, messages.len());
+        }
+        let mut responses = self.send_requests(messages).await.into_iter();
+
+        shares.iter().zip(outcomes).map(|(share, outcome)| match outcome {
+            Some(outcome) => outcome,
+            None => match responses.next() {
+                Some(Ok(response)) => Self::submit_outcome(share, response),
+                Some(Err(e)) => Err(e),
+                None => Err(PoolError::ProtocolError {
+                    url: self.url.clone(),
+                    error: "Missing share submission response".to_string(),
+                }),
+            },
+        }).collect()
+    }
 
+    /// 解析 `mining.submit` 的响应
+    fn submit_outcome(share: &Share, response: StratumMessage) -> Result<bool, PoolError> {
         if let Some(result) = response.result {
             let accepted = result.as_bool().unwrap_or(false);
 
@@ -403,35 +518,31 @@ impl StratumClient {
             } else {
                 info!("Rejected share from device {}", share.device_id);
             }
-
-            if accepted {
-                debug!("Share accepted by pool");
-            } else {
-                debug!("Share rejected by pool");
-            }
             Ok(accepted)
         } else if let Some(error) = response.error {
             // 记录拒绝的份额
             warn!("Rejected share from device {}: {}", share.device_id, error.message);
-
-            warn!("Share rejected: {}", error.message);
             Err(PoolError::ShareRejected { reason: error.message })
         } else {
             // 记录未知响应
             warn!("Unknown response format for share submission from device {}", share.device_id);
-
-            warn!("Unknown response format for share submission");
             Ok(false)
         }
     }
 
-    /// 获取工作
+    /// 获取工作 (extranonce2 取自第 0 个设备子区间)
     pub async fn get_work(&self) -> Result<Work, PoolError> {
+        self.get_work_for_device(0).await
+    }
+
+    /// 为设备获取工作，extranonce2 取自该设备的子区间
+    pub async fn get_work_for_device(&self, device_id: u32) -> Result<Work, PoolError> {
         let job = self.current_job.read().await;
 
         if let Some(job) = job.as_ref() {
-            // 构造工作数据
-            let work = self.build_work_from_job(job)?;
+            // 在异步上下文中取出会话状态，构造工作本身不再等待锁
+            let session = self.session_snapshot().await?;
+            let work = self.build_work_from_job(job, &session, device_id)?;
             Ok(work)
         } else {
             Err(PoolError::ProtocolError {
@@ -441,31 +552,18 @@ impl StratumClient {
         }
     }
 
-    /// 从作业构造工作
-    fn build_work_from_job(&self, job: &StratumJob) -> Result<Work, PoolError> {
+    /// 构造工作需要的会话状态快照
+    async fn session_snapshot(&self) -> Result<SessionSnapshot, PoolError> {
         // 验证extranonce配置
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.validate_extranonce_config().await
-            })
-        })?;
-
-        // 获取extranonce信息
-        let extranonce1 = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                self.extra_nonce1.read().await.clone()
-            })
-        }).ok_or_else(|| PoolError::ProtocolError {
-            url: self.url.clone(),
-            error: "Extranonce1 not available".to_string(),
-        })?;
+        self.validate_extranonce_config().await?;
 
-        let extranonce2_size = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                *self.extra_nonce2_size.read().await
-            })
-        });
+        let extranonce1 = self.extra_nonce1.read().await.clone()
+            .ok_or_else(|| PoolError::ProtocolError {
+                url: self.url.clone(),
+                error: "Extranonce1 not available".to_string(),
+            })?;
 
+        let extranonce2_size = *self.extra_nonce2_size.read().await;
         if extranonce2_size == 0 {
             return Err(PoolError::ProtocolError {
                 url: self.url.clone(),
@@ -473,11 +571,16 @@ impl StratumClient {
             });
         }
 
-        let difficulty = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(async {
-                *self.difficulty.read().await
-            })
-        });
+        Ok(SessionSnapshot {
+            extranonce1,
+            extranonce2_size,
+            difficulty: *self.difficulty.read().await,
+        })
+    }
+
+    /// 从作业和会话状态快照构造工作（同步，不访问异步锁）
+    fn build_work_from_job(&self, job: &StratumJob, session: &SessionSnapshot, device_id: u32) -> Result<Work, PoolError> {
+        let (extranonce1, extranonce2_size, difficulty) = (&session.extranonce1, session.extranonce2_size, session.difficulty);
 
         // 解析版本、nBits、nTime
         let version = u32::from_str_radix(&job.version, 16)
@@ -523,7 +626,7 @@ impl StratumClient {
             })?;
 
         // 解析extranonce1
-        let extranonce1_bytes = hex::decode(&extranonce1)
+        let extranonce1_bytes = hex::decode(extranonce1)
             .map_err(|_| PoolError::ProtocolError {
                 url: self.url.clone(),
                 error: "Invalid extranonce1 format".to_string(),
@@ -548,8 +651,13 @@ impl StratumClient {
             error: format!("Failed to create work from job: {}", e),
         })?;
 
-        // 生成extranonce2并计算merkle root
-        let extranonce2 = self.generate_extranonce2(extranonce2_size);
+        // Work::from_stratum_job 按主网 SHA256d 换算目标，其他算法和 regtest 按各自的难度 1 目标重新计算
+        if self.algorithm != Algorithm::Sha256d || self.network.diff1_target_override().is_some() {
+            work.target = self.algorithm.target_for_difficulty(self.network, difficulty);
+        }
+
+        // 取下一个extranonce2并计算merkle root
+        let extranonce2 = self.next_extranonce2(device_id, extranonce2_size)?;
         work.set_extranonce2(extranonce2);
 
         // 验证coinbase交易
@@ -564,6 +672,9 @@ impl StratumClient {
             error: format!("Failed to calculate merkle root: {}", e),
         })?;
 
+        // 区块头的第一块已确定，预先计算 midstate 交给核心
+        midstate::refresh(&mut work, self.algorithm);
+
         // 验证Work数据完整性
         // TODO: 重新启用验证 - DataValidator::validate_work(&work).map_err(|e| PoolError::ProtocolError {
         //     url: self.url.clone(),
@@ -578,6 +689,11 @@ impl StratumClient {
         *self.connected.read().await
     }
 
+    /// 已发出但还没收到响应的请求数
+    pub async fn pending_request_count(&self) -> usize {
+        self.pending_requests.read().await.len()
+    }
+
     /// 发送心跳检测
     pub async fn ping(&self) -> Result<(), PoolError> {
         // 首先检查连接状态
@@ -601,6 +717,11 @@ impl StratumClient {
             }
         }
 
+        // 不应答 mining.ping 的矿池只按连接状态判断
+        if !self.quirks.ping {
+            return Ok(());
+        }
+
         let message = StratumMessage {
             id: Some(self.next_message_id().await),
             method: Some("mining.ping".to_string()),
@@ -633,61 +754,85 @@ impl StratumClient {
 
     /// 发送请求并等待响应
     async fn send_request(&self, message: StratumMessage) -> Result<StratumMessage, PoolError> {
-        let message_id = message.id.unwrap();
-        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.send_requests(vec![message]).await
+            .pop()
+            .unwrap_or(Err(PoolError::Timeout { url: self.url.clone() }))
+    }
+
+    /// 流水线发送多个请求：先登记全部待处理请求，一次写入并刷新，再并发等待各自的响应。
+    /// 响应由消息循环按 ID 分发，顺序与请求一一对应。
+    async fn send_requests(&self, messages: Vec<StratumMessage>) -> Vec<Result<StratumMessage, PoolError>> {
+        if messages.is_empty() {
+            return Vec::new();
+        }
 
         // 注册待处理请求
-        self.pending_requests.write().await.insert(message_id, tx);
+        let mut receivers = Vec::with_capacity(messages.len());
+        {
+            let mut pending = self.pending_requests.write().await;
+            for message in &messages {
+                let message_id = message.id.unwrap_or_default();
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                pending.insert(message_id, tx);
+                receivers.push((message_id, rx));
+            }
+        }
 
         // 发送消息
-        self.send_message(message).await?;
+        if let Err(e) = self.send_messages(&messages).await {
+            let mut pending = self.pending_requests.write().await;
+            for (message_id, _) in &receivers {
+                pending.remove(message_id);
+            }
+            return receivers.iter().map(|_| Err(e.clone())).collect();
+        }
 
         // 等待响应
-        match timeout(Duration::from_secs(30), rx).await {
-            Ok(Ok(response)) => Ok(response),
-            Ok(Err(_)) => Err(PoolError::ProtocolError {
-                url: self.url.clone(),
-                error: "Request cancelled".to_string(),
-            }),
-            Err(_) => {
-                // 清理待处理请求
-                self.pending_requests.write().await.remove(&message_id);
-                Err(PoolError::Timeout { url: self.url.clone() })
+        futures::future::join_all(receivers.into_iter().map(|(message_id, rx)| async move {
+            match timeout(Duration::from_secs(30), rx).await {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(_)) => Err(PoolError::ProtocolError {
+                    url: self.url.clone(),
+                    error: "Request cancelled".to_string(),
+                }),
+                Err(_) => {
+                    // 清理待处理请求
+                    self.pending_requests.write().await.remove(&message_id);
+                    Err(PoolError::Timeout { url: self.url.clone() })
+                }
             }
-        }
+        })).await
     }
 
     /// 发送消息
     async fn send_message(&self, message: StratumMessage) -> Result<(), PoolError> {
-        debug!("📤 [Pool {}] 准备发送消息: {:?}", self.pool_id, message);
-
-        let json_str = serde_json::to_string(&message)
-            .map_err(|e| {
-                debug!("❌ [Pool {}] JSON序列化失败: {}", self.pool_id, e);
-                PoolError::ProtocolError {
-                    url: self.url.clone(),
-                    error: format!("JSON serialization error: {}", e),
-                }
-            })?;
+        self.send_messages(std::slice::from_ref(&message)).await
+    }
 
-        debug!("📤 [Pool {}] 发送JSON: {}", self.pool_id, json_str);
+    /// 按行写入多个消息，只刷新一次
+    async fn send_messages(&self, messages: &[StratumMessage]) -> Result<(), PoolError> {
+        let mut buffer = String::new();
+        for message in messages {
+            debug!("📤 [Pool {}] 准备发送消息: {}", self.pool_id, redact::to_redacted_json(message));
 
-        let mut writer_guard = self.writer.lock().await;
-        if let Some(writer) = writer_guard.as_mut() {
-            debug!("📤 [Pool {}] 写入JSON数据到TCP流", self.pool_id);
-            writer.write_all(json_str.as_bytes()).await
+            let json_str = serde_json::to_string(message)
                 .map_err(|e| {
-                    debug!("❌ [Pool {}] TCP写入JSON失败: {}", self.pool_id, e);
-                    PoolError::ConnectionFailed {
+                    debug!("❌ [Pool {}] JSON序列化失败: {}", self.pool_id, e);
+                    PoolError::ProtocolError {
                         url: self.url.clone(),
-                        error: e.to_string(),
+                        error: format!("JSON serialization error: {}", e),
                     }
                 })?;
+            buffer.push_str(&json_str);
+            buffer.push('\n');
+        }
 
-            debug!("📤 [Pool {}] 写入换行符", self.pool_id);
-            writer.write_all(b"\n").await
+        let mut writer_guard = self.writer.lock().await;
+        if let Some(writer) = writer_guard.as_mut() {
+            debug!("📤 [Pool {}] 写入 {} 条消息到TCP流", self.pool_id, messages.len());
+            writer.write_all(buffer.as_bytes()).await
                 .map_err(|e| {
-                    debug!("❌ [Pool {}] TCP写入换行符失败: {}", self.pool_id, e);
+                    debug!("❌ [Pool {}] TCP写入失败: {}", self.pool_id, e);
                     PoolError::ConnectionFailed {
                         url: self.url.clone(),
                         error: e.to_string(),
@@ -711,7 +856,7 @@ impl StratumClient {
             });
         }
 
-        debug!("✅ [Pool {}] 消息发送完成: {}", self.pool_id, json_str);
+        debug!("✅ [Pool {}] {} 条消息发送完成", self.pool_id, messages.len());
         Ok(())
     }
 
@@ -722,6 +867,8 @@ impl StratumClient {
         let pending_requests = self.pending_requests.clone();
         let current_job = self.current_job.clone();
         let difficulty = self.difficulty.clone();
+        let extra_nonce1 = self.extra_nonce1.clone();
+        let extra_nonce2_size = self.extra_nonce2_size.clone();
 
         let pool_id = self.pool_id;
 
@@ -746,7 +893,11 @@ impl StratumClient {
                         },
                         Ok(bytes_read) => {
                             debug!("📥 [Pool {}] 接收到 {} 字节数据: {}", pool_id, bytes_read, line.trim());
-                            if let Ok(message) = serde_json::from_str::<StratumMessage>(&line.trim()) {
+                            let parsed = {
+                                let _scope = alloc_audit::enter(Subsystem::StratumParse);
+                                serde_json::from_str::<StratumMessage>(line.trim())
+                            };
+                            if let Ok(message) = parsed {
                                 debug!("📥 [Pool {}] 解析消息成功: {:?}", pool_id, message);
 
                                 // 处理响应
@@ -769,7 +920,11 @@ impl StratumClient {
                                         "mining.notify" => {
                                             // 处理新作业通知
                                             if let Some(params) = &message.params {
-                                                if let Some(job) = Self::parse_job_notification(params) {
+                                                let job = {
+                                                    let _scope = alloc_audit::enter(Subsystem::StratumParse);
+                                                    Self::parse_job_notification(params)
+                                                };
+                                                if let Some(job) = job {
                                                     // 记录新工作接收
                                                     let _current_difficulty = *difficulty.read().await;
                                                     info!("Pool {} new job: {}", pool_id, job.job_id);
@@ -784,7 +939,7 @@ impl StratumClient {
                                                 if let Some(array) = params.as_array() {
                                                     if let Some(diff) = array.get(0).and_then(|v| v.as_f64()) {
                                                         // 验证难度值的合理性
-                                                        if diff > 0.0 && diff.is_finite() {
+                                                        if Self::is_valid_difficulty(diff) {
                                                             let old_difficulty = *difficulty.read().await;
                                                             *difficulty.write().await = diff;
 
@@ -807,6 +962,17 @@ impl StratumClient {
                                                 warn!("No parameters in mining.set_difficulty message");
                                             }
                                         }
+                                        "mining.set_extranonce" => {
+                                            // 新的 extranonce 从下一个作业开始生效
+                                            match message.params.as_ref().and_then(Self::parse_set_extranonce) {
+                                                Some((extranonce1, size)) => {
+                                                    info!("Pool {} extranonce changed to {} (extranonce2_size {})", pool_id, extranonce1, size);
+                                                    *extra_nonce1.write().await = Some(extranonce1);
+                                                    *extra_nonce2_size.write().await = size;
+                                                }
+                                                None => warn!("Invalid parameters for mining.set_extranonce: {:?}", message.params),
+                                            }
+                                        }
                                         _ => {
                                             debug!("📥 [Pool {}] 未知方法: {}", pool_id, method);
                                         }
@@ -860,6 +1026,17 @@ impl StratumClient {
         None
     }
 
+    /// 解析 `mining.set_extranonce` 的 [extranonce1, extranonce2_size]
+    fn parse_set_extranonce(params: &Value) -> Option<(String, usize)> {
+        let array = params.as_array()?;
+        let extranonce1 = array.first()?.as_str()?;
+        let size = array.get(1)?.as_u64()?;
+        if extranonce1.is_empty() || hex::decode(extranonce1).is_err() || size == 0 || size > 16 {
+            return None;
+        }
+        Some((extranonce1.to_string(), size as usize))
+    }
+
     /// 获取下一个消息ID
     async fn next_message_id(&self) -> u64 {
         let mut id = self.message_id.write().await;
@@ -867,11 +1044,13 @@ impl StratumClient {
         *id
     }
 
-    /// 生成extranonce2
-    fn generate_extranonce2(&self, size: usize) -> Vec<u8> {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        (0..size).map(|_| rng.gen::<u8>()).collect()
+    /// 设备子区间内的下一个extranonce2
+    fn next_extranonce2(&self, device_id: u32, size: usize) -> Result<Vec<u8>, PoolError> {
+        let mut counter = self.extranonce2_counter.lock().map_err(|_| PoolError::ProtocolError {
+            url: self.url.clone(),
+            error: "Extranonce2 counter lock poisoned".to_string(),
+        })?;
+        Ok(counter.next(device_id, size))
     }
 
     /// 检查extranonce是否已正确设置
@@ -1013,3 +1192,169 @@ impl StratumClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stratum 协议文档中的示例作业
+    async fn client_with_job() -> StratumClient {
+        let client = StratumClient::new(
+            "stratum+tcp://pool.example:3333".to_string(),
+            "worker".to_string(),
+            "x".to_string(),
+            0,
+            false,
+            None,
+        ).await.unwrap();
+        *client.extra_nonce1.write().await = Some("08000002".to_string());
+        *client.difficulty.write().await = 16.0;
+        *client.current_job.write().await = Some(StratumJob {
+            job_id: "bf".to_string(),
+            previous_hash: "4d16b6f85af6e2198f44ae2a6de67f78487ae5611b77c6c0440b921e00000000".to_string(),
+            coinbase1: "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff20020862062f503253482f04b8864e5008".to_string(),
+            coinbase2: "072f736c7573682f000000000100f2052a010000001976a914d23fcdf86f7e756a64a7a9688ef9903327048ed988ac00000000".to_string(),
+            merkle_branches: Vec::new(),
+            version: "00000002".to_string(),
+            nbits: "1c2ac4af".to_string(),
+            ntime: "504e86b9".to_string(),
+            clean_jobs: false,
+        });
+        client
+    }
+
+    // 以前构造工作时使用 block_in_place，在单线程运行时上会 panic
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_get_work_on_current_thread_runtime() {
+        let client = client_with_job().await;
+        let work = client.get_work().await.unwrap();
+        assert_eq!(work.job_id, "bf");
+        assert_eq!(work.extranonce2.len(), 4);
+        assert_eq!(work.difficulty, 16.0);
+    }
+
+    #[tokio::test]
+    async fn test_extranonce2_counts_per_device() {
+        let mut client = client_with_job().await;
+        client.set_extranonce2_partitions(2);
+
+        let first = client.get_work().await.unwrap();
+        let second = client.get_work().await.unwrap();
+        let device = client.get_work_for_device(1).await.unwrap();
+        assert_eq!(first.extranonce2, vec![0, 0, 0, 0]);
+        assert_eq!(second.extranonce2, vec![1, 0, 0, 0]);
+        assert_eq!(device.extranonce2, vec![0, 0, 0, 0x80]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_get_work_requires_extranonce1() {
+        let client = client_with_job().await;
+        *client.extra_nonce1.write().await = None;
+        assert!(client.get_work().await.is_err());
+    }
+
+    fn test_share(nonce: u32) -> Share {
+        Share {
+            id: uuid::Uuid::new_v4(),
+            pool_id: 0,
+            work_id: uuid::Uuid::new_v4(),
+            device_id: 0,
+            job_id: "bf".to_string(),
+            extra_nonce2: "00000001".to_string(),
+            nonce,
+            ntime: 0x504e86b9,
+            timestamp: std::time::SystemTime::now(),
+            difficulty: 1.0,
+            status: crate::pool::ShareStatus::Pending,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_shares_pipelines_requests() {
+        let client = client_with_job().await;
+        let (client_out, pool_in) = tokio::io::duplex(64 * 1024);
+        let (mut pool_out, client_in) = tokio::io::duplex(64 * 1024);
+        *client.writer.lock().await = Some(Box::new(client_out));
+        *client.reader.lock().await = Some(Box::new(client_in));
+        *client.connected.write().await = true;
+        client.start_message_loop().await.unwrap();
+
+        // 矿池收齐全部请求后才应答 (倒序)，只有流水线发送时才不会超时
+        let pool = tokio::spawn(async move {
+            let mut lines = BufReader::new(pool_in).lines();
+            let mut requests = Vec::new();
+            for _ in 0..3 {
+                let line = lines.next_line().await.unwrap().unwrap();
+                requests.push(serde_json::from_str::<StratumMessage>(&line).unwrap());
+            }
+            for request in requests.iter().rev() {
+                let nonce = request.params.as_ref().unwrap()[4].as_str().unwrap().to_string();
+                let response = json!({ "id": request.id, "result": nonce != "00000002", "error": null });
+                pool_out.write_all(format!("{}\n", response).as_bytes()).await.unwrap();
+            }
+            pool_out.flush().await.unwrap();
+            requests
+        });
+
+        let mut shares = vec![test_share(1), test_share(2), test_share(3)];
+        shares.insert(1, Share { extra_nonce2: String::new(), ..test_share(9) });
+        let outcomes = tokio::time::timeout(Duration::from_secs(5), client.submit_shares(&shares)).await.unwrap();
+
+        let requests = pool.await.unwrap();
+        assert!(requests.iter().all(|r| r.method.as_deref() == Some("mining.submit")));
+        assert!(matches!(outcomes[0], Ok(true)));
+        assert!(matches!(outcomes[1], Err(PoolError::ProtocolError { .. })));
+        assert!(matches!(outcomes[2], Ok(false)));
+        assert!(matches!(outcomes[3], Ok(true)));
+        assert!(client.pending_requests.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_quirks_shape_session() {
+        use crate::pool::quirks::{PoolQuirksConfig, QuirkProfile};
+
+        let mut client = StratumClient::new(
+            "stratum+tcp://pool.example.com:3333".to_string(),
+            "worker".to_string(), "x".to_string(), 0, false, None,
+        ).await.unwrap();
+        let config = PoolQuirksConfig { profile: QuirkProfile::Classic, extranonce2_size: Some(8), ..Default::default() };
+        client.set_quirks(PoolQuirks::resolve(&client.url, Some(&config)));
+
+        let (client_out, pool_in) = tokio::io::duplex(64 * 1024);
+        let (mut pool_out, client_in) = tokio::io::duplex(64 * 1024);
+        *client.writer.lock().await = Some(Box::new(client_out));
+        *client.reader.lock().await = Some(Box::new(client_in));
+        *client.connected.write().await = true;
+        client.start_message_loop().await.unwrap();
+
+        // 订阅响应中没有 extranonce2_size，使用差异配置中的值
+        let pool = tokio::spawn(async move {
+            let mut lines = BufReader::new(pool_in).lines();
+            let request: StratumMessage = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+            let response = json!({ "id": request.id, "result": [[], "08000002"], "error": null });
+            pool_out.write_all(format!("{}\n", response).as_bytes()).await.unwrap();
+            pool_out.flush().await.unwrap();
+            (request, lines, pool_out)
+        });
+        client.subscribe().await.unwrap();
+        assert_eq!(*client.extra_nonce2_size.read().await, 8);
+        // classic 配置不发送 mining.ping
+        client.ping().await.unwrap();
+
+        let (request, mut lines, mut pool_out) = pool.await.unwrap();
+        assert_eq!(request.params.unwrap()[0], json!("cgminer/4.12.1"));
+
+        let notification = json!({ "id": null, "method": "mining.set_extranonce", "params": ["0a0b0c0d", 6] });
+        pool_out.write_all(format!("{}\n", notification).as_bytes()).await.unwrap();
+        pool_out.flush().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while client.extra_nonce1.read().await.as_deref() != Some("0a0b0c0d") {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        assert_eq!(*client.extra_nonce2_size.read().await, 6);
+
+        drop(client);
+        assert!(lines.next_line().await.unwrap().is_none(), "ping must not be sent when disabled");
+    }
+}
@@ -1,6 +1,14 @@
 //! 智能矿池切换器
+//!
+//! 除按延迟和接受率评分的 [`PoolSwitcher`] 外，还包含按收益切换的 [`ProfitSwitcher`]
+//! (`[pools.profit_switch]`)：按外部收益数据或各矿池的静态费率计算每个矿池的相对收益，
+//! 其它矿池持续 `hold_secs` 高出当前矿池 `threshold_percent` 时切换，两次切换至少间隔
+//! `cooldown_secs`，避免在收益接近的矿池之间来回切换。
 
+use crate::config::PoolInfo;
 use crate::error::PoolError;
+use crate::mining::Algorithm;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
@@ -395,3 +403,219 @@ impl SwitchStats {
         }
     }
 }
+
+/// 按收益切换配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfitSwitchConfig {
+    /// 是否按收益切换矿池
+    pub enabled: bool,
+    /// 收益数据地址，返回 `{"<profit_key>": 收益, ...}` 形式的 JSON；未设置时只按各矿池的 `fee` 比较
+    pub source_url: Option<String>,
+    /// 检查间隔 (秒)
+    pub interval_secs: u64,
+    /// 其它矿池的收益至少高出当前矿池的比例 (%)
+    pub threshold_percent: f64,
+    /// 高出需要持续的时间 (秒)
+    pub hold_secs: u64,
+    /// 两次切换的最小间隔 (秒)
+    pub cooldown_secs: u64,
+}
+
+impl Default for ProfitSwitchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source_url: None,
+            interval_secs: 300,
+            threshold_percent: 5.0,
+            hold_secs: 900,
+            cooldown_secs: 3600,
+        }
+    }
+}
+
+/// 收益数据请求超时
+const PROFIT_SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 从收益数据地址获取各 `profit_key` 的收益，非数值的项被忽略
+pub async fn fetch_profitability(http: &reqwest::Client, url: &str) -> Result<HashMap<String, f64>, PoolError> {
+    let response = http.get(url)
+        .timeout(PROFIT_SOURCE_TIMEOUT)
+        .send().await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| PoolError::ConnectionFailed { url: url.to_string(), error: e.to_string() })?;
+    let body: serde_json::Value = response.json().await
+        .map_err(|e| PoolError::ProtocolError { url: url.to_string(), error: e.to_string() })?;
+    parse_profitability(&body)
+        .ok_or_else(|| PoolError::ProtocolError { url: url.to_string(), error: "expected a JSON object of numbers".to_string() })
+}
+
+fn parse_profitability(body: &serde_json::Value) -> Option<HashMap<String, f64>> {
+    Some(body.as_object()?.iter()
+        .filter_map(|(key, value)| value.as_f64().map(|profit| (key.clone(), profit)))
+        .collect())
+}
+
+/// 各矿池扣除费率后的相对收益，只包含启用且与 `algorithm` 相同的矿池。
+/// 有收益数据时按 `profit_key` (默认矿池名称) 取值，数据中没有的矿池不参与比较；
+/// 没有收益数据时各矿池的基础收益相同，只比较费率。
+pub fn pool_profitability(
+    pools: &[PoolInfo],
+    algorithm: Algorithm,
+    quotes: Option<&HashMap<String, f64>>,
+) -> HashMap<u32, f64> {
+    pools.iter().enumerate()
+        .filter(|(_, pool)| pool.enabled && pool.algorithm == algorithm)
+        .filter_map(|(index, pool)| {
+            let base = match quotes {
+                Some(quotes) => *quotes.get(pool.profit_key.as_ref().or(pool.name.as_ref())?)?,
+                None => 1.0,
+            };
+            Some((index as u32, base * (1.0 - pool.fee.unwrap_or(0.0) / 100.0)))
+        })
+        .collect()
+}
+
+/// 按收益切换的决策：记录持续领先的矿池和上次切换时间
+pub struct ProfitSwitcher {
+    config: ProfitSwitchConfig,
+    /// 当前领先的矿池及开始领先的时间
+    leader: Option<(u32, Instant)>,
+    last_switch: Option<Instant>,
+}
+
+impl ProfitSwitcher {
+    pub fn new(config: ProfitSwitchConfig) -> Self {
+        Self {
+            config,
+            leader: None,
+            last_switch: None,
+        }
+    }
+
+    /// 返回应切换到的矿池；当前矿池没有收益数据时不切换
+    pub fn evaluate(&mut self, current: u32, profits: &HashMap<u32, f64>, now: Instant) -> Option<u32> {
+        let Some(&current_profit) = profits.get(&current) else {
+            self.leader = None;
+            return None;
+        };
+        let required = current_profit * (1.0 + self.config.threshold_percent / 100.0);
+        let best = profits.iter()
+            .filter(|(pool_id, profit)| **pool_id != current && **profit > required)
+            .max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(pool_id, _)| *pool_id);
+
+        let Some(best) = best else {
+            self.leader = None;
+            return None;
+        };
+        let since = match self.leader {
+            Some((pool_id, since)) if pool_id == best => since,
+            _ => {
+                debug!("Pool {} is now more profitable than pool {}", best, current);
+                self.leader = Some((best, now));
+                now
+            }
+        };
+
+        let held = now.duration_since(since) >= Duration::from_secs(self.config.hold_secs);
+        let cooled = self.last_switch
+            .map_or(true, |last| now.duration_since(last) >= Duration::from_secs(self.config.cooldown_secs));
+        (held && cooled).then_some(best)
+    }
+
+    /// 切换完成后调用，开始冷却
+    pub fn record_switch(&mut self, now: Instant) {
+        self.last_switch = Some(now);
+        self.leader = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(name: &str, fee: Option<f64>) -> PoolInfo {
+        PoolInfo {
+            name: Some(name.to_string()),
+            url: format!("stratum+tcp://{}.example.com:3333", name),
+            username: "worker".to_string(),
+            password: "x".to_string(),
+            priority: 1,
+            quota: None,
+            enabled: true,
+            proxy: None,
+            algorithm: Algorithm::Sha256d,
+            protocol: Default::default(),
+            quirks: None,
+            fee,
+            profit_key: None,
+        }
+    }
+
+    fn config() -> ProfitSwitchConfig {
+        ProfitSwitchConfig {
+            enabled: true,
+            threshold_percent: 5.0,
+            hold_secs: 600,
+            cooldown_secs: 3600,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pool_profitability() {
+        let mut pools = vec![pool("a", Some(2.0)), pool("b", None), pool("c", Some(1.0))];
+        pools[2].profit_key = Some("c-pps".to_string());
+
+        let profits = pool_profitability(&pools, Algorithm::Sha256d, None);
+        assert!((profits[&0] - 0.98).abs() < 1e-9);
+        assert!((profits[&1] - 1.0).abs() < 1e-9);
+
+        let quotes = parse_profitability(&serde_json::json!({ "a": 100.0, "c-pps": 110.0, "note": "x" })).unwrap();
+        let profits = pool_profitability(&pools, Algorithm::Sha256d, Some(&quotes));
+        assert_eq!(profits.len(), 2);
+        assert!((profits[&0] - 98.0).abs() < 1e-9);
+        assert!((profits[&2] - 108.9).abs() < 1e-9);
+
+        pools[1].algorithm = Algorithm::Scrypt;
+        assert!(!pool_profitability(&pools, Algorithm::Sha256d, None).contains_key(&1));
+    }
+
+    #[test]
+    fn test_switch_after_hold_time() {
+        let mut switcher = ProfitSwitcher::new(config());
+        let start = Instant::now();
+        let profits = HashMap::from([(0, 100.0), (1, 106.0), (2, 104.0)]);
+
+        // 领先需要持续 hold_secs
+        assert_eq!(switcher.evaluate(0, &profits, start), None);
+        assert_eq!(switcher.evaluate(0, &profits, start + Duration::from_secs(300)), None);
+        assert_eq!(switcher.evaluate(0, &profits, start + Duration::from_secs(600)), Some(1));
+
+        // 领先中断后重新计时
+        let mut switcher = ProfitSwitcher::new(config());
+        switcher.evaluate(0, &profits, start);
+        switcher.evaluate(0, &HashMap::from([(0, 100.0), (1, 103.0)]), start + Duration::from_secs(300));
+        assert_eq!(switcher.evaluate(0, &profits, start + Duration::from_secs(600)), None);
+        assert_eq!(switcher.evaluate(0, &profits, start + Duration::from_secs(900)), None);
+        assert_eq!(switcher.evaluate(0, &profits, start + Duration::from_secs(1200)), Some(1));
+    }
+
+    #[test]
+    fn test_cooldown_prevents_thrashing() {
+        let mut switcher = ProfitSwitcher::new(ProfitSwitchConfig { hold_secs: 0, ..config() });
+        let start = Instant::now();
+
+        assert_eq!(switcher.evaluate(0, &HashMap::from([(0, 100.0), (1, 110.0)]), start), Some(1));
+        switcher.record_switch(start);
+
+        let back = HashMap::from([(0, 120.0), (1, 110.0)]);
+        assert_eq!(switcher.evaluate(1, &back, start + Duration::from_secs(60)), None);
+        assert_eq!(switcher.evaluate(1, &back, start + Duration::from_secs(3600)), Some(0));
+
+        // 当前矿池没有收益数据时不切换
+        assert_eq!(switcher.evaluate(3, &back, start + Duration::from_secs(7200)), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! 控制操作审计 (`[security.audit]`)
+//!
+//! API 和仪表板上每个改变状态的请求记录一条：谁 (令牌、API 密钥或登录用户)、做了什么、
+//! 什么时候、结果，以及受影响资源改动前后的值。记录追加写入 JSON Lines 文件，
+//! 超过保留天数或条数上限的旧记录被清理。
+
+use crate::error::MiningError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// 单次查询最多返回的条数
+pub const MAX_QUERY_LIMIT: usize = 1000;
+
+/// 审计配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    /// 审计日志文件 (JSON Lines)
+    pub path: String,
+    /// 记录保留天数，0 表示不按时间清理
+    pub retention_days: u32,
+    /// 最多保留的记录数
+    pub max_entries: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "./data/audit.jsonl".to_string(),
+            retention_days: 90,
+            max_entries: 10_000,
+        }
+    }
+}
+
+impl AuditConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.path.is_empty() {
+            errors.push(("security.audit.path", "security.audit.path cannot be empty when auditing is enabled".to_string()));
+        }
+        if self.max_entries == 0 {
+            errors.push(("security.audit.max_entries", "security.audit.max_entries must be greater than 0".to_string()));
+        }
+        errors
+    }
+}
+
+/// 一条审计记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    /// 操作者：`token`、`api_key`、`key:<名称> (<id>)`、`user:<用户名>` 或 `anonymous`
+    pub actor: String,
+    pub method: String,
+    pub path: String,
+    /// 响应状态码
+    pub status: u16,
+    /// 请求体 (JSON，敏感字段已遮蔽)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    /// 资源改动前的值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<serde_json::Value>,
+    /// 资源改动后的值，请求失败时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<serde_json::Value>,
+}
+
+/// 查询条件，按时间倒序返回
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    /// 操作者包含该字符串
+    pub actor: Option<String>,
+    /// 路径前缀
+    pub path: Option<String>,
+    /// 起止时间 (Unix 秒，含)
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    /// 只返回失败的请求
+    #[serde(default)]
+    pub failed: bool,
+    /// 返回条数，默认 100，最多 `MAX_QUERY_LIMIT`
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.actor.as_deref().map_or(true, |actor| entry.actor.contains(actor))
+            && self.path.as_deref().map_or(true, |path| entry.path.starts_with(path))
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+            && self.until.map_or(true, |until| entry.timestamp <= until)
+            && (!self.failed || entry.status >= 400)
+    }
+}
+
+/// 请求的操作者，由认证中间件放入请求扩展
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor(pub String);
+
+impl Actor {
+    pub fn anonymous() -> Self {
+        Actor("anonymous".to_string())
+    }
+}
+
+struct AuditState {
+    entries: VecDeque<AuditEntry>,
+    next_id: u64,
+    /// 文件中的行数，比内存中多出太多时重写文件
+    file_lines: usize,
+}
+
+/// 审计日志
+pub struct AuditLog {
+    config: AuditConfig,
+    path: PathBuf,
+    state: Mutex<AuditState>,
+}
+
+impl AuditLog {
+    pub fn new(config: AuditConfig) -> Self {
+        Self {
+            path: PathBuf::from(&config.path),
+            config,
+            state: Mutex::new(AuditState { entries: VecDeque::new(), next_id: 1, file_lines: 0 }),
+        }
+    }
+
+    /// 读取审计文件并清理过期记录，返回保留的条数
+    pub async fn load(&self) -> Result<usize, MiningError> {
+        let text = match tokio::fs::read_to_string(&self.path).await {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(MiningError::System(format!("Failed to read {}: {}", self.path.display(), e))),
+        };
+        let mut state = self.state.lock().await;
+        state.entries.clear();
+        state.file_lines = 0;
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            state.file_lines += 1;
+            // 进程中断时最后一行可能不完整，跳过无法解析的行
+            match serde_json::from_str::<AuditEntry>(line) {
+                Ok(entry) => state.entries.push_back(entry),
+                Err(e) => warn!("Skipping invalid audit record in {}: {}", self.path.display(), e),
+            }
+        }
+        state.next_id = state.entries.back().map_or(1, |entry| entry.id + 1);
+        self.prune(&mut state, unix_now());
+        if state.file_lines > state.entries.len() {
+            self.rewrite(&mut state).await?;
+        }
+        Ok(state.entries.len())
+    }
+
+    /// 追加一条记录，`id` 和 `timestamp` 由日志填写
+    pub async fn record(&self, mut entry: AuditEntry) -> Result<(), MiningError> {
+        let mut state = self.state.lock().await;
+        entry.id = state.next_id;
+        entry.timestamp = unix_now();
+        state.next_id += 1;
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| MiningError::System(format!("Failed to encode audit record: {}", e)))?;
+        line.push('\n');
+        state.entries.push_back(entry);
+        self.prune(&mut state, unix_now());
+
+        // 清理掉的记录累积到上限的十分之一再重写文件，平时只追加
+        if state.file_lines >= state.entries.len() + self.config.max_entries / 10 {
+            return self.rewrite(&mut state).await;
+        }
+        self.append(&line).await?;
+        state.file_lines += 1;
+        Ok(())
+    }
+
+    pub async fn query(&self, query: &AuditQuery) -> Vec<AuditEntry> {
+        let limit = query.limit.unwrap_or(100).min(MAX_QUERY_LIMIT);
+        let state = self.state.lock().await;
+        state.entries.iter().rev().filter(|entry| query.matches(entry)).take(limit).cloned().collect()
+    }
+
+    fn prune(&self, state: &mut AuditState, now: u64) {
+        let cutoff = match self.config.retention_days {
+            0 => 0,
+            days => now.saturating_sub(days as u64 * 86_400),
+        };
+        while state.entries.len() > self.config.max_entries
+            || state.entries.front().map_or(false, |entry| entry.timestamp < cutoff)
+        {
+            state.entries.pop_front();
+        }
+    }
+
+    async fn append(&self, line: &str) -> Result<(), MiningError> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await
+                .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| MiningError::System(format!("Failed to open {}: {}", self.path.display(), e)))?;
+        file.write_all(line.as_bytes()).await
+            .map_err(|e| MiningError::System(format!("Failed to write {}: {}", self.path.display(), e)))
+    }
+
+    /// 用内存中的记录重写文件，先写临时文件再改名
+    async fn rewrite(&self, state: &mut AuditState) -> Result<(), MiningError> {
+        let mut text = String::new();
+        for entry in &state.entries {
+            let line = serde_json::to_string(entry)
+                .map_err(|e| MiningError::System(format!("Failed to encode audit record: {}", e)))?;
+            text.push_str(&line);
+            text.push('\n');
+        }
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await
+                .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let tmp = PathBuf::from(format!("{}.tmp", self.path.display()));
+        tokio::fs::write(&tmp, text).await
+            .map_err(|e| MiningError::System(format!("Failed to write {}: {}", tmp.display(), e)))?;
+        tokio::fs::rename(&tmp, &self.path).await
+            .map_err(|e| MiningError::System(format!("Failed to write {}: {}", self.path.display(), e)))?;
+        state.file_lines = state.entries.len();
+        Ok(())
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(actor: &str, path: &str, status: u16) -> AuditEntry {
+        AuditEntry {
+            id: 0,
+            timestamp: 0,
+            actor: actor.to_string(),
+            method: "POST".to_string(),
+            path: path.to_string(),
+            status,
+            request: None,
+            previous: Some(serde_json::json!({ "frequency": 500 })),
+            current: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_query_and_retention() {
+        let dir = std::env::temp_dir().join(format!("cgminer-audit-{}", std::process::id()));
+        let config = AuditConfig {
+            path: dir.join("audit.jsonl").display().to_string(),
+            max_entries: 3,
+            ..Default::default()
+        };
+        let log = AuditLog::new(config.clone());
+        assert_eq!(log.load().await.unwrap(), 0);
+
+        log.record(entry("user:admin", "/api/v1/devices/0/config", 200)).await.unwrap();
+        log.record(entry("key:grafana (key_1)", "/api/v1/settings", 400)).await.unwrap();
+        log.record(entry("token", "/api/v1/devices/1/restart", 200)).await.unwrap();
+        log.record(entry("token", "/api/v1/control", 200)).await.unwrap();
+
+        // 超过条数上限时丢弃最旧的记录
+        let all = log.query(&AuditQuery::default()).await;
+        assert_eq!(all.iter().map(|e| e.id).collect::<Vec<_>>(), vec![4, 3, 2]);
+
+        let devices = log.query(&AuditQuery { path: Some("/api/v1/devices".to_string()), ..Default::default() }).await;
+        assert_eq!(devices.len(), 1);
+        let failed = log.query(&AuditQuery { failed: true, ..Default::default() }).await;
+        assert_eq!(failed[0].actor, "key:grafana (key_1)");
+
+        // 重新读取文件
+        let reloaded = AuditLog::new(config);
+        assert_eq!(reloaded.load().await.unwrap(), 3);
+        reloaded.record(entry("token", "/api/v1/logging", 200)).await.unwrap();
+        assert_eq!(reloaded.query(&AuditQuery { limit: Some(1), ..Default::default() }).await[0].id, 5);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
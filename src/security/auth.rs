@@ -0,0 +1,297 @@
+//! API 密钥管理
+//!
+//! 每个密钥有名称和权限范围 (read / control / admin)，明文只在创建和轮换时返回一次，
+//! 存储中只保留 SHA-256 摘要，整个列表经 `SimpleSecurityManager` 加密写入 `security.api_keys_file`。
+//! 存在任意密钥后 API 即要求认证，请求用 `Authorization: ApiKey <key>` 或 `Bearer <key>` 携带。
+
+use crate::error::MiningError;
+use crate::security::SimpleSecurityManager;
+use axum::http::Method;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tracing::info;
+
+/// 密钥明文前缀
+const KEY_PREFIX: &str = "cgk_";
+
+/// 列表中显示的明文前缀长度
+const DISPLAY_PREFIX_LEN: usize = 8;
+
+/// 最多保存的密钥数
+pub const MAX_API_KEYS: usize = 100;
+
+/// 权限范围，高一级包含低一级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    /// 只读：状态、指标、配置查询
+    Read,
+    /// 控制：重启/启停设备、调参、矿池切换
+    Control,
+    /// 管理：修改和保存配置、管理 API 密钥、查看审计记录、运行时剖析
+    Admin,
+}
+
+impl ApiScope {
+    /// 请求需要的权限范围
+    pub fn required_for(method: &Method, path: &str) -> ApiScope {
+        let read_only = [Method::GET, Method::HEAD, Method::OPTIONS].contains(method);
+        if ["/api/v1/keys", "/api/v1/audit", "/debug/"].iter().any(|prefix| path.starts_with(prefix)) {
+            ApiScope::Admin
+        } else if read_only {
+            ApiScope::Read
+        } else if ["/api/v1/config", "/api/v1/logging"].iter().any(|prefix| path.starts_with(prefix)) {
+            ApiScope::Admin
+        } else {
+            ApiScope::Control
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Control => "control",
+            ApiScope::Admin => "admin",
+        }
+    }
+}
+
+/// 保存的密钥
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredKey {
+    id: String,
+    name: String,
+    scope: ApiScope,
+    /// 明文开头几位，便于辨认
+    prefix: String,
+    /// 明文的 SHA-256 (hex)
+    hash: String,
+    created_at: u64,
+    rotated_at: Option<u64>,
+    last_used: Option<u64>,
+}
+
+impl StoredKey {
+    fn info(&self) -> ApiKeyInfo {
+        ApiKeyInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            scope: self.scope,
+            prefix: self.prefix.clone(),
+            created_at: self.created_at,
+            rotated_at: self.rotated_at,
+            last_used: self.last_used,
+        }
+    }
+}
+
+/// 列表和接口返回的密钥信息，不含明文和摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyInfo {
+    pub id: String,
+    pub name: String,
+    pub scope: ApiScope,
+    pub prefix: String,
+    pub created_at: u64,
+    pub rotated_at: Option<u64>,
+    /// 最近一次使用的时间，进程重启前未写入存储的部分会丢失
+    pub last_used: Option<u64>,
+}
+
+/// 创建或轮换后返回的明文，只出现这一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuedKey {
+    pub key: ApiKeyInfo,
+    pub secret: String,
+}
+
+/// API 密钥存储
+pub struct ApiKeyStore {
+    security: Arc<SimpleSecurityManager>,
+    path: PathBuf,
+    keys: RwLock<Vec<StoredKey>>,
+}
+
+impl ApiKeyStore {
+    pub fn new(security: Arc<SimpleSecurityManager>, path: impl Into<PathBuf>) -> Self {
+        Self { security, path: path.into(), keys: RwLock::new(Vec::new()) }
+    }
+
+    /// 读取存储文件，不存在时为空
+    pub async fn load(&self) -> Result<usize, MiningError> {
+        let keys: Vec<StoredKey> = match self.security.load_encrypted(&self.path).await? {
+            Some(data) => serde_json::from_slice(&data)
+                .map_err(|e| MiningError::System(format!("Invalid API key store {}: {}", self.path.display(), e)))?,
+            None => Vec::new(),
+        };
+        let count = keys.len();
+        *self.keys.write().await = keys;
+        Ok(count)
+    }
+
+    async fn save(&self, keys: &[StoredKey]) -> Result<(), MiningError> {
+        let data = serde_json::to_vec(keys)
+            .map_err(|e| MiningError::System(format!("Failed to encode API keys: {}", e)))?;
+        self.security.save_encrypted(&self.path, &data).await
+    }
+
+    /// 是否存在密钥，存在时 API 要求认证
+    pub async fn has_keys(&self) -> bool {
+        !self.keys.read().await.is_empty()
+    }
+
+    pub async fn list(&self) -> Vec<ApiKeyInfo> {
+        self.keys.read().await.iter().map(StoredKey::info).collect()
+    }
+
+    pub async fn create(&self, name: &str, scope: ApiScope) -> Result<IssuedKey, MiningError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 64 {
+            return Err(MiningError::System("API key name must be 1-64 characters".to_string()));
+        }
+
+        let mut keys = self.keys.write().await;
+        if keys.len() >= MAX_API_KEYS {
+            return Err(MiningError::System(format!("At most {} API keys can be stored", MAX_API_KEYS)));
+        }
+        let secret = generate_secret();
+        let key = StoredKey {
+            id: format!("key_{}", hex::encode(random_bytes::<4>())),
+            name: name.to_string(),
+            scope,
+            prefix: secret[..DISPLAY_PREFIX_LEN].to_string(),
+            hash: hash_secret(&secret),
+            created_at: unix_now(),
+            rotated_at: None,
+            last_used: None,
+        };
+        let info = key.info();
+        keys.push(key);
+        if let Err(e) = self.save(&keys).await {
+            keys.pop();
+            return Err(e);
+        }
+        info!("🔑 Created {} API key '{}' ({})", scope.as_str(), info.name, info.id);
+        Ok(IssuedKey { key: info, secret })
+    }
+
+    /// 换发新的明文，旧明文立即失效；密钥不存在时返回 None
+    pub async fn rotate(&self, id: &str) -> Result<Option<IssuedKey>, MiningError> {
+        let mut keys = self.keys.write().await;
+        let Some(index) = keys.iter().position(|key| key.id == id) else {
+            return Ok(None);
+        };
+        let previous = keys[index].clone();
+        let secret = generate_secret();
+        let key = &mut keys[index];
+        key.prefix = secret[..DISPLAY_PREFIX_LEN].to_string();
+        key.hash = hash_secret(&secret);
+        key.rotated_at = Some(unix_now());
+        let info = key.info();
+        if let Err(e) = self.save(&keys).await {
+            keys[index] = previous;
+            return Err(e);
+        }
+        info!("🔑 Rotated API key '{}' ({})", info.name, info.id);
+        Ok(Some(IssuedKey { key: info, secret }))
+    }
+
+    /// 吊销密钥，返回被吊销的密钥
+    pub async fn revoke(&self, id: &str) -> Result<Option<ApiKeyInfo>, MiningError> {
+        let mut keys = self.keys.write().await;
+        let Some(index) = keys.iter().position(|key| key.id == id) else {
+            return Ok(None);
+        };
+        let removed = keys.remove(index);
+        if let Err(e) = self.save(&keys).await {
+            keys.insert(index, removed);
+            return Err(e);
+        }
+        info!("🔑 Revoked API key '{}' ({})", removed.name, removed.id);
+        Ok(Some(removed.info()))
+    }
+
+    /// 校验明文，返回对应的密钥
+    pub async fn authenticate(&self, secret: &str) -> Option<ApiKeyInfo> {
+        if !secret.starts_with(KEY_PREFIX) {
+            return None;
+        }
+        let hash = hash_secret(secret);
+        let mut keys = self.keys.write().await;
+        let key = keys.iter_mut().find(|key| key.hash == hash)?;
+        key.last_used = Some(unix_now());
+        Some(key.info())
+    }
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn generate_secret() -> String {
+    format!("{}{}", KEY_PREFIX, URL_SAFE_NO_PAD.encode(random_bytes::<24>()))
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(ApiScope::required_for(&Method::GET, "/api/v1/status"), ApiScope::Read);
+        assert_eq!(ApiScope::required_for(&Method::POST, "/api/v1/devices/0/restart"), ApiScope::Control);
+        assert_eq!(ApiScope::required_for(&Method::GET, "/api/v1/config"), ApiScope::Read);
+        assert_eq!(ApiScope::required_for(&Method::POST, "/api/v1/config/save"), ApiScope::Admin);
+        assert_eq!(ApiScope::required_for(&Method::GET, "/api/v1/keys"), ApiScope::Admin);
+        assert_eq!(ApiScope::required_for(&Method::GET, "/api/v1/audit"), ApiScope::Admin);
+        assert_eq!(ApiScope::required_for(&Method::GET, "/debug/tasks"), ApiScope::Admin);
+        assert!(ApiScope::Admin >= ApiScope::Control && ApiScope::Control >= ApiScope::Read);
+    }
+
+    #[tokio::test]
+    async fn test_key_lifecycle() {
+        let dir = std::env::temp_dir().join(format!("cgminer-api-keys-{}", std::process::id()));
+        let security = Arc::new(SimpleSecurityManager::new(dir.join("secret.key")));
+        let store = ApiKeyStore::new(security.clone(), dir.join("keys.enc"));
+        assert!(!store.has_keys().await);
+
+        let issued = store.create("grafana", ApiScope::Read).await.unwrap();
+        assert!(issued.secret.starts_with(KEY_PREFIX));
+        assert!(issued.secret.starts_with(&issued.key.prefix));
+        assert_eq!(store.authenticate(&issued.secret).await.map(|key| key.scope), Some(ApiScope::Read));
+        assert_eq!(store.authenticate("cgk_wrong").await.map(|key| key.scope), None);
+
+        let rotated = store.rotate(&issued.key.id).await.unwrap().unwrap();
+        assert_eq!(store.authenticate(&issued.secret).await.map(|key| key.scope), None);
+        assert_eq!(store.authenticate(&rotated.secret).await.map(|key| key.scope), Some(ApiScope::Read));
+
+        // 重新读取加密存储
+        let reloaded = ApiKeyStore::new(security, dir.join("keys.enc"));
+        assert_eq!(reloaded.load().await.unwrap(), 1);
+        assert_eq!(reloaded.authenticate(&rotated.secret).await.map(|key| key.scope), Some(ApiScope::Read));
+        assert!(reloaded.revoke(&issued.key.id).await.unwrap().is_some());
+        assert!(reloaded.revoke(&issued.key.id).await.unwrap().is_none());
+        assert!(!reloaded.has_keys().await);
+
+        assert!(store.create(" ", ApiScope::Admin).await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
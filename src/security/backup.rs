@@ -0,0 +1,261 @@
+//! 加密备份包 (`--create-backup` / `--restore-backup`)
+//!
+//! 一个文件打包主配置和所有 include 文件、落盘的状态 (统计历史、API 密钥和本机密钥、审计、
+//! 推送订阅) 以及设备身份映射。密钥由口令派生 (Argon2id + AES-256-GCM)，不依赖本机密钥文件，
+//! SD 卡损坏后在新系统上用同一口令即可恢复。
+
+use crate::config::{include, Config};
+use crate::error::MiningError;
+use crate::security::write_private;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 备份包文件头
+const BUNDLE_MAGIC: &[u8] = b"CGMRSBK1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// 备份包格式版本
+const BUNDLE_VERSION: u32 = 1;
+
+/// 备份包中的一个文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// 原路径，相对路径按运行目录解释
+    pub path: String,
+    /// 文件内容 (base64)
+    pub content: String,
+}
+
+/// 设备身份：设备 ID 对应的硬件和核心位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceIdentity {
+    pub device_id: u32,
+    pub name: String,
+    pub device_type: String,
+    pub chain_id: u8,
+    pub core_name: String,
+    /// 设备在核心中的索引
+    pub device_index: u32,
+}
+
+/// 备份包内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupBundle {
+    pub version: u32,
+    pub created_at: u64,
+    pub hostname: Option<String>,
+    /// 备份时使用的主配置文件
+    pub config_path: String,
+    pub files: Vec<BackupFile>,
+    /// 最近一次运行记录的设备身份映射
+    pub devices: Vec<DeviceIdentity>,
+}
+
+impl BackupBundle {
+    /// 收集配置文件和状态文件，不存在的状态文件跳过
+    pub fn collect(config_path: &str, config: &Config) -> Result<Self, MiningError> {
+        let mut paths = include::layered_files(Path::new(config_path))
+            .map_err(|e| MiningError::System(format!("{:#}", e)))?;
+        paths.extend(state_files(config).into_iter().map(PathBuf::from).filter(|path| path.is_file()));
+
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            let content = std::fs::read(&path)
+                .map_err(|e| MiningError::System(format!("Failed to read {}: {}", path.display(), e)))?;
+            let path = path.display().to_string();
+            if files.iter().any(|file: &BackupFile| file.path == path) {
+                continue;
+            }
+            files.push(BackupFile { path, content: base64::engine::general_purpose::STANDARD.encode(content) });
+        }
+
+        let devices = if config.security.device_map_file.is_empty() {
+            Vec::new()
+        } else {
+            load_device_map(Path::new(&config.security.device_map_file))?
+        };
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            hostname: sysinfo::System::host_name(),
+            config_path: config_path.to_string(),
+            files,
+            devices,
+        })
+    }
+
+    /// 写回所有文件 (权限 0600)，返回写入的路径
+    pub fn restore(&self) -> Result<Vec<PathBuf>, MiningError> {
+        let mut restored = Vec::with_capacity(self.files.len());
+        for file in &self.files {
+            let path = PathBuf::from(&file.path);
+            // 备份包来自别的机器，不允许写到运行目录之外的相对路径
+            if path.components().any(|component| component == Component::ParentDir) {
+                return Err(MiningError::System(format!("Refusing to restore {}: path contains '..'", file.path)));
+            }
+            let content = base64::engine::general_purpose::STANDARD.decode(&file.content)
+                .map_err(|e| MiningError::System(format!("Corrupted entry {} in backup: {}", file.path, e)))?;
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+            }
+            let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+            write_private(&tmp, &content)?;
+            std::fs::rename(&tmp, &path)
+                .map_err(|e| MiningError::System(format!("Failed to write {}: {}", path.display(), e)))?;
+            restored.push(path);
+        }
+        Ok(restored)
+    }
+
+    /// 序列化并用口令加密：文件头 + salt + nonce + 密文
+    pub fn seal(&self, passphrase: &str) -> Result<Vec<u8>, MiningError> {
+        let plaintext = serde_json::to_vec(self)
+            .map_err(|e| MiningError::System(format!("Failed to encode backup: {}", e)))?;
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = cipher(passphrase, &salt)?
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| MiningError::System("Encryption failed".to_string()))?;
+        Ok([BUNDLE_MAGIC, &salt, &nonce, &ciphertext].concat())
+    }
+
+    pub fn open(data: &[u8], passphrase: &str) -> Result<Self, MiningError> {
+        let header = BUNDLE_MAGIC.len() + SALT_LEN + NONCE_LEN;
+        if data.len() < header || !data.starts_with(BUNDLE_MAGIC) {
+            return Err(MiningError::System("Not a cgminer-rs backup bundle".to_string()));
+        }
+        let (salt, rest) = data[BUNDLE_MAGIC.len()..].split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let plaintext = cipher(passphrase, salt)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| MiningError::System("Decryption failed: wrong passphrase or corrupted bundle".to_string()))?;
+        let bundle: Self = serde_json::from_slice(&plaintext)
+            .map_err(|e| MiningError::System(format!("Invalid backup contents: {}", e)))?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(MiningError::System(format!(
+                "Backup format version {} is newer than this build supports ({})", bundle.version, BUNDLE_VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+}
+
+/// 配置中指向的落盘状态文件
+fn state_files(config: &Config) -> Vec<&str> {
+    let security = &config.security;
+    let mut files = vec![
+        config.monitoring.history.path.as_str(),
+        config.monitoring.push.subscriptions_file.as_str(),
+        security.key_file.as_str(),
+        security.api_keys_file.as_str(),
+        security.audit.path.as_str(),
+        security.device_map_file.as_str(),
+    ];
+    files.extend(config.api.tls_cert.as_deref());
+    files.extend(config.api.tls_key.as_deref());
+    files.retain(|path| !path.is_empty());
+    files
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, MiningError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| MiningError::System(format!("Failed to derive backup key: {}", e)))?;
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+/// 读取设备身份映射，文件不存在时为空
+pub fn load_device_map(path: &Path) -> Result<Vec<DeviceIdentity>, MiningError> {
+    match std::fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data)
+            .map_err(|e| MiningError::System(format!("Invalid device map {}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(MiningError::System(format!("Failed to read {}: {}", path.display(), e))),
+    }
+}
+
+/// 保存设备身份映射，先写临时文件再改名
+pub async fn save_device_map(path: &Path, devices: &[DeviceIdentity]) -> Result<(), MiningError> {
+    let data = serde_json::to_vec_pretty(devices)
+        .map_err(|e| MiningError::System(format!("Failed to encode device map: {}", e)))?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir).await
+            .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+    tokio::fs::write(&tmp, data).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", tmp.display(), e)))?;
+    tokio::fs::rename(&tmp, path).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// 与上次记录相比位置或硬件变化的设备
+pub fn device_map_changes(previous: &[DeviceIdentity], current: &[DeviceIdentity]) -> Vec<String> {
+    let mut changes = Vec::new();
+    for before in previous {
+        match current.iter().find(|device| device.device_id == before.device_id) {
+            None => changes.push(format!("Device {} ({}) is no longer present", before.device_id, before.name)),
+            Some(now) if now != before => changes.push(format!(
+                "Device {} was {} on {}[{}], now {} on {}[{}]",
+                before.device_id, before.name, before.core_name, before.device_index,
+                now.name, now.core_name, now.device_index
+            )),
+            Some(_) => {}
+        }
+    }
+    for now in current.iter().filter(|now| !previous.iter().any(|before| before.device_id == now.device_id)) {
+        changes.push(format!("Device {} ({}) is new", now.device_id, now.name));
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_and_restore() {
+        let dir = std::env::temp_dir().join(format!("cgminer-backup-{}", std::process::id()));
+        let config = dir.join("cgminer.toml");
+        let bundle = BackupBundle {
+            version: BUNDLE_VERSION,
+            created_at: 1,
+            hostname: None,
+            config_path: config.display().to_string(),
+            files: vec![BackupFile {
+                path: config.display().to_string(),
+                content: base64::engine::general_purpose::STANDARD.encode("[api]\nport = 4028\n"),
+            }],
+            devices: Vec::new(),
+        };
+
+        let sealed = bundle.seal("correct horse").unwrap();
+        assert!(!sealed.windows(4).any(|window| window == b"4028"));
+        assert!(BackupBundle::open(&sealed, "wrong").is_err());
+        let opened = BackupBundle::open(&sealed, "correct horse").unwrap();
+        assert_eq!(opened, bundle);
+
+        assert_eq!(opened.restore().unwrap(), vec![config.clone()]);
+        assert_eq!(std::fs::read_to_string(&config).unwrap(), "[api]\nport = 4028\n");
+
+        // 不允许写到上级目录
+        let escaping = BackupBundle {
+            files: vec![BackupFile { path: "../escape.toml".to_string(), content: String::new() }],
+            ..bundle
+        };
+        assert!(escaping.restore().is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+//! 配置文件防篡改
+//!
+//! 启动时记录主配置和所有 include 文件的 SHA-256，并把内容备份到 `security.backup_dir`。
+//! 运行中定期比对，发现程序之外的修改时触发告警，`security.on_tamper = "rollback"`
+//! 时还原为最后一次备份。程序自己保存配置后调用 `accept` 更新基准和备份。
+
+use crate::error::MiningError;
+use crate::monitoring::alerts::{Alert, AlertSeverity, AlertType};
+use crate::monitoring::MonitoringSystem;
+use crate::security::write_private;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// 发现配置被修改时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TamperPolicy {
+    /// 不检查
+    Ignore,
+    /// 只告警
+    #[default]
+    Alert,
+    /// 告警并还原最后一次备份
+    Rollback,
+}
+
+/// 一次检测到的修改
+#[derive(Debug, Clone, PartialEq)]
+pub struct TamperEvent {
+    pub path: PathBuf,
+    /// 修改后的摘要，文件被删除时为 None
+    pub digest: Option<String>,
+    /// 是否已还原
+    pub restored: bool,
+}
+
+struct Baseline {
+    path: PathBuf,
+    digest: String,
+    backup: PathBuf,
+    /// 已告警过的摘要，同一次修改只告警一次
+    reported: Option<Option<String>>,
+}
+
+/// 配置文件守护
+pub struct ConfigGuard {
+    policy: TamperPolicy,
+    interval: Duration,
+    files: Mutex<Vec<Baseline>>,
+}
+
+impl ConfigGuard {
+    /// 记录基准摘要并备份文件
+    pub async fn new(
+        policy: TamperPolicy,
+        interval: Duration,
+        backup_dir: impl AsRef<Path>,
+        files: Vec<PathBuf>,
+    ) -> Result<Self, MiningError> {
+        let backup_dir = backup_dir.as_ref();
+        tokio::fs::create_dir_all(backup_dir).await
+            .map_err(|e| MiningError::System(format!("Failed to create {}: {}", backup_dir.display(), e)))?;
+
+        let mut baselines = Vec::with_capacity(files.len());
+        for path in files {
+            let content = read(&path).await?;
+            // 不同目录下可能有同名文件，备份名带上路径摘要
+            let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let tag = &digest(path.to_string_lossy().as_bytes())[..8];
+            let backup = backup_dir.join(format!("{}-{}.bak", tag, file_name));
+            write_backup(&backup, &content).await?;
+            baselines.push(Baseline { digest: digest(&content), path, backup, reported: None });
+        }
+        Ok(Self { policy, interval, files: Mutex::new(baselines) })
+    }
+
+    /// 程序主动修改了配置文件，以当前内容为新的基准和备份
+    pub async fn accept(&self, path: impl AsRef<Path>) -> Result<(), MiningError> {
+        let path = path.as_ref();
+        let mut files = self.files.lock().await;
+        let Some(baseline) = files.iter_mut().find(|baseline| same_file(&baseline.path, path)) else {
+            return Ok(());
+        };
+        let content = read(&baseline.path).await?;
+        write_backup(&baseline.backup, &content).await?;
+        baseline.digest = digest(&content);
+        baseline.reported = None;
+        Ok(())
+    }
+
+    /// 比对所有文件，返回新发现的修改
+    pub async fn check(&self) -> Vec<TamperEvent> {
+        let mut events = Vec::new();
+        let mut files = self.files.lock().await;
+        for baseline in files.iter_mut() {
+            let current = tokio::fs::read(&baseline.path).await.ok().map(|content| digest(&content));
+            if current.as_deref() == Some(baseline.digest.as_str()) {
+                baseline.reported = None;
+                continue;
+            }
+            if baseline.reported.as_ref() == Some(&current) {
+                continue;
+            }
+
+            let restored = self.policy == TamperPolicy::Rollback && match restore(baseline).await {
+                Ok(()) => true,
+                Err(e) => {
+                    error!("❌ Failed to restore {}: {}", baseline.path.display(), e);
+                    false
+                }
+            };
+            if !restored {
+                baseline.reported = Some(current.clone());
+            }
+            events.push(TamperEvent { path: baseline.path.clone(), digest: current, restored });
+        }
+        events
+    }
+
+    /// 定期检查，发现修改时触发严重告警
+    pub fn start(self: Arc<Self>, monitoring: Arc<MonitoringSystem>) {
+        info!("🛡️ Watching {} config file(s) for external changes (on_tamper = {:?})",
+              self.files.try_lock().map(|files| files.len()).unwrap_or(0), self.policy);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                for event in self.check().await {
+                    let action = if event.restored { "restored the last backup" } else { "left the file as is" };
+                    let change = match &event.digest {
+                        Some(digest) => format!("now sha256 {}", &digest[..16]),
+                        None => "file was removed".to_string(),
+                    };
+                    warn!("🛡️ Config file {} was modified outside cgminer-rs ({}); {}", event.path.display(), change, action);
+                    let alert = Alert::new(
+                        AlertType::System,
+                        AlertSeverity::Critical,
+                        "Config File Modified".to_string(),
+                        format!("{} was modified outside cgminer-rs ({}); {}", event.path.display(), change, action),
+                        "security".to_string(),
+                    )
+                    .with_label("path".to_string(), event.path.display().to_string())
+                    .with_label("restored".to_string(), event.restored.to_string());
+                    monitoring.raise_alert(alert).await;
+                }
+            }
+        });
+    }
+}
+
+async fn read(path: &Path) -> Result<Vec<u8>, MiningError> {
+    tokio::fs::read(path).await
+        .map_err(|e| MiningError::System(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+/// 备份可能含矿池密码，只有属主可读
+async fn write_backup(path: &Path, content: &[u8]) -> Result<(), MiningError> {
+    let path = path.to_path_buf();
+    let content = content.to_vec();
+    tokio::task::spawn_blocking(move || write_private(&path, &content))
+        .await
+        .map_err(|e| MiningError::System(format!("Failed to write backup: {}", e)))?
+}
+
+/// 用备份覆盖配置文件，先写临时文件再改名
+async fn restore(baseline: &Baseline) -> Result<(), MiningError> {
+    let content = read(&baseline.backup).await?;
+    if digest(&content) != baseline.digest {
+        return Err(MiningError::System(format!("Backup {} does not match the recorded digest", baseline.backup.display())));
+    }
+    let tmp = PathBuf::from(format!("{}.tmp", baseline.path.display()));
+    tokio::fs::write(&tmp, &content).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", tmp.display(), e)))?;
+    tokio::fs::rename(&tmp, &baseline.path).await
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", baseline.path.display(), e)))
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
+}
+
+fn digest(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_detect_and_rollback() {
+        let dir = std::env::temp_dir().join(format!("cgminer-integrity-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = dir.join("cgminer.toml");
+        std::fs::write(&config, "[api]\nport = 4028\n").unwrap();
+
+        let alert_only = ConfigGuard::new(TamperPolicy::Alert, Duration::from_secs(1), dir.join("alert"), vec![config.clone()])
+            .await
+            .unwrap();
+        assert!(alert_only.check().await.is_empty());
+
+        // 外部修改只告警一次
+        std::fs::write(&config, "[api]\nport = 1\n").unwrap();
+        let events = alert_only.check().await;
+        assert_eq!(events.len(), 1);
+        assert!(!events[0].restored);
+        assert!(alert_only.check().await.is_empty());
+
+        // 程序自己保存后成为新的基准
+        alert_only.accept(&config).await.unwrap();
+        assert!(alert_only.check().await.is_empty());
+
+        let rollback = ConfigGuard::new(TamperPolicy::Rollback, Duration::from_secs(1), dir.join("rollback"), vec![config.clone()])
+            .await
+            .unwrap();
+        std::fs::remove_file(&config).unwrap();
+        let events = rollback.check().await;
+        assert_eq!(events, vec![TamperEvent { path: config.clone(), digest: None, restored: true }]);
+        assert_eq!(std::fs::read_to_string(&config).unwrap(), "[api]\nport = 1\n");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
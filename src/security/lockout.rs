@@ -0,0 +1,219 @@
+//! 登录暴力破解防护 (`[security.lockout]`)
+//!
+//! 按客户端 IP 统计认证失败 (仪表板登录和无效的 `Authorization` 头)，窗口内失败次数达到上限后
+//! 锁定该 IP，每次再被锁定时长加倍。失败和锁定作为 `SecurityEvent` 广播，锁定时触发告警。
+
+use crate::monitoring::alerts::{Alert, AlertSeverity, AlertType};
+use crate::monitoring::MonitoringSystem;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// 同时跟踪的 IP 上限，超出时先清理过期记录
+const MAX_TRACKED: usize = 10_000;
+
+/// 锁定配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockoutConfig {
+    pub enabled: bool,
+    /// 窗口内允许的失败次数
+    pub max_failures: u32,
+    /// 统计窗口 (秒)，也是最后一次失败后忘记该 IP 的时间
+    pub window_secs: u64,
+    /// 第一次锁定时长 (秒)，之后每次加倍
+    pub lockout_secs: u64,
+    /// 锁定时长上限 (秒)
+    pub max_lockout_secs: u64,
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_failures: 5,
+            window_secs: 900,
+            lockout_secs: 60,
+            max_lockout_secs: 3600,
+        }
+    }
+}
+
+impl LockoutConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.max_failures == 0 {
+            errors.push(("security.lockout.max_failures", "security.lockout.max_failures must be greater than 0".to_string()));
+        }
+        if self.window_secs == 0 {
+            errors.push(("security.lockout.window_secs", "security.lockout.window_secs must be greater than 0".to_string()));
+        }
+        if self.lockout_secs == 0 || self.lockout_secs > self.max_lockout_secs {
+            errors.push(("security.lockout.lockout_secs", format!(
+                "security.lockout.lockout_secs must be between 1 and max_lockout_secs ({})", self.max_lockout_secs
+            )));
+        }
+        errors
+    }
+}
+
+/// 认证相关的安全事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecurityEvent {
+    /// 一次认证失败，`failures` 为窗口内的累计次数
+    AuthFailed { ip: IpAddr, source: &'static str, failures: u32 },
+    /// IP 被锁定
+    LockedOut { ip: IpAddr, source: &'static str, failures: u32, duration: Duration },
+}
+
+#[derive(Debug)]
+struct Attempts {
+    failures: u32,
+    /// 已被锁定的次数，决定下一次锁定时长
+    lockouts: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+/// 按 IP 的失败计数和锁定状态
+pub struct LoginGuard {
+    config: LockoutConfig,
+    attempts: Mutex<HashMap<IpAddr, Attempts>>,
+    events: broadcast::Sender<SecurityEvent>,
+}
+
+impl LoginGuard {
+    pub fn new(config: LockoutConfig) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self { config, attempts: Mutex::new(HashMap::new()), events }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SecurityEvent> {
+        self.events.subscribe()
+    }
+
+    /// IP 被锁定时返回剩余时长
+    pub fn check(&self, ip: IpAddr) -> Option<Duration> {
+        if !self.config.enabled {
+            return None;
+        }
+        let attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        let until = attempts.get(&ip)?.locked_until?;
+        until.checked_duration_since(Instant::now()).filter(|remaining| !remaining.is_zero())
+    }
+
+    /// 记录一次失败，触发锁定时返回锁定时长
+    pub fn record_failure(&self, ip: IpAddr, source: &'static str) -> Option<Duration> {
+        if !self.config.enabled {
+            return None;
+        }
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.window_secs);
+        let mut all = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        if all.len() >= MAX_TRACKED && !all.contains_key(&ip) {
+            let max_lockout = Duration::from_secs(self.config.max_lockout_secs);
+            all.retain(|_, attempts| now.duration_since(attempts.last_failure) < window.max(max_lockout));
+        }
+
+        let attempts = all.entry(ip).or_insert(Attempts { failures: 0, lockouts: 0, last_failure: now, locked_until: None });
+        // 锁定结束后安静了一个窗口，重新开始计数
+        let quiet_since = attempts.locked_until.map_or(attempts.last_failure, |until| until.max(attempts.last_failure));
+        if now.saturating_duration_since(quiet_since) >= window {
+            attempts.failures = 0;
+            attempts.lockouts = 0;
+        }
+        attempts.failures += 1;
+        attempts.last_failure = now;
+        let failures = attempts.failures;
+
+        let event = if failures >= self.config.max_failures {
+            let factor = 1u64 << attempts.lockouts.min(20);
+            let duration = Duration::from_secs(self.config.lockout_secs.saturating_mul(factor).min(self.config.max_lockout_secs));
+            attempts.failures = 0;
+            attempts.lockouts += 1;
+            attempts.locked_until = Some(now + duration);
+            SecurityEvent::LockedOut { ip, source, failures, duration }
+        } else {
+            SecurityEvent::AuthFailed { ip, source, failures }
+        };
+        drop(all);
+
+        let locked = match &event {
+            SecurityEvent::LockedOut { duration, .. } => Some(*duration),
+            SecurityEvent::AuthFailed { .. } => None,
+        };
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.events.send(event);
+        locked
+    }
+
+    /// 认证成功，清除该 IP 的记录
+    pub fn record_success(&self, ip: IpAddr) {
+        self.attempts.lock().unwrap_or_else(|e| e.into_inner()).remove(&ip);
+    }
+}
+
+/// 把锁定事件转成告警，发送端 (`LoginGuard`) 释放后结束
+pub fn forward_alerts(mut events: broadcast::Receiver<SecurityEvent>, monitoring: Arc<MonitoringSystem>) {
+    tokio::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let SecurityEvent::LockedOut { ip, source, failures, duration } = event else {
+                continue;
+            };
+            warn!("🔒 Locked out {} for {}s after {} failed {} attempts", ip, duration.as_secs(), failures, source);
+            let alert = Alert::new(
+                AlertType::System,
+                AlertSeverity::Warning,
+                "Login Lockout".to_string(),
+                format!("{} was locked out for {}s after {} failed {} attempts", ip, duration.as_secs(), failures, source),
+                "security".to_string(),
+            )
+            .with_label("ip".to_string(), ip.to_string())
+            .with_label("source".to_string(), source.to_string());
+            monitoring.raise_alert(alert).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_lockout() {
+        let guard = LoginGuard::new(LockoutConfig { max_failures: 3, lockout_secs: 60, max_lockout_secs: 100, ..Default::default() });
+        let mut events = guard.subscribe();
+        let ip: IpAddr = "192.0.2.7".parse().unwrap();
+        let other: IpAddr = "192.0.2.8".parse().unwrap();
+
+        assert_eq!(guard.record_failure(ip, "login"), None);
+        assert_eq!(guard.record_failure(ip, "login"), None);
+        assert_eq!(guard.check(ip), None);
+        assert_eq!(guard.record_failure(ip, "login"), Some(Duration::from_secs(60)));
+        assert!(guard.check(ip).is_some());
+        assert_eq!(guard.check(other), None);
+        assert_eq!(events.try_recv().unwrap(), SecurityEvent::AuthFailed { ip, source: "login", failures: 1 });
+
+        // 第二次锁定加倍，但不超过上限
+        for _ in 0..2 {
+            guard.record_failure(ip, "api");
+        }
+        assert_eq!(guard.record_failure(ip, "api"), Some(Duration::from_secs(100)));
+
+        guard.record_success(ip);
+        assert_eq!(guard.check(ip), None);
+    }
+}
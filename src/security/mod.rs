@@ -0,0 +1,222 @@
+//! 安全模块 (`[security]`)
+//!
+//! `SimpleSecurityManager` 用本机密钥文件 (AES-256-GCM) 加密需要落盘的敏感状态，
+//! 密钥在第一次写入加密文件时生成。`auth` 管理带权限范围的 API 密钥，
+//! `audit` 记录改变状态的控制操作，`integrity` 检测配置文件被外部修改，
+//! `backup` 生成和恢复口令加密的备份包，`lockout` 锁定反复认证失败的 IP。
+
+pub mod audit;
+pub mod auth;
+pub mod backup;
+pub mod integrity;
+pub mod lockout;
+
+use crate::error::MiningError;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::info;
+
+pub use audit::{Actor, AuditConfig, AuditLog};
+pub use auth::{ApiKeyStore, ApiScope};
+pub use integrity::{ConfigGuard, TamperPolicy};
+pub use lockout::{LockoutConfig, LoginGuard, SecurityEvent};
+
+/// AES-GCM nonce 长度
+const NONCE_LEN: usize = 12;
+
+/// 安全配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    /// 本机加密密钥文件，不存在时自动生成 (权限 0600)
+    pub key_file: String,
+    /// API 密钥存储 (加密)，为空时不启用 API 密钥管理
+    pub api_keys_file: String,
+    /// 控制操作审计
+    pub audit: AuditConfig,
+    /// 认证失败锁定
+    pub lockout: LockoutConfig,
+    /// 配置文件被外部修改时：ignore 不检查、alert 告警、rollback 告警并还原备份
+    pub on_tamper: TamperPolicy,
+    /// 配置文件检查间隔 (秒)
+    pub tamper_check_interval_secs: u64,
+    /// 配置文件备份目录
+    pub backup_dir: String,
+    /// 设备身份映射，每次启动后更新并与上次比对，为空时不记录
+    pub device_map_file: String,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            key_file: "./data/secret.key".to_string(),
+            api_keys_file: "./data/api-keys.enc".to_string(),
+            audit: AuditConfig::default(),
+            lockout: LockoutConfig::default(),
+            on_tamper: TamperPolicy::Alert,
+            tamper_check_interval_secs: 10,
+            backup_dir: "./data/config-backup".to_string(),
+            device_map_file: "./data/device-map.json".to_string(),
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// 返回 (配置键, 错误信息) 列表
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.api_keys_file.is_empty() && self.key_file.is_empty() {
+            errors.push(("security.key_file", "security.key_file is required to encrypt api_keys_file".to_string()));
+        }
+        if self.on_tamper != TamperPolicy::Ignore {
+            if self.tamper_check_interval_secs == 0 {
+                errors.push(("security.tamper_check_interval_secs", "security.tamper_check_interval_secs must be greater than 0".to_string()));
+            }
+            if self.backup_dir.is_empty() {
+                errors.push(("security.backup_dir", "security.backup_dir is required unless on_tamper = \"ignore\"".to_string()));
+            }
+        }
+        errors.extend(self.audit.validate());
+        errors.extend(self.lockout.validate());
+        errors
+    }
+}
+
+/// 本机数据加密
+pub struct SimpleSecurityManager {
+    key_file: PathBuf,
+    cipher: Mutex<Option<Aes256Gcm>>,
+}
+
+impl SimpleSecurityManager {
+    pub fn new(key_file: impl Into<PathBuf>) -> Self {
+        Self { key_file: key_file.into(), cipher: Mutex::new(None) }
+    }
+
+    /// 读取密钥，`create` 时密钥文件不存在则生成
+    fn cipher(&self, create: bool) -> Result<Aes256Gcm, MiningError> {
+        let mut cipher = self.cipher.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cipher) = cipher.as_ref() {
+            return Ok(cipher.clone());
+        }
+        let key = match std::fs::read_to_string(&self.key_file) {
+            Ok(text) => hex::decode(text.trim()).ok().filter(|key| key.len() == 32).ok_or_else(|| {
+                MiningError::System(format!("{} is not a 64-character hex key", self.key_file.display()))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && create => self.create_key()?,
+            Err(e) => return Err(MiningError::System(format!("Failed to read {}: {}", self.key_file.display(), e))),
+        };
+        let created = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        *cipher = Some(created.clone());
+        Ok(created)
+    }
+
+    fn create_key(&self) -> Result<Vec<u8>, MiningError> {
+        let mut key = vec![0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        if let Some(dir) = self.key_file.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        write_private(&self.key_file, hex::encode(&key).as_bytes())?;
+        info!("🔑 Generated encryption key {}", self.key_file.display());
+        Ok(key)
+    }
+
+    /// 加密，输出为 nonce + 密文
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, MiningError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self.cipher(true)?
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| MiningError::System("Encryption failed".to_string()))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, MiningError> {
+        if data.len() < NONCE_LEN {
+            return Err(MiningError::System("Encrypted data is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        self.cipher(false)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| MiningError::System(format!(
+                "Decryption failed: wrong key ({}) or corrupted data", self.key_file.display()
+            )))
+    }
+
+    /// 读取加密文件，文件不存在时返回 None
+    pub async fn load_encrypted(&self, path: &Path) -> Result<Option<Vec<u8>>, MiningError> {
+        match tokio::fs::read(path).await {
+            Ok(data) => self.decrypt(&data)
+                .map(Some)
+                .map_err(|e| MiningError::System(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(MiningError::System(format!("Failed to read {}: {}", path.display(), e))),
+        }
+    }
+
+    /// 加密后写入，先写临时文件再改名
+    pub async fn save_encrypted(&self, path: &Path, plaintext: &[u8]) -> Result<(), MiningError> {
+        let data = self.encrypt(plaintext)?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await
+                .map_err(|e| MiningError::System(format!("Failed to create {}: {}", dir.display(), e)))?;
+        }
+        let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+        let written = tmp.clone();
+        tokio::task::spawn_blocking(move || write_private(&written, &data))
+            .await
+            .map_err(|e| MiningError::System(format!("Failed to write {}: {}", tmp.display(), e)))??;
+        tokio::fs::rename(&tmp, path).await
+            .map_err(|e| MiningError::System(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+/// 只有属主可读写的文件
+pub(crate) fn write_private(path: &Path, data: &[u8]) -> Result<(), MiningError> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|e| MiningError::System(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_encrypted_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cgminer-security-{}", std::process::id()));
+        let manager = SimpleSecurityManager::new(dir.join("secret.key"));
+        let path = dir.join("state.enc");
+
+        assert!(manager.load_encrypted(&path).await.unwrap().is_none());
+        manager.save_encrypted(&path, b"hello").await.unwrap();
+        assert_ne!(std::fs::read(&path).unwrap(), b"hello");
+
+        // 另一个实例从同一个密钥文件读取
+        let reopened = SimpleSecurityManager::new(dir.join("secret.key"));
+        assert_eq!(reopened.load_encrypted(&path).await.unwrap().unwrap(), b"hello");
+
+        // 换了密钥无法解密
+        let other = SimpleSecurityManager::new(dir.join("other.key"));
+        other.encrypt(b"x").unwrap();
+        assert!(other.load_encrypted(&path).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,209 @@
+//! Windows 服务
+//!
+//! `--install-service` 把矿机注册为开机自启的 Windows 服务 (需要 `win-service` 特性和管理员权限)，
+//! 服务控制管理器 (SCM) 以 `--run-as-service` 启动本程序。SCM 的停止/关机控制通过
+//! [`request_stop`] 触发与 Ctrl+C 相同的有序停机；服务进程不响应用户注销。
+//!
+//! 其他平台和未启用特性的构建中，安装/卸载返回错误，[`stop_requested`] 永远不会完成。
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tokio_util::sync::CancellationToken;
+
+/// 服务名 (`sc query cgminer-rs`)
+pub const SERVICE_NAME: &str = "cgminer-rs";
+/// 服务管理器中显示的名称
+pub const SERVICE_DISPLAY_NAME: &str = "CGMiner-RS Bitcoin Miner";
+const SERVICE_DESCRIPTION: &str = "Runs the CGMiner-RS mining application in the background";
+
+static RUNNING_AS_SERVICE: AtomicBool = AtomicBool::new(false);
+
+fn stop_token() -> &'static CancellationToken {
+    static STOP: OnceLock<CancellationToken> = OnceLock::new();
+    STOP.get_or_init(CancellationToken::new)
+}
+
+/// 本进程是否由服务控制管理器启动
+pub fn is_running_as_service() -> bool {
+    RUNNING_AS_SERVICE.load(Ordering::Acquire)
+}
+
+/// 请求有序停机 (服务收到停止或关机控制)
+pub fn request_stop() {
+    stop_token().cancel();
+}
+
+/// 等待停机请求
+pub async fn stop_requested() {
+    stop_token().cancelled().await
+}
+
+/// SCM 启动服务时传给本程序的参数；配置文件和 profile 都写成绝对路径/显式值，因为服务的工作目录是 System32
+pub fn service_arguments(config_path: &Path, profile: Option<&str>) -> Vec<OsString> {
+    let mut arguments = vec![
+        OsString::from("--run-as-service"),
+        OsString::from("--config"),
+        config_path.as_os_str().to_os_string(),
+    ];
+    if let Some(profile) = profile {
+        arguments.push(OsString::from("--profile"));
+        arguments.push(OsString::from(profile));
+    }
+    arguments
+}
+
+#[cfg(all(windows, feature = "win-service"))]
+mod platform {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use windows_service::service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+        ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    type Entry = Box<dyn FnOnce() + Send>;
+    static ENTRY: Mutex<Option<Entry>> = Mutex::new(None);
+
+    pub fn install(config_path: &Path, profile: Option<&str>) -> Result<(), String> {
+        let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)
+            .map_err(|e| format!("Failed to open the service manager (run as Administrator): {}", e))?;
+        let executable = std::env::current_exe().map_err(|e| format!("Failed to locate the executable: {}", e))?;
+        let info = ServiceInfo {
+            name: OsString::from(SERVICE_NAME),
+            display_name: OsString::from(SERVICE_DISPLAY_NAME),
+            service_type: ServiceType::OWN_PROCESS,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: executable,
+            launch_arguments: service_arguments(config_path, profile),
+            dependencies: vec![],
+            account_name: None,
+            account_password: None,
+        };
+        let service = manager.create_service(&info, ServiceAccess::CHANGE_CONFIG)
+            .map_err(|e| format!("Failed to create service {}: {}", SERVICE_NAME, e))?;
+        service.set_description(SERVICE_DESCRIPTION)
+            .map_err(|e| format!("Failed to set the service description: {}", e))?;
+        Ok(())
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+            .map_err(|e| format!("Failed to open the service manager (run as Administrator): {}", e))?;
+        let service = manager.open_service(SERVICE_NAME, ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE)
+            .map_err(|e| format!("Failed to open service {}: {}", SERVICE_NAME, e))?;
+        if let Ok(status) = service.query_status() {
+            if status.current_state != ServiceState::Stopped {
+                let _ = service.stop();
+            }
+        }
+        service.delete().map_err(|e| format!("Failed to delete service {}: {}", SERVICE_NAME, e))
+    }
+
+    pub fn run(entry: Entry) -> Result<(), String> {
+        RUNNING_AS_SERVICE.store(true, Ordering::Release);
+        *ENTRY.lock().unwrap() = Some(entry);
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+            .map_err(|e| format!("Failed to connect to the service manager: {}", e))
+    }
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    fn service_main(_arguments: Vec<OsString>) {
+        let handler = |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown | ServiceControl::Preshutdown => {
+                request_stop();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        };
+        let status_handle = match service_control_handler::register(SERVICE_NAME, handler) {
+            Ok(handle) => handle,
+            Err(_) => return,
+        };
+        let status = |state, controls_accepted| ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::from_secs(15),
+            process_id: None,
+        };
+
+        let _ = status_handle.set_service_status(status(ServiceState::Running, ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN));
+        if let Some(entry) = ENTRY.lock().unwrap().take() {
+            entry();
+        }
+        let _ = status_handle.set_service_status(status(ServiceState::Stopped, ServiceControlAccept::empty()));
+    }
+}
+
+#[cfg(not(all(windows, feature = "win-service")))]
+mod platform {
+    use super::*;
+
+    const UNSUPPORTED: &str = "This build cannot run as a Windows service; rebuild on Windows with --features=win-service";
+
+    pub fn install(_config_path: &Path, _profile: Option<&str>) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn run(_entry: Box<dyn FnOnce() + Send>) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+/// 注册开机自启的服务，以 `config_path` 的配置运行
+pub fn install(config_path: &Path, profile: Option<&str>) -> Result<(), String> {
+    platform::install(config_path, profile)
+}
+
+/// 停止并删除服务
+pub fn uninstall() -> Result<(), String> {
+    platform::uninstall()
+}
+
+/// 连接服务控制管理器并在服务线程中执行 `entry`，服务停止后返回
+pub fn run(entry: impl FnOnce() + Send + 'static) -> Result<(), String> {
+    platform::run(Box::new(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_arguments() {
+        let arguments = service_arguments(Path::new("/etc/cgminer/cgminer.toml"), Some("farm-a"));
+        let arguments: Vec<_> = arguments.iter().map(|a| a.to_string_lossy().into_owned()).collect();
+        assert_eq!(arguments, vec!["--run-as-service", "--config", "/etc/cgminer/cgminer.toml", "--profile", "farm-a"]);
+        assert_eq!(service_arguments(Path::new("cgminer.toml"), None).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stop_request_wakes_waiters() {
+        let waiter = tokio::spawn(stop_requested());
+        request_stop();
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        assert!(!is_running_as_service());
+    }
+
+    #[cfg(not(all(windows, feature = "win-service")))]
+    #[test]
+    fn test_unsupported_build() {
+        assert!(install(Path::new("cgminer.toml"), None).is_err());
+        assert!(run(|| ()).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! 终端界面的状态和按键处理
+
+use crate::config::PoolInfo;
+use crate::device::{DeviceInfo, DeviceStatus};
+use crate::mining::manager::SystemStatus;
+use crate::mining::MiningManager;
+use crate::monitoring::{PoolMetrics, RecentShare};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::{Duration, Instant};
+
+/// 状态栏消息的显示时长
+const MESSAGE_TTL: Duration = Duration::from_secs(5);
+
+/// 矿池一行
+#[derive(Debug, Clone)]
+pub struct PoolLine {
+    pub id: u32,
+    pub name: Option<String>,
+    pub url: String,
+    pub username: String,
+    pub metrics: Option<PoolMetrics>,
+}
+
+/// 一次刷新采集的数据，与 API 读取的是同一份挖矿管理器和监控状态
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub status: SystemStatus,
+    pub devices: Vec<DeviceInfo>,
+    pub pools: Vec<PoolLine>,
+    /// 最近的份额，从新到旧
+    pub shares: Vec<RecentShare>,
+}
+
+impl Snapshot {
+    pub async fn collect(manager: &MiningManager) -> Self {
+        let monitoring = manager.get_monitoring_system();
+        let mut devices = manager.get_all_device_info().await;
+        devices.sort_by_key(|d| d.id);
+
+        let metrics = monitoring.get_all_pool_metrics().await;
+        let pools = pool_lines(&manager.get_config().await.pools.pools, metrics);
+
+        Self {
+            status: manager.get_system_status().await,
+            devices,
+            pools,
+            shares: monitoring.get_recent_shares().await,
+        }
+    }
+}
+
+/// 配置中的矿池按序号与矿池指标对应
+fn pool_lines(pools: &[PoolInfo], metrics: Vec<PoolMetrics>) -> Vec<PoolLine> {
+    pools.iter().enumerate().map(|(i, pool)| {
+        let id = i as u32;
+        PoolLine {
+            id,
+            name: pool.name.clone(),
+            url: pool.url.clone(),
+            username: pool.username.clone(),
+            metrics: metrics.iter().find(|m| m.pool_id == id).cloned(),
+        }
+    }).collect()
+}
+
+/// 按键触发的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// 退出界面并停止挖矿
+    Quit,
+    /// 重启设备
+    Restart(u32),
+    /// 启用 (true) 或暂停 (false) 设备
+    SetEnabled(u32, bool),
+}
+
+/// 终端界面状态
+#[derive(Debug, Default)]
+pub struct App {
+    pub snapshot: Option<Snapshot>,
+    /// 选中的设备行
+    pub selected: usize,
+    /// 状态栏消息
+    message: Option<(String, Instant)>,
+}
+
+impl App {
+    pub fn update(&mut self, snapshot: Snapshot) {
+        self.selected = self.selected.min(snapshot.devices.len().saturating_sub(1));
+        self.snapshot = Some(snapshot);
+    }
+
+    pub fn selected_device(&self) -> Option<&DeviceInfo> {
+        self.snapshot.as_ref()?.devices.get(self.selected)
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some((message.into(), Instant::now()));
+    }
+
+    /// 未过期的状态栏消息
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref()
+            .filter(|(_, at)| at.elapsed() < MESSAGE_TTL)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// 处理按键，返回需要执行的操作
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let device_count = self.snapshot.as_ref().map(|s| s.devices.len()).unwrap_or(0);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < device_count {
+                    self.selected += 1;
+                }
+                None
+            }
+            KeyCode::Char('r') => self.selected_device().map(|d| Action::Restart(d.id)),
+            KeyCode::Char('p') => self.selected_device()
+                .map(|d| Action::SetEnabled(d.id, matches!(d.status, DeviceStatus::Disabled))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mining::MiningState;
+    use crossterm::event::KeyEventKind;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new_with_kind(code, KeyModifiers::NONE, KeyEventKind::Press)
+    }
+
+    fn snapshot(statuses: &[DeviceStatus]) -> Snapshot {
+        Snapshot {
+            status: SystemStatus {
+                state: MiningState::Running,
+                uptime: Duration::from_secs(60),
+                total_hashrate: 0.0,
+                accepted_shares: 0,
+                rejected_shares: 0,
+                hardware_errors: 0,
+                active_devices: statuses.len() as u32,
+                connected_pools: 1,
+                current_difficulty: 1.0,
+                best_share: 0.0,
+                efficiency: 0.0,
+                power_consumption: 0.0,
+            },
+            devices: statuses.iter().enumerate().map(|(i, status)| {
+                let mut device = DeviceInfo::new(i as u32, format!("dev-{}", i), "sim".to_string(), i as u8);
+                device.update_status(status.clone());
+                device
+            }).collect(),
+            pools: Vec::new(),
+            shares: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_device_hotkeys() {
+        let mut app = App::default();
+        assert_eq!(app.handle_key(key(KeyCode::Char('r'))), None);
+
+        app.update(snapshot(&[DeviceStatus::Mining, DeviceStatus::Disabled]));
+        assert_eq!(app.handle_key(key(KeyCode::Char('r'))), Some(Action::Restart(0)));
+        assert_eq!(app.handle_key(key(KeyCode::Char('p'))), Some(Action::SetEnabled(0, false)));
+
+        // 已暂停的设备按 p 恢复
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.selected, 1);
+        assert_eq!(app.handle_key(key(KeyCode::Char('p'))), Some(Action::SetEnabled(1, true)));
+
+        // 设备减少时选中行跟着收回
+        app.update(snapshot(&[DeviceStatus::Mining]));
+        assert_eq!(app.selected, 0);
+
+        assert_eq!(app.handle_key(key(KeyCode::Char('q'))), Some(Action::Quit));
+        let ctrl_c = KeyEvent::new_with_kind(KeyCode::Char('c'), KeyModifiers::CONTROL, KeyEventKind::Press);
+        assert_eq!(app.handle_key(ctrl_c), Some(Action::Quit));
+    }
+}
@@ -0,0 +1,116 @@
+//! 终端界面 (`--tui`，需要 `--features=tui`)
+//!
+//! 类似原版 cgminer 的 curses 界面：每台设备一行、矿池状态和份额日志，
+//! 数据直接读取挖矿管理器和监控系统，与 API 看到的一致。
+//! 快捷键可以暂停/恢复和重启选中的设备，退出界面即停止挖矿。
+
+pub mod app;
+mod ui;
+
+use crate::mining::MiningManager;
+use app::{Action, App, Snapshot};
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use futures::StreamExt;
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::{self, Stdout};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 界面刷新间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 终端恢复守卫，退出或 panic 时把终端还原
+struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalGuard {
+    fn new() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        if let Err(e) = execute!(stdout, EnterAlternateScreen) {
+            let _ = disable_raw_mode();
+            return Err(e);
+        }
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        Ok(Self { terminal })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// 运行终端界面，用户退出 (q / Esc / Ctrl+C) 时返回
+pub async fn run(mining_manager: Arc<MiningManager>) -> io::Result<()> {
+    let mut guard = TerminalGuard::new()?;
+    let mut app = App::default();
+    let mut events = EventStream::new();
+    let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+    // 设备操作在后台执行，结果回传到状态栏
+    let (result_tx, mut result_rx) = mpsc::unbounded_channel::<String>();
+
+    loop {
+        guard.terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        tokio::select! {
+            _ = ticker.tick() => {
+                app.update(Snapshot::collect(&mining_manager).await);
+            }
+            Some(message) = result_rx.recv() => {
+                app.set_message(message);
+            }
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    match app.handle_key(key) {
+                        Some(Action::Quit) => break,
+                        Some(action) => {
+                            app.set_message(describe(&action, None));
+                            spawn_action(mining_manager.clone(), action, result_tx.clone());
+                        }
+                        None => {}
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => break,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+fn spawn_action(mining_manager: Arc<MiningManager>, action: Action, result_tx: mpsc::UnboundedSender<String>) {
+    tokio::spawn(async move {
+        let result = match action {
+            Action::Restart(device_id) => mining_manager.restart_device(device_id).await,
+            Action::SetEnabled(device_id, enabled) => mining_manager.set_device_enabled(device_id, enabled).await,
+            Action::Quit => return,
+        };
+        let _ = result_tx.send(describe(&action, Some(result.map_err(|e| e.to_string()))));
+    });
+}
+
+/// 状态栏上的操作说明，`result` 为空表示正在执行
+fn describe(action: &Action, result: Option<Result<(), String>>) -> String {
+    let what = match action {
+        Action::Restart(id) => format!("Restart device {}", id),
+        Action::SetEnabled(id, true) => format!("Resume device {}", id),
+        Action::SetEnabled(id, false) => format!("Pause device {}", id),
+        Action::Quit => "Quit".to_string(),
+    };
+    match result {
+        None => format!("{}...", what),
+        Some(Ok(())) => format!("{}: done", what),
+        Some(Err(e)) => format!("{}: failed ({})", what, e),
+    }
+}
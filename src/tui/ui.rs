@@ -0,0 +1,221 @@
+//! 终端界面绘制
+//!
+//! 布局沿用原版 cgminer 的 curses 界面：顶部汇总状态行，中间每台设备一行，
+//! 下面是矿池状态和份额日志，底部是快捷键提示。
+
+use crate::device::{DeviceInfo, DeviceStatus};
+use crate::monitoring::RecentShare;
+use crate::tui::app::{App, PoolLine};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use std::time::Duration;
+
+/// 份额日志显示的行数
+const SHARE_LINES: u16 = 8;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let pool_rows = app.snapshot.as_ref().map(|s| s.pools.len()).unwrap_or(0).max(1) as u16;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(pool_rows + 3),
+            Constraint::Length(SHARE_LINES + 2),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+
+    draw_summary(frame, app, chunks[0]);
+    draw_devices(frame, app, chunks[1]);
+    draw_pools(frame, app, chunks[2]);
+    draw_shares(frame, app, chunks[3]);
+    draw_footer(frame, app, chunks[4]);
+}
+
+fn draw_summary(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL)
+        .title(format!(" cgminer-rs {} ", env!("CARGO_PKG_VERSION")));
+    let line = match &app.snapshot {
+        Some(snapshot) => {
+            let s = &snapshot.status;
+            Line::from(vec![
+                Span::styled(format!("{:?}", s.state), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(
+                    "  {}  |  A:{}  R:{}  HW:{}  |  Devices: {}  Pools: {}  |  Diff: {}  Best: {}  |  Up: {}",
+                    format_hashrate(s.total_hashrate),
+                    s.accepted_shares,
+                    s.rejected_shares,
+                    s.hardware_errors,
+                    s.active_devices,
+                    s.connected_pools,
+                    s.current_difficulty,
+                    s.best_share,
+                    format_uptime(s.uptime),
+                )),
+            ])
+        }
+        None => Line::from("Waiting for mining status..."),
+    };
+    frame.render_widget(Paragraph::new(line).block(block), area);
+}
+
+fn draw_devices(frame: &mut Frame, app: &App, area: Rect) {
+    let devices = app.snapshot.as_ref().map(|s| s.devices.as_slice()).unwrap_or(&[]);
+    let header = Row::new(["ID", "Name", "Status", "Temp", "Fan", "Freq", "Volt", "Hashrate", "A", "R", "HW"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let rows = devices.iter().map(device_row);
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Min(16),
+        Constraint::Length(12),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(9),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(6),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(" Devices "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let mut state = TableState::default();
+    if !devices.is_empty() {
+        state.select(Some(app.selected));
+    }
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn device_row(device: &DeviceInfo) -> Row<'static> {
+    let color = match device.status {
+        DeviceStatus::Mining => Color::Green,
+        DeviceStatus::Idle | DeviceStatus::Disabled | DeviceStatus::Uninitialized => Color::DarkGray,
+        DeviceStatus::Initializing | DeviceStatus::Restarting => Color::Yellow,
+        DeviceStatus::Error(_) | DeviceStatus::Overheated => Color::Red,
+    };
+    let status = match &device.status {
+        DeviceStatus::Error(_) => "Error".to_string(),
+        status => format!("{:?}", status),
+    };
+    let optional = |value: Option<u32>, unit: &str| value.map(|v| format!("{}{}", v, unit)).unwrap_or_else(|| "--".to_string());
+
+    Row::new(vec![
+        Cell::from(device.id.to_string()),
+        Cell::from(device.name.clone()),
+        Cell::from(status).style(Style::default().fg(color)),
+        Cell::from(device.temperature.map(|t| format!("{:.1}°C", t)).unwrap_or_else(|| "--".to_string())),
+        Cell::from(optional(device.fan_speed, "%")),
+        Cell::from(optional(device.frequency, "MHz")),
+        Cell::from(optional(device.voltage, "mV")),
+        Cell::from(format_hashrate(device.hashrate)),
+        Cell::from(device.accepted_shares.to_string()),
+        Cell::from(device.rejected_shares.to_string()),
+        Cell::from(device.hardware_errors.to_string()),
+    ])
+}
+
+fn draw_pools(frame: &mut Frame, app: &App, area: Rect) {
+    let pools = app.snapshot.as_ref().map(|s| s.pools.as_slice()).unwrap_or(&[]);
+    let header = Row::new(["#", "Pool", "Worker", "Status", "Ping", "A", "R", "Stale", "Diff"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Min(24),
+        Constraint::Length(16),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(pools.iter().map(pool_row), widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(" Pools "));
+    frame.render_widget(table, area);
+}
+
+fn pool_row(pool: &PoolLine) -> Row<'static> {
+    let label = match &pool.name {
+        Some(name) => format!("{} ({})", name, pool.url),
+        None => pool.url.clone(),
+    };
+    let mut cells = vec![
+        Cell::from(pool.id.to_string()),
+        Cell::from(label),
+        Cell::from(pool.username.clone()),
+    ];
+    match &pool.metrics {
+        Some(m) => {
+            let (status, color) = if m.connected { ("Alive", Color::Green) } else { ("Dead", Color::Red) };
+            cells.extend([
+                Cell::from(status).style(Style::default().fg(color)),
+                Cell::from(m.ping.map(|p| format!("{}ms", p.as_millis())).unwrap_or_else(|| "--".to_string())),
+                Cell::from(m.accepted_shares.to_string()),
+                Cell::from(m.rejected_shares.to_string()),
+                Cell::from(m.stale_shares.to_string()),
+                Cell::from(format!("{}", m.difficulty)),
+            ]);
+        }
+        None => cells.push(Cell::from("--").style(Style::default().fg(Color::DarkGray))),
+    }
+    Row::new(cells)
+}
+
+fn draw_shares(frame: &mut Frame, app: &App, area: Rect) {
+    let shares = app.snapshot.as_ref().map(|s| s.shares.as_slice()).unwrap_or(&[]);
+    let lines: Vec<Line> = if shares.is_empty() {
+        vec![Line::styled("No shares yet", Style::default().fg(Color::DarkGray))]
+    } else {
+        shares.iter().take(SHARE_LINES as usize).map(share_line).collect()
+    };
+    let block = Block::default().borders(Borders::ALL).title(" Shares ");
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn share_line(share: &RecentShare) -> Line<'static> {
+    let time = chrono::DateTime::<chrono::Local>::from(share.timestamp).format("%H:%M:%S");
+    let (verdict, color) = if share.accepted { ("Accepted", Color::Green) } else { ("Rejected", Color::Red) };
+    let mut spans = vec![
+        Span::raw(format!("[{}] ", time)),
+        Span::styled(verdict, Style::default().fg(color)),
+        Span::raw(format!(" device {} diff {:.2}", share.device_id, share.difficulty)),
+    ];
+    if let Some(reason) = &share.reason {
+        spans.push(Span::styled(format!(" ({})", reason), Style::default().fg(Color::DarkGray)));
+    }
+    Line::from(spans)
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let text = match app.message() {
+        Some(message) => Line::styled(message.to_string(), Style::default().fg(Color::Yellow)),
+        None => Line::from(" ↑/↓ select device   [P]ause/resume   [R]estart   [Q]uit"),
+    };
+    frame.render_widget(Paragraph::new(text), area);
+}
+
+/// 算力单位为 H/s，自动换算单位
+fn format_hashrate(hashrate: f64) -> String {
+    const UNITS: &[&str] = &["H/s", "KH/s", "MH/s", "GH/s", "TH/s", "PH/s"];
+
+    let mut value = hashrate.max(0.0);
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    format!("{}d {:02}:{:02}:{:02}", secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60, secs % 60)
+}
@@ -3,9 +3,11 @@
 //! 提供各种通用的工具函数和格式化功能
 
 pub mod hashrate_formatter;
+pub mod ring_buffer;
 
 // 重新导出常用函数
 pub use hashrate_formatter::{format_hashrate, format_hashrate_compact, parse_hashrate};
+pub use ring_buffer::RingBuffer;
 
 /// 算力显示宏 - 智能单位自适应
 ///
@@ -0,0 +1,228 @@
+//! 固定容量的环形缓冲区
+//!
+//! 设备温度/算力读数、指标历史、告警历史都只保留最近 N 条。原来用 `Vec::push` + `remove(0)`，
+//! 每次写入都要移动整个数组。`RingBuffer` 创建时一次分配容量，写满后新值覆盖最旧的值，
+//! 写入是 O(1)，并提供按时间顺序的迭代以及均值、百分位等统计。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
+
+/// 未指定容量时 (`Default`、反序列化) 的容量
+pub const DEFAULT_RING_CAPACITY: usize = 100;
+
+/// 固定容量的环形缓冲区，迭代顺序从旧到新
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    /// 创建容量为 `capacity` 的缓冲区，容量至少为 1
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// 写入新值，缓冲区已满时返回被覆盖的最旧值
+    pub fn push(&mut self, value: T) -> Option<T> {
+        let evicted = if self.items.len() >= self.capacity {
+            self.items.pop_front()
+        } else {
+            None
+        };
+        self.items.push_back(value);
+        evicted
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= self.capacity
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 修改容量；缩小时丢弃最旧的值并释放多余的内存
+    pub fn set_capacity(&mut self, capacity: usize) {
+        let capacity = capacity.max(1);
+        if self.items.len() > capacity {
+            self.items.drain(..self.items.len() - capacity);
+        }
+        if capacity < self.capacity {
+            self.items.shrink_to(capacity);
+        } else {
+            self.items.reserve_exact(capacity - self.items.len());
+        }
+        self.capacity = capacity;
+    }
+
+    /// 最旧的值
+    pub fn first(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// 最新的值
+    pub fn last(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// 从旧到新迭代
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// 最近的 `n` 个值，从旧到新
+    pub fn recent(&self, n: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.range(self.items.len().saturating_sub(n)..)
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// 从旧到新复制到 `Vec`
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.items.iter().cloned().collect()
+    }
+}
+
+impl<T: Copy + Into<f64>> RingBuffer<T> {
+    /// 平均值，空缓冲区为 None
+    pub fn mean(&self) -> Option<f64> {
+        if self.items.is_empty() {
+            return None;
+        }
+        Some(self.items.iter().map(|&v| v.into()).sum::<f64>() / self.items.len() as f64)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.items.iter().map(|&v| v.into()).reduce(f64::min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.items.iter().map(|&v| v.into()).reduce(f64::max)
+    }
+
+    /// 第 `p` 百分位 (0-100，最近秩法)，空缓冲区为 None
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let mut values: Vec<f64> = self.items.iter().map(|&v| v.into()).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * values.len() as f64).ceil() as usize;
+        Some(values[rank.saturating_sub(1).min(values.len() - 1)])
+    }
+}
+
+impl<T> Default for RingBuffer<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_RING_CAPACITY)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+/// 按默认容量收集，值多于默认容量时容量取值的个数
+impl<T> FromIterator<T> for RingBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: VecDeque<T> = iter.into_iter().collect();
+        let capacity = items.len().max(DEFAULT_RING_CAPACITY);
+        let mut buffer = Self::new(capacity);
+        buffer.items = items;
+        buffer
+    }
+}
+
+/// 序列化为从旧到新的数组，与原来的 `Vec` 字段格式相同
+impl<T: Serialize> Serialize for RingBuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.items)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for RingBuffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_overwrites_oldest() {
+        let mut buffer = RingBuffer::new(3);
+        assert_eq!(buffer.push(1), None);
+        buffer.push(2);
+        buffer.push(3);
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push(4), Some(1));
+        assert_eq!(buffer.to_vec(), vec![2, 3, 4]);
+        assert_eq!((buffer.first(), buffer.last()), (Some(&2), Some(&4)));
+        assert_eq!(buffer.recent(2).copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(buffer.recent(10).count(), 3);
+    }
+
+    #[test]
+    fn test_set_capacity_keeps_newest() {
+        let mut buffer: RingBuffer<u32> = (0..10).collect();
+        buffer.set_capacity(4);
+        assert_eq!(buffer.to_vec(), vec![6, 7, 8, 9]);
+        buffer.set_capacity(6);
+        buffer.push(10);
+        buffer.push(11);
+        buffer.push(12);
+        assert_eq!(buffer.to_vec(), vec![7, 8, 9, 10, 11, 12]);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut buffer = RingBuffer::new(100);
+        assert_eq!(buffer.mean(), None);
+        for v in (1..=100).rev() {
+            buffer.push(v as f32);
+        }
+        assert_eq!(buffer.mean(), Some(50.5));
+        assert_eq!(buffer.percentile(50.0), Some(50.0));
+        assert_eq!(buffer.percentile(99.0), Some(99.0));
+        assert_eq!(buffer.percentile(0.0), Some(1.0));
+        assert_eq!((buffer.min(), buffer.max()), (Some(1.0), Some(100.0)));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut buffer = RingBuffer::new(2);
+        buffer.push(1.5f64);
+        buffer.push(2.5);
+        buffer.push(3.5);
+        let json = serde_json::to_string(&buffer).unwrap();
+        assert_eq!(json, "[2.5,3.5]");
+        let restored: RingBuffer<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.to_vec(), vec![2.5, 3.5]);
+        assert_eq!(restored.capacity(), DEFAULT_RING_CAPACITY);
+    }
+}
@@ -0,0 +1,147 @@
+//! 内嵌的仪表板资源
+//!
+//! `web/dashboard/` 下的页面、脚本和样式在编译时通过 rust-embed 打包进二进制，
+//! 部署时不需要额外拷贝文件。配置了 `web.static_path` 时，该目录中的同名文件
+//! 优先于内嵌资源，便于在不重新编译的情况下调整页面。
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use rust_embed::RustEmbed;
+use std::path::{Component, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 仪表板资源
+#[derive(RustEmbed)]
+#[folder = "web/dashboard/"]
+struct DashboardAssets;
+
+/// 仪表板入口页面
+const INDEX: &str = "index.html";
+
+/// 登录页面
+const LOGIN: &str = "login.html";
+
+/// 机群页面
+const FLEET: &str = "fleet.html";
+
+/// 历史图表页面
+const HISTORY: &str = "history.html";
+
+/// 仪表板资源来源：可选的覆盖目录，其余取内嵌资源
+#[derive(Debug, Clone, Default)]
+pub struct Assets {
+    override_dir: Option<PathBuf>,
+}
+
+impl Assets {
+    pub fn new(override_dir: Option<&str>) -> Self {
+        let override_dir = override_dir.map(PathBuf::from);
+        if let Some(ref dir) = override_dir {
+            if dir.is_dir() {
+                info!("📁 Dashboard assets in {} override the embedded files", dir.display());
+            } else {
+                warn!("Dashboard asset override directory {} does not exist, using embedded files", dir.display());
+            }
+        }
+        Self { override_dir }
+    }
+
+    /// 覆盖目录中的文件，路径只允许普通的相对路径
+    fn override_file(&self, path: &str) -> Option<PathBuf> {
+        let dir = self.override_dir.as_ref()?;
+        let relative = std::path::Path::new(path);
+        if path.is_empty() || relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return None;
+        }
+        let file = dir.join(relative);
+        file.is_file().then_some(file)
+    }
+
+    /// 资源响应，覆盖文件读取失败时回退到内嵌资源
+    async fn response(&self, path: &str) -> Response {
+        if let Some(file) = self.override_file(path) {
+            match tokio::fs::read(&file).await {
+                Ok(data) => {
+                    let mime = mime_guess::from_path(&file).first_or_octet_stream();
+                    let headers = [
+                        (header::CONTENT_TYPE, mime.to_string()),
+                        (header::CACHE_CONTROL, "no-cache".to_string()),
+                    ];
+                    return (headers, data).into_response();
+                }
+                Err(e) => warn!("Failed to read dashboard asset {}: {}", file.display(), e),
+            }
+        }
+        embedded(path)
+    }
+}
+
+/// 内嵌资源的响应，不存在时返回 404
+fn embedded(path: &str) -> Response {
+    let Some(file) = DashboardAssets::get(path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let headers = [
+        (header::CONTENT_TYPE, file.metadata.mimetype().to_string()),
+        (header::CACHE_CONTROL, "no-cache".to_string()),
+        (header::ETAG, format!("\"{}\"", hex::encode(file.metadata.sha256_hash()))),
+    ];
+    (headers, file.data).into_response()
+}
+
+/// 仪表板首页
+pub async fn index(State(assets): State<Arc<Assets>>) -> Response {
+    assets.response(INDEX).await
+}
+
+/// 登录页
+pub async fn login(State(assets): State<Arc<Assets>>) -> Response {
+    assets.response(LOGIN).await
+}
+
+/// 机群页
+pub async fn fleet(State(assets): State<Arc<Assets>>) -> Response {
+    assets.response(FLEET).await
+}
+
+/// 历史图表页
+pub async fn history(State(assets): State<Arc<Assets>>) -> Response {
+    assets.response(HISTORY).await
+}
+
+/// `/assets/<path>` 资源
+pub async fn asset(State(assets): State<Arc<Assets>>, Path(path): Path<String>) -> Response {
+    assets.response(&path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_are_embedded() {
+        for path in [INDEX, LOGIN, FLEET, HISTORY, "app.js", "app.css", "login.js", "fleet.js", "history.js", "sw.js"] {
+            assert!(DashboardAssets::get(path).is_some(), "{} is not embedded", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_override_dir() {
+        let dir = std::env::temp_dir().join(format!("cgminer-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("app.css"), "body { color: red; }").unwrap();
+        let assets = Assets::new(dir.to_str());
+
+        assert_eq!(assets.override_file("app.css"), Some(dir.join("app.css")));
+        // 覆盖目录中没有的文件使用内嵌资源
+        assert_eq!(assets.override_file("app.js"), None);
+        assert_eq!(assets.response("app.js").await.status(), StatusCode::OK);
+        // 不允许跳出覆盖目录
+        assert_eq!(assets.override_file("../app.css"), None);
+        assert_eq!(assets.override_file("/etc/passwd"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+//! Web界面登录认证 (`[web.auth]`)
+//!
+//! 配置中只保存 Argon2 密码哈希（`cgminer-rs --hash-password` 生成）。
+//! 登录成功后发放 HttpOnly 会话 cookie，会话同时带一个 CSRF 令牌，
+//! 除 GET/HEAD 外的请求必须在 `X-CSRF-Token` 头中回传该令牌。
+
+use crate::error::MiningError;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Json, Redirect, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// 会话 cookie 名
+pub const SESSION_COOKIE: &str = "cgminer_session";
+
+/// CSRF 令牌请求头
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// 登录认证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebAuthConfig {
+    /// 是否要求登录
+    pub enabled: bool,
+    /// 用户名
+    pub username: String,
+    /// Argon2 密码哈希 (PHC 格式)
+    pub password_hash: String,
+    /// 会话有效期 (秒)，从最后一次访问起算
+    pub session_ttl_secs: u64,
+}
+
+impl Default for WebAuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            username: "admin".to_string(),
+            password_hash: String::new(),
+            session_ttl_secs: 8 * 3600,
+        }
+    }
+}
+
+impl WebAuthConfig {
+    /// 检查配置，返回错误说明
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.username.trim().is_empty() {
+            return Err("Web login username cannot be empty".to_string());
+        }
+        if self.password_hash.is_empty() {
+            return Err("Web login is enabled but password_hash is not set; generate one with --hash-password".to_string());
+        }
+        PasswordHash::new(&self.password_hash)
+            .map_err(|e| format!("password_hash is not a valid Argon2 hash ({}); generate one with --hash-password", e))?;
+        if self.session_ttl_secs == 0 {
+            return Err("Web session_ttl_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 生成配置用的密码哈希
+pub fn hash_password(password: &str) -> Result<String, MiningError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| MiningError::System(format!("Failed to hash password: {}", e)))
+}
+
+/// 校验密码
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+/// 常量时间比较，避免通过响应时间猜测令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 随机令牌 (32 字节，十六进制)
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// 登录会话
+#[derive(Debug, Clone)]
+struct Session {
+    username: String,
+    csrf_token: String,
+    expires_at: Instant,
+}
+
+/// 会话信息，返回给前端
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub username: String,
+    pub csrf_token: String,
+    pub expires_in_secs: u64,
+}
+
+/// 登录请求
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// 认证拒绝原因
+#[derive(Debug)]
+pub enum AuthRejection {
+    /// 页面请求未登录，跳转到登录页
+    LoginRequired,
+    /// API 请求未登录或会话过期
+    Unauthorized,
+    /// CSRF 令牌缺失或不匹配
+    CsrfMismatch,
+}
+
+impl IntoResponse for AuthRejection {
+    fn into_response(self) -> Response {
+        let (code, message) = match self {
+            AuthRejection::LoginRequired => return Redirect::to("/login").into_response(),
+            AuthRejection::Unauthorized => (StatusCode::UNAUTHORIZED, "Login Required"),
+            AuthRejection::CsrfMismatch => (StatusCode::FORBIDDEN, "Invalid CSRF Token"),
+        };
+        let body = serde_json::json!({
+            "error": message,
+            "code": code.as_u16()
+        });
+        (code, Json(body)).into_response()
+    }
+}
+
+/// 从 Cookie 头中取出会话 ID
+pub(crate) fn session_id(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| pair.trim().strip_prefix(SESSION_COOKIE)?.strip_prefix('='))
+}
+
+/// Web 登录认证和会话存储
+pub struct WebAuth {
+    config: WebAuthConfig,
+    /// 启用 TLS 时 cookie 带 Secure 属性
+    secure_cookie: bool,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl WebAuth {
+    pub fn new(config: WebAuthConfig, secure_cookie: bool) -> Self {
+        Self {
+            config,
+            secure_cookie,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.config.session_ttl_secs.max(1))
+    }
+
+    /// 查找并续期会话，过期会话顺带清理
+    fn touch(&self, session_id: &str) -> Option<Session> {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.retain(|_, session| session.expires_at > now);
+        let session = sessions.get_mut(session_id)?;
+        session.expires_at = now + self.ttl();
+        Some(session.clone())
+    }
+
+    /// 校验用户名和密码，成功时创建会话
+    async fn login(&self, request: LoginRequest) -> Option<(String, Session)> {
+        let password_hash = self.config.password_hash.clone();
+        let password = request.password;
+        // Argon2 校验耗时数十毫秒，不占用异步工作线程
+        let password_ok = tokio::task::spawn_blocking(move || verify_password(&password, &password_hash))
+            .await
+            .unwrap_or(false);
+        let username_ok = constant_time_eq(request.username.as_bytes(), self.config.username.as_bytes());
+        if !(password_ok && username_ok) {
+            warn!("🔒 Web login failed for user '{}'", request.username);
+            return None;
+        }
+
+        let session = Session {
+            username: self.config.username.clone(),
+            csrf_token: random_token(),
+            expires_at: Instant::now() + self.ttl(),
+        };
+        let session_id = random_token();
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner()).insert(session_id.clone(), session.clone());
+        info!("🔓 Web login: {}", session.username);
+        Some((session_id, session))
+    }
+
+    fn logout(&self, session_id: &str) {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id);
+    }
+
+    /// 请求是否允许通过，返回会话的用户名；未启用登录时为 None
+    pub(crate) fn authorize(&self, method: &Method, page: bool, session_id: Option<&str>, csrf: Option<&str>) -> Result<Option<String>, AuthRejection> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let session = session_id.and_then(|id| self.touch(id)).ok_or(if page {
+            AuthRejection::LoginRequired
+        } else {
+            AuthRejection::Unauthorized
+        })?;
+        if !matches!(*method, Method::GET | Method::HEAD) {
+            let csrf_ok = csrf.map_or(false, |token| constant_time_eq(token.as_bytes(), session.csrf_token.as_bytes()));
+            if !csrf_ok {
+                return Err(AuthRejection::CsrfMismatch);
+            }
+        }
+        Ok(Some(session.username))
+    }
+
+    fn session_info(&self, session: &Session) -> SessionInfo {
+        SessionInfo {
+            username: session.username.clone(),
+            csrf_token: session.csrf_token.clone(),
+            expires_in_secs: self.config.session_ttl_secs,
+        }
+    }
+
+    fn session_cookie(&self, session_id: &str, max_age: u64) -> String {
+        format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
+            SESSION_COOKIE,
+            session_id,
+            max_age,
+            if self.secure_cookie { "; Secure" } else { "" }
+        )
+    }
+}
+
+/// 登录、登出和会话查询路由
+pub fn routes(auth: Arc<WebAuth>) -> Router {
+    Router::new()
+        .route("/api/login", post(login).layer(DefaultBodyLimit::max(4 * 1024)))
+        .route("/api/logout", post(logout))
+        .route("/api/session", get(session))
+        .with_state(auth)
+}
+
+async fn login(State(auth): State<Arc<WebAuth>>, Json(request): Json<LoginRequest>) -> Response {
+    if !auth.enabled() {
+        return Json(serde_json::json!({ "auth_required": false })).into_response();
+    }
+    match auth.login(request).await {
+        Some((session_id, session)) => {
+            let cookie = auth.session_cookie(&session_id, auth.config.session_ttl_secs);
+            ([(header::SET_COOKIE, cookie)], Json(auth.session_info(&session))).into_response()
+        }
+        None => AuthRejection::Unauthorized.into_response(),
+    }
+}
+
+async fn logout(State(auth): State<Arc<WebAuth>>, headers: HeaderMap) -> Response {
+    if let Some(session_id) = session_id(&headers) {
+        auth.logout(session_id);
+    }
+    (StatusCode::NO_CONTENT, [(header::SET_COOKIE, auth.session_cookie("", 0))]).into_response()
+}
+
+async fn session(State(auth): State<Arc<WebAuth>>, headers: HeaderMap) -> Json<serde_json::Value> {
+    let info = session_id(&headers).and_then(|id| auth.touch(id)).map(|session| auth.session_info(&session));
+    Json(serde_json::json!({
+        "auth_required": auth.enabled(),
+        "session": info,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let hash = hash_password("hunter2").unwrap();
+        assert!(verify_password("hunter2", &hash));
+        assert!(!verify_password("hunter3", &hash));
+
+        let config = WebAuthConfig { enabled: true, password_hash: hash, ..Default::default() };
+        assert!(config.validate().is_ok());
+        let invalid = WebAuthConfig { enabled: true, password_hash: "plaintext".to_string(), ..Default::default() };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_and_csrf() {
+        let config = WebAuthConfig { enabled: true, password_hash: hash_password("pw").unwrap(), ..Default::default() };
+        let auth = WebAuth::new(config, false);
+
+        let wrong = LoginRequest { username: "admin".to_string(), password: "nope".to_string() };
+        assert!(auth.login(wrong).await.is_none());
+
+        let request = LoginRequest { username: "admin".to_string(), password: "pw".to_string() };
+        let (session_id, session) = auth.login(request).await.unwrap();
+
+        assert!(auth.authorize(&Method::GET, false, Some(&session_id), None).is_ok());
+        assert!(matches!(auth.authorize(&Method::GET, true, None, None), Err(AuthRejection::LoginRequired)));
+        assert!(matches!(auth.authorize(&Method::POST, false, Some(&session_id), None), Err(AuthRejection::CsrfMismatch)));
+        assert!(auth.authorize(&Method::POST, false, Some(&session_id), Some(&session.csrf_token)).is_ok());
+
+        auth.logout(&session_id);
+        assert!(matches!(auth.authorize(&Method::GET, false, Some(&session_id), None), Err(AuthRejection::Unauthorized)));
+    }
+
+    #[test]
+    fn test_session_id_from_cookie() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(session_id(&headers), None);
+
+        headers.insert(header::COOKIE, "theme=dark; cgminer_session=abc123".parse().unwrap());
+        assert_eq!(session_id(&headers), Some("abc123"));
+
+        headers.insert(header::COOKIE, "cgminer_session_old=x".parse().unwrap());
+        assert_eq!(session_id(&headers), None);
+    }
+}
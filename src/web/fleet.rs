@@ -0,0 +1,390 @@
+//! 机群视图 (`[web.fleet]`)
+//!
+//! 启用后后台任务定时轮询配置中各矿机的 API，汇总总算力、各矿机的活跃告警和离线矿机，
+//! 在 `/fleet` 页面展示，适合没有单独管理系统的小型矿场。
+
+use crate::api::{ApiResponse, SystemStatusResponse};
+use crate::error::MiningError;
+use crate::logging::redact;
+use crate::monitoring::alerts::AlertSeverity;
+use axum::extract::State;
+use axum::response::Json;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 机群视图配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FleetConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 轮询间隔 (秒)
+    pub poll_interval_secs: u64,
+    /// 单次请求超时 (秒)
+    pub timeout_secs: u64,
+    /// 被轮询的矿机
+    pub rigs: Vec<FleetRig>,
+}
+
+impl Default for FleetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval_secs: 15,
+            timeout_secs: 5,
+            rigs: Vec::new(),
+        }
+    }
+}
+
+/// 被轮询的矿机
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FleetRig {
+    /// 显示名称
+    pub name: String,
+    /// API 地址，如 http://192.168.1.20:4028
+    pub url: String,
+    /// 对方的 api.auth_token
+    #[serde(default)]
+    pub auth_token: Option<String>,
+}
+
+impl std::fmt::Debug for FleetRig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FleetRig")
+            .field("name", &self.name)
+            .field("url", &redact::redact_url(&self.url))
+            .field("auth_token", &redact::mask_option(&self.auth_token))
+            .finish()
+    }
+}
+
+impl FleetConfig {
+    /// 检查配置，返回 (配置键, 错误说明)
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+        if !self.enabled {
+            return errors;
+        }
+        if self.rigs.is_empty() {
+            errors.push(("web.fleet.rigs", "Fleet view is enabled but no rigs are configured".to_string()));
+        }
+        if self.poll_interval_secs == 0 {
+            errors.push(("web.fleet.poll_interval_secs", "Fleet poll_interval_secs must be greater than 0".to_string()));
+        }
+        for (i, rig) in self.rigs.iter().enumerate() {
+            if rig.name.trim().is_empty() {
+                errors.push(("web.fleet.rigs", format!("Fleet rig #{} has an empty name", i + 1)));
+            } else if self.rigs[..i].iter().any(|other| other.name == rig.name) {
+                errors.push(("web.fleet.rigs", format!("Fleet rig name '{}' is used more than once", rig.name)));
+            }
+            match url::Url::parse(&rig.url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                _ => errors.push(("web.fleet.rigs", format!(
+                    "Fleet rig '{}' url '{}' must be an http(s) URL", rig.name, redact::redact_url(&rig.url)
+                ))),
+            }
+        }
+        errors
+    }
+}
+
+/// 矿机的活跃告警，只取机群页面需要的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RigAlert {
+    pub severity: AlertSeverity,
+    pub title: String,
+    #[serde(default)]
+    pub source: String,
+}
+
+/// 单台矿机的最近一次轮询结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RigStatus {
+    pub name: String,
+    pub url: String,
+    /// 最近一次轮询是否成功
+    pub online: bool,
+    /// 最近一次成功轮询的时间 (Unix 秒)
+    pub last_seen: Option<u64>,
+    /// 最近一次轮询失败的原因
+    pub error: Option<String>,
+    /// 最近一次成功获取的状态，离线时保留
+    pub status: Option<SystemStatusResponse>,
+    /// 活跃告警，对方未启用仪表板时为空
+    pub alerts: Vec<RigAlert>,
+}
+
+impl RigStatus {
+    fn new(rig: &FleetRig) -> Self {
+        Self {
+            name: rig.name.clone(),
+            url: redact::redact_url(&rig.url),
+            online: false,
+            last_seen: None,
+            error: Some("Not polled yet".to_string()),
+            status: None,
+            alerts: Vec::new(),
+        }
+    }
+}
+
+/// `/api/fleet` 返回的机群汇总，算力和份额只统计在线矿机
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetSummary {
+    pub timestamp: u64,
+    pub rigs_total: usize,
+    pub rigs_online: usize,
+    pub total_hashrate: f64,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub hardware_errors: u64,
+    pub active_devices: u32,
+    pub alert_count: usize,
+    pub critical_alerts: usize,
+    pub rigs: Vec<RigStatus>,
+}
+
+impl FleetSummary {
+    pub fn from_rigs(rigs: Vec<RigStatus>) -> Self {
+        let mut summary = Self {
+            timestamp: unix_now(),
+            rigs_total: rigs.len(),
+            rigs_online: 0,
+            total_hashrate: 0.0,
+            accepted_shares: 0,
+            rejected_shares: 0,
+            hardware_errors: 0,
+            active_devices: 0,
+            alert_count: 0,
+            critical_alerts: 0,
+            rigs: Vec::new(),
+        };
+        for rig in &rigs {
+            summary.alert_count += rig.alerts.len();
+            summary.critical_alerts += rig.alerts.iter().filter(|a| a.severity == AlertSeverity::Critical).count();
+            let (true, Some(status)) = (rig.online, &rig.status) else {
+                continue;
+            };
+            summary.rigs_online += 1;
+            summary.total_hashrate += status.total_hashrate;
+            summary.accepted_shares += status.accepted_shares;
+            summary.rejected_shares += status.rejected_shares;
+            summary.hardware_errors += status.hardware_errors;
+            summary.active_devices += status.active_devices;
+        }
+        summary.rigs = rigs;
+        summary
+    }
+}
+
+/// 机群轮询器
+pub struct FleetMonitor {
+    config: FleetConfig,
+    client: reqwest::Client,
+    rigs: RwLock<Vec<RigStatus>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl FleetMonitor {
+    pub fn new(config: FleetConfig) -> Result<Self, MiningError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| MiningError::System(format!("Failed to create fleet client: {}", e)))?;
+        let rigs = config.rigs.iter().map(RigStatus::new).collect();
+        Ok(Self {
+            config,
+            client,
+            rigs: RwLock::new(rigs),
+            handle: Mutex::new(None),
+        })
+    }
+
+    /// 启动后台轮询
+    pub fn start(self: &Arc<Self>) {
+        let monitor = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(monitor.config.poll_interval_secs.max(1)));
+            loop {
+                ticker.tick().await;
+                monitor.poll_all().await;
+            }
+        });
+        if let Some(old) = self.handle.lock().unwrap().replace(handle) {
+            old.abort();
+        }
+        info!("🛰️ Fleet view polling {} rigs every {}s", self.config.rigs.len(), self.config.poll_interval_secs);
+    }
+
+    /// 停止后台轮询
+    pub fn stop(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    /// 当前的机群汇总
+    pub async fn summary(&self) -> FleetSummary {
+        FleetSummary::from_rigs(self.rigs.read().await.clone())
+    }
+
+    /// 并发轮询所有矿机
+    async fn poll_all(&self) {
+        let results = futures::future::join_all(self.config.rigs.iter().map(|rig| self.poll_rig(rig))).await;
+
+        let mut rigs = self.rigs.write().await;
+        for (status, result) in rigs.iter_mut().zip(results) {
+            match result {
+                Ok((system, alerts)) => {
+                    if !status.online && status.last_seen.is_some() {
+                        info!("🛰️ Fleet rig '{}' is back online", status.name);
+                    }
+                    status.online = true;
+                    status.last_seen = Some(unix_now());
+                    status.error = None;
+                    status.status = Some(system);
+                    status.alerts = alerts;
+                }
+                Err(e) => {
+                    if status.online {
+                        warn!("🛰️ Fleet rig '{}' is offline: {}", status.name, e);
+                    }
+                    status.online = false;
+                    status.error = Some(e);
+                    status.alerts.clear();
+                }
+            }
+        }
+    }
+
+    /// 轮询单台矿机：状态来自 `/api/v1/status`，告警来自对方仪表板的 `/api/alerts`
+    async fn poll_rig(&self, rig: &FleetRig) -> Result<(SystemStatusResponse, Vec<RigAlert>), String> {
+        let response: ApiResponse<SystemStatusResponse> = self.get_json(rig, "/api/v1/status").await?
+            .ok_or_else(|| "status endpoint not found".to_string())?;
+        let status = match response.data {
+            Some(status) if response.success => status,
+            _ => return Err(response.error.unwrap_or_else(|| "status request failed".to_string())),
+        };
+
+        // 对方未启用仪表板时没有告警接口，不影响在线状态
+        let alerts = match self.get_json::<ActiveAlerts>(rig, "/api/alerts").await {
+            Ok(alerts) => alerts.map(|a| a.active_alerts).unwrap_or_default(),
+            Err(e) => {
+                debug!("Failed to fetch alerts from fleet rig '{}': {}", rig.name, e);
+                Vec::new()
+            }
+        };
+        Ok((status, alerts))
+    }
+
+    /// GET 请求并解析 JSON，404 时返回 None
+    async fn get_json<T: DeserializeOwned>(&self, rig: &FleetRig, path: &str) -> Result<Option<T>, String> {
+        let url = format!("{}{}", rig.url.trim_end_matches('/'), path);
+        let mut request = self.client.get(&url);
+        if let Some(token) = &rig.auth_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.map_err(|e| e.without_url().to_string())?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => response.json().await.map(Some).map_err(|e| e.without_url().to_string()),
+            status => Err(format!("HTTP {}", status)),
+        }
+    }
+}
+
+/// `/api/alerts` 响应中用到的部分
+#[derive(Deserialize)]
+struct ActiveAlerts {
+    #[serde(default)]
+    active_alerts: Vec<RigAlert>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// 机群汇总接口
+pub async fn fleet(State(fleet): State<Arc<FleetMonitor>>) -> Json<FleetSummary> {
+    Json(fleet.summary().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rig(name: &str, url: &str) -> FleetRig {
+        FleetRig { name: name.to_string(), url: url.to_string(), auth_token: None }
+    }
+
+    fn online(name: &str, hashrate: f64, alerts: Vec<RigAlert>) -> RigStatus {
+        let mut status = RigStatus::new(&rig(name, "http://127.0.0.1:4028"));
+        status.online = true;
+        status.error = None;
+        status.alerts = alerts;
+        status.status = Some(SystemStatusResponse {
+            version: "test".to_string(),
+            uptime: 60,
+            mining_state: "Running".to_string(),
+            total_hashrate: hashrate,
+            accepted_shares: 10,
+            rejected_shares: 1,
+            hardware_errors: 0,
+            active_devices: 4,
+            connected_pools: 1,
+            current_difficulty: 1.0,
+            best_share: 1.0,
+        });
+        status
+    }
+
+    #[test]
+    fn test_summary_counts_online_rigs() {
+        let critical = RigAlert { severity: AlertSeverity::Critical, title: "hot".to_string(), source: "device_0".to_string() };
+        let warning = RigAlert { severity: AlertSeverity::Warning, title: "slow".to_string(), source: "pool_0".to_string() };
+        // 离线矿机保留最近一次的状态，但不计入算力
+        let mut offline = online("rig-c", 300.0, Vec::new());
+        offline.online = false;
+
+        let summary = FleetSummary::from_rigs(vec![
+            online("rig-a", 100.0, vec![critical]),
+            online("rig-b", 50.0, vec![warning]),
+            offline,
+        ]);
+
+        assert_eq!(summary.rigs_total, 3);
+        assert_eq!(summary.rigs_online, 2);
+        assert_eq!(summary.total_hashrate, 150.0);
+        assert_eq!(summary.accepted_shares, 20);
+        assert_eq!(summary.active_devices, 8);
+        assert_eq!(summary.alert_count, 2);
+        assert_eq!(summary.critical_alerts, 1);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = FleetConfig { enabled: true, ..FleetConfig::default() };
+        assert_eq!(config.validate().len(), 1);
+
+        config.rigs = vec![rig("rig-a", "http://10.0.0.2:4028"), rig("rig-a", "stratum+tcp://10.0.0.3:3333")];
+        let errors: Vec<String> = config.validate().into_iter().map(|(_, e)| e).collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+
+        config.rigs.truncate(1);
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn test_rig_debug_masks_token() {
+        let mut rig = rig("rig-a", "http://10.0.0.2:4028");
+        rig.auth_token = Some("secret-token".to_string());
+        assert!(!format!("{:?}", rig).contains("secret-token"));
+    }
+}
@@ -0,0 +1,112 @@
+//! 历史图表接口
+//!
+//! 查询监控系统的降采样指标历史 (`[monitoring.history]`)，`/history` 页面用它绘制
+//! 1 小时、24 小时和 7 天的曲线，`format=csv` 时返回可下载的 CSV。
+
+use crate::monitoring::store::{HistoryPoint, HistoryRange, SeriesId};
+use crate::monitoring::MetricsStore;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    #[serde(default)]
+    range: HistoryRange,
+    /// `total`、`device:<id>` 或 `pool:<id>`，默认 `total`
+    series: Option<String>,
+    /// `json` (默认) 或 `csv`
+    format: Option<String>,
+}
+
+pub fn routes(store: Arc<MetricsStore>) -> Router {
+    Router::new()
+        .route("/api/history", get(history))
+        .route("/api/history/series", get(series))
+        .with_state(store)
+}
+
+fn error_response(code: StatusCode, error: impl std::fmt::Display) -> Response {
+    (code, Json(json!({ "error": error.to_string(), "code": code.as_u16() }))).into_response()
+}
+
+/// 有历史数据的曲线
+async fn series(State(store): State<Arc<MetricsStore>>) -> Json<serde_json::Value> {
+    let series: Vec<String> = store.series().await.iter().map(SeriesId::to_string).collect();
+    Json(json!({ "series": series }))
+}
+
+async fn history(State(store): State<Arc<MetricsStore>>, Query(query): Query<HistoryQuery>) -> Response {
+    let series = match query.series.as_deref().unwrap_or("total").parse::<SeriesId>() {
+        Ok(series) => series,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    let Some(points) = store.query(series, query.range).await else {
+        return error_response(StatusCode::NOT_FOUND, format!("No history for {}", series));
+    };
+
+    match query.format.as_deref() {
+        None | Some("json") => Json(json!({
+            "series": series.to_string(),
+            "range": query.range,
+            "bucket_secs": query.range.bucket_secs(),
+            "points": points,
+        })).into_response(),
+        Some("csv") => {
+            let filename = format!("cgminer-history-{}-{}.csv", series.to_string().replace(':', "-"), query.range.as_str());
+            let headers = [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+            ];
+            (headers, to_csv(&points)).into_response()
+        }
+        Some(other) => error_response(StatusCode::BAD_REQUEST, format!("Unknown format '{}', expected json or csv", other)),
+    }
+}
+
+/// 每个时间桶一行，没有的字段留空
+fn to_csv(points: &[HistoryPoint]) -> String {
+    let mut csv = String::from("timestamp,time,hashrate_ghs,temperature_c,accepted,rejected,errors,online\n");
+    for p in points {
+        let time = chrono::DateTime::from_timestamp(p.timestamp as i64, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{}",
+            p.timestamp,
+            time,
+            p.hashrate.map(|h| format!("{:.3}", h)).unwrap_or_default(),
+            p.temperature.map(|t| format!("{:.1}", t)).unwrap_or_default(),
+            p.accepted,
+            p.rejected,
+            p.errors,
+            p.online.map(|o| format!("{:.3}", o)).unwrap_or_default(),
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv() {
+        let points = [
+            HistoryPoint { timestamp: 0, hashrate: Some(1234.5), temperature: Some(61.5), accepted: 3, ..HistoryPoint::default() },
+            HistoryPoint { timestamp: 60, online: Some(0.5), rejected: 1, errors: 2, ..HistoryPoint::default() },
+        ];
+        let csv = to_csv(&points);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "0,1970-01-01T00:00:00+00:00,1234.500,61.5,3,0,0,");
+        assert_eq!(lines[2], "60,1970-01-01T00:01:00+00:00,,,0,1,2,0.500");
+    }
+}
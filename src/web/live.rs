@@ -0,0 +1,124 @@
+//! 仪表板实时数据
+//!
+//! `/api/dashboard` 返回完整快照；`/api/live` 是 SSE 流，连接后先推送一次快照，
+//! 之后逐条转发监控事件，客户端落后导致事件丢失时重新推送快照。
+
+use crate::monitoring::{
+    DeviceMetrics, MiningMetrics, MonitoringEvent, MonitoringSystem, PoolMetrics, RecentShare, SystemMetrics,
+};
+use serde::Serialize;
+use serde_json::json;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Json;
+use futures::Stream;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+
+/// 快照中算力曲线的最大点数
+const HASHRATE_POINTS: usize = 360;
+
+/// SSE 保活间隔
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// 算力曲线上的一个点
+#[derive(Debug, Clone, Serialize)]
+pub struct HashratePoint {
+    /// Unix 时间戳 (毫秒)
+    pub timestamp: u64,
+    pub hashrate: f64,
+}
+
+/// 仪表板快照
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardSnapshot {
+    /// Unix 时间戳 (毫秒)
+    pub timestamp: u64,
+    pub state: String,
+    pub system: Option<SystemMetrics>,
+    pub mining: Option<MiningMetrics>,
+    pub devices: Vec<DeviceMetrics>,
+    pub pools: Vec<PoolMetrics>,
+    /// 最近的份额，从新到旧
+    pub shares: Vec<RecentShare>,
+    pub hashrate_history: Vec<HashratePoint>,
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// 采集仪表板快照
+pub async fn snapshot(monitoring: &MonitoringSystem) -> DashboardSnapshot {
+    let hashrate_history = monitoring.get_mining_history(HASHRATE_POINTS).await
+        .iter()
+        .map(|m| HashratePoint { timestamp: unix_millis(m.timestamp), hashrate: m.total_hashrate })
+        .collect();
+
+    DashboardSnapshot {
+        timestamp: unix_millis(SystemTime::now()),
+        state: format!("{:?}", monitoring.get_state().await),
+        system: monitoring.get_system_metrics().await,
+        mining: monitoring.get_mining_metrics().await,
+        devices: monitoring.get_all_device_metrics().await,
+        pools: monitoring.get_all_pool_metrics().await,
+        shares: monitoring.get_recent_shares().await,
+        hashrate_history,
+    }
+}
+
+/// 监控事件的 SSE 数据，事件名为 `MonitoringEvent::event_type`
+fn event_data(event: &MonitoringEvent) -> serde_json::Value {
+    let data = match event {
+        MonitoringEvent::SystemMetricsUpdate { metrics, .. } => serde_json::to_value(metrics),
+        MonitoringEvent::MiningMetricsUpdate { metrics, .. } => serde_json::to_value(metrics),
+        MonitoringEvent::DeviceMetricsUpdate { metrics, .. } => serde_json::to_value(metrics),
+        MonitoringEvent::PoolMetricsUpdate { metrics, .. } => serde_json::to_value(metrics),
+        MonitoringEvent::AlertTriggered { alert, .. } => serde_json::to_value(alert),
+        MonitoringEvent::AlertResolved { alert_id, .. } => Ok(json!({ "id": alert_id })),
+        MonitoringEvent::ShareRecorded { share, .. } => serde_json::to_value(share),
+    };
+    let mut data = data.unwrap_or(serde_json::Value::Null);
+    if let serde_json::Value::Object(ref mut map) = data {
+        map.insert("received_at".to_string(), json!(unix_millis(event.timestamp())));
+    }
+    data
+}
+
+fn sse_event<T: Serialize>(name: &str, data: &T) -> Result<Event, Infallible> {
+    let data = serde_json::to_string(data).unwrap_or_else(|_| "null".to_string());
+    Ok(Event::default().event(name).data(data))
+}
+
+/// `/api/dashboard` 处理器
+pub async fn dashboard(State(monitoring): State<Arc<MonitoringSystem>>) -> Json<DashboardSnapshot> {
+    Json(snapshot(&monitoring).await)
+}
+
+/// `/api/live` SSE 处理器
+pub async fn live(
+    State(monitoring): State<Arc<MonitoringSystem>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // 先订阅再采集快照，两者之间的事件不会丢失
+    let receiver = monitoring.subscribe_events();
+    let initial = snapshot(&monitoring).await;
+
+    let stream = futures::stream::unfold(
+        (monitoring, receiver, Some(initial)),
+        |(monitoring, mut receiver, pending)| async move {
+            if let Some(initial) = pending {
+                return Some((sse_event("snapshot", &initial), (monitoring, receiver, None)));
+            }
+            let event = match receiver.recv().await {
+                Ok(event) => sse_event(event.event_type(), &event_data(&event)),
+                Err(RecvError::Lagged(_)) => sse_event("snapshot", &snapshot(&monitoring).await),
+                Err(RecvError::Closed) => return None,
+            };
+            Some((event, (monitoring, receiver, None)))
+        },
+    );
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(KEEP_ALIVE))
+}
@@ -0,0 +1,76 @@
+//! 仪表板的 Web Push 订阅接口
+//!
+//! 推送器和订阅存储在监控系统中 (`[monitoring.push]`)，这里只负责浏览器的订阅和退订。
+
+use crate::monitoring::alerts::AlertSeverity;
+use crate::monitoring::push::PushPayload;
+use crate::monitoring::PushNotifier;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use web_push::SubscriptionInfo;
+
+/// 订阅请求体上限，浏览器的订阅 JSON 不超过 1KB
+const SUBSCRIPTION_BODY_LIMIT: usize = 4 * 1024;
+
+#[derive(Deserialize)]
+struct UnsubscribeRequest {
+    endpoint: String,
+}
+
+pub fn routes(notifier: Arc<PushNotifier>) -> Router {
+    Router::new()
+        .route("/api/push/key", get(key))
+        .route("/api/push/subscribe", post(subscribe).layer(DefaultBodyLimit::max(SUBSCRIPTION_BODY_LIMIT)))
+        .route("/api/push/unsubscribe", post(unsubscribe).layer(DefaultBodyLimit::max(SUBSCRIPTION_BODY_LIMIT)))
+        .route("/api/push/test", post(test))
+        .with_state(notifier)
+}
+
+fn error_response(error: impl std::fmt::Display) -> Response {
+    let code = StatusCode::INTERNAL_SERVER_ERROR;
+    (code, Json(json!({ "error": error.to_string(), "code": code.as_u16() }))).into_response()
+}
+
+/// VAPID 公钥，浏览器订阅时作为 applicationServerKey
+async fn key(State(notifier): State<Arc<PushNotifier>>) -> Json<serde_json::Value> {
+    Json(json!({
+        "public_key": notifier.public_key(),
+        "subscriptions": notifier.subscription_count().await,
+    }))
+}
+
+async fn subscribe(State(notifier): State<Arc<PushNotifier>>, Json(subscription): Json<SubscriptionInfo>) -> Response {
+    if !subscription.endpoint.starts_with("https://") {
+        let code = StatusCode::BAD_REQUEST;
+        return (code, Json(json!({ "error": "Push endpoint must be an https URL", "code": code.as_u16() }))).into_response();
+    }
+    match notifier.subscribe(subscription).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn unsubscribe(State(notifier): State<Arc<PushNotifier>>, Json(request): Json<UnsubscribeRequest>) -> Response {
+    match notifier.unsubscribe(&request.endpoint).await {
+        Ok(removed) => Json(json!({ "removed": removed })).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// 向所有订阅发送一条测试通知
+async fn test(State(notifier): State<Arc<PushNotifier>>) -> Json<serde_json::Value> {
+    let payload = PushPayload {
+        title: "🔔 CGMiner-RS".to_string(),
+        body: "Test notification: critical alerts will show up like this.".to_string(),
+        tag: "test".to_string(),
+        severity: AlertSeverity::Info,
+        url: "/".to_string(),
+    };
+    Json(json!(notifier.send_all(&payload).await))
+}
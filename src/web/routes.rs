@@ -0,0 +1,79 @@
+//! Web仪表板路由
+
+use crate::web::{WebConfig, assets, auth, fleet, handlers, history, live, push};
+use crate::web::assets::Assets;
+use crate::web::auth::WebAuth;
+use crate::web::fleet::FleetMonitor;
+use crate::monitoring::MonitoringSystem;
+use axum::routing::get;
+use axum::Router;
+use std::sync::Arc;
+
+/// 仪表板页面、资源和数据接口，合并进 API 服务器的路由
+///
+/// 访问控制由 API 服务器的认证中间件统一处理。启用机群视图时传入轮询器。
+pub fn router(
+    config: &WebConfig,
+    monitoring: Arc<MonitoringSystem>,
+    web_auth: Arc<WebAuth>,
+    fleet_monitor: Option<Arc<FleetMonitor>>,
+) -> Router {
+    // 仪表板首页和资源
+    let mut pages = Router::new()
+        .route("/", get(assets::index))
+        .route("/login", get(assets::login))
+        .route("/assets/*path", get(assets::asset));
+    if fleet_monitor.is_some() {
+        pages = pages.route("/fleet", get(assets::fleet));
+    }
+    let metrics_store = monitoring.metrics_store();
+    if metrics_store.is_some() {
+        pages = pages.route("/history", get(assets::history));
+    }
+    let pages = pages.with_state(Arc::new(Assets::new(config.static_path.as_deref())));
+
+    // 机群汇总
+    let fleet = match fleet_monitor {
+        Some(monitor) => Router::new()
+            .route("/api/fleet", get(fleet::fleet))
+            .with_state(monitor),
+        None => Router::new(),
+    };
+
+    // 历史图表数据
+    let history = match metrics_store {
+        Some(store) => history::routes(store),
+        None => Router::new(),
+    };
+
+    // 告警推送订阅，监控系统未启用推送时不注册
+    let push = match monitoring.push_notifier() {
+        Some(notifier) => push::routes(notifier),
+        None => Router::new(),
+    };
+
+    Router::new()
+        // 监控数据
+        .route("/api/status", get(handlers::api_status))
+        .route("/api/metrics/system", get(handlers::api_system_metrics))
+        .route("/api/metrics/mining", get(handlers::api_mining_metrics))
+        .route("/api/metrics/devices", get(handlers::api_device_metrics))
+        .route("/api/metrics/devices/:id", get(handlers::api_single_device_metrics))
+        .route("/api/metrics/pools", get(handlers::api_pool_metrics))
+        .route("/api/metrics/pools/:id", get(handlers::api_single_pool_metrics))
+        .route("/api/stats/performance", get(handlers::api_performance_stats))
+        .route("/api/stats/history", get(handlers::api_metrics_history))
+        .route("/api/alerts", get(handlers::api_alerts))
+
+        // 仪表板快照和实时推送
+        .route("/api/dashboard", get(live::dashboard))
+        .route("/api/live", get(live::live))
+        .with_state(monitoring)
+
+        // 登录、登出和会话查询
+        .merge(auth::routes(web_auth))
+        .merge(fleet)
+        .merge(push)
+        .merge(history)
+        .merge(pages)
+}
@@ -19,7 +19,7 @@ async fn test_cpu_btc_core_config_loading() {
         // 验证Bitcoin软算法核心配置
         if let Some(cpu_btc_config) = &config.cores.cpu_btc {
             assert!(cpu_btc_config.enabled, "Bitcoin软算法核心应该被启用");
-            assert!(cpu_btc_config.device_count > 0, "设备数量应该大于0");
+            assert!(cpu_btc_config.resolved_device_count() > 0, "设备数量应该大于0");
             assert!(cpu_btc_config.min_hashrate > 0.0, "最小算力应该大于0");
             assert!(cpu_btc_config.max_hashrate > cpu_btc_config.min_hashrate, "最大算力应该大于最小算力");
             assert!(cpu_btc_config.error_rate >= 0.0 && cpu_btc_config.error_rate <= 1.0, "错误率应该在0-1之间");
@@ -95,8 +95,8 @@ async fn test_config_validation() {
         if let Some(cpu_btc_config) = &config.cores.cpu_btc {
             if cpu_btc_config.enabled {
                 // 验证设备数量合理性
-                assert!(cpu_btc_config.device_count <= 64, "设备数量不应该超过64");
-                assert!(cpu_btc_config.device_count >= 1, "设备数量应该至少为1");
+                assert!(cpu_btc_config.resolved_device_count() <= 64, "设备数量不应该超过64");
+                assert!(cpu_btc_config.resolved_device_count() >= 1, "设备数量应该至少为1");
 
                 // 验证算力范围合理性
                 assert!(cpu_btc_config.min_hashrate >= 1_000_000.0, "最小算力应该至少为1 MH/s");
@@ -259,21 +259,39 @@ fn create_test_config() -> Config {
         cores: cgminer_rs::config::CoresConfig {
             enabled_cores: vec!["cpu-btc".to_string()],
             default_core: "cpu-btc".to_string(),
+            priority: Default::default(),
             cpu_btc: Some(cgminer_rs::config::BtcSoftwareCoreConfig {
                 enabled: true,
-                device_count: 4,
+                device_count: cgminer_rs::mining::DeviceCount::Fixed(4),
+                reserved_cores: 1,
                 min_hashrate: 1_000_000_000.0,
                 max_hashrate: 5_000_000_000.0,
                 error_rate: 0.01,
                 batch_size: 1000,
                 work_timeout_ms: 5000,
+                hash_backend: Default::default(),
                 cpu_affinity: None,
+                load_target: Default::default(),
+                thermal_throttle: Default::default(),
+                worker_priority: Default::default(),
             }),
             maijie_l7: None,
+            sim: None,
+            whatsminer: None,
+            avalon: None,
+            plugins: Default::default(),
+            failover: Default::default(),
         },
         devices: cgminer_rs::config::DeviceConfig {
             auto_detect: true,
             scan_interval: 10,
+            fan_control: Default::default(),
+            ramp_up: Default::default(),
+            thermal: Default::default(),
+            auto_tune: Default::default(),
+            power: Default::default(),
+            chip_health: Default::default(),
+            boards: Vec::new(),
             chains: vec![
                 cgminer_rs::config::ChainConfig {
                     id: 0,
@@ -297,6 +315,8 @@ fn create_test_config() -> Config {
             strategy: cgminer_rs::config::PoolStrategy::Failover,
             failover_timeout: 60,
             retry_interval: 30,
+            address_check: Default::default(),
+            submit_pipeline_depth: 0,
             pools: vec![
                 cgminer_rs::config::PoolInfo {
                     url: "stratum+tcp://pool.example.com:4444".to_string(),
@@ -314,6 +334,9 @@ fn create_test_config() -> Config {
             port: 8080,
             auth_token: Some("test_token".to_string()),
             allow_origins: vec!["*".to_string()],
+            tls_cert: None,
+            tls_key: None,
+            tls: Default::default(),
         },
         monitoring: cgminer_rs::config::MonitoringConfig {
             enabled: true,
@@ -331,12 +354,16 @@ fn create_test_config() -> Config {
                 max_error_rate: 5.0,
                 min_hashrate: 30.0,
             },
+            push: Default::default(),
+            // 测试中只在内存里保留历史
+            history: cgminer_rs::monitoring::HistoryConfig { path: String::new(), ..Default::default() },
         },
         hashmeter: cgminer_rs::mining::HashmeterConfig {
             enabled: true,
             log_interval: 5,
             per_device_stats: true,
             console_output: true,
+            format: cgminer_rs::mining::HashmeterFormat::Standard,
             hashrate_unit: "AUTO".to_string(),
         },
     }
@@ -423,7 +450,7 @@ impl cgminer_rs::device::MiningDevice for TestMiningDevice {
         Ok(())
     }
 
-    async fn submit_work(&mut self, _work: cgminer_rs::device::Work) -> Result<(), cgminer_rs::error::DeviceError> {
+    async fn submit_work(&mut self, _work: std::sync::Arc<cgminer_rs::device::Work>) -> Result<(), cgminer_rs::error::DeviceError> {
         Ok(())
     }
 
@@ -0,0 +1,46 @@
+//! 针对本地 regtest 节点的端到端测试：取工作 → 在 CPU 上找满足目标的 nonce → 提交份额
+//!
+//! bitcoind 自 0.10 起不再提供 getwork，需要在 regtest 节点前运行一个把 `getblocktemplate`
+//! 转换为 getwork 的代理，并把代理的地址传给测试。默认忽略：
+//!
+//! ```text
+//! CGMINER_REGTEST_URL=http://127.0.0.1:9332 CGMINER_REGTEST_USER=user CGMINER_REGTEST_PASS=pass \
+//!     cargo test --test regtest_e2e -- --ignored
+//! ```
+
+use cgminer_rs::mining::{Algorithm, Network};
+use cgminer_rs::pool::getwork::GetworkClient;
+use cgminer_rs::pool::Share;
+use sha2::{Digest, Sha256};
+
+fn env(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_regtest_share_pipeline() {
+    let mut client = GetworkClient::new(
+        env("CGMINER_REGTEST_URL", "http://127.0.0.1:9332"),
+        env("CGMINER_REGTEST_USER", "user"),
+        env("CGMINER_REGTEST_PASS", "pass"),
+        0,
+    ).expect("valid getwork URL");
+    client.set_network(Network::Regtest);
+    client.connect().await.expect("regtest node reachable");
+
+    let work = client.get_work().await.expect("work from regtest node");
+    // regtest 下难度 1 即 regtest 的工作量证明上限，大约每两次哈希就有一次满足
+    assert!(work.difficulty <= 1.0, "unexpected regtest work difficulty {}", work.difficulty);
+
+    let mut header = work.header;
+    let nonce = (0..u32::MAX).find(|nonce| {
+        header[76..80].copy_from_slice(&nonce.to_le_bytes());
+        let hash = Sha256::digest(Sha256::digest(header));
+        Algorithm::Sha256d.hash_difficulty(Network::Regtest, &hash).unwrap_or(0.0) >= work.difficulty
+    }).expect("nonce below the regtest target");
+
+    let share = Share::new(0, work.id, 0, work.job_id.clone(), String::new(), nonce, work.ntime, work.difficulty);
+    assert!(client.submit_share(&share).await.expect("submit response"), "regtest node rejected the share");
+    client.disconnect().await.unwrap();
+}
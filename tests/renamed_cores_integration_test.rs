@@ -197,27 +197,47 @@ fn create_cpu_btc_test_config() -> Config {
         cores: cgminer_rs::config::CoresConfig {
             enabled_cores: vec!["cpu-btc".to_string()],
             default_core: "cpu-btc".to_string(),
+            priority: Default::default(),
             cpu_btc: Some(cgminer_rs::config::BtcSoftwareCoreConfig {
                 enabled: true,
-                device_count: 2,
+                device_count: cgminer_rs::mining::DeviceCount::Fixed(2),
+                reserved_cores: 1,
                 min_hashrate: 1_000_000_000.0,
                 max_hashrate: 4_000_000_000.0,
                 error_rate: 0.01,
                 batch_size: 1000,
                 work_timeout_ms: 3000,
+                hash_backend: Default::default(),
                 cpu_affinity: None,
+                load_target: Default::default(),
+                thermal_throttle: Default::default(),
+                worker_priority: Default::default(),
             }),
             maijie_l7: None,
+            sim: None,
+            whatsminer: None,
+            avalon: None,
+            plugins: Default::default(),
+            failover: Default::default(),
         },
         devices: cgminer_rs::config::DeviceConfig {
             auto_detect: true,
             scan_interval: 10,
+            fan_control: Default::default(),
+            ramp_up: Default::default(),
+            thermal: Default::default(),
+            auto_tune: Default::default(),
+            power: Default::default(),
+            chip_health: Default::default(),
+            boards: Vec::new(),
             chains: vec![],
         },
         pools: cgminer_rs::config::PoolConfig {
             strategy: cgminer_rs::config::PoolStrategy::Failover,
             failover_timeout: 60,
             retry_interval: 30,
+            address_check: Default::default(),
+            submit_pipeline_depth: 0,
             pools: vec![
                 cgminer_rs::config::PoolInfo {
                     url: "stratum+tcp://test.pool.com:4444".to_string(),
@@ -235,6 +255,9 @@ fn create_cpu_btc_test_config() -> Config {
             port: 8080,
             auth_token: None,
             allow_origins: vec!["*".to_string()],
+            tls_cert: None,
+            tls_key: None,
+            tls: Default::default(),
         },
         monitoring: cgminer_rs::config::MonitoringConfig {
             enabled: true,
@@ -252,6 +275,8 @@ fn create_cpu_btc_test_config() -> Config {
                 max_error_rate: 5.0,
                 min_hashrate: 1.0,
             },
+            push: Default::default(),
+            history: Default::default(),
         },
     }
 }
@@ -269,6 +294,7 @@ fn create_maijie_l7_test_config() -> Config {
         cores: cgminer_rs::config::CoresConfig {
             enabled_cores: vec!["maijie-l7".to_string()],
             default_core: "maijie-l7".to_string(),
+            priority: Default::default(),
             cpu_btc: None,
             maijie_l7: Some(cgminer_rs::config::MaijieL7CoreConfig {
                 enabled: true,
@@ -279,10 +305,22 @@ fn create_maijie_l7_test_config() -> Config {
                 power_limit: 3000.0,
                 cooling_mode: "auto".to_string(),
             }),
+            sim: None,
+            whatsminer: None,
+            avalon: None,
+            plugins: Default::default(),
+            failover: Default::default(),
         },
         devices: cgminer_rs::config::DeviceConfig {
             auto_detect: true,
             scan_interval: 10,
+            fan_control: Default::default(),
+            ramp_up: Default::default(),
+            thermal: Default::default(),
+            auto_tune: Default::default(),
+            power: Default::default(),
+            chip_health: Default::default(),
+            boards: Vec::new(),
             chains: vec![
                 cgminer_rs::config::ChainConfig {
                     id: 0,
@@ -314,6 +352,8 @@ fn create_maijie_l7_test_config() -> Config {
             strategy: cgminer_rs::config::PoolStrategy::Failover,
             failover_timeout: 60,
             retry_interval: 30,
+            address_check: Default::default(),
+            submit_pipeline_depth: 0,
             pools: vec![
                 cgminer_rs::config::PoolInfo {
                     url: "stratum+tcp://test.pool.com:4444".to_string(),
@@ -331,6 +371,9 @@ fn create_maijie_l7_test_config() -> Config {
             port: 8080,
             auth_token: None,
             allow_origins: vec!["*".to_string()],
+            tls_cert: None,
+            tls_key: None,
+            tls: Default::default(),
         },
         monitoring: cgminer_rs::config::MonitoringConfig {
             enabled: true,
@@ -348,6 +391,8 @@ fn create_maijie_l7_test_config() -> Config {
                 max_error_rate: 5.0,
                 min_hashrate: 50.0, // 更高的最小算力适合ASIC
             },
+            push: Default::default(),
+            history: Default::default(),
         },
     }
 }
@@ -365,15 +410,21 @@ fn create_mixed_cores_test_config() -> Config {
         cores: cgminer_rs::config::CoresConfig {
             enabled_cores: vec!["cpu-btc".to_string(), "maijie-l7".to_string()],
             default_core: "cpu-btc".to_string(),
+            priority: Default::default(),
             cpu_btc: Some(cgminer_rs::config::BtcSoftwareCoreConfig {
                 enabled: true,
-                device_count: 2,
+                device_count: cgminer_rs::mining::DeviceCount::Fixed(2),
+                reserved_cores: 1,
                 min_hashrate: 1_000_000_000.0,
                 max_hashrate: 4_000_000_000.0,
                 error_rate: 0.01,
                 batch_size: 1000,
                 work_timeout_ms: 3000,
+                hash_backend: Default::default(),
                 cpu_affinity: None,
+                load_target: Default::default(),
+                thermal_throttle: Default::default(),
+                worker_priority: Default::default(),
             }),
             maijie_l7: Some(cgminer_rs::config::MaijieL7CoreConfig {
                 enabled: false, // 默认禁用，避免在没有硬件时出错
@@ -384,16 +435,30 @@ fn create_mixed_cores_test_config() -> Config {
                 power_limit: 3000.0,
                 cooling_mode: "auto".to_string(),
             }),
+            sim: None,
+            whatsminer: None,
+            avalon: None,
+            plugins: Default::default(),
+            failover: Default::default(),
         },
         devices: cgminer_rs::config::DeviceConfig {
             auto_detect: true,
             scan_interval: 10,
+            fan_control: Default::default(),
+            ramp_up: Default::default(),
+            thermal: Default::default(),
+            auto_tune: Default::default(),
+            power: Default::default(),
+            chip_health: Default::default(),
+            boards: Vec::new(),
             chains: vec![],
         },
         pools: cgminer_rs::config::PoolConfig {
             strategy: cgminer_rs::config::PoolStrategy::Failover,
             failover_timeout: 60,
             retry_interval: 30,
+            address_check: Default::default(),
+            submit_pipeline_depth: 0,
             pools: vec![
                 cgminer_rs::config::PoolInfo {
                     url: "stratum+tcp://test.pool.com:4444".to_string(),
@@ -411,6 +476,9 @@ fn create_mixed_cores_test_config() -> Config {
             port: 8080,
             auth_token: None,
             allow_origins: vec!["*".to_string()],
+            tls_cert: None,
+            tls_key: None,
+            tls: Default::default(),
         },
         monitoring: cgminer_rs::config::MonitoringConfig {
             enabled: true,
@@ -428,6 +496,8 @@ fn create_mixed_cores_test_config() -> Config {
                 max_error_rate: 5.0,
                 min_hashrate: 1.0,
             },
+            push: Default::default(),
+            history: Default::default(),
         },
     }
 }
@@ -1,6 +1,6 @@
 use cgminer_rs::config::Config;
 use cgminer_rs::mining::MiningManager;
-use cgminer_rs::api::server::ApiServer;
+use cgminer_rs::api::server::{ApiServer, ServerConfig};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -32,7 +32,7 @@ async fn test_full_system_integration() {
     );
 
     // Test 4: API server initialization
-    let api_server = ApiServer::new(config.api.clone(), mining_manager.clone());
+    let api_server = ApiServer::new(ServerConfig::from_config(&config), mining_manager.clone());
 
     // Test 4: Start API server
     api_server.start().await.expect("Failed to start API server");
@@ -231,7 +231,7 @@ async fn test_websocket_integration() {
             .expect("Failed to create mining manager")
     );
 
-    let api_server = ApiServer::new(config.api.clone(), mining_manager.clone());
+    let api_server = ApiServer::new(ServerConfig::from_config(&config), mining_manager.clone());
     api_server.start().await.expect("Failed to start API server");
 
     sleep(Duration::from_millis(500)).await;
@@ -266,7 +266,7 @@ async fn test_concurrent_operations() {
             .expect("Failed to create mining manager")
     );
 
-    let api_server = ApiServer::new(config.api.clone(), mining_manager.clone());
+    let api_server = ApiServer::new(ServerConfig::from_config(&config), mining_manager.clone());
     api_server.start().await.expect("Failed to start API server");
     mining_manager.start().await.expect("Failed to start mining manager");
 
@@ -323,6 +323,7 @@ fn create_integration_test_config() -> Config {
         cores: cgminer_rs::config::CoresConfig {
             enabled_cores: vec!["software".to_string()],
             default_core: "software".to_string(),
+            priority: Default::default(),
             software_core: Some(cgminer_rs::config::SoftwareCoreConfig {
                 enabled: true,
                 device_count: 2,
@@ -334,10 +335,22 @@ fn create_integration_test_config() -> Config {
                 cpu_affinity: None,
             }),
             asic_core: None,
+            sim: None,
+            whatsminer: None,
+            avalon: None,
+            plugins: Default::default(),
+            failover: Default::default(),
         },
         devices: cgminer_rs::config::DeviceConfig {
             auto_detect: true,
             scan_interval: 5,
+            fan_control: Default::default(),
+            ramp_up: Default::default(),
+            thermal: Default::default(),
+            auto_tune: Default::default(),
+            power: Default::default(),
+            chip_health: Default::default(),
+            boards: Vec::new(),
             chains: vec![
                 cgminer_rs::config::ChainConfig {
                     id: 0,
@@ -353,6 +366,8 @@ fn create_integration_test_config() -> Config {
             strategy: cgminer_rs::config::PoolStrategy::Failover,
             failover_timeout: 30,
             retry_interval: 10,
+            address_check: Default::default(),
+            submit_pipeline_depth: 0,
             pools: vec![
                 cgminer_rs::config::PoolInfo {
                     url: "stratum+tcp://test.pool.com:4444".to_string(),
@@ -370,6 +385,9 @@ fn create_integration_test_config() -> Config {
             port: 8080,
             auth_token: None, // No auth for testing
             allow_origins: vec!["*".to_string()],
+            tls_cert: None,
+            tls_key: None,
+            tls: Default::default(),
         },
         monitoring: cgminer_rs::config::MonitoringConfig {
             enabled: true,
@@ -387,6 +405,8 @@ fn create_integration_test_config() -> Config {
                 max_error_rate: 5.0,
                 min_hashrate: 1.0, // Low threshold for testing
             },
+            push: Default::default(),
+            history: Default::default(),
         },
     }
 }
@@ -0,0 +1,713 @@
+// CGMiner-RS 仪表板
+//
+// 连接 /api/live (SSE)，收到 snapshot 后整体渲染，之后按事件增量更新。
+// 浏览器断线后 EventSource 会自动重连，重连时服务端重新推送快照。
+// 设备卡片上的控制按钮调用 /api/v1/devices/{id}/...，先乐观更新界面，失败时回滚。
+
+(() => {
+    'use strict';
+
+    const MAX_POINTS = 360;      // 算力曲线保留的点数
+    const MAX_SHARES = 50;       // 份额列表长度
+    const MAX_ALERTS = 20;       // 告警列表长度
+    const TEMP_WARM = 75;        // 温度提示阈值 (°C)
+    const TEMP_HOT = 85;         // 温度告警阈值 (°C)
+    const FREQUENCY_RANGE = [100, 1000];   // 与服务端校验一致 (MHz)
+    const VOLTAGE_RANGE = [600, 1000];     // 与服务端校验一致 (mV)
+    const TUNE_STEP = 5;
+
+    const state = {
+        hashrate: [],            // [{ timestamp, hashrate }]
+        devices: new Map(),
+        pools: new Map(),
+        shares: [],
+        alerts: new Map(),
+        csrfToken: null,         // 启用登录时由 /api/session 提供
+        loginEnabled: false,
+        control: new Map(),      // 设备控制状态 { enabled, pending }
+        drafts: new Map(),       // 滑块上尚未应用的频率/电压
+        expanded: new Set(),     // 展开调参面板的设备
+        dragging: false,         // 拖动滑块时暂停重绘设备卡片
+        devicesDirty: false,
+    };
+
+    const $ = (id) => document.getElementById(id);
+
+    // ---------- 格式化 ----------
+
+    // 监控系统的算力单位为 GH/s
+    function formatHashrate(ghs) {
+        if (!Number.isFinite(ghs)) return '--';
+        const units = ['GH/s', 'TH/s', 'PH/s'];
+        let value = ghs;
+        let unit = 0;
+        while (Math.abs(value) >= 1000 && unit < units.length - 1) {
+            value /= 1000;
+            unit += 1;
+        }
+        return `${value.toFixed(2)} ${units[unit]}`;
+    }
+
+    // serde 序列化的 SystemTime / Duration
+    function toMillis(time) {
+        if (time == null) return null;
+        if (typeof time === 'number') return time;
+        return time.secs_since_epoch * 1000 + Math.floor(time.nanos_since_epoch / 1e6);
+    }
+
+    function durationSecs(duration) {
+        return duration ? duration.secs + duration.nanos / 1e9 : 0;
+    }
+
+    function formatUptime(secs) {
+        const days = Math.floor(secs / 86400);
+        const hours = Math.floor((secs % 86400) / 3600);
+        const minutes = Math.floor((secs % 3600) / 60);
+        return days > 0 ? `${days}d ${hours}h` : `${hours}h ${minutes}m`;
+    }
+
+    function formatClock(millis) {
+        return millis ? new Date(millis).toLocaleTimeString() : '--';
+    }
+
+    function escapeHtml(text) {
+        return String(text).replace(/[&<>"']/g, (c) => ({
+            '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;',
+        }[c]));
+    }
+
+    // ---------- 渲染 ----------
+
+    function renderMining(mining) {
+        if (!mining) return;
+        const total = mining.accepted_shares + mining.rejected_shares;
+        $('total-hashrate').textContent = formatHashrate(mining.total_hashrate);
+        $('share-counts').textContent = `${mining.accepted_shares} / ${mining.rejected_shares}`;
+        $('reject-rate').textContent = total > 0
+            ? `${(mining.rejected_shares / total * 100).toFixed(2)}%`
+            : '0.00%';
+        $('hardware-errors').textContent = mining.hardware_errors;
+    }
+
+    function renderSystem(system) {
+        if (!system) return;
+        $('system-temperature').textContent = `${system.temperature.toFixed(1)}°C`;
+        $('uptime').textContent = formatUptime(durationSecs(system.uptime));
+    }
+
+    function controlFor(id) {
+        if (!state.control.has(id)) state.control.set(id, { enabled: true, pending: false });
+        return state.control.get(id);
+    }
+
+    function renderTune(d) {
+        const draft = state.drafts.get(d.device_id) || {};
+        const frequency = draft.frequency ?? d.frequency;
+        const voltage = draft.voltage ?? d.voltage;
+        return `
+            <div class="device-tune">
+                <label>频率 <span>${frequency} MHz</span></label>
+                <input type="range" min="${FREQUENCY_RANGE[0]}" max="${FREQUENCY_RANGE[1]}" step="${TUNE_STEP}"
+                    value="${frequency}" data-field="frequency" data-device="${d.device_id}">
+                <label>电压 <span>${voltage} mV</span></label>
+                <input type="range" min="${VOLTAGE_RANGE[0]}" max="${VOLTAGE_RANGE[1]}" step="${TUNE_STEP}"
+                    value="${voltage}" data-field="voltage" data-device="${d.device_id}">
+                <button data-action="apply" data-device="${d.device_id}"
+                    ${state.drafts.has(d.device_id) ? '' : 'disabled'}>应用</button>
+            </div>`;
+    }
+
+    function renderDevices() {
+        const container = $('devices');
+        if (state.dragging) {
+            state.devicesDirty = true;
+            return;
+        }
+        state.devicesDirty = false;
+        const devices = [...state.devices.values()].sort((a, b) => a.device_id - b.device_id);
+        $('device-count').textContent = devices.length ? `${devices.length} 台` : '';
+
+        if (!devices.length) {
+            container.innerHTML = '<div class="empty">暂无设备数据</div>';
+            return;
+        }
+
+        container.innerHTML = devices.map((d) => {
+            const id = d.device_id;
+            const control = controlFor(id);
+            const busy = control.pending ? 'disabled' : '';
+            const level = !control.enabled || d.hashrate <= 0 ? 'idle'
+                : d.temperature >= TEMP_HOT ? 'hot'
+                : d.temperature >= TEMP_WARM ? 'warm' : '';
+            const note = control.pending ? '处理中...'
+                : !control.enabled ? '已禁用'
+                : d.frequency ? `${d.frequency} MHz` : '';
+            return `
+                <div class="device ${level}">
+                    <div class="device-title">
+                        <span>设备 ${id}</span>
+                        <span class="muted">${note}</span>
+                    </div>
+                    <div class="device-temp">${d.temperature.toFixed(1)}°C</div>
+                    <div class="device-row"><span>算力</span><span>${formatHashrate(d.hashrate)}</span></div>
+                    <div class="device-row"><span>风扇</span><span>${d.fan_speed}%</span></div>
+                    <div class="device-row"><span>功耗</span><span>${d.power_consumption.toFixed(0)} W</span></div>
+                    <div class="device-row"><span>份额</span><span>${d.accepted_shares} / ${d.rejected_shares}</span></div>
+                    <div class="device-actions">
+                        <button data-action="restart" data-device="${id}" ${busy}>重启</button>
+                        <button data-action="${control.enabled ? 'disable' : 'enable'}" data-device="${id}" ${busy}>
+                            ${control.enabled ? '禁用' : '启用'}</button>
+                        <button data-action="tune" data-device="${id}"
+                            class="${state.expanded.has(id) ? 'active' : ''}">调参</button>
+                    </div>
+                    ${state.expanded.has(id) ? renderTune(d) : ''}
+                </div>`;
+        }).join('');
+    }
+
+    function renderPools() {
+        const body = $('pools');
+        const pools = [...state.pools.values()].sort((a, b) => a.pool_id - b.pool_id);
+
+        if (!pools.length) {
+            body.innerHTML = '<tr><td colspan="6" class="empty">暂无矿池数据</td></tr>';
+            return;
+        }
+
+        body.innerHTML = pools.map((p) => {
+            const ping = p.ping ? `${Math.round(durationSecs(p.ping) * 1000)} ms` : '--';
+            const status = p.connected
+                ? '<span class="badge ok">在线</span>'
+                : '<span class="badge bad">离线</span>';
+            return `
+                <tr>
+                    <td>#${p.pool_id}</td>
+                    <td>${status}</td>
+                    <td>${ping}</td>
+                    <td>${p.accepted_shares}</td>
+                    <td>${p.rejected_shares}</td>
+                    <td>${p.difficulty}</td>
+                </tr>`;
+        }).join('');
+    }
+
+    function renderShares() {
+        const list = $('shares');
+        if (!state.shares.length) {
+            list.innerHTML = '<li class="empty">暂无份额</li>';
+            return;
+        }
+
+        list.innerHTML = state.shares.map((s) => `
+            <li class="${s.accepted ? '' : 'rejected'}">
+                <span>${formatClock(toMillis(s.timestamp))}</span>
+                <span>设备 ${s.device_id}</span>
+                <span>难度 ${s.difficulty.toFixed(2)}</span>
+                <span title="${s.reason ? escapeHtml(s.reason) : ''}">${s.accepted ? '✅ 接受' : '❌ 拒绝'}</span>
+            </li>`).join('');
+    }
+
+    function renderAlerts() {
+        const list = $('alerts');
+        const alerts = [...state.alerts.values()]
+            .sort((a, b) => toMillis(b.triggered_at) - toMillis(a.triggered_at));
+
+        if (!alerts.length) {
+            list.innerHTML = '<li class="empty">暂无告警</li>';
+            return;
+        }
+
+        list.innerHTML = alerts.map((a) => `
+            <li class="${a.severity.toLowerCase()}">
+                <span>${formatClock(toMillis(a.triggered_at))}</span>
+                <span>${escapeHtml(a.title)}</span>
+                <span class="muted">${escapeHtml(a.description)}</span>
+            </li>`).join('');
+    }
+
+    // ---------- 设备控制 ----------
+
+    let toastTimer = null;
+
+    function showToast(message, isError) {
+        const toast = $('toast');
+        toast.textContent = message;
+        toast.className = `toast ${isError ? 'error' : ''}`;
+        toast.hidden = false;
+        clearTimeout(toastTimer);
+        toastTimer = setTimeout(() => { toast.hidden = true; }, 4000);
+    }
+
+    // 确认后发送控制请求：先执行 apply 乐观更新，请求失败时执行 revert 回滚
+    async function controlDevice(id, { confirmText, method, path, body, apply, revert }) {
+        const control = controlFor(id);
+        if (control.pending || !window.confirm(confirmText)) return;
+
+        control.pending = true;
+        if (apply) apply(control);
+        renderDevices();
+
+        try {
+            const response = await request(method, path, body);
+            const result = await response.json().catch(() => ({}));
+            if (!response.ok || result.success === false) {
+                throw new Error(result.error || `HTTP ${response.status}`);
+            }
+            showToast(result.data || '操作成功');
+        } catch (error) {
+            if (revert) revert(control);
+            showToast(`设备 ${id} 操作失败: ${error.message}`, true);
+        } finally {
+            control.pending = false;
+            renderDevices();
+        }
+    }
+
+    const deviceActions = {
+        restart(id) {
+            controlDevice(id, {
+                confirmText: `确定重启设备 ${id}？重启期间该设备停止挖矿。`,
+                method: 'POST',
+                path: `/api/v1/devices/${id}/restart`,
+            });
+        },
+        disable(id) {
+            controlDevice(id, {
+                confirmText: `确定禁用设备 ${id}？`,
+                method: 'POST',
+                path: `/api/v1/devices/${id}/disable`,
+                apply: (control) => { control.enabled = false; },
+                revert: (control) => { control.enabled = true; },
+            });
+        },
+        enable(id) {
+            controlDevice(id, {
+                confirmText: `确定启用设备 ${id}？`,
+                method: 'POST',
+                path: `/api/v1/devices/${id}/enable`,
+                apply: (control) => { control.enabled = true; },
+                revert: (control) => { control.enabled = false; },
+            });
+        },
+        tune(id) {
+            if (state.expanded.has(id)) {
+                state.expanded.delete(id);
+                state.drafts.delete(id);
+            } else {
+                state.expanded.add(id);
+            }
+            renderDevices();
+        },
+        apply(id) {
+            const device = state.devices.get(id);
+            const draft = state.drafts.get(id);
+            if (!device || !draft) return;
+            const previous = { frequency: device.frequency, voltage: device.voltage };
+            const next = { frequency: draft.frequency ?? device.frequency, voltage: draft.voltage ?? device.voltage };
+            controlDevice(id, {
+                confirmText: `将设备 ${id} 设置为 ${next.frequency} MHz / ${next.voltage} mV？`
+                    + '\n不合适的频率和电压可能导致硬件错误或过热。',
+                method: 'PUT',
+                path: `/api/v1/devices/${id}/config`,
+                body: next,
+                apply: () => {
+                    Object.assign(device, next);
+                    state.drafts.delete(id);
+                },
+                revert: () => {
+                    Object.assign(device, previous);
+                    state.drafts.set(id, draft);
+                },
+            });
+        },
+    };
+
+    function bindDeviceControls() {
+        const container = $('devices');
+        container.addEventListener('click', (event) => {
+            const button = event.target.closest('button[data-action]');
+            if (!button) return;
+            const action = deviceActions[button.dataset.action];
+            if (action) action(Number(button.dataset.device));
+        });
+
+        // 滑块只更新草稿和数值显示，点击"应用"后才发送
+        container.addEventListener('input', (event) => {
+            const input = event.target;
+            if (input.type !== 'range') return;
+            const id = Number(input.dataset.device);
+            const draft = state.drafts.get(id) || {};
+            draft[input.dataset.field] = Number(input.value);
+            state.drafts.set(id, draft);
+            const unit = input.dataset.field === 'frequency' ? 'MHz' : 'mV';
+            input.previousElementSibling.querySelector('span').textContent = `${input.value} ${unit}`;
+            const apply = input.parentElement.querySelector('button[data-action="apply"]');
+            if (apply) apply.disabled = false;
+        });
+
+        container.addEventListener('pointerdown', (event) => {
+            if (event.target.type === 'range') state.dragging = true;
+        });
+        document.addEventListener('pointerup', () => {
+            if (!state.dragging) return;
+            state.dragging = false;
+            if (state.devicesDirty) renderDevices();
+        });
+    }
+
+    // ---------- 算力曲线 ----------
+
+    function drawChart() {
+        const canvas = $('hashrate-chart');
+        const ratio = window.devicePixelRatio || 1;
+        const width = canvas.clientWidth;
+        const height = canvas.clientHeight;
+        canvas.width = width * ratio;
+        canvas.height = height * ratio;
+
+        const ctx = canvas.getContext('2d');
+        ctx.scale(ratio, ratio);
+        ctx.clearRect(0, 0, width, height);
+
+        const points = state.hashrate;
+        const pad = { left: 70, right: 12, top: 12, bottom: 24 };
+        const plotWidth = width - pad.left - pad.right;
+        const plotHeight = height - pad.top - pad.bottom;
+
+        ctx.font = '11px sans-serif';
+        ctx.fillStyle = '#6b7787';
+
+        if (points.length < 2) {
+            ctx.fillText('等待算力数据...', pad.left, pad.top + plotHeight / 2);
+            $('chart-range').textContent = '';
+            return;
+        }
+
+        const start = points[0].timestamp;
+        const end = points[points.length - 1].timestamp;
+        const max = Math.max(...points.map((p) => p.hashrate)) * 1.1 || 1;
+        const x = (t) => pad.left + (end > start ? (t - start) / (end - start) : 1) * plotWidth;
+        const y = (v) => pad.top + plotHeight - (v / max) * plotHeight;
+
+        // 网格和刻度
+        ctx.strokeStyle = '#263241';
+        ctx.lineWidth = 1;
+        for (let i = 0; i <= 4; i += 1) {
+            const value = (max / 4) * i;
+            const gy = y(value);
+            ctx.beginPath();
+            ctx.moveTo(pad.left, gy);
+            ctx.lineTo(width - pad.right, gy);
+            ctx.stroke();
+            ctx.fillText(formatHashrate(value), 4, gy + 4);
+        }
+        ctx.fillText(formatClock(start), pad.left, height - 6);
+        const endLabel = formatClock(end);
+        ctx.fillText(endLabel, width - pad.right - ctx.measureText(endLabel).width, height - 6);
+
+        // 曲线和填充
+        ctx.beginPath();
+        points.forEach((p, i) => (i === 0 ? ctx.moveTo(x(p.timestamp), y(p.hashrate)) : ctx.lineTo(x(p.timestamp), y(p.hashrate))));
+        ctx.strokeStyle = '#3fa9f5';
+        ctx.lineWidth = 2;
+        ctx.stroke();
+
+        ctx.lineTo(x(end), y(0));
+        ctx.lineTo(x(start), y(0));
+        ctx.closePath();
+        ctx.fillStyle = 'rgba(63, 169, 245, 0.12)';
+        ctx.fill();
+
+        $('chart-range').textContent = `${formatClock(start)} - ${endLabel}`;
+    }
+
+    function pushHashrate(timestamp, hashrate) {
+        state.hashrate.push({ timestamp, hashrate });
+        if (state.hashrate.length > MAX_POINTS) {
+            state.hashrate.splice(0, state.hashrate.length - MAX_POINTS);
+        }
+        drawChart();
+    }
+
+    // ---------- 事件处理 ----------
+
+    function applySnapshot(snapshot) {
+        state.hashrate = snapshot.hashrate_history.slice(-MAX_POINTS);
+        state.devices = new Map(snapshot.devices.map((d) => [d.device_id, d]));
+        state.pools = new Map(snapshot.pools.map((p) => [p.pool_id, p]));
+        state.shares = snapshot.shares.slice(0, MAX_SHARES);
+
+        renderMining(snapshot.mining);
+        renderSystem(snapshot.system);
+        renderDevices();
+        renderPools();
+        renderShares();
+        drawChart();
+    }
+
+    const handlers = {
+        snapshot: applySnapshot,
+        mining_metrics_update(metrics) {
+            renderMining(metrics);
+            pushHashrate(toMillis(metrics.timestamp) || metrics.received_at, metrics.total_hashrate);
+        },
+        system_metrics_update: renderSystem,
+        device_metrics_update(metrics) {
+            state.devices.set(metrics.device_id, metrics);
+            renderDevices();
+        },
+        pool_metrics_update(metrics) {
+            state.pools.set(metrics.pool_id, metrics);
+            renderPools();
+        },
+        share_recorded(share) {
+            state.shares.unshift(share);
+            state.shares.length = Math.min(state.shares.length, MAX_SHARES);
+            renderShares();
+        },
+        alert_triggered(alert) {
+            state.alerts.set(alert.id, alert);
+            while (state.alerts.size > MAX_ALERTS) {
+                state.alerts.delete(state.alerts.keys().next().value);
+            }
+            renderAlerts();
+        },
+        alert_resolved({ id }) {
+            state.alerts.delete(id);
+            renderAlerts();
+        },
+    };
+
+    function setConnected(connected) {
+        $('connection-dot').className = `dot ${connected ? 'online' : 'offline'}`;
+        $('connection-text').textContent = connected ? '实时' : '重新连接中...';
+    }
+
+    // ---------- 会话 ----------
+
+    // 带会话和 CSRF 令牌的 API 请求，会话失效时跳转登录页
+    async function request(method, path, body) {
+        const headers = { 'content-type': 'application/json' };
+        if (state.csrfToken) headers['x-csrf-token'] = state.csrfToken;
+        const response = await fetch(path, {
+            method,
+            headers,
+            credentials: 'same-origin',
+            body: body === undefined ? undefined : JSON.stringify(body),
+        });
+        if (response.status === 401) {
+            window.location.replace('/login');
+        }
+        return response;
+    }
+
+    async function loadSession() {
+        const response = await request('GET', '/api/session');
+        if (!response.ok) return;
+        const info = await response.json();
+        state.loginEnabled = info.auth_required;
+        if (info.auth_required && info.session) {
+            state.csrfToken = info.session.csrf_token;
+            const logout = $('logout');
+            logout.hidden = false;
+            logout.title = info.session.username;
+            logout.onclick = async () => {
+                await request('POST', '/api/logout');
+                window.location.replace('/login');
+            };
+        }
+    }
+
+    // 启用了机群视图时显示入口
+    async function detectFleet() {
+        const response = await request('GET', '/api/fleet');
+        $('fleet-link').hidden = !response.ok;
+    }
+
+    // 启用了指标历史时显示历史图表入口
+    async function detectHistory() {
+        const response = await request('GET', '/api/history/series');
+        $('history-link').hidden = !response.ok;
+    }
+
+    // ---------- 告警推送 ----------
+
+    // VAPID 公钥是 base64url，PushManager 需要字节数组
+    function base64UrlToBytes(text) {
+        const base64 = (text + '='.repeat((4 - text.length % 4) % 4)).replace(/-/g, '+').replace(/_/g, '/');
+        return Uint8Array.from(atob(base64), (c) => c.charCodeAt(0));
+    }
+
+    function renderPushToggle(subscribed) {
+        const button = $('push-toggle');
+        button.classList.toggle('active', subscribed);
+        button.title = subscribed ? '已订阅严重告警通知，点击退订' : '订阅严重告警通知';
+    }
+
+    async function togglePush(registration) {
+        const existing = await registration.pushManager.getSubscription();
+        if (existing) {
+            await request('POST', '/api/push/unsubscribe', { endpoint: existing.endpoint });
+            await existing.unsubscribe();
+            renderPushToggle(false);
+            showToast('已退订告警通知');
+            return;
+        }
+
+        if (await Notification.requestPermission() !== 'granted') {
+            showToast('浏览器未允许通知', true);
+            return;
+        }
+        const key = await (await request('GET', '/api/push/key')).json();
+        const subscription = await registration.pushManager.subscribe({
+            userVisibleOnly: true,
+            applicationServerKey: base64UrlToBytes(key.public_key),
+        });
+        const response = await request('POST', '/api/push/subscribe', subscription.toJSON());
+        if (!response.ok) {
+            await subscription.unsubscribe();
+            showToast('订阅告警通知失败', true);
+            return;
+        }
+        renderPushToggle(true);
+        showToast('已订阅严重告警通知');
+    }
+
+    // 服务端启用了推送且浏览器支持时显示订阅按钮 (需要 HTTPS 或 localhost)
+    async function setupPush() {
+        if (!('serviceWorker' in navigator) || !('PushManager' in window) || !window.isSecureContext) return;
+        const response = await request('GET', '/api/push/key');
+        if (!response.ok) return;
+
+        const registration = await navigator.serviceWorker.register('/assets/sw.js');
+        renderPushToggle(Boolean(await registration.pushManager.getSubscription()));
+        const button = $('push-toggle');
+        button.hidden = false;
+        button.onclick = () => {
+            button.disabled = true;
+            togglePush(registration)
+                .catch((error) => showToast(`告警通知操作失败: ${error.message}`, true))
+                .finally(() => { button.disabled = false; });
+        };
+    }
+
+    // ---------- API 密钥 ----------
+
+    function formatDate(secs) {
+        return secs ? new Date(secs * 1000).toLocaleString() : '--';
+    }
+
+    function renderKeys(keys) {
+        $('keys').innerHTML = keys.length
+            ? keys.map((k) => `
+                <tr>
+                    <td>${escapeHtml(k.name)}</td>
+                    <td><span class="badge">${k.scope}</span></td>
+                    <td><code>${escapeHtml(k.prefix)}…</code></td>
+                    <td>${formatDate(k.created_at)}</td>
+                    <td>${formatDate(k.last_used)}</td>
+                    <td class="key-actions">
+                        <button data-rotate="${escapeHtml(k.id)}">轮换</button>
+                        <button data-revoke="${escapeHtml(k.id)}">吊销</button>
+                    </td>
+                </tr>`).join('')
+            : '<tr><td colspan="6" class="empty">暂无密钥</td></tr>';
+        // 没有登录时仪表板靠"未启用认证"访问，创建密钥后 API 会要求认证
+        $('keys-note').textContent = !keys.length && !state.loginEnabled
+            ? '创建第一个密钥后 API 需要认证，请先启用 [web.auth]'
+            : `${keys.length} 个密钥`;
+    }
+
+    function showSecret(issued) {
+        const box = $('key-secret');
+        box.innerHTML = `密钥 <b>${escapeHtml(issued.key.name)}</b> 的明文只显示这一次：<code>${escapeHtml(issued.secret)}</code>`;
+        box.hidden = false;
+    }
+
+    // 有 admin 权限时显示密钥面板
+    async function loadKeys() {
+        const response = await request('GET', '/api/v1/keys');
+        if (!response.ok) return;
+        const body = await response.json();
+        renderKeys(body.data || []);
+        $('keys-panel').hidden = false;
+    }
+
+    async function keyAction(method, path, body) {
+        const response = await request(method, path, body);
+        const result = await response.json().catch(() => ({}));
+        if (!response.ok || !result.success) {
+            throw new Error(result.error || `HTTP ${response.status}`);
+        }
+        return result.data;
+    }
+
+    function bindKeyControls() {
+        $('key-form').addEventListener('submit', async (event) => {
+            event.preventDefault();
+            try {
+                const issued = await keyAction('POST', '/api/v1/keys', { name: $('key-name').value, scope: $('key-scope').value });
+                $('key-name').value = '';
+                showSecret(issued);
+                await loadKeys();
+            } catch (error) {
+                showToast(`创建密钥失败: ${error.message}`, true);
+            }
+        });
+
+        $('keys').addEventListener('click', async (event) => {
+            const { rotate, revoke } = event.target.dataset;
+            try {
+                if (rotate && confirm('轮换后旧密钥立即失效，确定吗？')) {
+                    showSecret(await keyAction('POST', `/api/v1/keys/${encodeURIComponent(rotate)}/rotate`));
+                } else if (revoke && confirm('吊销后使用该密钥的工具将无法访问，确定吗？')) {
+                    await keyAction('DELETE', `/api/v1/keys/${encodeURIComponent(revoke)}`);
+                    showToast('密钥已吊销');
+                } else {
+                    return;
+                }
+                await loadKeys();
+            } catch (error) {
+                showToast(`操作失败: ${error.message}`, true);
+            }
+        });
+    }
+
+    window.cgminer = { request };
+
+    function connect() {
+        const source = new EventSource('/api/live');
+        source.onopen = () => setConnected(true);
+        source.onerror = () => {
+            setConnected(false);
+            // 会话过期时 EventSource 不会自动恢复，检查会话决定是否跳转
+            if (source.readyState === EventSource.CLOSED) {
+                loadSession().catch(() => {});
+            }
+        };
+
+        Object.entries(handlers).forEach(([name, handler]) => {
+            source.addEventListener(name, (event) => {
+                try {
+                    handler(JSON.parse(event.data));
+                } catch (error) {
+                    console.error(`处理 ${name} 事件失败:`, error);
+                }
+            });
+        });
+    }
+
+    window.addEventListener('resize', drawChart);
+    document.addEventListener('DOMContentLoaded', () => {
+        loadSession()
+            .catch((error) => console.error('获取会话失败:', error))
+            .then(() => loadKeys())
+            .catch(() => {});
+        detectFleet().catch(() => {});
+        detectHistory().catch(() => {});
+        setupPush().catch((error) => console.error('初始化告警推送失败:', error));
+        bindDeviceControls();
+        bindKeyControls();
+        connect();
+    });
+})();
@@ -0,0 +1,113 @@
+// CGMiner-RS 机群视图
+//
+// 定时请求 /api/fleet 渲染各矿机的状态。服务端按 web.fleet.poll_interval_secs 轮询矿机，
+// 这里的刷新间隔只决定页面多快看到新结果。
+
+(() => {
+    'use strict';
+
+    const REFRESH_MS = 5000;
+
+    const $ = (id) => document.getElementById(id);
+
+    // 与仪表板一致，算力单位为 GH/s
+    function formatHashrate(ghs) {
+        if (!Number.isFinite(ghs)) return '--';
+        const units = ['GH/s', 'TH/s', 'PH/s'];
+        let value = ghs;
+        let unit = 0;
+        while (Math.abs(value) >= 1000 && unit < units.length - 1) {
+            value /= 1000;
+            unit += 1;
+        }
+        return `${value.toFixed(2)} ${units[unit]}`;
+    }
+
+    function formatUptime(secs) {
+        const days = Math.floor(secs / 86400);
+        const hours = Math.floor((secs % 86400) / 3600);
+        const minutes = Math.floor((secs % 3600) / 60);
+        return days > 0 ? `${days}d ${hours}h` : `${hours}h ${minutes}m`;
+    }
+
+    function formatSeen(secs) {
+        if (!secs) return '--';
+        const ago = Math.max(0, Math.round(Date.now() / 1000 - secs));
+        return ago < 60 ? `${ago} 秒前` : new Date(secs * 1000).toLocaleTimeString();
+    }
+
+    function escapeHtml(text) {
+        return String(text).replace(/[&<>"']/g, (c) => ({
+            '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;',
+        }[c]));
+    }
+
+    function renderAlerts(alerts) {
+        if (!alerts.length) return '<span class="muted">无</span>';
+        return alerts.map((a) => `
+            <div class="${a.severity.toLowerCase()}" title="${escapeHtml(a.source)}">${escapeHtml(a.title)}</div>`).join('');
+    }
+
+    function renderRig(rig) {
+        const s = rig.status;
+        const badge = rig.online
+            ? '<span class="badge ok">在线</span>'
+            : `<span class="badge bad" title="${escapeHtml(rig.error || '')}">离线</span>`;
+        // 离线矿机显示最近一次的数据，灰色表示已过时
+        const stale = rig.online ? '' : ' class="muted"';
+        return `
+            <tr${rig.online ? '' : ' class="offline"'}>
+                <td title="${escapeHtml(rig.url)}">${escapeHtml(rig.name)}</td>
+                <td>${badge}</td>
+                <td${stale}>${s ? formatHashrate(s.total_hashrate) : '--'}</td>
+                <td${stale}>${s ? s.accepted_shares : '--'}</td>
+                <td${stale}>${s ? s.rejected_shares : '--'}</td>
+                <td${stale}>${s ? s.active_devices : '--'}</td>
+                <td${stale}>${s ? formatUptime(s.uptime) : '--'}</td>
+                <td class="fleet-alerts">${renderAlerts(rig.alerts)}</td>
+                <td>${formatSeen(rig.last_seen)}</td>
+            </tr>`;
+    }
+
+    function render(fleet) {
+        $('total-hashrate').textContent = formatHashrate(fleet.total_hashrate);
+        $('rigs-online').textContent = `${fleet.rigs_online} / ${fleet.rigs_total}`;
+        $('share-counts').textContent = `${fleet.accepted_shares} / ${fleet.rejected_shares}`;
+        $('active-devices').textContent = fleet.active_devices;
+        $('alert-counts').textContent = `${fleet.alert_count} (${fleet.critical_alerts})`;
+        $('updated').textContent = `更新于 ${new Date(fleet.timestamp * 1000).toLocaleTimeString()}`;
+
+        // 离线和有告警的矿机排在前面
+        const rank = (rig) => (rig.online ? 2 : 0) + (rig.alerts.length ? 0 : 1);
+        const rigs = [...fleet.rigs].sort((a, b) => rank(a) - rank(b) || a.name.localeCompare(b.name));
+        $('rigs').innerHTML = rigs.length
+            ? rigs.map(renderRig).join('')
+            : '<tr><td colspan="9" class="empty">暂无矿机数据</td></tr>';
+    }
+
+    function setConnected(connected) {
+        $('connection-dot').className = `dot ${connected ? 'online' : 'offline'}`;
+        $('connection-text').textContent = connected ? '已连接' : '无法连接';
+    }
+
+    async function refresh() {
+        try {
+            const response = await fetch('/api/fleet', { credentials: 'same-origin' });
+            if (response.status === 401) {
+                window.location.replace('/login');
+                return;
+            }
+            if (!response.ok) throw new Error(`HTTP ${response.status}`);
+            render(await response.json());
+            setConnected(true);
+        } catch (error) {
+            console.error('获取机群数据失败:', error);
+            setConnected(false);
+        }
+    }
+
+    document.addEventListener('DOMContentLoaded', () => {
+        refresh();
+        setInterval(refresh, REFRESH_MS);
+    });
+})();
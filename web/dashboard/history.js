@@ -0,0 +1,231 @@
+// CGMiner-RS 历史图表
+//
+// 从 /api/history 读取 [monitoring.history] 保存的降采样指标：1 小时为 1 分钟一个点，
+// 24 小时为 5 分钟，7 天为 1 小时。选中的范围和曲线保存在地址栏的 hash 中。
+
+(() => {
+    'use strict';
+
+    const REFRESH_MS = 60000;
+
+    const $ = (id) => document.getElementById(id);
+
+    const state = {
+        range: '24h',
+        series: 'total',
+        points: [],
+    };
+
+    // 与仪表板一致，算力单位为 GH/s
+    function formatHashrate(ghs) {
+        if (!Number.isFinite(ghs)) return '--';
+        const units = ['GH/s', 'TH/s', 'PH/s'];
+        let value = ghs;
+        let unit = 0;
+        while (Math.abs(value) >= 1000 && unit < units.length - 1) {
+            value /= 1000;
+            unit += 1;
+        }
+        return `${value.toFixed(2)} ${units[unit]}`;
+    }
+
+    function formatTime(secs) {
+        const date = new Date(secs * 1000);
+        return state.range === '7d'
+            ? `${date.getMonth() + 1}/${date.getDate()} ${date.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}`
+            : date.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' });
+    }
+
+    function seriesLabel(series) {
+        if (series === 'total') return '总体';
+        const [kind, id] = series.split(':');
+        return kind === 'device' ? `设备 ${id}` : `矿池 ${id}`;
+    }
+
+    // ---------- 绘图 ----------
+
+    function prepare(canvas) {
+        const ratio = window.devicePixelRatio || 1;
+        const width = canvas.clientWidth;
+        const height = canvas.clientHeight;
+        canvas.width = width * ratio;
+        canvas.height = height * ratio;
+        const ctx = canvas.getContext('2d');
+        ctx.scale(ratio, ratio);
+        ctx.clearRect(0, 0, width, height);
+        ctx.font = '11px sans-serif';
+        ctx.fillStyle = '#6b7787';
+        const pad = { left: 70, right: 12, top: 12, bottom: 24 };
+        return { ctx, width, height, pad, plotWidth: width - pad.left - pad.right, plotHeight: height - pad.top - pad.bottom };
+    }
+
+    // 网格、纵轴刻度和首尾时间
+    function drawAxes(chart, max, format, start, end) {
+        const { ctx, width, height, pad, plotHeight } = chart;
+        ctx.strokeStyle = '#263241';
+        ctx.lineWidth = 1;
+        for (let i = 0; i <= 4; i += 1) {
+            const value = (max / 4) * i;
+            const gy = pad.top + plotHeight - (value / max) * plotHeight;
+            ctx.beginPath();
+            ctx.moveTo(pad.left, gy);
+            ctx.lineTo(width - pad.right, gy);
+            ctx.stroke();
+            ctx.fillText(format(value), 4, gy + 4);
+        }
+        ctx.fillText(formatTime(start), pad.left, height - 6);
+        const endLabel = formatTime(end);
+        ctx.fillText(endLabel, width - pad.right - ctx.measureText(endLabel).width, height - 6);
+    }
+
+    function drawLine(canvas, key, format, color, fixedMax) {
+        const chart = prepare(canvas);
+        const points = state.points.filter((p) => Number.isFinite(p[key]));
+        if (points.length < 2) {
+            chart.ctx.fillText('暂无数据', chart.pad.left, chart.pad.top + chart.plotHeight / 2);
+            return;
+        }
+
+        const { ctx, pad, plotWidth, plotHeight } = chart;
+        const start = points[0].timestamp;
+        const end = points[points.length - 1].timestamp;
+        const max = fixedMax || Math.max(...points.map((p) => p[key])) * 1.1 || 1;
+        const x = (t) => pad.left + ((t - start) / (end - start)) * plotWidth;
+        const y = (v) => pad.top + plotHeight - (v / max) * plotHeight;
+        drawAxes(chart, max, format, start, end);
+
+        ctx.beginPath();
+        points.forEach((p, i) => (i === 0 ? ctx.moveTo(x(p.timestamp), y(p[key])) : ctx.lineTo(x(p.timestamp), y(p[key]))));
+        ctx.strokeStyle = color;
+        ctx.lineWidth = 2;
+        ctx.stroke();
+    }
+
+    // 每个时间桶一根柱子，接受在下、拒绝叠在上面
+    function drawShares(canvas) {
+        const chart = prepare(canvas);
+        const points = state.points;
+        if (!points.length) {
+            chart.ctx.fillText('暂无数据', chart.pad.left, chart.pad.top + chart.plotHeight / 2);
+            return;
+        }
+
+        const { ctx, pad, plotWidth, plotHeight } = chart;
+        const start = points[0].timestamp;
+        const end = points[points.length - 1].timestamp;
+        const max = Math.max(...points.map((p) => p.accepted + p.rejected)) * 1.1 || 1;
+        const slot = plotWidth / points.length;
+        const barWidth = Math.max(1, slot - 1);
+        const h = (v) => (v / max) * plotHeight;
+        drawAxes(chart, max, (v) => v.toFixed(0), start, end);
+
+        points.forEach((p, i) => {
+            const bx = pad.left + i * slot;
+            const base = pad.top + plotHeight;
+            ctx.fillStyle = '#2ecc71';
+            ctx.fillRect(bx, base - h(p.accepted), barWidth, h(p.accepted));
+            ctx.fillStyle = '#e74c3c';
+            ctx.fillRect(bx, base - h(p.accepted) - h(p.rejected), barWidth, h(p.rejected));
+        });
+    }
+
+    function render() {
+        const points = state.points;
+        const isPool = state.series.startsWith('pool:');
+        const isDevice = state.series.startsWith('device:');
+
+        $('main-title').textContent = isPool ? '🌐 在线率' : '📈 算力';
+        if (isPool) {
+            drawLine($('main-chart'), 'online', (v) => `${Math.round(v * 100)}%`, '#3fa9f5', 1);
+        } else {
+            drawLine($('main-chart'), 'hashrate', formatHashrate, '#3fa9f5');
+        }
+        drawShares($('share-chart'));
+
+        $('temperature-panel').hidden = !isDevice;
+        if (isDevice) {
+            drawLine($('temperature-chart'), 'temperature', (v) => `${v.toFixed(1)}°C`, '#f5a623');
+        }
+
+        const accepted = points.reduce((sum, p) => sum + p.accepted, 0);
+        const rejected = points.reduce((sum, p) => sum + p.rejected, 0);
+        const errors = points.reduce((sum, p) => sum + p.errors, 0);
+        $('share-totals').textContent = `接受 ${accepted} / 拒绝 ${rejected} / ${isPool ? '过期' : '硬件错误'} ${errors}`;
+        $('chart-range').textContent = points.length
+            ? `${seriesLabel(state.series)} · ${new Date(points[0].timestamp * 1000).toLocaleString()} 起`
+            : seriesLabel(state.series);
+    }
+
+    // ---------- 数据 ----------
+
+    function setConnected(connected) {
+        $('connection-dot').className = `dot ${connected ? 'online' : 'offline'}`;
+        $('connection-text').textContent = connected ? '已连接' : '无法连接';
+    }
+
+    async function fetchJson(path) {
+        const response = await fetch(path, { credentials: 'same-origin' });
+        if (response.status === 401) {
+            window.location.replace('/login');
+            throw new Error('未登录');
+        }
+        if (!response.ok) throw new Error(`HTTP ${response.status}`);
+        return response.json();
+    }
+
+    function query(format) {
+        const params = new URLSearchParams({ range: state.range, series: state.series });
+        if (format) params.set('format', format);
+        return `/api/history?${params}`;
+    }
+
+    async function loadSeries() {
+        const { series } = await fetchJson('/api/history/series');
+        if (!series.includes(state.series)) state.series = 'total';
+        $('series').innerHTML = series
+            .map((s) => `<option value="${s}"${s === state.series ? ' selected' : ''}>${seriesLabel(s)}</option>`)
+            .join('');
+    }
+
+    async function refresh() {
+        try {
+            const history = await fetchJson(query());
+            state.points = history.points;
+            render();
+            setConnected(true);
+        } catch (error) {
+            console.error('获取历史数据失败:', error);
+            setConnected(false);
+        }
+    }
+
+    function select(changes) {
+        Object.assign(state, changes);
+        window.location.hash = `${state.range}/${state.series}`;
+        document.querySelectorAll('#ranges button').forEach((button) => {
+            button.classList.toggle('active', button.dataset.range === state.range);
+        });
+        $('csv-link').href = query('csv');
+        refresh();
+    }
+
+    window.addEventListener('resize', render);
+    document.addEventListener('DOMContentLoaded', async () => {
+        const [range, series] = window.location.hash.slice(1).split('/');
+        if (['1h', '24h', '7d'].includes(range)) state.range = range;
+        if (series) state.series = series;
+
+        document.querySelectorAll('#ranges button').forEach((button) => {
+            button.onclick = () => select({ range: button.dataset.range });
+        });
+        $('series').onchange = (event) => select({ series: event.target.value });
+
+        try {
+            await loadSeries();
+        } catch (error) {
+            console.error('获取曲线列表失败:', error);
+        }
+        select({});
+        setInterval(refresh, REFRESH_MS);
+    });
+})();
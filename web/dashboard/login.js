@@ -0,0 +1,32 @@
+// CGMiner-RS 登录页
+
+(() => {
+    'use strict';
+
+    const form = document.getElementById('login-form');
+    const error = document.getElementById('login-error');
+
+    form.addEventListener('submit', async (event) => {
+        event.preventDefault();
+        error.textContent = '';
+
+        try {
+            const response = await fetch('/api/login', {
+                method: 'POST',
+                headers: { 'content-type': 'application/json' },
+                body: JSON.stringify({
+                    username: form.username.value,
+                    password: form.password.value,
+                }),
+            });
+            if (!response.ok) {
+                error.textContent = response.status === 401 ? '用户名或密码错误' : `登录失败 (HTTP ${response.status})`;
+                form.password.value = '';
+                return;
+            }
+            window.location.replace('/');
+        } catch (e) {
+            error.textContent = '无法连接到矿机';
+        }
+    });
+})();
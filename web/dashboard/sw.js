@@ -0,0 +1,32 @@
+// CGMiner-RS 告警推送的 Service Worker：页面关闭时也能显示严重告警通知
+'use strict';
+
+self.addEventListener('push', (event) => {
+    let payload = { title: 'CGMiner-RS', body: '', tag: 'cgminer', url: '/' };
+    if (event.data) {
+        try {
+            payload = { ...payload, ...event.data.json() };
+        } catch (error) {
+            payload.body = event.data.text();
+        }
+    }
+    event.waitUntil(self.registration.showNotification(payload.title, {
+        body: payload.body,
+        tag: payload.tag,
+        renotify: true,
+        requireInteraction: payload.severity === 'Critical',
+        data: { url: payload.url },
+    }));
+});
+
+// 点击通知时聚焦已打开的仪表板，没有则新开一个
+self.addEventListener('notificationclick', (event) => {
+    event.notification.close();
+    const url = new URL((event.notification.data && event.notification.data.url) || '/', self.location.origin).href;
+    event.waitUntil((async () => {
+        const windows = await self.clients.matchAll({ type: 'window', includeUncontrolled: true });
+        const existing = windows.find((client) => client.url === url);
+        if (existing) return existing.focus();
+        return self.clients.openWindow(url);
+    })());
+});